@@ -0,0 +1,67 @@
+//! Hot paths of loading and drawing a dataset, on synthetic inputs from
+//! `javelin_tui::datasets`. Run with `cargo bench`.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+use javelin_tui::bench::{coo_sparsity_grid, expand_dense_row_major, normalize_for_display};
+use javelin_tui::datasets::{synthetic_coo_batch, synthetic_dense_batch};
+use javelin_tui::display::{RenderOptions, render_batch_to_string};
+use javelin_tui::functions::sparse_viz::ConnectivityGraph;
+
+const SEED: u64 = 42;
+
+fn expand_dense(c: &mut Criterion) {
+    let batch = synthetic_dense_batch(10_000, 512, SEED).unwrap();
+    c.bench_function("expand_dense_row_major/10000x512", |b| {
+        b.iter(|| expand_dense_row_major(black_box(&batch)).unwrap())
+    });
+}
+
+fn render_wide_rows(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render_rows_window");
+    for features in [512, 2048] {
+        let batch =
+            normalize_for_display(&synthetic_dense_batch(1_000, features, SEED).unwrap()).unwrap();
+        let opts = RenderOptions {
+            width: 200,
+            height: 50,
+            visible_cols: 16,
+            col_offset: features / 2,
+            ..RenderOptions::default()
+        };
+        group.bench_with_input(
+            BenchmarkId::new("1000_rows", features),
+            &batch,
+            |b, batch| b.iter(|| render_batch_to_string(black_box(batch), &opts)),
+        );
+    }
+    group.finish();
+}
+
+fn coo_sparsity_map(c: &mut Criterion) {
+    // 100k×100k with 10 non-zeros per row: 1M triples.
+    let batch = synthetic_coo_batch(100_000, 100_000, 10, SEED).unwrap();
+    c.bench_function("coo_view_sparsity_map/1M_nnz", |b| {
+        b.iter(|| coo_sparsity_grid(black_box(&batch), 48, 160).unwrap())
+    });
+}
+
+fn connectivity_graph(c: &mut Criterion) {
+    let batch = synthetic_coo_batch(5_000, 5_000, 10, SEED).unwrap();
+    let mut group = c.benchmark_group("connectivity_graph");
+    group.sample_size(10);
+    group.bench_function("from_coo_batch/5000_nodes", |b| {
+        b.iter(|| ConnectivityGraph::from_coo_batch(black_box(&batch)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    expand_dense,
+    render_wide_rows,
+    coo_sparsity_map,
+    connectivity_graph
+);
+criterion_main!(benches);
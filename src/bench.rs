@@ -0,0 +1,21 @@
+//! Entry points for the criterion benches in `benches/` into code that is
+//! otherwise crate-private. Not a stable API.
+
+use anyhow::Result;
+use arrow_array::RecordBatch;
+
+/// Expand a DenseRowMajor batch into `col_*` columns.
+pub fn expand_dense_row_major(batch: &RecordBatch) -> Result<RecordBatch> {
+    crate::functions::functions::expand_dense_row_major(batch)
+}
+
+/// Put `batch` in the shape the viewer renders.
+pub fn normalize_for_display(batch: &RecordBatch) -> Result<RecordBatch> {
+    crate::functions::functions::normalize_for_display(batch)
+}
+
+/// Read a SparseCoo batch and build the sparsity map of its top-left
+/// `rows`×`cols` block.
+pub fn coo_sparsity_grid(batch: &RecordBatch, rows: usize, cols: usize) -> Result<Vec<Vec<bool>>> {
+    crate::display::display_coo::coo_sparsity_grid(batch, rows, cols)
+}
@@ -0,0 +1,276 @@
+//! On-disk cache of full-dataset column statistics.
+//!
+//! Entries live under `~/.cache/javelin/` (or `$XDG_CACHE_HOME/javelin`,
+//! or `$JAVELIN_CACHE_DIR`) as
+//! `<hash(canonical dataset path)>/<lance version>/<hash(column)>.json`.
+//! Writing an entry for a new version removes the directories of older
+//! versions of the same dataset, so stale stats never linger. Files are
+//! written to a temporary name and renamed into place, so concurrent
+//! processes only ever see complete entries.
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+/// Makes temporary file names unique between threads of one process.
+static TMP_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Turn the cache off for the rest of the process (`--no-cache`).
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Statistics of one column over the whole dataset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnStats {
+    /// Non-null values seen
+    pub count: u64,
+    pub null_count: u64,
+    /// `None` for non-numeric or all-null columns
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    pub std: Option<f64>,
+    /// Equal-width bins over `[min, max]`; empty when there is no range
+    pub histogram: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    path: String,
+    version: u64,
+    column: String,
+    stats: ColumnStats,
+}
+
+/// Handle to the statistics cache; a disabled cache never hits or writes.
+#[derive(Debug, Clone)]
+pub struct StatsCache {
+    root: Option<PathBuf>,
+}
+
+impl StatsCache {
+    /// The user cache directory, unless disabled via [`disable`] or no home
+    /// directory can be found.
+    pub fn user() -> Self {
+        if DISABLED.load(Ordering::Relaxed) {
+            return Self::disabled();
+        }
+        Self { root: user_dir() }
+    }
+
+    /// A cache rooted at `root` (mainly for tests).
+    pub fn at(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: Some(root.into()),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self { root: None }
+    }
+
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    fn dataset_dir(&self, dataset: &Path) -> Option<PathBuf> {
+        let canonical = dataset
+            .canonicalize()
+            .unwrap_or_else(|_| dataset.to_path_buf());
+        let key = fnv1a(canonical.to_string_lossy().as_bytes());
+        Some(self.root.as_ref()?.join(format!("{key:016x}")))
+    }
+
+    fn entry_path(&self, dataset: &Path, version: u64, column: &str) -> Option<PathBuf> {
+        let dir = self.dataset_dir(dataset)?.join(version.to_string());
+        Some(dir.join(format!("{:016x}.json", fnv1a(column.as_bytes()))))
+    }
+
+    /// Cached stats for `column` at `version`, if present and readable.
+    pub fn get(&self, dataset: &Path, version: u64, column: &str) -> Option<ColumnStats> {
+        let path = self.entry_path(dataset, version, column)?;
+        let bytes = fs::read(&path).ok()?;
+        let entry: Entry = match serde_json::from_slice(&bytes) {
+            Ok(e) => e,
+            Err(e) => {
+                warn!("stats cache: ignoring unreadable entry {:?}: {e}", path);
+                return None;
+            }
+        };
+        // Guard against hash collisions between columns or datasets.
+        if entry.version != version || entry.column != column {
+            return None;
+        }
+        debug!("stats cache: hit {:?} v{} {}", dataset, version, column);
+        Some(entry.stats)
+    }
+
+    /// Store stats for `column` at `version` and drop entries of any other
+    /// version of the same dataset.
+    pub fn put(
+        &self,
+        dataset: &Path,
+        version: u64,
+        column: &str,
+        stats: &ColumnStats,
+    ) -> Result<()> {
+        let (Some(dataset_dir), Some(path)) = (
+            self.dataset_dir(dataset),
+            self.entry_path(dataset, version, column),
+        ) else {
+            return Ok(());
+        };
+
+        if let Ok(entries) = fs::read_dir(&dataset_dir) {
+            for old in entries.flatten() {
+                if old.file_name() != version.to_string().as_str() {
+                    debug!("stats cache: removing stale {:?}", old.path());
+                    let _ = fs::remove_dir_all(old.path());
+                }
+            }
+        }
+
+        let dir = path.parent().expect("entry path has a parent");
+        fs::create_dir_all(dir).with_context(|| format!("creating cache dir {:?}", dir))?;
+
+        let entry = Entry {
+            path: dataset.display().to_string(),
+            version,
+            column: column.to_string(),
+            stats: stats.clone(),
+        };
+        let tmp = dir.join(format!(
+            ".{}.{}.{}.tmp",
+            path.file_name().unwrap().to_string_lossy(),
+            std::process::id(),
+            TMP_SEQ.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp, serde_json::to_vec(&entry)?)
+            .with_context(|| format!("writing cache entry {:?}", tmp))?;
+        fs::rename(&tmp, &path).with_context(|| format!("renaming cache entry to {:?}", path))?;
+        Ok(())
+    }
+
+    /// Remove every cached entry. Returns the number of datasets cleared.
+    pub fn clear(&self) -> Result<usize> {
+        let Some(root) = &self.root else {
+            return Ok(0);
+        };
+        let entries = match fs::read_dir(root) {
+            Ok(e) => e,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e).with_context(|| format!("reading cache dir {:?}", root)),
+        };
+        let mut cleared = 0;
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                fs::remove_dir_all(&path)
+                    .with_context(|| format!("removing cache dir {:?}", path))?;
+                cleared += 1;
+            }
+        }
+        Ok(cleared)
+    }
+}
+
+fn user_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("JAVELIN_CACHE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))?;
+    Some(base.join("javelin"))
+}
+
+/// 64-bit FNV-1a; stable across Rust versions, unlike `DefaultHasher`.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in bytes {
+        hash ^= u64::from(*b);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(mean: f64) -> ColumnStats {
+        ColumnStats {
+            count: 3,
+            null_count: 1,
+            min: Some(0.0),
+            max: Some(2.0),
+            mean: Some(mean),
+            std: Some(0.5),
+            histogram: vec![1, 1, 1],
+        }
+    }
+
+    #[test]
+    fn round_trips_and_invalidates_on_new_version() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = StatsCache::at(tmp.path().join("cache"));
+        let dataset = tmp.path().join("d.lance");
+        fs::create_dir(&dataset).unwrap();
+
+        assert_eq!(cache.get(&dataset, 1, "a"), None);
+        cache.put(&dataset, 1, "a", &stats(1.0)).unwrap();
+        assert_eq!(cache.get(&dataset, 1, "a"), Some(stats(1.0)));
+        assert_eq!(cache.get(&dataset, 1, "b"), None);
+
+        cache.put(&dataset, 2, "a", &stats(2.0)).unwrap();
+        assert_eq!(cache.get(&dataset, 2, "a"), Some(stats(2.0)));
+        assert_eq!(cache.get(&dataset, 1, "a"), None, "old version is dropped");
+    }
+
+    #[test]
+    fn clear_removes_everything_and_disabled_is_inert() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = StatsCache::at(tmp.path().join("cache"));
+        assert_eq!(cache.clear().unwrap(), 0, "missing root is fine");
+
+        for name in ["x.lance", "y.lance"] {
+            cache
+                .put(&tmp.path().join(name), 1, "c", &stats(0.0))
+                .unwrap();
+        }
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert_eq!(cache.get(&tmp.path().join("x.lance"), 1, "c"), None);
+
+        let off = StatsCache::disabled();
+        off.put(&tmp.path().join("x.lance"), 1, "c", &stats(0.0))
+            .unwrap();
+        assert_eq!(off.get(&tmp.path().join("x.lance"), 1, "c"), None);
+    }
+
+    #[test]
+    fn concurrent_writers_leave_a_complete_entry() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("cache");
+        let dataset = tmp.path().join("d.lance");
+
+        std::thread::scope(|s| {
+            for i in 0..8 {
+                let cache = StatsCache::at(&root);
+                let dataset = dataset.clone();
+                s.spawn(move || {
+                    for _ in 0..20 {
+                        cache.put(&dataset, 1, "a", &stats(i as f64)).unwrap();
+                    }
+                });
+            }
+        });
+
+        let got = StatsCache::at(&root).get(&dataset, 1, "a").unwrap();
+        assert!(got.mean.unwrap() < 8.0);
+    }
+}
@@ -0,0 +1,409 @@
+//! The synthetic matrices `generate` writes. Behind the `generate`
+//! feature, with the dependencies only they need.
+
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_distr::{Distribution, Normal, Uniform};
+use sprs::{CsMat, TriMat};
+
+/// Generate multiple Gaussian cliques with clear separation for motif detection.
+///
+/// `outlier_frac` of the points (clamped to `[0, 1]`) are drawn uniformly and
+/// belong to no clique.
+///
+/// Returns:
+/// - points: Vec<Vec<f64>> (n_points x dims)
+/// - adjacency: CsMat<f64> sparse symmetric 0/1 adjacency matrix
+/// - norms: Vec<f64> L2 norm of each point
+pub fn make_gaussian_cliques_multi(
+    n_points: usize,
+    noise: f64,
+    n_cliques: usize,
+    dims: usize,
+    outlier_frac: f64,
+    seed: u64,
+) -> (Vec<Vec<f64>>, CsMat<f64>, Vec<f64>) {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let mut rows = Vec::with_capacity(n_points);
+    let n_cliques = n_cliques.max(1);
+
+    // Add some outliers.
+    let n_outliers = (n_points as f64 * outlier_frac.clamp(0.0, 1.0)).round() as usize;
+    let n_cluster_points = n_points - n_outliers;
+
+    // Distribute points evenly across cliques.
+    let base = n_cluster_points / n_cliques;
+    let rem = n_cluster_points % n_cliques;
+
+    // Generate clique centers with maximum separation.
+    let grid_size = (n_cliques as f64).sqrt().ceil() as usize;
+    let spacing = 20.0;
+
+    // Use up to 4 dims for separation, at least 2.
+    let separation_dims = dims.clamp(2, 4);
+
+    let mut clique_centers = Vec::new();
+    for i in 0..n_cliques {
+        let mut center = vec![0.0; dims];
+
+        // Grid layout in first 2 dimensions.
+        let grid_x = (i % grid_size) as f64;
+        let grid_y = (i / grid_size) as f64;
+        center[0] = grid_x * spacing;
+        if dims > 1 {
+            center[1] = grid_y * spacing;
+        }
+
+        // For at least 2/3 of clusters, add distinct patterns in extra dims.
+        if i < (n_cliques * 2 / 3).max(1) {
+            for (d, c) in center.iter_mut().enumerate().take(separation_dims).skip(2) {
+                let pattern_offset = match d {
+                    2 => (i % 3) as f64 * spacing * 0.8,
+                    3 => ((i / 3) % 3) as f64 * spacing * 0.6,
+                    _ => ((i / 9) % 2) as f64 * spacing * 0.4,
+                };
+                *c = pattern_offset;
+            }
+
+            let small_offset = Uniform::new(-spacing * 0.2, spacing * 0.2).unwrap();
+            for c in center.iter_mut().skip(separation_dims) {
+                *c = small_offset.sample(&mut rng);
+            }
+        } else {
+            let medium_offset = Uniform::new(-spacing * 0.3, spacing * 0.3).unwrap();
+            for c in center.iter_mut().skip(2) {
+                *c = medium_offset.sample(&mut rng);
+            }
+        }
+
+        clique_centers.push(center);
+    }
+
+    // Verify centroid distinctiveness (debug only).
+    let min_distinct_distance = spacing * 0.5;
+    let mut distinct_count = 0;
+    for i in 0..n_cliques.min(n_cliques * 2 / 3) {
+        let mut is_distinct = false;
+        for j in (i + 1)..n_cliques {
+            let dist: f64 = clique_centers[i]
+                .iter()
+                .zip(clique_centers[j].iter())
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f64>()
+                .sqrt();
+
+            if dist > min_distinct_distance {
+                is_distinct = true;
+                break;
+            }
+        }
+        if is_distinct {
+            distinct_count += 1;
+        }
+    }
+
+    debug_assert!(
+        distinct_count >= (n_cliques * 2 / 3).saturating_sub(1),
+        "Expected at least 2/3 distinct centroids, got {}/{}",
+        distinct_count,
+        n_cliques
+    );
+
+    // Track clique membership to build adjacency.
+    let mut memberships = Vec::with_capacity(n_points);
+
+    // Generate points for each clique.
+    for (clique_idx, center) in clique_centers.iter().enumerate() {
+        let n_for_clique = base + if clique_idx < rem { 1 } else { 0 };
+
+        for _ in 0..n_for_clique {
+            let mut point = Vec::with_capacity(dims);
+            for &c in center {
+                let normal = Normal::new(c, noise).unwrap();
+                point.push(normal.sample(&mut rng));
+            }
+            rows.push(point);
+            memberships.push(Some(clique_idx));
+        }
+    }
+
+    // Generate outliers uniformly.
+    let outlier_dist = Uniform::new(-10.0, (grid_size as f64) * spacing + 10.0).unwrap();
+    for _ in 0..n_outliers {
+        let mut point = Vec::with_capacity(dims);
+        for _ in 0..dims {
+            point.push(outlier_dist.sample(&mut rng));
+        }
+        rows.push(point);
+        memberships.push(None);
+    }
+
+    // Ensure exact count.
+    if rows.len() > n_points {
+        rows.truncate(n_points);
+        memberships.truncate(n_points);
+    }
+    while rows.len() < n_points {
+        let mut point = Vec::with_capacity(dims);
+        for _ in 0..dims {
+            point.push(outlier_dist.sample(&mut rng));
+        }
+        rows.push(point);
+        memberships.push(None);
+    }
+
+    // Shuffle points and memberships consistently.
+    let mut indices: Vec<usize> = (0..n_points).collect();
+    indices.shuffle(&mut rng);
+
+    let mut shuffled_rows = Vec::with_capacity(n_points);
+    let mut shuffled_memberships = Vec::with_capacity(n_points);
+    for idx in indices {
+        shuffled_rows.push(rows[idx].clone());
+        shuffled_memberships.push(memberships[idx]);
+    }
+
+    // Compute L2 norms for each row.
+    let mut norms = Vec::with_capacity(n_points);
+    for row in &shuffled_rows {
+        let sq_sum: f64 = row.iter().map(|v| v * v).sum();
+        norms.push(sq_sum.sqrt());
+    }
+
+    // Build sparse adjacency as CsMat<f64>, symmetric 0/1.
+    let mut triplets = TriMat::<f64>::new((n_points, n_points));
+
+    for i in 0..n_points {
+        if let Some(ci) = shuffled_memberships[i] {
+            for (j, membership) in shuffled_memberships.iter().enumerate().skip(i + 1) {
+                if *membership == Some(ci) {
+                    // Undirected edge (i,j) and (j,i) with weight 1.0.
+                    triplets.add_triplet(i, j, 1.0);
+                    triplets.add_triplet(j, i, 1.0);
+                }
+            }
+        }
+    }
+
+    let adj = triplets.to_csr();
+
+    (shuffled_rows, adj, norms)
+}
+
+/// Generate a symmetric banded matrix: entry `(i, j)` is nonzero iff `|i - j| <= bandwidth`.
+///
+/// Returns the same artifacts as `make_gaussian_cliques_multi`:
+/// - dense: Vec<Vec<f64>> (n x n) densified matrix
+/// - matrix: CsMat<f64> in CSR
+/// - norms: Vec<f64> L2 norm of each row
+pub fn make_banded_matrix(
+    n: usize,
+    bandwidth: usize,
+    seed: u64,
+) -> (Vec<Vec<f64>>, CsMat<f64>, Vec<f64>) {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let weight = Uniform::new(0.5, 1.0).unwrap();
+    let mut triplets = TriMat::<f64>::new((n, n));
+
+    for i in 0..n {
+        triplets.add_triplet(i, i, weight.sample(&mut rng));
+        for j in (i + 1)..(i + bandwidth + 1).min(n) {
+            let w = weight.sample(&mut rng);
+            triplets.add_triplet(i, j, w);
+            triplets.add_triplet(j, i, w);
+        }
+    }
+
+    with_dense_and_norms(triplets.to_csr())
+}
+
+/// Generate a symmetric block-diagonal matrix with `blocks` dense blocks of
+/// near-equal size along the diagonal (the first `n % blocks` blocks get one
+/// extra row). No entries exist outside the blocks.
+///
+/// Returns dense (n x n), CSR matrix and row norms like `make_banded_matrix`.
+pub fn make_block_diagonal(
+    n: usize,
+    blocks: usize,
+    seed: u64,
+) -> (Vec<Vec<f64>>, CsMat<f64>, Vec<f64>) {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let weight = Uniform::new(0.5, 1.0).unwrap();
+    let mut triplets = TriMat::<f64>::new((n, n));
+
+    let blocks = blocks.clamp(1, n.max(1));
+    let base = n / blocks;
+    let rem = n % blocks;
+    let mut start = 0;
+    for b in 0..blocks {
+        let end = start + base + usize::from(b < rem);
+        for i in start..end {
+            triplets.add_triplet(i, i, weight.sample(&mut rng));
+            for j in (i + 1)..end {
+                let w = weight.sample(&mut rng);
+                triplets.add_triplet(i, j, w);
+                triplets.add_triplet(j, i, w);
+            }
+        }
+        start = end;
+    }
+
+    with_dense_and_norms(triplets.to_csr())
+}
+
+/// Generate an undirected 0/1 graph with a power-law degree distribution.
+///
+/// Uses the Chung–Lu model: node `i` gets weight `(i + 1)^(-1 / (alpha - 1))`,
+/// rescaled to a mean expected degree of 4, and edge `(i, j)` is kept with
+/// probability `min(1, w_i * w_j / sum(w))`. `alpha` must be greater than 1;
+/// typical real-world graphs sit in `2..3`. No self-loops are generated.
+///
+/// Returns dense (n x n) adjacency, CSR adjacency and row norms.
+pub fn make_powerlaw_graph(
+    n: usize,
+    alpha: f64,
+    seed: u64,
+) -> (Vec<Vec<f64>>, CsMat<f64>, Vec<f64>) {
+    const MEAN_DEGREE: f64 = 4.0;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let coin = Uniform::new(0.0, 1.0).unwrap();
+    let mut triplets = TriMat::<f64>::new((n, n));
+
+    let exponent = -1.0 / (alpha.max(1.0 + 1e-6) - 1.0);
+    let raw: Vec<f64> = (0..n).map(|i| ((i + 1) as f64).powf(exponent)).collect();
+    let raw_sum: f64 = raw.iter().sum();
+    let mean_degree = MEAN_DEGREE.min(n.saturating_sub(1) as f64);
+    let weights: Vec<f64> = raw
+        .iter()
+        .map(|w| w * mean_degree * n as f64 / raw_sum)
+        .collect();
+    let total: f64 = weights.iter().sum();
+
+    // Shuffle node ids so hubs are not all packed at the top-left corner.
+    let mut ids: Vec<usize> = (0..n).collect();
+    ids.shuffle(&mut rng);
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let p = (weights[i] * weights[j] / total).min(1.0);
+            if coin.sample(&mut rng) < p {
+                triplets.add_triplet(ids[i], ids[j], 1.0);
+                triplets.add_triplet(ids[j], ids[i], 1.0);
+            }
+        }
+    }
+
+    with_dense_and_norms(triplets.to_csr())
+}
+
+/// Densify a square CSR matrix and compute its row L2 norms.
+fn with_dense_and_norms(matrix: CsMat<f64>) -> (Vec<Vec<f64>>, CsMat<f64>, Vec<f64>) {
+    let mut dense = vec![vec![0.0; matrix.cols()]; matrix.rows()];
+    for (i, row) in matrix.outer_iterator().enumerate() {
+        for (j, &v) in row.iter() {
+            dense[i][j] = v;
+        }
+    }
+    let norms = dense
+        .iter()
+        .map(|row| row.iter().map(|v| v * v).sum::<f64>().sqrt())
+        .collect();
+    (dense, matrix, norms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cliques_outlier_frac_controls_membership() {
+        // No outliers: 12 points over 3 cliques of 4 → 3 * 4 * 3 directed edges.
+        let (points, adj, norms) = make_gaussian_cliques_multi(12, 0.1, 3, 4, 0.0, 7);
+        assert_eq!(points.len(), 12);
+        assert_eq!(norms.len(), 12);
+        assert_eq!(adj.nnz(), 3 * 4 * 3);
+
+        // All outliers: no clique edges at all.
+        let (_, adj, _) = make_gaussian_cliques_multi(12, 0.1, 3, 4, 1.0, 7);
+        assert_eq!(adj.nnz(), 0);
+    }
+
+    fn assert_symmetric(m: &CsMat<f64>) {
+        for (v, (i, j)) in m.iter() {
+            assert_eq!(m.get(j, i), Some(v), "entry ({i}, {j}) has no mirror");
+        }
+    }
+
+    #[test]
+    fn banded_matrix_respects_bandwidth() {
+        let (n, bw) = (30, 3);
+        let (dense, m, norms) = make_banded_matrix(n, bw, 1);
+        assert_eq!((m.rows(), m.cols()), (n, n));
+        assert_eq!(dense.len(), n);
+        assert_eq!(norms.len(), n);
+        assert_symmetric(&m);
+
+        for (_, (i, j)) in m.iter() {
+            assert!(i.abs_diff(j) <= bw, "({i}, {j}) is outside the band");
+        }
+        // Full band: n diagonal entries plus two triangles of decreasing width.
+        let expected: usize = n + 2 * (1..=bw).map(|k| n - k).sum::<usize>();
+        assert_eq!(m.nnz(), expected);
+        assert!(norms.iter().all(|&x| x > 0.0));
+    }
+
+    #[test]
+    fn banded_matrix_with_zero_bandwidth_is_diagonal() {
+        let (_, m, _) = make_banded_matrix(10, 0, 1);
+        assert_eq!(m.nnz(), 10);
+        assert!(m.iter().all(|(_, (i, j))| i == j));
+    }
+
+    #[test]
+    fn block_diagonal_has_expected_blocks() {
+        // 10 rows in 3 blocks → sizes 4, 3, 3.
+        let (_, m, _) = make_block_diagonal(10, 3, 2);
+        assert_symmetric(&m);
+        assert_eq!(m.nnz(), 16 + 9 + 9);
+
+        let block_of = |i: usize| match i {
+            0..=3 => 0,
+            4..=6 => 1,
+            _ => 2,
+        };
+        for (_, (i, j)) in m.iter() {
+            assert_eq!(block_of(i), block_of(j), "({i}, {j}) crosses blocks");
+        }
+        assert!(m.get(0, 3).is_some());
+        assert!(m.get(3, 4).is_none());
+    }
+
+    #[test]
+    fn block_diagonal_clamps_block_count() {
+        let (_, m, _) = make_block_diagonal(5, 50, 2);
+        assert_eq!(m.nnz(), 5, "one block per row is a diagonal matrix");
+    }
+
+    #[test]
+    fn powerlaw_graph_is_simple_and_heavy_tailed() {
+        let n = 400;
+        let (_, m, norms) = make_powerlaw_graph(n, 2.2, 3);
+        assert_eq!((m.rows(), m.cols()), (n, n));
+        assert_eq!(norms.len(), n);
+        assert_symmetric(&m);
+        assert!(m.iter().all(|(&v, (i, j))| i != j && v == 1.0));
+
+        assert!(m.nnz() > 0);
+        assert!(m.nnz() <= n * (n - 1));
+
+        let mut degrees: Vec<usize> = m.outer_iterator().map(|r| r.nnz()).collect();
+        degrees.sort_unstable();
+        let median = degrees[n / 2].max(1);
+        let max = *degrees.last().unwrap();
+        assert!(
+            max >= 5 * median,
+            "expected hubs: max degree {max}, median {median}"
+        );
+    }
+}
@@ -0,0 +1,380 @@
+#[cfg(feature = "generate")]
+mod generators;
+
+#[cfg(feature = "generate")]
+pub use generators::{
+    make_banded_matrix, make_block_diagonal, make_gaussian_cliques_multi, make_powerlaw_graph,
+};
+
+use rand::SeedableRng;
+use rand::distr::{Distribution, Uniform};
+use sprs::{CsMat, TriMat};
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub fn remove_directory_if_exists<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    // Check if the path actually exists before attempting removal.
+    if path.as_ref().exists() {
+        println!("Attempting to remove directory: {:?}", path.as_ref());
+        // Attempt to remove the directory and its contents recursively.
+        match fs::remove_dir_all(&path) {
+            Ok(_) => {
+                println!("Successfully removed directory: {:?}", path.as_ref());
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("Failed to remove directory {:?}: {}", path.as_ref(), e);
+                Err(e)
+            }
+        }
+    } else {
+        println!(
+            "Directory does not exist, skipping removal: {:?}",
+            path.as_ref()
+        );
+        Ok(())
+    }
+}
+
+use std::path::PathBuf;
+
+/// Converts a full file path to a `file://` URI for Lance.
+pub fn path_to_uri(path: &Path) -> String {
+    path.canonicalize()
+        .unwrap_or_else(|_| {
+            if path.is_absolute() {
+                path.to_path_buf()
+            } else if path.is_relative() {
+                std::env::current_dir()
+                    .unwrap_or_else(|_| PathBuf::from("/"))
+                    .join(path)
+            } else {
+                PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)
+            }
+        })
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Open the Lance dataset at `path`. A missing path is an
+/// [`ErrorCategory::OpenFailure`](crate::errors::ErrorCategory), and a
+/// regular file (Lance datasets are directories) an `UnsupportedLayout`.
+pub async fn open_dataset(path: &Path) -> anyhow::Result<lance::Dataset> {
+    use crate::errors::ErrorCategory;
+
+    if !path.exists() {
+        return Err(ErrorCategory::OpenFailure.error(format!("{} does not exist", path.display())));
+    }
+    if path.is_file() {
+        return Err(ErrorCategory::UnsupportedLayout.error(format!(
+            "{} is a file, not a Lance dataset directory",
+            path.display()
+        )));
+    }
+    Ok(lance::Dataset::open(&path_to_uri(path)).await?)
+}
+
+/// Open the dataset at `path` and run `read` on it. When `read` fails
+/// because a writer committed a new version mid-scan and removed data files
+/// of the one being read, the dataset is reopened at its latest version and
+/// `read` runs once more, with a "dataset changed during read; reloaded at
+/// vN" note on stderr instead of the object store's missing-file error.
+pub async fn read_dataset<T, F, Fut>(path: &Path, mut read: F) -> anyhow::Result<T>
+where
+    F: FnMut(lance::Dataset) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<T>>,
+{
+    let dataset = open_dataset(path).await?;
+    let version = dataset.version().version;
+    #[cfg(test)]
+    test_hooks::before_read(path);
+    let err = match read(dataset).await {
+        Err(e) if is_concurrent_change(&e) => e,
+        result => return result,
+    };
+    let latest = open_dataset(path).await?;
+    let reloaded = latest.version().version;
+    if reloaded == version {
+        return Err(err);
+    }
+    let note = format!("dataset changed during read; reloaded at v{reloaded}");
+    log::warn!("{note} (was v{version}): {err:#}");
+    eprintln!("Note: {note}");
+    read(latest).await.map_err(|e| e.context(note))
+}
+
+/// Whether `err` is what reading an old version whose files a newer commit
+/// replaced looks like: a missing data file or a version conflict. Lance
+/// passes object store errors on as text, hence the message check.
+fn is_concurrent_change(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<lance::Error>()
+            && matches!(
+                e,
+                lance::Error::NotFound { .. }
+                    | lance::Error::CommitConflict { .. }
+                    | lance::Error::RetryableCommitConflict { .. }
+                    | lance::Error::VersionNotFound { .. }
+            )
+        {
+            return true;
+        }
+        let message = cause.to_string();
+        message.contains("not found") && message.contains("/data/")
+    })
+}
+
+/// Lets tests act on a dataset between [`read_dataset`] opening and reading
+/// it, e.g. to commit a new version under it.
+#[cfg(test)]
+pub(crate) mod test_hooks {
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+    use std::sync::Mutex;
+
+    type Hook = Box<dyn FnOnce() + Send>;
+
+    static BEFORE_READ: Mutex<Option<HashMap<PathBuf, Hook>>> = Mutex::new(None);
+
+    /// Run `hook` once, the next time `path` is read.
+    #[cfg_attr(not(feature = "generate"), allow(dead_code))]
+    pub(crate) fn set_before_read(path: &Path, hook: impl FnOnce() + Send + 'static) {
+        BEFORE_READ
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .insert(path.to_path_buf(), Box::new(hook));
+    }
+
+    pub(super) fn before_read(path: &Path) {
+        let hook = BEFORE_READ
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|hooks| hooks.remove(path));
+        if let Some(hook) = hook {
+            hook();
+        }
+    }
+}
+
+/// Write a single RecordBatch as a new Lance dataset at `uri`.
+///
+/// Fails if a dataset already exists at the destination.
+pub async fn write_lance_batch(uri: &str, batch: arrow_array::RecordBatch) -> anyhow::Result<()> {
+    use arrow_array::RecordBatchIterator;
+    use lance::dataset::{Dataset, WriteMode, WriteParams};
+
+    log::info!("Writing Lance dataset to {}", uri);
+    let schema = batch.schema();
+    let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+    let params = WriteParams {
+        mode: WriteMode::Create,
+        ..WriteParams::default()
+    };
+    Dataset::write(reader, uri, Some(params)).await?;
+    Ok(())
+}
+
+/// Append `batch` to the Lance dataset at `uri` as a new version, which
+/// is returned. The batch must match the dataset's schema; its schema
+/// metadata is not written.
+pub async fn append_lance_batch(uri: &str, batch: RecordBatch) -> anyhow::Result<u64> {
+    use arrow_array::RecordBatchIterator;
+    use lance::dataset::{Dataset, WriteMode, WriteParams};
+
+    log::info!(
+        "Appending {} rows to Lance dataset {}",
+        batch.num_rows(),
+        uri
+    );
+    let schema = batch.schema();
+    let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+    let params = WriteParams {
+        mode: WriteMode::Append,
+        ..WriteParams::default()
+    };
+    let dataset = Dataset::write(reader, uri, Some(params)).await?;
+    Ok(dataset.version().version)
+}
+
+/// Writes a new Lance dataset one batch at a time: the first batch creates
+/// it (failing if one already exists), later ones are appended, so a
+/// conversion never holds more than one batch of output.
+pub struct BatchWriter {
+    uri: String,
+    schema: arrow::datatypes::SchemaRef,
+    rows: usize,
+    created: bool,
+}
+
+impl BatchWriter {
+    pub fn new(uri: &str, schema: arrow::datatypes::SchemaRef) -> Self {
+        Self {
+            uri: uri.to_string(),
+            schema,
+            rows: 0,
+            created: false,
+        }
+    }
+
+    /// Write `batch`, which must have the writer's schema.
+    pub async fn write(&mut self, batch: RecordBatch) -> anyhow::Result<()> {
+        use arrow_array::RecordBatchIterator;
+        use lance::dataset::{Dataset, WriteMode, WriteParams};
+
+        let params = WriteParams {
+            mode: if self.created {
+                WriteMode::Append
+            } else {
+                WriteMode::Create
+            },
+            ..WriteParams::default()
+        };
+        self.rows += batch.num_rows();
+        let reader = RecordBatchIterator::new(vec![Ok(batch)], self.schema.clone());
+        Dataset::write(reader, self.uri.as_str(), Some(params)).await?;
+        self.created = true;
+        Ok(())
+    }
+
+    /// Create the dataset empty if nothing was written; returns the rows
+    /// written.
+    pub async fn finish(mut self) -> anyhow::Result<usize> {
+        if !self.created {
+            self.write(RecordBatch::new_empty(self.schema.clone()))
+                .await?;
+        }
+        Ok(self.rows)
+    }
+}
+
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow_array::{ArrayRef, FixedSizeListArray, Float64Array, RecordBatch, UInt32Array};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Pack equally sized rows into a `{ vector: FixedSizeList<Float64>[F] }` batch
+/// (DenseRowMajor layout).
+pub fn dense_rows_to_batch(rows: &[Vec<f64>]) -> anyhow::Result<RecordBatch> {
+    let width = rows.first().map(Vec::len).unwrap_or(0);
+    if width == 0 {
+        anyhow::bail!("cannot build a dense batch with zero columns");
+    }
+    if let Some(bad) = rows.iter().position(|r| r.len() != width) {
+        anyhow::bail!("row {bad} has {} values, expected {width}", rows[bad].len());
+    }
+
+    let item = Arc::new(Field::new("item", DataType::Float64, false));
+    let values = Float64Array::from_iter_values(rows.iter().flatten().copied());
+    let list = FixedSizeListArray::new(item.clone(), width as i32, Arc::new(values), None);
+    let schema = Schema::new(vec![Field::new(
+        "vector",
+        DataType::FixedSizeList(item, width as i32),
+        false,
+    )]);
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(list) as ArrayRef],
+    )?)
+}
+
+/// Flatten a sparse matrix into a `{ row: UInt32, col: UInt32, value: Float64 }`
+/// batch (SparseCoo layout), with `rows`, `cols` and `nnz` in the schema metadata.
+pub fn csr_to_coo_batch(matrix: &CsMat<f64>) -> anyhow::Result<RecordBatch> {
+    let mut row = Vec::with_capacity(matrix.nnz());
+    let mut col = Vec::with_capacity(matrix.nnz());
+    let mut value = Vec::with_capacity(matrix.nnz());
+    for (&v, (r, c)) in matrix.iter() {
+        row.push(u32::try_from(r)?);
+        col.push(u32::try_from(c)?);
+        value.push(v);
+    }
+
+    let metadata = HashMap::from([
+        ("rows".to_string(), matrix.rows().to_string()),
+        ("cols".to_string(), matrix.cols().to_string()),
+        ("nnz".to_string(), matrix.nnz().to_string()),
+    ]);
+    let schema = Schema::new(vec![
+        Field::new("row", DataType::UInt32, false),
+        Field::new("col", DataType::UInt32, false),
+        Field::new("value", DataType::Float64, false),
+    ])
+    .with_metadata(metadata);
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt32Array::from(row)) as ArrayRef,
+            Arc::new(UInt32Array::from(col)) as ArrayRef,
+            Arc::new(Float64Array::from(value)) as ArrayRef,
+        ],
+    )?)
+}
+
+/// Wrap a slice of values as a single Float64 column named `name` (Vector1D layout).
+pub fn vector_to_batch(name: &str, values: &[f64]) -> anyhow::Result<RecordBatch> {
+    let schema = Schema::new(vec![Field::new(name, DataType::Float64, false)]);
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(Float64Array::from(values.to_vec())) as ArrayRef],
+    )?)
+}
+
+/// A `rows`×`features` DenseRowMajor batch of uniform values in `[-1, 1)`,
+/// the same for the same `seed`; a synthetic input for the benches.
+pub fn synthetic_dense_batch(
+    rows: usize,
+    features: usize,
+    seed: u64,
+) -> anyhow::Result<RecordBatch> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let uniform = Uniform::new(-1.0, 1.0)?;
+    let rows: Vec<Vec<f64>> = (0..rows)
+        .map(|_| (0..features).map(|_| uniform.sample(&mut rng)).collect())
+        .collect();
+    dense_rows_to_batch(&rows)
+}
+
+/// A `rows`×`cols` SparseCoo batch with `per_row` distinct non-zeros in
+/// every row (at most `cols`), in row-major order, the same for the same
+/// `seed`; a synthetic input for the benches.
+pub fn synthetic_coo_batch(
+    rows: usize,
+    cols: usize,
+    per_row: usize,
+    seed: u64,
+) -> anyhow::Result<RecordBatch> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let uniform = Uniform::new(0.1, 1.0)?;
+    let per_row = per_row.min(cols);
+    let mut triplets = TriMat::<f64>::with_capacity((rows, cols), rows * per_row);
+    for r in 0..rows {
+        for c in rand::seq::index::sample(&mut rng, cols, per_row) {
+            triplets.add_triplet(r, c, uniform.sample(&mut rng));
+        }
+    }
+    csr_to_coo_batch(&triplets.to_csr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synthetic_batches_are_deterministic() {
+        let a = synthetic_dense_batch(4, 3, 9).unwrap();
+        assert_eq!(a, synthetic_dense_batch(4, 3, 9).unwrap());
+        assert_ne!(a, synthetic_dense_batch(4, 3, 10).unwrap());
+        assert_eq!(a.num_rows(), 4);
+
+        let coo = synthetic_coo_batch(50, 20, 3, 9).unwrap();
+        assert_eq!(coo, synthetic_coo_batch(50, 20, 3, 9).unwrap());
+        assert_eq!(coo.num_rows(), 150, "three distinct columns per row");
+        assert_eq!(coo.schema().metadata()["cols"], "20");
+        // More non-zeros per row than columns is capped.
+        assert_eq!(synthetic_coo_batch(2, 4, 9, 1).unwrap().num_rows(), 8);
+    }
+}
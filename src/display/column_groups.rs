@@ -0,0 +1,461 @@
+//! Column groups of the N×F table (`g`): features whose display names share
+//! a prefix (`txt_0`, `txt_1`, … up to `--group-delimiter`, `_` by default),
+//! or that a `--groups` file lists together, collapse into one summary
+//! column holding the row mean of the members. `Enter` on a summary column
+//! expands its group in place and on a member collapses it again; a thick
+//! separator marks where one group ends and the next column begins.
+//!
+//! The viewer's columns become a walk over this grouping: a feature outside
+//! every group, a collapsed group's summary, or an expanded group's members.
+//! Summary columns are appended after the columns of the data, so the
+//! schema index of every real column stays what it was.
+
+use anyhow::{Context, Result, bail};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow_array::{ArrayRef, Float64Array, RecordBatch};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::display::BORDER_ACCENT;
+use crate::display::display::{FEATURE_NAME_KEY, display_name};
+use crate::functions::batch_source::BatchSource;
+use crate::functions::stats::numeric_values;
+
+/// Field metadata key naming the group of a summary column.
+pub const GROUP_KEY: &str = "javelin.group";
+
+/// Where the groups come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupSpec {
+    /// Display-name prefixes up to the first occurrence of this delimiter
+    Delimiter(String),
+    /// A groups file: a JSON object of group name → member names, or one
+    /// `name: member, member, …` line per group
+    File(PathBuf),
+}
+
+impl Default for GroupSpec {
+    fn default() -> Self {
+        GroupSpec::Delimiter("_".to_string())
+    }
+}
+
+static SPEC: Mutex<Option<GroupSpec>> = Mutex::new(None);
+
+/// Set how `g` groups the columns in the viewers opened from now on
+/// (`--group-delimiter`, `--groups`).
+pub fn configure_column_groups(spec: GroupSpec) {
+    *SPEC.lock().unwrap() = Some(spec);
+}
+
+pub(crate) fn configured_spec() -> GroupSpec {
+    SPEC.lock().unwrap().clone().unwrap_or_default()
+}
+
+/// Groups from a groups file, in file order (JSON objects by name).
+pub(crate) fn load_groups(path: &Path) -> Result<Vec<(String, Vec<String>)>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("cannot read groups file {path:?}"))?;
+    if text.trim_start().starts_with('{') {
+        let groups: std::collections::BTreeMap<String, Vec<String>> = serde_json::from_str(&text)
+            .with_context(|| {
+            format!("{path:?} is not a JSON object of group name → member names")
+        })?;
+        return Ok(groups.into_iter().collect());
+    }
+    let mut groups = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, members)) = line.split_once(':') else {
+            bail!(
+                "{path:?} line {}: expected `name: member, member, …`",
+                n + 1
+            );
+        };
+        let members = members
+            .split(',')
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty())
+            .collect();
+        groups.push((name.trim().to_string(), members));
+    }
+    Ok(groups)
+}
+
+#[derive(Debug, Clone)]
+struct Group {
+    name: String,
+    /// Schema columns, in feature order
+    members: Vec<usize>,
+    /// Schema column of the summary in the grouped source
+    summary: usize,
+    expanded: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Entry {
+    /// A feature outside every group, by schema column
+    Single(usize),
+    /// Index into the groups
+    Group(usize),
+}
+
+/// The grouping of one viewer's features.
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnGroups {
+    entries: Vec<Entry>,
+    groups: Vec<Group>,
+    /// The viewer's features, by schema column
+    features: Vec<usize>,
+}
+
+impl ColumnGroups {
+    /// Group the schema columns `features` of `data` as `spec` says. Groups
+    /// of one feature are left as that feature.
+    pub(crate) fn new(data: &BatchSource, features: &[usize], spec: &GroupSpec) -> Result<Self> {
+        let schema = data.schema();
+        let name = |col: usize| display_name(schema.field(col)).to_string();
+        // feature → group name
+        let keys: Vec<Option<String>> = match spec {
+            // features known only by their `col_*` column share no prefix
+            GroupSpec::Delimiter(delimiter) => features
+                .iter()
+                .map(|&c| {
+                    let field = schema.field(c);
+                    let unnamed = !field.metadata().contains_key(FEATURE_NAME_KEY)
+                        && field.name().starts_with("col_");
+                    Some(name(c))
+                        .filter(|_| !unnamed)?
+                        .split_once(delimiter.as_str())
+                        .map(|(prefix, _)| prefix.to_string())
+                        .filter(|p| !p.is_empty())
+                })
+                .collect(),
+            GroupSpec::File(path) => {
+                let mut owner: HashMap<String, String> = HashMap::new();
+                for (group, members) in load_groups(path)? {
+                    for member in members {
+                        owner.entry(member).or_insert_with(|| group.clone());
+                    }
+                }
+                features
+                    .iter()
+                    .map(|&c| {
+                        owner
+                            .get(&name(c))
+                            .or_else(|| owner.get(schema.field(c).name()))
+                            .cloned()
+                    })
+                    .collect()
+            }
+        };
+
+        let mut sizes: HashMap<&str, usize> = HashMap::new();
+        for key in keys.iter().flatten() {
+            *sizes.entry(key).or_default() += 1;
+        }
+        let mut entries = Vec::new();
+        let mut groups: Vec<Group> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for (&col, key) in features.iter().zip(&keys) {
+            match key.as_deref().filter(|k| sizes[k] > 1) {
+                None => entries.push(Entry::Single(col)),
+                Some(key) => match index.get(key) {
+                    Some(&g) => groups[g].members.push(col),
+                    None => {
+                        index.insert(key, groups.len());
+                        entries.push(Entry::Group(groups.len()));
+                        groups.push(Group {
+                            name: key.to_string(),
+                            members: vec![col],
+                            summary: data.num_columns() + groups.len(),
+                            expanded: false,
+                        });
+                    }
+                },
+            }
+        }
+        Ok(Self {
+            entries,
+            groups,
+            features: features.to_vec(),
+        })
+    }
+
+    /// The viewer's features, by schema column.
+    pub(crate) fn features(&self) -> &[usize] {
+        &self.features
+    }
+
+    /// Number of groups, each with a summary column.
+    pub(crate) fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// The columns the table walks, by schema column of the grouped source.
+    pub(crate) fn view_columns(&self) -> Vec<usize> {
+        let mut cols = Vec::new();
+        for entry in &self.entries {
+            match *entry {
+                Entry::Single(col) => cols.push(col),
+                Entry::Group(g) if self.groups[g].expanded => cols.extend(&self.groups[g].members),
+                Entry::Group(g) => cols.push(self.groups[g].summary),
+            }
+        }
+        cols
+    }
+
+    /// The feature (index into the viewer's features) shown at position
+    /// `i` of [`view_columns`](Self::view_columns); `None` for a summary.
+    pub(crate) fn feature(&self, i: usize) -> Option<usize> {
+        let col = *self.view_columns().get(i)?;
+        self.features.iter().position(|&c| c == col)
+    }
+
+    /// Position in [`view_columns`](Self::view_columns) of feature
+    /// `feature`, or of its group's summary while collapsed.
+    pub(crate) fn position(&self, feature: usize) -> Option<usize> {
+        let col = *self.features.get(feature)?;
+        let shown = match self.group_of(col) {
+            Some(g) if !self.groups[g].expanded => self.groups[g].summary,
+            _ => col,
+        };
+        self.view_columns().iter().position(|&c| c == shown)
+    }
+
+    /// The group a member or summary column belongs to.
+    fn group_of(&self, col: usize) -> Option<usize> {
+        self.groups
+            .iter()
+            .position(|g| g.summary == col || g.members.contains(&col))
+    }
+
+    /// Expand the group of the summary `col`, or collapse the expanded
+    /// group `col` is a member of. Returns where the group now starts in
+    /// [`view_columns`](Self::view_columns), `None` outside every group.
+    pub(crate) fn toggle(&mut self, col: usize) -> Option<usize> {
+        let g = self.group_of(col)?;
+        let group = &mut self.groups[g];
+        group.expanded = !group.expanded;
+        let first = if group.expanded {
+            group.members[0]
+        } else {
+            group.summary
+        };
+        self.view_columns().iter().position(|&c| c == first)
+    }
+
+    /// Name of the group of a member or summary column.
+    pub(crate) fn name(&self, col: usize) -> Option<&str> {
+        Some(&self.groups[self.group_of(col)?].name)
+    }
+
+    /// The features shown by `len` view columns from `start`, as the
+    /// `(start, len)` of the range of feature indices spanning them.
+    pub(crate) fn feature_span(&self, start: usize, len: usize) -> (usize, usize) {
+        let features: Vec<usize> = self
+            .view_columns()
+            .iter()
+            .skip(start)
+            .take(len)
+            .flat_map(|&col| match self.group_of(col) {
+                Some(g) if self.groups[g].summary == col => self.groups[g].members.clone(),
+                _ => vec![col],
+            })
+            .filter_map(|col| self.features.iter().position(|&c| c == col))
+            .collect();
+        match (features.iter().min(), features.iter().max()) {
+            (Some(&lo), Some(&hi)) => (lo, hi + 1 - lo),
+            _ => (0, 0),
+        }
+    }
+
+    /// Title note, e.g. `3 groups, 1 expanded`.
+    pub(crate) fn note(&self) -> String {
+        let expanded = self.groups.iter().filter(|g| g.expanded).count();
+        format!("{} groups, {} expanded", self.groups.len(), expanded)
+    }
+
+    /// Positions in `window` (schema columns as shown) after which a
+    /// group ends or begins.
+    pub(crate) fn boundaries(&self, window: &[usize]) -> Vec<usize> {
+        window
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| {
+                let (a, b) = (self.group_of(pair[0]), self.group_of(pair[1]));
+                a != b && (a.is_some() || b.is_some())
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// `data` with a summary column per group appended: the mean of the
+    /// members' non-null numeric values of each row (null without any).
+    pub(crate) fn source(&self, data: &BatchSource) -> Result<BatchSource> {
+        let schema = data.schema();
+        let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+        for group in &self.groups {
+            let metadata = HashMap::from([
+                (GROUP_KEY.to_string(), group.name.clone()),
+                (
+                    FEATURE_NAME_KEY.to_string(),
+                    format!("{} ({})", group.name, group.members.len()),
+                ),
+            ]);
+            fields.push(
+                Field::new(format!("{}*", group.name), DataType::Float64, true)
+                    .with_metadata(metadata),
+            );
+        }
+        let schema = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
+        data.try_map(|batch, _| {
+            let mut columns = batch.columns().to_vec();
+            for group in &self.groups {
+                columns.push(group_mean(batch, &group.members));
+            }
+            Ok(RecordBatch::try_new(schema.clone(), columns)?)
+        })
+    }
+}
+
+fn group_mean(batch: &RecordBatch, members: &[usize]) -> ArrayRef {
+    let mut sums = vec![0.0; batch.num_rows()];
+    let mut counts = vec![0usize; batch.num_rows()];
+    for &col in members {
+        let Some(values) = numeric_values(batch.column(col)) else {
+            continue;
+        };
+        for (r, v) in values.iter().enumerate() {
+            if let Some(v) = v {
+                sums[r] += v;
+                counts[r] += 1;
+            }
+        }
+    }
+    let means: Float64Array = sums
+        .iter()
+        .zip(&counts)
+        .map(|(&s, &n)| (n > 0).then(|| s / n as f64))
+        .collect();
+    Arc::new(means)
+}
+
+/// Draw a thick separator in the gap after each column of `boundaries`
+/// (positions after the Row column) of a bordered table in `area` laid out
+/// with `widths` and one cell of spacing.
+pub(crate) fn render_group_separators(
+    f: &mut Frame,
+    area: Rect,
+    widths: &[Constraint],
+    boundaries: &[usize],
+) {
+    let inner = Rect {
+        x: area.x + 1,
+        width: area.width.saturating_sub(2),
+        ..area
+    };
+    let cells = Layout::horizontal(widths.iter().copied())
+        .spacing(1)
+        .split(inner);
+    let buf = f.buffer_mut();
+    for &b in boundaries {
+        // the Row column comes first
+        let Some(cell) = cells.get(b + 1) else {
+            continue;
+        };
+        let x = cell.right();
+        if x >= inner.right() {
+            continue;
+        }
+        for y in area.y + 1..area.bottom().saturating_sub(1) {
+            buf[(x, y)]
+                .set_symbol("┃")
+                .set_style(Style::default().fg(BORDER_ACCENT));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+
+    fn named(names: &[&str]) -> BatchSource {
+        let fields: Vec<Field> = names
+            .iter()
+            .enumerate()
+            .map(|(i, n)| {
+                Field::new(format!("col_{i}"), DataType::Float64, true).with_metadata(
+                    HashMap::from([(FEATURE_NAME_KEY.to_string(), n.to_string())]),
+                )
+            })
+            .collect();
+        let columns: Vec<ArrayRef> = (0..names.len())
+            .map(|i| Arc::new(Float64Array::from(vec![Some(i as f64), None])) as ArrayRef)
+            .collect();
+        BatchSource::from(RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap())
+    }
+
+    #[test]
+    fn prefixes_collapse_and_expand_in_place() {
+        let data = named(&["txt_0", "id", "txt_1", "img_0", "img_1", "lone_0"]);
+        let features: Vec<usize> = (0..6).collect();
+        let mut groups = ColumnGroups::new(&data, &features, &GroupSpec::default()).unwrap();
+        // txt at its first member, then id, img, and the one-member prefix
+        assert_eq!(groups.view_columns(), [6, 1, 7, 5]);
+        assert_eq!(groups.feature(1), Some(1));
+        assert_eq!(groups.feature(0), None);
+        assert_eq!(groups.position(2), Some(0));
+
+        let shown = groups.source(&data).unwrap();
+        let batch = shown.whole();
+        let txt = batch
+            .column(6)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!((txt.value(0), txt.is_null(1)), (1.0, true));
+        assert_eq!(display_name(batch.schema().field(7)), "img (2)");
+
+        assert_eq!(groups.toggle(6), Some(0));
+        assert_eq!(groups.view_columns(), [0, 2, 1, 7, 5]);
+        assert_eq!(groups.boundaries(&groups.view_columns()), [1, 2, 3]);
+        assert_eq!(groups.position(2), Some(1));
+        assert_eq!(groups.feature_span(3, 2), (3, 3));
+        assert_eq!(groups.note(), "2 groups, 1 expanded");
+        assert_eq!(groups.name(2), Some("txt"));
+        assert_eq!(groups.toggle(2), Some(0));
+        assert_eq!(groups.toggle(1), None);
+    }
+
+    #[test]
+    fn groups_files_name_members_by_display_or_column_name() {
+        let data = named(&["a", "b", "c"]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("groups.txt");
+        std::fs::write(&path, "# pairs\nfirst: a, col_2\n").unwrap();
+        let groups = ColumnGroups::new(&data, &[0, 1, 2], &GroupSpec::File(path.clone())).unwrap();
+        assert_eq!(groups.view_columns(), [3, 1]);
+
+        std::fs::write(&path, r#"{"x": ["b", "c"]}"#).unwrap();
+        let groups = ColumnGroups::new(&data, &[0, 1, 2], &GroupSpec::File(path.clone())).unwrap();
+        assert_eq!(groups.view_columns(), [0, 3]);
+
+        std::fs::write(&path, "no delimiter here\n").unwrap();
+        assert!(ColumnGroups::new(&data, &[0, 1, 2], &GroupSpec::File(path)).is_err());
+    }
+}
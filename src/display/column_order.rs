@@ -0,0 +1,168 @@
+//! The display order of the N×F feature columns: **Shift+Left** /
+//! **Shift+Right** move the pinned (else the leftmost visible) column one
+//! place so related features can sit side by side, and [`RESET_ORDER_KEY`]
+//! goes back to schema order. The batch itself is never reordered; the
+//! order is kept by name in the dataset's session state and applied to the
+//! viewer's columns and to what `W` writes.
+
+use anyhow::Result;
+use arrow::datatypes::Schema;
+use arrow_array::RecordBatch;
+
+/// Key putting the columns back in schema order.
+pub(crate) const RESET_ORDER_KEY: char = '0';
+
+/// Column names in display order; empty for schema order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ColumnOrder(Vec<String>);
+
+impl ColumnOrder {
+    pub(crate) fn new(names: impl IntoIterator<Item = String>) -> Self {
+        Self(names.into_iter().collect())
+    }
+
+    pub(crate) fn names(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Back to schema order; false when already there.
+    pub(crate) fn reset(&mut self) -> bool {
+        !std::mem::take(&mut self.0).is_empty()
+    }
+
+    /// `cols` (columns of `schema`) in display order: the named ones as
+    /// ordered, then any others (new in a later version, say) as given.
+    pub(crate) fn apply(&self, schema: &Schema, cols: &[usize]) -> Vec<usize> {
+        let position = |c: usize| {
+            self.0
+                .iter()
+                .position(|name| name == schema.field(c).name())
+        };
+        let mut ordered: Vec<usize> = cols.to_vec();
+        // stable: the unnamed ones keep their order after the named ones
+        ordered.sort_by_key(|&c| position(c).unwrap_or(usize::MAX));
+        ordered
+    }
+
+    /// Swap columns `a` and `b` in `ordered` (as [`apply`](Self::apply)
+    /// gave it), keeping the result as the display order.
+    pub(crate) fn swap(&mut self, schema: &Schema, ordered: &[usize], a: usize, b: usize) {
+        self.0 = ordered
+            .iter()
+            .map(|&c| {
+                let c = if c == a {
+                    b
+                } else if c == b {
+                    a
+                } else {
+                    c
+                };
+                schema.field(c).name().clone()
+            })
+            .collect();
+    }
+}
+
+/// `batch` with the `slots` columns (in schema order) holding the columns
+/// of `ordered` instead; the other columns stay where they are.
+pub(crate) fn reorder_columns(
+    batch: &RecordBatch,
+    slots: &[usize],
+    ordered: &[usize],
+) -> Result<RecordBatch> {
+    let projection: Vec<usize> = (0..batch.num_columns())
+        .map(|i| slots.iter().position(|&c| c == i).map_or(i, |k| ordered[k]))
+        .collect();
+    Ok(batch.project(&projection)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::LanceLayout;
+    use crate::display::state::{Dims, SpreadsheetState};
+    use arrow::datatypes::{DataType, Field};
+    use arrow_array::{ArrayRef, Float64Array, StringArray};
+    use std::sync::Arc;
+
+    fn schema(names: &[&str]) -> Schema {
+        Schema::new(
+            names
+                .iter()
+                .map(|n| Field::new(*n, DataType::Float64, true))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Names of the features as shown after moving the current one right
+    /// (`true`) or left for each of `moves`, as the viewer does it.
+    fn shown_after(moves: &[bool], start: usize) -> (Vec<String>, SpreadsheetState) {
+        let schema = schema(&["a", "b", "c", "d"]);
+        let features = [0, 1, 2, 3];
+        let dims = Dims {
+            rows: 10,
+            features: 4,
+            visible: 2,
+        };
+        let mut nav = SpreadsheetState::new(LanceLayout::DenseRowMajor, dims);
+        nav.show_feature(start, false);
+        let mut order = ColumnOrder::default();
+        for &right in moves {
+            let shown = order.apply(&schema, &features);
+            if let Some((from, to)) = nav.move_feature(right) {
+                order.swap(&schema, &shown, shown[from], shown[to]);
+            }
+        }
+        let names = order
+            .apply(&schema, &features)
+            .iter()
+            .map(|&c| schema.field(c).name().clone())
+            .collect();
+        (names, nav)
+    }
+
+    #[test]
+    fn moves_stop_at_either_end() {
+        // `a` walks right to the end and stays there
+        let (names, nav) = shown_after(&[true, true, true, true], 0);
+        assert_eq!(names, ["b", "c", "d", "a"]);
+        assert_eq!(nav.current_feature(), 3);
+
+        // `c` walks to the front, stays there, then steps back right
+        let (names, nav) = shown_after(&[false, false, false, true], 2);
+        assert_eq!(names, ["a", "c", "b", "d"]);
+        assert_eq!(nav.current_feature(), 1);
+
+        // there and back again is schema order
+        let (names, _) = shown_after(&[true, false], 1);
+        assert_eq!(names, ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn order_applies_by_name_and_reorders_exports() {
+        let schema = schema(&["id", "x", "y", "z"]);
+        let mut order = ColumnOrder::new(["z".to_string(), "x".to_string()]);
+        // `y` is not named and comes after the named ones
+        assert_eq!(order.apply(&schema, &[1, 2, 3]), [3, 1, 2]);
+
+        let batch = RecordBatch::try_from_iter([
+            ("id", Arc::new(StringArray::from(vec!["r0"])) as ArrayRef),
+            ("x", Arc::new(Float64Array::from(vec![1.0])) as ArrayRef),
+            ("y", Arc::new(Float64Array::from(vec![2.0])) as ArrayRef),
+            ("z", Arc::new(Float64Array::from(vec![3.0])) as ArrayRef),
+        ])
+        .unwrap();
+        let written = reorder_columns(&batch, &[1, 2, 3], &[3, 1, 2]).unwrap();
+        let names: Vec<&str> = written
+            .schema_ref()
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect();
+        assert_eq!(names, ["id", "z", "x", "y"]);
+
+        assert!(order.reset());
+        assert!(!order.reset());
+        assert_eq!(order.apply(&schema, &[1, 2, 3]), [1, 2, 3]);
+    }
+}
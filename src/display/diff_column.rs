@@ -0,0 +1,193 @@
+//! The computed `Δ` column of the N×F table: with two columns marked (`x`),
+//! `-` adds the second minus the first (e.g. a reconstruction minus its
+//! original), then their relative error, after the visible features. Cells
+//! are coloured by magnitude against the largest one and the footer carries
+//! the max and mean absolute error over the loaded rows. The column lives
+//! only in the view: it is never part of the data or anything written out.
+
+use ratatui::style::Color;
+
+use crate::display::display::{display_name, extract_numeric_value, mix_colors};
+use crate::display::*;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::functions::is_numeric;
+
+/// Key cycling the column through its modes.
+pub(crate) const DIFF_KEY: char = '-';
+
+/// What the `Δ` column holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// `b − a`
+    Difference,
+    /// `(b − a) / |a|`; undefined where `a` is 0
+    RelativeError,
+}
+
+impl DiffKind {
+    /// The next mode of [`DIFF_KEY`]: difference, relative error, off.
+    pub(crate) fn cycle(kind: Option<DiffKind>) -> Option<DiffKind> {
+        match kind {
+            None => Some(DiffKind::Difference),
+            Some(DiffKind::Difference) => Some(DiffKind::RelativeError),
+            Some(DiffKind::RelativeError) => None,
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            DiffKind::Difference => "Δ diff",
+            DiffKind::RelativeError => "Δ rel",
+        }
+    }
+}
+
+/// The `Δ` values of columns `a` and `b` for every loaded row.
+#[derive(Debug, Clone)]
+pub(crate) struct DiffColumn {
+    pub(crate) a: usize,
+    pub(crate) b: usize,
+    pub(crate) kind: DiffKind,
+    /// Per row; `None` where either value is null or the error undefined
+    values: Vec<Option<f64>>,
+    /// Largest |Δ| (0 when there is none)
+    pub(crate) max_abs: f64,
+    /// Mean |Δ| over the rows that have one
+    pub(crate) mean_abs: Option<f64>,
+}
+
+impl DiffColumn {
+    /// `Δ` of schema columns `a` and `b` of `data`; `None` unless both are
+    /// numeric.
+    pub(crate) fn new(data: &BatchSource, a: usize, b: usize, kind: DiffKind) -> Option<Self> {
+        let schema = data.schema();
+        if !is_numeric(schema.field(a).data_type()) || !is_numeric(schema.field(b).data_type()) {
+            return None;
+        }
+        let mut values = Vec::with_capacity(data.num_rows());
+        for (_, batch) in data.iter() {
+            let (col_a, col_b) = (batch.column(a), batch.column(b));
+            for r in 0..batch.num_rows() {
+                let value = if col_a.is_null(r) || col_b.is_null(r) {
+                    None
+                } else {
+                    extract_numeric_value(col_a, r)
+                        .zip(extract_numeric_value(col_b, r))
+                        .and_then(|(x, y)| match kind {
+                            DiffKind::Difference => Some(y - x),
+                            DiffKind::RelativeError if x == 0.0 => None,
+                            DiffKind::RelativeError => Some((y - x) / x.abs()),
+                        })
+                };
+                values.push(value.filter(|v| v.is_finite()));
+            }
+        }
+        let abs: Vec<f64> = values.iter().flatten().map(|v| v.abs()).collect();
+        let max_abs = abs.iter().copied().fold(0.0, f64::max);
+        let mean_abs = (!abs.is_empty()).then(|| abs.iter().sum::<f64>() / abs.len() as f64);
+        Some(Self {
+            a,
+            b,
+            kind,
+            values,
+            max_abs,
+            mean_abs,
+        })
+    }
+
+    /// `Δ` of the two marked features (indices into `features`), the
+    /// first being the reference; `None` unless exactly two are marked.
+    pub(crate) fn of_marks(
+        data: &BatchSource,
+        features: &[usize],
+        marked: &[usize],
+        kind: DiffKind,
+    ) -> Option<Self> {
+        match *marked {
+            [a, b] => Self::new(data, *features.get(a)?, *features.get(b)?, kind),
+            _ => None,
+        }
+    }
+
+    /// `Δ` of row `row`.
+    pub(crate) fn value(&self, row: usize) -> Option<f64> {
+        self.values.get(row).copied().flatten()
+    }
+
+    /// Text colour of `value`: plain near zero, warm at the largest |Δ|.
+    pub(crate) fn color(&self, value: f64) -> Color {
+        let level = if self.max_abs > 0.0 {
+            value.abs() / self.max_abs
+        } else {
+            0.0
+        };
+        mix_colors(TEXT_PRIMARY, TEXT_WARNING, level)
+    }
+
+    /// Title note naming the operands, e.g. `Δ = col_1_hat − col_1`.
+    pub(crate) fn note(&self, data: &BatchSource) -> String {
+        let schema = data.schema();
+        let (a, b) = (
+            display_name(schema.field(self.a)),
+            display_name(schema.field(self.b)),
+        );
+        match self.kind {
+            DiffKind::Difference => format!("Δ = {b} − {a} (computed)"),
+            DiffKind::RelativeError => format!("Δ = ({b} − {a}) / |{a}| (computed)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::{Float64Array, RecordBatch, StringArray};
+    use std::sync::Arc;
+
+    #[test]
+    fn differences_skip_nulls_and_zero_denominators() {
+        let schema = Schema::new(vec![
+            Field::new("col_0", DataType::Float64, true),
+            Field::new("col_0_hat", DataType::Float64, true),
+            Field::new("label", DataType::Utf8, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float64Array::from(vec![
+                    Some(2.0),
+                    Some(0.0),
+                    None,
+                    Some(-4.0),
+                ])),
+                Arc::new(Float64Array::from(vec![
+                    Some(2.5),
+                    Some(1.0),
+                    Some(3.0),
+                    Some(-3.0),
+                ])),
+                Arc::new(StringArray::from(vec!["a", "b", "c", "d"])),
+            ],
+        )
+        .unwrap();
+        let data = BatchSource::from(batch);
+
+        let diff = DiffColumn::new(&data, 0, 1, DiffKind::Difference).unwrap();
+        let values: Vec<_> = (0..4).map(|r| diff.value(r)).collect();
+        assert_eq!(values, [Some(0.5), Some(1.0), None, Some(1.0)]);
+        assert_eq!(diff.max_abs, 1.0);
+        assert_eq!(diff.mean_abs, Some(2.5 / 3.0));
+        assert_eq!(diff.note(&data), "Δ = col_0_hat − col_0 (computed)");
+        assert_eq!(diff.color(1.0), TEXT_WARNING);
+        assert_eq!(diff.color(0.0), TEXT_PRIMARY);
+
+        // Relative to |col_0|: undefined where it is 0.
+        let rel = DiffColumn::new(&data, 0, 1, DiffKind::RelativeError).unwrap();
+        let values: Vec<_> = (0..4).map(|r| rel.value(r)).collect();
+        assert_eq!(values, [Some(0.25), None, None, Some(0.25)]);
+
+        assert!(DiffColumn::new(&data, 0, 2, DiffKind::Difference).is_none());
+        assert_eq!(DiffKind::cycle(Some(DiffKind::RelativeError)), None);
+    }
+}
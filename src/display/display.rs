@@ -1,335 +1,2331 @@
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Result, bail};
 use arrow::array::*;
-use arrow::datatypes::DataType;
+use arrow::datatypes::{DataType, Field, Schema};
 use arrow_array::{ArrayRef, RecordBatch};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent},
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::text::Span;
+use ratatui::text::{Line, Span};
 use ratatui::{
     Frame, Terminal,
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    widgets::{
+        Block, Borders, Cell, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        Table, Wrap,
+    },
 };
+use std::collections::HashMap;
 use std::io;
-
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use unicode_width::UnicodeWidthStr;
+
+use crate::cache::ColumnStats;
+use crate::display::column_groups::{
+    ColumnGroups, GroupSpec, configured_spec, render_group_separators,
+};
+use crate::display::column_order::{ColumnOrder, RESET_ORDER_KEY, reorder_columns};
+use crate::display::diff_column::{DIFF_KEY, DiffColumn, DiffKind};
+use crate::display::display_bookmarks::{
+    BOOKMARKS_FILE, BookmarkAction, BookmarkList, Bookmarks, render_bookmark_list, render_notice,
+};
+use crate::display::display_column_picker::{ColumnPicker, PickerAction, render_column_picker};
+use crate::display::display_empty::{display_empty_window, is_empty_view, render_empty_ui};
+use crate::display::display_export::{
+    EXPORT_KEY, ExportAction, ExportChoices, ExportPrompt, render_export_prompt,
+};
+use crate::display::display_frequencies::{FrequencyPopup, render_frequency_popup};
+use crate::display::display_hidden::{
+    HIDDEN_LIST_KEY, HIDE_COLUMN_KEY, HiddenAction, HiddenColumns, HiddenList, hidden_note,
+    render_hidden_list,
+};
+use crate::display::display_log::{LogPopup, render_log_popup};
+use crate::display::display_metadata::{MetadataPopup, render_metadata_popup};
+use crate::display::display_neighbors::{
+    BackgroundSearch, MetricPrompt, NeighborAction, NeighborsPopup, render_metric_prompt,
+    render_neighbors_popup, render_search_toast,
+};
+use crate::display::display_rename::{RenameAction, RenamePrompt, render_rename_prompt};
+use crate::display::display_scatter::{ScatterView, render_scatter};
+use crate::display::display_sparse_rows::{
+    SparseRecordDetail, render_sparse_detail, render_sparse_rows_ui,
+};
+use crate::display::display_thumbnail::{
+    MatrixThumbnail, ThumbnailState, render_thumbnail, thumbnail_split,
+};
+use crate::display::follow::{FOLLOW_KEY, Follow, render_follow_badge};
+use crate::display::index_base::{
+    INDEX_BASE_KEY, IndexBase, configured_index_base, index_base, set_index_base,
+};
+use crate::display::keys::{is_interrupt, pressed};
+use crate::display::null_rows::{NULL_ROWS_KEY, NullFilter, NullRows};
+use crate::display::outlier_cells::OutlierCells;
+use crate::display::redraw::{Redraw, poll_event};
+use crate::display::review::{
+    FLAG_KEY, FlagsAction, FlagsPrompt, REVIEW_KEY, centered_start, flags_file,
+    render_flags_prompt, render_review_badge,
+};
+use crate::display::row_profile::PROFILE_KEY;
+use crate::display::row_stats::{
+    Aggregate, DEFAULT_AGGREGATES, NULL_POLICY_KEY, NullPolicy, RowStats, stat_color,
+};
+use crate::display::screenshot::{ScreenCapture, buffer_text};
+use crate::display::state::{Dims, SpreadsheetState};
+use crate::display::stats_columns::{skipped_columns, skipped_note};
+use crate::display::transform::{ColumnTransform, TRANSFORM_KEY, Transform, transform_note};
+use crate::display::value_format::{FORMAT_KEY, ValueFormatter, is_formattable};
+use crate::display::vector_cells::{
+    VECTOR_COL_WIDTH, VectorDetail, render_vector_detail, vector_elements, vector_preview,
+    vector_type_label, vector_width,
+};
+use crate::display::vector_columns::{
+    PARTIAL_MARK, VectorColumn, VectorKind, vector_note, with_vector_columns,
+};
+use crate::display::window::{SourceWindow, feature_window, vertical_window};
+use crate::display::window_summary::{
+    SUMMARY_KEY, SummaryPanel, render_window_summary, summary_split, window_summaries,
+};
 use crate::display::*;
-use crate::display::{display_1d::render_1d_ui, display_transposed::render_transposed_ui};
+use crate::display::{
+    display_1d::{VectorPanels, render_1d_ui},
+    display_ragged::render_ragged_ui,
+    display_transposed::render_transposed_ui,
+};
+use crate::functions::batch_source::BatchSource;
+use crate::functions::binary::{binary_value, is_binary_type, short_hex};
+use crate::functions::constant_columns::{
+    HIDE_CONSTANT_KEY, constant_columns, constant_notice, constant_threshold,
+};
+use crate::functions::deletions::{drop_tombstones, is_tombstone};
+use crate::functions::diff::AddedRows;
+use crate::functions::encoded::resolve;
+use crate::functions::export::{Provenance, export_row_ids, export_view};
+use crate::functions::format::{format_float, truncate_text};
+use crate::functions::functions::{
+    DenseMatrix, declared_dims, first_metadata_value, is_metadata_column, is_numeric,
+    is_vector_type, metadata_only_rows,
+};
+use crate::functions::laplacian::LaplacianReport;
+use crate::functions::reorder::Reordering;
+use crate::functions::set_meta::set_meta_from_viewer;
+use crate::functions::stats::source_column_stats;
+use crate::functions::unsupported::{
+    UNSUPPORTED_KEY, hidden_columns, hidden_count, hidden_notice, is_supported, placeholder,
+    table_columns, table_unsupported,
+};
+use crate::logging;
+use crate::session::SessionStore;
 
 // === Public entry point =====================================================
 
-pub(crate) fn display_spreadsheet_interactive(batch: &RecordBatch) -> Result<()> {
-    use log::{debug, info};
+pub(crate) fn display_spreadsheet_interactive(
+    data: &BatchSource,
+    dense: Vec<DenseMatrix>,
+    visible_cols: Option<usize>,
+    session: &SessionStore,
+    provenance: &Provenance,
+) -> Result<()> {
+    display_spreadsheet_window(
+        data,
+        SourceWindow::whole(data.num_rows()),
+        dense,
+        None,
+        None,
+        visible_cols,
+        session,
+        provenance,
+        None,
+    )
+}
 
-    let num_rows = batch.num_rows();
-    let num_cols = batch.num_columns();
-    let layout = crate::functions::functions::detect_lance_layout(batch);
+/// Like [`display_spreadsheet_interactive`] for rows that are a window of a
+/// larger dataset; titles count rows in `source` terms. `dense` holds the
+/// packed matrix behind each batch of a `col_*` expanded source, read by the
+/// `o` overview panel and the `N` nearest-row search; it is empty for other
+/// layouts. `laplacian` adds its checks to the Structure section of the COO
+/// view. `added` (`--since-version`) rows get their own background in the
+/// N×F table and a count in its metadata panel. `visible_cols` (`--visible-cols`) overrides the column count the
+/// session last used, [`DEFAULT_VISIBLE_COLS`] without either. Row bookmarks
+/// and that count are loaded from and saved to `session`; rows saved with
+/// `W` are tagged with `provenance`. With `follow` (`--follow`) the rows
+/// newer versions append are added as they arrive.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn display_spreadsheet_window(
+    data: &BatchSource,
+    mut source: SourceWindow,
+    mut dense: Vec<DenseMatrix>,
+    laplacian: Option<&LaplacianReport>,
+    added: Option<&AddedRows>,
+    visible_cols: Option<usize>,
+    session: &SessionStore,
+    provenance: &Provenance,
+    mut follow: Option<Follow>,
+) -> Result<()> {
+    use log::{debug, info, warn};
+
+    let num_rows = data.num_rows();
+    let num_cols = data.num_columns();
+    let layout = crate::functions::functions::detect_lance_layout(&data.empty_batch());
+    // each viewer starts from `--index-base`, whatever an earlier one on
+    // this blocking thread switched to
+    set_index_base(configured_index_base());
+
+    if let (Some((rows, cols)), Some((n_rows, n_cols))) =
+        (dims_mismatch(data, source), declared_dims(data))
+    {
+        warn!(
+            "display_spreadsheet_interactive: metadata declares n_rows={n_rows}, n_cols={n_cols} but the data is {rows}×{cols}"
+        );
+    }
 
     info!(
-        "display_spreadsheet_interactive: starting viewer for batch (rows={}, cols={})",
-        num_rows, num_cols
+        "display_spreadsheet_interactive: starting viewer for {} batches (rows={}, cols={})",
+        data.batches().len(),
+        num_rows,
+        num_cols
     );
 
-    if num_cols == 0 {
-        println!("No columns to display");
-        info!("display_spreadsheet_interactive: abort, no columns");
-        return Err(anyhow!(
-            "display_spreadsheet_interactive: abort, no columns"
-        ));
-    }
-
-    // Discover all feature columns once (col_*)
-    let all_col_indices = collect_feature_cols(batch)?;
+    // Discover all feature columns once (col_*); ragged rows and sparse
+    // records have none
+    let features = if matches!(layout, LanceLayout::RaggedList | LanceLayout::SparsePerRow) {
+        Vec::new()
+    } else {
+        collect_feature_cols(&data.empty_batch()).unwrap_or_else(|e| {
+            warn!("display_spreadsheet_interactive: {e}");
+            Vec::new()
+        })
+    };
+    // Columns the table cannot show stay hidden until `U`
+    let unsupported = table_unsupported(&data.schema(), layout);
+    let mut show_unsupported = false;
+    let mut all_col_indices = table_columns(&features, &unsupported, false);
     info!(
         "display_spreadsheet_interactive: found {} feature columns",
         all_col_indices.len()
     );
 
+    if is_empty_view(data, &layout, &all_col_indices) {
+        info!("display_spreadsheet_interactive: nothing to tabulate, showing the placeholder");
+        return display_empty_window(data, layout, source);
+    }
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut col_offset: usize = 0; // horizontal scroll over features (N×F)
-    let mut row_offset: usize = 0; // horizontal scroll over rows (F×N)
-    let mut row_start: usize = 0; // vertical scroll (top visible row / feature)
-    let mut sparse_col_offset: usize = 0; // NEW: horizontal scroll for sparse COO matrix
-    let visible: usize = 8; // number of visible items horizontally
-    let mut transposed = false; // false = N×F, true = F×N
+    let mut session_state = session.load();
+    // Columns hidden with `d` or moved with Shift+Left/Right in an earlier
+    // session stay so
+    let stored_schema = data.schema();
+    let mut hidden = HiddenColumns::new(session_state.hidden_columns.iter().cloned());
+    let mut column_order = ColumnOrder::new(session_state.column_order.iter().cloned());
+    let mut formats = ValueFormatter::new(&session_state.value_formats); // Ctrl-f in the picker
+    let mut hidden_cols = hidden.count(&stored_schema, &all_col_indices);
+    all_col_indices = hidden.visible(
+        &stored_schema,
+        &column_order.apply(&stored_schema, &all_col_indices),
+    );
+    let mut dims = Dims {
+        rows: num_rows,
+        features: all_col_indices.len(),
+        // number of visible items horizontally, `<`/`>` adjust
+        visible: visible_cols
+            .or(session_state.visible_cols)
+            .unwrap_or(DEFAULT_VISIBLE_COLS)
+            .max(1),
+    };
+    if visible_cols.is_some() && session_state.visible_cols != visible_cols {
+        session_state.visible_cols = visible_cols;
+        if let Err(e) = session.save(&session_state) {
+            warn!("display_spreadsheet_interactive: visible columns not saved: {e:#}");
+        }
+    }
+    let mut nav = SpreadsheetState::new(layout, dims); // offsets, transpose, pin
+    let mut metadata_popup: Option<MetadataPopup> = None; // `M` overlay
+    let mut written_metadata: HashMap<String, String> = HashMap::new(); // set with `=` in `M`
+    let mut column_picker: Option<ColumnPicker> = None; // `|` overlay
+    let mut record_detail: Option<SparseRecordDetail> = None; // `Enter` overlay (sparse records)
+    let mut vector_detail: Option<VectorDetail> = None; // `Enter` overlay (vector cells)
+    let mut frequency_popup: Option<FrequencyPopup> = None; // `i` overlay
+    let mut marked: Vec<usize> = Vec::new(); // up to two features for the scatter plot (`x`)
+    let mut label_col: Option<usize> = None; // column colouring the scatter plot (`c`)
+    let mut scatter: Option<ScatterView> = None; // `v` view of the marked features
+    let mut diff: Option<DiffColumn> = None; // `-` column of the marked features
+    let mut norm: Option<VectorColumn> = None; // `n` column, computed on first use
+    let mut show_norm = false;
+    let mut dot: Option<VectorColumn> = None; // `.` column of a reference row
+    let mut view_stats: Option<Vec<ColumnStats>> = None; // of the features, for `!` and `z`
+    let mut outlier_cells: Option<OutlierCells> = None; // `!` cells, computed on first use
+    let mut show_outliers = false;
+    let mut show_summary = false; // `S` panel of the window against all rows
+    let mut transform: Option<ColumnTransform> = None; // `z` preview transform
+    let mut transformed: Option<BatchSource> = None; // `data` as `transform` shows it
+    let mut groups: Option<ColumnGroups> = None; // `g` column groups, `Enter` expands
+    let mut grouped: Option<BatchSource> = None; // the shown rows with the group means
+    let mut reordering: Option<Reordering> = None; // `r` RCM order of a COO matrix
+    let mut thumbnail = MatrixThumbnail::from_matrices(&dense).map(ThumbnailState::new); // `o` side panel
+    let mut show_thumbnail = false;
+    let mut vector_panels = VectorPanels::default(); // 1D side panels: Tab focuses the histogram, `p` the row profile
+    let mut thumbnail_area: Option<Rect> = None; // where the panel was last drawn
+    let mut metric_prompt: Option<MetricPrompt> = None; // `N` overlay
+    let mut neighbor_search: Option<BackgroundSearch> = None; // running `N` search
+    let mut neighbors_popup: Option<NeighborsPopup> = None; // `N` results
+    let mut rename_prompt: Option<RenamePrompt> = None; // `R` overlay
+    let mut renamed: Option<BatchSource> = None; // `data` with session renames
+    let mut null_rows = NullRows::default(); // `u` filter and its NULL mask
+    let mut filtered: Option<BatchSource> = None; // the renamed rows `null_rows` keeps
+    let mut row_stats = RowStats::configured(); // trailing aggregates, `a` toggles
+    row_stats.set_excluding(hidden_cols > 0);
+    row_stats.set_skipped(skipped_columns(data, &features));
+    let mut hidden_list: Option<HiddenList> = None; // `D` overlay
+    let mut columns_changed = false; // set by `d` and `D`; refits what depends on the columns
+    let mut bookmarks = Bookmarks::new(session_state.bookmarks.iter().copied())
+        .with_flags(session_state.flagged.iter().copied()); // `b` toggles, `x` flags in review
+    let mut flags_prompt: Option<FlagsPrompt> = None; // `V` overlay ending a review
+    let mut export_prompt: Option<ExportPrompt> = None; // `W` overlay
+    let mut export_choices = ExportChoices::default(); // what the last `W` wrote as shown
+    let mut bookmark_list: Option<BookmarkList> = None; // `'` overlay
+    let mut log_popup: Option<LogPopup> = None; // `L` overlay
+    let mut notice = hidden_notice(&hidden_columns(&data.schema(), layout)); // one-line note until the next key
+    let mut constant: Vec<usize> = Vec::new(); // near-constant features, `Z` hides them
+    if matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other) {
+        // looked for once, in the stats `!`, `z` and `S` share
+        let stats = view_stats.insert(source_column_stats(data, &all_col_indices));
+        let found = constant_columns(stats.iter(), constant_threshold());
+        constant = found.iter().map(|&(i, _)| all_col_indices[i]).collect();
+        notice = match (notice, constant_notice(&found)) {
+            (Some(unsupported), Some(constant)) => Some(format!("{unsupported}; {constant}")),
+            (unsupported, constant) => unsupported.or(constant),
+        };
+    }
+    let mut capture = ScreenCapture::new(); // `Ctrl-s` screen dump
+    let mut redraw = Redraw::new(); // draw only after input or new data
+    let mut followed: Option<BatchSource> = None; // `data` with the rows `follow` added
+    let mut page = 0; // rows the table showed last, for pinning its last page
 
     info!(
         "display_spreadsheet_interactive: initial state mode=N×F, visible={}, offsets=(col=0,row=0,start=0)",
-        visible
+        dims.visible
     );
 
     loop {
-        terminal.draw(|f| match layout {
-            LanceLayout::SparseCoo => {
-                crate::display::display_coo::render_coo_ui(f, batch, row_start, sparse_col_offset)
+        if let Some(new) = follow.as_mut().and_then(Follow::poll) {
+            let grown = followed.as_ref().unwrap_or(data);
+            let grown = BatchSource::new(
+                grown.schema(),
+                [grown.batches(), new.rows.batches()].concat(),
+            )?;
+            info!(
+                "display_spreadsheet_interactive: {} rows appended in version {}",
+                new.rows.num_rows(),
+                new.version
+            );
+            // the packed matrices only stay when every batch has one
+            if dense.len() + new.dense.len() == grown.batches().len() {
+                dense.extend(new.dense);
+            } else {
+                dense.clear();
             }
-            LanceLayout::Vector1D => {
-                render_1d_ui(
+            source.total_rows = new.total_rows;
+            renamed = renamed.map(|r| grown.with_schema(r.schema())).transpose()?;
+            // appended rows can end a rising column
+            row_stats.set_skipped(skipped_columns(&grown, &features));
+            followed = Some(grown);
+            // everything derived from the rows follows them, as for `u`;
+            // cached row aggregates stay, the new rows get theirs when shown
+            let all_rows = renamed.as_ref().or(followed.as_ref()).unwrap_or(data);
+            null_rows.rows_changed();
+            filtered = null_rows.apply(all_rows, &features)?;
+            let rows = filtered.as_ref().unwrap_or(all_rows);
+            let dense_rows: &[DenseMatrix] = if filtered.is_some() { &[] } else { &dense };
+            norm = norm.map(|_| VectorColumn::norm(rows, dense_rows, &all_col_indices));
+            dot = dot.map(|d| match d.kind {
+                VectorKind::Dot { reference } => {
+                    VectorColumn::dot(rows, dense_rows, &all_col_indices, reference)
+                }
+                VectorKind::Norm => d,
+            });
+            diff = diff.and_then(|d| DiffColumn::of_marks(rows, &all_col_indices, &marked, d.kind));
+            view_stats = None;
+            outlier_cells = None;
+            if show_outliers || transform.is_some() {
+                let stats = view_stats.insert(source_column_stats(rows, &all_col_indices));
+                if show_outliers {
+                    outlier_cells = Some(OutlierCells::new(rows, &all_col_indices, stats));
+                }
+                transform =
+                    transform.map(|t| ColumnTransform::new(t.kind, rows, &all_col_indices, stats));
+            }
+            transformed = transform.as_ref().map(|t| t.source(rows)).transpose()?;
+            grouped = groups
+                .as_ref()
+                .map(|g| g.source(transformed.as_ref().unwrap_or(rows)))
+                .transpose()?;
+            if let Some(state) = thumbnail.as_mut()
+                && let Some(thumb) = match transform.as_ref() {
+                    Some(t) => MatrixThumbnail::from_matrices_mapped(&dense, |f, v| t.apply(f, v)),
+                    None => MatrixThumbnail::from_matrices(&dense),
+                }
+            {
+                state.thumb = thumb;
+            }
+            redraw.mark();
+        }
+        let all_rows = renamed.as_ref().or(followed.as_ref()).unwrap_or(data);
+        let data = filtered.as_ref().unwrap_or(all_rows);
+        let num_rows = data.num_rows();
+        // Kept rows are numbered from the first and lose their place in
+        // the dense matrices
+        let source = match filtered.as_ref() {
+            Some(kept) => null_rows.window(source, kept),
+            None => source,
+        };
+        let dense_rows: &[DenseMatrix] = if filtered.is_some() { &[] } else { &dense };
+        if std::mem::take(&mut columns_changed) {
+            let table_cols = table_columns(&features, &unsupported, show_unsupported);
+            let ordered = column_order.apply(&stored_schema, &table_cols);
+            all_col_indices = hidden.visible(&stored_schema, &ordered);
+            hidden_cols = table_cols.len() - all_col_indices.len();
+            // everything holding feature positions follows the columns
+            marked.clear();
+            diff = None;
+            dot = None;
+            if groups.take().is_some() {
+                grouped = None;
+            }
+            norm = norm.map(|_| VectorColumn::norm(data, dense_rows, &all_col_indices));
+            view_stats = None;
+            outlier_cells = None;
+            if show_outliers || transform.is_some() {
+                let stats = view_stats.insert(source_column_stats(data, &all_col_indices));
+                if show_outliers {
+                    outlier_cells = Some(OutlierCells::new(data, &all_col_indices, stats));
+                }
+                transform =
+                    transform.map(|t| ColumnTransform::new(t.kind, data, &all_col_indices, stats));
+            }
+            transformed = transform.as_ref().map(|t| t.source(data)).transpose()?;
+            if let Some(state) = thumbnail.as_mut()
+                && let Some(t) = transform.as_ref()
+                && let Some(thumb) =
+                    MatrixThumbnail::from_matrices_mapped(&dense, |f, v| t.apply(f, v))
+            {
+                state.thumb = thumb;
+            }
+            row_stats.set_excluding(hidden_cols > 0);
+            row_stats.invalidate();
+            session_state.hidden_columns = hidden.names().clone();
+            session_state.column_order = column_order.names().to_vec();
+            if let Err(e) = session.save(&session_state) {
+                warn!("display_spreadsheet_interactive: column changes not saved: {e:#}");
+            }
+        }
+        // Groups only apply to the N×F table; F×N shows every feature.
+        let grouping = groups.as_ref().filter(|_| !nav.transposed());
+        let shown = match grouping {
+            Some(_) => grouped.as_ref(),
+            None => transformed.as_ref(),
+        }
+        .unwrap_or(data);
+        if neighbor_search.as_ref().is_some_and(|s| s.is_finished())
+            && let Some(search) = neighbor_search.take()
+        {
+            let (row, metric) = (search.row, search.metric);
+            info!(
+                "display_spreadsheet_interactive: nearest rows to {} ({}) ready",
+                row,
+                metric.label()
+            );
+            neighbors_popup = Some(NeighborsPopup::new(data, row, metric, search.join()));
+            redraw.mark();
+        }
+
+        dims.rows = num_rows;
+        dims.features = grouping.map_or(all_col_indices.len(), |g| g.view_columns().len());
+        nav.clamp(dims);
+        if let Some(follow) = follow.as_mut() {
+            follow.observe(&nav);
+            if follow.pin(&mut nav, num_rows, page) {
+                redraw.mark();
+            }
+        }
+
+        // The progress toast of a running search changes on every poll.
+        if neighbor_search.is_some() {
+            redraw.mark();
+        }
+        if redraw.take() {
+            let computed: Vec<&VectorColumn> =
+                norm.iter().filter(|_| show_norm).chain(&dot).collect();
+            let view_cols = grouping.map(|g| g.view_columns());
+            if show_summary && view_stats.is_none() {
+                view_stats = Some(source_column_stats(data, &all_col_indices));
+            }
+            let summary =
+                view_stats
+                    .as_deref()
+                    .filter(|_| show_summary)
+                    .map(|stats| SummaryPanel {
+                        rows: data,
+                        features: &all_col_indices,
+                        stats,
+                    });
+            let mut drawn_rows = None;
+            let frame = terminal.draw(|f| {
+                (thumbnail_area, drawn_rows) = render_frame(
                     f,
-                    batch,
-                    &all_col_indices,
-                    col_offset,
-                    visible,
-                    num_rows,
-                    num_cols,
-                    row_start,
+                    shown,
+                    &layout,
+                    view_cols.as_deref().unwrap_or(&all_col_indices),
+                    nav.offsets(&marked),
+                    nav.visible(),
+                    nav.row_start(),
+                    nav.transposed(),
+                    source,
+                    thumbnail.as_mut().filter(|_| show_thumbnail),
+                    reordering.as_ref(),
+                    laplacian,
+                    &vector_panels,
+                    &row_stats,
+                    &bookmarks,
+                    diff.as_ref(),
+                    &computed,
+                    outlier_cells.as_ref().filter(|_| show_outliers),
+                    grouping,
+                    added,
+                    hidden_cols,
+                    summary,
+                    &formats,
                 );
-            }
-            _ => {
-                if transposed {
-                    render_transposed_ui(
-                        f,
-                        batch,
-                        &all_col_indices,
-                        row_offset,
-                        visible,
-                        num_rows,
-                        num_cols,
-                        row_start,
-                    );
-                } else {
-                    render_base_ui(
+                if let Some(popup) = metadata_popup.as_mut() {
+                    render_metadata_popup(f, popup);
+                }
+                if let Some(picker) = column_picker.as_mut() {
+                    render_column_picker(f, picker, &formats);
+                }
+                if let Some(detail) = record_detail.as_mut() {
+                    render_sparse_detail(f, detail);
+                }
+                if let Some(detail) = vector_detail.as_mut() {
+                    render_vector_detail(f, detail);
+                }
+                if let Some(popup) = frequency_popup.as_mut() {
+                    render_frequency_popup(f, popup);
+                }
+                if let Some(prompt) = metric_prompt.as_ref() {
+                    render_metric_prompt(f, prompt);
+                }
+                if let Some(search) = neighbor_search.as_ref() {
+                    render_search_toast(f, search);
+                }
+                if let Some(popup) = neighbors_popup.as_ref() {
+                    render_neighbors_popup(f, popup);
+                }
+                if let Some(prompt) = rename_prompt.as_ref() {
+                    render_rename_prompt(f, prompt);
+                }
+                if let Some(list) = bookmark_list.as_ref() {
+                    render_bookmark_list(f, list);
+                }
+                if let Some(list) = hidden_list.as_ref() {
+                    render_hidden_list(f, list);
+                }
+                if let Some(popup) = log_popup.as_mut() {
+                    render_log_popup(f, popup, &logging::tail(logging::RING_LINES));
+                }
+                if let Some(follow) = follow.as_ref() {
+                    render_follow_badge(f, follow);
+                }
+                if let Some(row) = bookmarks.cursor() {
+                    render_review_badge(
                         f,
-                        batch,
-                        &all_col_indices,
-                        col_offset,
-                        visible,
-                        num_rows,
-                        num_cols,
-                        row_start,
+                        &row_label(data, row),
+                        bookmarks.is_flagged(data, row),
+                        bookmarks.flags().len(),
                     );
                 }
+                if let Some(prompt) = flags_prompt.as_ref() {
+                    render_flags_prompt(f, prompt);
+                }
+                if let Some(prompt) = export_prompt.as_ref() {
+                    render_export_prompt(f, prompt);
+                }
+                if let Some(text) = notice.as_deref() {
+                    render_notice(f, text);
+                }
+                if let Some(view) = scatter.as_ref() {
+                    render_scatter(f, view);
+                }
+                capture.render(f);
+            })?;
+            capture.keep(frame.buffer);
+            // a new page height (first frame, resize) moves the last page
+            if let Some(rows) = drawn_rows
+                && follow.is_some()
+                && std::mem::replace(&mut page, rows.len) != rows.len
+            {
+                redraw.mark();
+                continue;
             }
-        })?;
+        }
 
-        // clamp horizontal offsets
-        if let LanceLayout::SparseCoo = layout {
-            // For sparse COO, get the matrix dimensions to clamp properly
-            // You may need to extract this info or pass it from render_coo_ui
-            // For now, we'll handle it in the key event section
-        } else if transposed {
-            let max_row_off = num_rows.saturating_sub(visible);
-            if row_offset > max_row_off {
-                debug!(
-                    "display_spreadsheet_interactive: clamp row_offset {} -> {}",
-                    row_offset, max_row_off
-                );
-                row_offset = max_row_off;
-            }
-        } else {
-            let max_col_off = all_col_indices.len().saturating_sub(visible);
-            if col_offset > max_col_off {
-                debug!(
-                    "display_spreadsheet_interactive: clamp col_offset {} -> {}",
-                    col_offset, max_col_off
+        let Some(event) = poll_event(redraw.poll_timeout())? else {
+            redraw.observe(None);
+            continue;
+        };
+        redraw.observe(Some(&event));
+
+        // Clicks are only captured while the overview panel is open.
+        if let Event::Mouse(mouse) = event {
+            if let (MouseEventKind::Down(MouseButton::Left), Some(area), Some(state)) =
+                (mouse.kind, thumbnail_area, thumbnail.as_mut())
+                && let Some((row, feature)) = state.click(area, mouse.column, mouse.row)
+            {
+                info!(
+                    "display_spreadsheet_interactive: overview click -> row {}, feature {}",
+                    row, feature
                 );
-                col_offset = max_col_off;
+                nav.scroll_to(row, view_position(grouping, feature));
             }
+            continue;
         }
 
-        // clamp vertical offset
-        let max_row_start = num_rows.saturating_sub(1);
-        if row_start > max_row_start {
-            debug!(
-                "display_spreadsheet_interactive: clamp row_start {} -> {}",
-                row_start, max_row_start
-            );
-            row_start = max_row_start;
-        }
-
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
-                match code {
-                    KeyCode::Char('q') | KeyCode::Esc => {
-                        info!("display_spreadsheet_interactive: user quit (q/ESC)");
-                        break;
-                    }
-
-                    KeyCode::Char('t') => {
-                        // Transpose only for dense layouts
-                        match layout {
-                            LanceLayout::DenseRowMajor | LanceLayout::Other => {
-                                transposed = !transposed;
-                                col_offset = 0;
-                                row_offset = 0;
-                                row_start = 0;
-                                info!(
-                                    "display_spreadsheet_interactive: toggle transpose -> mode={} (N×F=false,F×N=true)",
-                                    transposed
-                                );
-                            }
-                            _ => {
-                                // SparseCoo and Vector1D don't support transpose
+        if let Some(key) = pressed(&event) {
+            let KeyEvent {
+                code, modifiers, ..
+            } = key;
+            // Ctrl-C quits from any popup.
+            if is_interrupt(&key) {
+                info!("display_spreadsheet_interactive: user quit (Ctrl-C)");
+                break;
+            }
+            notice = None;
+            // `Ctrl-s` dumps whatever is on screen, popups included.
+            if capture.handle_key(&key) {
+                continue;
+            }
+            if let Some(view) = scatter.as_mut() {
+                if !view.handle_key(code) {
+                    scatter = None;
+                }
+                continue;
+            }
+            // The metadata popup swallows keys while it is open.
+            if let Some(popup) = metadata_popup.as_mut() {
+                if !popup.handle_key(code) {
+                    metadata_popup = None;
+                } else if let Some((key, value)) = popup.take_write() {
+                    let dataset = Path::new(&provenance.source);
+                    match set_meta_from_viewer(dataset, &key, &value) {
+                        Ok(version) => {
+                            info!(
+                                "display_spreadsheet_interactive: schema metadata {key} = {value} written as version {version}"
+                            );
+                            popup.written(&key, &value, version);
+                            written_metadata.insert(key, value);
+                        }
+                        Err(e) => popup.failed(format!("{e:#}")),
+                    }
+                }
+                continue;
+            }
+            if let Some(popup) = log_popup.as_mut() {
+                if !popup.handle_key(code) {
+                    log_popup = None;
+                }
+                continue;
+            }
+            if let Some(detail) = record_detail.as_mut() {
+                if !detail.handle_key(code) {
+                    record_detail = None;
+                }
+                continue;
+            }
+            if let Some(detail) = vector_detail.as_mut() {
+                if !detail.handle_key(code) {
+                    vector_detail = None;
+                }
+                continue;
+            }
+            if let Some(popup) = frequency_popup.as_mut() {
+                if !popup.handle_key(code) {
+                    frequency_popup = None;
+                }
+                continue;
+            }
+            if let Some(prompt) = metric_prompt.as_mut() {
+                match prompt.handle_key(code) {
+                    NeighborAction::Search(metric) => {
+                        info!(
+                            "display_spreadsheet_interactive: nearest rows to {} ({})",
+                            prompt.row,
+                            metric.label()
+                        );
+                        neighbor_search = Some(BackgroundSearch::start(&dense, prompt.row, metric));
+                        metric_prompt = None;
+                    }
+                    NeighborAction::Close => metric_prompt = None,
+                    _ => {}
+                }
+                continue;
+            }
+            if let Some(prompt) = rename_prompt.as_mut() {
+                match prompt.handle_key(code) {
+                    RenameAction::Apply(name) => {
+                        info!(
+                            "display_spreadsheet_interactive: column {} renamed to {:?}",
+                            prompt.col_idx, name
+                        );
+                        let col_idx = prompt.col_idx;
+                        rename_prompt = None;
+                        let named = set_feature_names(&all_rows.empty_batch(), &[(col_idx, name)])?;
+                        let all_rows = all_rows.with_schema(named.schema())?;
+                        let kept = null_rows.apply(&all_rows, &features)?;
+                        let data = kept.as_ref().unwrap_or(&all_rows);
+                        transformed = transform.as_ref().map(|t| t.source(data)).transpose()?;
+                        grouped = groups
+                            .as_ref()
+                            .map(|g| g.source(transformed.as_ref().unwrap_or(data)))
+                            .transpose()?;
+                        filtered = kept;
+                        renamed = Some(all_rows);
+                    }
+                    RenameAction::Close => rename_prompt = None,
+                    RenameAction::Stay => {}
+                }
+                continue;
+            }
+            if let Some(list) = bookmark_list.as_mut() {
+                match list.handle_key(code) {
+                    BookmarkAction::Jump(row) => {
+                        nav.scroll_to_row(row);
+                        bookmark_list = None;
+                    }
+                    BookmarkAction::Close => bookmark_list = None,
+                    BookmarkAction::Stay => {}
+                }
+                continue;
+            }
+            if let Some(prompt) = flags_prompt.as_mut() {
+                match prompt.handle_key(code) {
+                    FlagsAction::Write { path, lance } => {
+                        flags_prompt = None;
+                        let ids: Vec<u64> = bookmarks.flags().iter().copied().collect();
+                        let lance_path = path.with_extension("lance");
+                        let written = bookmarks.export_flags(&path).and_then(|()| {
+                            if lance {
+                                export_row_ids(&ids, provenance, &lance_path)?;
                             }
+                            Ok(())
+                        });
+                        notice = Some(match written {
+                            Ok(()) if lance => format!(
+                                "{} flags written to {} and {}",
+                                ids.len(),
+                                path.display(),
+                                lance_path.display()
+                            ),
+                            Ok(()) => format!("{} flags written to {}", ids.len(), path.display()),
+                            Err(e) => format!("{e:#}"),
+                        });
+                        info!(
+                            "display_spreadsheet_interactive: {}",
+                            notice.as_deref().unwrap_or_default()
+                        );
+                    }
+                    FlagsAction::Close => flags_prompt = None,
+                    FlagsAction::Stay => {}
+                }
+                continue;
+            }
+            if let Some(prompt) = export_prompt.as_mut() {
+                match prompt.handle_key(code) {
+                    ExportAction::Write(choices) => {
+                        export_prompt = None;
+                        export_choices = choices;
+                        let exported = match transformed.as_ref() {
+                            Some(shown) if choices.transformed => shown,
+                            _ => data,
+                        };
+                        let computed: Vec<&VectorColumn> = norm
+                            .iter()
+                            .filter(|_| show_norm && choices.computed)
+                            .chain(dot.iter().filter(|_| choices.computed))
+                            .collect();
+                        // features in the order shown; deleted rows shown by
+                        // `--include-deleted` stay deleted
+                        let table_cols = table_columns(&features, &unsupported, show_unsupported);
+                        let ordered = column_order.apply(&stored_schema, &table_cols);
+                        let written = reorder_columns(exported.whole(), &table_cols, &ordered)
+                            .and_then(|batch| with_vector_columns(&batch, data, &computed))
+                            .and_then(|batch| drop_tombstones(&batch))
+                            .and_then(|batch| {
+                                if choices.formatted {
+                                    formats.apply(&batch)
+                                } else {
+                                    Ok(batch)
+                                }
+                            })
+                            .and_then(|batch| export_view(&batch, provenance, Path::new(".")));
+                        notice = Some(match written {
+                            Ok(path) => format!("{} rows written to {}", num_rows, path.display()),
+                            Err(e) => format!("{e:#}"),
+                        });
+                        info!(
+                            "display_spreadsheet_interactive: {}",
+                            notice.as_deref().unwrap_or_default()
+                        );
+                    }
+                    ExportAction::Close => export_prompt = None,
+                    ExportAction::Stay => {}
+                }
+                continue;
+            }
+            if let Some(list) = hidden_list.as_mut() {
+                match list.handle_key(code) {
+                    HiddenAction::Show(names) => {
+                        for name in &names {
+                            hidden.show(name);
                         }
+                        info!("display_spreadsheet_interactive: columns {names:?} shown again");
+                        columns_changed = true;
                     }
-
-                    // horizontal right
-                    KeyCode::Right | KeyCode::Char('l') => {
-                        if let LanceLayout::SparseCoo = layout {
-                            // Horizontal scroll for sparse matrix columns
-                            sparse_col_offset += 1;
-                            debug!(
-                                "display_spreadsheet_interactive: sparse_col_offset -> {} (→)",
-                                sparse_col_offset
+                    HiddenAction::Close => hidden_list = None,
+                    HiddenAction::Stay => {}
+                }
+                continue;
+            }
+            if let Some(popup) = neighbors_popup.as_mut() {
+                match popup.handle_key(code) {
+                    NeighborAction::Jump(row) => {
+                        nav.scroll_to_row(row);
+                        neighbors_popup = None;
+                    }
+                    NeighborAction::Close => neighbors_popup = None,
+                    _ => {}
+                }
+                continue;
+            }
+            if let Some(picker) = column_picker.as_mut() {
+                if code == KeyCode::Char('x') && modifiers.contains(KeyModifiers::CONTROL) {
+                    if let Some(feature_idx) = picker.selected_feature() {
+                        toggle_mark(&mut marked, feature_idx);
+                        diff = diff.and_then(|d| {
+                            DiffColumn::of_marks(data, &all_col_indices, &marked, d.kind)
+                        });
+                    }
+                    continue;
+                }
+                if code == KeyCode::Char(FORMAT_KEY) && modifiers.contains(KeyModifiers::CONTROL) {
+                    let col = picker
+                        .selected_feature()
+                        .and_then(|feature_idx| all_col_indices.get(feature_idx).copied());
+                    if let Some(col) = col {
+                        let schema = data.schema();
+                        let field = schema.field(col);
+                        if is_formattable(field.data_type()) {
+                            let format = formats.cycle(field.name());
+                            info!(
+                                "display_spreadsheet_interactive: column {} format -> {}",
+                                field.name(),
+                                format
                             );
-                        } else if transposed {
-                            let max = num_rows.saturating_sub(visible);
-                            if row_offset < max {
-                                row_offset += 1;
-                                debug!(
-                                    "display_spreadsheet_interactive: row_offset -> {} (F×N, →)",
-                                    row_offset
-                                );
+                            session_state.value_formats = formats.labels();
+                            if let Err(e) = session.save(&session_state) {
+                                warn!("display_spreadsheet_interactive: formats not saved: {e:#}");
                             }
                         } else {
-                            let max = all_col_indices.len().saturating_sub(visible);
-                            if col_offset < max {
-                                col_offset += 1;
-                                debug!(
-                                    "display_spreadsheet_interactive: col_offset -> {} (N×F, →)",
-                                    col_offset
-                                );
-                            }
+                            notice = Some(format!(
+                                "{} is not an integer column; formats apply to integers",
+                                display_name(field)
+                            ));
                         }
                     }
+                    continue;
+                }
+                match picker.handle_key(code) {
+                    PickerAction::Stay => {}
+                    PickerAction::Close => column_picker = None,
+                    PickerAction::Jump { feature_idx, pin } => {
+                        nav.show_feature(view_position(grouping, feature_idx), pin);
+                        column_picker = None;
+                        info!(
+                            "display_spreadsheet_interactive: picker jump -> feature {} (pinned={})",
+                            feature_idx, pin
+                        );
+                    }
+                }
+                continue;
+            }
+            // A focused distribution panel takes its bin and range keys.
+            if vector_panels.distribution.focused {
+                if matches!(code, KeyCode::Tab | KeyCode::Esc) {
+                    vector_panels.distribution.focused = false;
+                } else {
+                    vector_panels.distribution.handle_key(code);
+                }
+                continue;
+            }
+            // A focused overview panel takes the arrows and Enter.
+            if let Some(state) = thumbnail
+                .as_mut()
+                .filter(|s| s.focused && thumbnail_area.is_some())
+            {
+                if matches!(code, KeyCode::Tab | KeyCode::Esc) {
+                    state.focused = false;
+                } else if let Some((row, feature)) = state.handle_key(code) {
+                    info!(
+                        "display_spreadsheet_interactive: overview jump -> row {}, feature {}",
+                        row, feature
+                    );
+                    nav.scroll_to(row, view_position(grouping, feature));
+                }
+                continue;
+            }
 
-                    // horizontal left
-                    KeyCode::Left | KeyCode::Char('h') => {
-                        if let LanceLayout::SparseCoo = layout {
-                            // Horizontal scroll for sparse matrix columns
-                            if sparse_col_offset > 0 {
-                                sparse_col_offset -= 1;
-                                debug!(
-                                    "display_spreadsheet_interactive: sparse_col_offset -> {} (←)",
-                                    sparse_col_offset
-                                );
-                            }
-                        } else if transposed {
-                            if row_offset > 0 {
-                                row_offset -= 1;
-                                debug!(
-                                    "display_spreadsheet_interactive: row_offset -> {} (F×N, ←)",
-                                    row_offset
-                                );
-                            }
-                        } else if col_offset > 0 {
-                            col_offset -= 1;
-                            debug!(
-                                "display_spreadsheet_interactive: col_offset -> {} (N×F, ←)",
-                                col_offset
-                            );
-                        }
+            match code {
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    info!("display_spreadsheet_interactive: user quit (q/ESC)");
+                    break;
+                }
+
+                // Review mode: Space steps the cursor down a row, centred,
+                // `x` flags it, `V` ends the review and writes the flags
+                KeyCode::Char(REVIEW_KEY) if bookmarks.cursor().is_some() => {
+                    bookmarks.set_cursor(None);
+                    info!(
+                        "display_spreadsheet_interactive: review ended, {} flagged",
+                        bookmarks.flags().len()
+                    );
+                    flags_prompt = Some(FlagsPrompt::new(
+                        &flags_file(&provenance.source),
+                        bookmarks.flags().len(),
+                    ));
+                }
+                KeyCode::Char(REVIEW_KEY)
+                    if layout != LanceLayout::SparseCoo && !nav.transposed() && num_rows > 0 =>
+                {
+                    // resume at the row the last review stopped on
+                    let row = session_state
+                        .review_row
+                        .and_then(|id| row_of_id(data, id))
+                        .unwrap_or(nav.row_start());
+                    bookmarks.set_cursor(Some(row));
+                    nav.scroll_to_row(centered_start(row, page));
+                    info!("display_spreadsheet_interactive: reviewing from row {row}");
+                }
+                KeyCode::Char(' ') if bookmarks.cursor().is_some() && num_rows > 0 => {
+                    let row = bookmarks
+                        .cursor()
+                        .map_or(0, |r| (r + 1).min(num_rows.saturating_sub(1)));
+                    bookmarks.set_cursor(Some(row));
+                    nav.scroll_to_row(centered_start(row, page));
+                    session_state.review_row = Some(row_id(data, row));
+                    if let Err(e) = session.save(&session_state) {
+                        warn!("display_spreadsheet_interactive: review row not saved: {e:#}");
+                    }
+                }
+                KeyCode::Char(FLAG_KEY) if bookmarks.cursor().is_some() => {
+                    let row = bookmarks.cursor().unwrap_or_default();
+                    if is_tombstone(data, row) {
+                        notice = Some("deleted rows cannot be flagged".to_string());
+                        continue;
                     }
+                    let flagged = bookmarks.toggle_flag(data, row);
+                    info!(
+                        "display_spreadsheet_interactive: row {} flag -> {}",
+                        row_label(data, row),
+                        flagged
+                    );
+                    session_state.flagged = bookmarks.flags().clone();
+                    session_state.review_row = Some(row_id(data, row));
+                    if let Err(e) = session.save(&session_state) {
+                        warn!("display_spreadsheet_interactive: flags not saved: {e:#}");
+                    }
+                }
 
-                    // jump first/last horizontally
-                    KeyCode::Char('H') => {
-                        if let LanceLayout::SparseCoo = layout {
-                            sparse_col_offset = 0;
-                            debug!("display_spreadsheet_interactive: sparse_col_offset -> 0 (H)");
-                        } else if transposed {
-                            row_offset = 0;
-                            debug!("display_spreadsheet_interactive: row_offset -> 0 (H)");
+                KeyCode::Char('a') => {
+                    row_stats.toggle();
+                    info!(
+                        "display_spreadsheet_interactive: row aggregates {:?}",
+                        row_stats.columns()
+                    );
+                }
+
+                KeyCode::Char('A') => {
+                    row_stats.toggle_scope();
+                    info!(
+                        "display_spreadsheet_interactive: row aggregates over {}",
+                        if row_stats.is_windowed() {
+                            "the visible window"
                         } else {
-                            col_offset = 0;
-                            debug!("display_spreadsheet_interactive: col_offset -> 0 (H)");
+                            "everything"
                         }
+                    );
+                }
+
+                // Back to following the last page after scrolling up
+                KeyCode::Char(FOLLOW_KEY) if follow.is_some() => {
+                    if let Some(follow) = follow.as_mut() {
+                        follow.resume();
                     }
-                    KeyCode::Char('E') => {
-                        if let LanceLayout::SparseCoo = layout {
-                            // Jump to end - will be clamped in render function
-                            sparse_col_offset = usize::MAX;
-                            debug!("display_spreadsheet_interactive: sparse_col_offset -> MAX (E)");
-                        } else if transposed {
-                            row_offset = num_rows.saturating_sub(visible);
-                            debug!(
-                                "display_spreadsheet_interactive: row_offset -> {} (E)",
-                                row_offset
-                            );
+                }
+
+                KeyCode::Char(INDEX_BASE_KEY) => {
+                    let base = index_base().next();
+                    set_index_base(base);
+                    notice = Some(base.label().to_string());
+                    info!("display_spreadsheet_interactive: {}", base.label());
+                }
+
+                KeyCode::Char(NULL_POLICY_KEY) => {
+                    row_stats.cycle_null_policy();
+                    info!(
+                        "display_spreadsheet_interactive: NULLs in aggregates: {:?}",
+                        row_stats.null_policy()
+                    );
+                }
+
+                KeyCode::Char('M') => {
+                    info!("display_spreadsheet_interactive: opening metadata popup");
+                    let schema = data.schema();
+                    let mut metadata = schema.metadata().clone();
+                    metadata.extend(written_metadata.clone());
+                    let schema = schema.as_ref().clone().with_metadata(metadata);
+                    metadata_popup = Some(MetadataPopup::new(&schema).editable());
+                }
+
+                // Column search only makes sense for the N×F feature table
+                KeyCode::Char('|')
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other) =>
+                {
+                    info!("display_spreadsheet_interactive: opening column picker");
+                    column_picker = Some(ColumnPicker::new(data.whole(), &all_col_indices));
+                }
+
+                // Expand the selected (top visible) sparse record
+                KeyCode::Enter
+                    if layout == LanceLayout::SparsePerRow && nav.row_start() < num_rows =>
+                {
+                    info!(
+                        "display_spreadsheet_interactive: opening record {} entries",
+                        nav.row_start()
+                    );
+                    record_detail = Some(SparseRecordDetail::new(data, nav.row_start()));
+                }
+
+                // Every value of the top row's vector in the leftmost
+                // visible column, with a sparkline
+                KeyCode::Enter
+                    if !nav.transposed()
+                        && nav.current_row() < num_rows
+                        && view_feature(grouping, nav.current_feature())
+                            .and_then(|f| all_col_indices.get(f))
+                            .is_some_and(|&c| {
+                                is_vector_type(data.schema().field(c).data_type())
+                            }) =>
+                {
+                    let col = view_feature(grouping, nav.current_feature())
+                        .and_then(|f| all_col_indices.get(f).copied())
+                        .unwrap_or_default();
+                    info!(
+                        "display_spreadsheet_interactive: opening vector of row {} in column {}",
+                        nav.current_row(),
+                        col
+                    );
+                    vector_detail = VectorDetail::new(data, nav.current_row(), col);
+                }
+
+                // Value counts of the leftmost visible feature (N×F, 1D) or
+                // the top feature row (F×N)
+                KeyCode::Char('i') => {
+                    let target = match layout {
+                        LanceLayout::DenseRowMajor | LanceLayout::Other if nav.transposed() => {
+                            all_col_indices.get(nav.row_start()).copied()
+                        }
+                        LanceLayout::DenseRowMajor | LanceLayout::Other => {
+                            view_feature(grouping, nav.current_feature())
+                                .and_then(|f| all_col_indices.get(f).copied())
+                        }
+                        LanceLayout::Vector1D => {
+                            all_col_indices.get(nav.current_feature()).copied()
+                        }
+                        _ => None,
+                    };
+                    if let Some(col_idx) = target {
+                        info!(
+                            "display_spreadsheet_interactive: frequencies of column {}",
+                            col_idx
+                        );
+                        frequency_popup = Some(FrequencyPopup::new(data.whole(), col_idx));
+                    }
+                }
+
+                // Mark the leftmost visible feature for the scatter plot, or
+                // use it to colour the plot
+                KeyCode::Char('x') | KeyCode::Char('c')
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other) =>
+                {
+                    let Some(feature_idx) = view_feature(grouping, nav.current_feature()) else {
+                        notice = Some("Enter expands the group to pick a column".to_string());
+                        continue;
+                    };
+                    if code == KeyCode::Char('x') {
+                        toggle_mark(&mut marked, feature_idx);
+                        diff = diff.and_then(|d| {
+                            DiffColumn::of_marks(data, &all_col_indices, &marked, d.kind)
+                        });
+                    } else if let Some(&col_idx) = all_col_indices.get(feature_idx) {
+                        label_col = (label_col != Some(col_idx)).then_some(col_idx);
+                    }
+                    info!(
+                        "display_spreadsheet_interactive: marked {:?}, label column {:?}",
+                        marked, label_col
+                    );
+                }
+
+                // Tail of the log
+                KeyCode::Char('L') => {
+                    info!("display_spreadsheet_interactive: opening log popup");
+                    log_popup = Some(LogPopup::new());
+                }
+
+                // Difference, then relative error, of the two marked features
+                KeyCode::Char(DIFF_KEY)
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other) =>
+                {
+                    let next = DiffKind::cycle(diff.as_ref().map(|d| d.kind));
+                    diff = next.and_then(|kind| {
+                        DiffColumn::of_marks(data, &all_col_indices, &marked, kind)
+                    });
+                    if next.is_some() && diff.is_none() {
+                        notice = Some(if marked.len() < 2 {
+                            "mark two columns with x to compare them".to_string()
                         } else {
-                            col_offset = all_col_indices.len().saturating_sub(visible);
-                            debug!(
-                                "display_spreadsheet_interactive: col_offset -> {} (E)",
-                                col_offset
+                            "the marked columns are not both numeric".to_string()
+                        });
+                    }
+                    info!(
+                        "display_spreadsheet_interactive: diff column -> {:?}",
+                        diff.as_ref().map(|d| (d.a, d.b, d.kind))
+                    );
+                }
+
+                // L2 norm of each row, and its dot product with the top row
+                KeyCode::Char('n')
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other)
+                        && !all_col_indices.is_empty() =>
+                {
+                    show_norm = !show_norm;
+                    norm.get_or_insert_with(|| {
+                        VectorColumn::norm(data, dense_rows, &all_col_indices)
+                    });
+                    info!("display_spreadsheet_interactive: norm column shown={show_norm}");
+                }
+                KeyCode::Char('.')
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other)
+                        && !all_col_indices.is_empty() =>
+                {
+                    let row = nav.current_row();
+                    dot = match dot.take() {
+                        Some(d) if d.kind == VectorKind::Dot { reference: row } => None,
+                        _ => Some(VectorColumn::dot(data, dense_rows, &all_col_indices, row)),
+                    };
+                    info!(
+                        "display_spreadsheet_interactive: dot column -> {:?}",
+                        dot.as_ref().map(|d| d.kind)
+                    );
+                }
+
+                // Highlight values outside the `outliers` fences
+                KeyCode::Char('!')
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other) =>
+                {
+                    show_outliers = !show_outliers;
+                    let stats = view_stats
+                        .get_or_insert_with(|| source_column_stats(data, &all_col_indices));
+                    let cells = outlier_cells
+                        .get_or_insert_with(|| OutlierCells::new(data, &all_col_indices, stats));
+                    info!(
+                        "display_spreadsheet_interactive: outliers {} ({})",
+                        if show_outliers { "shown" } else { "hidden" },
+                        cells.note()
+                    );
+                }
+
+                // Compare the drawn window with all rows beside the table
+                KeyCode::Char(SUMMARY_KEY)
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other) =>
+                {
+                    show_summary = !show_summary;
+                    if show_summary && grouping.is_some() {
+                        notice = Some(
+                            "the summaries show once the columns are ungrouped (g)".to_string(),
+                        );
+                    }
+                    info!(
+                        "display_spreadsheet_interactive: window summaries {}",
+                        if show_summary { "shown" } else { "hidden" }
+                    );
+                }
+
+                // Fewer, wider or more, narrower feature (F×N: sample) columns
+                KeyCode::Char('<' | '>') => {
+                    if code == KeyCode::Char('<') {
+                        dims.visible = dims.visible.saturating_sub(1).max(1);
+                    } else if dims.visible < dims.features.max(dims.rows) {
+                        dims.visible += 1;
+                    }
+                    info!(
+                        "display_spreadsheet_interactive: visible columns -> {}",
+                        dims.visible
+                    );
+                    if session_state.visible_cols != Some(dims.visible) {
+                        session_state.visible_cols = Some(dims.visible);
+                        if let Err(e) = session.save(&session_state) {
+                            warn!(
+                                "display_spreadsheet_interactive: visible columns not saved: {e:#}"
                             );
                         }
                     }
+                }
 
-                    // vertical scroll
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        if row_start > 0 {
-                            row_start -= 1;
-                            debug!(
-                                "display_spreadsheet_interactive: row_start -> {} (↑/k)",
-                                row_start
-                            );
+                // Show the features raw, z-scored or min-max scaled
+                KeyCode::Char('z')
+                    if matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other)
+                        && !all_col_indices.is_empty() =>
+                {
+                    let stats = view_stats
+                        .get_or_insert_with(|| source_column_stats(data, &all_col_indices));
+                    transform = Transform::cycle(transform.as_ref().map(|t| t.kind))
+                        .map(|kind| ColumnTransform::new(kind, data, &all_col_indices, stats));
+                    transformed = transform.as_ref().map(|t| t.source(data)).transpose()?;
+                    grouped = groups
+                        .as_ref()
+                        .map(|g| g.source(transformed.as_ref().unwrap_or(data)))
+                        .transpose()?;
+                    row_stats.invalidate();
+                    if let Some(state) = thumbnail.as_mut()
+                        && let Some(thumb) =
+                            MatrixThumbnail::from_matrices_mapped(&dense, |f, v| {
+                                transform.as_ref().map_or(v, |t| t.apply(f, v))
+                            })
+                    {
+                        state.thumb = thumb;
+                    }
+                    info!(
+                        "display_spreadsheet_interactive: transform -> {}",
+                        transform.as_ref().map_or("raw", |t| t.kind.label())
+                    );
+                }
+
+                // Show the columns the table cannot show as values, as
+                // placeholders after the features, or hide them again
+                KeyCode::Char(UNSUPPORTED_KEY) if !unsupported.is_empty() => {
+                    show_unsupported = !show_unsupported;
+                    let table_cols = table_columns(&features, &unsupported, show_unsupported);
+                    let ordered = column_order.apply(&stored_schema, &table_cols);
+                    all_col_indices = hidden.visible(&stored_schema, &ordered);
+                    hidden_cols = table_cols.len() - all_col_indices.len();
+                    // groups are built over the features they were shown
+                    if groups.take().is_some() {
+                        grouped = None;
+                    }
+                    info!(
+                        "display_spreadsheet_interactive: {} unsupported columns {}",
+                        unsupported.len(),
+                        if show_unsupported { "shown" } else { "hidden" }
+                    );
+                }
+
+                // Only the rows with a NULL feature, then only the rows
+                // without, then all rows again
+                KeyCode::Char(NULL_ROWS_KEY)
+                    if matches!(
+                        layout,
+                        LanceLayout::DenseRowMajor | LanceLayout::Other | LanceLayout::Vector1D
+                    ) && !features.is_empty()
+                        && neighbor_search.is_none() =>
+                {
+                    let filter = null_rows.cycle(all_rows, &features)?;
+                    let kept = null_rows.apply(all_rows, &features)?;
+                    let rows = kept.as_ref().unwrap_or(all_rows);
+                    let dense_rows: &[DenseMatrix] = if kept.is_some() { &[] } else { &dense };
+                    // the review cursor stays on its row, or ends if the
+                    // row is filtered out
+                    let cursor_id = bookmarks.cursor().map(|r| row_id(data, r));
+                    bookmarks.set_cursor(cursor_id.and_then(|id| row_of_id(rows, id)));
+                    // everything derived from the rows follows them; the
+                    // dot column's reference row may be gone
+                    norm = norm.map(|_| VectorColumn::norm(rows, dense_rows, &all_col_indices));
+                    dot = None;
+                    diff = diff.and_then(|d| {
+                        DiffColumn::of_marks(rows, &all_col_indices, &marked, d.kind)
+                    });
+                    view_stats = None;
+                    outlier_cells = None;
+                    if show_outliers {
+                        let stats = view_stats.insert(source_column_stats(rows, &all_col_indices));
+                        outlier_cells = Some(OutlierCells::new(rows, &all_col_indices, stats));
+                    }
+                    transformed = transform.as_ref().map(|t| t.source(rows)).transpose()?;
+                    grouped = groups
+                        .as_ref()
+                        .map(|g| g.source(transformed.as_ref().unwrap_or(rows)))
+                        .transpose()?;
+                    row_stats.invalidate();
+                    if kept.is_some() && show_thumbnail {
+                        show_thumbnail = false;
+                        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+                        thumbnail_area = None;
+                    }
+                    notice = match filter {
+                        NullFilter::Complete if rows.num_rows() == all_rows.num_rows() => {
+                            Some("no row has a NULL feature".to_string())
+                        }
+                        _ => None,
+                    };
+                    info!(
+                        "display_spreadsheet_interactive: rows {} ({} of {})",
+                        filter.label().unwrap_or("all"),
+                        rows.num_rows(),
+                        all_rows.num_rows()
+                    );
+                    filtered = kept;
+                }
+
+                // Collapse the features sharing a name prefix (or listed
+                // together by `--groups`) into their row means
+                KeyCode::Char('g')
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other)
+                        && !all_col_indices.is_empty() =>
+                {
+                    let current = view_feature(grouping, nav.current_feature());
+                    if groups.take().is_some() {
+                        grouped = None;
+                        nav.show_feature(current.unwrap_or_default(), false);
+                    } else {
+                        match ColumnGroups::new(data, &all_col_indices, &configured_spec()) {
+                            Ok(g) if g.is_empty() => {
+                                notice = Some("no two features share a group".to_string());
+                            }
+                            Ok(g) => {
+                                grouped = Some(g.source(transformed.as_ref().unwrap_or(data))?);
+                                nav.show_feature(
+                                    view_position(Some(&g), current.unwrap_or_default()),
+                                    false,
+                                );
+                                groups = Some(g);
+                            }
+                            Err(e) => notice = Some(format!("{e:#}")),
                         }
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        if row_start < max_row_start {
-                            row_start += 1;
-                            debug!(
-                                "display_spreadsheet_interactive: row_start -> {} (↓/j)",
-                                row_start
-                            );
+                    info!(
+                        "display_spreadsheet_interactive: column groups -> {}",
+                        groups.as_ref().map_or("off".to_string(), |g| g.note())
+                    );
+                }
+                KeyCode::Enter if !nav.transposed() && groups.is_some() => {
+                    if let Some(g) = groups.as_mut() {
+                        let col = g.view_columns().get(nav.current_feature()).copied();
+                        let name = col.and_then(|c| g.name(c)).map(str::to_string);
+                        match col.and_then(|c| g.toggle(c)) {
+                            Some(start) => nav.show_feature(start, false),
+                            None => notice = Some("not a grouped column".to_string()),
+                        }
+                        info!(
+                            "display_spreadsheet_interactive: group {:?} toggled ({})",
+                            name,
+                            g.note()
+                        );
+                    }
+                }
+
+                // Rename the leftmost visible feature for this session
+                KeyCode::Char('R')
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other) =>
+                {
+                    if let Some(&col_idx) = view_feature(grouping, nav.current_feature())
+                        .and_then(|f| all_col_indices.get(f))
+                    {
+                        let schema = data.schema();
+                        let field = schema.field(col_idx);
+                        rename_prompt = Some(RenamePrompt::new(
+                            col_idx,
+                            field.name(),
+                            display_name(field),
+                        ));
+                    }
+                }
+
+                // Hide the leftmost visible feature, here and in later
+                // sessions; `D` lists the hidden ones
+                KeyCode::Char(HIDDEN_LIST_KEY) => {
+                    info!("display_spreadsheet_interactive: opening hidden columns list");
+                    hidden_list = Some(HiddenList::new(&hidden));
+                }
+                KeyCode::Char(HIDE_COLUMN_KEY)
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other)
+                        && !all_col_indices.is_empty() =>
+                {
+                    let Some(feature_idx) = view_feature(grouping, nav.current_feature()) else {
+                        notice = Some("Enter expands the group to pick a column".to_string());
+                        continue;
+                    };
+                    if all_col_indices.len() == 1 {
+                        notice = Some("the last column cannot be hidden".to_string());
+                        continue;
+                    }
+                    let name = stored_schema.field(all_col_indices[feature_idx]).name();
+                    hidden.hide(name);
+                    info!("display_spreadsheet_interactive: column {name:?} hidden");
+                    nav.show_feature(feature_idx.min(all_col_indices.len() - 2), false);
+                    columns_changed = true;
+                }
+
+                // Hide every near-constant feature, as `d` would one by one
+                KeyCode::Char(HIDE_CONSTANT_KEY)
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other) =>
+                {
+                    let names: Vec<&String> = constant
+                        .iter()
+                        .map(|&c| stored_schema.field(c).name())
+                        .filter(|name| !hidden.names().contains(*name))
+                        .collect();
+                    if names.is_empty() {
+                        notice = Some("no near-constant columns to hide".to_string());
+                        continue;
+                    }
+                    if names.len() >= all_col_indices.len() {
+                        notice = Some("every column is near-constant; none hidden".to_string());
+                        continue;
+                    }
+                    for name in &names {
+                        hidden.hide(name);
+                    }
+                    info!(
+                        "display_spreadsheet_interactive: {} near-constant columns hidden",
+                        names.len()
+                    );
+                    notice = Some(format!(
+                        "{} near-constant columns hidden — {HIDDEN_LIST_KEY} brings them back",
+                        names.len()
+                    ));
+                    columns_changed = true;
+                }
+
+                // Move the pinned (else the leftmost) feature one place, here
+                // and in later sessions; `0` goes back to schema order
+                KeyCode::Left | KeyCode::Right
+                    if modifiers.contains(KeyModifiers::SHIFT)
+                        && !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other) =>
+                {
+                    if grouping.is_some() {
+                        notice = Some("ungroup (g) to move columns".to_string());
+                        continue;
+                    }
+                    let Some((from, to)) = nav.move_feature(code == KeyCode::Right) else {
+                        continue;
+                    };
+                    let (moved, passed) = (all_col_indices[from], all_col_indices[to]);
+                    let table_cols = table_columns(&features, &unsupported, show_unsupported);
+                    let ordered = column_order.apply(&stored_schema, &table_cols);
+                    column_order.swap(&stored_schema, &ordered, moved, passed);
+                    let name = display_name(stored_schema.field(moved));
+                    notice = Some(format!(
+                        "{name} moved to column {} of {}",
+                        to + 1,
+                        all_col_indices.len()
+                    ));
+                    info!("display_spreadsheet_interactive: {name:?} moved {from} -> {to}");
+                    columns_changed = true;
+                }
+                KeyCode::Char(RESET_ORDER_KEY)
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other) =>
+                {
+                    if column_order.reset() {
+                        notice = Some("columns back in schema order".to_string());
+                        info!("display_spreadsheet_interactive: column order reset");
+                        nav.show_feature(0, false);
+                        columns_changed = true;
+                    }
+                }
+
+                // Nearest rows to the top visible row of a dense matrix
+                KeyCode::Char('N')
+                    if !dense_rows.is_empty()
+                        && neighbor_search.is_none()
+                        && nav.row_start() < num_rows =>
+                {
+                    metric_prompt = Some(MetricPrompt::new(nav.row_start()));
+                }
+
+                // Bookmark the top row (F×N: the first sample column); COO
+                // triples are not rows
+                KeyCode::Char('b')
+                    if layout != LanceLayout::SparseCoo && nav.current_row() < num_rows =>
+                {
+                    let row = nav.current_row();
+                    if is_tombstone(data, row) {
+                        notice = Some("deleted rows cannot be bookmarked".to_string());
+                        continue;
+                    }
+                    let marked = bookmarks.toggle(data, row);
+                    info!(
+                        "display_spreadsheet_interactive: row {} bookmark -> {}",
+                        row_label(data, row),
+                        marked
+                    );
+                    session_state.bookmarks = bookmarks.ids().clone();
+                    if let Err(e) = session.save(&session_state) {
+                        warn!("display_spreadsheet_interactive: bookmarks not saved: {e:#}");
+                    }
+                }
+                KeyCode::Char('\'') if layout != LanceLayout::SparseCoo => {
+                    let cols: Vec<usize> = if all_col_indices.is_empty() {
+                        (0..data.num_columns())
+                            .filter(|&c| data.schema().field(c).name() != ROWID_COLUMN)
+                            .collect()
+                    } else {
+                        all_col_indices.clone()
+                    };
+                    bookmark_list = Some(BookmarkList::new(data, &bookmarks, &cols));
+                }
+                KeyCode::Char('B') if layout != LanceLayout::SparseCoo => {
+                    let path = Path::new(BOOKMARKS_FILE);
+                    notice = Some(match bookmarks.export(path) {
+                        Ok(()) => format!(
+                            "{} bookmarks written to {}",
+                            bookmarks.ids().len(),
+                            path.display()
+                        ),
+                        Err(e) => format!("{e:#}"),
+                    });
+                    info!(
+                        "display_spreadsheet_interactive: {}",
+                        notice.as_deref().unwrap_or_default()
+                    );
+                }
+
+                // Reverse Cuthill–McKee order of a square COO matrix
+                KeyCode::Char('r') if layout == LanceLayout::SparseCoo => {
+                    if reordering.take().is_none() {
+                        match Reordering::rcm(data.whole()) {
+                            Ok(r) => reordering = Some(r),
+                            Err(e) => notice = Some(format!("{e:#}")),
                         }
                     }
+                    info!(
+                        "display_spreadsheet_interactive: RCM order -> {:?}",
+                        reordering
+                            .as_ref()
+                            .map(|r| (r.before.bandwidth, r.after.bandwidth))
+                    );
+                }
 
-                    // Graph visualization mode (only for SparseCoo)
-                    KeyCode::Char('v') => {
-                        if let LanceLayout::SparseCoo = layout {
-                            info!("display_spreadsheet_interactive: entering graph view");
+                // Save the loaded rows, as shown, as a new Lance dataset
+                KeyCode::Char(EXPORT_KEY) => {
+                    export_prompt = Some(ExportPrompt::new(export_choices, num_rows));
+                }
 
-                            // Temporarily exit terminal mode
-                            disable_raw_mode()?;
-                            execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+                // Overview of the whole dense matrix beside the N×F table
+                KeyCode::Char('o')
+                    if thumbnail.is_some() && filtered.is_none() && !nav.transposed() =>
+                {
+                    show_thumbnail = !show_thumbnail;
+                    if show_thumbnail {
+                        execute!(terminal.backend_mut(), EnableMouseCapture)?;
+                    } else {
+                        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+                        thumbnail_area = None;
+                    }
+                    info!(
+                        "display_spreadsheet_interactive: overview panel -> {}",
+                        show_thumbnail
+                    );
+                }
+                KeyCode::Tab if layout == LanceLayout::Vector1D => {
+                    vector_panels.distribution.focused = true;
+                }
+                KeyCode::Char(PROFILE_KEY) if layout == LanceLayout::Vector1D => {
+                    vector_panels.profile = !vector_panels.profile;
+                    info!(
+                        "display_spreadsheet_interactive: row profile -> {}",
+                        vector_panels.profile
+                    );
+                }
+                KeyCode::Tab if thumbnail_area.is_some() => {
+                    if let Some(state) = thumbnail.as_mut() {
+                        state.focused = true;
+                    }
+                }
 
-                            // Show connectivity visualization
-                            if let Err(e) =
-                                crate::display::display_sparse_viz::display_connectivity_interactive(
-                                    batch,
-                                )
-                            {
-                                eprintln!("Error displaying connectivity: {}", e);
+                // Scatter plot of the two marked features
+                KeyCode::Char('v')
+                    if !nav.transposed()
+                        && matches!(layout, LanceLayout::DenseRowMajor | LanceLayout::Other) =>
+                {
+                    if let [x, y] = marked[..] {
+                        scatter = ScatterView::new(
+                            data.whole(),
+                            all_col_indices[x],
+                            all_col_indices[y],
+                            label_col,
+                        );
+                        info!(
+                            "display_spreadsheet_interactive: scatter of features {} and {} ({})",
+                            x,
+                            y,
+                            if scatter.is_some() {
+                                "opened"
+                            } else {
+                                "not numeric"
                             }
+                        );
+                    }
+                }
+                // Graph visualization mode (SparseCoo, and SparsePerRow
+                // flattened to COO)
+                KeyCode::Char('v') => {
+                    if let LanceLayout::SparseCoo | LanceLayout::SparsePerRow = layout {
+                        info!("display_spreadsheet_interactive: entering graph view");
+
+                        // Temporarily exit terminal mode
+                        disable_raw_mode()?;
+                        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+
+                        // Show connectivity visualization
+                        if let Err(e) =
+                            crate::display::display_sparse_viz::display_connectivity_interactive(
+                                data.whole(),
+                            )
+                        {
+                            eprintln!("Error displaying connectivity: {}", e);
+                        }
 
-                            // Re-enter terminal mode for COO view
-                            enable_raw_mode()?;
-                            execute!(io::stdout(), EnterAlternateScreen)?;
+                        // Re-enter terminal mode for COO view
+                        enable_raw_mode()?;
+                        execute!(io::stdout(), EnterAlternateScreen)?;
 
-                            // Recreate terminal
-                            let backend = CrosstermBackend::new(io::stdout());
-                            terminal = Terminal::new(backend)?;
+                        // Recreate terminal
+                        let backend = CrosstermBackend::new(io::stdout());
+                        terminal = Terminal::new(backend)?;
 
-                            info!("display_spreadsheet_interactive: returned from graph view");
+                        info!("display_spreadsheet_interactive: returned from graph view");
+                    }
+                }
+
+                _ => {
+                    if nav.handle_key(key) {
+                        debug!("display_spreadsheet_interactive: navigation -> {:?}", nav);
+                    }
+                }
+            }
+        }
+    }
+
+    disable_raw_mode()?;
+    if show_thumbnail {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    info!("display_spreadsheet_interactive: terminal restored, exiting viewer");
+    Ok(())
+}
+
+/// Mark `feature_idx` for the scatter plot, or unmark it if it already is.
+/// Marking a third feature drops the oldest mark.
+fn toggle_mark(marked: &mut Vec<usize>, feature_idx: usize) {
+    if let Some(pos) = marked.iter().position(|&m| m == feature_idx) {
+        marked.remove(pos);
+    } else {
+        if marked.len() == 2 {
+            marked.remove(0);
+        }
+        marked.push(feature_idx);
+    }
+}
+
+/// The feature (index into the features) at position `i` of the N×F
+/// table, whose columns walk `groups` when given; `None` for a group's
+/// summary column.
+fn view_feature(groups: Option<&ColumnGroups>, i: usize) -> Option<usize> {
+    match groups {
+        Some(g) => g.feature(i),
+        None => Some(i),
+    }
+}
+
+/// Position of `feature` in the N×F table, or of its collapsed group.
+fn view_position(groups: Option<&ColumnGroups>, feature: usize) -> usize {
+    groups.and_then(|g| g.position(feature)).unwrap_or(feature)
+}
+
+/// Horizontal scroll position of each view; only the one matching the active
+/// layout/mode is used.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct HorizontalOffsets {
+    /// Feature offset in the N×F view
+    pub col_offset: usize,
+    /// Sample-row offset in the F×N view
+    pub row_offset: usize,
+    /// Column offset of the COO sparsity map
+    pub sparse_col_offset: usize,
+    /// Shade the band around the diagonal of the COO sparsity map
+    pub sparse_band: bool,
+    /// Feature index kept as the first column of the N×F view
+    pub pinned_col: Option<usize>,
+    /// Feature indices marked for the scatter plot
+    pub marked: [Option<usize>; 2],
+}
+
+/// Smallest terminal area (width, height) the view for `layout` (F×N when
+/// `transposed`) can lay out its metadata, content and status panels in.
+pub(crate) fn min_area(layout: &LanceLayout, transposed: bool) -> (u16, u16) {
+    match layout {
+        // 3 metadata + 3 border/header/row (triples) + 7 structure footer
+        LanceLayout::SparseCoo => (40, 14),
+        // 3 metadata + 1 column strip + 6 table (borders, 2-line header, one
+        // row, footer) + 3 status
+        LanceLayout::DenseRowMajor | LanceLayout::Other if !transposed => (40, 13),
+        // as above, without the column strip
+        LanceLayout::DenseRowMajor
+        | LanceLayout::Vector1D
+        | LanceLayout::RaggedList
+        | LanceLayout::SparsePerRow
+        | LanceLayout::Other => (40, 12),
+    }
+}
+
+/// Draw a "window too small" placeholder if the frame is below `min`.
+/// Returns `true` when the placeholder was drawn and the caller should stop.
+pub(crate) fn render_too_small(f: &mut Frame, (min_w, min_h): (u16, u16)) -> bool {
+    let area = f.area();
+    if area.width >= min_w && area.height >= min_h {
+        return false;
+    }
+    let msg = format!("window too small (need at least {min_w}×{min_h})");
+    let p = Paragraph::new(Span::styled(msg, Style::default().fg(TEXT_SECONDARY)))
+        .wrap(Wrap { trim: true });
+    f.render_widget(p, area);
+    true
+}
+
+/// Render one frame of the viewer for `layout`, with the overview panel
+/// beside the N×F table when `thumbnail` is given, the COO matrix under
+/// `reordering` with the `laplacian` checks in its Structure section, and the
+/// `diff` and `computed` columns after the N×F features. With `groups`, the
+/// N×F table walks their view columns, which `all_col_indices` then holds.
+/// The status bar counts the `hidden` columns (`d`). Returns the overview
+/// panel's area when it was drawn, and the window of rows a row table
+/// showed (not F×N or the COO view), so `--follow` can pin its last page.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_frame(
+    f: &mut Frame,
+    data: &BatchSource,
+    layout: &LanceLayout,
+    all_col_indices: &[usize],
+    offsets: HorizontalOffsets,
+    visible: usize,
+    row_start: usize,
+    transposed: bool,
+    source: SourceWindow,
+    thumbnail: Option<&mut ThumbnailState>,
+    reordering: Option<&Reordering>,
+    laplacian: Option<&LaplacianReport>,
+    vector_panels: &VectorPanels,
+    stats: &RowStats,
+    bookmarks: &Bookmarks,
+    diff: Option<&DiffColumn>,
+    computed: &[&VectorColumn],
+    outliers: Option<&OutlierCells>,
+    groups: Option<&ColumnGroups>,
+    added: Option<&AddedRows>,
+    hidden: usize,
+    summary: Option<SummaryPanel>,
+    formats: &ValueFormatter,
+) -> (Option<Rect>, Option<ScrollWindow>) {
+    if render_too_small(f, min_area(layout, transposed)) {
+        return (None, None);
+    }
+    if is_empty_view(data, layout, all_col_indices) {
+        render_empty_ui(f, data, layout, source, false);
+        render_limit_banner(f, f.area(), source);
+        return (None, None);
+    }
+
+    let num_rows = data.num_rows();
+    // the group summaries are not columns of the data, and the hidden
+    // unsupported ones are not counted
+    let num_cols = data_column_count(data)
+        - groups.map_or(0, |g| g.len())
+        - hidden_count(&data.schema(), *layout, all_col_indices);
+    let (mut main, mut panel) = (f.area(), None);
+    let rows = match layout {
+        // The COO view reads the triplets as one batch
+        LanceLayout::SparseCoo => {
+            crate::display::display_coo::render_coo_ui(
+                f,
+                data.whole(),
+                row_start,
+                offsets.sparse_col_offset,
+                offsets.sparse_band,
+                reordering,
+                laplacian,
+            );
+            None
+        }
+        LanceLayout::RaggedList => render_ragged_ui(f, data, row_start, source, bookmarks),
+        LanceLayout::SparsePerRow => render_sparse_rows_ui(f, data, row_start, source, bookmarks),
+        LanceLayout::Vector1D => render_1d_ui(
+            f,
+            data,
+            all_col_indices,
+            offsets.col_offset,
+            visible,
+            num_rows,
+            num_cols,
+            row_start,
+            source,
+            bookmarks,
+            vector_panels,
+        ),
+        _ if transposed => {
+            render_transposed_ui(
+                f,
+                data,
+                all_col_indices,
+                offsets.row_offset,
+                visible,
+                num_rows,
+                num_cols,
+                row_start,
+                source,
+                stats,
+                bookmarks,
+                hidden,
+            );
+            None
+        }
+        _ => {
+            let (area, panel_area) = match thumbnail {
+                Some(_) => thumbnail_split(f.area()),
+                None => (f.area(), None),
+            };
+            // the summaries sit between the table and the overview
+            let (area, summary_area) = match summary.filter(|_| groups.is_none()) {
+                Some(_) => summary_split(area),
+                None => (area, None),
+            };
+            let (rows_window, cols_window) = render_base_ui(
+                f,
+                area,
+                data,
+                all_col_indices,
+                offsets,
+                visible,
+                num_rows,
+                num_cols,
+                row_start,
+                source,
+                stats,
+                bookmarks,
+                diff,
+                computed,
+                outliers,
+                groups,
+                added,
+                hidden,
+                formats,
+            );
+            main = area;
+            if let (Some(summary), Some(drawn)) = (summary, summary_area) {
+                let (_, cols) = drawn_features(
+                    all_col_indices,
+                    offsets.col_offset,
+                    offsets.pinned_col,
+                    visible,
+                );
+                let rows = vertical_window(rows_window.start, rows_window.len, num_rows);
+                let summaries = window_summaries(summary, &cols, rows.clone());
+                render_window_summary(f, drawn, rows, &summaries, &data.schema());
+            }
+            if let (Some(state), Some(drawn)) = (thumbnail, panel_area) {
+                // the panel is laid out by feature
+                let cols_window = match groups {
+                    Some(g) => {
+                        let (start, len) = g.feature_span(cols_window.start, cols_window.len);
+                        ScrollWindow {
+                            start,
+                            len,
+                            total: g.features().len(),
                         }
                     }
+                    None => cols_window,
+                };
+                render_thumbnail(f, drawn, state, rows_window, cols_window);
+                panel = Some(drawn);
+            }
+            Some(rows_window)
+        }
+    };
+    render_limit_banner(f, main, source);
+    (panel, rows)
+}
+
+/// Flag a `display --limit` read, and rows deleted in the dataset's
+/// version, on the top border of `area` (the metadata panel's),
+/// right-aligned in the warning colour.
+pub(crate) fn render_limit_banner(f: &mut Frame, area: Rect, source: SourceWindow) {
+    let Some(banner) = source.banner() else {
+        return;
+    };
+    let text = format!(" {banner} ");
+    let width = (text.width() as u16).min(area.width.saturating_sub(2));
+    let banner_area = Rect {
+        x: area.right().saturating_sub(width + 1),
+        y: area.y,
+        width,
+        height: 1.min(area.height),
+    };
+    f.render_widget(
+        Paragraph::new(Span::styled(text, Style::default().fg(TEXT_WARNING))),
+        banner_area,
+    );
+}
+
+// === Non-interactive rendering ==============================================
+
+/// Viewport and scroll state for [`render_batch_to_string`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Terminal width in cells
+    pub width: u16,
+    /// Terminal height in cells
+    pub height: u16,
+    /// First visible row (or feature, when transposed)
+    pub row_start: usize,
+    /// Horizontal scroll offset of the active view
+    pub col_offset: usize,
+    /// Number of feature (or sample) columns shown at once
+    pub visible_cols: usize,
+    /// Show dense layouts as F×N instead of N×F
+    pub transposed: bool,
+    /// Feature index pinned as the first N×F column
+    pub pinned_col: Option<usize>,
+    /// Where the batch sits in its dataset; `None` for the whole dataset
+    pub source: Option<SourceWindow>,
+    /// Aggregate columns after each row (each feature when transposed);
+    /// empty hides them
+    pub row_stats: &'static [Aggregate],
+    /// Aggregate over the visible features (rows when transposed) only
+    pub windowed_stats: bool,
+    /// How NULLs enter the aggregates, as `--null-policy` says
+    pub null_policy: NullPolicy,
+    /// Source row ids shown as bookmarked
+    pub bookmarks: &'static [u64],
+    /// Feature indices (reference first) of a computed `Δ` column
+    pub diff: Option<(usize, usize, DiffKind)>,
+    /// Highlight outlying values, as `!` does
+    pub outliers: bool,
+    /// Show the features transformed, as `z` does
+    pub transform: Option<Transform>,
+    /// Add the L2-norm column, as `n` does
+    pub norm: bool,
+    /// Add the dot product with this row, as `.` does
+    pub dot: Option<usize>,
+    /// Collapse the features into `_`-prefix groups, as `g` does, with
+    /// the groups of these names expanded
+    pub groups: Option<&'static [&'static str]>,
+    /// Rows marked as added since a version, as `--since-version` does
+    pub added: Option<AddedRows>,
+    /// Show the columns the table cannot show as values, as `U` does
+    pub unsupported: bool,
+    /// Show only the rows with (or without) a NULL feature, as `u` does
+    pub null_rows: NullFilter,
+    /// Hide the columns of these names, as `d` does
+    pub hidden: &'static [&'static str],
+    /// Compare the drawn window with all rows in a side panel, as `S` does
+    pub summary: bool,
+    /// Show the integer columns of these names in these formats (`raw`,
+    /// `thousands`, `bytes` or `hex`), as the column picker's Ctrl-f does
+    pub formats: &'static [(&'static str, &'static str)],
+    /// Number rows and features from this base, as `--index-base` and `#`
+    /// do
+    pub index_base: IndexBase,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            width: 100,
+            height: 30,
+            row_start: 0,
+            col_offset: 0,
+            visible_cols: DEFAULT_VISIBLE_COLS,
+            transposed: false,
+            pinned_col: None,
+            source: None,
+            row_stats: DEFAULT_AGGREGATES,
+            windowed_stats: false,
+            null_policy: NullPolicy::Skip,
+            bookmarks: &[],
+            diff: None,
+            outliers: false,
+            transform: None,
+            norm: false,
+            dot: None,
+            groups: None,
+            added: None,
+            unsupported: false,
+            null_rows: NullFilter::Off,
+            hidden: &[],
+            summary: false,
+            formats: &[],
+            index_base: IndexBase::Zero,
+        }
+    }
+}
+
+/// Render one frame of the viewer for `batch` into an off-screen buffer and
+/// return it as text, one line per terminal row (trailing spaces trimmed).
+///
+/// The layout (dense, 1D, COO) is detected the same way as in the interactive
+/// viewer; normalize DenseRowMajor batches with `col_*` expansion first if the
+/// expanded table is wanted.
+pub fn render_batch_to_string(batch: &RecordBatch, opts: &RenderOptions) -> String {
+    render_source_to_string(&BatchSource::from(batch.clone()), opts)
+}
+
+/// [`render_batch_to_string`] for rows held in several batches.
+pub(crate) fn render_source_to_string(data: &BatchSource, opts: &RenderOptions) -> String {
+    buffer_text(&render_source_to_buffer(data, opts))
+}
+
+/// One frame of the viewer for `data` drawn off-screen, as the buffer.
+pub(crate) fn render_source_to_buffer(data: &BatchSource, opts: &RenderOptions) -> Buffer {
+    use ratatui::backend::TestBackend;
+
+    set_index_base(opts.index_base);
+    let layout = crate::functions::functions::detect_lance_layout(&data.empty_batch());
+    let features = collect_feature_cols(&data.empty_batch()).unwrap_or_default();
+    let unsupported = table_unsupported(&data.schema(), layout);
+    let hidden = HiddenColumns::new(opts.hidden.iter().map(|name| name.to_string()));
+    let table_cols = table_columns(&features, &unsupported, opts.unsupported);
+    let all_col_indices = hidden.visible(&data.schema(), &table_cols);
+    let source = opts
+        .source
+        .unwrap_or_else(|| SourceWindow::whole(data.num_rows()));
+    let mut null_rows = NullRows::new(opts.null_rows);
+    let filtered = null_rows
+        .apply(data, &features)
+        .expect("kept rows match the schema");
+    let source = match filtered.as_ref() {
+        Some(kept) => null_rows.window(source, kept),
+        None => source,
+    };
+    let data = filtered.as_ref().unwrap_or(data);
+    let offsets = HorizontalOffsets {
+        col_offset: opts.col_offset,
+        row_offset: opts.col_offset,
+        sparse_col_offset: opts.col_offset,
+        pinned_col: opts.pinned_col,
+        ..HorizontalOffsets::default()
+    };
+
+    let diff = opts
+        .diff
+        .and_then(|(a, b, kind)| DiffColumn::of_marks(data, &all_col_indices, &[a, b], kind));
+    let column_stats = if opts.outliers || opts.transform.is_some() || opts.summary {
+        source_column_stats(data, &all_col_indices)
+    } else {
+        Vec::new()
+    };
+    let outliers = opts
+        .outliers
+        .then(|| OutlierCells::new(data, &all_col_indices, &column_stats));
+    let transformed = opts.transform.map(|kind| {
+        ColumnTransform::new(kind, data, &all_col_indices, &column_stats)
+            .source(data)
+            .expect("transformed columns match the schema")
+    });
+    let shown = transformed.as_ref().unwrap_or(data);
+    let norm = opts
+        .norm
+        .then(|| VectorColumn::norm(data, &[], &all_col_indices));
+    let dot = opts
+        .dot
+        .map(|row| VectorColumn::dot(data, &[], &all_col_indices, row));
+    let computed: Vec<&VectorColumn> = norm.iter().chain(&dot).collect();
+    let groups = opts.groups.map(|expanded| {
+        let mut groups = ColumnGroups::new(data, &all_col_indices, &GroupSpec::default())
+            .expect("prefix groups cannot fail");
+        for &name in expanded {
+            let col = groups
+                .view_columns()
+                .into_iter()
+                .find(|&c| groups.name(c) == Some(name));
+            if let Some(col) = col {
+                groups.toggle(col);
+            }
+        }
+        groups
+    });
+    let grouped = groups
+        .as_ref()
+        .map(|g| g.source(shown).expect("group means match the schema"));
+    let shown = grouped.as_ref().unwrap_or(shown);
+    let view_cols = groups.as_ref().map(|g| g.view_columns());
+    let formats = ValueFormatter::new(opts.formats.iter().copied());
+
+    let mut stats = RowStats::new(opts.row_stats, true)
+        .windowed(opts.windowed_stats)
+        .with_null_policy(opts.null_policy);
+    let hidden_cols = table_cols.len() - all_col_indices.len();
+    stats.set_excluding(hidden_cols > 0);
+    stats.set_skipped(skipped_columns(data, &features));
+
+    let mut terminal = Terminal::new(TestBackend::new(opts.width, opts.height))
+        .expect("TestBackend cannot fail to initialise");
+    terminal
+        .draw(|f| {
+            render_frame(
+                f,
+                shown,
+                &layout,
+                view_cols.as_deref().unwrap_or(&all_col_indices),
+                offsets,
+                opts.visible_cols,
+                opts.row_start,
+                opts.transposed,
+                source,
+                None,
+                None,
+                None,
+                &VectorPanels::default(),
+                &stats,
+                &Bookmarks::new(opts.bookmarks.iter().copied()),
+                diff.as_ref(),
+                &computed,
+                outliers.as_ref(),
+                groups.as_ref(),
+                opts.added.as_ref(),
+                hidden_cols,
+                opts.summary.then_some(SummaryPanel {
+                    rows: data,
+                    features: &all_col_indices,
+                    stats: &column_stats,
+                }),
+                &formats,
+            );
+        })
+        .expect("TestBackend cannot fail to draw");
+
+    terminal.backend().buffer().clone()
+}
+
+// === Scroll position ========================================================
+
+/// A window of `len` items starting at `start` out of `total`, along one axis.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ScrollWindow {
+    pub start: usize,
+    pub len: usize,
+    pub total: usize,
+}
+
+impl ScrollWindow {
+    fn overflows(&self) -> bool {
+        self.total > self.len
+    }
+
+    /// Vim-style position label: `ALL` when everything fits, `TOP`/`BOT` at
+    /// either end, otherwise how far through the scrollable range we are.
+    pub(crate) fn label(&self) -> String {
+        let max_start = self.total.saturating_sub(self.len);
+        if max_start == 0 {
+            "ALL".to_string()
+        } else if self.start == 0 {
+            "TOP".to_string()
+        } else if self.start >= max_start {
+            "BOT".to_string()
+        } else {
+            format!("{}%", self.start * 100 / max_start)
+        }
+    }
+
+    fn state(&self) -> ScrollbarState {
+        ScrollbarState::new(self.total.saturating_sub(self.len) + 1)
+            .position(self.start)
+            .viewport_content_length(self.len)
+    }
+}
+
+/// Draw scrollbars over the right and bottom borders of a bordered table
+/// `area`; each bar is only shown when its axis has more items than fit.
+pub(crate) fn render_table_scrollbars(
+    f: &mut Frame,
+    area: Rect,
+    vertical: ScrollWindow,
+    horizontal: ScrollWindow,
+) {
+    let style = Style::default().fg(BORDER_ACCENT);
+    if vertical.overflows() {
+        let bar = Scrollbar::new(ScrollbarOrientation::VerticalRight).style(style);
+        let inner = area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        });
+        f.render_stateful_widget(bar, inner, &mut vertical.state());
+    }
+    if horizontal.overflows() {
+        let bar = Scrollbar::new(ScrollbarOrientation::HorizontalBottom)
+            .begin_symbol(Some("◄"))
+            .end_symbol(Some("►"))
+            .style(style);
+        let inner = area.inner(Margin {
+            vertical: 0,
+            horizontal: 1,
+        });
+        f.render_stateful_widget(bar, inner, &mut horizontal.state());
+    }
+}
+
+// === Formatting helpers =====================================================
+
+/// Decimal places of float cells.
+pub(crate) const CELL_DECIMALS: usize = 8;
+/// Decimal places of the derived avg/std columns, one fewer than the cells
+/// they summarize.
+pub(crate) const STAT_DECIMALS: usize = CELL_DECIMALS - 1;
+/// Decimal places of the `Δ` column's footer, short enough for "mean " to
+/// fit beside them.
+const DIFF_DECIMALS: usize = 4;
+
+/// Population mean and standard deviation of `vals`, by Welford's online
+/// update over the values scaled down by a power of two, so neither the
+/// running mean nor the squared deviations overflow for values near
+/// `f64::MAX`. With infinities the mean is the one of their sign, or NaN
+/// when both occur, and the standard deviation is NaN.
+pub(crate) fn mean_std(vals: &[f64]) -> Option<(f64, f64)> {
+    if vals.is_empty() {
+        return None;
+    }
+    let pos = vals.contains(&f64::INFINITY);
+    let neg = vals.contains(&f64::NEG_INFINITY);
+    if pos || neg {
+        let mean = match (pos, neg) {
+            (true, true) => f64::NAN,
+            (true, false) => f64::INFINITY,
+            _ => f64::NEG_INFINITY,
+        };
+        return Some((mean, f64::NAN));
+    }
+    // dividing by a power of two is exact
+    let largest = vals.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    let scale = if largest > 1.0 {
+        2f64.powi(largest.log2().floor() as i32)
+    } else {
+        1.0
+    };
+    let (mut mean, mut m2) = (0.0, 0.0);
+    for (k, v) in vals.iter().enumerate() {
+        let x = v / scale;
+        let delta = x - mean;
+        mean += delta / (k + 1) as f64;
+        m2 += delta * (x - mean);
+    }
+    let var = (m2 / vals.len() as f64).max(0.0);
+    Some((mean * scale, var.sqrt() * scale))
+}
 
-                    _ => {}
-                }
-            }
+/// A numeric value as f64; `None` for other types (nulls are not checked).
+pub(crate) fn extract_numeric_value(array: &ArrayRef, row_idx: usize) -> Option<f64> {
+    match array.data_type() {
+        DataType::Float32 => {
+            let arr = array.as_any().downcast_ref::<Float32Array>().unwrap();
+            Some(arr.value(row_idx) as f64)
+        }
+        DataType::Float64 => {
+            let arr = array.as_any().downcast_ref::<Float64Array>().unwrap();
+            Some(arr.value(row_idx))
         }
+        DataType::Int32 => {
+            let arr = array.as_any().downcast_ref::<Int32Array>().unwrap();
+            Some(arr.value(row_idx) as f64)
+        }
+        DataType::Int64 => {
+            let arr = array.as_any().downcast_ref::<Int64Array>().unwrap();
+            Some(arr.value(row_idx) as f64)
+        }
+        DataType::UInt32 => {
+            let arr = array.as_any().downcast_ref::<UInt32Array>().unwrap();
+            Some(arr.value(row_idx) as f64)
+        }
+        DataType::UInt64 => {
+            let arr = array.as_any().downcast_ref::<UInt64Array>().unwrap();
+            Some(arr.value(row_idx) as f64)
+        }
+        _ => None,
     }
-
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
-    info!("display_spreadsheet_interactive: terminal restored, exiting viewer");
-    Ok(())
 }
 
-// === Formatting helpers =====================================================
+/// Values of global row `row_idx` across the numeric feature columns of
+/// `cols`, and the elements of its vector columns, `None` for a NULL.
+pub(crate) fn row_values(data: &BatchSource, cols: &[usize], row_idx: usize) -> Vec<Option<f64>> {
+    let Some((batch, r)) = data.locate(row_idx) else {
+        return Vec::new();
+    };
+    cols.iter()
+        .map(|&c| batch.column(c))
+        .flat_map(|col| match col.data_type() {
+            // a vector cell contributes its elements
+            dt if is_vector_type(dt) => vector_elements(col, r),
+            dt if is_numeric(dt) => vec![
+                (!col.is_null(r))
+                    .then(|| extract_numeric_value(col, r))
+                    .flatten(),
+            ],
+            _ => Vec::new(),
+        })
+        .collect()
+}
 
 pub(crate) fn format_value(array: &ArrayRef, row_idx: usize) -> String {
+    format_value_with_decimals(array, row_idx, CELL_DECIMALS)
+}
+
+/// [`format_value`] with floats to `decimals` places.
+pub(crate) fn format_value_with_decimals(
+    array: &ArrayRef,
+    row_idx: usize,
+    decimals: usize,
+) -> String {
     if array.is_null(row_idx) {
         return "NULL".to_string();
     }
@@ -337,11 +2333,11 @@ pub(crate) fn format_value(array: &ArrayRef, row_idx: usize) -> String {
     match array.data_type() {
         DataType::Float32 => {
             let arr = array.as_any().downcast_ref::<Float32Array>().unwrap();
-            format!("{:.8}", arr.value(row_idx))
+            format_float(arr.value(row_idx) as f64, decimals)
         }
         DataType::Float64 => {
             let arr = array.as_any().downcast_ref::<Float64Array>().unwrap();
-            format!("{:.8}", arr.value(row_idx))
+            format_float(arr.value(row_idx), decimals)
         }
         DataType::Int32 => {
             let arr = array.as_any().downcast_ref::<Int32Array>().unwrap();
@@ -363,16 +2359,78 @@ pub(crate) fn format_value(array: &ArrayRef, row_idx: usize) -> String {
             let arr = array.as_any().downcast_ref::<BooleanArray>().unwrap();
             if arr.value(row_idx) { "true" } else { "false" }.to_string()
         }
-        DataType::Utf8 => {
-            let arr = array.as_any().downcast_ref::<StringArray>().unwrap();
-            let s = arr.value(row_idx);
-            if s.len() > 10 {
-                format!("{}…", &s[0..9])
-            } else {
-                s.to_string()
-            }
-        }
-        _ => "?".to_string(),
+        DataType::Utf8 => truncate_text(array.as_string::<i32>().value(row_idx)),
+        DataType::LargeUtf8 => truncate_text(array.as_string::<i64>().value(row_idx)),
+        dt if is_binary_type(dt) => short_hex(binary_value(array, row_idx).unwrap_or_default()),
+        dt if is_vector_type(dt) => vector_preview(array, row_idx),
+        DataType::Dictionary(_, _) | DataType::RunEndEncoded(_, _) => match resolve(array, row_idx)
+        {
+            Some((values, index)) => format_value_with_decimals(&values, index, decimals),
+            None => "NULL".to_string(),
+        },
+        _ => placeholder(array, row_idx),
+    }
+}
+
+/// Width of a feature column (a sample-row column when transposed).
+pub(crate) const VALUE_COL_WIDTH: u16 = 12;
+/// Feature (or sample) columns shown at once unless `--visible-cols` or the
+/// session says otherwise.
+pub const DEFAULT_VISIBLE_COLS: usize = 8;
+/// Width of the avg/std columns.
+pub(crate) const STAT_COL_WIDTH: u16 = 12;
+
+/// Widths the columns of a bordered table in `area` get once laid out, the
+/// same way [`Table`] splits them (one cell of spacing). A narrow terminal
+/// squeezes columns below their constraints.
+pub(crate) fn column_widths(area: Rect, constraints: &[Constraint]) -> Vec<u16> {
+    let inner = Rect {
+        width: area.width.saturating_sub(2),
+        ..area
+    };
+    Layout::horizontal(constraints.iter().copied())
+        .spacing(1)
+        .split(inner)
+        .iter()
+        .map(|r| r.width)
+        .collect()
+}
+
+/// Width of the value columns of a bordered table in `area` showing up to
+/// `visible` of them next to columns of the fixed widths `others`: an equal
+/// share of the room left, at least [`VALUE_COL_WIDTH`], so a smaller
+/// `--visible-cols` shows wider values.
+pub(crate) fn value_col_width(area: Rect, others: &[u16], visible: usize) -> u16 {
+    let spacing = (others.len() + visible).saturating_sub(1) as u16;
+    let room = area
+        .width
+        .saturating_sub(2 + spacing)
+        .saturating_sub(others.iter().sum());
+    (room / visible.max(1) as u16).max(VALUE_COL_WIDTH)
+}
+
+/// `text` right-aligned in a column `width` cells wide, for numbers and row
+/// labels. Each line is padded on the left rather than aligned by ratatui,
+/// whose right alignment drops the leading digits of a value that does not
+/// fit; pass the laid-out width from [`column_widths`].
+pub(crate) fn right(text: impl AsRef<str>, width: u16) -> String {
+    text.as_ref()
+        .lines()
+        .map(|line| {
+            let pad = usize::from(width).saturating_sub(line.width());
+            format!("{}{line}", " ".repeat(pad))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Cell text of a value (or header) of a `data_type` column: right-aligned
+/// for numeric columns so decimal points line up, left-aligned otherwise.
+pub(crate) fn aligned(data_type: &DataType, text: String, width: u16) -> String {
+    if is_numeric(data_type) {
+        right(text, width)
+    } else {
+        text
     }
 }
 
@@ -390,14 +2448,23 @@ pub(crate) fn blend_colors(c1: Color, c2: Color) -> Color {
     }
 }
 
+/// `c1` moved `level` (0–1) of the way to `c2`.
+pub(crate) fn mix_colors(c1: Color, c2: Color, level: f64) -> Color {
+    let (Color::Rgb(r0, g0, b0), Color::Rgb(r1, g1, b1)) = (c1, c2) else {
+        return c2;
+    };
+    let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * level.clamp(0.0, 1.0)) as u8;
+    Color::Rgb(lerp(r0, r1), lerp(g0, g1), lerp(b0, b1))
+}
+
 /// Get the background color for a cell based on row and column index
 pub(crate) fn get_cell_bg_color(row_idx: usize, col_idx: usize) -> Color {
-    let row_bg = if row_idx % 2 == 0 {
+    let row_bg = if row_idx.is_multiple_of(2) {
         EVEN_ROW_BG
     } else {
         ODD_ROW_BG
     };
-    let col_bg = if col_idx % 2 == 0 {
+    let col_bg = if col_idx.is_multiple_of(2) {
         EVEN_COL_BG
     } else {
         ODD_COL_BG
@@ -405,9 +2472,164 @@ pub(crate) fn get_cell_bg_color(row_idx: usize, col_idx: usize) -> Color {
     blend_colors(row_bg, col_bg)
 }
 
+// === Metadata columns ======================================================
+
+/// Number of columns excluding the recognised metadata columns and the
+/// synthetic row-id column; a vector column counts its elements.
+pub(crate) fn data_column_count(data: &BatchSource) -> usize {
+    data.schema()
+        .fields()
+        .iter()
+        .filter(|f| !is_metadata_column(f.name()) && f.name() != ROWID_COLUMN)
+        .map(|f| vector_width(f.data_type()).unwrap_or(1))
+        .sum()
+}
+
+// === Source row ids ========================================================
+
+/// The [`ROWID_COLUMN`] value of `row`, if the batches carry one.
+fn source_row_id(data: &BatchSource, row: usize) -> Option<u64> {
+    data.locate(row).and_then(|(batch, r)| {
+        let ids = batch.column_by_name(ROWID_COLUMN)?;
+        let ids = ids.as_any().downcast_ref::<UInt64Array>()?;
+        (!ids.is_null(r)).then(|| ids.value(r))
+    })
+}
+
+/// Source row id of `row`: its [`ROWID_COLUMN`] value, else its position
+/// (rows without ids are the dataset from its first row).
+pub(crate) fn row_id(data: &BatchSource, row: usize) -> u64 {
+    source_row_id(data, row).unwrap_or(row as u64)
+}
+
+/// The row of `data` with source row id `id`, if it is loaded.
+pub(crate) fn row_of_id(data: &BatchSource, id: u64) -> Option<usize> {
+    if data.schema().column_with_name(ROWID_COLUMN).is_none() {
+        return (id < data.num_rows() as u64).then_some(id as usize);
+    }
+    data.iter().find_map(|(start, batch)| {
+        let ids = batch.column_by_name(ROWID_COLUMN)?;
+        let ids = ids.as_any().downcast_ref::<UInt64Array>()?;
+        let r = (0..ids.len()).find(|&r| !ids.is_null(r) && ids.value(r) == id)?;
+        Some(start + r)
+    })
+}
+
+/// Label for the Row column: `#<source id>` when the batches carry
+/// [`ROWID_COLUMN`], [`TOMBSTONE_LABEL`] for a deleted row, otherwise the
+/// row's position in `data`; both counted from the index base.
+pub(crate) fn row_label(data: &BatchSource, row: usize) -> String {
+    let base = index_base();
+    match source_row_id(data, row) {
+        Some(id) => format!("#{}", base.show(id as usize)),
+        None if is_tombstone(data, row) => TOMBSTONE_LABEL.to_string(),
+        None => base.show(row).to_string(),
+    }
+}
+
+/// Row label of a deleted row, which has no source id.
+pub(crate) const TOMBSTONE_LABEL: &str = "✗ del";
+
+/// Row column text of `row` in a column `width` wide: the label
+/// right-aligned after a one-cell gutter holding the row's
+/// [`mark`](Bookmarks::mark), if any.
+pub(crate) fn row_label_text(
+    data: &BatchSource,
+    row: usize,
+    width: u16,
+    bookmarks: &Bookmarks,
+) -> String {
+    let gutter = bookmarks.mark(data, row).unwrap_or(' ');
+    format!(
+        "{gutter}{}",
+        right(row_label(data, row), width.saturating_sub(1))
+    )
+}
+
+/// Width of the Row column: a bookmark gutter plus at least 5 cells, wider
+/// for long source ids or more rows than that holds. Labels are
+/// right-aligned in it. 0 when the index base hides the column.
+pub(crate) fn row_label_width(data: &BatchSource) -> u16 {
+    let base = index_base();
+    if !base.row_column() {
+        return 0;
+    }
+    let widest_id = data
+        .batches()
+        .iter()
+        .filter_map(|batch| {
+            let ids = batch.column_by_name(ROWID_COLUMN)?;
+            arrow::compute::max(ids.as_any().downcast_ref::<UInt64Array>()?)
+        })
+        .max()
+        .map_or(0, |max| base.show(max as usize).to_string().len() + 1);
+    let widest_row = base
+        .show(data.num_rows().saturating_sub(1))
+        .to_string()
+        .len();
+    widest_id.max(widest_row).max(5) as u16 + 1
+}
+
+/// Text for the metadata panel. Metadata often arrives as a separate
+/// single-row batch concatenated onto the data, so each metadata column is
+/// read at its first non-null row instead of row 0.
+pub(crate) fn metadata_text(
+    data: &BatchSource,
+    num_rows: usize,
+    num_cols: usize,
+    sep: &str,
+) -> String {
+    let value_of =
+        |name: &str| first_metadata_value(data, name, |col, row| Some(format_value(col, row)));
+
+    match value_of("name_id") {
+        Some(name) => {
+            let nrows_val = value_of("n_rows").unwrap_or_else(|| "?".to_string());
+            let ncols_val = value_of("n_cols").unwrap_or_else(|| "?".to_string());
+            format!("name_id: {name}{sep}n_rows: {nrows_val}{sep}n_cols: {ncols_val}")
+        }
+        None => format!("rows: {num_rows}{sep}cols: {num_cols}"),
+    }
+}
+
+/// The actual shape of the data (source rows without metadata-only rows ×
+/// feature columns) when the `n_rows`/`n_cols` columns declare another.
+pub(crate) fn dims_mismatch(data: &BatchSource, source: SourceWindow) -> Option<(usize, usize)> {
+    let declared = declared_dims(data)?;
+    let actual = (
+        source.total_rows.saturating_sub(metadata_only_rows(data)),
+        source.total_cols.unwrap_or_else(|| data_column_count(data)),
+    );
+    (declared != actual).then_some(actual)
+}
+
+/// [`metadata_text`] as a styled line. With a `mismatch` the declared
+/// `n_rows`/`n_cols` are drawn in the warning colour, followed by the actual
+/// shape.
+pub(crate) fn metadata_line(
+    data: &BatchSource,
+    num_rows: usize,
+    num_cols: usize,
+    sep: &str,
+    mismatch: Option<(usize, usize)>,
+) -> Line<'static> {
+    let text = metadata_text(data, num_rows, num_cols, sep);
+    let normal = Style::default().fg(TEXT_SECONDARY);
+    match (mismatch, text.find("n_rows:")) {
+        (Some((rows, cols)), Some(at)) => Line::from(vec![
+            Span::styled(text[..at].to_string(), normal),
+            Span::styled(
+                format!("{} (mismatch: actual {rows}×{cols})", &text[at..]),
+                Style::default().fg(TEXT_WARNING),
+            ),
+        ]),
+        _ => Line::from(Span::styled(text, normal)),
+    }
+}
+
 // === Column selection / windows ============================================
 
-fn collect_feature_cols(batch: &RecordBatch) -> Result<Vec<usize>> {
+pub(crate) fn collect_feature_cols(batch: &RecordBatch) -> Result<Vec<usize>> {
     let schema = batch.schema();
 
     // 1) Preferred: explicit `col_*` feature columns
@@ -433,30 +2655,22 @@ fn collect_feature_cols(batch: &RecordBatch) -> Result<Vec<usize>> {
         return Ok(vec![0]);
     }
 
-    // 3) Fallback for generic numeric tables
+    // 3) Fallback for generic tables: numeric columns plus Boolean, string
+    //    and binary columns (shown as-is, left out of avg/std), skipping
+    //    metadata columns
     cols = schema
         .fields()
         .iter()
         .enumerate()
-        .filter_map(|(i, f)| match f.data_type() {
-            DataType::Float32
-            | DataType::Float64
-            | DataType::Int8
-            | DataType::Int16
-            | DataType::Int32
-            | DataType::Int64
-            | DataType::UInt8
-            | DataType::UInt16
-            | DataType::UInt32
-            | DataType::UInt64 => Some(i),
-            _ => None,
-        })
+        .filter(|(_, f)| !is_metadata_column(f.name()) && f.name() != ROWID_COLUMN)
+        .filter(|(_, f)| is_supported(f.data_type()))
+        .map(|(i, _)| i)
         .collect();
 
     if cols.is_empty() {
         bail!(
             "The file should be formatted with `col_*` feature columns \
-             or at least one numeric column; got schema {:?}",
+             or at least one numeric, Boolean, string or binary column; got schema {:?}",
             schema
         );
     }
@@ -464,28 +2678,289 @@ fn collect_feature_cols(batch: &RecordBatch) -> Result<Vec<usize>> {
     Ok(cols)
 }
 
-fn feature_window<'a>(
-    all_cols: &'a [usize],
+/// The batch columns the N×F table draws from `col_offset`, the pinned
+/// feature first, and the positions in `all_col_indices` of the scrolled
+/// ones.
+fn drawn_features(
+    all_col_indices: &[usize],
     col_offset: usize,
+    pinned_col: Option<usize>,
     visible_cols: usize,
-) -> &'a [usize] {
-    let start = col_offset.min(all_cols.len());
-    let end = (start + visible_cols).min(all_cols.len());
-    &all_cols[start..end]
+) -> (Range<usize>, Vec<usize>) {
+    let pinned = pinned_col.and_then(|p| all_col_indices.get(p).copied());
+    let (mut cols, mut window) = feature_window(all_col_indices, col_offset, visible_cols);
+    if pinned.is_some_and(|p| !window.contains(&p)) {
+        // the pinned column takes one of the window's places
+        (cols, window) = feature_window(all_col_indices, col_offset, visible_cols.max(2) - 1);
+    }
+    (cols, pinned_window(pinned, window, visible_cols))
+}
+
+/// `window` with the pinned column (a batch column index) moved to the front,
+/// keeping `visible_cols` columns in total.
+fn pinned_window(pinned: Option<usize>, window: &[usize], visible_cols: usize) -> Vec<usize> {
+    match pinned {
+        Some(p) => std::iter::once(p)
+            .chain(window.iter().copied().filter(|&c| c != p))
+            .take(visible_cols.max(1))
+            .collect(),
+        None => window.to_vec(),
+    }
+}
+
+// === Null summaries ========================================================
+
+/// `"3% null"` for a column with `nulls` of `len` values null, `None` when
+/// it has none. Counts come from the validity bitmaps via `null_count`, so
+/// this is O(batches) per column.
+pub(crate) fn null_label(nulls: usize, len: usize) -> Option<String> {
+    if nulls == 0 {
+        return None;
+    }
+    let pct = nulls * 100 / len;
+    Some(if pct == 0 {
+        "<1% null".to_string()
+    } else {
+        format!("{pct}% null")
+    })
+}
+
+/// Header height for `col_window`: two lines when any visible column has
+/// nulls (name + null indicator), otherwise one.
+pub(crate) fn header_height(data: &BatchSource, col_window: &[usize]) -> u16 {
+    if col_window.iter().any(|&i| data.null_count(i) > 0) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Field metadata key holding a feature column's display name (`--names`,
+/// `R` in the viewer, or written by the producer of the dataset).
+pub(crate) const FEATURE_NAME_KEY: &str = "feature_name";
+
+/// Name to show for `field`: its `feature_name` metadata, else its name.
+pub(crate) fn display_name(field: &Field) -> &str {
+    field
+        .metadata()
+        .get(FEATURE_NAME_KEY)
+        .map_or(field.name().as_str(), String::as_str)
+}
+
+/// `batch` with the display name of each listed column set (`Some`) or
+/// cleared back to the column name (`None`). Only the schema changes.
+pub(crate) fn set_feature_names(
+    batch: &RecordBatch,
+    names: &[(usize, Option<String>)],
+) -> Result<RecordBatch> {
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    for (col_idx, name) in names {
+        let Some(field) = fields.get_mut(*col_idx) else {
+            continue;
+        };
+        let mut metadata = field.metadata().clone();
+        match name {
+            Some(name) => metadata.insert(FEATURE_NAME_KEY.to_string(), name.clone()),
+            None => metadata.remove(FEATURE_NAME_KEY),
+        };
+        field.set_metadata(metadata);
+    }
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        batch.columns().to_vec(),
+    )?)
+}
+
+/// Header cell text of column `col`: its name, plus a null indicator line
+/// if needed.
+pub(crate) fn header_text(name: &str, data: &BatchSource, col: usize) -> String {
+    match null_label(data.null_count(col), data.num_rows()) {
+        Some(label) => format!("{name}\n{label}"),
+        None => name.to_string(),
+    }
+}
+
+/// Short Arrow type tag for header cells, e.g. `f64`; `None` for types
+/// without one.
+pub(crate) fn dtype_tag(data_type: &DataType) -> Option<&'static str> {
+    Some(match data_type {
+        DataType::Float16 => "f16",
+        DataType::Float32 => "f32",
+        DataType::Float64 => "f64",
+        DataType::Int8 => "i8",
+        DataType::Int16 => "i16",
+        DataType::Int32 => "i32",
+        DataType::Int64 => "i64",
+        DataType::UInt8 => "u8",
+        DataType::UInt16 => "u16",
+        DataType::UInt32 => "u32",
+        DataType::UInt64 => "u64",
+        DataType::Boolean => "bool",
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => "str",
+        DataType::Binary | DataType::LargeBinary | DataType::BinaryView => "bin",
+        DataType::Dictionary(_, _) => "dict",
+        DataType::RunEndEncoded(_, _) => "ree",
+        _ => return None,
+    })
+}
+
+/// `name` with its type tag (`col_3 ·f64`) when both fit in `width`.
+pub(crate) fn with_dtype_tag(name: &str, data_type: &DataType, width: u16) -> String {
+    match dtype_tag(data_type) {
+        Some(tag) if name.width() + tag.width() + 2 <= usize::from(width) => {
+            format!("{name} ·{tag}")
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// The column strip above the N×F table: the focused (leftmost) column's
+/// full name, Arrow type, nullability and position, which truncated headers
+/// cannot show. A renamed column also gives its schema name.
+pub(crate) fn column_info_line(
+    schema: &Schema,
+    col: usize,
+    feature: usize,
+    total_features: usize,
+    source: SourceWindow,
+) -> Line<'static> {
+    let field = schema.field(col);
+    let name = display_name(field);
+    let mut details = Vec::new();
+    if name != field.name() {
+        details.push(format!("column {}", field.name()));
+    }
+    details.push(
+        vector_type_label(field.data_type()).unwrap_or_else(|| field.data_type().to_string()),
+    );
+    details.push(
+        if field.is_nullable() {
+            "nullable"
+        } else {
+            "not null"
+        }
+        .to_string(),
+    );
+    details.push(format!(
+        "feature {} of {}",
+        index_base().show(source.col_offset + feature),
+        source.total_cols.unwrap_or(total_features)
+    ));
+    details.push(format!("schema index {col}"));
+    Line::from(vec![
+        Span::styled(
+            format!(" {name}"),
+            Style::default().fg(HEADER_FG).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("  {}", details.join(" · ")),
+            Style::default().fg(TEXT_SECONDARY),
+        ),
+    ])
+}
+
+/// Footer row with the null count of each visible column (right-aligned in
+/// the matching `widths`), then the rows with null dimensions under each
+/// `computed` column, padded with `trailing` empty cells (e.g. under
+/// avg/std).
+pub(crate) fn render_null_footer<'a>(
+    data: &BatchSource,
+    col_window: &[usize],
+    widths: &[u16],
+    computed: &[&VectorColumn],
+    trailing: usize,
+) -> Row<'a> {
+    let style = Style::default().fg(TEXT_SECONDARY).bg(HEADER_BG);
+    let mut cells = vec![Cell::from("nulls").style(style.add_modifier(Modifier::BOLD))];
+    for (&i, &width) in col_window.iter().zip(widths) {
+        cells.push(Cell::from(right(data.null_count(i).to_string(), width)).style(style));
+    }
+    cells.extend(computed_footer_cells(
+        computed,
+        &widths[col_window.len()..],
+        style,
+    ));
+    for _ in 0..trailing {
+        cells.push(Cell::from("").style(style));
+    }
+    Row::new(cells).height(1)
+}
+
+/// [`render_null_footer`] for a table with the `Δ` column after the
+/// `col_window` ones: under it the max and mean |Δ|, one per line.
+fn render_diff_footer<'a>(
+    data: &BatchSource,
+    col_window: &[usize],
+    widths: &[u16],
+    diff: &DiffColumn,
+    computed: &[&VectorColumn],
+    trailing: usize,
+) -> Row<'a> {
+    let style = Style::default().fg(TEXT_SECONDARY).bg(HEADER_BG);
+    let mut cells = vec![Cell::from("nulls").style(style.add_modifier(Modifier::BOLD))];
+    for (&i, &width) in col_window.iter().zip(widths) {
+        cells.push(Cell::from(right(data.null_count(i).to_string(), width)).style(style));
+    }
+    let mean = diff
+        .mean_abs
+        .map_or_else(|| "NA".to_string(), |m| format_float(m, DIFF_DECIMALS));
+    let summary = format!(
+        "max {}\nmean {}",
+        format_float(diff.max_abs, DIFF_DECIMALS),
+        mean
+    );
+    cells.push(
+        Cell::from(right(summary, widths[col_window.len()]))
+            .style(Style::default().fg(TEXT_WARNING).bg(HEADER_BG)),
+    );
+    cells.extend(computed_footer_cells(
+        computed,
+        &widths[col_window.len() + 1..],
+        style,
+    ));
+    for _ in 0..trailing {
+        cells.push(Cell::from("").style(style));
+    }
+    Row::new(cells).height(2)
+}
+
+/// Footer cells of the `computed` columns: the rows with a null dimension.
+fn computed_footer_cells<'a>(
+    computed: &[&VectorColumn],
+    widths: &[u16],
+    style: Style,
+) -> impl Iterator<Item = Cell<'a>> {
+    computed.iter().zip(widths).map(move |(column, &width)| {
+        Cell::from(right(
+            format!("{}{PARTIAL_MARK}", column.partial_rows()),
+            width,
+        ))
+        .style(style)
+    })
 }
 
 // === Header / rows =========================================================
 
+/// Header row; `widths` are the laid-out widths of the table's columns.
 fn render_header<'a>(
-    batch: &'a RecordBatch,
+    data: &BatchSource,
     col_window: &'a [usize],
     col_offset: usize,
+    widths: &[u16],
+    stats: &RowStats,
+    diff: Option<&DiffColumn>,
+    computed: &[&VectorColumn],
 ) -> Row<'a> {
-    let schema = batch.schema();
+    let schema = data.schema();
+    // aggregates follow the features, the `Δ` column and the computed ones
+    let first_computed = col_window.len() + usize::from(diff.is_some()) + 1;
+    let n = first_computed - 1 + computed.len();
 
     // Row index header with special styling
     let mut header_cells = vec![
-        Cell::from("Row").style(
+        Cell::from(right("Row", widths[0])).style(
             Style::default()
                 .fg(HEADER_FG)
                 .bg(HEADER_BG)
@@ -493,15 +2968,24 @@ fn render_header<'a>(
         ),
     ];
 
-    // Feature column headers with alternating colors
+    // Feature column headers with alternating colors; numeric ones sit
+    // right-aligned over their values
     for (display_idx, &schema_idx) in col_window.iter().enumerate() {
-        let col_bg = if (col_offset + display_idx) % 2 == 0 {
+        let col_bg = if (col_offset + display_idx).is_multiple_of(2) {
             blend_colors(HEADER_BG, EVEN_COL_BG)
         } else {
             blend_colors(HEADER_BG, ODD_COL_BG)
         };
 
-        let cell = Cell::from(schema.field(schema_idx).name().to_string());
+        let field = schema.field(schema_idx);
+        let width = widths[display_idx + 1];
+        let name = match field.metadata().get(TRANSFORM_KEY) {
+            Some(tag) => format!("{} [{tag}]", display_name(field)),
+            None => display_name(field).to_string(),
+        };
+        let name = with_dtype_tag(&name, field.data_type(), width);
+        let text = header_text(&name, data, schema_idx);
+        let cell = Cell::from(aligned(field.data_type(), text, width));
         header_cells.push(
             cell.style(
                 Style::default()
@@ -512,130 +2996,252 @@ fn render_header<'a>(
         );
     }
 
-    // Stats headers with accent color
-    header_cells.push(
-        Cell::from("avg").style(
-            Style::default()
-                .fg(TEXT_ACCENT)
-                .bg(HEADER_BG)
-                .add_modifier(Modifier::BOLD),
-        ),
-    );
-    header_cells.push(
-        Cell::from("std").style(
-            Style::default()
-                .fg(TEXT_ACCENT)
-                .bg(HEADER_BG)
-                .add_modifier(Modifier::BOLD),
-        ),
-    );
+    // The computed column reads apart from the data ones
+    if let Some(diff) = diff {
+        header_cells.push(
+            Cell::from(right(diff.kind.label(), widths[first_computed - 1])).style(
+                Style::default()
+                    .fg(TEXT_WARNING)
+                    .bg(HEADER_BG)
+                    .add_modifier(Modifier::BOLD | Modifier::ITALIC),
+            ),
+        );
+    }
+
+    for (j, column) in computed.iter().enumerate() {
+        header_cells.push(
+            Cell::from(right(column.header(data), widths[first_computed + j])).style(
+                Style::default()
+                    .fg(TEXT_ACCENT)
+                    .bg(HEADER_BG)
+                    .add_modifier(Modifier::BOLD | Modifier::ITALIC),
+            ),
+        );
+    }
+
+    // Aggregate headers with accent color
+    for (k, aggregate) in stats.columns().iter().enumerate() {
+        header_cells.push(
+            Cell::from(right(stats.header(false, *aggregate), widths[n + 1 + k])).style(
+                Style::default()
+                    .fg(TEXT_ACCENT)
+                    .bg(HEADER_BG)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        );
+    }
 
-    Row::new(header_cells).height(1)
+    Row::new(header_cells).height(header_height(data, col_window))
 }
 
 // === UI ====================================================================
 
+#[allow(clippy::too_many_arguments)]
 fn render_base_ui(
     f: &mut Frame,
-    batch: &RecordBatch,
+    area: Rect,
+    data: &BatchSource,
     all_col_indices: &[usize],
-    col_offset: usize,
+    offsets: HorizontalOffsets,
     visible_cols: usize,
     num_rows: usize,
     num_cols: usize,
     row_start: usize,
-) {
+    source: SourceWindow,
+    stats: &RowStats,
+    bookmarks: &Bookmarks,
+    diff: Option<&DiffColumn>,
+    computed: &[&VectorColumn],
+    outliers: Option<&OutlierCells>,
+    groups: Option<&ColumnGroups>,
+    added: Option<&AddedRows>,
+    hidden: usize,
+    formats: &ValueFormatter,
+) -> (ScrollWindow, ScrollWindow) {
+    let HorizontalOffsets {
+        col_offset,
+        pinned_col,
+        marked,
+        ..
+    } = offsets;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // metadata
+            Constraint::Length(1), // focused column
             Constraint::Min(0),    // table
             Constraint::Length(3), // status
         ])
-        .split(f.area());
+        .split(area);
+    let (column_strip, table_area, status_area) = (chunks[1], chunks[2], chunks[3]);
 
-    let schema = batch.schema();
+    // metadata row with color; declared dimensions that disagree with the
+    // data are flagged
+    let mut meta_line = metadata_line(
+        data,
+        num_rows,
+        num_cols,
+        "    ",
+        dims_mismatch(data, source),
+    );
+    if let Some(added) = added {
+        meta_line.push_span(Span::styled(
+            format!("    {}", added.note()),
+            Style::default().fg(TEXT_ACCENT),
+        ));
+    }
 
-    // metadata row with color
-    let mut name_idx = None;
-    let mut n_rows_idx = None;
-    let mut n_cols_idx = None;
-    for (i, field) in schema.fields().iter().enumerate() {
-        match field.name().as_str() {
-            "name_id" => name_idx = Some(i),
-            "n_rows" => n_rows_idx = Some(i),
-            "n_cols" => n_cols_idx = Some(i),
-            _ => {}
-        }
-    }
-
-    let meta_text = if let Some(name_i) = name_idx {
-        let name = format_value(batch.column(name_i), 0);
-        let nrows_val = n_rows_idx
-            .map(|i| format_value(batch.column(i), 0))
-            .unwrap_or_else(|| "?".to_string());
-        let ncols_val = n_cols_idx
-            .map(|i| format_value(batch.column(i), 0))
-            .unwrap_or_else(|| "?".to_string());
-        format!("name_id: {name}    n_rows: {nrows_val}    n_cols: {ncols_val}")
-    } else {
-        format!("rows: {num_rows}    cols: {num_cols}")
-    };
+    let header_paragraph = Paragraph::new(meta_line).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_ACCENT))
+            .title(" Metadata "),
+    );
+    f.render_widget(header_paragraph, chunks[0]);
 
-    let header_paragraph =
-        Paragraph::new(Span::styled(meta_text, Style::default().fg(TEXT_SECONDARY))).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(BORDER_ACCENT))
-                .title(" Metadata "),
+    // horizontal feature window
+    let (cols, col_window) = drawn_features(all_col_indices, col_offset, pinned_col, visible_cols);
+    let col_offset = cols.start;
+    let col_window = col_window.as_slice();
+
+    let schema = data.schema();
+    if let Some(&focused) = col_window.first() {
+        let feature = all_col_indices.iter().position(|&c| c == focused);
+        f.render_widget(
+            Paragraph::new(column_info_line(
+                &schema,
+                focused,
+                feature.unwrap_or_default(),
+                all_col_indices.len(),
+                source,
+            )),
+            column_strip,
         );
-    f.render_widget(header_paragraph, chunks[0]);
+    }
 
-    // table window size
-    let table_area_height = chunks[1].height.saturating_sub(3);
-    let max_visible_rows = table_area_height as usize;
-    let end_row = (row_start + max_visible_rows).min(num_rows);
+    let mut others = vec![row_label_width(data)]; // "Row" column
+    if diff.is_some() {
+        others.push(VALUE_COL_WIDTH);
+    }
+    others.extend(computed.iter().map(|_| VALUE_COL_WIDTH));
+    others.extend(stats.columns().iter().map(|_| STAT_COL_WIDTH));
+    let value_width = value_col_width(table_area, &others, visible_cols);
+    let mut widths = vec![Constraint::Length(others[0])];
+    for &c in col_window {
+        // vector previews need more room than a number
+        let width = if is_vector_type(schema.field(c).data_type()) {
+            value_width.max(VECTOR_COL_WIDTH)
+        } else {
+            value_width
+        };
+        widths.push(Constraint::Length(width));
+    }
+    widths.extend(others[1..].iter().map(|&w| Constraint::Length(w)));
+    let cell_widths = column_widths(table_area, &widths);
 
-    // horizontal feature window
-    let col_window = feature_window(all_col_indices, col_offset, visible_cols);
-    let header_row = render_header(batch, col_window, col_offset);
+    let header_row = render_header(
+        data,
+        col_window,
+        col_offset,
+        &cell_widths,
+        stats,
+        diff,
+        computed,
+    );
+    let footer_row = match diff {
+        Some(diff) => render_diff_footer(
+            data,
+            col_window,
+            &cell_widths[1..],
+            diff,
+            computed,
+            stats.columns().len(),
+        ),
+        None => render_null_footer(
+            data,
+            col_window,
+            &cell_widths[1..],
+            computed,
+            stats.columns().len(),
+        ),
+    };
+    let footer_height = if diff.is_some() { 2 } else { 1 };
+
+    // table window size: borders, header and null-count footer
+    let table_area_height = table_area
+        .height
+        .saturating_sub(2 + footer_height + header_height(data, col_window));
+    let max_visible_rows = table_area_height as usize;
+    let row_range = vertical_window(row_start, max_visible_rows, num_rows);
+    let row_start = row_range.start;
 
+    // marks and aggregates are by feature, never of a group's summary
+    let features = groups.map_or(all_col_indices, |g| g.features());
     let rows = render_rows_window(
-        batch,
+        data,
         col_window,
-        all_col_indices,
+        features,
         row_start,
-        end_row,
+        row_range.end,
         col_offset,
+        &cell_widths,
+        stats,
+        bookmarks,
+        diff,
+        computed,
+        outliers,
+        added,
+        formats,
     );
 
-    let mut widths = vec![Constraint::Length(5)]; // "Row" column
-    for _ in col_window {
-        widths.push(Constraint::Length(12));
-    }
-    widths.push(Constraint::Length(10)); // avg
-    widths.push(Constraint::Length(10)); // std
-
     let total_feat_cols = all_col_indices.len();
-    let start_col = if total_feat_cols == 0 {
-        0
+
+    let pinned_note = pinned_col
+        .and_then(|p| all_col_indices.get(p).copied())
+        .map(|c| format!(", pinned {}", display_name(schema.field(c))))
+        .unwrap_or_default();
+    let marked_names: Vec<String> = marked
+        .iter()
+        .flatten()
+        .filter_map(|&m| features.get(m))
+        .map(|&c| display_name(schema.field(c)).to_string())
+        .collect();
+    let marked_note = if marked_names.is_empty() {
+        String::new()
     } else {
-        col_offset + 1
+        format!(", marked {}", marked_names.join(" & "))
     };
-    let end_col = (col_offset + col_window.len()).min(total_feat_cols);
-
+    let diff_note = diff
+        .map(|d| format!(", {}", d.note(data)))
+        .unwrap_or_default();
+    let computed_note = if computed.is_empty() {
+        String::new()
+    } else {
+        format!(", {}", vector_note(computed, data))
+    };
+    let outlier_note = outliers
+        .map(|o| format!(", {}", o.note()))
+        .unwrap_or_default();
+    let group_note = groups
+        .map(|g| format!(", {}", g.note()))
+        .unwrap_or_default();
     let title = format!(
-        " Lance Data (rows {}–{} of {}, feature cols {}–{} of {}) ",
-        row_start + 1,
-        end_row,
-        num_rows,
-        start_col,
-        end_col,
-        total_feat_cols
+        " Lance Data (rows {}, {}{}{}{}{}{}{}{}) ",
+        source.span(row_range),
+        source.col_span("feature cols", cols, total_feat_cols),
+        pinned_note,
+        marked_note,
+        diff_note,
+        computed_note,
+        outlier_note,
+        group_note,
+        transform_note(&schema)
     );
 
-    let table = Table::new(rows, widths)
+    let boundaries = groups.map(|g| g.boundaries(col_window));
+    let table = Table::new(rows, widths.clone())
         .header(header_row)
+        .footer(footer_row)
         .block(
             Block::default()
                 .borders(Borders::ALL)
@@ -644,31 +3250,82 @@ fn render_base_ui(
         )
         .column_spacing(1);
 
-    f.render_widget(table, chunks[1]);
+    f.render_widget(table, table_area);
+    if let Some(boundaries) = boundaries {
+        render_group_separators(f, table_area, &widths, &boundaries);
+    }
+
+    let rows_window = ScrollWindow {
+        start: row_start,
+        len: max_visible_rows,
+        total: num_rows,
+    };
+    let cols_window = ScrollWindow {
+        start: col_offset,
+        len: visible_cols,
+        total: total_feat_cols,
+    };
+    render_table_scrollbars(f, table_area, rows_window, cols_window);
 
     let status = format!(
-        " {} rows × {} total cols | {} feature cols (col_*) | mode: N×F | ↑↓ scroll rows | ←→ scroll features | t transpose | q quit ",
-        num_rows, num_cols, total_feat_cols
+        " {} | {} rows × {} total cols | {} feature cols (col_*){}{} | mode: N×F | ↑↓ scroll rows | ←→ scroll features | t transpose | i values | o overview | N nearest | b/'/B bookmarks | V review | W export | x mark, c label, v scatter, - diff | n norm, . dot | ! outliers | z scale | S window vs all | g groups, Enter expand | </> columns | R rename | d hide, D hidden, Z constant | a/A stats, scope | M metadata | L log | | columns | Ctrl-s save screen | q quit ",
+        rows_window.label(),
+        num_rows,
+        num_cols,
+        total_feat_cols,
+        hidden_note(hidden),
+        skipped_note(&data.schema(), stats.skipped())
     );
     let status_widget = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(BORDER_ACCENT))
         .title(Span::styled(status, Style::default().fg(TEXT_ACCENT)));
-    f.render_widget(status_widget, chunks[2]);
+    f.render_widget(status_widget, status_area);
+
+    (rows_window, cols_window)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_rows_window<'a>(
-    batch: &'a RecordBatch,
+    data: &BatchSource,
     col_window: &'a [usize],
     all_cols: &'a [usize],
     row_start: usize,
     row_end: usize,
     col_offset: usize,
+    widths: &[u16],
+    stats: &RowStats,
+    bookmarks: &Bookmarks,
+    diff: Option<&DiffColumn>,
+    computed: &[&VectorColumn],
+    outliers: Option<&OutlierCells>,
+    added: Option<&AddedRows>,
+    formats: &ValueFormatter,
 ) -> Vec<Row<'a>> {
     let mut out = Vec::with_capacity(row_end.saturating_sub(row_start));
+    let schema = data.schema();
+    let first_computed = col_window.len() + usize::from(diff.is_some()) + 1;
+    // a windowed aggregate skips the group summaries it shows
+    let stat_window: Vec<usize> = stats
+        .aggregated(col_window)
+        .into_iter()
+        .filter(|c| all_cols.contains(c))
+        .collect();
+    let stat_cols = stats.aggregated(all_cols);
+    let n = first_computed - 1 + computed.len();
 
     for row_idx in row_start..row_end {
-        let row_bg = if row_idx % 2 == 0 {
+        // Rows past a seam come from the next batch
+        let Some((batch, r)) = data.locate(row_idx) else {
+            break;
+        };
+        // A deleted row (`--include-deleted`) is dimmed and struck through
+        let tombstone = is_tombstone(data, row_idx);
+        // A row added since `--since-version` has its own background
+        let new = !tombstone && added.is_some_and(|a| a.contains(row_id(data, row_idx)));
+        let row_bg = if new {
+            NEW_ROW_BG
+        } else if row_idx % 2 == 0 {
             EVEN_ROW_BG
         } else {
             ODD_ROW_BG
@@ -676,7 +3333,7 @@ fn render_rows_window<'a>(
 
         // Row index cell
         let mut cells = vec![
-            Cell::from(row_idx.to_string()).style(
+            Cell::from(row_label_text(data, row_idx, widths[0], bookmarks)).style(
                 Style::default()
                     .fg(TEXT_SECONDARY)
                     .bg(row_bg)
@@ -687,64 +3344,70 @@ fn render_rows_window<'a>(
         // Feature value cells with alternating column colors
         for (display_idx, &col_idx) in col_window.iter().enumerate() {
             let col = batch.column(col_idx);
-            let s = format_value(col, row_idx);
-            let cell_bg = get_cell_bg_color(row_idx, col_offset + display_idx);
+            let s = aligned(
+                col.data_type(),
+                formats.format(schema.field(col_idx).name(), col, r),
+                widths[display_idx + 1],
+            );
+            let cell_bg = if new {
+                NEW_ROW_BG
+            } else {
+                get_cell_bg_color(row_idx, col_offset + display_idx)
+            };
+            let fg = if outliers.is_some_and(|o| o.is_outlier(col_idx, row_idx)) {
+                TEXT_WARNING
+            } else {
+                TEXT_PRIMARY
+            };
 
-            cells.push(Cell::from(s).style(Style::default().fg(TEXT_PRIMARY).bg(cell_bg)));
+            cells.push(Cell::from(s).style(Style::default().fg(fg).bg(cell_bg)));
         }
 
-        // Calculate stats over all features
-        let mut vals: Vec<f64> = Vec::with_capacity(all_cols.len());
-        for &col_idx in all_cols {
-            let col = batch.column(col_idx);
-            if col.is_null(row_idx) {
-                continue;
-            }
-            match col.data_type() {
-                DataType::Float32 => {
-                    let a = col.as_any().downcast_ref::<Float32Array>().unwrap();
-                    vals.push(a.value(row_idx) as f64);
-                }
-                DataType::Float64 => {
-                    let a = col.as_any().downcast_ref::<Float64Array>().unwrap();
-                    vals.push(a.value(row_idx));
-                }
-                DataType::Int32 => {
-                    let a = col.as_any().downcast_ref::<Int32Array>().unwrap();
-                    vals.push(a.value(row_idx) as f64);
-                }
-                DataType::Int64 => {
-                    let a = col.as_any().downcast_ref::<Int64Array>().unwrap();
-                    vals.push(a.value(row_idx) as f64);
-                }
-                DataType::UInt32 => {
-                    let a = col.as_any().downcast_ref::<UInt32Array>().unwrap();
-                    vals.push(a.value(row_idx) as f64);
-                }
-                DataType::UInt64 => {
-                    let a = col.as_any().downcast_ref::<UInt64Array>().unwrap();
-                    vals.push(a.value(row_idx) as f64);
-                }
-                _ => {}
-            }
+        // The computed column, coloured by magnitude
+        if let Some(diff) = diff {
+            let (text, fg) = match diff.value(row_idx) {
+                Some(v) => (format_float(v, STAT_DECIMALS), diff.color(v)),
+                None => ("NA".to_string(), TEXT_SECONDARY),
+            };
+            cells.push(
+                Cell::from(right(text, widths[first_computed - 1]))
+                    .style(Style::default().fg(fg).bg(row_bg)),
+            );
         }
 
-        let (avg_str, std_str) = if vals.is_empty() {
-            ("NA".to_string(), "NA".to_string())
-        } else {
-            let n = vals.len() as f64;
-            let sum: f64 = vals.iter().sum();
-            let mean = sum / n;
-            let var: f64 = vals.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n;
-            let std = var.sqrt();
-            (format!("{:.4}", mean), format!("{:.4}", std))
-        };
+        // Norm and dot products; a null dimension counted as 0 is starred
+        for (j, column) in computed.iter().enumerate() {
+            let text = match column.value(row_idx) {
+                Some((v, false)) => format_float(v, STAT_DECIMALS),
+                Some((v, true)) => format!("{}{PARTIAL_MARK}", format_float(v, STAT_DECIMALS)),
+                None => "NA".to_string(),
+            };
+            cells.push(
+                Cell::from(right(text, widths[first_computed + j]))
+                    .style(Style::default().fg(TEXT_PRIMARY).bg(row_bg)),
+            );
+        }
 
-        // Stats cells with accent color
-        cells.push(Cell::from(avg_str).style(Style::default().fg(TEXT_ACCENT).bg(row_bg)));
-        cells.push(Cell::from(std_str).style(Style::default().fg(TEXT_ACCENT).bg(row_bg)));
+        // Aggregates over all features with accent color
+        if !stats.columns().is_empty() {
+            let texts = stats.texts(false, row_idx, &stat_window, |window| {
+                row_values(data, window.unwrap_or(&stat_cols), row_idx)
+            });
+            for (k, text) in texts.into_iter().enumerate() {
+                let color = stat_color(&text);
+                cells.push(
+                    Cell::from(right(text, widths[n + 1 + k]))
+                        .style(Style::default().fg(color).bg(row_bg)),
+                );
+            }
+        }
 
-        out.push(Row::new(cells).height(1));
+        let row = Row::new(cells).height(1);
+        out.push(if tombstone {
+            row.style(Style::default().add_modifier(Modifier::CROSSED_OUT | Modifier::DIM))
+        } else {
+            row
+        });
     }
 
     out
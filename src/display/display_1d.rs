@@ -1,6 +1,5 @@
 use arrow::array::*;
-use arrow::datatypes::DataType;
-use arrow_array::{ArrayRef, RecordBatch};
+use arrow_array::ArrayRef;
 use ratatui::layout::Rect;
 use ratatui::text::Line;
 use ratatui::text::Span;
@@ -11,6 +10,102 @@ use ratatui::{
     style::{Color, Modifier, Style},
     widgets::{Block, Borders, Paragraph, Row, Table},
 };
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::display::BORDER_ACCENT;
+use crate::display::display::{
+    ScrollWindow, aligned, column_widths, display_name, extract_numeric_value,
+    format_value_with_decimals, header_height, header_text, metadata_text, render_null_footer,
+    render_table_scrollbars, right, row_label_text, row_label_width,
+};
+use crate::display::display_bookmarks::Bookmarks;
+use crate::display::display_histogram::{Clamp, DistributionPanel, Histogram, bar_rows};
+use crate::display::row_profile::render_row_profile;
+use crate::display::window::{SourceWindow, feature_window, vertical_window};
+use crate::functions::batch_source::BatchSource;
+
+/// Width of a value column, room for 12 decimal digits.
+const VALUE_1D_WIDTH: u16 = 26;
+
+/// The side panels of the 1D view: the distribution (`Tab` focuses it) and
+/// the row profile (`p`), drawn from values read once per column window
+/// and data rather than on every frame.
+#[derive(Default)]
+pub(crate) struct VectorPanels {
+    pub distribution: DistributionPanel,
+    pub profile: bool,
+    values: RefCell<Option<VectorValues>>,
+}
+
+/// The visible columns' values, as the panels use them.
+struct VectorValues {
+    /// The column window and the arrays read, to tell when they change
+    key: Vec<usize>,
+    /// Every non-NULL value, sorted
+    sorted: Rc<Vec<f64>>,
+    /// Per row, the mean of its non-NULL values; `None` when there are none
+    rows: Rc<Vec<Option<f64>>>,
+}
+
+impl VectorPanels {
+    /// The sorted values and the per-row means of `col_window`, read again
+    /// only when the window or the arrays behind it changed.
+    fn values(
+        &self,
+        data: &BatchSource,
+        col_window: &[usize],
+    ) -> (Rc<Vec<f64>>, Rc<Vec<Option<f64>>>) {
+        let key: Vec<usize> = col_window
+            .iter()
+            .copied()
+            .chain(data.batches().iter().flat_map(|batch| {
+                col_window
+                    .iter()
+                    .map(|&c| Arc::as_ptr(batch.column(c)) as *const () as usize)
+            }))
+            .collect();
+        let mut cached = self.values.borrow_mut();
+        if let Some(values) = cached.as_ref().filter(|v| v.key == key) {
+            return (values.sorted.clone(), values.rows.clone());
+        }
+
+        // All numeric values of the visible columns, column by column
+        let mut sorted: Vec<f64> = Vec::new();
+        for &col_idx in col_window {
+            for batch in data.batches() {
+                let col = batch.column(col_idx);
+                for row_idx in 0..batch.num_rows() {
+                    if !col.is_null(row_idx)
+                        && let Some(val) = extract_numeric_value(col, row_idx)
+                    {
+                        sorted.push(val);
+                    }
+                }
+            }
+        }
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let rows: Vec<Option<f64>> = (0..data.num_rows())
+            .map(|row| {
+                let (batch, r) = data.locate(row)?;
+                let vals: Vec<f64> = col_window
+                    .iter()
+                    .map(|&c| batch.column(c))
+                    .filter(|col| !col.is_null(r))
+                    .filter_map(|col| extract_numeric_value(col, r))
+                    .collect();
+                (!vals.is_empty()).then(|| vals.iter().sum::<f64>() / vals.len() as f64)
+            })
+            .collect();
+        let values = cached.insert(VectorValues {
+            key,
+            sorted: Rc::new(sorted),
+            rows: Rc::new(rows),
+        });
+        (values.sorted.clone(), values.rows.clone())
+    }
+}
 
 /// Render a 1D vector dataset (LanceLayout::Vector1D).
 ///
@@ -18,16 +113,22 @@ use ratatui::{
 /// - Top: metadata (same style as main viewer)
 /// - Middle: table with `Row | value` (no avg/std), 12 decimal digits for floats
 /// - Bottom: status bar
-pub fn render_1d_ui(
+///
+/// Returns the rows window drawn.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_1d_ui(
     f: &mut Frame,
-    batch: &RecordBatch,
+    data: &BatchSource,
     col_indices: &[usize],
     col_offset: usize,
     visible_cols: usize,
     num_rows: usize,
     num_cols: usize,
     row_start: usize,
-) {
+    source: SourceWindow,
+    bookmarks: &Bookmarks,
+    panels: &VectorPanels,
+) -> Option<ScrollWindow> {
     // 1) First split: metadata / content / status (vertical)
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -38,174 +139,186 @@ pub fn render_1d_ui(
         ])
         .split(f.area());
 
-    // 2) Split content area: table on left, stats panel on right (horizontal)
+    // 2) Split content area: table on left, stats panel (and the row
+    //    profile beside it when shown) on right (horizontal)
     let content_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(30), // table
-            Constraint::Percentage(70), // stats panel
-        ])
+        .constraints(if panels.profile {
+            [
+                Constraint::Percentage(30), // table
+                Constraint::Percentage(35), // stats panel
+                Constraint::Percentage(35), // row profile
+            ]
+            .as_slice()
+        } else {
+            [
+                Constraint::Percentage(30), // table
+                Constraint::Percentage(70), // stats panel
+            ]
+            .as_slice()
+        })
         .split(main_chunks[1]);
 
-    let schema = batch.schema();
-
     // ---- Metadata header (unchanged) ----
-    let mut name_idx = None;
-    let mut n_rows_idx = None;
-    let mut n_cols_idx = None;
-    for (i, field) in schema.fields().iter().enumerate() {
-        match field.name().as_str() {
-            "name_id" => name_idx = Some(i),
-            "n_rows" => n_rows_idx = Some(i),
-            "n_cols" => n_cols_idx = Some(i),
-            _ => {}
-        }
-    }
-
-    let meta_text = if let Some(name_i) = name_idx {
-        let name = format_value(batch.column(name_i), 0);
-        let nrows_val = n_rows_idx
-            .map(|i| format_value(batch.column(i), 0))
-            .unwrap_or_else(|| "?".to_string());
-        let ncols_val = n_cols_idx
-            .map(|i| format_value(batch.column(i), 0))
-            .unwrap_or_else(|| "?".to_string());
-        format!("name_id: {name} n_rows: {nrows_val} n_cols: {ncols_val}")
-    } else {
-        format!("rows: {num_rows} cols: {num_cols}")
-    };
+    let meta_text = metadata_text(data, num_rows, num_cols, " ");
 
     let header_paragraph = Paragraph::new(Span::raw(meta_text))
         .block(Block::default().borders(Borders::ALL).title(" Metadata "));
     f.render_widget(header_paragraph, main_chunks[0]);
 
     // ---- Table rendering (use content_chunks[0] instead of chunks[1]) ----
-    let table_area_height = content_chunks[0].height.saturating_sub(3);
+    let (cols, col_window) = feature_window(col_indices, col_offset, visible_cols);
+    // borders, header and null-count footer
+    let table_area_height = content_chunks[0]
+        .height
+        .saturating_sub(3 + header_height(data, col_window));
     let max_visible_rows = table_area_height as usize;
-    let end_row = (row_start + max_visible_rows).min(num_rows);
-    let col_window = feature_window(col_indices, col_offset, visible_cols);
+    let row_range = vertical_window(row_start, max_visible_rows, num_rows);
 
-    let header_row = render_header_1d(batch, col_window);
-    let rows = render_rows_window_1d(batch, col_window, row_start, end_row);
-
-    let mut widths = vec![Constraint::Length(5)];
+    let mut widths = vec![Constraint::Length(row_label_width(data))];
     for _ in col_window {
-        widths.push(Constraint::Length(26));
+        widths.push(Constraint::Length(VALUE_1D_WIDTH));
     }
+    // The table panel is usually narrower than the columns want
+    let cell_widths = column_widths(content_chunks[0], &widths);
+
+    let header_row = render_header_1d(data, col_window, &cell_widths);
+    let rows = render_rows_window_1d(
+        data,
+        col_window,
+        row_range.start,
+        row_range.end,
+        &cell_widths,
+        bookmarks,
+    );
 
     let total_feat_cols = col_indices.len();
-    let start_col = if total_feat_cols == 0 {
-        0
-    } else {
-        col_offset + 1
-    };
-    let end_col = (col_offset + col_window.len()).min(total_feat_cols);
     let title = format!(
-        " Lance Vector Data (rows {}–{} of {}, cols {}–{} of {}) ",
-        row_start + 1,
-        end_row,
-        num_rows,
-        start_col,
-        end_col,
-        total_feat_cols,
+        " Lance Vector Data (rows {}, {}) ",
+        source.span(row_range.clone()),
+        source.col_span("cols", cols.clone(), total_feat_cols),
     );
 
     let table = Table::new(rows, widths)
         .header(header_row)
+        .footer(render_null_footer(
+            data,
+            col_window,
+            &cell_widths[1..],
+            &[],
+            0,
+        ))
         .block(Block::default().borders(Borders::ALL).title(title))
         .column_spacing(1);
     f.render_widget(table, content_chunks[0]);
 
+    let rows_window = ScrollWindow {
+        start: row_range.start,
+        len: max_visible_rows,
+        total: num_rows,
+    };
+    let cols_window = ScrollWindow {
+        start: cols.start,
+        len: visible_cols,
+        total: total_feat_cols,
+    };
+    render_table_scrollbars(f, content_chunks[0], rows_window, cols_window);
+
     // ---- NEW: Statistics Panel ----
-    render_stats_panel(f, batch, col_window, content_chunks[1]);
+    if !col_window.is_empty() {
+        let (sorted, rows) = panels.values(data, col_window);
+        render_stats_panel(f, &sorted, content_chunks[1], &panels.distribution);
+        if panels.profile {
+            render_row_profile(f, content_chunks[2], &rows);
+        }
+    }
 
     // ---- Status bar (unchanged) ----
     let status = format!(
-        " {} rows × {} total cols | {} vector column(s) | mode: 1D | ↑↓ scroll rows | ←→ scroll columns | q quit ",
-        num_rows, num_cols, total_feat_cols
+        " {} | {} rows × {} total cols | {} vector column(s) | mode: 1D | ↑↓ scroll rows | ←→ scroll columns | Tab histogram | p row profile | i values | M metadata | L log | q quit ",
+        rows_window.label(),
+        num_rows,
+        num_cols,
+        total_feat_cols
     );
     let status_widget = Block::default().borders(Borders::ALL).title(status);
     f.render_widget(status_widget, main_chunks[2]);
+    Some(rows_window)
 }
 
 // ============= helpers (copied / specialized) ===============================
 
-fn render_stats_panel(f: &mut Frame, batch: &RecordBatch, col_window: &[usize], area: Rect) {
-    if col_window.is_empty() {
-        return;
-    }
-
-    // Collect all numeric values from visible columns
-    let mut all_values: Vec<f64> = Vec::new();
-    for &col_idx in col_window {
-        let col = batch.column(col_idx);
-        for row_idx in 0..batch.num_rows() {
-            if !col.is_null(row_idx) {
-                if let Some(val) = extract_numeric_value(col, row_idx) {
-                    all_values.push(val);
-                }
-            }
-        }
-    }
+/// The distribution panel: a histogram of the visible columns' `sorted`
+/// values over the bins and range `panel` picks, with how many values the
+/// range leaves out, and their count, mean and median.
+fn render_stats_panel(f: &mut Frame, all_values: &[f64], area: Rect, panel: &DistributionPanel) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(if panel.focused {
+            Style::default().fg(BORDER_ACCENT)
+        } else {
+            Style::default()
+        })
+        .title(panel.title());
 
     if all_values.is_empty() {
-        let empty_block = Block::default()
-            .borders(Borders::ALL)
-            .title(" Distribution ");
-        f.render_widget(empty_block, area);
+        f.render_widget(block, area);
         return;
     }
 
     // Calculate statistics
-    all_values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
     let mean = all_values.iter().sum::<f64>() / all_values.len() as f64;
-    let median = if all_values.len() % 2 == 0 {
+    let median = if all_values.len().is_multiple_of(2) {
         (all_values[all_values.len() / 2 - 1] + all_values[all_values.len() / 2]) / 2.0
     } else {
         all_values[all_values.len() / 2]
     };
 
-    // Create histogram
-    let min_val = all_values[0];
-    let max_val = all_values[all_values.len() - 1];
+    // Create histogram, over the clamped range when `%` picked one
     let available_width = area.width.saturating_sub(4) as usize;
-    let num_bins = 20.min(available_width / 3); // space for columns
-    let bin_width = (max_val - min_val) / num_bins as f64;
-
-    let mut bins = vec![0usize; num_bins];
-    for &val in &all_values {
-        let bin_idx = if bin_width > 0.0 {
-            ((val - min_val) / bin_width).floor() as usize
-        } else {
-            0
-        };
-        let bin_idx = bin_idx.min(num_bins - 1);
-        bins[bin_idx] += 1;
+    let num_bins = panel.bins(available_width / 3); // space for columns
+    if num_bins == 0 {
+        f.render_widget(Paragraph::new("too narrow").block(block), area);
+        return;
     }
+    let (binned, min_val, max_val, outside) = match panel.clamp {
+        Clamp::All => (
+            all_values.to_vec(),
+            all_values[0],
+            all_values[all_values.len() - 1],
+            0,
+        ),
+        clamp => {
+            let finite: Vec<f64> = all_values
+                .iter()
+                .copied()
+                .filter(|v| v.is_finite())
+                .collect();
+            let (lo, hi) = clamp.bounds(&finite).unwrap_or((f64::NAN, f64::NAN));
+            let kept: Vec<f64> = finite
+                .iter()
+                .copied()
+                .filter(|v| (lo..=hi).contains(v))
+                .collect();
+            let outside = finite.len() - kept.len();
+            (kept, lo, hi, outside)
+        }
+    };
 
-    let max_count = *bins.iter().max().unwrap_or(&1);
-
-    // Build vertical histogram (columns grow upward)
+    // Build vertical histogram (columns grow upward); degenerate inputs
+    // (a single value, all values equal) get a message instead of bars
     let chart_height = area.height.saturating_sub(10) as usize; // reserve space for labels
     let mut lines = vec![Line::from("")];
-
-    // Draw histogram rows from top to bottom
-    for level in (1..=chart_height).rev() {
-        let mut row_str = String::new();
-        for &count in &bins {
-            let bar_height = if max_count > 0 {
-                (count as f64 / max_count as f64 * chart_height as f64).ceil() as usize
-            } else {
-                0
-            };
-
-            if bar_height >= level {
-                row_str.push_str("██");
-            } else {
-                row_str.push_str("  ");
-            }
+    match Histogram::new(&binned, num_bins, false) {
+        Ok(hist) => {
+            let heights: Vec<f64> = hist.counts.iter().map(|&c| c as f64).collect();
+            lines.extend(
+                bar_rows(&heights, chart_height, 2)
+                    .into_iter()
+                    .map(Line::from),
+            );
         }
-        lines.push(Line::from(row_str));
+        Err(message) => lines.push(Line::from(message).style(Style::default().fg(Color::Yellow))),
     }
 
     // Add baseline
@@ -215,10 +328,19 @@ fn render_stats_panel(f: &mut Frame, batch: &RecordBatch, col_window: &[usize],
     let axis_line = format!(
         "{:<8.2}{}>{:>8.2}",
         min_val,
-        " ".repeat(num_bins * 2 - 18),
+        " ".repeat((num_bins * 2).saturating_sub(18)),
         max_val
     );
     lines.push(Line::from(axis_line));
+    if panel.clamp != Clamp::All {
+        lines.push(
+            Line::from(format!(
+                "central {}%: {outside} outside [{min_val:.2}, {max_val:.2}]",
+                panel.clamp.percent()
+            ))
+            .style(Style::default().fg(Color::Yellow)),
+        );
+    }
     lines.push(Line::from(""));
 
     // Statistics on one line horizontally
@@ -230,127 +352,22 @@ fn render_stats_panel(f: &mut Frame, batch: &RecordBatch, col_window: &[usize],
     );
     lines.push(Line::from(stats_line).style(Style::default().fg(Color::Cyan)));
 
-    let paragraph = Paragraph::new(lines).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title(" Distribution ")
-            .padding(Padding::horizontal(2)), // 2 spaces on left and right
-    );
+    let paragraph = Paragraph::new(lines).block(block.padding(Padding::horizontal(2))); // 2 spaces on left and right
     f.render_widget(paragraph, area);
 }
 
-fn extract_numeric_value(array: &ArrayRef, row_idx: usize) -> Option<f64> {
-    match array.data_type() {
-        DataType::Float32 => {
-            let arr = array.as_any().downcast_ref::<Float32Array>().unwrap();
-            Some(arr.value(row_idx) as f64)
-        }
-        DataType::Float64 => {
-            let arr = array.as_any().downcast_ref::<Float64Array>().unwrap();
-            Some(arr.value(row_idx))
-        }
-        DataType::Int32 => {
-            let arr = array.as_any().downcast_ref::<Int32Array>().unwrap();
-            Some(arr.value(row_idx) as f64)
-        }
-        DataType::Int64 => {
-            let arr = array.as_any().downcast_ref::<Int64Array>().unwrap();
-            Some(arr.value(row_idx) as f64)
-        }
-        DataType::UInt32 => {
-            let arr = array.as_any().downcast_ref::<UInt32Array>().unwrap();
-            Some(arr.value(row_idx) as f64)
-        }
-        DataType::UInt64 => {
-            let arr = array.as_any().downcast_ref::<UInt64Array>().unwrap();
-            Some(arr.value(row_idx) as f64)
-        }
-        _ => None,
-    }
-}
-
-fn format_value(array: &ArrayRef, row_idx: usize) -> String {
-    if array.is_null(row_idx) {
-        return "NULL".to_string();
-    }
-    match array.data_type() {
-        DataType::Float32 => {
-            let arr = array.as_any().downcast_ref::<Float32Array>().unwrap();
-            format!("{:.4}", arr.value(row_idx))
-        }
-        DataType::Float64 => {
-            let arr = array.as_any().downcast_ref::<Float64Array>().unwrap();
-            format!("{:.4}", arr.value(row_idx))
-        }
-        DataType::Int32 => {
-            let arr = array.as_any().downcast_ref::<Int32Array>().unwrap();
-            format!("{}", arr.value(row_idx))
-        }
-        DataType::Int64 => {
-            let arr = array.as_any().downcast_ref::<Int64Array>().unwrap();
-            format!("{}", arr.value(row_idx))
-        }
-        DataType::UInt32 => {
-            let arr = array.as_any().downcast_ref::<UInt32Array>().unwrap();
-            format!("{}", arr.value(row_idx))
-        }
-        DataType::UInt64 => {
-            let arr = array.as_any().downcast_ref::<UInt64Array>().unwrap();
-            format!("{}", arr.value(row_idx))
-        }
-        DataType::Boolean => {
-            let arr = array.as_any().downcast_ref::<BooleanArray>().unwrap();
-            if arr.value(row_idx) {
-                "true".to_string()
-            } else {
-                "false".to_string()
-            }
-        }
-        DataType::Utf8 => {
-            let arr = array.as_any().downcast_ref::<StringArray>().unwrap();
-            let s = arr.value(row_idx);
-            if s.len() > 10 {
-                format!("{}…", &s[0..9])
-            } else {
-                s.to_string()
-            }
-        }
-        _ => "?".to_string(),
-    }
-}
-
+/// Cells of the 1D table show floats to 12 places.
 fn format_value_12f(array: &ArrayRef, row_idx: usize) -> String {
-    if array.is_null(row_idx) {
-        return "NULL".to_string();
-    }
-    match array.data_type() {
-        DataType::Float32 => {
-            let arr = array.as_any().downcast_ref::<Float32Array>().unwrap();
-            format!("{:.12}", arr.value(row_idx) as f64)
-        }
-        DataType::Float64 => {
-            let arr = array.as_any().downcast_ref::<Float64Array>().unwrap();
-            format!("{:.12}", arr.value(row_idx))
-        }
-        _ => format_value(array, row_idx),
-    }
-}
-
-fn feature_window<'a>(
-    all_cols: &'a [usize],
-    col_offset: usize,
-    visible_cols: usize,
-) -> &'a [usize] {
-    let start = col_offset.min(all_cols.len());
-    let end = (start + visible_cols).min(all_cols.len());
-    &all_cols[start..end]
+    format_value_with_decimals(array, row_idx, 12)
 }
 
-fn render_header_1d<'a>(batch: &'a RecordBatch, col_window: &'a [usize]) -> Row<'a> {
-    let schema = batch.schema();
-    let mut header_cells = vec!["Row".to_string()];
-    for &i in col_window {
-        header_cells.push(schema.field(i).name().to_string());
+fn render_header_1d<'a>(data: &BatchSource, col_window: &'a [usize], widths: &[u16]) -> Row<'a> {
+    let schema = data.schema();
+    let mut header_cells = vec![right("Row", widths[0])];
+    for (display_idx, &i) in col_window.iter().enumerate() {
+        let field = schema.field(i);
+        let text = header_text(display_name(field), data, i);
+        header_cells.push(aligned(field.data_type(), text, widths[display_idx + 1]));
     }
     Row::new(header_cells)
         .style(
@@ -358,24 +375,61 @@ fn render_header_1d<'a>(batch: &'a RecordBatch, col_window: &'a [usize]) -> Row<
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
         )
-        .height(1)
+        .height(header_height(data, col_window))
 }
 
 fn render_rows_window_1d<'a>(
-    batch: &'a RecordBatch,
+    data: &BatchSource,
     col_window: &'a [usize],
     row_start: usize,
     row_end: usize,
+    widths: &[u16],
+    bookmarks: &Bookmarks,
 ) -> Vec<Row<'a>> {
     let mut out = Vec::with_capacity(row_end.saturating_sub(row_start));
     for row_idx in row_start..row_end {
-        let mut cells = vec![row_idx.to_string()];
-        for &col_idx in col_window {
+        let Some((batch, r)) = data.locate(row_idx) else {
+            break;
+        };
+        let mut cells = vec![row_label_text(data, row_idx, widths[0], bookmarks)];
+        for (display_idx, &col_idx) in col_window.iter().enumerate() {
             let col = batch.column(col_idx);
-            let s = format_value_12f(col, row_idx);
-            cells.push(s);
+            let text = format_value_12f(col, r);
+            cells.push(aligned(col.data_type(), text, widths[display_idx + 1]));
         }
         out.push(Row::new(cells).height(1));
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::RecordBatch;
+
+    #[test]
+    fn panel_values_are_read_once_per_window_and_data() {
+        let batch = RecordBatch::try_from_iter([
+            (
+                "a",
+                Arc::new(Float64Array::from(vec![3.0, 1.0])) as ArrayRef,
+            ),
+            (
+                "b",
+                Arc::new(Float64Array::from(vec![Some(5.0), None])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let data = BatchSource::from(batch);
+        let panels = VectorPanels::default();
+
+        let (sorted, rows) = panels.values(&data, &[0, 1]);
+        assert_eq!(*sorted, [1.0, 3.0, 5.0]);
+        assert_eq!(*rows, [Some(4.0), Some(1.0)]);
+        // the same window of the same arrays comes from the cache
+        let (again, _) = panels.values(&data, &[0, 1]);
+        assert!(Rc::ptr_eq(&sorted, &again));
+        let (other, _) = panels.values(&data, &[1]);
+        assert_eq!(*other, [5.0]);
+    }
+}
@@ -0,0 +1,357 @@
+//! Row bookmarks: `b` toggles one on the top row (marked `◆` in the Row
+//! column), `'` opens a jump list with a preview of each bookmarked row, and
+//! `B` writes the bookmarks to a plain list of row ids. Bookmarks name rows
+//! by source row id, so they carry over between windows and samples of a
+//! dataset and persist in its session state. The rows flagged in review
+//! mode (see [`review`](crate::display::review)) and its cursor share the
+//! Row column's gutter, so they are kept here too.
+
+use anyhow::{Context, Result};
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::display::display::{format_value, row_id, row_of_id};
+use crate::display::display_metadata::centered;
+use crate::display::index_base::index_base;
+use crate::display::review::{CURSOR_MARK, FLAG_MARK};
+use crate::display::*;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::format::truncate_to_width;
+
+/// Marker of a bookmarked row in the Row column.
+pub(crate) const BOOKMARK_MARK: char = '◆';
+
+/// File `B` writes the bookmarked row ids to, in the working directory.
+pub(crate) const BOOKMARKS_FILE: &str = "javelin_bookmarks.txt";
+
+/// Leading values previewed per row in the jump list.
+const PREVIEW_VALUES: usize = 4;
+
+/// Bookmarked and review-flagged rows by source row id, and the review
+/// cursor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Bookmarks {
+    ids: BTreeSet<u64>,
+    flags: BTreeSet<u64>,
+    /// Batch row of the review cursor while reviewing
+    cursor: Option<usize>,
+}
+
+impl Bookmarks {
+    pub(crate) fn new(ids: impl IntoIterator<Item = u64>) -> Self {
+        Self {
+            ids: ids.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// These bookmarks with the rows `flags` flagged.
+    pub(crate) fn with_flags(self, flags: impl IntoIterator<Item = u64>) -> Self {
+        Self {
+            flags: flags.into_iter().collect(),
+            ..self
+        }
+    }
+
+    pub(crate) fn ids(&self) -> &BTreeSet<u64> {
+        &self.ids
+    }
+
+    pub(crate) fn flags(&self) -> &BTreeSet<u64> {
+        &self.flags
+    }
+
+    pub(crate) fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    pub(crate) fn set_cursor(&mut self, row: Option<usize>) {
+        self.cursor = row;
+    }
+
+    /// Whether row `row` of `data` is bookmarked.
+    pub(crate) fn contains(&self, data: &BatchSource, row: usize) -> bool {
+        !self.ids.is_empty() && self.ids.contains(&row_id(data, row))
+    }
+
+    /// Whether row `row` of `data` is flagged.
+    pub(crate) fn is_flagged(&self, data: &BatchSource, row: usize) -> bool {
+        !self.flags.is_empty() && self.flags.contains(&row_id(data, row))
+    }
+
+    /// Bookmark row `row` of `data`, or drop its bookmark. Returns whether
+    /// it is now bookmarked.
+    pub(crate) fn toggle(&mut self, data: &BatchSource, row: usize) -> bool {
+        toggle_id(&mut self.ids, row_id(data, row))
+    }
+
+    /// Flag row `row` of `data`, or drop its flag. Returns whether it is
+    /// now flagged.
+    pub(crate) fn toggle_flag(&mut self, data: &BatchSource, row: usize) -> bool {
+        toggle_id(&mut self.flags, row_id(data, row))
+    }
+
+    /// The Row column gutter of row `row` of `data`: the review cursor,
+    /// else [`FLAG_MARK`], else [`BOOKMARK_MARK`]; `None` for none.
+    pub(crate) fn mark(&self, data: &BatchSource, row: usize) -> Option<char> {
+        if self.cursor == Some(row) {
+            Some(CURSOR_MARK)
+        } else if self.is_flagged(data, row) {
+            Some(FLAG_MARK)
+        } else if self.contains(data, row) {
+            Some(BOOKMARK_MARK)
+        } else {
+            None
+        }
+    }
+
+    /// Write the row ids to `path`, one per line in ascending order.
+    pub(crate) fn export(&self, path: &Path) -> Result<()> {
+        write_ids(&self.ids, path).with_context(|| format!("writing bookmarks to {:?}", path))
+    }
+
+    /// Write the flagged row ids to `path`, as [`export`](Self::export).
+    pub(crate) fn export_flags(&self, path: &Path) -> Result<()> {
+        write_ids(&self.flags, path).with_context(|| format!("writing flags to {:?}", path))
+    }
+}
+
+fn toggle_id(ids: &mut BTreeSet<u64>, id: u64) -> bool {
+    if ids.remove(&id) {
+        false
+    } else {
+        ids.insert(id);
+        true
+    }
+}
+
+fn write_ids(ids: &BTreeSet<u64>, path: &Path) -> std::io::Result<()> {
+    let text: String = ids.iter().map(|id| format!("{id}\n")).collect();
+    std::fs::write(path, text)
+}
+
+/// What the viewer should do after a key press in the jump list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BookmarkAction {
+    Stay,
+    Close,
+    /// Scroll so this batch row is on top
+    Jump(usize),
+}
+
+/// The `'` jump list.
+pub(crate) struct BookmarkList {
+    /// (row id, batch row when loaded, preview), in id order
+    entries: Vec<(u64, Option<usize>, String)>,
+    selected: usize,
+}
+
+impl BookmarkList {
+    /// The bookmarks with the first values of `cols` (data columns) as each
+    /// loaded row's preview.
+    pub(crate) fn new(data: &BatchSource, bookmarks: &Bookmarks, cols: &[usize]) -> Self {
+        let entries = bookmarks
+            .ids()
+            .iter()
+            .map(|&id| {
+                let row = row_of_id(data, id);
+                let preview = match row.and_then(|r| data.locate(r)) {
+                    Some((batch, r)) => {
+                        let mut values: Vec<String> = cols
+                            .iter()
+                            .take(PREVIEW_VALUES)
+                            .map(|&c| truncate_to_width(&format_value(batch.column(c), r), 12))
+                            .collect();
+                        if cols.len() > PREVIEW_VALUES {
+                            values.push("…".to_string());
+                        }
+                        values.join("  ")
+                    }
+                    None => "(not in the loaded rows)".to_string(),
+                };
+                (id, row, preview)
+            })
+            .collect();
+        Self {
+            entries,
+            selected: 0,
+        }
+    }
+
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> BookmarkAction {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('\'') => {
+                return BookmarkAction::Close;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.entries.len().saturating_sub(1))
+            }
+            KeyCode::Enter => {
+                if let Some(&(_, Some(row), _)) = self.entries.get(self.selected) {
+                    return BookmarkAction::Jump(row);
+                }
+            }
+            _ => {}
+        }
+        BookmarkAction::Stay
+    }
+}
+
+pub(crate) fn render_bookmark_list(f: &mut Frame, list: &BookmarkList) {
+    let area = centered(f.area(), 70);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(format!(" Bookmarks ({}) ", list.entries.len()))
+        .title_bottom(" ↑↓ select | Enter jump | Esc close ");
+    f.render_widget(Clear, area);
+
+    if list.entries.is_empty() {
+        let msg = Paragraph::new(Span::styled(
+            "No bookmarks yet; b bookmarks the top row",
+            Style::default().fg(TEXT_SECONDARY),
+        ))
+        .block(block);
+        f.render_widget(msg, area);
+        return;
+    }
+
+    // Keep the selection in view.
+    let visible = area.height.saturating_sub(3) as usize;
+    let skip = (list.selected + 1).saturating_sub(visible);
+    let rows: Vec<Row> = list
+        .entries
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(visible)
+        .map(|(i, (id, row, preview))| {
+            let style = if i == list.selected {
+                Style::default()
+                    .fg(TEXT_ACCENT)
+                    .add_modifier(Modifier::REVERSED)
+            } else if row.is_none() {
+                Style::default().fg(TEXT_SECONDARY)
+            } else {
+                Style::default().fg(TEXT_PRIMARY)
+            };
+            Row::new(vec![
+                Cell::from(format!("#{}", index_base().show(*id as usize))),
+                Cell::from(preview.clone()),
+            ])
+            .style(style)
+        })
+        .collect();
+    let table = Table::new(rows, [Constraint::Length(12), Constraint::Min(8)])
+        .header(
+            Row::new(["row", "values"])
+                .style(Style::default().fg(HEADER_FG).add_modifier(Modifier::BOLD)),
+        )
+        .block(block)
+        .column_spacing(1);
+    f.render_widget(table, area);
+}
+
+/// A one-line note in the bottom-right corner above the status bar, e.g.
+/// where `B` wrote the bookmarks.
+pub(crate) fn render_notice(f: &mut Frame, text: &str) {
+    let frame = f.area();
+    let width = (text.chars().count() as u16 + 4).min(frame.width);
+    let area = Rect {
+        x: frame.x + frame.width - width,
+        y: frame.y + frame.height.saturating_sub(6),
+        width,
+        height: 3.min(frame.height),
+    };
+    let notice = Paragraph::new(Span::styled(text, Style::default().fg(TEXT_ACCENT))).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_ACCENT)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(notice, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functions::functions::attach_row_ids;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::{Float64Array, RecordBatch};
+    use std::sync::Arc;
+
+    #[test]
+    fn bookmarks_follow_source_ids_and_jump_to_loaded_rows() {
+        let schema = Schema::new(vec![Field::new("col_0", DataType::Float64, true)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Float64Array::from(vec![1.5, 2.5, 3.5]))],
+        )
+        .unwrap();
+        // A window of source rows 40..43
+        let window = BatchSource::from(attach_row_ids(&batch, vec![40, 41, 42]).unwrap());
+
+        let mut bookmarks = Bookmarks::new([7]);
+        assert!(bookmarks.toggle(&window, 1));
+        assert!(bookmarks.contains(&window, 1));
+        assert!(!bookmarks.contains(&window, 0));
+        assert_eq!(bookmarks.ids(), &BTreeSet::from([7, 41]));
+
+        let mut list = BookmarkList::new(&window, &bookmarks, &[0]);
+        assert_eq!(
+            list.entries[0],
+            (7, None, "(not in the loaded rows)".into())
+        );
+        assert_eq!(list.entries[1], (41, Some(1), "2.50000000".into()));
+        // Row 7 is not loaded, so Enter stays on it.
+        assert_eq!(list.handle_key(KeyCode::Enter), BookmarkAction::Stay);
+        list.handle_key(KeyCode::Down);
+        assert_eq!(list.handle_key(KeyCode::Enter), BookmarkAction::Jump(1));
+        assert_eq!(list.handle_key(KeyCode::Esc), BookmarkAction::Close);
+
+        let tmp = tempfile::tempdir().unwrap();
+        let out = tmp.path().join("bookmarks.txt");
+        bookmarks.export(&out).unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "7\n41\n");
+
+        assert!(!bookmarks.toggle(&window, 1));
+        assert_eq!(bookmarks.ids(), &BTreeSet::from([7]));
+    }
+
+    #[test]
+    fn flags_and_the_review_cursor_share_the_gutter() {
+        let schema = Schema::new(vec![Field::new("col_0", DataType::Float64, true)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Float64Array::from(vec![1.5, 2.5, 3.5]))],
+        )
+        .unwrap();
+        let window = BatchSource::from(attach_row_ids(&batch, vec![40, 41, 42]).unwrap());
+
+        let mut marks = Bookmarks::new([40, 41]).with_flags([42]);
+        assert!(marks.toggle_flag(&window, 1));
+        marks.set_cursor(Some(2));
+        let gutter: Vec<Option<char>> = (0..3).map(|r| marks.mark(&window, r)).collect();
+        assert_eq!(
+            gutter,
+            [Some(BOOKMARK_MARK), Some(FLAG_MARK), Some(CURSOR_MARK)]
+        );
+        assert!(marks.is_flagged(&window, 2));
+
+        let tmp = tempfile::tempdir().unwrap();
+        let out = tmp.path().join("flags.txt");
+        marks.export_flags(&out).unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "41\n42\n");
+        assert!(!marks.toggle_flag(&window, 2));
+        assert_eq!(marks.flags(), &BTreeSet::from([41]));
+    }
+}
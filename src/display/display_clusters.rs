@@ -0,0 +1,267 @@
+//! Full-screen `clusters --tui` view: the clustering summary on top, a
+//! selectable list of clusters on the left and the selected cluster's
+//! statistics and feature means on the right.
+
+use anyhow::Result;
+use crossterm::{
+    event::KeyCode,
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{
+    Frame, Terminal,
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+};
+use std::io;
+
+use crate::display::redraw::{poll_event, run_view};
+use crate::display::*;
+use crate::functions::clusters::{ClusterReport, cluster_lines, summary_lines};
+
+/// Selection state of the cluster list.
+pub(crate) struct ClusterView<'a> {
+    report: &'a ClusterReport,
+    selected: usize,
+    /// First feature mean shown in the detail panel
+    means_offset: usize,
+}
+
+impl<'a> ClusterView<'a> {
+    pub(crate) fn new(report: &'a ClusterReport) -> Self {
+        Self {
+            report,
+            selected: 0,
+            means_offset: 0,
+        }
+    }
+
+    /// Apply a key; returns false when the view should close.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> bool {
+        let last = self.report.clusters.len().saturating_sub(1);
+        let features = self.report.features;
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                self.means_offset = 0;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(last);
+                self.means_offset = 0;
+            }
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = last,
+            KeyCode::PageDown => {
+                self.means_offset = (self.means_offset + 10).min(features.saturating_sub(1))
+            }
+            KeyCode::PageUp => self.means_offset = self.means_offset.saturating_sub(10),
+            _ => {}
+        }
+        true
+    }
+}
+
+pub(crate) fn render_clusters(f: &mut Frame, view: &ClusterView) {
+    let report = view.report;
+    let summary = summary_lines(report);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(summary.len() as u16 + 2),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(f.area());
+
+    let summary: Vec<Line> = summary
+        .into_iter()
+        .map(|l| Line::from(Span::styled(l, Style::default().fg(TEXT_SECONDARY))))
+        .collect();
+    f.render_widget(
+        Paragraph::new(summary).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_ACCENT))
+                .title(" Clusters "),
+        ),
+        chunks[0],
+    );
+
+    let middle = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(34), Constraint::Min(0)])
+        .split(chunks[1]);
+
+    // Keep the selection in view.
+    let visible = middle[0].height.saturating_sub(3) as usize;
+    let skip = (view.selected + 1).saturating_sub(visible);
+    let rows: Vec<Row> = report
+        .clusters
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(visible)
+        .map(|(i, c)| {
+            let style = if i == view.selected {
+                Style::default()
+                    .fg(TEXT_ACCENT)
+                    .add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().fg(TEXT_PRIMARY)
+            };
+            Row::new(vec![
+                Cell::from(c.label.to_string()),
+                Cell::from(c.size.to_string()),
+                Cell::from(c.silhouette.map_or("-".to_string(), |s| format!("{s:.3}"))),
+            ])
+            .style(style)
+        })
+        .collect();
+    let list = Table::new(
+        rows,
+        [
+            Constraint::Length(9),
+            Constraint::Length(10),
+            Constraint::Length(10),
+        ],
+    )
+    .header(
+        Row::new(["cluster", "size", "silh."])
+            .style(Style::default().fg(HEADER_FG).add_modifier(Modifier::BOLD)),
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_PRIMARY))
+            .title(" Clusters "),
+    )
+    .column_spacing(1);
+    f.render_widget(list, middle[0]);
+
+    let detail_block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_PRIMARY));
+    let Some(cluster) = report.clusters.get(view.selected) else {
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                "no labelled rows",
+                Style::default().fg(TEXT_SECONDARY),
+            ))
+            .block(detail_block.title(" Cluster ")),
+            middle[1],
+        );
+        return;
+    };
+    let mut lines: Vec<Line> = cluster_lines(report, cluster)
+        .into_iter()
+        .map(|l| Line::from(Span::styled(l, Style::default().fg(TEXT_PRIMARY))))
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "feature means:",
+        Style::default().fg(HEADER_FG),
+    )));
+    lines.extend(
+        cluster
+            .means
+            .iter()
+            .enumerate()
+            .skip(view.means_offset)
+            .map(|(i, m)| {
+                Line::from(Span::styled(
+                    format!("  col_{i:<6} {m:.6}"),
+                    Style::default().fg(TEXT_SECONDARY),
+                ))
+            }),
+    );
+    f.render_widget(
+        Paragraph::new(lines).block(detail_block.title(format!(" Cluster {} ", cluster.label))),
+        middle[1],
+    );
+
+    let status = " ↑↓ select cluster | PgUp/PgDn scroll means | q quit ";
+    f.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_ACCENT))
+            .title(Span::styled(status, Style::default().fg(TEXT_ACCENT))),
+        chunks[2],
+    );
+}
+
+/// Full-screen cluster view of `report` until the user quits.
+pub(crate) fn display_clusters_interactive(report: &ClusterReport) -> Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let mut view = ClusterView::new(report);
+    run_view(
+        &mut terminal,
+        &mut view,
+        poll_event,
+        render_clusters,
+        ClusterView::handle_key,
+    )?;
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functions::clusters::ClusterStats;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn selecting_a_cluster_shows_its_detail() {
+        let cluster = |label, size, means: Vec<f64>| ClusterStats {
+            label,
+            size,
+            means,
+            within_distance: 0.5,
+            nearest: Some((if label == 1 { 2 } else { 1 }, 4.0)),
+            silhouette: Some(0.75),
+        };
+        let report = ClusterReport {
+            rows: 30,
+            features: 2,
+            skipped_rows: 0,
+            clusters: vec![
+                cluster(1, 10, vec![0.0, 1.0]),
+                cluster(2, 20, vec![4.0, 1.0]),
+            ],
+            min_centroid_distance: Some((1, 2, 4.0)),
+            silhouette: Some(0.75),
+            sample_size: 30,
+        };
+        let mut view = ClusterView::new(&report);
+        assert!(view.handle_key(KeyCode::Down));
+        assert!(view.handle_key(KeyCode::Down));
+
+        let mut terminal = Terminal::new(TestBackend::new(90, 20)).unwrap();
+        terminal.draw(|f| render_clusters(f, &view)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|line| line.iter().map(|c| c.symbol()).collect::<String>() + "\n")
+            .collect();
+        assert!(text.contains(" Cluster 2 "), "{text}");
+        assert!(text.contains("size: 20 (66.7% of rows)"), "{text}");
+        assert!(
+            text.contains("nearest centroid: cluster 1 at 4.000000"),
+            "{text}"
+        );
+        assert!(text.contains("col_0      4.000000"), "{text}");
+        assert!(!view.handle_key(KeyCode::Char('q')));
+    }
+}
@@ -0,0 +1,327 @@
+//! Fuzzy column picker for wide feature tables (`|` key). **Ctrl-f** cycles
+//! the highlighted integer column's display format (see [`value_format`]).
+//!
+//! [`value_format`]: crate::display::value_format
+
+use arrow::array::Array;
+use arrow::compute::kernels::aggregate::{max, min};
+use arrow::datatypes::{DataType, Float64Type};
+use arrow_array::{ArrayRef, RecordBatch};
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::display::display::FEATURE_NAME_KEY;
+use crate::display::display_metadata::centered;
+use crate::display::index_base::index_base;
+use crate::display::value_format::{ValueFormat, ValueFormatter};
+use crate::display::*;
+
+/// One pickable feature column.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PickerEntry {
+    /// Position in the feature list (what `col_offset` counts)
+    pub feature_idx: usize,
+    pub name: String,
+    /// Original feature name from metadata, when the writer recorded one
+    pub label: Option<String>,
+    /// Min/max of the loaded rows, for numeric columns
+    pub range: Option<(f64, f64)>,
+}
+
+/// What the viewer should do after a key press in the picker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PickerAction {
+    Stay,
+    Close,
+    /// Scroll so this feature is leftmost; `pin` keeps it there
+    Jump {
+        feature_idx: usize,
+        pin: bool,
+    },
+}
+
+pub(crate) struct ColumnPicker {
+    entries: Vec<PickerEntry>,
+    query: String,
+    selected: usize,
+}
+
+impl ColumnPicker {
+    /// Picker over the feature columns `feature_cols` of `batch`.
+    pub(crate) fn new(batch: &RecordBatch, feature_cols: &[usize]) -> Self {
+        let schema = batch.schema();
+        let listed = schema_feature_names(schema.metadata());
+        let entries = feature_cols
+            .iter()
+            .enumerate()
+            .map(|(feature_idx, &col_idx)| {
+                let field = schema.field(col_idx);
+                let label = field
+                    .metadata()
+                    .get(FEATURE_NAME_KEY)
+                    .cloned()
+                    .or_else(|| listed.as_ref()?.get(feature_idx).cloned());
+                PickerEntry {
+                    feature_idx,
+                    name: field.name().clone(),
+                    label,
+                    range: column_range(batch.column(col_idx)),
+                }
+            })
+            .collect();
+        Self {
+            entries,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Entries matching the query, best match first. Each entry is matched
+    /// on `"<index> <name> <label>"`, the index as shown.
+    pub(crate) fn matches(&self) -> Vec<&PickerEntry> {
+        if self.query.is_empty() {
+            return self.entries.iter().collect();
+        }
+        let base = index_base();
+        let mut scored: Vec<(i32, &PickerEntry)> = self
+            .entries
+            .iter()
+            .filter_map(|e| {
+                let haystack = format!(
+                    "{} {} {}",
+                    base.show(e.feature_idx),
+                    e.name,
+                    e.label.as_deref().unwrap_or("")
+                );
+                fuzzy_score(&self.query, &haystack).map(|s| (s, e))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.feature_idx.cmp(&b.1.feature_idx)));
+        scored.into_iter().map(|(_, e)| e).collect()
+    }
+
+    /// Feature index of the highlighted entry.
+    pub(crate) fn selected_feature(&self) -> Option<usize> {
+        self.matches().get(self.selected).map(|e| e.feature_idx)
+    }
+
+    /// Apply a key press. Printable keys edit the query; Enter jumps to the
+    /// selected column and Tab jumps and pins it.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> PickerAction {
+        match code {
+            KeyCode::Esc => return PickerAction::Close,
+            KeyCode::Enter | KeyCode::Tab => {
+                return match self.matches().get(self.selected) {
+                    Some(e) => PickerAction::Jump {
+                        feature_idx: e.feature_idx,
+                        pin: code == KeyCode::Tab,
+                    },
+                    None => PickerAction::Stay,
+                };
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected += 1,
+            KeyCode::PageUp => self.selected = self.selected.saturating_sub(10),
+            KeyCode::PageDown => self.selected += 10,
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            _ => {}
+        }
+        self.selected = self.selected.min(self.matches().len().saturating_sub(1));
+        PickerAction::Stay
+    }
+}
+
+/// Subsequence match of `query` in `text` (case-insensitive). Consecutive
+/// characters and matches at word starts score higher; `None` if some query
+/// character is missing.
+pub(crate) fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut prev: Option<usize> = None;
+    for q in query.to_lowercase().chars() {
+        let found = (pos..text.len()).find(|&i| text[i] == q)?;
+        score += 1;
+        if prev.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || matches!(text[found - 1], ' ' | '_' | '-' | '.') {
+            score += 3;
+        }
+        prev = Some(found);
+        pos = found + 1;
+    }
+    // Prefer shorter names among equal matches.
+    Some(score * 100 - text.len() as i32)
+}
+
+/// Original feature names listed in schema metadata `feature_names`, either
+/// as a JSON array or a comma-separated list.
+fn schema_feature_names(md: &std::collections::HashMap<String, String>) -> Option<Vec<String>> {
+    let raw = md.get("feature_names")?;
+    if let Ok(names) = serde_json::from_str::<Vec<String>>(raw) {
+        return Some(names);
+    }
+    Some(raw.split(',').map(|s| s.trim().to_string()).collect())
+}
+
+fn column_range(col: &ArrayRef) -> Option<(f64, f64)> {
+    if !col.data_type().is_numeric() || col.null_count() == col.len() {
+        return None;
+    }
+    let values = arrow::compute::cast(col, &DataType::Float64).ok()?;
+    let values = values
+        .as_any()
+        .downcast_ref::<arrow_array::PrimitiveArray<Float64Type>>()?;
+    Some((min(values)?, max(values)?))
+}
+
+/// Draw the picker over the current frame; columns with a display format
+/// in `formats` show it.
+pub(crate) fn render_column_picker(
+    f: &mut Frame,
+    picker: &mut ColumnPicker,
+    formats: &ValueFormatter,
+) {
+    let area = centered(f.area(), 80);
+    let inner_height = area.height.saturating_sub(2) as usize;
+
+    let matches = picker.matches();
+    let first = picker
+        .selected
+        .saturating_sub(inner_height.saturating_sub(1));
+    let mut lines: Vec<Line> = Vec::new();
+    for (i, entry) in matches.iter().enumerate().skip(first).take(inner_height) {
+        let style = if i == picker.selected {
+            Style::default()
+                .fg(HEADER_FG)
+                .bg(HEADER_BG)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(TEXT_PRIMARY)
+        };
+        let mut spans = vec![
+            Span::styled(
+                format!("{:>5} ", index_base().show(entry.feature_idx)),
+                Style::default().fg(TEXT_SECONDARY),
+            ),
+            Span::styled(entry.name.clone(), style),
+        ];
+        if let Some(label) = &entry.label {
+            spans.push(Span::styled(format!("  {label}"), style));
+        }
+        if let Some((lo, hi)) = entry.range {
+            spans.push(Span::styled(
+                format!("  [{lo:.4}, {hi:.4}]"),
+                Style::default().fg(TEXT_ACCENT),
+            ));
+        }
+        let format = formats.get(&entry.name);
+        if format != ValueFormat::Raw {
+            spans.push(Span::styled(
+                format!("  as {format}"),
+                Style::default().fg(TEXT_SECONDARY),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    if matches.is_empty() {
+        lines.push(Line::from("no columns match the search"));
+    }
+
+    let title = format!(
+        " Columns /{}▏ ({} of {}) ",
+        picker.query,
+        matches.len(),
+        picker.entries.len()
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(title)
+        .title_bottom(
+             " type to search | ↑↓ select | Enter jump | Tab jump+pin | Ctrl-x mark | Ctrl-f format | Esc close ",
+        );
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{Field, Schema};
+    use arrow_array::Float64Array;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn wide_batch() -> RecordBatch {
+        let fields: Vec<Field> = (0..4)
+            .map(|i| Field::new(format!("col_{i}"), DataType::Float64, true))
+            .collect();
+        let schema = Schema::new(fields).with_metadata(HashMap::from([(
+            "feature_names".to_string(),
+            r#"["height","weight","heart_rate","age"]"#.to_string(),
+        )]));
+        let cols = (0..4)
+            .map(|i| Arc::new(Float64Array::from(vec![i as f64, i as f64 * 10.0])) as ArrayRef)
+            .collect();
+        RecordBatch::try_new(Arc::new(schema), cols).unwrap()
+    }
+
+    #[test]
+    fn fuzzy_matching_prefers_tight_matches() {
+        assert!(fuzzy_score("hr", "heart_rate").is_some());
+        assert!(fuzzy_score("xyz", "heart_rate").is_none());
+        assert!(
+            fuzzy_score("hei", "height").unwrap() > fuzzy_score("hei", "heart_rate").unwrap_or(0)
+        );
+    }
+
+    #[test]
+    fn picker_lists_labels_ranges_and_jumps() {
+        let batch = wide_batch();
+        let mut picker = ColumnPicker::new(&batch, &[0, 1, 2, 3]);
+        assert_eq!(picker.entries[2].label.as_deref(), Some("heart_rate"));
+        assert_eq!(picker.entries[3].range, Some((3.0, 30.0)));
+
+        for c in "weig".chars() {
+            assert_eq!(picker.handle_key(KeyCode::Char(c)), PickerAction::Stay);
+        }
+        assert_eq!(picker.matches()[0].name, "col_1");
+        assert_eq!(
+            picker.handle_key(KeyCode::Enter),
+            PickerAction::Jump {
+                feature_idx: 1,
+                pin: false
+            }
+        );
+
+        // Searching by index works too; Tab pins.
+        picker.handle_key(KeyCode::Backspace);
+        for _ in 0..4 {
+            picker.handle_key(KeyCode::Backspace);
+        }
+        picker.handle_key(KeyCode::Char('3'));
+        assert_eq!(
+            picker.handle_key(KeyCode::Tab),
+            PickerAction::Jump {
+                feature_idx: 3,
+                pin: true
+            }
+        );
+        assert_eq!(picker.handle_key(KeyCode::Esc), PickerAction::Close);
+    }
+}
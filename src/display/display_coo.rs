@@ -1,15 +1,24 @@
-use anyhow::{Context, Result, anyhow};
-use arrow_array::{Float64Array, RecordBatch, UInt32Array};
+use anyhow::Result;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, UInt32Type};
+use arrow_array::{Array, Float64Array, RecordBatch, UInt32Array};
+use log::debug;
 use ratatui::text::Span;
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::Line,
     widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
 };
+use std::ops::Range;
 
+use crate::display::index_base::index_base;
 use crate::display::*;
+use crate::functions::functions::canonical_coo;
+use crate::functions::laplacian::LaplacianReport;
+use crate::functions::reorder::Reordering;
+use crate::functions::sparse_stats::Band;
 
 /// Render one frame for a COO (row, col, value) sparse matrix.
 ///
@@ -23,15 +32,31 @@ use crate::display::*;
 ///   └───────────────────────────────────────────────┘
 ///
 /// `triple_offset` controls vertical scrolling in the triples table
-/// and the visible row band in the sparsity map.
+/// and the visible row band in the sparsity map, which labels its rows in a
+/// left gutter and its columns on a ruler above. With `band` the map shades
+/// the band ±bandwidth around the diagonal and highlights the entries on its
+/// edge. With a `reordering` the whole view shows the matrix renumbered by
+/// it and the Structure section compares the band before and after. With a
+/// `laplacian` report the Structure section gains Laplacian and Spectrum
+/// panels.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn render_coo_ui(
     f: &mut Frame,
     batch: &RecordBatch,
     triple_offset: usize,
     col_offset: usize,
+    band: bool,
+    reordering: Option<&Reordering>,
+    laplacian: Option<&LaplacianReport>,
 ) {
-    // Extract COO components and basic stats.
-    let coo = match CooView::from_batch(batch) {
+    // Extract COO components and basic stats, under the reordering if any.
+    let view = match reordering {
+        Some(reordering) => reordering
+            .apply(batch)
+            .and_then(|b| CooView::from_batch(&b)),
+        None => CooView::from_batch(batch),
+    };
+    let coo = match view {
         Ok(c) => c,
         Err(e) => {
             let p = Paragraph::new(Span::styled(
@@ -49,8 +74,17 @@ pub(crate) fn render_coo_ui(
         }
     };
 
-    let nnz = coo.nnz;
+    // Duplicate coordinates count once towards density and structure.
+    let aggregate = coo.aggregate();
+    let nnz = aggregate.entries.len();
     let (n_rows, n_cols) = (coo.n_rows, coo.n_cols);
+    let matrix_band = Band::of_entries(
+        aggregate
+            .entries
+            .iter()
+            .map(|&(r, c, _)| (r as usize, c as usize)),
+        n_rows,
+    );
 
     // Top (metadata), middle (triples + sparsity), bottom (diagonals/connectivity).
     let outer = Layout::default()
@@ -58,12 +92,13 @@ pub(crate) fn render_coo_ui(
         .constraints([
             Constraint::Length(3), // Metadata header
             Constraint::Min(0),    // Middle content (takes remaining space)
-            Constraint::Length(6), // Structure footer (2 border + 6 content lines)
+            // Structure footer (2 border + 5 content lines, 7 with Laplacian panels)
+            Constraint::Length(if laplacian.is_some() { 9 } else { 7 }),
         ])
         .split(f.area());
 
     // --- Top: metadata line ---------------------------------------------------
-    let meta_text = format!(
+    let mut meta_text = format!(
         "rows: {}  cols: {}  nnz: {}  density: {:.6}",
         n_rows,
         n_cols,
@@ -74,6 +109,12 @@ pub(crate) fn render_coo_ui(
             (nnz as f64) / ((n_rows * n_cols) as f64)
         }
     );
+    let duplicates = coo.nnz - nnz;
+    if duplicates > 0 {
+        meta_text.push_str(&format!(
+            "  {duplicates} duplicate coordinates coalesced (values summed)"
+        ));
+    }
 
     let meta = Paragraph::new(Span::styled(meta_text, Style::default().fg(TEXT_SECONDARY))).block(
         Block::default()
@@ -89,23 +130,54 @@ pub(crate) fn render_coo_ui(
         .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
         .split(outer[1]);
 
-    render_triples_table(f, &coo, triple_offset, middle[0]);
-    render_sparsity_map(f, &coo, middle[1], triple_offset, col_offset);
+    render_triples_table(f, &coo, &aggregate, triple_offset, middle[0]);
+    let overlay = band.then_some(&matrix_band);
+    render_sparsity_map(
+        f,
+        &coo,
+        overlay,
+        reordering.is_some(),
+        middle[1],
+        triple_offset,
+        col_offset,
+    );
 
     // --- Bottom: diagonals + connectivity summary ---------------------------
-    let diag_summary = summarize_diagonals(&coo, 6);
-    let conn_summary = summarize_connectivity(&coo, 6);
+    let diag_summary = summarize_diagonals(&aggregate, 6);
+    let conn_summary = summarize_connectivity(&aggregate, coo.n_rows, 6);
 
-    let summary_text = vec![
+    let mut summary_text = vec![
         Line::from(diag_summary),
+        Line::from(format!(
+            "Bandwidth: {} (lower {}, upper {})  profile: {}",
+            matrix_band.bandwidth,
+            matrix_band.lower_bandwidth,
+            matrix_band.upper_bandwidth,
+            matrix_band.profile
+        )),
         Line::from(conn_summary),
         Line::from(Span::styled(
-            "Press 'v' for graph connectivity view",
+            "Press 'v' for graph view, 'B' to shade the band, 'r' for RCM order",
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::ITALIC),
         )),
     ];
+    if let Some(reordering) = reordering {
+        summary_text.insert(
+            2,
+            Line::from(Span::styled(
+                format!(
+                    "RCM order: bandwidth {} → {}, profile {} → {}",
+                    reordering.before.bandwidth,
+                    reordering.after.bandwidth,
+                    reordering.before.profile,
+                    reordering.after.profile
+                ),
+                Style::default().fg(TEXT_WARNING),
+            )),
+        );
+    }
 
     let summary = Paragraph::new(summary_text)
         .style(Style::default().fg(TEXT_ACCENT))
@@ -117,78 +189,60 @@ pub(crate) fn render_coo_ui(
         )
         .wrap(Wrap { trim: true });
 
-    f.render_widget(summary, outer[2]);
+    let Some(report) = laplacian else {
+        f.render_widget(summary, outer[2]);
+        return;
+    };
+    let footer = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(40),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+        ])
+        .split(outer[2]);
+    f.render_widget(summary, footer[0]);
+    render_report_panel(f, " Laplacian ", report.structure_lines(), footer[1]);
+    render_report_panel(f, " Spectrum ", report.spectrum_lines(), footer[2]);
+}
+
+fn render_report_panel(f: &mut Frame, title: &str, lines: Vec<String>, area: Rect) {
+    let lines: Vec<Line> = lines.into_iter().map(Line::from).collect();
+    let panel = Paragraph::new(lines)
+        .style(Style::default().fg(TEXT_PRIMARY))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_PRIMARY))
+                .title(title.to_string()),
+        )
+        .wrap(Wrap { trim: true });
+    f.render_widget(panel, area);
 }
 
 // ======================= Internal COO helpers ===============================
 
-struct CooView<'a> {
-    row: &'a UInt32Array,
-    col: &'a UInt32Array,
-    val: &'a Float64Array,
+/// The triples of a SparseCoo batch with the indices as UInt32 and the
+/// values as Float64, whatever integer / float types the batch stores.
+struct CooView {
+    row: UInt32Array,
+    col: UInt32Array,
+    val: Float64Array,
     n_rows: usize,
     n_cols: usize,
     nnz: usize,
 }
 
-impl<'a> CooView<'a> {
-    fn from_batch(batch: &'a RecordBatch) -> Result<Self> {
-        if batch.num_columns() < 3 {
-            return Err(anyhow!(
-                "expected at least 3 columns (row, col, value), got {}",
-                batch.num_columns()
-            ));
-        }
-
-        // Locate row/col/value by name, regardless of order.
+impl CooView {
+    fn from_batch(batch: &RecordBatch) -> Result<Self> {
+        // row, col, value first, cast; fails on negative or oversized indices.
+        let canonical = canonical_coo(batch)?;
         let schema = batch.schema();
-        let mut row_idx = None;
-        let mut col_idx = None;
-        let mut val_idx = None;
-
-        for (i, f) in schema.fields().iter().enumerate() {
-            match f.name().as_str() {
-                "row" => row_idx = Some(i),
-                "col" => col_idx = Some(i),
-                "value" => val_idx = Some(i),
-                _ => {}
-            }
-        }
-
-        let (row_i, col_i, val_i) = match (row_idx, col_idx, val_idx) {
-            (Some(r), Some(c), Some(v)) => (r, c, v),
-            _ => {
-                return Err(anyhow!(
-                    "COO schema must contain columns named 'row', 'col', and 'value'"
-                ));
-            }
-        };
-
-        let row = batch
-            .column(row_i)
-            .as_any()
-            .downcast_ref::<UInt32Array>()
-            .context("row must be UInt32")?;
-        let col = batch
-            .column(col_i)
-            .as_any()
-            .downcast_ref::<UInt32Array>()
-            .context("col must be UInt32")?;
-        let val = batch
-            .column(val_i)
-            .as_any()
-            .downcast_ref::<Float64Array>()
-            .context("value must be Float64")?;
+        let row = canonical.column(0).as_primitive::<UInt32Type>().clone();
+        let col = canonical.column(1).as_primitive::<UInt32Type>().clone();
+        let val = canonical.column(2).as_primitive::<Float64Type>().clone();
 
         let nnz = row.len();
-        if col.len() != nnz || val.len() != nnz {
-            return Err(anyhow!(
-                "row/col/value length mismatch: row={}, col={}, value={}",
-                nnz,
-                col.len(),
-                val.len()
-            ));
-        }
 
         // Determine matrix dimensions from schema metadata or infer from max index.
         let md = schema.metadata();
@@ -203,7 +257,10 @@ impl<'a> CooView<'a> {
         }
 
         let (nr, nc) = match (n_rows, n_cols) {
-            (Some(r), Some(c)) => (r, c),
+            (Some(r), Some(c)) => {
+                debug!("CooView: dimensions {r}×{c} from schema metadata");
+                (r, c)
+            }
             _ => {
                 // Fallback: infer as 1 + max(row), 1 + max(col)
                 let mut max_r = 0u32;
@@ -218,7 +275,12 @@ impl<'a> CooView<'a> {
                         max_c = cv;
                     }
                 }
-                (max_r as usize + 1, max_c as usize + 1)
+                let (r, c) = (max_r as usize + 1, max_c as usize + 1);
+                debug!(
+                    "CooView: no rows/cols schema metadata, inferred {r}×{c} from max indices; \
+                     trailing all-zero rows/cols are not counted"
+                );
+                (r, c)
             }
         };
 
@@ -231,13 +293,54 @@ impl<'a> CooView<'a> {
             nnz,
         })
     }
+
+    /// The triples with equal coordinates summed into one entry.
+    fn aggregate(&self) -> CooAggregate {
+        let mut order: Vec<usize> = (0..self.nnz).collect();
+        order.sort_by_key(|&i| (self.row.value(i), self.col.value(i)));
+
+        let mut entries = Vec::with_capacity(self.nnz);
+        let mut multiplicity = vec![1; self.nnz];
+        for group in order.chunk_by(|&a, &b| {
+            (self.row.value(a), self.col.value(a)) == (self.row.value(b), self.col.value(b))
+        }) {
+            let i = group[0];
+            let value = group
+                .iter()
+                .filter(|&&j| !self.val.is_null(j))
+                .map(|&j| self.val.value(j))
+                .reduce(|a, b| a + b);
+            entries.push((self.row.value(i), self.col.value(i), value));
+            if group.len() > 1 {
+                for &j in group {
+                    multiplicity[j] = group.len();
+                }
+            }
+        }
+        CooAggregate {
+            entries,
+            multiplicity,
+        }
+    }
+}
+
+/// Coalesced coordinates of a [`CooView`].
+struct CooAggregate {
+    /// (row, col, summed value) in row-major order; the value is null when
+    /// every triple at the coordinate is
+    entries: Vec<(u32, u32, Option<f64>)>,
+    /// How many triples share each raw triple's coordinate
+    multiplicity: Vec<usize>,
 }
 
 // ========================= Triples table panel ==============================
 
-fn render_triples_table<'a>(
+/// The raw triples; values of triples sharing a coordinate are flagged
+/// `×k`.
+fn render_triples_table(
     f: &mut Frame,
-    coo: &CooView<'a>,
+    coo: &CooView,
+    aggregate: &CooAggregate,
     triple_offset: usize,
     area: ratatui::prelude::Rect,
 ) {
@@ -284,13 +387,22 @@ fn render_triples_table<'a>(
     .height(1);
 
     // Rows with alternating colors
+    let base = index_base();
     let mut rows_ui = Vec::with_capacity(end.saturating_sub(start));
     for i in start..end {
-        let r = coo.row.value(i);
-        let c = coo.col.value(i);
-        let v = coo.val.value(i);
+        let r = base.show(coo.row.value(i) as usize);
+        let c = base.show(coo.col.value(i) as usize);
+        let mut v = if coo.val.is_null(i) {
+            "NULL".to_string()
+        } else {
+            format!("{:.4}", coo.val.value(i))
+        };
+        let duplicated = aggregate.multiplicity[i] > 1;
+        if duplicated {
+            v.push_str(&format!(" ×{}", aggregate.multiplicity[i]));
+        }
 
-        let row_bg = if (i - start) % 2 == 0 {
+        let row_bg = if (i - start).is_multiple_of(2) {
             EVEN_ROW_BG
         } else {
             ODD_ROW_BG
@@ -305,7 +417,15 @@ fn render_triples_table<'a>(
             // ),
             Cell::from(format!("{r}")).style(Style::default().fg(TEXT_PRIMARY).bg(row_bg)),
             Cell::from(format!("{c}")).style(Style::default().fg(TEXT_PRIMARY).bg(row_bg)),
-            Cell::from(format!("{:.4}", v)).style(Style::default().fg(TEXT_PRIMARY).bg(row_bg)),
+            Cell::from(v).style(
+                Style::default()
+                    .fg(if duplicated {
+                        TEXT_WARNING
+                    } else {
+                        TEXT_PRIMARY
+                    })
+                    .bg(row_bg),
+            ),
         ];
         rows_ui.push(Row::new(cells).height(1));
     }
@@ -317,12 +437,11 @@ fn render_triples_table<'a>(
         Constraint::Length(14),
     ];
 
-    let title = format!(
-        " Triples [{}–{} of {}] ",
-        if coo.nnz == 0 { 0 } else { start },
-        end,
-        coo.nnz
-    );
+    let title = if start < end {
+        format!(" Triples [{} of {}] ", base.span(start..end), coo.nnz)
+    } else {
+        format!(" Triples [0 of {}] ", coo.nnz)
+    };
 
     let table = Table::new(rows_ui, widths)
         .header(header)
@@ -339,9 +458,73 @@ fn render_triples_table<'a>(
 
 // ========================= Sparsity map panel ===============================
 
-fn render_sparsity_map<'a>(
+/// Which cells of the `rows`×`cols` block hold a non-zero, one grid cell per
+/// matrix cell (no downsampling).
+fn sparsity_grid(coo: &CooView, rows: Range<usize>, cols: Range<usize>) -> Vec<Vec<bool>> {
+    let mut grid = vec![vec![false; cols.len()]; rows.len()];
+    for i in 0..coo.nnz {
+        let r = coo.row.value(i) as usize;
+        let c = coo.col.value(i) as usize;
+        if rows.contains(&r) && cols.contains(&c) {
+            grid[r - rows.start][c - cols.start] = true;
+        }
+    }
+    grid
+}
+
+/// [`CooView::from_batch`] and the sparsity grid of the top-left
+/// `rows`×`cols` block of `batch`, as the COO view builds them per frame.
+pub(crate) fn coo_sparsity_grid(
+    batch: &RecordBatch,
+    rows: usize,
+    cols: usize,
+) -> Result<Vec<Vec<bool>>> {
+    let coo = CooView::from_batch(batch)?;
+    Ok(sparsity_grid(
+        &coo,
+        0..rows.min(coo.n_rows),
+        0..cols.min(coo.n_cols),
+    ))
+}
+
+/// Map cells kept beside the row gutter; with less room the map drops it.
+const MIN_MAP_CELLS: usize = 4;
+/// Columns between the ticks of the column ruler.
+const RULER_STEP: usize = 10;
+
+/// One line over the map cells of `cols`: a `|` on every column whose shown
+/// index is a multiple of [`RULER_STEP`], replaced by the index where it
+/// fits before the next tick.
+fn column_ruler(cols: Range<usize>) -> String {
+    let base = index_base();
+    let mut ruler = vec![' '; cols.len()];
+    let ticks: Vec<usize> = cols
+        .clone()
+        .filter(|&c| base.show(c).is_multiple_of(RULER_STEP))
+        .collect();
+    for (k, &c) in ticks.iter().enumerate() {
+        let at = c - cols.start;
+        let label = base.show(c).to_string();
+        let room = ticks
+            .get(k + 1)
+            .map_or(cols.len(), |&next| next - cols.start)
+            - at;
+        if label.len() < room || (k + 1 == ticks.len() && label.len() <= room) {
+            for (i, ch) in label.chars().enumerate() {
+                ruler[at + i] = ch;
+            }
+        } else {
+            ruler[at] = '|';
+        }
+    }
+    ruler.into_iter().collect()
+}
+
+fn render_sparsity_map(
     f: &mut Frame,
-    coo: &CooView<'a>,
+    coo: &CooView,
+    band: Option<&Band>,
+    reordered: bool,
     area: ratatui::prelude::Rect,
     triple_offset: usize,
     col_offset: usize,
@@ -355,62 +538,77 @@ fn render_sparsity_map<'a>(
         return;
     }
 
-    // Determine visible row window
+    // The column ruler takes the top line when there is a second, and the
+    // row gutter the left columns when a few map cells still fit beside it;
+    // the cells keep one per matrix cell in what is left.
+    let base = index_base();
+    let ruler = inner_height > 1;
+    let map_height = inner_height - usize::from(ruler);
     let row_start = triple_offset.min(coo.n_rows.saturating_sub(1));
-    let row_end = (row_start + inner_height).min(coo.n_rows);
-    let visible_rows = row_end - row_start;
+    let row_end = (row_start + map_height).min(coo.n_rows);
+    let gutter = base.show(row_end - 1).to_string().len() + 1;
+    let gutter = if inner_width >= gutter + MIN_MAP_CELLS {
+        gutter
+    } else {
+        0
+    };
 
     // Determine visible column window with horizontal scrolling
     let col_start = col_offset.min(coo.n_cols.saturating_sub(1));
-    let col_end = (col_start + inner_width).min(coo.n_cols);
-    let visible_cols = col_end - col_start;
-
-    // Create grid with 1:1 mapping (no downsampling)
-    let mut grid = vec![vec![false; visible_cols]; visible_rows];
-
-    // Map each non-zero entry to the grid with 1:1 mapping
-    for i in 0..coo.nnz {
-        let r = coo.row.value(i) as usize;
-        let c = coo.col.value(i) as usize;
+    let col_end = (col_start + inner_width - gutter).min(coo.n_cols);
 
-        // Check if this entry is in the visible window
-        if r >= row_start && r < row_end && c >= col_start && c < col_end {
-            let gr = r - row_start;
-            let gc = c - col_start;
-            if gr < visible_rows && gc < visible_cols {
-                grid[gr][gc] = true;
-            }
-        }
-    }
+    let grid = sparsity_grid(coo, row_start..row_end, col_start..col_end);
 
-    // Build colored text with asterisks and dots
+    // Build colored text with asterisks and dots; the band overlay shades
+    // its cells and marks the entries on its edge.
     let mut lines = Vec::new();
-    for row in &grid {
+    if ruler {
+        lines.push(Line::from(Span::styled(
+            format!("{:gutter$}{}", "", column_ruler(col_start..col_end)),
+            Style::default().fg(TEXT_SECONDARY),
+        )));
+    }
+    for (gr, row) in grid.iter().enumerate() {
         let mut spans = Vec::new();
-        for &has_value in row {
-            if has_value {
-                spans.push(Span::styled(
-                    "*",
-                    Style::default()
-                        .fg(SPARSE_ASTERISK)
-                        .add_modifier(Modifier::BOLD),
-                ));
+        if gutter > 0 {
+            spans.push(Span::styled(
+                format!("{:>w$} ", base.show(row_start + gr), w = gutter - 1),
+                Style::default().fg(TEXT_SECONDARY),
+            ));
+        }
+        for (gc, &has_value) in row.iter().enumerate() {
+            let (r, c) = (row_start + gr, col_start + gc);
+            let mut style = if has_value {
+                Style::default()
+                    .fg(SPARSE_ASTERISK)
+                    .add_modifier(Modifier::BOLD)
             } else {
-                spans.push(Span::styled("·", Style::default().fg(SPARSE_DOT)));
+                Style::default().fg(SPARSE_DOT)
+            };
+            if let Some(band) = band.filter(|b| b.contains(r, c)) {
+                style = style.bg(SPARSE_BAND_BG);
+                if has_value && band.on_edge(r, c) {
+                    style = style.fg(TEXT_WARNING).add_modifier(Modifier::REVERSED);
+                }
             }
+            spans.push(Span::styled(if has_value { "*" } else { "·" }, style));
         }
         lines.push(Line::from(spans));
     }
 
-    let title = format!(
-        " Sparsity rows {}–{} of {}, cols {}–{} of {} (←→ to scroll cols) ",
-        row_start,
-        row_end.saturating_sub(1),
+    let mut title = format!(
+        " Sparsity rows {} of {}, cols {} of {} (←→ to scroll cols) ",
+        base.span(row_start..row_end),
         coo.n_rows,
-        col_start,
-        col_end.saturating_sub(1),
+        base.span(col_start..col_end),
         coo.n_cols
     );
+    if reordered {
+        title.push_str("RCM order ");
+    }
+    if let Some(band) = band {
+        title.push_str(&format!("band ±{} ", band.bandwidth));
+    }
 
     let para = Paragraph::new(lines).block(
         Block::default()
@@ -423,39 +621,40 @@ fn render_sparsity_map<'a>(
 
 // ===================== Diagonals / connectivity summary =====================
 
-fn summarize_diagonals(coo: &CooView<'_>, max_items: usize) -> String {
-    let mut entries = Vec::new();
-    for i in 0..coo.nnz {
-        let r = coo.row.value(i) as usize;
-        let c = coo.col.value(i) as usize;
-        if r == c {
-            entries.push((r, coo.val.value(i)));
-        }
-    }
+fn summarize_diagonals(aggregate: &CooAggregate, max_items: usize) -> String {
+    let mut entries: Vec<_> = aggregate
+        .entries
+        .iter()
+        .filter(|(r, c, _)| r == c)
+        .map(|&(r, _, v)| (r, v))
+        .collect();
 
     if entries.is_empty() {
         return "Diagonals: no non-zero entries on main diagonal".to_string();
     }
 
-    entries.sort_by_key(|(r, _)| *r);
     entries.truncate(max_items);
 
     let mut s = String::from("Diagonals (row == col):");
     for (r, v) in entries {
-        s.push_str(&format!("  ({r}, {r}): {:.4}", v));
+        let r = index_base().show(r as usize);
+        match v {
+            Some(v) => s.push_str(&format!("  ({r}, {r}): {:.4}", v)),
+            None => s.push_str(&format!("  ({r}, {r}): NULL")),
+        }
     }
     s
 }
 
-fn summarize_connectivity(coo: &CooView<'_>, max_rows: usize) -> String {
-    if coo.n_rows == 0 {
+fn summarize_connectivity(aggregate: &CooAggregate, n_rows: usize, max_rows: usize) -> String {
+    if n_rows == 0 {
         return "Connectivity: empty matrix".to_string();
     }
 
-    let mut counts = vec![0usize; coo.n_rows];
-    for i in 0..coo.nnz {
-        let r = coo.row.value(i) as usize;
-        if r < coo.n_rows {
+    let mut counts = vec![0usize; n_rows];
+    for &(r, _, _) in &aggregate.entries {
+        let r = r as usize;
+        if r < n_rows {
             counts[r] += 1;
         }
     }
@@ -477,7 +676,338 @@ fn summarize_connectivity(coo: &CooView<'_>, max_rows: usize) -> String {
 
     let mut s = String::from("Most connected rows (by nnz):");
     for (r, c) in rows {
+        let r = index_base().show(r);
         s.push_str(&format!("  row {r}: {c} connections"));
     }
     s
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::csr_to_coo_batch;
+    use crate::display::screenshot::buffer_text;
+    use crate::functions::laplacian::LaplacianMode;
+    use ratatui::{Terminal, backend::TestBackend};
+    use sprs::TriMat;
+    use std::sync::Arc;
+
+    fn render_to_text(batch: &RecordBatch, width: u16, height: u16) -> String {
+        render_with_report(batch, None, width, height)
+    }
+
+    fn render_with_report(
+        batch: &RecordBatch,
+        report: Option<&LaplacianReport>,
+        width: u16,
+        height: u16,
+    ) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|f| render_coo_ui(f, batch, 0, 0, false, None, report))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|c| c.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn dimensions_come_from_metadata_when_last_row_is_empty() {
+        // 5×5 matrix whose last row and column are entirely zero.
+        let mut tri = TriMat::<f64>::new((5, 5));
+        tri.add_triplet(0, 1, 1.0);
+        tri.add_triplet(1, 0, 1.0);
+        tri.add_triplet(3, 3, 2.0);
+        let batch = csr_to_coo_batch(&tri.to_csr()).unwrap();
+
+        let coo = CooView::from_batch(&batch).unwrap();
+        assert_eq!((coo.n_rows, coo.n_cols, coo.nnz), (5, 5, 3));
+        let text = render_to_text(&batch, 100, 20);
+        assert!(text.contains("rows: 5  cols: 5  nnz: 3"), "{text}");
+
+        // Without metadata the trailing row/column is lost.
+        let bare = RecordBatch::try_new(
+            Arc::new(
+                batch
+                    .schema()
+                    .as_ref()
+                    .clone()
+                    .with_metadata(Default::default()),
+            ),
+            batch.columns().to_vec(),
+        )
+        .unwrap();
+        let coo = CooView::from_batch(&bare).unwrap();
+        assert_eq!((coo.n_rows, coo.n_cols), (4, 4));
+    }
+
+    #[test]
+    fn indices_and_values_are_cast_from_wider_and_signed_types() {
+        use arrow::compute::cast;
+        use arrow::datatypes::{DataType, Field, Schema};
+        use arrow_array::{ArrayRef, Int32Array};
+
+        let mut tri = TriMat::<f64>::new((4, 6));
+        tri.add_triplet(0, 5, 1.5);
+        tri.add_triplet(3, 2, -0.25);
+        let batch = csr_to_coo_batch(&tri.to_csr()).unwrap();
+        let expected = CooView::from_batch(&batch).unwrap();
+
+        for index in [
+            DataType::UInt32,
+            DataType::UInt64,
+            DataType::Int32,
+            DataType::Int64,
+        ] {
+            for value in [DataType::Float64, DataType::Float32] {
+                let schema = Schema::new(vec![
+                    Field::new("row", index.clone(), false),
+                    Field::new("col", index.clone(), false),
+                    Field::new("value", value.clone(), false),
+                ])
+                .with_metadata(batch.schema().metadata().clone());
+                let cols: Vec<ArrayRef> = vec![
+                    cast(batch.column(0), &index).unwrap(),
+                    cast(batch.column(1), &index).unwrap(),
+                    cast(batch.column(2), &value).unwrap(),
+                ];
+                let typed = RecordBatch::try_new(Arc::new(schema), cols).unwrap();
+                let coo = CooView::from_batch(&typed).unwrap();
+                assert_eq!(coo.row, expected.row, "{index} / {value}");
+                assert_eq!(coo.col, expected.col, "{index} / {value}");
+                assert_eq!(coo.val, expected.val, "{index} / {value}");
+                assert_eq!((coo.n_rows, coo.n_cols), (4, 6));
+            }
+        }
+
+        let negative = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("row", DataType::Int32, false),
+                Field::new("col", DataType::Int32, false),
+                Field::new("value", DataType::Float64, false),
+            ])),
+            vec![
+                Arc::new(Int32Array::from(vec![1])),
+                Arc::new(Int32Array::from(vec![-7])),
+                Arc::new(Float64Array::from(vec![1.0])),
+            ],
+        )
+        .unwrap();
+        let err = CooView::from_batch(&negative).err().unwrap();
+        assert!(format!("{err:#}").contains("-7"), "{err:#}");
+    }
+
+    #[test]
+    fn duplicate_coordinates_are_summed_and_flagged() {
+        use arrow::datatypes::{DataType, Field, Schema};
+
+        // (1, 1) three times, (0, 2) twice with one null, (2, 0) once.
+        let schema = Schema::new(vec![
+            Field::new("row", DataType::UInt32, false),
+            Field::new("col", DataType::UInt32, false),
+            Field::new("value", DataType::Float64, true),
+        ])
+        .with_metadata([("rows".into(), "3".into()), ("cols".into(), "3".into())].into());
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(UInt32Array::from(vec![1, 0, 1, 2, 0, 1])),
+                Arc::new(UInt32Array::from(vec![1, 2, 1, 0, 2, 1])),
+                Arc::new(Float64Array::from(vec![
+                    Some(1.0),
+                    Some(0.5),
+                    Some(2.0),
+                    Some(4.0),
+                    None,
+                    Some(0.25),
+                ])),
+            ],
+        )
+        .unwrap();
+
+        let coo = CooView::from_batch(&batch).unwrap();
+        let aggregate = coo.aggregate();
+        assert_eq!(
+            aggregate.entries,
+            [(0, 2, Some(0.5)), (1, 1, Some(3.25)), (2, 0, Some(4.0))]
+        );
+        assert_eq!(aggregate.multiplicity, [3, 2, 3, 1, 2, 3]);
+
+        let text = render_to_text(&batch, 120, 24);
+        assert!(
+            text.contains("nnz: 3  density: 0.333333  3 duplicate coordinates coalesced"),
+            "{text}"
+        );
+        assert!(text.contains("(1, 1): 3.2500"), "{text}");
+        assert!(text.contains("row 0: 1 connections"), "{text}");
+        assert!(text.contains("1.0000 ×3"), "{text}");
+        assert!(text.contains("NULL ×2"), "{text}");
+        assert!(text.contains("4.0000 "), "{text}");
+        assert!(!text.contains("4.0000 ×"), "{text}");
+    }
+
+    #[test]
+    fn band_overlay_shades_the_tridiagonal_band() {
+        let mut tri = TriMat::<f64>::new((6, 6));
+        for i in 0..6 {
+            tri.add_triplet(i, i, 2.0);
+            if i + 1 < 6 {
+                tri.add_triplet(i, i + 1, -1.0);
+                tri.add_triplet(i + 1, i, -1.0);
+            }
+        }
+        let batch = csr_to_coo_batch(&tri.to_csr()).unwrap();
+
+        let text = render_to_text(&batch, 120, 24);
+        assert!(
+            text.contains("Bandwidth: 1 (lower 1, upper 1)  profile: 16"),
+            "{text}"
+        );
+        assert!(!text.contains("band ±"), "{text}");
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 24)).unwrap();
+        terminal
+            .draw(|f| render_coo_ui(f, &batch, 0, 0, true, None, None))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let row_text = |y: u16| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        };
+        let title_y = (0..buffer.area.height)
+            .find(|&y| row_text(y).contains("Sparsity rows"))
+            .unwrap();
+        assert!(row_text(title_y).contains("band ±1"));
+        // First map cell: x of the "*" on the top row of the map, under
+        // the column ruler
+        let top = row_text(title_y + 2);
+        let map_x = top.chars().position(|c| c == '*').unwrap() as u16;
+        let cell = |r: u16, c: u16| &buffer[(map_x + c, title_y + 2 + r)];
+        // Inside the band: shaded; the off-diagonals set the bandwidth.
+        assert_eq!(cell(0, 0).bg, SPARSE_BAND_BG);
+        assert_eq!(cell(0, 1).fg, TEXT_WARNING);
+        assert_eq!(cell(0, 0).fg, SPARSE_ASTERISK);
+        // Outside the band: plain.
+        assert_eq!(cell(0, 2).symbol(), "·");
+        assert_ne!(cell(0, 2).bg, SPARSE_BAND_BG);
+    }
+
+    #[test]
+    fn sparsity_map_labels_rows_and_columns() {
+        let mut tri = TriMat::<f64>::new((30, 30));
+        for i in 0..30 {
+            tri.add_triplet(i, i, 1.0);
+        }
+        let batch = csr_to_coo_batch(&tri.to_csr()).unwrap();
+        let map_lines = |offset: usize, col_offset: usize, width: u16| {
+            let mut terminal = Terminal::new(TestBackend::new(width, 24)).unwrap();
+            terminal
+                .draw(|f| render_coo_ui(f, &batch, offset, col_offset, false, None, None))
+                .unwrap();
+            let text = buffer_text(terminal.backend().buffer());
+            let lines: Vec<String> = text.lines().map(|l| l.to_string()).collect();
+            let title = lines
+                .iter()
+                .position(|l| l.contains("Sparsity rows"))
+                .unwrap();
+            let left = lines[title].chars().count()
+                - lines[title][lines[title].find("┌ Sparsity").unwrap()..]
+                    .chars()
+                    .count();
+            lines[title + 1..]
+                .iter()
+                .map(|l| l.chars().skip(left + 1).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+
+        // Ruler over the cells, right-aligned absolute rows in the gutter;
+        // each diagonal entry sits under its column.
+        let lines = map_lines(8, 5, 120);
+        assert!(lines[0].starts_with("        10        20"), "{lines:?}");
+        assert!(lines[1].starts_with(" 8 ···*"), "{lines:?}");
+        assert!(lines[3].starts_with("10 ·····*"), "{lines:?}");
+
+        // Narrow panels drop the gutter before the cells, short ones the ruler.
+        let coo = CooView::from_batch(&batch).unwrap();
+        let narrow = |width: u16, height: u16| {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            terminal
+                .draw(|f| render_sparsity_map(f, &coo, None, false, f.area(), 8, 5))
+                .unwrap();
+            buffer_text(terminal.backend().buffer())
+        };
+        assert!(narrow(8, 8).contains("│···*··│"), "{}", narrow(8, 8));
+        assert!(narrow(12, 8).contains("│ 9 ····*··│"), "{}", narrow(12, 8));
+        let short = narrow(12, 3);
+        assert!(short.contains("│8 ···*····│"), "{short}");
+
+        assert_eq!(column_ruler(5..26), "     10        20    ");
+        assert_eq!(column_ruler(0..12), "0         10");
+        assert_eq!(column_ruler(95..112), "     100       | ");
+        assert_eq!(column_ruler(0..3), "0  ");
+    }
+
+    #[test]
+    fn rcm_order_renumbers_the_view() {
+        // The path 0 − 3 − 1 − 4 − 2: bandwidth 3, 1 once reordered.
+        let mut tri = TriMat::<f64>::new((5, 5));
+        for i in 0..5 {
+            tri.add_triplet(i, i, 2.0);
+        }
+        for (a, b) in [(0, 3), (3, 1), (1, 4), (4, 2)] {
+            tri.add_triplet(a, b, 1.0);
+            tri.add_triplet(b, a, 1.0);
+        }
+        let batch = csr_to_coo_batch(&tri.to_csr()).unwrap();
+        let reordering = Reordering::rcm(&batch).unwrap();
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 24)).unwrap();
+        terminal
+            .draw(|f| render_coo_ui(f, &batch, 0, 0, false, Some(&reordering), None))
+            .unwrap();
+        let text = buffer_text(terminal.backend().buffer());
+        assert!(
+            text.contains("RCM order: bandwidth 3 → 1, profile 19 → 13"),
+            "{text}"
+        );
+        assert!(text.contains("Bandwidth: 1 (lower 1, upper 1)"), "{text}");
+        assert!(text.contains("(←→ to scroll cols) RCM order"), "{text}");
+    }
+
+    #[test]
+    fn laplacian_report_adds_structure_panels() {
+        // Path graph 0 - 1 - 2.
+        let mut tri = TriMat::<f64>::new((3, 3));
+        for (r, c, v) in [
+            (0, 0, 1.0),
+            (0, 1, -1.0),
+            (1, 0, -1.0),
+            (1, 1, 2.0),
+            (1, 2, -1.0),
+            (2, 1, -1.0),
+            (2, 2, 1.0),
+        ] {
+            tri.add_triplet(r, c, v);
+        }
+        let matrix = tri.to_csr();
+        let batch = csr_to_coo_batch(&matrix).unwrap();
+        let report = LaplacianReport::new(&matrix, LaplacianMode::Combinatorial, 10);
+
+        let text = render_with_report(&batch, Some(&report), 160, 24);
+        assert!(text.contains(" Laplacian "), "{text}");
+        assert!(text.contains("row sums ≈ 0: ok"), "{text}");
+        assert!(text.contains(" Spectrum "), "{text}");
+        assert!(
+            text.contains("largest:  0.000000, 1.000000, 3.000000"),
+            "{text}"
+        );
+
+        let plain = render_to_text(&batch, 160, 24);
+        assert!(!plain.contains(" Spectrum "), "{plain}");
+    }
+}
@@ -0,0 +1,277 @@
+//! Placeholder for a batch with nothing to tabulate — no rows, no columns or
+//! no feature columns: the schema, row count and detected layout where the
+//! table would be, between the usual metadata and status bars. `M` opens the
+//! metadata popup, `?` lists the keys and `q` quits.
+
+use anyhow::Result;
+use crossterm::{
+    event::KeyCode,
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{
+    Frame, Terminal,
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use std::cell::RefCell;
+use std::io;
+
+use crate::display::display::{
+    data_column_count, dims_mismatch, metadata_line, render_limit_banner,
+};
+use crate::display::display_metadata::{MetadataPopup, render_metadata_popup};
+use crate::display::redraw::{poll_event, run_view};
+use crate::display::window::SourceWindow;
+use crate::display::*;
+use crate::functions::batch_source::BatchSource;
+
+/// Whether `data` has nothing for the table views to show: no rows, no
+/// columns, or no feature columns in a layout that tabulates them.
+pub(crate) fn is_empty_view(data: &BatchSource, layout: &LanceLayout, features: &[usize]) -> bool {
+    data.num_rows() == 0
+        || data.num_columns() == 0
+        || features.is_empty()
+            && matches!(
+                layout,
+                LanceLayout::DenseRowMajor | LanceLayout::Vector1D | LanceLayout::Other
+            )
+}
+
+/// Why `data` is empty, as the placeholder's headline.
+fn headline(data: &BatchSource) -> &'static str {
+    if data.num_columns() == 0 {
+        "No columns"
+    } else if data.num_rows() == 0 {
+        "No rows"
+    } else {
+        "No feature columns to show"
+    }
+}
+
+/// Draw the placeholder for `data`; `show_keys` adds the key list.
+pub(crate) fn render_empty_ui(
+    f: &mut Frame,
+    data: &BatchSource,
+    layout: &LanceLayout,
+    source: SourceWindow,
+    show_keys: bool,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // metadata
+            Constraint::Min(0),    // message
+            Constraint::Length(3), // status
+        ])
+        .split(f.area());
+
+    let meta_line = metadata_line(
+        data,
+        data.num_rows(),
+        data_column_count(data),
+        "    ",
+        dims_mismatch(data, source),
+    );
+    f.render_widget(
+        Paragraph::new(meta_line).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_ACCENT))
+                .title(" Metadata "),
+        ),
+        chunks[0],
+    );
+
+    let schema = data.schema();
+    let mut tail = vec![Line::default()];
+    if show_keys {
+        tail.push(Line::from(Span::styled(
+            "M schema metadata   ? hide keys   q quit",
+            Style::default().fg(TEXT_ACCENT),
+        )));
+    }
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            headline(data),
+            Style::default().fg(HEADER_FG).add_modifier(Modifier::BOLD),
+        )),
+        Line::default(),
+        Line::from(Span::styled(
+            format!("rows: {}    layout: {layout:?}", data.num_rows()),
+            Style::default().fg(TEXT_SECONDARY),
+        )),
+        Line::from(Span::styled(
+            format!("schema ({} columns)", schema.fields().len()),
+            Style::default().fg(TEXT_SECONDARY),
+        )),
+    ];
+    // Fields fill the room left by the lines above and below
+    let inner_height = chunks[1].height.saturating_sub(2) as usize;
+    let room = inner_height.saturating_sub(lines.len() + tail.len());
+    let fields = schema.fields();
+    let shown = if fields.len() > room {
+        room.saturating_sub(1)
+    } else {
+        fields.len()
+    };
+    lines.extend(fields.iter().take(shown).map(|field| {
+        Line::from(Span::styled(
+            format!("{}: {}", field.name(), field.data_type()),
+            Style::default().fg(TEXT_PRIMARY),
+        ))
+    }));
+    if shown < fields.len() {
+        lines.push(Line::from(Span::styled(
+            format!("… {} more", fields.len() - shown),
+            Style::default().fg(TEXT_SECONDARY),
+        )));
+    }
+    lines.extend(tail);
+
+    // Centre the message vertically too
+    let top = inner_height.saturating_sub(lines.len()) / 2;
+    let lines: Vec<Line> = std::iter::repeat_n(Line::default(), top)
+        .chain(lines)
+        .collect();
+    f.render_widget(
+        Paragraph::new(lines).alignment(Alignment::Center).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_PRIMARY))
+                .title(" Lance Data "),
+        ),
+        chunks[1],
+    );
+
+    let status = format!(
+        " {} | {} rows × {} total cols | mode: empty | M metadata | ? keys | q quit ",
+        headline(data),
+        data.num_rows(),
+        data_column_count(data)
+    );
+    f.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_ACCENT))
+            .title(Span::styled(status, Style::default().fg(TEXT_ACCENT))),
+        chunks[2],
+    );
+}
+
+/// Keys of the placeholder: the metadata popup swallows keys while open.
+struct EmptyView {
+    /// Drawing the popup clamps its scroll, so it is mutated while rendering
+    popup: RefCell<Option<MetadataPopup>>,
+    show_keys: bool,
+}
+
+impl EmptyView {
+    fn handle_key(&mut self, data: &BatchSource, code: KeyCode) -> bool {
+        let popup = self.popup.get_mut();
+        if let Some(p) = popup.as_mut() {
+            if !p.handle_key(code) {
+                *popup = None;
+            }
+            return true;
+        }
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('M') => *popup = Some(MetadataPopup::new(&data.schema())),
+            KeyCode::Char('?') => self.show_keys = !self.show_keys,
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Full-screen placeholder for `data` until the user quits.
+pub(crate) fn display_empty_window(
+    data: &BatchSource,
+    layout: LanceLayout,
+    source: SourceWindow,
+) -> Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let mut view = EmptyView {
+        popup: RefCell::new(None),
+        show_keys: false,
+    };
+    run_view(
+        &mut terminal,
+        &mut view,
+        poll_event,
+        |f, view| {
+            render_empty_ui(f, data, &layout, source, view.show_keys);
+            render_limit_banner(f, f.area(), source);
+            if let Some(popup) = view.popup.borrow_mut().as_mut() {
+                render_metadata_popup(f, popup);
+            }
+        },
+        |view, code| view.handle_key(data, code),
+    )?;
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::RecordBatch;
+    use ratatui::backend::TestBackend;
+    use std::sync::Arc;
+
+    #[test]
+    fn keys_toggle_the_list_and_the_metadata_popup() {
+        let schema = Schema::new(vec![Field::new("col_0", DataType::Float64, true)])
+            .with_metadata([("source".to_string(), "unit".to_string())].into());
+        let data = BatchSource::from(RecordBatch::new_empty(Arc::new(schema)));
+        let mut view = EmptyView {
+            popup: RefCell::new(None),
+            show_keys: false,
+        };
+        let render = |view: &EmptyView| {
+            let mut terminal = Terminal::new(TestBackend::new(60, 16)).unwrap();
+            terminal
+                .draw(|f| {
+                    render_empty_ui(
+                        f,
+                        &data,
+                        &LanceLayout::Other,
+                        SourceWindow::whole(0),
+                        view.show_keys,
+                    );
+                    if let Some(popup) = view.popup.borrow_mut().as_mut() {
+                        render_metadata_popup(f, popup);
+                    }
+                })
+                .unwrap();
+            let buffer = terminal.backend().buffer();
+            buffer
+                .content
+                .iter()
+                .map(|c| c.symbol())
+                .collect::<String>()
+        };
+
+        assert!(view.handle_key(&data, KeyCode::Char('?')));
+        assert!(render(&view).contains("hide keys"));
+        assert!(view.handle_key(&data, KeyCode::Char('M')));
+        assert!(render(&view).contains("source"));
+        // `q` closes the popup before it quits.
+        assert!(view.handle_key(&data, KeyCode::Char('q')));
+        assert!(view.popup.borrow().is_none());
+        assert!(!view.handle_key(&data, KeyCode::Char('q')));
+    }
+}
@@ -0,0 +1,173 @@
+//! Export prompt of the N×F table (`W`): before the loaded rows are written
+//! to a new `javelin-export-<UTC timestamp>.lance`, a few keys choose what
+//! the export takes from the view rather than from the stored data. The
+//! choices start from the ones of the last export in the viewer.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::display::display_metadata::centered;
+use crate::display::*;
+
+/// Key opening the prompt.
+pub(crate) const EXPORT_KEY: char = 'W';
+/// Key toggling [`ExportChoices::transformed`].
+const TRANSFORMED_KEY: char = 't';
+/// Key toggling [`ExportChoices::computed`].
+const COMPUTED_KEY: char = 'c';
+/// Key toggling [`ExportChoices::formatted`].
+const FORMATTED_KEY: char = 'f';
+
+/// What an export writes as shown instead of as stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct ExportChoices {
+    /// The values as scaled by `z`, not raw
+    pub transformed: bool,
+    /// The `n` norm and `.` dot-product columns shown, after the features
+    pub computed: bool,
+    /// Integer columns with a display format as the strings shown
+    pub formatted: bool,
+}
+
+impl ExportChoices {
+    /// The options in the order listed: key, description, chosen.
+    fn options(&self) -> Vec<(char, &'static str, bool)> {
+        vec![
+            (
+                TRANSFORMED_KEY,
+                "values as scaled by z (raw otherwise)",
+                self.transformed,
+            ),
+            (
+                COMPUTED_KEY,
+                "the n norm and . dot-product columns shown",
+                self.computed,
+            ),
+            (
+                FORMATTED_KEY,
+                "integer columns with a display format as the strings shown",
+                self.formatted,
+            ),
+        ]
+    }
+
+    /// Flip the option of `key`, if there is one.
+    fn toggle(&mut self, key: char) {
+        let option = match key {
+            TRANSFORMED_KEY => &mut self.transformed,
+            COMPUTED_KEY => &mut self.computed,
+            FORMATTED_KEY => &mut self.formatted,
+            _ => return,
+        };
+        *option = !*option;
+    }
+}
+
+/// What the viewer should do after a key press in the prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExportAction {
+    Stay,
+    Close,
+    /// Write the rows with these choices
+    Write(ExportChoices),
+}
+
+/// Choices for one export of `rows` loaded rows.
+pub(crate) struct ExportPrompt {
+    choices: ExportChoices,
+    rows: usize,
+}
+
+impl ExportPrompt {
+    pub(crate) fn new(choices: ExportChoices, rows: usize) -> Self {
+        Self { choices, rows }
+    }
+
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> ExportAction {
+        match code {
+            KeyCode::Esc => ExportAction::Close,
+            KeyCode::Enter => ExportAction::Write(self.choices),
+            KeyCode::Char(c) => {
+                self.choices.toggle(c);
+                ExportAction::Stay
+            }
+            _ => ExportAction::Stay,
+        }
+    }
+}
+
+pub(crate) fn render_export_prompt(f: &mut Frame, prompt: &ExportPrompt) {
+    let options = prompt.choices.options();
+    let area = centered(f.area(), 60);
+    let area = Rect {
+        height: area.height.min(options.len() as u16 + 2),
+        ..area
+    };
+    let keys: Vec<String> = options.iter().map(|(key, _, _)| key.to_string()).collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(format!(" Export {} loaded rows ", prompt.rows))
+        .title_bottom(format!(
+            " Enter write | {} toggle | Esc cancel ",
+            keys.join("/")
+        ));
+    let lines: Vec<Line> = options
+        .into_iter()
+        .map(|(key, label, chosen)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("[{}] ", if chosen { "x" } else { " " }),
+                    Style::default().fg(TEXT_PRIMARY),
+                ),
+                Span::styled(format!("{key} "), Style::default().fg(TEXT_ACCENT)),
+                Span::styled(label, Style::default().fg(TEXT_SECONDARY)),
+            ])
+        })
+        .collect();
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_toggle_the_choices_and_enter_writes_them() {
+        let mut prompt = ExportPrompt::new(ExportChoices::default(), 3);
+        assert_eq!(
+            prompt.handle_key(KeyCode::Char(TRANSFORMED_KEY)),
+            ExportAction::Stay
+        );
+        prompt.handle_key(KeyCode::Char(COMPUTED_KEY));
+        // keys without an option change nothing
+        assert_eq!(prompt.handle_key(KeyCode::Char('q')), ExportAction::Stay);
+        let both = ExportChoices {
+            transformed: true,
+            computed: true,
+            formatted: false,
+        };
+        assert_eq!(prompt.handle_key(KeyCode::Enter), ExportAction::Write(both));
+
+        // the last choices come back, and toggle off again
+        let mut prompt = ExportPrompt::new(both, 3);
+        prompt.handle_key(KeyCode::Char(FORMATTED_KEY));
+        prompt.handle_key(KeyCode::Char(TRANSFORMED_KEY));
+        assert_eq!(
+            prompt.handle_key(KeyCode::Enter),
+            ExportAction::Write(ExportChoices {
+                computed: true,
+                formatted: true,
+                ..ExportChoices::default()
+            })
+        );
+        assert_eq!(prompt.handle_key(KeyCode::Esc), ExportAction::Close);
+    }
+}
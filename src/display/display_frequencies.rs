@@ -0,0 +1,167 @@
+//! Popup with the frequency table of one column (`i` key): the distinct
+//! values of a Boolean, string or integer column with counts and shares.
+
+use arrow_array::RecordBatch;
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::Constraint,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Cell, Clear, Row, Table},
+};
+
+use crate::display::display::display_name;
+use crate::display::display_metadata::centered;
+use crate::display::*;
+use crate::functions::frequencies::{DEFAULT_MAX_DISTINCT, FrequencyTable, column_frequencies};
+
+/// Width of the share bar in the last column.
+const BAR_WIDTH: usize = 20;
+
+/// State of the frequency popup for one column of the loaded batch.
+pub(crate) struct FrequencyPopup {
+    name: String,
+    table: FrequencyTable,
+    scroll: usize,
+}
+
+impl FrequencyPopup {
+    pub(crate) fn new(batch: &RecordBatch, col_idx: usize) -> Self {
+        Self {
+            name: display_name(batch.schema().field(col_idx)).to_string(),
+            table: column_frequencies(batch.column(col_idx), DEFAULT_MAX_DISTINCT),
+            scroll: 0,
+        }
+    }
+
+    /// Apply a key press. Returns `false` when the popup should close.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('i') => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll += 1,
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll += 10,
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            _ => {}
+        }
+        true
+    }
+}
+
+fn share_bar(fraction: f64) -> String {
+    "█".repeat((fraction * BAR_WIDTH as f64).round() as usize)
+}
+
+/// Draw the popup over the current frame; the scroll offset is clamped to
+/// the values once the popup height is known.
+pub(crate) fn render_frequency_popup(f: &mut Frame, popup: &mut FrequencyPopup) {
+    let area = centered(f.area(), 80);
+    let visible = area.height.saturating_sub(3) as usize;
+    let table = &popup.table;
+
+    let mut rows: Vec<Row> = Vec::new();
+    if table.is_high_cardinality() {
+        rows.push(Row::new(vec![
+            Cell::from(table.cardinality_label()).style(Style::default().fg(TEXT_SECONDARY)),
+        ]));
+    } else {
+        popup.scroll = popup.scroll.min(table.values.len().saturating_sub(visible));
+        rows.extend(
+            table
+                .values
+                .iter()
+                .skip(popup.scroll)
+                .take(visible)
+                .map(|v| {
+                    let fraction = table.fraction(v.count);
+                    Row::new(vec![
+                        Cell::from(v.value.clone()).style(Style::default().fg(TEXT_PRIMARY)),
+                        Cell::from(v.count.to_string()),
+                        Cell::from(format!("{:.2}%", fraction * 100.0)),
+                        Cell::from(share_bar(fraction)).style(Style::default().fg(TEXT_ACCENT)),
+                    ])
+                }),
+        );
+    }
+
+    let title = format!(
+        " Frequencies: {} ({} rows, {}, {} null) ",
+        popup.name,
+        table.rows,
+        table.cardinality_label(),
+        table.nulls
+    );
+    let widget = Table::new(
+        rows,
+        [
+            Constraint::Min(12),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(BAR_WIDTH as u16),
+        ],
+    )
+    .header(
+        Row::new(["value", "count", "share", ""])
+            .style(Style::default().fg(HEADER_FG).add_modifier(Modifier::BOLD)),
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_ACCENT))
+            .title(title)
+            .title_bottom(" ↑↓ scroll | i/Esc close "),
+    )
+    .column_spacing(1);
+
+    f.render_widget(Clear, area);
+    f.render_widget(widget, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::{ArrayRef, StringArray};
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::sync::Arc;
+
+    fn render(popup: &mut FrequencyPopup) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(70, 12)).unwrap();
+        terminal.draw(|f| render_frequency_popup(f, popup)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|line| line.iter().map(|c| c.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn popup_lists_values_by_frequency() {
+        let labels: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("b"),
+            Some("a"),
+            Some("b"),
+            None,
+        ]));
+        let schema = Schema::new(vec![Field::new("label", DataType::Utf8, true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![labels]).unwrap();
+
+        let mut popup = FrequencyPopup::new(&batch, 0);
+        for _ in 0..10 {
+            assert!(popup.handle_key(KeyCode::Down));
+        }
+        let text = render(&mut popup);
+        assert_eq!(popup.scroll, 0);
+        assert!(
+            text.contains("Frequencies: label (4 rows, 2 distinct, 1 null)"),
+            "{text}"
+        );
+        let b = text.lines().position(|l| l.contains("50.00%")).unwrap();
+        let a = text.lines().position(|l| l.contains("25.00%")).unwrap();
+        assert!(b < a, "{text}");
+        assert!(!popup.handle_key(KeyCode::Char('i')));
+    }
+}
@@ -0,0 +1,200 @@
+//! Hidden columns: `d` takes the leftmost visible feature out of the table,
+//! and `D` lists the hidden ones to bring back.
+//! Columns are hidden by name, and the set is kept in the dataset's session
+//! state, so a noisy column stays out of the way in later sessions too.
+//! Aggregates over all features leave hidden columns out.
+
+use arrow::datatypes::Schema;
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use std::collections::BTreeSet;
+
+use crate::display::display_metadata::centered;
+use crate::display::*;
+
+/// Key hiding the leftmost visible feature.
+pub(crate) const HIDE_COLUMN_KEY: char = 'd';
+/// Key opening the list of hidden columns.
+pub(crate) const HIDDEN_LIST_KEY: char = 'D';
+
+/// Column names taken out of the table with [`HIDE_COLUMN_KEY`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct HiddenColumns(BTreeSet<String>);
+
+impl HiddenColumns {
+    pub(crate) fn new(names: impl IntoIterator<Item = String>) -> Self {
+        Self(names.into_iter().collect())
+    }
+
+    pub(crate) fn names(&self) -> &BTreeSet<String> {
+        &self.0
+    }
+
+    /// Hide the column `name`; false when it already was.
+    pub(crate) fn hide(&mut self, name: &str) -> bool {
+        self.0.insert(name.to_string())
+    }
+
+    /// Show the column `name` again; false when it was not hidden.
+    pub(crate) fn show(&mut self, name: &str) -> bool {
+        self.0.remove(name)
+    }
+
+    /// `cols` (columns of `schema`) without the hidden ones; all of them
+    /// when none would be left, as a saved set may name every feature of a
+    /// later version.
+    pub(crate) fn visible(&self, schema: &Schema, cols: &[usize]) -> Vec<usize> {
+        let shown: Vec<usize> = cols
+            .iter()
+            .copied()
+            .filter(|&c| !self.0.contains(schema.field(c).name()))
+            .collect();
+        if shown.is_empty() {
+            cols.to_vec()
+        } else {
+            shown
+        }
+    }
+
+    /// How many of `cols` [`HiddenColumns::visible`] leaves out.
+    pub(crate) fn count(&self, schema: &Schema, cols: &[usize]) -> usize {
+        cols.len() - self.visible(schema, cols).len()
+    }
+}
+
+/// `", 2 hidden"` for the status bar, or nothing when no column is hidden.
+pub(crate) fn hidden_note(hidden: usize) -> String {
+    if hidden == 0 {
+        String::new()
+    } else {
+        format!(", {hidden} hidden")
+    }
+}
+
+/// What the viewer should do after a key press in the hidden-columns list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HiddenAction {
+    Stay,
+    Close,
+    /// Show these columns again
+    Show(Vec<String>),
+}
+
+/// The [`HIDDEN_LIST_KEY`] list of hidden columns.
+pub(crate) struct HiddenList {
+    names: Vec<String>,
+    selected: usize,
+}
+
+impl HiddenList {
+    pub(crate) fn new(hidden: &HiddenColumns) -> Self {
+        Self {
+            names: hidden.names().iter().cloned().collect(),
+            selected: 0,
+        }
+    }
+
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> HiddenAction {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => return HiddenAction::Close,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.names.len().saturating_sub(1))
+            }
+            KeyCode::Enter if self.selected < self.names.len() => {
+                let name = self.names.remove(self.selected);
+                self.selected = self.selected.min(self.names.len().saturating_sub(1));
+                return HiddenAction::Show(vec![name]);
+            }
+            KeyCode::Char('a') if !self.names.is_empty() => {
+                self.selected = 0;
+                return HiddenAction::Show(std::mem::take(&mut self.names));
+            }
+            _ => {}
+        }
+        HiddenAction::Stay
+    }
+}
+
+pub(crate) fn render_hidden_list(f: &mut Frame, list: &HiddenList) {
+    let area = centered(f.area(), 50);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(format!(" Hidden columns ({}) ", list.names.len()))
+        .title_bottom(" ↑↓ select | Enter show | a show all | Esc close ");
+    f.render_widget(Clear, area);
+
+    if list.names.is_empty() {
+        let msg = Paragraph::new(Span::styled(
+            "No hidden columns; d hides the leftmost feature",
+            Style::default().fg(TEXT_SECONDARY),
+        ))
+        .block(block);
+        f.render_widget(msg, area);
+        return;
+    }
+
+    // Keep the selection in view.
+    let visible = area.height.saturating_sub(2) as usize;
+    let skip = (list.selected + 1).saturating_sub(visible);
+    let lines: Vec<Line> = list
+        .names
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(visible)
+        .map(|(i, name)| {
+            let style = if i == list.selected {
+                Style::default()
+                    .fg(TEXT_ACCENT)
+                    .add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().fg(TEXT_PRIMARY)
+            };
+            Line::from(Span::styled(name.clone(), style))
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field};
+
+    #[test]
+    fn hidden_names_leave_the_table_but_never_empty_it() {
+        let schema = Schema::new(vec![
+            Field::new("col_0", DataType::Float64, true),
+            Field::new("blob", DataType::Utf8, true),
+            Field::new("col_1", DataType::Float64, true),
+        ]);
+        let mut hidden = HiddenColumns::default();
+        assert!(hidden.hide("blob"));
+        assert!(!hidden.hide("blob"));
+        assert_eq!(hidden.visible(&schema, &[0, 1, 2]), [0, 2]);
+        assert_eq!(hidden.count(&schema, &[0, 1, 2]), 1);
+
+        hidden.hide("col_0");
+        hidden.hide("col_1");
+        assert_eq!(hidden.visible(&schema, &[0, 1, 2]), [0, 1, 2]);
+
+        let mut list = HiddenList::new(&hidden);
+        list.handle_key(KeyCode::Down);
+        assert_eq!(
+            list.handle_key(KeyCode::Enter),
+            HiddenAction::Show(vec!["col_0".to_string()])
+        );
+        assert_eq!(
+            list.handle_key(KeyCode::Char('a')),
+            HiddenAction::Show(vec!["blob".to_string(), "col_1".to_string()])
+        );
+        assert_eq!(list.handle_key(KeyCode::Enter), HiddenAction::Stay);
+    }
+}
@@ -0,0 +1,543 @@
+//! Histogram of a numeric vector: the binning and bar drawing shared by the
+//! 1D viewer's distribution panel and the full-screen `plot-lambdas` view,
+//! which adds log axes, a cumulative mode and bin-count keys. The panel,
+//! once focused with `Tab`, takes its own bin-count keys and can clamp its
+//! range to the central 99% or 95% of the values.
+
+use anyhow::Result;
+use crossterm::{
+    event::KeyCode,
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{
+    Frame, Terminal,
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use std::cell::Cell;
+use std::io;
+
+use crate::display::redraw::{poll_event, run_view};
+use crate::display::*;
+
+/// Bin counts over equal-width bins of the finite values (of their
+/// logarithm with a log x axis).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Histogram {
+    /// `counts.len() + 1` bin edges, in data units
+    pub edges: Vec<f64>,
+    pub counts: Vec<u64>,
+    /// NaN/Inf values left out
+    pub non_finite: usize,
+    /// Values ≤ 0 left out of a log x axis
+    pub non_positive: usize,
+}
+
+impl Histogram {
+    /// Bin `values` into `bins` bins. Inputs with nothing to bin (no finite
+    /// values, a single value, all values equal) return the message to show
+    /// instead.
+    pub(crate) fn new(values: &[f64], bins: usize, log_x: bool) -> Result<Self, String> {
+        let non_finite = values.iter().filter(|v| !v.is_finite()).count();
+        let finite = values.iter().copied().filter(|v| v.is_finite());
+        let (kept, non_positive): (Vec<f64>, usize) = if log_x {
+            let all: Vec<f64> = finite.collect();
+            let positive: Vec<f64> = all.iter().copied().filter(|&v| v > 0.0).collect();
+            let excluded = all.len() - positive.len();
+            (positive.into_iter().map(f64::ln).collect(), excluded)
+        } else {
+            (finite.collect(), 0)
+        };
+
+        let scale = |v: f64| if log_x { v.exp() } else { v };
+        match kept.len() {
+            0 if non_finite > 0 => return Err(format!("no finite values ({non_finite} NaN/Inf)")),
+            0 if log_x && non_positive > 0 => {
+                return Err(format!(
+                    "no positive values for a log x axis ({non_positive} ≤ 0)"
+                ));
+            }
+            0 => return Err("no values to plot".to_string()),
+            1 => {
+                return Err(format!(
+                    "a single value ({}); nothing to bin",
+                    scale(kept[0])
+                ));
+            }
+            _ => {}
+        }
+        let (lo, hi) = kept
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v), hi.max(v))
+            });
+        if lo == hi {
+            return Err(format!(
+                "all {} values equal {}; nothing to bin",
+                kept.len(),
+                scale(lo)
+            ));
+        }
+
+        let bins = bins.max(1);
+        let width = (hi - lo) / bins as f64;
+        let mut counts = vec![0u64; bins];
+        for v in kept {
+            let idx = (((v - lo) / width).floor() as usize).min(bins - 1);
+            counts[idx] += 1;
+        }
+        let edges = (0..=bins).map(|i| scale(lo + i as f64 * width)).collect();
+        Ok(Self {
+            edges,
+            counts,
+            non_finite,
+            non_positive,
+        })
+    }
+
+    /// Values in each bin or any bin before it.
+    pub(crate) fn cumulative(&self) -> Vec<u64> {
+        self.counts
+            .iter()
+            .scan(0, |acc, &c| {
+                *acc += c;
+                Some(*acc)
+            })
+            .collect()
+    }
+
+    pub(crate) fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+}
+
+/// Rows of a vertical bar chart, top first: each of `heights` is drawn
+/// `col_width` cells wide and scaled so the largest fills `rows` rows.
+pub(crate) fn bar_rows(heights: &[f64], rows: usize, col_width: usize) -> Vec<String> {
+    let max = heights.iter().copied().fold(0.0, f64::max);
+    (1..=rows)
+        .rev()
+        .map(|level| {
+            heights
+                .iter()
+                .map(|&h| {
+                    let bar = if max > 0.0 {
+                        (h / max * rows as f64).ceil() as usize
+                    } else {
+                        0
+                    };
+                    if bar >= level { "█" } else { " " }.repeat(col_width)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Key of the distribution panel dropping a bin.
+pub(crate) const FEWER_BINS_KEY: char = '(';
+/// Key of the distribution panel adding a bin.
+pub(crate) const MORE_BINS_KEY: char = ')';
+/// Key of the distribution panel cycling its [`Clamp`].
+pub(crate) const CLAMP_KEY: char = '%';
+
+/// Bins of the distribution panel until `(`/`)` pick others.
+const DEFAULT_PANEL_BINS: usize = 20;
+
+/// Central share of the values the distribution panel spans, so one
+/// outlier does not squeeze the rest into a bin or two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Clamp {
+    #[default]
+    All,
+    P99,
+    P95,
+}
+
+impl Clamp {
+    /// The clamp after this one, for [`CLAMP_KEY`].
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Clamp::All => Clamp::P99,
+            Clamp::P99 => Clamp::P95,
+            Clamp::P95 => Clamp::All,
+        }
+    }
+
+    pub(crate) fn percent(self) -> u32 {
+        match self {
+            Clamp::All => 100,
+            Clamp::P99 => 99,
+            Clamp::P95 => 95,
+        }
+    }
+
+    /// The lowest and highest value spanned among the `sorted` finite
+    /// values, cutting at least an equal tail off each end (the ranks are
+    /// rounded inwards).
+    pub(crate) fn bounds(self, sorted: &[f64]) -> Option<(f64, f64)> {
+        let (first, last) = (sorted.first()?, sorted.last()?);
+        if self == Clamp::All {
+            return Some((*first, *last));
+        }
+        let tail = f64::from(100 - self.percent()) / 200.0;
+        let last_rank = (sorted.len() - 1) as f64;
+        let lo = (last_rank * tail).ceil() as usize;
+        let hi = ((last_rank * (1.0 - tail)).floor() as usize).max(lo);
+        Some((sorted[lo], sorted[hi]))
+    }
+}
+
+/// State of the 1D viewer's distribution panel: whether it has the keys
+/// (`Tab`), its bin count and its [`Clamp`].
+#[derive(Debug)]
+pub(crate) struct DistributionPanel {
+    pub focused: bool,
+    pub clamp: Clamp,
+    bins: usize,
+    /// Bins the panel had room for when last drawn
+    fit: Cell<usize>,
+}
+
+impl Default for DistributionPanel {
+    fn default() -> Self {
+        Self {
+            focused: false,
+            clamp: Clamp::default(),
+            bins: DEFAULT_PANEL_BINS,
+            fit: Cell::new(DEFAULT_PANEL_BINS),
+        }
+    }
+}
+
+impl DistributionPanel {
+    /// Bins drawn with room for `fit`, remembered for the keys.
+    pub(crate) fn bins(&self, fit: usize) -> usize {
+        self.fit.set(fit);
+        self.bins.min(fit)
+    }
+
+    /// Apply a key press while focused: [`FEWER_BINS_KEY`] and
+    /// [`MORE_BINS_KEY`] step from the bins shown, within what fits, and
+    /// [`CLAMP_KEY`] cycles the range.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) {
+        let shown = self.bins.min(self.fit.get()).max(1);
+        match code {
+            KeyCode::Char(FEWER_BINS_KEY) => self.bins = (shown - 1).max(1),
+            KeyCode::Char(MORE_BINS_KEY) => self.bins = (shown + 1).min(self.fit.get().max(1)),
+            KeyCode::Char(CLAMP_KEY) => self.clamp = self.clamp.next(),
+            _ => {}
+        }
+    }
+
+    /// Panel title, naming its keys while focused.
+    pub(crate) fn title(&self) -> String {
+        if self.focused {
+            format!(
+                " Distribution: {FEWER_BINS_KEY}/{MORE_BINS_KEY} bins, {CLAMP_KEY} range {}%, Tab back ",
+                self.clamp.percent()
+            )
+        } else {
+            " Distribution (Tab focus) ".to_string()
+        }
+    }
+}
+
+/// Interactive state of the full-screen histogram.
+pub(crate) struct HistogramView {
+    name: String,
+    values: Vec<f64>,
+    pub bins: usize,
+    pub log_x: bool,
+    pub log_y: bool,
+    pub cumulative: bool,
+}
+
+impl HistogramView {
+    pub(crate) fn new(name: impl Into<String>, values: Vec<f64>, bins: usize) -> Self {
+        Self {
+            name: name.into(),
+            values,
+            bins: bins.max(1),
+            log_x: false,
+            log_y: false,
+            cumulative: false,
+        }
+    }
+
+    /// Apply a key press. Returns `false` when the view should close.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            KeyCode::Char('y') => self.log_y = !self.log_y,
+            KeyCode::Char('x') => self.log_x = !self.log_x,
+            KeyCode::Char('c') => self.cumulative = !self.cumulative,
+            KeyCode::Char('+') | KeyCode::Char('=') => self.bins += 1.max(self.bins / 4),
+            KeyCode::Char('-') => self.bins = self.bins.saturating_sub(1.max(self.bins / 5)).max(1),
+            _ => {}
+        }
+        true
+    }
+
+    fn mode_label(&self) -> String {
+        format!(
+            "x {} | y {} | {}",
+            if self.log_x { "log" } else { "linear" },
+            if self.log_y { "log" } else { "linear" },
+            if self.cumulative {
+                "cumulative"
+            } else {
+                "counts"
+            }
+        )
+    }
+}
+
+/// Draw the histogram view over the whole frame. Bins that do not fit the
+/// width are merged on screen by drawing at most one bin per column.
+pub(crate) fn render_histogram_view(f: &mut Frame, view: &HistogramView) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // summary
+            Constraint::Min(0),    // chart
+            Constraint::Length(3), // status
+        ])
+        .split(f.area());
+
+    let inner_width = chunks[1].width.saturating_sub(2) as usize;
+    let bins = view.bins.min(inner_width.max(1));
+    let hist = Histogram::new(&view.values, bins, view.log_x);
+
+    let summary = match &hist {
+        Ok(h) => {
+            let mut text = format!(
+                "{} values | {} bins | range [{:.6}, {:.6}]",
+                h.total(),
+                h.counts.len(),
+                h.edges[0],
+                h.edges[h.edges.len() - 1]
+            );
+            if h.non_finite > 0 {
+                text.push_str(&format!(" | {} NaN/Inf skipped", h.non_finite));
+            }
+            if h.non_positive > 0 {
+                text.push_str(&format!(" | {} ≤ 0 skipped (log x)", h.non_positive));
+            }
+            text
+        }
+        Err(_) => format!("{} values", view.values.len()),
+    };
+    f.render_widget(
+        Paragraph::new(Span::styled(summary, Style::default().fg(TEXT_SECONDARY))).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_ACCENT))
+                .title(format!(" Histogram: {} ", view.name)),
+        ),
+        chunks[0],
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_PRIMARY))
+        .title(format!(" {} ", view.mode_label()));
+    let lines: Vec<Line> = match &hist {
+        Err(message) => vec![Line::from(Span::styled(
+            message.clone(),
+            Style::default().fg(SPARSE_ASTERISK),
+        ))],
+        Ok(h) => {
+            let counts = if view.cumulative {
+                h.cumulative()
+            } else {
+                h.counts.clone()
+            };
+            let heights: Vec<f64> = counts
+                .iter()
+                .map(|&c| {
+                    if view.log_y {
+                        (c as f64).ln_1p()
+                    } else {
+                        c as f64
+                    }
+                })
+                .collect();
+            let max_count = counts.iter().copied().max().unwrap_or(0);
+            let col_width = (inner_width / counts.len()).max(1);
+            let rows = chunks[1].height.saturating_sub(4) as usize;
+
+            let mut lines = vec![Line::from(Span::styled(
+                format!("max {max_count}"),
+                Style::default().fg(TEXT_SECONDARY),
+            ))];
+            lines.extend(
+                bar_rows(&heights, rows, col_width)
+                    .into_iter()
+                    .map(|row| Line::from(Span::styled(row, Style::default().fg(TEXT_ACCENT)))),
+            );
+            let chart_width = col_width * counts.len();
+            lines.push(Line::from("─".repeat(chart_width)));
+            let lo = format!("{:.4}", h.edges[0]);
+            let hi = format!("{:.4}", h.edges[h.edges.len() - 1]);
+            lines.push(Line::from(format!(
+                "{lo}{}{hi}",
+                " ".repeat(chart_width.saturating_sub(lo.len() + hi.len()))
+            )));
+            lines
+        }
+    };
+    f.render_widget(Paragraph::new(lines).block(block), chunks[1]);
+
+    let status = " y log/linear counts | x log/linear bins | c cumulative | +/- bins | q quit ";
+    f.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_ACCENT))
+            .title(Span::styled(status, Style::default().fg(TEXT_ACCENT))),
+        chunks[2],
+    );
+}
+
+/// Full-screen histogram of `values` until the user quits.
+pub(crate) fn display_histogram_interactive(
+    name: &str,
+    values: Vec<f64>,
+    bins: usize,
+) -> Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let mut view = HistogramView::new(name, values, bins);
+    run_view(
+        &mut terminal,
+        &mut view,
+        poll_event,
+        render_histogram_view,
+        HistogramView::handle_key,
+    )?;
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    fn render(view: &HistogramView) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(60, 16)).unwrap();
+        terminal.draw(|f| render_histogram_view(f, view)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|line| line.iter().map(|c| c.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn bins_linear_log_and_cumulative() {
+        let values = [1.0, 2.0, 3.0, 4.0, 10.0, 100.0, f64::NAN, -1.0];
+        let h = Histogram::new(&values, 3, false).unwrap();
+        assert_eq!(h.counts, [6, 0, 1]);
+        assert_eq!(h.non_finite, 1);
+        assert_eq!(h.cumulative(), [6, 6, 7]);
+
+        // Log bins: [1, 4.64), [4.64, 21.5), [21.5, 100]; -1 is left out.
+        let h = Histogram::new(&values, 3, true).unwrap();
+        assert_eq!(h.counts, [4, 1, 1]);
+        assert_eq!(h.non_positive, 1);
+        assert!((h.edges[3] - 100.0).abs() < 1e-9);
+
+        assert_eq!(bar_rows(&[1.0, 2.0], 2, 2), ["  ██", "████"]);
+    }
+
+    #[test]
+    fn degenerate_inputs_render_a_message() {
+        for (values, message) in [
+            (vec![], "no values to plot"),
+            (
+                vec![f64::NAN, f64::INFINITY],
+                "no finite values (2 NaN/Inf)",
+            ),
+            (vec![2.5], "a single value (2.5); nothing to bin"),
+            (vec![3.0; 4], "all 4 values equal 3; nothing to bin"),
+        ] {
+            assert_eq!(Histogram::new(&values, 10, false), Err(message.to_string()));
+            let text = render(&HistogramView::new("lambdas", values, 10));
+            assert!(text.contains(message), "{text}");
+        }
+        assert!(
+            Histogram::new(&[0.0, -2.0], 10, true)
+                .unwrap_err()
+                .starts_with("no positive values")
+        );
+    }
+
+    #[test]
+    fn keys_toggle_modes_and_adjust_bins() {
+        let mut view = HistogramView::new("lambdas", (1..=100).map(f64::from).collect(), 20);
+        for key in ['y', 'x', 'c', '+'] {
+            assert!(view.handle_key(KeyCode::Char(key)));
+        }
+        assert!(view.log_y && view.log_x && view.cumulative);
+        assert_eq!(view.bins, 25);
+        for _ in 0..50 {
+            view.handle_key(KeyCode::Char('-'));
+        }
+        assert_eq!(view.bins, 1);
+
+        let text = render(&view);
+        assert!(text.contains("x log | y log | cumulative"), "{text}");
+        assert!(text.contains("100 values | 1 bins"), "{text}");
+        assert!(!view.handle_key(KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn panel_keys_step_bins_within_the_fit_and_cycle_the_clamp() {
+        let mut panel = DistributionPanel::default();
+        assert_eq!(panel.bins(12), 12);
+        // steps start from the bins shown, not the 20 asked for
+        panel.handle_key(KeyCode::Char(FEWER_BINS_KEY));
+        assert_eq!(panel.bins(12), 11);
+        for _ in 0..3 {
+            panel.handle_key(KeyCode::Char(MORE_BINS_KEY));
+        }
+        assert_eq!(panel.bins(12), 12);
+        for _ in 0..20 {
+            panel.handle_key(KeyCode::Char(FEWER_BINS_KEY));
+        }
+        assert_eq!(panel.bins(12), 1);
+
+        let clamps: Vec<Clamp> = (0..3)
+            .map(|_| {
+                panel.handle_key(KeyCode::Char(CLAMP_KEY));
+                panel.clamp
+            })
+            .collect();
+        assert_eq!(clamps, [Clamp::P99, Clamp::P95, Clamp::All]);
+        panel.focused = true;
+        assert!(panel.title().contains("(/) bins, % range 100%"));
+    }
+
+    #[test]
+    fn clamps_cut_equal_tails_rounding_inwards() {
+        // 0..=99 and one outlier at 10000
+        let mut values: Vec<f64> = (0..100).map(f64::from).collect();
+        values.push(10_000.0);
+        assert_eq!(Clamp::All.bounds(&values), Some((0.0, 10_000.0)));
+        assert_eq!(Clamp::P99.bounds(&values), Some((1.0, 99.0)));
+        assert_eq!(Clamp::P95.bounds(&values), Some((3.0, 97.0)));
+        assert_eq!(Clamp::P95.bounds(&[]), None);
+    }
+}
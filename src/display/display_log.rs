@@ -0,0 +1,146 @@
+//! Popup with the tail of the log (`L` key), read from the in-memory ring in
+//! [`crate::logging`]. `1`–`4` set the level logged from then on.
+
+use crossterm::event::KeyCode;
+use log::LevelFilter;
+use ratatui::{
+    Frame,
+    style::Style,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::display::display_metadata::centered;
+use crate::display::*;
+use crate::logging;
+
+/// Levels selected by `1`–`4`.
+const LEVELS: [LevelFilter; 4] = [
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+];
+
+/// State of the log popup: lines scrolled up from the newest one.
+#[derive(Debug, Default)]
+pub(crate) struct LogPopup {
+    scroll: usize,
+}
+
+impl LogPopup {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a key press. Returns `false` when the popup should close.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('L') => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.scroll += 1,
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageUp => self.scroll += 10,
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = usize::MAX,
+            KeyCode::End | KeyCode::Char('G') => self.scroll = 0,
+            KeyCode::Char(c @ '1'..='4') => {
+                let level = LEVELS[c as usize - '1' as usize];
+                log::info!("log level set to {level}");
+                logging::set_level(level);
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Draw the popup over the current frame with `lines`, oldest first. The
+/// scroll offset is clamped to them.
+pub(crate) fn render_log_popup(f: &mut Frame, popup: &mut LogPopup, lines: &[String]) {
+    let area = centered(f.area(), 80);
+    let inner_height = area.height.saturating_sub(2) as usize;
+    popup.scroll = popup.scroll.min(lines.len().saturating_sub(inner_height));
+    let end = lines.len() - popup.scroll;
+    let start = end.saturating_sub(inner_height);
+
+    let mut text: Vec<Line> = lines[start..end]
+        .iter()
+        .map(|line| {
+            let colour = if line.contains(" ERROR ") {
+                TEXT_WARNING
+            } else if line.contains(" WARN ") {
+                HEADER_FG
+            } else {
+                TEXT_PRIMARY
+            };
+            Line::styled(line.clone(), Style::default().fg(colour))
+        })
+        .collect();
+    if lines.is_empty() {
+        text.push(Line::from("nothing logged yet"));
+    }
+
+    let position = if popup.scroll == 0 {
+        "newest".to_string()
+    } else {
+        format!("{} lines up", popup.scroll)
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(format!(
+            " Log ({} lines, {position}, level {}) ",
+            lines.len(),
+            logging::level().as_str().to_lowercase()
+        ))
+        .title_bottom(" ↑↓ scroll | 1 error 2 warn 3 info 4 debug | Esc close ");
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::screenshot::buffer_text;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    fn render(popup: &mut LogPopup, lines: &[String]) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        terminal
+            .draw(|f| render_log_popup(f, popup, lines))
+            .unwrap();
+        buffer_text(terminal.backend().buffer())
+    }
+
+    #[test]
+    fn the_popup_follows_the_tail_and_scrolls_back() {
+        let lines: Vec<String> = (0..40)
+            .map(|i| format!("[t INFO  javelin] line {i:02}"))
+            .collect();
+        let mut popup = LogPopup::new();
+        let text = render(&mut popup, &lines);
+        assert!(text.contains("Log (40 lines, newest"), "{text}");
+        assert!(
+            text.contains("line 39") && !text.contains("line 20"),
+            "{text}"
+        );
+
+        popup.handle_key(KeyCode::PageUp);
+        let text = render(&mut popup, &lines);
+        assert!(
+            text.contains("10 lines up") && text.contains("line 29"),
+            "{text}"
+        );
+        assert!(!text.contains("line 30"), "{text}");
+
+        // Home is clamped to the oldest line once drawn.
+        popup.handle_key(KeyCode::Home);
+        let text = render(&mut popup, &lines);
+        assert!(text.contains("line 00"), "{text}");
+        assert_eq!(popup.scroll, 40 - 14);
+
+        assert!(!popup.handle_key(KeyCode::Esc));
+        let text = render(&mut LogPopup::new(), &[]);
+        assert!(text.contains("nothing logged yet"), "{text}");
+    }
+}
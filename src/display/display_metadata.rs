@@ -0,0 +1,426 @@
+//! Popup listing schema-level and field-level Arrow metadata (`M` key).
+//! In the table viewers `=` sets a schema-level key: typed as `key=value`,
+//! confirmed with `y`, and written by the viewer as a new dataset version.
+
+use arrow::datatypes::Schema;
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::display::*;
+use crate::functions::set_meta::validate_key;
+
+/// A schema-level key being set with `=`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MetaEdit {
+    /// `key=value` as typed so far
+    Typing(String),
+    /// Waiting for `y` before the write
+    Confirm { key: String, value: String },
+}
+
+/// State of the metadata popup: scroll position, an optional filter and,
+/// when editable, the `=` edit.
+pub(crate) struct MetadataPopup {
+    entries: Vec<MetadataEntry>,
+    scroll: usize,
+    query: String,
+    editing: bool,
+    editable: bool,
+    edit: Option<MetaEdit>,
+    /// Confirmed key and value for the viewer to write
+    write: Option<(String, String)>,
+    /// Outcome of the last edit, shown above the entries
+    status: Option<String>,
+}
+
+impl MetadataPopup {
+    pub(crate) fn new(schema: &Schema) -> Self {
+        Self {
+            entries: schema_metadata_entries(schema),
+            scroll: 0,
+            query: String::new(),
+            editing: false,
+            editable: false,
+            edit: None,
+            write: None,
+            status: None,
+        }
+    }
+
+    /// Accept `=` edits of schema-level keys, for a viewer that writes them.
+    pub(crate) fn editable(self) -> Self {
+        Self {
+            editable: true,
+            ..self
+        }
+    }
+
+    /// The key and value confirmed since the last call, to be written.
+    pub(crate) fn take_write(&mut self) -> Option<(String, String)> {
+        self.write.take()
+    }
+
+    /// Record a written key: its entry is updated and the new version named.
+    pub(crate) fn written(&mut self, key: &str, value: &str, version: u64) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|e| e.scope == "schema" && e.key == key)
+        {
+            Some(entry) => entry.value = value.to_string(),
+            None => {
+                let at = self
+                    .entries
+                    .iter()
+                    .position(|e| e.scope != "schema" || e.key.as_str() > key)
+                    .unwrap_or(self.entries.len());
+                self.entries.insert(
+                    at,
+                    MetadataEntry {
+                        scope: "schema".to_string(),
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    },
+                );
+            }
+        }
+        self.status = Some(format!("{key} set, written as version {version}"));
+    }
+
+    /// Record a write that failed.
+    pub(crate) fn failed(&mut self, message: String) {
+        self.status = Some(message);
+    }
+
+    /// Entries matching the current filter (case-insensitive, any of
+    /// scope/key/value).
+    fn visible(&self) -> Vec<&MetadataEntry> {
+        if self.query.is_empty() {
+            return self.entries.iter().collect();
+        }
+        let q = self.query.to_lowercase();
+        self.entries
+            .iter()
+            .filter(|e| {
+                e.scope.to_lowercase().contains(&q)
+                    || e.key.to_lowercase().contains(&q)
+                    || e.value.to_lowercase().contains(&q)
+            })
+            .collect()
+    }
+
+    /// Apply a key press. Returns `false` when the popup should close.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> bool {
+        if let Some(edit) = self.edit.take() {
+            self.edit = self.edit_key(edit, code);
+            return true;
+        }
+        if self.editing {
+            match code {
+                KeyCode::Enter => self.editing = false,
+                KeyCode::Esc => {
+                    self.editing = false;
+                    self.query.clear();
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                }
+                KeyCode::Char(c) => self.query.push(c),
+                _ => {}
+            }
+            self.scroll = 0;
+            return true;
+        }
+
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('M') => return false,
+            KeyCode::Char('/') => {
+                self.editing = true;
+                self.query.clear();
+                self.scroll = 0;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll += 1,
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll += 10,
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::Char('=') if self.editable => {
+                self.edit = Some(MetaEdit::Typing(String::new()));
+                self.status = None;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// The `=` edit after `code`; `None` once it is confirmed or dropped.
+    fn edit_key(&mut self, edit: MetaEdit, code: KeyCode) -> Option<MetaEdit> {
+        match edit {
+            MetaEdit::Typing(mut text) => match code {
+                KeyCode::Esc => None,
+                KeyCode::Enter => {
+                    let Some((key, value)) = text.split_once('=') else {
+                        self.status = Some("type the key, =, then the value".to_string());
+                        return Some(MetaEdit::Typing(text));
+                    };
+                    if let Err(e) = validate_key(key) {
+                        self.status = Some(format!("{e:#}"));
+                        return Some(MetaEdit::Typing(text));
+                    }
+                    self.status = None;
+                    Some(MetaEdit::Confirm {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    })
+                }
+                KeyCode::Backspace => {
+                    text.pop();
+                    Some(MetaEdit::Typing(text))
+                }
+                KeyCode::Char(c) => {
+                    text.push(c);
+                    Some(MetaEdit::Typing(text))
+                }
+                _ => Some(MetaEdit::Typing(text)),
+            },
+            MetaEdit::Confirm { key, value } => {
+                if matches!(code, KeyCode::Char('y' | 'Y')) {
+                    self.write = Some((key, value));
+                } else {
+                    self.status = Some("not written".to_string());
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Split `text` into lines of at most `width` characters, honouring
+/// embedded newlines.
+fn wrap_chars(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut out = Vec::new();
+    for raw in text.split('\n') {
+        let chars: Vec<char> = raw.chars().collect();
+        if chars.is_empty() {
+            out.push(String::new());
+            continue;
+        }
+        for chunk in chars.chunks(width) {
+            out.push(chunk.iter().collect());
+        }
+    }
+    out
+}
+
+pub(crate) fn centered(area: Rect, percent: u16) -> Rect {
+    let w = (u32::from(area.width) * u32::from(percent) / 100) as u16;
+    let h = (u32::from(area.height) * u32::from(percent) / 100) as u16;
+    Rect {
+        x: area.x + (area.width - w) / 2,
+        y: area.y + (area.height - h) / 2,
+        width: w,
+        height: h,
+    }
+}
+
+/// Draw the popup over the current frame. The scroll offset is clamped to the
+/// wrapped content, which is only known once the popup width is.
+pub(crate) fn render_metadata_popup(f: &mut Frame, popup: &mut MetadataPopup) {
+    let area = centered(f.area(), 80);
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let inner_height = area.height.saturating_sub(2) as usize;
+
+    let visible = popup.visible();
+    let mut lines: Vec<Line> = Vec::new();
+    if let Some(status) = popup.status.as_deref() {
+        lines.push(Line::from(Span::styled(
+            status.to_string(),
+            Style::default().fg(TEXT_WARNING),
+        )));
+    }
+    for entry in &visible {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", entry.scope),
+                Style::default().fg(TEXT_SECONDARY),
+            ),
+            Span::styled(
+                entry.key.clone(),
+                Style::default().fg(HEADER_FG).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        for chunk in wrap_chars(&entry.value, inner_width.saturating_sub(2)) {
+            lines.push(Line::from(Span::styled(
+                format!("  {chunk}"),
+                Style::default().fg(TEXT_PRIMARY),
+            )));
+        }
+    }
+    let visible_len = visible.len();
+    if visible_len == 0 {
+        let msg = if popup.entries.is_empty() {
+            "no schema or field metadata"
+        } else {
+            "no entries match the search"
+        };
+        lines.push(Line::from(msg));
+    }
+
+    let max_scroll = lines.len().saturating_sub(inner_height);
+    popup.scroll = popup.scroll.min(max_scroll);
+
+    let title = if let Some(edit) = popup.edit.as_ref() {
+        match edit {
+            MetaEdit::Typing(text) => format!(" Set schema metadata: {text}▏ "),
+            MetaEdit::Confirm { key, value } => {
+                format!(" Write {key} = {value} as a new version? y/n ")
+            }
+        }
+    } else if popup.editing || !popup.query.is_empty() {
+        let cursor = if popup.editing { "▏" } else { "" };
+        format!(
+            " Metadata /{}{} ({} of {}) ",
+            popup.query,
+            cursor,
+            visible_len,
+            popup.entries.len()
+        )
+    } else {
+        format!(" Metadata ({} entries) ", popup.entries.len())
+    };
+
+    let keys = if popup.editable {
+        " ↑↓ scroll | / search | = set key | Esc close "
+    } else {
+        " ↑↓ scroll | / search | Esc close "
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(title)
+        .title_bottom(keys);
+    let para = Paragraph::new(lines)
+        .block(block)
+        .scroll((popup.scroll.min(u16::MAX as usize) as u16, 0));
+
+    f.render_widget(Clear, area);
+    f.render_widget(para, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field};
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::collections::HashMap;
+
+    fn schema() -> Schema {
+        let field = Field::new("value", DataType::Float64, true)
+            .with_metadata(HashMap::from([("unit".to_string(), "metres".to_string())]));
+        Schema::new(vec![field]).with_metadata(HashMap::from([
+            ("rows".to_string(), "4".to_string()),
+            (
+                "provenance".to_string(),
+                format!("{{\"pipeline\":\"{}\"}}", "x".repeat(120)),
+            ),
+        ]))
+    }
+
+    fn render(popup: &mut MetadataPopup) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        terminal.draw(|f| render_metadata_popup(f, popup)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|line| line.iter().map(|c| c.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn entries_list_schema_then_fields_sorted_by_key() {
+        let entries = schema_metadata_entries(&schema());
+        let keys: Vec<_> = entries
+            .iter()
+            .map(|e| format!("{}.{}", e.scope, e.key))
+            .collect();
+        assert_eq!(keys, ["schema.provenance", "schema.rows", "value.unit"]);
+    }
+
+    #[test]
+    fn long_values_wrap_inside_the_popup() {
+        let mut popup = MetadataPopup::new(&schema());
+        let text = render(&mut popup);
+        assert!(text.contains("Metadata (3 entries)"), "{text}");
+        assert!(text.contains("{\"pipeline\""), "{text}");
+        // 120 x's cannot fit on one 44-column line.
+        let x_lines = text.lines().filter(|l| l.contains("xxxxxxxx")).count();
+        assert!(x_lines >= 3, "{text}");
+    }
+
+    #[test]
+    fn search_filters_entries_and_escape_closes() {
+        let mut popup = MetadataPopup::new(&schema());
+        assert!(popup.handle_key(KeyCode::Char('/')));
+        for c in "METRE".chars() {
+            popup.handle_key(KeyCode::Char(c));
+        }
+        popup.handle_key(KeyCode::Enter);
+        let text = render(&mut popup);
+        assert!(text.contains("/METRE (1 of 3)"), "{text}");
+        assert!(
+            text.contains("unit") && !text.contains("provenance"),
+            "{text}"
+        );
+
+        // Scrolling far past the end is clamped when drawn.
+        for _ in 0..50 {
+            popup.handle_key(KeyCode::Down);
+        }
+        render(&mut popup);
+        assert_eq!(popup.scroll, 0);
+
+        assert!(!popup.handle_key(KeyCode::Esc));
+    }
+
+    #[test]
+    fn equals_sets_a_schema_key_after_confirmation() {
+        let mut popup = MetadataPopup::new(&schema());
+        popup.handle_key(KeyCode::Char('='));
+        assert!(popup.edit.is_none(), "read-only popups ignore =");
+
+        let mut popup = MetadataPopup::new(&schema()).editable();
+        popup.handle_key(KeyCode::Char('='));
+        for c in "cols=7".chars() {
+            popup.handle_key(KeyCode::Char(c));
+        }
+        popup.handle_key(KeyCode::Enter);
+        assert!(render(&mut popup).contains("Write cols = 7 as a new version? y/n"));
+        assert!(popup.take_write().is_none());
+        popup.handle_key(KeyCode::Char('y'));
+        assert_eq!(
+            popup.take_write(),
+            Some(("cols".to_string(), "7".to_string()))
+        );
+
+        popup.written("cols", "7", 3);
+        let keys: Vec<_> = popup.entries.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, ["cols", "provenance", "rows", "unit"]);
+        assert!(render(&mut popup).contains("cols set, written as version 3"));
+
+        // an empty key is refused before the confirmation
+        popup.handle_key(KeyCode::Char('='));
+        popup.handle_key(KeyCode::Char('='));
+        popup.handle_key(KeyCode::Enter);
+        assert!(matches!(popup.edit, Some(MetaEdit::Typing(_))));
+        assert!(render(&mut popup).contains("cannot be empty"));
+    }
+}
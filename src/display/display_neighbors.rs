@@ -0,0 +1,348 @@
+//! Nearest rows to the selected row (`N` key): a metric prompt, a search on
+//! a background thread with a progress toast, then a popup of the closest
+//! rows to jump to.
+
+use anyhow::Result;
+use arrow_array::Array;
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::JoinHandle;
+
+use crate::display::display::row_label;
+use crate::display::display_metadata::centered;
+use crate::display::index_base::index_base;
+use crate::display::*;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::functions::DenseMatrix;
+use crate::functions::neighbors::{DEFAULT_NEIGHBORS, Metric, Neighbor, matrix_neighbors};
+
+const METRICS: [Metric; 2] = [Metric::Cosine, Metric::Euclidean];
+
+/// What the viewer should do after a key press in the prompt or popup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NeighborAction {
+    Stay,
+    Close,
+    /// Start the search with this metric (prompt)
+    Search(Metric),
+    /// Scroll so this batch row is on top (results)
+    Jump(usize),
+}
+
+/// Metric choice for a search from `row`.
+pub(crate) struct MetricPrompt {
+    pub row: usize,
+    selected: usize,
+}
+
+impl MetricPrompt {
+    pub(crate) fn new(row: usize) -> Self {
+        Self { row, selected: 0 }
+    }
+
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> NeighborAction {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => NeighborAction::Close,
+            KeyCode::Char('c') => NeighborAction::Search(Metric::Cosine),
+            KeyCode::Char('e') => NeighborAction::Search(Metric::Euclidean),
+            KeyCode::Up | KeyCode::Down | KeyCode::Char('k') | KeyCode::Char('j') => {
+                self.selected = 1 - self.selected;
+                NeighborAction::Stay
+            }
+            KeyCode::Enter => NeighborAction::Search(METRICS[self.selected]),
+            _ => NeighborAction::Stay,
+        }
+    }
+}
+
+pub(crate) fn render_metric_prompt(f: &mut Frame, prompt: &MetricPrompt) {
+    let area = centered(f.area(), 40);
+    let area = Rect {
+        height: area.height.min(4),
+        ..area
+    };
+    let lines: Vec<Line> = METRICS
+        .iter()
+        .enumerate()
+        .map(|(i, metric)| {
+            let style = if i == prompt.selected {
+                Style::default()
+                    .fg(TEXT_ACCENT)
+                    .add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().fg(TEXT_PRIMARY)
+            };
+            let key = &metric.label()[..1];
+            Line::from(Span::styled(format!("[{key}] {}", metric.label()), style))
+        })
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(format!(
+            " Nearest rows to row {}: distance ",
+            index_base().show(prompt.row)
+        ));
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// A search running on a background thread so the viewer stays responsive
+/// on large matrices.
+pub(crate) struct BackgroundSearch {
+    pub row: usize,
+    pub metric: Metric,
+    rows: usize,
+    done: Arc<AtomicUsize>,
+    handle: JoinHandle<Result<Vec<Neighbor>>>,
+}
+
+impl BackgroundSearch {
+    /// Start searching `matrices`, the packed matrix of each batch (cheap
+    /// clones of the shared buffers), for the rows nearest to `row`.
+    pub(crate) fn start(matrices: &[DenseMatrix], row: usize, metric: Metric) -> Self {
+        let matrices = matrices.to_vec();
+        let rows = matrices.iter().map(|m| m.list.len()).sum();
+        let done = Arc::new(AtomicUsize::new(0));
+        let progress = done.clone();
+        let handle = std::thread::spawn(move || {
+            matrix_neighbors(&matrices, row, DEFAULT_NEIGHBORS, metric, |n| {
+                progress.store(n, Ordering::Relaxed)
+            })
+        });
+        Self {
+            row,
+            metric,
+            rows,
+            done,
+            handle,
+        }
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Wait for the result; a panic in the search is reported as an error.
+    pub(crate) fn join(self) -> Result<Vec<Neighbor>> {
+        self.handle
+            .join()
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("nearest-row search panicked")))
+    }
+}
+
+/// Small progress box in the bottom-right corner, above the status bar.
+pub(crate) fn render_search_toast(f: &mut Frame, search: &BackgroundSearch) {
+    let done = search.done.load(Ordering::Relaxed);
+    let percent = (done * 100).checked_div(search.rows).unwrap_or(100);
+    let text = format!(
+        " {} neighbours of row {}: {percent}% ",
+        search.metric.label(),
+        search.row
+    );
+    let frame = f.area();
+    let width = (text.chars().count() as u16 + 2).min(frame.width);
+    let area = Rect {
+        x: frame.x + frame.width - width,
+        y: frame.y + frame.height.saturating_sub(6),
+        width,
+        height: 3.min(frame.height),
+    };
+    let toast = Paragraph::new(Span::styled(text, Style::default().fg(TEXT_ACCENT))).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_ACCENT)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(toast, area);
+}
+
+/// Results of a finished search.
+pub(crate) struct NeighborsPopup {
+    title: String,
+    /// (batch row, row label, distance), closest first; or why it failed
+    entries: Result<Vec<(usize, String, f64)>, String>,
+    selected: usize,
+}
+
+impl NeighborsPopup {
+    /// Popup for the `result` of a search from row `row` of `data`.
+    pub(crate) fn new(
+        data: &BatchSource,
+        row: usize,
+        metric: Metric,
+        result: Result<Vec<Neighbor>>,
+    ) -> Self {
+        let title = format!(
+            " Nearest rows to {} ({} distance) ",
+            row_label(data, row),
+            metric.label()
+        );
+        let entries = result
+            .map(|neighbors| {
+                neighbors
+                    .iter()
+                    .map(|n| (n.row, row_label(data, n.row), n.distance))
+                    .collect()
+            })
+            .map_err(|e| e.to_string());
+        Self {
+            title,
+            entries,
+            selected: 0,
+        }
+    }
+
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> NeighborAction {
+        let len = self.entries.as_ref().map_or(0, Vec::len);
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('N') => return NeighborAction::Close,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(len.saturating_sub(1))
+            }
+            KeyCode::Enter => {
+                if let Ok(entries) = &self.entries
+                    && let Some(&(row, _, _)) = entries.get(self.selected)
+                {
+                    return NeighborAction::Jump(row);
+                }
+            }
+            _ => {}
+        }
+        NeighborAction::Stay
+    }
+}
+
+pub(crate) fn render_neighbors_popup(f: &mut Frame, popup: &NeighborsPopup) {
+    let area = centered(f.area(), 60);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(popup.title.clone())
+        .title_bottom(" ↑↓ select | Enter jump | Esc close ");
+    f.render_widget(Clear, area);
+
+    let entries = match &popup.entries {
+        Ok(entries) => entries,
+        Err(reason) => {
+            let msg = Paragraph::new(Span::styled(
+                reason.clone(),
+                Style::default().fg(TEXT_SECONDARY),
+            ))
+            .block(block);
+            f.render_widget(msg, area);
+            return;
+        }
+    };
+
+    // Keep the selection in view.
+    let visible = area.height.saturating_sub(3) as usize;
+    let skip = (popup.selected + 1).saturating_sub(visible);
+    let rows: Vec<Row> = entries
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(visible)
+        .map(|(rank, (_, label, distance))| {
+            let style = if rank == popup.selected {
+                Style::default()
+                    .fg(TEXT_ACCENT)
+                    .add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().fg(TEXT_PRIMARY)
+            };
+            Row::new(vec![
+                Cell::from((rank + 1).to_string()),
+                Cell::from(label.clone()),
+                Cell::from(format!("{distance:.6}")),
+            ])
+            .style(style)
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(5),
+            Constraint::Min(8),
+            Constraint::Length(12),
+        ],
+    )
+    .header(
+        Row::new(["rank", "row", "distance"])
+            .style(Style::default().fg(HEADER_FG).add_modifier(Modifier::BOLD)),
+    )
+    .block(block)
+    .column_spacing(1);
+    f.render_widget(table, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functions::functions::expand_dense_row_major;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::{FixedSizeListArray, Float64Array, RecordBatch};
+    use ratatui::{Terminal, backend::TestBackend};
+
+    #[test]
+    fn search_runs_in_the_background_and_jumps_to_a_neighbour() {
+        // Row r is (r, 1): euclidean neighbours of row 5 are 4 and 6, then 3 and 7.
+        let values: Vec<f64> = (0..10).flat_map(|r| [r as f64, 1.0]).collect();
+        let list = FixedSizeListArray::try_new(
+            Arc::new(Field::new("item", DataType::Float64, true)),
+            2,
+            Arc::new(Float64Array::from(values)),
+            None,
+        )
+        .unwrap();
+        let schema = Schema::new(vec![Field::new("vector", list.data_type().clone(), false)]);
+        let raw = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(list)]).unwrap();
+        let matrix = DenseMatrix::from_batch(&raw, 0..2).unwrap();
+        let data = BatchSource::from(expand_dense_row_major(&raw).unwrap());
+
+        let mut prompt = MetricPrompt::new(5);
+        assert_eq!(prompt.handle_key(KeyCode::Down), NeighborAction::Stay);
+        let NeighborAction::Search(metric) = prompt.handle_key(KeyCode::Enter) else {
+            panic!("Enter should start the search");
+        };
+        assert_eq!(metric, Metric::Euclidean);
+
+        let search = BackgroundSearch::start(&[matrix], prompt.row, metric);
+        while !search.is_finished() {
+            std::thread::yield_now();
+        }
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        let screen = |terminal: &Terminal<TestBackend>| -> String {
+            let buffer = terminal.backend().buffer();
+            buffer.content.iter().map(|c| c.symbol()).collect()
+        };
+        terminal.draw(|f| render_search_toast(f, &search)).unwrap();
+        assert!(screen(&terminal).contains("row 5: 100%"));
+
+        let (row, metric) = (search.row, search.metric);
+        let mut popup = NeighborsPopup::new(&data, row, metric, search.join());
+        terminal
+            .draw(|f| render_neighbors_popup(f, &popup))
+            .unwrap();
+        let text = screen(&terminal);
+        assert!(
+            text.contains("Nearest rows to 5 (euclidean distance)"),
+            "{text}"
+        );
+        assert!(text.contains("1.000000"), "{text}");
+
+        popup.handle_key(KeyCode::Down);
+        popup.handle_key(KeyCode::Down);
+        assert_eq!(popup.handle_key(KeyCode::Enter), NeighborAction::Jump(3));
+        assert_eq!(popup.handle_key(KeyCode::Esc), NeighborAction::Close);
+    }
+}
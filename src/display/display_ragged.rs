@@ -0,0 +1,284 @@
+use arrow::array::*;
+use arrow::datatypes::DataType;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+};
+
+use crate::display::display::{
+    ScrollWindow, metadata_text, render_table_scrollbars, right, row_label_text, row_label_width,
+};
+use crate::display::display_bookmarks::Bookmarks;
+use crate::display::*;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::functions::{LengthSummary, length_summary, list_type_name, row_length};
+
+/// Width of the `len` column.
+const LEN_WIDTH: u16 = 6;
+
+/// Render a variable-length list column (LanceLayout::RaggedList).
+///
+/// Layout:
+/// - Top: metadata
+/// - Middle: `Row | len | values` with as many leading values as fit and an
+///   ellipsis for the rest, next to a row-length panel
+/// - Bottom: status bar
+///
+/// Returns the rows window drawn.
+pub(crate) fn render_ragged_ui(
+    f: &mut Frame,
+    data: &BatchSource,
+    row_start: usize,
+    source: SourceWindow,
+    bookmarks: &Bookmarks,
+) -> Option<ScrollWindow> {
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // metadata
+            Constraint::Min(0),    // table + lengths panel
+            Constraint::Length(3), // status
+        ])
+        .split(f.area());
+    let content_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+        .split(main_chunks[1]);
+
+    let num_rows = data.num_rows();
+    let schema = data.schema();
+    let list_idx = schema
+        .fields()
+        .iter()
+        .position(|f| f.name() != ROWID_COLUMN)
+        .unwrap_or(0);
+    let field = schema.field(list_idx).clone();
+
+    let meta = Paragraph::new(Span::styled(
+        metadata_text(data, num_rows, 1, "    "),
+        Style::default().fg(TEXT_SECONDARY),
+    ))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_ACCENT))
+            .title(" Metadata "),
+    );
+    f.render_widget(meta, main_chunks[0]);
+
+    // borders + header
+    let table_area = content_chunks[0];
+    let max_visible_rows = table_area.height.saturating_sub(3) as usize;
+    let end_row = (row_start + max_visible_rows).min(num_rows);
+    let label_width = row_label_width(data);
+    let values_width = table_area
+        .width
+        .saturating_sub(2 + label_width + LEN_WIDTH + 2) as usize;
+
+    let header = Row::new(vec![
+        Cell::from(right("Row", label_width)),
+        Cell::from("len"),
+        Cell::from(format!(
+            "{} ({})",
+            field.name(),
+            list_type_name(field.data_type())
+        )),
+    ])
+    .style(
+        Style::default()
+            .fg(HEADER_FG)
+            .bg(HEADER_BG)
+            .add_modifier(Modifier::BOLD),
+    );
+    let rows: Vec<Row> = (row_start..end_row)
+        .map(|r| {
+            let bg = if r % 2 == 0 { EVEN_ROW_BG } else { ODD_ROW_BG };
+            let values = data
+                .locate(r)
+                .and_then(|(batch, local)| list_values(batch.column(list_idx), local));
+            let (len, preview) = match values {
+                Some(values) => (
+                    values.len().to_string(),
+                    preview_values(&values, values_width),
+                ),
+                None => ("-".to_string(), "NULL".to_string()),
+            };
+            Row::new(vec![
+                Cell::from(row_label_text(data, r, label_width, bookmarks)).style(
+                    Style::default()
+                        .fg(TEXT_SECONDARY)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Cell::from(len).style(Style::default().fg(TEXT_ACCENT)),
+                Cell::from(preview).style(Style::default().fg(TEXT_PRIMARY)),
+            ])
+            .style(Style::default().bg(bg))
+        })
+        .collect();
+
+    let title = format!(
+        " Lance Ragged Rows (rows {}) ",
+        source.span(row_start..end_row)
+    );
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(label_width),
+            Constraint::Length(LEN_WIDTH),
+            Constraint::Min(0),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_PRIMARY))
+            .title(title),
+    )
+    .column_spacing(1);
+    f.render_widget(table, table_area);
+
+    let rows_window = ScrollWindow {
+        start: row_start,
+        len: max_visible_rows,
+        total: num_rows,
+    };
+    render_table_scrollbars(
+        f,
+        table_area,
+        rows_window,
+        ScrollWindow {
+            start: 0,
+            len: 1,
+            total: 1,
+        },
+    );
+
+    render_lengths_panel(f, data, list_idx, content_chunks[1]);
+
+    let status = format!(
+        " {} | {} rows | ragged list column | mode: ragged | ↑↓ scroll rows | M metadata | L log | q quit ",
+        rows_window.label(),
+        num_rows
+    );
+    let status_widget = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(Span::styled(status, Style::default().fg(TEXT_ACCENT)));
+    f.render_widget(status_widget, main_chunks[2]);
+    Some(rows_window)
+}
+
+/// Row-length distribution of the loaded rows of list column `list_idx`.
+fn render_lengths_panel(f: &mut Frame, data: &BatchSource, list_idx: usize, area: Rect) {
+    let lengths: Vec<usize> = data
+        .batches()
+        .iter()
+        .flat_map(|batch| {
+            let list = batch.column(list_idx);
+            (0..list.len()).filter_map(|r| row_length(list, r))
+        })
+        .collect();
+    let null_rows = data.num_rows() - lengths.len();
+
+    let mut lines = vec![Line::from("")];
+    match length_summary(&lengths) {
+        Some(LengthSummary { min, mean, max }) => {
+            lines.push(Line::from(format!("min:    {min}")));
+            lines.push(Line::from(format!("mean:   {mean:.2}")));
+            lines.push(Line::from(format!("max:    {max}")));
+            lines.push(Line::from(format!(
+                "values: {}",
+                lengths.iter().sum::<usize>()
+            )));
+        }
+        None => lines.push(Line::from("no non-null rows")),
+    }
+    if null_rows > 0 {
+        lines.push(Line::from(format!("null rows: {null_rows}")));
+    }
+
+    let panel = Paragraph::new(lines)
+        .style(Style::default().fg(TEXT_SECONDARY))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_PRIMARY))
+                .title(" Row lengths "),
+        );
+    f.render_widget(panel, area);
+}
+
+/// Values of row `r` of a List/LargeList column as Float64; `None` for a
+/// null row.
+fn list_values(list: &ArrayRef, r: usize) -> Option<Float64Array> {
+    if list.is_null(r) {
+        return None;
+    }
+    let values = match list.data_type() {
+        DataType::List(_) => list.as_list::<i32>().value(r),
+        DataType::LargeList(_) => list.as_list::<i64>().value(r),
+        _ => return None,
+    };
+    let values = arrow::compute::cast(&values, &DataType::Float64).ok()?;
+    Some(
+        values
+            .as_primitive::<arrow::datatypes::Float64Type>()
+            .clone(),
+    )
+}
+
+/// Leading values of a row, comma separated, cut off with `…` once they no
+/// longer fit in `width` cells.
+pub(crate) fn preview_values(values: &Float64Array, width: usize) -> String {
+    let mut out = String::new();
+    for (i, v) in values.iter().enumerate() {
+        let item = match v {
+            Some(v) => format!("{v:.4}"),
+            None => "NULL".to_string(),
+        };
+        let sep = if i == 0 { "" } else { ", " };
+        // Keep room for ", …" unless this is the last value.
+        let reserve = if i + 1 < values.len() { 3 } else { 0 };
+        if out.chars().count() + sep.len() + item.len() + reserve > width {
+            out.push_str(if i == 0 { "…" } else { ", …" });
+            return out;
+        }
+        out.push_str(sep);
+        out.push_str(&item);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn previews_end_in_an_ellipsis_when_cut() {
+        let values = Float64Array::from(vec![1.0, 2.5, -3.0]);
+        assert_eq!(preview_values(&values, 80), "1.0000, 2.5000, -3.0000");
+        assert_eq!(preview_values(&values, 20), "1.0000, 2.5000, …");
+        assert_eq!(preview_values(&values, 3), "…");
+        assert_eq!(
+            preview_values(&Float64Array::from(Vec::<f64>::new()), 10),
+            ""
+        );
+    }
+
+    #[test]
+    fn length_summary_reports_min_mean_max() {
+        assert_eq!(
+            length_summary(&[2, 5, 2]),
+            Some(LengthSummary {
+                min: 2,
+                mean: 3.0,
+                max: 5
+            })
+        );
+        assert_eq!(length_summary(&[]), None);
+    }
+}
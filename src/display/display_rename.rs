@@ -0,0 +1,120 @@
+//! Rename the current feature column for this session (`R` key): a one-line
+//! prompt whose result is stored as `feature_name` metadata on the viewed
+//! batch. An empty name restores the column's own name.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::display::display_metadata::centered;
+use crate::display::*;
+
+/// What the viewer should do after a key press in the prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RenameAction {
+    Stay,
+    Close,
+    /// Set the display name; `None` clears it
+    Apply(Option<String>),
+}
+
+/// Text entry for the new name of batch column `col_idx`.
+pub(crate) struct RenamePrompt {
+    pub col_idx: usize,
+    /// Arrow name of the column, shown in the title
+    column: String,
+    input: String,
+}
+
+impl RenamePrompt {
+    /// Start editing from the column's current display name.
+    pub(crate) fn new(col_idx: usize, column: &str, current: &str) -> Self {
+        Self {
+            col_idx,
+            column: column.to_string(),
+            input: if current == column {
+                String::new()
+            } else {
+                current.to_string()
+            },
+        }
+    }
+
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> RenameAction {
+        match code {
+            KeyCode::Esc => RenameAction::Close,
+            KeyCode::Enter => {
+                let name = self.input.trim();
+                RenameAction::Apply((!name.is_empty()).then(|| name.to_string()))
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                RenameAction::Stay
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                RenameAction::Stay
+            }
+            _ => RenameAction::Stay,
+        }
+    }
+}
+
+pub(crate) fn render_rename_prompt(f: &mut Frame, prompt: &RenamePrompt) {
+    let area = centered(f.area(), 50);
+    let area = Rect {
+        height: area.height.min(3),
+        ..area
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(format!(" Rename {} ", prompt.column))
+        .title_bottom(" Enter apply (empty resets) | Esc cancel ");
+    let line = Line::from(vec![
+        Span::styled(prompt.input.clone(), Style::default().fg(TEXT_PRIMARY)),
+        Span::styled("▏", Style::default().fg(TEXT_ACCENT)),
+    ]);
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(line).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    #[test]
+    fn typing_a_name_applies_it_and_an_empty_name_resets() {
+        let mut prompt = RenamePrompt::new(2, "col_1", "col_1");
+        for c in "aeg".chars() {
+            assert_eq!(prompt.handle_key(KeyCode::Char(c)), RenameAction::Stay);
+        }
+        prompt.handle_key(KeyCode::Backspace);
+        prompt.handle_key(KeyCode::Char('e'));
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+        terminal.draw(|f| render_rename_prompt(f, &prompt)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content.iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Rename col_1"), "{text}");
+        assert!(text.contains("aee"), "{text}");
+        assert_eq!(
+            prompt.handle_key(KeyCode::Enter),
+            RenameAction::Apply(Some("aee".to_string()))
+        );
+
+        // An existing name is the starting text; clearing it resets.
+        let mut prompt = RenamePrompt::new(2, "col_1", "age");
+        for _ in 0..3 {
+            prompt.handle_key(KeyCode::Backspace);
+        }
+        assert_eq!(prompt.handle_key(KeyCode::Enter), RenameAction::Apply(None));
+        assert_eq!(prompt.handle_key(KeyCode::Esc), RenameAction::Close);
+    }
+}
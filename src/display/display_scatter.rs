@@ -0,0 +1,466 @@
+//! Scatter plot of two marked feature columns (`x` to mark, `v` to plot):
+//! braille dots over all loaded rows, shaded by how many points share a dot,
+//! or coloured by the label column (`c` in the table).
+
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use arrow::util::display::array_value_to_string;
+use arrow_array::{Array, Float64Array, RecordBatch};
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    symbols::Marker,
+    text::Span,
+    widgets::{Axis, Block, Borders, Chart, Clear, Dataset, GraphType, LegendPosition, Paragraph},
+};
+use std::collections::HashMap;
+
+use crate::display::display::display_name;
+use crate::display::*;
+
+/// Colours of the most frequent labels; the rest share [`OTHER_LABEL`].
+const LABEL_COLORS: [Color; 7] = [
+    TEXT_SECONDARY,
+    HEADER_FG,
+    TEXT_ACCENT,
+    SPARSE_ASTERISK,
+    Color::Rgb(189, 147, 249), // Purple
+    Color::Rgb(241, 250, 140), // Yellow
+    Color::Rgb(255, 85, 85),   // Red
+];
+const OTHER_LABEL: Color = BORDER_PRIMARY;
+
+/// Points per dot (at least) for each density shade, lightest first.
+const DENSITY_LEVELS: [(usize, &str, Color); 4] = [
+    (1, "1", BORDER_ACCENT),
+    (2, "2–4", TEXT_ACCENT),
+    (5, "5–19", HEADER_FG),
+    (20, "20+", SPARSE_ASTERISK),
+];
+
+/// Fraction of the visible span moved per arrow key.
+const PAN_STEP: f64 = 0.1;
+/// Span divisor per `+` key.
+const ZOOM_STEP: f64 = 1.25;
+
+/// Per-point label of the designated label column, as an index into the
+/// legend.
+struct Labels {
+    column: String,
+    /// Most frequent first, at most `LABEL_COLORS.len()` plus "other"
+    legend: Vec<String>,
+    of_point: Vec<usize>,
+}
+
+/// State of the scatter view.
+pub(crate) struct ScatterView {
+    x_name: String,
+    y_name: String,
+    points: Vec<(f64, f64)>,
+    labels: Option<Labels>,
+    /// Rows left out because a coordinate was NaN or infinite
+    non_finite: usize,
+    /// Rows left out because a coordinate was null
+    nulls: usize,
+    full: [[f64; 2]; 2],
+    /// Visible x and y bounds
+    view: [[f64; 2]; 2],
+    color_by_label: bool,
+}
+
+impl ScatterView {
+    /// Scatter of batch columns `x_col` against `y_col`, optionally coloured
+    /// by `label_col`. `None` when either column is not numeric.
+    pub(crate) fn new(
+        batch: &RecordBatch,
+        x_col: usize,
+        y_col: usize,
+        label_col: Option<usize>,
+    ) -> Option<Self> {
+        let as_f64 = |idx: usize| -> Option<Float64Array> {
+            let col = batch.column(idx);
+            if !col.data_type().is_numeric() {
+                return None;
+            }
+            let values = cast(col, &DataType::Float64).ok()?;
+            values.as_any().downcast_ref::<Float64Array>().cloned()
+        };
+        let (xs, ys) = (as_f64(x_col)?, as_f64(y_col)?);
+
+        let mut points = Vec::with_capacity(batch.num_rows());
+        let mut rows = Vec::with_capacity(batch.num_rows());
+        let (mut non_finite, mut nulls) = (0, 0);
+        for r in 0..batch.num_rows() {
+            if xs.is_null(r) || ys.is_null(r) {
+                nulls += 1;
+                continue;
+            }
+            let (x, y) = (xs.value(r), ys.value(r));
+            if !x.is_finite() || !y.is_finite() {
+                non_finite += 1;
+                continue;
+            }
+            points.push((x, y));
+            rows.push(r);
+        }
+
+        let labels = label_col.map(|idx| {
+            let col = batch.column(idx);
+            let text = |r: usize| {
+                if col.is_null(r) {
+                    "null".to_string()
+                } else {
+                    array_value_to_string(col, r).unwrap_or_default()
+                }
+            };
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for &r in &rows {
+                *counts.entry(text(r)).or_default() += 1;
+            }
+            let mut legend: Vec<(String, usize)> = counts.into_iter().collect();
+            legend.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let mut legend: Vec<String> = legend.into_iter().map(|(v, _)| v).collect();
+            if legend.len() > LABEL_COLORS.len() {
+                legend.truncate(LABEL_COLORS.len());
+                legend.push("other".to_string());
+            }
+            let of_point = rows
+                .iter()
+                .map(|&r| {
+                    let value = text(r);
+                    legend
+                        .iter()
+                        .position(|l| *l == value)
+                        .unwrap_or(LABEL_COLORS.len())
+                })
+                .collect();
+            Labels {
+                column: display_name(batch.schema().field(idx)).to_string(),
+                legend,
+                of_point,
+            }
+        });
+
+        let full = [
+            padded_range(points.iter().map(|p| p.0)),
+            padded_range(points.iter().map(|p| p.1)),
+        ];
+        let schema = batch.schema();
+        Some(Self {
+            x_name: display_name(schema.field(x_col)).to_string(),
+            y_name: display_name(schema.field(y_col)).to_string(),
+            color_by_label: labels.as_ref().is_some_and(|l| l.legend.len() > 1),
+            points,
+            labels,
+            non_finite,
+            nulls,
+            full,
+            view: full,
+        })
+    }
+
+    /// Apply a key press. Returns `false` when the view should close.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> bool {
+        let pan = |[lo, hi]: &mut [f64; 2], dir: f64| {
+            let step = (*hi - *lo) * PAN_STEP * dir;
+            *lo += step;
+            *hi += step;
+        };
+        let zoom = |[lo, hi]: &mut [f64; 2], factor: f64| {
+            let (mid, half) = ((*lo + *hi) / 2.0, (*hi - *lo) / 2.0 / factor);
+            *lo = mid - half;
+            *hi = mid + half;
+        };
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            KeyCode::Left | KeyCode::Char('h') => pan(&mut self.view[0], -1.0),
+            KeyCode::Right | KeyCode::Char('l') => pan(&mut self.view[0], 1.0),
+            KeyCode::Down | KeyCode::Char('j') => pan(&mut self.view[1], -1.0),
+            KeyCode::Up | KeyCode::Char('k') => pan(&mut self.view[1], 1.0),
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.view.iter_mut().for_each(|axis| zoom(axis, ZOOM_STEP))
+            }
+            KeyCode::Char('-') => self
+                .view
+                .iter_mut()
+                .for_each(|axis| zoom(axis, 1.0 / ZOOM_STEP)),
+            KeyCode::Char('0') | KeyCode::Home => self.view = self.full,
+            KeyCode::Char('c') => {
+                self.color_by_label =
+                    !self.color_by_label && self.labels.as_ref().is_some_and(|l| l.legend.len() > 1)
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// The labels dots are coloured by, when label colouring is on.
+    fn coloring(&self) -> Option<&Labels> {
+        self.labels.as_ref().filter(|_| self.color_by_label)
+    }
+
+    /// Visible points binned into `cols` × `rows` dots (braille resolution of
+    /// the plot area). Each occupied dot yields its centre, grouped by shade:
+    /// density level, or label when colouring by label.
+    fn dots(&self, cols: usize, rows: usize) -> Vec<Vec<(f64, f64)>> {
+        let [[x0, x1], [y0, y1]] = self.view;
+        let labels = self.coloring();
+        let groups = labels.map_or(1, |l| l.legend.len());
+        let (cols, rows) = (cols.max(1), rows.max(1));
+        // Per dot: point count per group
+        let mut bins: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (i, &(x, y)) in self.points.iter().enumerate() {
+            if x < x0 || x > x1 || y < y0 || y > y1 {
+                continue;
+            }
+            let cx = (((x - x0) / (x1 - x0)) * cols as f64).min(cols as f64 - 1.0) as usize;
+            let cy = (((y - y0) / (y1 - y0)) * rows as f64).min(rows as f64 - 1.0) as usize;
+            let group = labels.map_or(0, |l| l.of_point[i]);
+            bins.entry((cx, cy)).or_insert_with(|| vec![0; groups])[group] += 1;
+        }
+
+        let shades = if labels.is_some() {
+            groups
+        } else {
+            DENSITY_LEVELS.len()
+        };
+        let mut out = vec![Vec::new(); shades];
+        let mut cells: Vec<_> = bins.into_iter().collect();
+        cells.sort_by_key(|(cell, _)| *cell);
+        for ((cx, cy), counts) in cells {
+            let centre = (
+                x0 + (cx as f64 + 0.5) / cols as f64 * (x1 - x0),
+                y0 + (cy as f64 + 0.5) / rows as f64 * (y1 - y0),
+            );
+            let shade = if labels.is_some() {
+                // The dot takes the colour of its most common label.
+                (0..groups)
+                    .max_by_key(|&g| (counts[g], usize::MAX - g))
+                    .unwrap_or(0)
+            } else {
+                DENSITY_LEVELS
+                    .iter()
+                    .rposition(|&(min, _, _)| counts[0] >= min)
+                    .unwrap_or(0)
+            };
+            out[shade].push(centre);
+        }
+        out
+    }
+}
+
+/// Data range widened by 2% on each side (±0.5 when all values are equal,
+/// 0..1 when there are none).
+fn padded_range(values: impl Iterator<Item = f64>) -> [f64; 2] {
+    let (lo, hi) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    if lo > hi {
+        return [0.0, 1.0];
+    }
+    if lo == hi {
+        return [lo - 0.5, hi + 0.5];
+    }
+    let pad = (hi - lo) * 0.02;
+    [lo - pad, hi + pad]
+}
+
+fn axis_labels([lo, hi]: [f64; 2]) -> Vec<Span<'static>> {
+    let mid = (lo + hi) / 2.0;
+    [lo, mid, hi]
+        .into_iter()
+        .map(|v| Span::styled(format!("{v:.4}"), Style::default().fg(TEXT_SECONDARY)))
+        .collect()
+}
+
+/// Draw the scatter view over the whole frame.
+pub(crate) fn render_scatter(f: &mut Frame, view: &ScatterView) {
+    let area = f.area();
+    let coloring = view
+        .coloring()
+        .map(|labels| format!(", colour: {}", labels.column))
+        .unwrap_or_default();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(format!(
+            " Scatter: {} vs {} ({} points{coloring}) ",
+            view.y_name,
+            view.x_name,
+            view.points.len()
+        ))
+        .title_bottom(" ←→↑↓ pan | +/- zoom | 0 reset | c label colours | Esc back ");
+
+    // Braille packs 2×4 dots per cell; leave room for the y labels and axes.
+    let plot_cols = area.width.saturating_sub(14) as usize * 2;
+    let plot_rows = area.height.saturating_sub(5) as usize * 4;
+    let dots = view.dots(plot_cols, plot_rows);
+
+    let datasets: Vec<Dataset> = dots
+        .iter()
+        .enumerate()
+        .filter(|(_, points)| !points.is_empty())
+        .map(|(shade, points)| {
+            let (name, color) = match view.coloring() {
+                Some(labels) => (
+                    labels.legend[shade].clone(),
+                    LABEL_COLORS.get(shade).copied().unwrap_or(OTHER_LABEL),
+                ),
+                None => {
+                    let (_, name, color) = DENSITY_LEVELS[shade];
+                    (name.to_string(), color)
+                }
+            };
+            Dataset::default()
+                .name(name)
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Scatter)
+                .style(Style::default().fg(color))
+                .data(points)
+        })
+        .collect();
+
+    let chart = Chart::new(datasets)
+        .block(block)
+        .legend_position(Some(LegendPosition::TopLeft))
+        .hidden_legend_constraints((
+            ratatui::layout::Constraint::Percentage(40),
+            ratatui::layout::Constraint::Percentage(60),
+        ))
+        .x_axis(
+            Axis::default()
+                .title(Span::styled(
+                    view.x_name.clone(),
+                    Style::default().fg(HEADER_FG),
+                ))
+                .style(Style::default().fg(BORDER_PRIMARY))
+                .bounds(view.view[0])
+                .labels(axis_labels(view.view[0])),
+        )
+        .y_axis(
+            Axis::default()
+                .title(Span::styled(
+                    view.y_name.clone(),
+                    Style::default().fg(HEADER_FG),
+                ))
+                .style(Style::default().fg(BORDER_PRIMARY))
+                .bounds(view.view[1])
+                .labels(axis_labels(view.view[1])),
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(chart, area);
+
+    if view.non_finite + view.nulls > 0 {
+        let note = format!(
+            " dropped: {} NaN/Inf, {} null ",
+            view.non_finite, view.nulls
+        );
+        let width = (note.chars().count() as u16).min(area.width.saturating_sub(2));
+        let corner = Rect {
+            x: area.x + area.width.saturating_sub(width + 1),
+            y: area.y + 1,
+            width,
+            height: 1.min(area.height),
+        };
+        f.render_widget(
+            Paragraph::new(Span::styled(note, Style::default().fg(SPARSE_ASTERISK))),
+            corner,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{Field, Schema};
+    use arrow_array::{ArrayRef, StringArray};
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::sync::Arc;
+
+    fn batch() -> RecordBatch {
+        let x: Vec<Option<f64>> = (0..50)
+            .map(|i| Some(i as f64))
+            .chain([Some(f64::NAN), None, Some(1.0)])
+            .collect();
+        let y: Vec<Option<f64>> = (0..50)
+            .map(|i| Some((i * 2) as f64))
+            .chain([Some(1.0), Some(1.0), Some(f64::INFINITY)])
+            .collect();
+        let label: Vec<&str> = (0..53).map(|i| if i < 25 { "a" } else { "b" }).collect();
+        let schema = Schema::new(vec![
+            Field::new("col_0", DataType::Float64, true),
+            Field::new("col_1", DataType::Float64, true),
+            Field::new("label", DataType::Utf8, false),
+        ]);
+        let cols: Vec<ArrayRef> = vec![
+            Arc::new(Float64Array::from(x)),
+            Arc::new(Float64Array::from(y)),
+            Arc::new(StringArray::from(label)),
+        ];
+        RecordBatch::try_new(Arc::new(schema), cols).unwrap()
+    }
+
+    fn render(view: &ScatterView) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| render_scatter(f, view)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|line| line.iter().map(|c| c.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn drops_non_finite_points_and_labels_axes() {
+        let view = ScatterView::new(&batch(), 0, 1, None).unwrap();
+        assert_eq!(view.points.len(), 50);
+        assert_eq!((view.non_finite, view.nulls), (2, 1));
+        // 0..49 padded by 2% on each side.
+        let [lo, hi] = view.full[0];
+        assert!((lo + 0.98).abs() < 1e-9 && (hi - 49.98).abs() < 1e-9);
+
+        let text = render(&view);
+        assert!(
+            text.contains("Scatter: col_1 vs col_0 (50 points)"),
+            "{text}"
+        );
+        assert!(text.contains("dropped: 2 NaN/Inf, 1 null"), "{text}");
+        assert!(
+            text.contains("-0.9800") && text.contains("49.9800"),
+            "{text}"
+        );
+        assert!(text.chars().any(|c| ('\u{2801}'..='\u{28ff}').contains(&c)));
+
+        // String columns cannot be plotted.
+        assert!(ScatterView::new(&batch(), 0, 2, None).is_none());
+    }
+
+    #[test]
+    fn zooming_panning_and_label_colours() {
+        let mut view = ScatterView::new(&batch(), 0, 1, Some(2)).unwrap();
+        let legend = &view.labels.as_ref().unwrap().legend;
+        assert_eq!(legend, &["a", "b"], "25 points each, ties by value");
+        assert!(view.color_by_label);
+        let dots = view.dots(10, 10);
+        assert_eq!(dots.len(), 2);
+        assert!(!dots[0].is_empty() && !dots[1].is_empty());
+
+        view.handle_key(KeyCode::Char('+'));
+        let [lo, hi] = view.view[0];
+        assert!((hi - lo - 50.96 / ZOOM_STEP).abs() < 1e-9);
+        view.handle_key(KeyCode::Right);
+        assert!(view.view[0][0] > lo);
+        view.handle_key(KeyCode::Char('0'));
+        assert_eq!(view.view, view.full);
+
+        // Without label colours, dots are shaded by density.
+        view.handle_key(KeyCode::Char('c'));
+        let dots = view.dots(1, 1);
+        assert_eq!(dots[3].len(), 1, "all 50 points share the single dot");
+        assert!(!view.handle_key(KeyCode::Esc));
+    }
+}
@@ -0,0 +1,459 @@
+//! Viewer for per-record sparse structs (LanceLayout::SparsePerRow): one
+//! record per table row, and an `Enter` popup listing the selected record's
+//! entries.
+
+use arrow_array::StructArray;
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+};
+
+use crate::display::display::{
+    ScrollWindow, metadata_text, render_table_scrollbars, right, row_label, row_label_text,
+    row_label_width,
+};
+use crate::display::display_bookmarks::Bookmarks;
+use crate::display::display_metadata::centered;
+use crate::display::index_base::index_base;
+use crate::display::*;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::functions::{
+    SparseEntry, SparseRecord, SparseRecordKind, sparse_record, sparse_records,
+};
+
+/// Index range and value statistics of one record.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RecordSummary {
+    pub nnz: usize,
+    pub rows: Option<(usize, usize)>,
+    pub cols: Option<(usize, usize)>,
+    /// min, mean, max over the non-null values
+    pub values: Option<(f64, f64, f64)>,
+}
+
+pub(crate) fn record_summary(entries: &[SparseEntry]) -> RecordSummary {
+    let range = |it: &mut dyn Iterator<Item = usize>| {
+        it.fold(None, |acc: Option<(usize, usize)>, v| match acc {
+            Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+            None => Some((v, v)),
+        })
+    };
+    let values: Vec<f64> = entries.iter().filter_map(|e| e.value).collect();
+    let values = (!values.is_empty()).then(|| {
+        (
+            values.iter().copied().fold(f64::INFINITY, f64::min),
+            values.iter().sum::<f64>() / values.len() as f64,
+            values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        )
+    });
+    RecordSummary {
+        nnz: entries.len(),
+        rows: range(&mut entries.iter().map(|e| e.row)),
+        cols: range(&mut entries.iter().map(|e| e.col)),
+        values,
+    }
+}
+
+fn range_text(range: Option<(usize, usize)>) -> String {
+    let base = index_base();
+    match range {
+        Some((lo, hi)) if lo == hi => base.show(lo).to_string(),
+        Some((lo, hi)) => base.span(lo..hi + 1),
+        None => "-".to_string(),
+    }
+}
+
+/// Table cells after the Row label for record `r`.
+fn record_cells(records: &StructArray, kind: SparseRecordKind, r: usize) -> Vec<String> {
+    match sparse_record(records, kind, r) {
+        Ok(Some(record)) => {
+            let s = record_summary(&record.entries);
+            let (min, mean, max) = match s.values {
+                Some((min, mean, max)) => (
+                    format!("{min:.4}"),
+                    format!("{mean:.4}"),
+                    format!("{max:.4}"),
+                ),
+                None => ("-".into(), "-".into(), "-".into()),
+            };
+            vec![
+                s.nnz.to_string(),
+                range_text(s.rows),
+                range_text(s.cols),
+                min,
+                mean,
+                max,
+            ]
+        }
+        Ok(None) => vec!["-".into(), "NULL".into()],
+        // The reason is too long for a cell; `Enter` shows it.
+        Err(_) => vec!["!".into(), "invalid".into()],
+    }
+}
+
+/// Render a per-record sparse struct column (LanceLayout::SparsePerRow).
+///
+/// Layout:
+/// - Top: metadata
+/// - Middle: `Row | nnz | rows | cols | min | mean | max`, one record per
+///   row; the top visible record is the selection `Enter` expands
+/// - Bottom: status bar
+///
+/// Returns the rows window drawn, `None` when the records cannot be read.
+pub(crate) fn render_sparse_rows_ui(
+    f: &mut Frame,
+    data: &BatchSource,
+    row_start: usize,
+    source: SourceWindow,
+    bookmarks: &Bookmarks,
+) -> Option<ScrollWindow> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // metadata
+            Constraint::Min(0),    // records
+            Constraint::Length(3), // status
+        ])
+        .split(f.area());
+
+    let num_rows = data.num_rows();
+    let meta = Paragraph::new(Span::styled(
+        metadata_text(data, num_rows, 1, "    "),
+        Style::default().fg(TEXT_SECONDARY),
+    ))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_ACCENT))
+            .title(" Metadata "),
+    );
+    f.render_widget(meta, chunks[0]);
+
+    let Ok((_, kind)) = sparse_records(&data.empty_batch()) else {
+        return None;
+    };
+    let kind_name = match kind {
+        SparseRecordKind::Coo => "COO",
+        SparseRecordKind::Csr => "CSR",
+    };
+
+    // borders + header
+    let table_area = chunks[1];
+    let max_visible_rows = table_area.height.saturating_sub(3) as usize;
+    let end_row = (row_start + max_visible_rows).min(num_rows);
+    let label_width = row_label_width(data);
+
+    let mut header = vec![Cell::from(right("Row", label_width))];
+    header.extend(["nnz", "rows", "cols", "min", "mean", "max"].map(Cell::from));
+    let header = Row::new(header).style(
+        Style::default()
+            .fg(HEADER_FG)
+            .bg(HEADER_BG)
+            .add_modifier(Modifier::BOLD),
+    );
+    let rows: Vec<Row> = (row_start..end_row)
+        .map(|r| {
+            let bg = if r % 2 == 0 { EVEN_ROW_BG } else { ODD_ROW_BG };
+            let mut cells = vec![
+                Cell::from(row_label_text(data, r, label_width, bookmarks)).style(
+                    Style::default()
+                        .fg(TEXT_SECONDARY)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ];
+            let record = data.locate(r).and_then(|(batch, local)| {
+                let (records, _) = sparse_records(batch).ok()?;
+                Some(record_cells(records, kind, local))
+            });
+            cells.extend(
+                record
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|c| Cell::from(c).style(Style::default().fg(TEXT_PRIMARY))),
+            );
+            let style = Style::default().bg(bg);
+            Row::new(cells).style(if r == row_start {
+                style.add_modifier(Modifier::REVERSED)
+            } else {
+                style
+            })
+        })
+        .collect();
+
+    let title = format!(
+        " Lance Sparse Records ({kind_name}, rows {}) ",
+        source.span(row_start..end_row)
+    );
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(label_width),
+            Constraint::Length(6),
+            Constraint::Length(11),
+            Constraint::Length(11),
+            Constraint::Min(10),
+            Constraint::Min(10),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_PRIMARY))
+            .title(title),
+    )
+    .column_spacing(1);
+    f.render_widget(table, table_area);
+
+    let rows_window = ScrollWindow {
+        start: row_start,
+        len: max_visible_rows,
+        total: num_rows,
+    };
+    render_table_scrollbars(
+        f,
+        table_area,
+        rows_window,
+        ScrollWindow {
+            start: 0,
+            len: 1,
+            total: 1,
+        },
+    );
+
+    let status = format!(
+        " {} | {} records | sparse {kind_name} records | mode: sparse rows | ↑↓ select | Enter entries | v graph | M metadata | L log | q quit ",
+        rows_window.label(),
+        num_rows
+    );
+    let status_widget = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(Span::styled(status, Style::default().fg(TEXT_ACCENT)));
+    f.render_widget(status_widget, chunks[2]);
+    Some(rows_window)
+}
+
+/// State of the `Enter` popup: one record's decoded entries and a scroll
+/// position.
+pub(crate) struct SparseRecordDetail {
+    label: String,
+    record: Result<Option<SparseRecord>, String>,
+    scroll: usize,
+}
+
+impl SparseRecordDetail {
+    /// Popup for global row `r` of `data`.
+    pub(crate) fn new(data: &BatchSource, r: usize) -> Self {
+        let record = match data.locate(r) {
+            Some((batch, local)) => sparse_records(batch)
+                .and_then(|(records, kind)| sparse_record(records, kind, local))
+                .map_err(|e| e.to_string()),
+            None => Ok(None),
+        };
+        Self {
+            label: row_label(data, r),
+            record,
+            scroll: 0,
+        }
+    }
+
+    /// Apply a key press. Returns `false` when the popup should close.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll += 1,
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll += 10,
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll = usize::MAX,
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Draw the entries popup over the current frame; the scroll offset is
+/// clamped to the entries once the popup height is known.
+pub(crate) fn render_sparse_detail(f: &mut Frame, detail: &mut SparseRecordDetail) {
+    let area = centered(f.area(), 80);
+    let visible = area.height.saturating_sub(3) as usize;
+
+    let (title, rows): (String, Vec<Row>) = match &detail.record {
+        Ok(Some(record)) => {
+            detail.scroll = detail
+                .scroll
+                .min(record.entries.len().saturating_sub(visible));
+            let title = format!(
+                " Record {}: {} entries{} ",
+                detail.label,
+                record.entries.len(),
+                record
+                    .block_rows
+                    .map(|n| format!(" in {n} block rows"))
+                    .unwrap_or_default()
+            );
+            let rows = record
+                .entries
+                .iter()
+                .enumerate()
+                .skip(detail.scroll)
+                .take(visible)
+                .map(|(k, e)| {
+                    Row::new(vec![
+                        Cell::from(k.to_string()).style(Style::default().fg(TEXT_SECONDARY)),
+                        Cell::from(e.row.to_string()),
+                        Cell::from(e.col.to_string()),
+                        Cell::from(
+                            e.value
+                                .map(|v| format!("{v:.6}"))
+                                .unwrap_or_else(|| "NULL".to_string()),
+                        )
+                        .style(Style::default().fg(TEXT_ACCENT)),
+                    ])
+                    .style(Style::default().fg(TEXT_PRIMARY))
+                })
+                .collect();
+            (title, rows)
+        }
+        Ok(None) => (
+            format!(" Record {}: NULL ", detail.label),
+            vec![Row::new(vec!["", "null record"])],
+        ),
+        Err(e) => (
+            format!(" Record {}: invalid ", detail.label),
+            vec![Row::new(vec![String::new(), e.clone()])],
+        ),
+    };
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Min(12),
+        ],
+    )
+    .header(
+        Row::new(["#", "row", "col", "value"])
+            .style(Style::default().fg(HEADER_FG).add_modifier(Modifier::BOLD)),
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(BORDER_ACCENT))
+            .title(title)
+            .title_bottom(" ↑↓ scroll | Enter/Esc close "),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(table, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functions::functions::sparse_per_row_to_coo;
+    use arrow::datatypes::{DataType, Field, Fields, Schema};
+    use arrow_array::RecordBatch;
+    use arrow_array::types::{Float64Type, UInt32Type};
+    use arrow_array::{Array as _, ArrayRef, ListArray};
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::sync::Arc;
+
+    /// Three CSR records: two rows, one row with no entries, and a null.
+    fn csr_batch() -> RecordBatch {
+        let indptr = ListArray::from_iter_primitive::<UInt32Type, _, _>([
+            Some(vec![Some(0), Some(2), Some(3)]),
+            Some(vec![Some(0), Some(0)]),
+            None,
+        ]);
+        let indices = ListArray::from_iter_primitive::<UInt32Type, _, _>([
+            Some(vec![Some(1), Some(4), Some(2)]),
+            Some(vec![]),
+            None,
+        ]);
+        let data = ListArray::from_iter_primitive::<Float64Type, _, _>([
+            Some(vec![Some(1.0), Some(-2.0), Some(4.0)]),
+            Some(vec![]),
+            None,
+        ]);
+        let fields = Fields::from(vec![
+            Field::new("indptr", indptr.data_type().clone(), true),
+            Field::new("indices", indices.data_type().clone(), true),
+            Field::new("data", data.data_type().clone(), true),
+        ]);
+        let records = StructArray::try_new(
+            fields.clone(),
+            vec![
+                Arc::new(indptr) as ArrayRef,
+                Arc::new(indices),
+                Arc::new(data),
+            ],
+            Some(vec![true, true, false].into()),
+        )
+        .unwrap();
+        let schema = Schema::new(vec![Field::new("record", DataType::Struct(fields), true)]);
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(records) as ArrayRef]).unwrap()
+    }
+
+    fn render(detail: &mut SparseRecordDetail) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+        terminal.draw(|f| render_sparse_detail(f, detail)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|line| line.iter().map(|c| c.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn summaries_cover_index_ranges_and_values() {
+        let batch = csr_batch();
+        let (records, kind) = sparse_records(&batch).unwrap();
+        assert_eq!(kind, SparseRecordKind::Csr);
+        let record = sparse_record(records, kind, 0).unwrap().unwrap();
+        assert_eq!(record.block_rows, Some(2));
+        assert_eq!(
+            record_summary(&record.entries),
+            RecordSummary {
+                nnz: 3,
+                rows: Some((0, 1)),
+                cols: Some((1, 4)),
+                values: Some((-2.0, 1.0, 4.0)),
+            }
+        );
+        assert_eq!(record_cells(records, kind, 1)[..2], ["0", "-"]);
+        assert_eq!(record_cells(records, kind, 2), ["-", "NULL"]);
+
+        // Flattened, the second block's (empty) row follows the first's two.
+        let coo = sparse_per_row_to_coo(&batch).unwrap();
+        assert_eq!(coo.num_rows(), 3);
+        assert_eq!(coo.schema().metadata()["rows"], "3");
+    }
+
+    #[test]
+    fn detail_lists_entries_and_clamps_scroll() {
+        let mut detail = SparseRecordDetail::new(&BatchSource::from(csr_batch()), 0);
+        for _ in 0..20 {
+            assert!(detail.handle_key(KeyCode::Down));
+        }
+        let text = render(&mut detail);
+        assert_eq!(detail.scroll, 0);
+        assert!(
+            text.contains("Record 0: 3 entries in 2 block rows"),
+            "{text}"
+        );
+        assert!(text.contains("-2.000000"), "{text}");
+        assert!(!detail.handle_key(KeyCode::Enter));
+    }
+}
@@ -6,7 +6,7 @@ use crate::functions::sparse_viz::ConnectivityGraph;
 use anyhow::Result;
 use arrow::record_batch::RecordBatch;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent},
+    event::KeyCode,
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -21,10 +21,13 @@ use ratatui::{
 use std::io;
 
 // Color palette
+use crate::display::display::render_too_small;
+use crate::display::index_base::{INDEX_BASE_KEY, index_base, set_index_base};
+use crate::display::keys::{is_interrupt, pressed, step};
+use crate::display::redraw::{Redraw, poll_event};
+use crate::display::screenshot::ScreenCapture;
 use crate::display::*;
 
-const TEXT_WARNING: Color = Color::Rgb(255, 121, 198);
-
 /// View mode for the connectivity visualization
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ViewMode {
@@ -66,7 +69,7 @@ impl ViewMode {
 /// Interactive viewer for sparse matrix connectivity
 pub fn display_connectivity_interactive(batch: &RecordBatch) -> Result<()> {
     // Build connectivity graph
-    let graph = ConnectivityGraph::from_coo_batch(batch)?;
+    let graph = ConnectivityGraph::from_batch(batch)?;
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -78,59 +81,74 @@ pub fn display_connectivity_interactive(batch: &RecordBatch) -> Result<()> {
     let mut scroll_offset = 0;
     let mut selected_node: Option<usize> = None;
 
+    let mut redraw = Redraw::new();
+    let mut capture = ScreenCapture::new();
+
     loop {
-        terminal
-            .draw(|f| render_connectivity_ui(f, &graph, view_mode, scroll_offset, selected_node))?;
-
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
-                match code {
-                    KeyCode::Char('q') | KeyCode::Esc => break,
-
-                    // View mode switching
-                    KeyCode::Tab => {
-                        view_mode = view_mode.next();
-                        scroll_offset = 0;
-                    }
-                    KeyCode::BackTab => {
-                        view_mode = view_mode.prev();
-                        scroll_offset = 0;
-                    }
-
-                    // Vertical scrolling
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        if scroll_offset > 0 {
-                            scroll_offset -= 1;
-                        }
-                    }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        scroll_offset += 1;
-                    }
-                    KeyCode::PageUp => {
-                        scroll_offset = scroll_offset.saturating_sub(10);
-                    }
-                    KeyCode::PageDown => {
-                        scroll_offset += 10;
-                    }
-                    KeyCode::Home | KeyCode::Char('g') => {
-                        scroll_offset = 0;
-                    }
-                    KeyCode::End | KeyCode::Char('G') => {
-                        scroll_offset = usize::MAX; // Will be clamped
-                    }
-
-                    // Node selection (when in Nodes view)
-                    KeyCode::Enter => {
-                        if view_mode == ViewMode::Nodes && scroll_offset < graph.nodes.len() {
-                            selected_node = Some(scroll_offset);
-                        }
-                    }
-                    KeyCode::Char('c') => {
-                        selected_node = None;
-                    }
+        if redraw.take() {
+            let frame = terminal.draw(|f| {
+                render_connectivity_ui(f, &graph, view_mode, scroll_offset, selected_node);
+                capture.render(f);
+            })?;
+            capture.keep(frame.buffer);
+        }
+
+        let event = poll_event(redraw.poll_timeout())?;
+        redraw.observe(event.as_ref());
+        if let Some(key) = event.as_ref().and_then(pressed) {
+            if is_interrupt(&key) {
+                break;
+            }
+            if capture.handle_key(&key) {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+
+                // View mode switching
+                KeyCode::Tab => {
+                    view_mode = view_mode.next();
+                    scroll_offset = 0;
+                }
+                KeyCode::BackTab => {
+                    view_mode = view_mode.prev();
+                    scroll_offset = 0;
+                }
+
+                // Vertical scrolling
+                KeyCode::Up | KeyCode::Char('k') => {
+                    scroll_offset = scroll_offset.saturating_sub(step(&key));
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    scroll_offset = scroll_offset.saturating_add(step(&key));
+                }
+                KeyCode::PageUp => {
+                    scroll_offset = scroll_offset.saturating_sub(10);
+                }
+                KeyCode::PageDown => {
+                    scroll_offset += 10;
+                }
+                KeyCode::Home | KeyCode::Char('g') => {
+                    scroll_offset = 0;
+                }
+                KeyCode::End | KeyCode::Char('G') => {
+                    scroll_offset = usize::MAX; // Will be clamped
+                }
 
-                    _ => {}
+                // Node selection (when in Nodes view)
+                KeyCode::Enter
+                    if view_mode == ViewMode::Nodes && scroll_offset < graph.nodes.len() =>
+                {
+                    selected_node = Some(scroll_offset);
+                }
+                KeyCode::Char('c') => {
+                    selected_node = None;
                 }
+                KeyCode::Char(INDEX_BASE_KEY) => {
+                    set_index_base(index_base().next());
+                }
+
+                _ => {}
             }
         }
     }
@@ -149,6 +167,10 @@ fn render_connectivity_ui(
     scroll_offset: usize,
     selected_node: Option<usize>,
 ) {
+    if render_too_small(f, (40, 10)) {
+        return;
+    }
+
     // Main layout: header, content, footer
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -209,8 +231,9 @@ fn render_header(f: &mut Frame, graph: &ConnectivityGraph, area: Rect) {
 /// Render footer with controls
 fn render_footer(f: &mut Frame, view_mode: ViewMode, area: Rect) {
     let controls = format!(
-        "View: {} | Tab/Shift+Tab: Switch view | ↑↓/jk: Scroll | Enter: Select | c: Clear | q: Quit",
-        view_mode.as_str()
+        "View: {} | Tab/Shift+Tab: Switch view | ↑↓/jk: Scroll | Enter: Select | c: Clear | #: {} | q: Quit",
+        view_mode.as_str(),
+        index_base().label()
     );
 
     let footer = Paragraph::new(Span::styled(controls, Style::default().fg(TEXT_ACCENT))).block(
@@ -316,6 +339,7 @@ fn render_statistics(f: &mut Frame, graph: &ConnectivityGraph, area: Rect) {
 
 fn render_top_hubs(f: &mut Frame, graph: &ConnectivityGraph, area: Rect) {
     let hubs = graph.get_hubs(20);
+    let base = index_base();
 
     let items: Vec<ListItem> = hubs
         .iter()
@@ -326,7 +350,7 @@ fn render_top_hubs(f: &mut Frame, graph: &ConnectivityGraph, area: Rect) {
                 .connected_to
                 .iter()
                 .take(5)
-                .map(|n| n.to_string())
+                .map(|&n| base.show(n).to_string())
                 .collect();
 
             let line = Line::from(vec![
@@ -335,7 +359,7 @@ fn render_top_hubs(f: &mut Frame, graph: &ConnectivityGraph, area: Rect) {
                     Style::default().fg(TEXT_SECONDARY).bg(bg),
                 ),
                 Span::styled(
-                    format!("Node {:3} ", node.id),
+                    format!("Node {:3} ", base.show(node.id)),
                     Style::default()
                         .fg(TEXT_ACCENT)
                         .bg(bg)
@@ -390,13 +414,14 @@ fn render_nodes_view(
     let inner_height = layout[0].height.saturating_sub(2) as usize;
     let start = scroll_offset.min(graph.nodes.len().saturating_sub(1));
     let end = (start + inner_height).min(graph.nodes.len());
+    let base = index_base();
 
     let items: Vec<ListItem> = graph.nodes[start..end]
         .iter()
         .enumerate()
         .map(|(i, node)| {
             let idx = start + i;
-            let bg = if idx % 2 == 0 {
+            let bg = if idx.is_multiple_of(2) {
                 EVEN_ROW_BG
             } else {
                 ODD_ROW_BG
@@ -413,7 +438,7 @@ fn render_nodes_view(
             };
 
             let line = Line::from(vec![
-                Span::styled(format!("{:4} ", node.id), style.fg(TEXT_ACCENT)),
+                Span::styled(format!("{:4} ", base.show(node.id)), style.fg(TEXT_ACCENT)),
                 Span::styled(
                     format!("deg={:3} ", node.degree),
                     style.fg(if node.degree > 10 {
@@ -437,9 +462,8 @@ fn render_nodes_view(
         .collect();
 
     let title = format!(
-        " Nodes [{}-{} of {}] ",
-        start,
-        end.saturating_sub(1),
+        " Nodes [{} of {}] ",
+        base.span(start..end),
         graph.nodes.len()
     );
     let list = List::new(items).block(
@@ -468,7 +492,7 @@ fn render_node_details(
                 Line::from(vec![
                     Span::styled("Node ID: ", Style::default().fg(TEXT_SECONDARY)),
                     Span::styled(
-                        format!("{}", node.id),
+                        format!("{}", index_base().show(node.id)),
                         Style::default()
                             .fg(TEXT_ACCENT)
                             .add_modifier(Modifier::BOLD),
@@ -500,7 +524,7 @@ fn render_node_details(
                     break;
                 }
                 lines.push(Line::from(Span::styled(
-                    format!("  • Node {}", neighbor),
+                    format!("  • Node {}", index_base().show(neighbor)),
                     Style::default().fg(TEXT_PRIMARY),
                 )));
             }
@@ -541,13 +565,14 @@ fn render_edges_view(f: &mut Frame, graph: &ConnectivityGraph, area: Rect, scrol
     let inner_height = area.height.saturating_sub(2) as usize;
     let start = scroll_offset.min(graph.edges.len().saturating_sub(1));
     let end = (start + inner_height).min(graph.edges.len());
+    let base = index_base();
 
     let items: Vec<ListItem> = graph.edges[start..end]
         .iter()
         .enumerate()
         .map(|(i, edge)| {
             let idx = start + i;
-            let bg = if idx % 2 == 0 {
+            let bg = if idx.is_multiple_of(2) {
                 EVEN_ROW_BG
             } else {
                 ODD_ROW_BG
@@ -555,16 +580,16 @@ fn render_edges_view(f: &mut Frame, graph: &ConnectivityGraph, area: Rect, scrol
 
             let line = Line::from(vec![
                 Span::styled(
-                    format!("{:4}. ", idx),
+                    format!("{:4}. ", base.show(idx)),
                     Style::default().fg(TEXT_SECONDARY).bg(bg),
                 ),
                 Span::styled(
-                    format!("{:3} ", edge.from),
+                    format!("{:3} ", base.show(edge.from)),
                     Style::default().fg(TEXT_ACCENT).bg(bg),
                 ),
                 Span::styled("⟷ ", Style::default().fg(TEXT_PRIMARY).bg(bg)),
                 Span::styled(
-                    format!("{:3} ", edge.to),
+                    format!("{:3} ", base.show(edge.to)),
                     Style::default().fg(TEXT_ACCENT).bg(bg),
                 ),
                 Span::styled(
@@ -578,9 +603,8 @@ fn render_edges_view(f: &mut Frame, graph: &ConnectivityGraph, area: Rect, scrol
         .collect();
 
     let title = format!(
-        " Edges [{}-{} of {}] ",
-        start,
-        end.saturating_sub(1),
+        " Edges [{} of {}] ",
+        base.span(start..end),
         graph.edges.len()
     );
     let list = List::new(items).block(
@@ -604,19 +628,24 @@ fn render_components_view(
     let inner_height = area.height.saturating_sub(2) as usize;
     let start = scroll_offset.min(components.len().saturating_sub(1));
     let end = (start + inner_height).min(components.len());
+    let base = index_base();
 
     let items: Vec<ListItem> = components[start..end]
         .iter()
         .enumerate()
         .map(|(i, comp)| {
             let idx = start + i;
-            let bg = if idx % 2 == 0 {
+            let bg = if idx.is_multiple_of(2) {
                 EVEN_ROW_BG
             } else {
                 ODD_ROW_BG
             };
 
-            let preview: Vec<String> = comp.iter().take(10).map(|n| n.to_string()).collect();
+            let preview: Vec<String> = comp
+                .iter()
+                .take(10)
+                .map(|&n| base.show(n).to_string())
+                .collect();
             let preview_str = if comp.len() > 10 {
                 format!("[{}...]", preview.join(", "))
             } else {
@@ -625,7 +654,7 @@ fn render_components_view(
 
             let line = Line::from(vec![
                 Span::styled(
-                    format!("Component {:3}: ", idx),
+                    format!("Component {:3}: ", base.show(idx)),
                     Style::default()
                         .fg(TEXT_ACCENT)
                         .bg(bg)
@@ -643,9 +672,8 @@ fn render_components_view(
         .collect();
 
     let title = format!(
-        " Connected Components [{}-{} of {}] ",
-        start,
-        end.saturating_sub(1),
+        " Connected Components [{} of {}] ",
+        base.span(start..end),
         components.len()
     );
     let list = List::new(items).block(
@@ -657,3 +685,54 @@ fn render_components_view(
 
     f.render_widget(list, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::csr_to_coo_batch;
+    use ratatui::backend::TestBackend;
+    use sprs::TriMat;
+
+    fn render_to_text(
+        graph: &ConnectivityGraph,
+        mode: ViewMode,
+        width: u16,
+        height: u16,
+    ) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|f| render_connectivity_ui(f, graph, mode, 0, Some(0)))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|line| line.iter().map(|c| c.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn tiny_areas_show_placeholder_in_every_mode() {
+        let mut tri = TriMat::<f64>::new((3, 3));
+        tri.add_triplet(0, 1, 1.0);
+        tri.add_triplet(1, 2, 2.0);
+        let batch = csr_to_coo_batch(&tri.to_csr()).unwrap();
+        let graph = ConnectivityGraph::from_coo_batch(&batch).unwrap();
+
+        for mode in [
+            ViewMode::Overview,
+            ViewMode::Nodes,
+            ViewMode::Edges,
+            ViewMode::Components,
+        ] {
+            for (w, h) in [(1, 1), (10, 3), (20, 5)] {
+                let text = render_to_text(&graph, mode, w, h);
+                assert!(text.starts_with('w'), "{mode:?} at {w}×{h}: {text:?}");
+            }
+            // Right at the minimum the real view is drawn.
+            let text = render_to_text(&graph, mode, 40, 10);
+            assert!(!text.contains("window too small"), "{mode:?}: {text}");
+        }
+    }
+}
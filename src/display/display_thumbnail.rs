@@ -0,0 +1,398 @@
+//! Zoomed-out heatmap of a whole dense matrix (`o` key): each cell's colour
+//! is the mean absolute value of its block, with the table's visible window
+//! outlined. Built straight from the FixedSizeList values, before any
+//! `col_*` expansion.
+
+use arrow_array::{Array, FixedSizeListArray, Float64Array};
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders},
+};
+use std::ops::Range;
+
+use crate::display::display::{ScrollWindow, blend_colors, mix_colors};
+use crate::display::*;
+use crate::functions::functions::DenseMatrix;
+
+/// Upper bound on the precomputed grid along each axis; panels are at most
+/// a few dozen cells, so finer grids would only cost memory.
+const GRID_MAX: usize = 256;
+
+/// Width of the side panel, borders included.
+pub(crate) const THUMBNAIL_WIDTH: u16 = 34;
+
+/// Block sums of |value| over a dense N×F matrix on a grid of at most
+/// [`GRID_MAX`]² blocks.
+#[derive(Debug, Clone)]
+pub(crate) struct MatrixThumbnail {
+    rows: usize,
+    cols: usize,
+    grid_rows: usize,
+    grid_cols: usize,
+    sums: Vec<f64>,
+    counts: Vec<u32>,
+}
+
+impl MatrixThumbnail {
+    /// Thumbnail of the viewer's features of a dense matrix held as one
+    /// packed matrix per batch.
+    pub(crate) fn from_matrices(matrices: &[DenseMatrix]) -> Option<Self> {
+        Self::from_matrices_mapped(matrices, |_, v| v)
+    }
+
+    /// [`from_matrices`](Self::from_matrices) with each value replaced by
+    /// `map(feature, value)` first, `feature` counting from the viewer's
+    /// first one (a `z` transform).
+    pub(crate) fn from_matrices_mapped(
+        matrices: &[DenseMatrix],
+        map: impl Fn(usize, f64) -> f64,
+    ) -> Option<Self> {
+        let lists: Vec<&FixedSizeListArray> = matrices.iter().map(|m| &m.list).collect();
+        Self::from_lists_mapped(&lists, matrices.first()?.features.clone(), map)
+    }
+
+    /// Stream over the values of the lists (consecutive row blocks of one
+    /// matrix) once, adding each |value| to its block.
+    #[cfg(test)]
+    pub(crate) fn from_lists(lists: &[&FixedSizeListArray], cols: Range<usize>) -> Option<Self> {
+        Self::from_lists_mapped(lists, cols, |_, v| v)
+    }
+
+    fn from_lists_mapped(
+        lists: &[&FixedSizeListArray],
+        cols: Range<usize>,
+        map: impl Fn(usize, f64) -> f64,
+    ) -> Option<Self> {
+        let width = lists.first()?.value_length() as usize;
+        let cols = cols.start.min(width)..cols.end.min(width);
+        let (rows, n_cols) = (lists.iter().map(|l| l.len()).sum::<usize>(), cols.len());
+        if rows == 0 || n_cols == 0 {
+            return None;
+        }
+        let grid_rows = rows.min(GRID_MAX);
+        let grid_cols = n_cols.min(GRID_MAX);
+        let mut sums = vec![0.0; grid_rows * grid_cols];
+        let mut counts = vec![0u32; grid_rows * grid_cols];
+
+        let mut offset = 0;
+        for list in lists {
+            let values = list.values().as_any().downcast_ref::<Float64Array>()?;
+            for r in 0..list.len() {
+                if list.is_null(r) {
+                    continue;
+                }
+                let g_row = (offset + r) * grid_rows / rows;
+                let start = list.value_offset(r) as usize;
+                for c in cols.clone() {
+                    let i = start + c;
+                    if values.is_null(i) {
+                        continue;
+                    }
+                    let cell = g_row * grid_cols + (c - cols.start) * grid_cols / n_cols;
+                    sums[cell] += map(c - cols.start, values.value(i)).abs();
+                    counts[cell] += 1;
+                }
+            }
+            offset += list.len();
+        }
+        Some(Self {
+            rows,
+            cols: n_cols,
+            grid_rows,
+            grid_cols,
+            sums,
+            counts,
+        })
+    }
+
+    /// Mean |value| per panel cell for a `height` × `width` panel, row-major;
+    /// `None` where a block holds no values.
+    pub(crate) fn cell_means(&self, height: usize, width: usize) -> Vec<Option<f64>> {
+        let span = |i: usize, n: usize, grid: usize| {
+            let lo = i * grid / n;
+            lo..((i + 1) * grid / n).max(lo + 1)
+        };
+        let mut out = Vec::with_capacity(height * width);
+        for pr in 0..height {
+            for pc in 0..width {
+                let (mut sum, mut count) = (0.0, 0u64);
+                for gr in span(pr, height, self.grid_rows) {
+                    for gc in span(pc, width, self.grid_cols) {
+                        sum += self.sums[gr * self.grid_cols + gc];
+                        count += u64::from(self.counts[gr * self.grid_cols + gc]);
+                    }
+                }
+                out.push((count > 0).then(|| sum / count as f64));
+            }
+        }
+        out
+    }
+
+    /// Panel `height` × `width` cells used for this matrix: never more cells
+    /// than matrix rows/columns, so each cell covers at least one value.
+    pub(crate) fn panel_size(&self, area: Rect) -> (usize, usize) {
+        (
+            (area.height as usize).min(self.rows),
+            (area.width as usize).min(self.cols),
+        )
+    }
+
+    /// First matrix (row, feature) covered by panel cell (`pr`, `pc`).
+    pub(crate) fn cell_origin(
+        &self,
+        (height, width): (usize, usize),
+        pr: usize,
+        pc: usize,
+    ) -> (usize, usize) {
+        (
+            pr * self.rows / height.max(1),
+            pc * self.cols / width.max(1),
+        )
+    }
+
+    /// Panel cell containing matrix position (`row`, `col`).
+    fn cell_of(&self, (height, width): (usize, usize), row: usize, col: usize) -> (usize, usize) {
+        (
+            (row.min(self.rows - 1) * height / self.rows),
+            (col.min(self.cols - 1) * width / self.cols),
+        )
+    }
+}
+
+/// The thumbnail plus the keyboard cursor used to pick a region.
+pub(crate) struct ThumbnailState {
+    pub thumb: MatrixThumbnail,
+    /// Whether arrow keys move the cursor instead of scrolling the table
+    pub focused: bool,
+    /// Cursor cell (row, col) in the last drawn panel
+    pub cursor: (usize, usize),
+    /// Panel size in cells when last drawn
+    pub size: (usize, usize),
+}
+
+impl ThumbnailState {
+    pub(crate) fn new(thumb: MatrixThumbnail) -> Self {
+        Self {
+            thumb,
+            focused: false,
+            cursor: (0, 0),
+            size: (1, 1),
+        }
+    }
+
+    /// Move the cursor while focused. Returns the matrix (row, feature) to
+    /// jump to on `Enter`.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> Option<(usize, usize)> {
+        let (h, w) = self.size;
+        let (r, c) = &mut self.cursor;
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => *r = r.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => *r = (*r + 1).min(h.saturating_sub(1)),
+            KeyCode::Left | KeyCode::Char('h') => *c = c.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => *c = (*c + 1).min(w.saturating_sub(1)),
+            KeyCode::Enter => {
+                self.focused = false;
+                return Some(self.thumb.cell_origin(self.size, *r, *c));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Matrix (row, feature) under a click at terminal position (`x`, `y`),
+    /// given the panel's outer `area`.
+    pub(crate) fn click(&mut self, area: Rect, x: u16, y: u16) -> Option<(usize, usize)> {
+        let inner = thumbnail_inner(area);
+        let (h, w) = self.thumb.panel_size(inner);
+        if x < inner.x || y < inner.y {
+            return None;
+        }
+        let (pr, pc) = ((y - inner.y) as usize, (x - inner.x) as usize);
+        if pr >= h || pc >= w {
+            return None;
+        }
+        self.cursor = (pr, pc);
+        Some(self.thumb.cell_origin((h, w), pr, pc))
+    }
+}
+
+/// Split the frame into the viewer area and the thumbnail panel on the
+/// right; `None` when the frame is too narrow to spare the panel.
+pub(crate) fn thumbnail_split(area: Rect) -> (Rect, Option<Rect>) {
+    if area.width < THUMBNAIL_WIDTH + 40 {
+        return (area, None);
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(THUMBNAIL_WIDTH)])
+        .split(area);
+    (chunks[0], Some(chunks[1]))
+}
+
+fn thumbnail_inner(area: Rect) -> Rect {
+    Block::default().borders(Borders::ALL).inner(area)
+}
+
+/// Dark-to-warm colour for a block's mean |value| relative to the largest.
+fn heat_color(level: f64) -> Color {
+    mix_colors(SPARSE_DOT, HEADER_FG, level)
+}
+
+/// Box-drawing character for a cell on the visible-window outline.
+fn outline_symbol(top: bool, bottom: bool, left: bool, right: bool) -> &'static str {
+    match (top || bottom, left || right) {
+        (true, true) if top && left => "┌",
+        (true, true) if top && right => "┐",
+        (true, true) if left => "└",
+        (true, true) => "┘",
+        (true, false) => "─",
+        (false, true) => "│",
+        (false, false) => " ",
+    }
+}
+
+/// Draw the panel in `area` with the table's visible `rows`/`cols` window
+/// outlined.
+pub(crate) fn render_thumbnail(
+    f: &mut Frame,
+    area: Rect,
+    state: &mut ThumbnailState,
+    rows: ScrollWindow,
+    cols: ScrollWindow,
+) {
+    let title = if state.focused {
+        " Overview (Enter jump, Tab back) "
+    } else {
+        " Overview (Tab focus) "
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(if state.focused {
+            BORDER_ACCENT
+        } else {
+            BORDER_PRIMARY
+        }))
+        .title(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let thumb = &state.thumb;
+    let size @ (h, w) = thumb.panel_size(inner);
+    if h == 0 || w == 0 {
+        return;
+    }
+    state.size = size;
+    state.cursor = (state.cursor.0.min(h - 1), state.cursor.1.min(w - 1));
+
+    let means = thumb.cell_means(h, w);
+    let max = means.iter().flatten().copied().fold(0.0, f64::max);
+    let (top, left) = thumb.cell_of(size, rows.start, cols.start);
+    let (bottom, right) = thumb.cell_of(
+        size,
+        (rows.start + rows.len.max(1)).saturating_sub(1),
+        (cols.start + cols.len.max(1)).saturating_sub(1),
+    );
+
+    let buf: &mut Buffer = f.buffer_mut();
+    for pr in 0..h {
+        for pc in 0..w {
+            let bg = match means[pr * w + pc] {
+                Some(m) if max > 0.0 => heat_color(m / max),
+                Some(_) => heat_color(0.0),
+                None => blend_colors(EVEN_ROW_BG, ODD_ROW_BG),
+            };
+            let inside = (top..=bottom).contains(&pr) && (left..=right).contains(&pc);
+            let symbol = if state.focused && state.cursor == (pr, pc) {
+                "╳"
+            } else if inside {
+                outline_symbol(pr == top, pr == bottom, pc == left, pc == right)
+            } else {
+                " "
+            };
+            let fg = if state.cursor == (pr, pc) && state.focused {
+                TEXT_ACCENT
+            } else {
+                TEXT_PRIMARY
+            };
+            if let Some(cell) = buf.cell_mut((inner.x + pc as u16, inner.y + pr as u16)) {
+                cell.set_symbol(symbol)
+                    .set_style(Style::default().fg(fg).bg(bg));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field};
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::sync::Arc;
+
+    /// `rows` × `cols` matrix whose value is the row index.
+    fn list(rows: usize, cols: usize) -> FixedSizeListArray {
+        let values: Vec<f64> = (0..rows)
+            .flat_map(|r| std::iter::repeat_n(r as f64, cols))
+            .collect();
+        FixedSizeListArray::try_new(
+            Arc::new(Field::new("item", DataType::Float64, true)),
+            cols as i32,
+            Arc::new(Float64Array::from(values)),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn blocks_average_absolute_values() {
+        let thumb = MatrixThumbnail::from_lists(&[&list(4, 6)], 0..6).unwrap();
+        // Two panel rows: rows 0–1 (mean 0.5) and rows 2–3 (mean 2.5).
+        let means = thumb.cell_means(2, 3);
+        assert_eq!(means[0], Some(0.5));
+        assert_eq!(means[5], Some(2.5));
+        assert_eq!(thumb.cell_origin((2, 3), 1, 2), (2, 4));
+
+        // A feature range narrows the matrix.
+        let narrow = MatrixThumbnail::from_lists(&[&list(4, 6)], 2..4).unwrap();
+        assert_eq!(narrow.panel_size(Rect::new(0, 0, 30, 30)), (4, 2));
+    }
+
+    #[test]
+    fn panel_outlines_the_visible_window_and_jumps_on_enter() {
+        let mut state =
+            ThumbnailState::new(MatrixThumbnail::from_lists(&[&list(100, 50)], 0..50).unwrap());
+        let mut terminal = Terminal::new(TestBackend::new(THUMBNAIL_WIDTH, 12)).unwrap();
+        let window = |start, len, total| ScrollWindow { start, len, total };
+        terminal
+            .draw(|f| {
+                render_thumbnail(
+                    f,
+                    f.area(),
+                    &mut state,
+                    window(0, 20, 100),
+                    window(0, 8, 50),
+                )
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        assert_eq!(state.size, (10, 32));
+        assert_eq!(buffer[(1, 1)].symbol(), "┌");
+        assert_eq!(buffer[(1, 2)].symbol(), "└");
+
+        state.focused = true;
+        for _ in 0..5 {
+            state.handle_key(KeyCode::Down);
+            state.handle_key(KeyCode::Right);
+        }
+        assert_eq!(state.handle_key(KeyCode::Enter), Some((50, 7)));
+        assert!(!state.focused);
+        assert_eq!(
+            state.click(Rect::new(0, 0, THUMBNAIL_WIDTH, 12), 1, 10),
+            Some((90, 0))
+        );
+    }
+}
@@ -1,6 +1,4 @@
 use arrow::array::*;
-use arrow::datatypes::DataType;
-use arrow_array::RecordBatch;
 use ratatui::text::Span;
 use ratatui::{
     Frame,
@@ -9,13 +7,40 @@ use ratatui::{
     widgets::{Block, Borders, Cell, Paragraph, Row, Table},
 };
 
-use crate::display::display::{blend_colors, format_value, get_cell_bg_color};
+use crate::display::ROWID_COLUMN;
+use crate::display::display::{
+    STAT_COL_WIDTH, ScrollWindow, VALUE_COL_WIDTH, aligned, blend_colors, column_widths,
+    display_name, extract_numeric_value, format_value, get_cell_bg_color, metadata_text,
+    render_table_scrollbars, right, row_label, value_col_width,
+};
+use crate::display::display_bookmarks::Bookmarks;
+use crate::display::display_hidden::hidden_note;
+use crate::display::index_base::index_base;
+use crate::display::row_stats::{RowStats, stat_color};
+use crate::display::transform::transform_note;
+use crate::display::window::{SourceWindow, row_window, vertical_window};
 use crate::display::*;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::functions::is_numeric;
 
 // === Transposed UI (F×N mode) ==============================================
 
-/// Render transposed header for F×N view (row indices as columns)
-fn render_transposed_header<'a>(row_window_start: usize, row_window: &[usize]) -> Row<'a> {
+/// Width of the per-feature null count column
+const NULLS_WIDTH: u16 = 6;
+
+/// Render transposed header for F×N view (row indices as columns);
+/// `widths` are the laid-out widths of the table's columns.
+fn render_transposed_header<'a>(
+    data: &BatchSource,
+    row_window_start: usize,
+    row_window: &[usize],
+    widths: &[u16],
+    stats: &RowStats,
+    bookmarks: &Bookmarks,
+) -> Row<'a> {
+    let n = row_window.len();
+    let has_row_ids = data.schema().column_with_name(ROWID_COLUMN).is_some();
+
     // Feature index header with special styling
     let mut header_cells = vec![
         Cell::from("Feature").style(
@@ -28,14 +53,23 @@ fn render_transposed_header<'a>(row_window_start: usize, row_window: &[usize]) -
 
     // Row index headers with alternating colors
     for (display_idx, &row_idx) in row_window.iter().enumerate() {
-        let col_bg = if (row_window_start + display_idx) % 2 == 0 {
+        let col_bg = if (row_window_start + display_idx).is_multiple_of(2) {
             blend_colors(HEADER_BG, EVEN_COL_BG)
         } else {
             blend_colors(HEADER_BG, ODD_COL_BG)
         };
 
+        let label = if has_row_ids {
+            row_label(data, row_idx)
+        } else {
+            format!("R{}", index_base().show(row_idx))
+        };
+        let label = match bookmarks.mark(data, row_idx) {
+            Some(mark) => format!("{mark}{label}"),
+            None => label,
+        };
         header_cells.push(
-            Cell::from(format!("R{}", row_idx)).style(
+            Cell::from(right(label, widths[display_idx + 1])).style(
                 Style::default()
                     .fg(HEADER_FG)
                     .bg(col_bg)
@@ -44,19 +78,22 @@ fn render_transposed_header<'a>(row_window_start: usize, row_window: &[usize]) -
         );
     }
 
-    // Stats headers with accent color
-    header_cells.push(
-        Cell::from("avg").style(
-            Style::default()
-                .fg(TEXT_ACCENT)
-                .bg(HEADER_BG)
-                .add_modifier(Modifier::BOLD),
-        ),
-    );
+    // Aggregate headers with accent color
+    let aggregates = stats.columns();
+    for (k, aggregate) in aggregates.iter().enumerate() {
+        header_cells.push(
+            Cell::from(right(stats.header(true, *aggregate), widths[n + 1 + k])).style(
+                Style::default()
+                    .fg(TEXT_ACCENT)
+                    .bg(HEADER_BG)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        );
+    }
     header_cells.push(
-        Cell::from("std").style(
+        Cell::from(right("nulls", widths[n + 1 + aggregates.len()])).style(
             Style::default()
-                .fg(TEXT_ACCENT)
+                .fg(TEXT_SECONDARY)
                 .bg(HEADER_BG)
                 .add_modifier(Modifier::BOLD),
         ),
@@ -66,20 +103,24 @@ fn render_transposed_header<'a>(row_window_start: usize, row_window: &[usize]) -
 }
 
 /// Render transposed rows for F×N view (each row is a feature)
+#[allow(clippy::too_many_arguments)]
 fn render_transposed_rows<'a>(
-    batch: &'a RecordBatch,
+    data: &BatchSource,
     all_col_indices: &[usize],
     feat_start: usize,
     feat_end: usize,
     row_window_start: usize,
     row_window: &[usize],
+    widths: &[u16],
+    stats: &RowStats,
 ) -> Vec<Row<'a>> {
-    let schema = batch.schema();
+    let schema = data.schema();
+    let n = row_window.len();
     let mut out = Vec::with_capacity(feat_end.saturating_sub(feat_start));
 
     for (feat_display_idx, &feat_idx) in all_col_indices[feat_start..feat_end].iter().enumerate() {
         let feat_abs_idx = feat_start + feat_display_idx;
-        let row_bg = if feat_abs_idx % 2 == 0 {
+        let row_bg = if feat_abs_idx.is_multiple_of(2) {
             EVEN_ROW_BG
         } else {
             ODD_ROW_BG
@@ -87,7 +128,7 @@ fn render_transposed_rows<'a>(
 
         // Feature name cell
         let mut cells = vec![
-            Cell::from(schema.field(feat_idx).name().to_string()).style(
+            Cell::from(display_name(schema.field(feat_idx)).to_string()).style(
                 Style::default()
                     .fg(TEXT_SECONDARY)
                     .bg(row_bg)
@@ -95,69 +136,64 @@ fn render_transposed_rows<'a>(
             ),
         ];
 
-        let col = batch.column(feat_idx);
+        let data_type = schema.field(feat_idx).data_type();
 
-        // Values for selected rows with alternating column colors
+        // Values for selected rows, each read from the batch holding it,
+        // with alternating column colors
         for (display_idx, &row_idx) in row_window.iter().enumerate() {
-            let s = if row_idx < col.len() {
-                format_value(col, row_idx)
-            } else {
-                "OOB".to_string()
-            };
+            let text = data
+                .locate(row_idx)
+                .map(|(batch, r)| format_value(batch.column(feat_idx), r))
+                .unwrap_or_default();
+            let s = aligned(data_type, text, widths[display_idx + 1]);
             let cell_bg = get_cell_bg_color(feat_abs_idx, row_window_start + display_idx);
 
             cells.push(Cell::from(s).style(Style::default().fg(TEXT_PRIMARY).bg(cell_bg)));
         }
 
-        // Calculate stats across this feature (all rows)
-        let mut vals: Vec<f64> = Vec::new();
-        for r in 0..col.len() {
-            if col.is_null(r) {
-                continue;
-            }
-            match col.data_type() {
-                DataType::Float32 => {
-                    let a = col.as_any().downcast_ref::<Float32Array>().unwrap();
-                    vals.push(a.value(r) as f64);
-                }
-                DataType::Float64 => {
-                    let a = col.as_any().downcast_ref::<Float64Array>().unwrap();
-                    vals.push(a.value(r));
-                }
-                DataType::Int32 => {
-                    let a = col.as_any().downcast_ref::<Int32Array>().unwrap();
-                    vals.push(a.value(r) as f64);
-                }
-                DataType::Int64 => {
-                    let a = col.as_any().downcast_ref::<Int64Array>().unwrap();
-                    vals.push(a.value(r) as f64);
-                }
-                DataType::UInt32 => {
-                    let a = col.as_any().downcast_ref::<UInt32Array>().unwrap();
-                    vals.push(a.value(r) as f64);
+        // Aggregates across this feature (all rows) with accent color
+        let aggregates = stats.columns().len();
+        if aggregates > 0 {
+            let texts = stats.texts(true, feat_abs_idx, row_window, |window| {
+                if !is_numeric(data.schema().field(feat_idx).data_type()) {
+                    return Vec::new();
                 }
-                DataType::UInt64 => {
-                    let a = col.as_any().downcast_ref::<UInt64Array>().unwrap();
-                    vals.push(a.value(r) as f64);
+                let value = |col: &ArrayRef, r: usize| {
+                    (!col.is_null(r))
+                        .then(|| extract_numeric_value(col, r))
+                        .flatten()
+                };
+                match window {
+                    Some(rows) => rows
+                        .iter()
+                        .filter_map(|&row| data.locate(row))
+                        .map(|(batch, r)| value(batch.column(feat_idx), r))
+                        .collect(),
+                    None => data
+                        .batches()
+                        .iter()
+                        .flat_map(|batch| {
+                            let col = batch.column(feat_idx);
+                            (0..col.len()).map(move |r| value(col, r))
+                        })
+                        .collect(),
                 }
-                _ => {}
+            });
+            for (k, text) in texts.into_iter().enumerate() {
+                let color = stat_color(&text);
+                cells.push(
+                    Cell::from(right(text, widths[n + 1 + k]))
+                        .style(Style::default().fg(color).bg(row_bg)),
+                );
             }
         }
-
-        let (avg_str, std_str) = if vals.is_empty() {
-            ("NA".to_string(), "NA".to_string())
-        } else {
-            let n = vals.len() as f64;
-            let sum: f64 = vals.iter().sum();
-            let mean = sum / n;
-            let var: f64 = vals.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n;
-            let std = var.sqrt();
-            (format!("{:.4}", mean), format!("{:.4}", std))
-        };
-
-        // Stats cells with accent color
-        cells.push(Cell::from(avg_str).style(Style::default().fg(TEXT_ACCENT).bg(row_bg)));
-        cells.push(Cell::from(std_str).style(Style::default().fg(TEXT_ACCENT).bg(row_bg)));
+        cells.push(
+            Cell::from(right(
+                data.null_count(feat_idx).to_string(),
+                widths[n + 1 + aggregates],
+            ))
+            .style(Style::default().fg(TEXT_SECONDARY).bg(row_bg)),
+        );
 
         out.push(Row::new(cells).height(1));
     }
@@ -166,15 +202,20 @@ fn render_transposed_rows<'a>(
 }
 
 /// Render the full UI in transposed mode (F×N: features as rows, samples as columns)
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn render_transposed_ui(
     f: &mut Frame,
-    batch: &RecordBatch,
+    data: &BatchSource,
     all_col_indices: &[usize],
     row_offset: usize,
     visible_cols: usize,
     num_rows: usize,
     num_cols: usize,
     feat_start: usize,
+    source: SourceWindow,
+    stats: &RowStats,
+    bookmarks: &Bookmarks,
+    hidden: usize,
 ) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -185,33 +226,8 @@ pub(crate) fn render_transposed_ui(
         ])
         .split(f.area());
 
-    let schema = batch.schema();
-
     // Metadata row with color
-    let mut name_idx = None;
-    let mut n_rows_idx = None;
-    let mut n_cols_idx = None;
-    for (i, field) in schema.fields().iter().enumerate() {
-        match field.name().as_str() {
-            "name_id" => name_idx = Some(i),
-            "n_rows" => n_rows_idx = Some(i),
-            "n_cols" => n_cols_idx = Some(i),
-            _ => {}
-        }
-    }
-
-    let meta_text = if let Some(name_i) = name_idx {
-        let name = format_value(batch.column(name_i), 0);
-        let nrows_val = n_rows_idx
-            .map(|i| format_value(batch.column(i), 0))
-            .unwrap_or_else(|| "?".to_string());
-        let ncols_val = n_cols_idx
-            .map(|i| format_value(batch.column(i), 0))
-            .unwrap_or_else(|| "?".to_string());
-        format!("name_id: {name}    n_rows: {nrows_val}    n_cols: {ncols_val}")
-    } else {
-        format!("rows: {num_rows}    cols: {num_cols}")
-    };
+    let meta_text = metadata_text(data, num_rows, num_cols, "    ");
 
     let header_paragraph =
         Paragraph::new(Span::styled(meta_text, Style::default().fg(TEXT_SECONDARY))).block(
@@ -222,43 +238,52 @@ pub(crate) fn render_transposed_ui(
         );
     f.render_widget(header_paragraph, chunks[0]);
 
-    // Determine vertical window for features
+    // Vertical window of features, horizontal window of sample rows
     let table_area_height = chunks[1].height.saturating_sub(3);
     let max_visible_feats = table_area_height as usize;
-    let feat_end = (feat_start + max_visible_feats).min(all_col_indices.len());
-
-    // Horizontal window: which sample rows to show
-    let row_window: Vec<usize> = (row_offset..(row_offset + visible_cols).min(num_rows)).collect();
+    let feats = vertical_window(feat_start, max_visible_feats, all_col_indices.len());
+    let (feat_start, feat_end) = (feats.start, feats.end);
+    let samples = row_window(row_offset, visible_cols, num_rows);
+    let row_offset = samples.start;
+    let row_window: Vec<usize> = samples.clone().collect();
+
+    let mut others = vec![VALUE_COL_WIDTH]; // "Feature" column
+    others.extend(stats.columns().iter().map(|_| STAT_COL_WIDTH));
+    others.push(NULLS_WIDTH);
+    let value_width = value_col_width(chunks[1], &others, visible_cols);
+    let mut widths = vec![Constraint::Length(VALUE_COL_WIDTH)];
+    for _ in &row_window {
+        widths.push(Constraint::Length(value_width));
+    }
+    widths.extend(others[1..].iter().map(|&w| Constraint::Length(w)));
+    let cell_widths = column_widths(chunks[1], &widths);
 
-    let header_row = render_transposed_header(row_offset, &row_window);
+    let header_row = render_transposed_header(
+        data,
+        row_offset,
+        &row_window,
+        &cell_widths,
+        stats,
+        bookmarks,
+    );
     let rows = render_transposed_rows(
-        batch,
+        data,
         all_col_indices,
         feat_start,
         feat_end,
         row_offset,
         &row_window,
+        &cell_widths,
+        stats,
     );
 
-    let mut widths = vec![Constraint::Length(12)]; // "Feature" column
-    for _ in &row_window {
-        widths.push(Constraint::Length(12));
-    }
-    widths.push(Constraint::Length(10)); // avg
-    widths.push(Constraint::Length(10)); // std
-
     let total_feat_cols = all_col_indices.len();
-    let start_row = row_offset + 1;
-    let end_row = (row_offset + row_window.len()).min(num_rows);
 
     let title = format!(
-        " Lance Data Transposed (features {}–{} of {}, sample rows {}–{} of {}) ",
-        feat_start + 1,
-        feat_end,
-        total_feat_cols,
-        start_row,
-        end_row,
-        num_rows
+        " Lance Data Transposed ({}, sample rows {}{}) ",
+        source.col_span("features", feats, total_feat_cols),
+        source.span(samples),
+        transform_note(&data.schema()),
     );
 
     let table = Table::new(rows, widths)
@@ -273,9 +298,25 @@ pub(crate) fn render_transposed_ui(
 
     f.render_widget(table, chunks[1]);
 
+    let feats_window = ScrollWindow {
+        start: feat_start,
+        len: max_visible_feats,
+        total: total_feat_cols,
+    };
+    let rows_window = ScrollWindow {
+        start: row_offset,
+        len: visible_cols,
+        total: num_rows,
+    };
+    render_table_scrollbars(f, chunks[1], feats_window, rows_window);
+
     let status = format!(
-        " {} rows × {} total cols | {} feature cols (col_*) | mode: F×N | ↑↓ scroll features | ←→ scroll rows | t transpose | q quit ",
-        num_rows, num_cols, total_feat_cols
+        " {} | {} rows × {} total cols | {} feature cols (col_*){} | mode: F×N | ↑↓ scroll features | ←→ scroll rows | </> columns | t transpose | i values | a/A stats, scope | M metadata | L log | q quit ",
+        feats_window.label(),
+        num_rows,
+        num_cols,
+        total_feat_cols,
+        hidden_note(hidden)
     );
     let status_widget = Block::default()
         .borders(Borders::ALL)
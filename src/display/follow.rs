@@ -0,0 +1,211 @@
+//! The viewer half of `display --follow`: take the rows the poller of
+//! [`spawn_follow`](crate::functions::follow::spawn_follow) reads, keep
+//! the view pinned to the last page while following, pause when the view
+//! is scrolled up and resume on [`FOLLOW_KEY`], with a badge on the bottom
+//! border saying which.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Span,
+    widgets::Paragraph,
+};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use unicode_width::UnicodeWidthStr;
+
+use crate::display::state::SpreadsheetState;
+use crate::display::{TEXT_ACCENT, TEXT_SECONDARY, TEXT_WARNING};
+use crate::functions::follow::{FollowUpdate, FollowedRows};
+
+/// Key resuming a paused follow, on the last page.
+pub(crate) const FOLLOW_KEY: char = 'G';
+
+/// Follow state of one viewer.
+pub(crate) struct Follow {
+    updates: Receiver<FollowUpdate>,
+    version: u64,
+    /// Pinned to the last page; off once scrolled up
+    following: bool,
+    /// Top row (F×N: first sample column) of the last pin
+    pinned: Option<usize>,
+    /// Rows that arrived while paused
+    unseen: usize,
+    /// Why the poller stopped
+    stopped: Option<String>,
+}
+
+impl Follow {
+    /// Following from `version`, with its updates arriving on `updates`.
+    pub(crate) fn new(updates: Receiver<FollowUpdate>, version: u64) -> Self {
+        Self {
+            updates,
+            version,
+            following: true,
+            pinned: None,
+            unseen: 0,
+            stopped: None,
+        }
+    }
+
+    /// The next appended rows, if a new version brought any; a stop is
+    /// recorded for the badge.
+    pub(crate) fn poll(&mut self) -> Option<FollowedRows> {
+        loop {
+            match self.updates.try_recv() {
+                Ok(FollowUpdate::Appended(rows)) => {
+                    self.version = rows.version;
+                    if !self.following {
+                        self.unseen += rows.rows.num_rows();
+                    }
+                    return Some(rows);
+                }
+                Ok(FollowUpdate::Stopped { version, reason }) => {
+                    self.version = version;
+                    self.stopped = Some(reason);
+                }
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => return None,
+            }
+        }
+    }
+
+    /// Pause when `nav` was scrolled above the last pin.
+    pub(crate) fn observe(&mut self, nav: &SpreadsheetState) {
+        if self.following && self.pinned.is_some_and(|top| nav.current_row() < top) {
+            self.following = false;
+        }
+    }
+
+    /// Follow again from the next [`pin`](Self::pin).
+    pub(crate) fn resume(&mut self) {
+        self.following = true;
+        self.unseen = 0;
+    }
+
+    /// While following, scroll `nav` to the last page of `rows`, `page`
+    /// rows tall (the N×F table) or the last sample columns (F×N).
+    /// Returns whether it moved.
+    pub(crate) fn pin(&mut self, nav: &mut SpreadsheetState, rows: usize, page: usize) -> bool {
+        if !self.following {
+            return false;
+        }
+        let before = nav.current_row();
+        // F×N scrolls whole windows of sample columns
+        let top = if nav.transposed() {
+            rows
+        } else {
+            rows.saturating_sub(page.max(1))
+        };
+        nav.scroll_to_row(top);
+        self.pinned = Some(nav.current_row());
+        nav.current_row() != before
+    }
+
+    /// `FOLLOW v12`, `FOLLOW paused, 40 new (G)` or why it stopped.
+    pub(crate) fn badge(&self) -> String {
+        match (&self.stopped, self.following) {
+            (Some(reason), _) => format!("FOLLOW stopped: {reason}"),
+            (None, true) => format!("FOLLOW v{}", self.version),
+            (None, false) if self.unseen > 0 => {
+                format!("FOLLOW paused, {} new ({FOLLOW_KEY})", self.unseen)
+            }
+            (None, false) => format!("FOLLOW paused ({FOLLOW_KEY})"),
+        }
+    }
+}
+
+/// Draw the [`Follow::badge`] right-aligned on the bottom border of the
+/// frame: bold while following, plain while paused, in the warning colour
+/// once stopped.
+pub(crate) fn render_follow_badge(f: &mut Frame, follow: &Follow) {
+    let frame = f.area();
+    let text = format!(" {} ", follow.badge());
+    let width = (text.width() as u16).min(frame.width.saturating_sub(2));
+    let area = Rect {
+        x: frame.right().saturating_sub(width + 1),
+        y: frame.bottom().saturating_sub(1),
+        width,
+        height: 1.min(frame.height),
+    };
+    let style = match (&follow.stopped, follow.following) {
+        (Some(_), _) => Style::default().fg(TEXT_WARNING),
+        (None, true) => Style::default()
+            .fg(TEXT_ACCENT)
+            .add_modifier(Modifier::BOLD),
+        (None, false) => Style::default().fg(TEXT_SECONDARY),
+    };
+    f.render_widget(Paragraph::new(Span::styled(text, style)), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::dense_rows_to_batch;
+    use crate::display::LanceLayout;
+    use crate::display::state::Dims;
+    use crate::functions::batch_source::BatchSource;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use std::sync::mpsc::channel;
+
+    fn appended(rows: usize, total_rows: usize, version: u64) -> FollowUpdate {
+        let batch = dense_rows_to_batch(&vec![vec![1.0; 2]; rows]).unwrap();
+        FollowUpdate::Appended(FollowedRows {
+            rows: BatchSource::from(batch),
+            dense: Vec::new(),
+            total_rows,
+            version,
+        })
+    }
+
+    #[test]
+    fn scrolling_up_pauses_and_resuming_pins_the_last_page() {
+        let (updates, receiver) = channel();
+        let mut follow = Follow::new(receiver, 3);
+        let dims = |rows| Dims {
+            rows,
+            features: 2,
+            visible: 8,
+        };
+        let mut nav = SpreadsheetState::new(LanceLayout::DenseRowMajor, dims(50));
+
+        // Pinned: the last 10 rows of 50, then of 60.
+        assert!(follow.pin(&mut nav, 50, 10));
+        assert_eq!(nav.current_row(), 40);
+        assert_eq!(follow.badge(), "FOLLOW v3");
+        updates.send(appended(10, 60, 4)).unwrap();
+        assert_eq!(follow.poll().unwrap().total_rows, 60);
+        assert!(follow.poll().is_none());
+        nav.clamp(dims(60));
+        assert!(follow.pin(&mut nav, 60, 10));
+        assert_eq!(nav.current_row(), 50);
+        assert!(!follow.pin(&mut nav, 60, 10));
+
+        // Scrolling up pauses; new rows are counted, not scrolled to.
+        nav.handle_key(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+        follow.observe(&nav);
+        updates.send(appended(5, 65, 5)).unwrap();
+        follow.poll().unwrap();
+        nav.clamp(dims(65));
+        assert!(!follow.pin(&mut nav, 65, 10));
+        assert_eq!(nav.current_row(), 49);
+        assert_eq!(follow.badge(), "FOLLOW paused, 5 new (G)");
+
+        follow.resume();
+        assert!(follow.pin(&mut nav, 65, 10));
+        assert_eq!(nav.current_row(), 55);
+        assert_eq!(follow.badge(), "FOLLOW v5");
+
+        updates
+            .send(FollowUpdate::Stopped {
+                version: 6,
+                reason: "version 6 has 3 rows, fewer than the 65 shown".to_string(),
+            })
+            .unwrap();
+        assert!(follow.poll().is_none());
+        assert!(
+            follow
+                .badge()
+                .starts_with("FOLLOW stopped: version 6 has 3 rows")
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Where the viewers start counting. Every row, feature and node index
+//! they show — Row labels, table titles, COO triples, sparsity map ranges,
+//! connectivity node ids — and the indices the column picker searches are
+//! 0-based, 1-based (as in MATLAB or R), or 0-based without the Row column
+//! for more room. `--index-base` picks the start and [`INDEX_BASE_KEY`]
+//! cycles through them. Stored data, bookmark files and exports keep
+//! 0-based indices.
+use clap::ValueEnum;
+use std::cell::Cell;
+use std::ops::Range;
+use std::sync::Mutex;
+
+/// How the viewers number rows, features and nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum IndexBase {
+    /// From 0, with the Row column
+    #[default]
+    #[value(name = "0")]
+    Zero,
+    /// From 1, with the Row column
+    #[value(name = "1")]
+    One,
+    /// From 0, without the Row column
+    #[value(name = "none")]
+    NoRowColumn,
+}
+
+/// Key cycling the [`IndexBase`] in the viewers.
+pub(crate) const INDEX_BASE_KEY: char = '#';
+
+impl IndexBase {
+    /// The base after this one, for [`INDEX_BASE_KEY`].
+    pub(crate) fn next(self) -> Self {
+        match self {
+            IndexBase::Zero => IndexBase::One,
+            IndexBase::One => IndexBase::NoRowColumn,
+            IndexBase::NoRowColumn => IndexBase::Zero,
+        }
+    }
+
+    /// Notice shown when [`INDEX_BASE_KEY`] switches to this base.
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            IndexBase::Zero => "indices from 0",
+            IndexBase::One => "indices from 1",
+            IndexBase::NoRowColumn => "indices from 0, Row column hidden",
+        }
+    }
+
+    /// Whether tables show the Row column.
+    pub(crate) fn row_column(self) -> bool {
+        self != IndexBase::NoRowColumn
+    }
+
+    /// How 0-based `index` is shown.
+    pub(crate) fn show(self, index: usize) -> usize {
+        match self {
+            IndexBase::One => index + 1,
+            IndexBase::Zero | IndexBase::NoRowColumn => index,
+        }
+    }
+
+    /// `"a–b"` for the items in `range`, first and last as shown.
+    pub(crate) fn span(self, range: Range<usize>) -> String {
+        format!(
+            "{}–{}",
+            self.show(range.start),
+            self.show(range.end.saturating_sub(1))
+        )
+    }
+}
+
+static CONFIGURED: Mutex<Option<IndexBase>> = Mutex::new(None);
+
+thread_local! {
+    /// The base a viewer switched to; viewers draw on their own thread.
+    static CURRENT: Cell<Option<IndexBase>> = const { Cell::new(None) };
+}
+
+/// Set the index base the viewers opened from now on start with
+/// (`--index-base`).
+pub fn configure_index_base(base: IndexBase) {
+    *CONFIGURED.lock().unwrap() = Some(base);
+}
+
+/// The base chosen with [`configure_index_base`].
+pub(crate) fn configured_index_base() -> IndexBase {
+    CONFIGURED.lock().unwrap().unwrap_or_default()
+}
+
+/// The base the viewer on this thread numbers with.
+pub(crate) fn index_base() -> IndexBase {
+    CURRENT.get().unwrap_or_else(configured_index_base)
+}
+
+/// Number with `base` on this thread from now on.
+pub(crate) fn set_index_base(base: IndexBase) {
+    CURRENT.set(Some(base));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bases_shift_shown_indices() {
+        assert_eq!(IndexBase::Zero.span(0..10), "0–9");
+        assert_eq!(IndexBase::One.span(0..10), "1–10");
+        assert_eq!(IndexBase::NoRowColumn.span(5..6), "5–5");
+        assert_eq!(IndexBase::One.show(41), 42);
+
+        assert!(!IndexBase::NoRowColumn.row_column());
+        assert_eq!(IndexBase::NoRowColumn.next(), IndexBase::Zero);
+
+        // The switch is per thread, over the configured start.
+        set_index_base(IndexBase::One);
+        assert_eq!(index_base(), IndexBase::One);
+        let other = std::thread::spawn(index_base).join().unwrap();
+        assert_eq!(other, configured_index_base());
+    }
+}
@@ -0,0 +1,61 @@
+//! Key events as the viewers see them: presses only (Windows also reports
+//! releases), Ctrl-C as quit everywhere, and Shift on the arrows for fast
+//! scrolling.
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+/// Rows or columns moved by Shift+Arrow
+pub(crate) const FAST_STEP: usize = 10;
+
+/// The key of a key press; releases and non-key events give `None`.
+pub(crate) fn pressed(event: &Event) -> Option<KeyEvent> {
+    match event {
+        Event::Key(key) if key.kind == KeyEventKind::Press => Some(*key),
+        _ => None,
+    }
+}
+
+/// Ctrl-C, which raw mode delivers as a plain key.
+pub(crate) fn is_interrupt(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// How far a movement key moves: [`FAST_STEP`] with Shift, else 1.
+pub(crate) fn step(key: &KeyEvent) -> usize {
+    if key.modifiers.contains(KeyModifiers::SHIFT) {
+        FAST_STEP
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEventState;
+
+    #[test]
+    fn releases_are_dropped_and_modifiers_kept() {
+        let press = KeyEvent::new(KeyCode::Down, KeyModifiers::SHIFT);
+        let release = KeyEvent::new_with_kind_and_state(
+            KeyCode::Down,
+            KeyModifiers::SHIFT,
+            KeyEventKind::Release,
+            KeyEventState::NONE,
+        );
+        assert_eq!(pressed(&Event::Key(press)), Some(press));
+        assert_eq!(pressed(&Event::Key(release)), None);
+        assert_eq!(pressed(&Event::Resize(80, 24)), None);
+
+        assert_eq!(step(&press), FAST_STEP);
+        assert_eq!(step(&KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)), 1);
+        assert!(is_interrupt(&KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL
+        )));
+        assert!(!is_interrupt(&KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::NONE
+        )));
+    }
+}
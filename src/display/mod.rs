@@ -1,20 +1,73 @@
+pub(crate) mod column_groups;
+pub(crate) mod column_order;
+pub(crate) mod diff_column;
+#[allow(clippy::module_inception)]
 pub(crate) mod display;
 pub(crate) mod display_1d;
+pub(crate) mod display_bookmarks;
+pub(crate) mod display_clusters;
+pub(crate) mod display_column_picker;
 pub(crate) mod display_coo;
+pub(crate) mod display_empty;
+pub(crate) mod display_export;
+pub(crate) mod display_frequencies;
+pub(crate) mod display_hidden;
+pub(crate) mod display_histogram;
+pub(crate) mod display_log;
+pub(crate) mod display_metadata;
+pub(crate) mod display_neighbors;
+pub(crate) mod display_ragged;
+pub(crate) mod display_rename;
+pub(crate) mod display_scatter;
+pub(crate) mod display_sparse_rows;
 pub(crate) mod display_sparse_viz;
+pub(crate) mod display_thumbnail;
 pub(crate) mod display_transposed;
+pub(crate) mod follow;
+pub(crate) mod index_base;
+pub(crate) mod keys;
+pub(crate) mod null_rows;
+pub(crate) mod outlier_cells;
+pub(crate) mod redraw;
+pub(crate) mod review;
+pub(crate) mod row_profile;
+pub(crate) mod row_stats;
+pub(crate) mod screenshot;
+pub(crate) mod state;
+pub(crate) mod stats_columns;
+pub(crate) mod transform;
+pub(crate) mod value_format;
+pub(crate) mod vector_cells;
+pub(crate) mod vector_columns;
+pub(crate) mod window;
+pub(crate) mod window_summary;
 
-/// Logical view of how a Lance dataset is stored.
-///
-/// - DenseRowMajor: { vector: FixedSizeList<Float64>[F] } – each row is a dense vector
-/// - SparseCoo:     { row: UInt32, col: UInt32, value: Float64 } – COO triplets
-/// - Vector1D:      single primitive column (e.g. lambdas, norms, indices)
-/// - Other:         anything else; shown as‑is
-pub enum LanceLayout {
-    DenseRowMajor,
-    SparseCoo,
-    Vector1D,
-    Other,
+pub use crate::functions::constant_columns::{
+    DEFAULT_CONSTANT_THRESHOLD, configure_constant_threshold,
+};
+pub use crate::functions::functions::{LanceLayout, ROWID_COLUMN};
+pub use crate::functions::info::{MetadataEntry, schema_metadata_entries};
+pub use column_groups::{GroupSpec, configure_column_groups};
+pub use diff_column::DiffKind;
+pub use display::{RenderOptions, render_batch_to_string};
+pub use index_base::{IndexBase, configure_index_base};
+pub use null_rows::NullFilter;
+pub use row_stats::{Aggregate, DEFAULT_AGGREGATES, NullPolicy, configure_row_stats};
+pub use stats_columns::{StatsColumns, configure_stats_columns};
+pub use transform::Transform;
+pub use window::SourceWindow;
+
+/// Run a viewer's blocking event loop on tokio's blocking pool, so the
+/// runtime's workers stay free for background loads while it is open.
+/// Panics in the viewer propagate as before.
+pub(crate) async fn run_viewer<T: Send + 'static>(
+    viewer: impl FnOnce() -> anyhow::Result<T> + Send + 'static,
+) -> anyhow::Result<T> {
+    match tokio::task::spawn_blocking(viewer).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(e.into()),
+    }
 }
 
 // === Color Definitions =====================================================
@@ -24,6 +77,9 @@ use ratatui::style::Color;
 const EVEN_ROW_BG: Color = Color::Rgb(40, 42, 54);
 const ODD_ROW_BG: Color = Color::Rgb(50, 52, 64);
 
+// Background of rows added since `--since-version`
+const NEW_ROW_BG: Color = Color::Rgb(38, 64, 52); // Dim green
+
 // Alternating column background colors
 const EVEN_COL_BG: Color = Color::Rgb(44, 46, 58);
 const ODD_COL_BG: Color = Color::Rgb(54, 56, 68);
@@ -36,6 +92,7 @@ const HEADER_BG: Color = Color::Rgb(68, 71, 90);
 const TEXT_PRIMARY: Color = Color::Rgb(248, 248, 242); // Off-white
 const TEXT_SECONDARY: Color = Color::Rgb(139, 233, 253); // Cyan
 const TEXT_ACCENT: Color = Color::Rgb(80, 250, 123); // Green
+const TEXT_WARNING: Color = Color::Rgb(255, 121, 198); // Pink
 
 // Border colors
 const BORDER_PRIMARY: Color = Color::Rgb(98, 114, 164); // Blue-purple
@@ -44,4 +101,4 @@ const BORDER_ACCENT: Color = Color::Rgb(139, 233, 253); // Cyan
 // Sparse visualization colors
 const SPARSE_ASTERISK: Color = Color::Rgb(255, 121, 198); // Hot pink
 const SPARSE_DOT: Color = Color::Rgb(68, 71, 90); // Dark gray
-const SPARSE_BORDER: Color = Color::Rgb(80, 250, 123); // Green
+const SPARSE_BAND_BG: Color = Color::Rgb(40, 60, 70); // Dim teal
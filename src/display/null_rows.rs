@@ -0,0 +1,232 @@
+//! `u`: show only the rows with a NULL in some feature column, then only the
+//! rows without any, then all rows again. Which rows hold a NULL is read
+//! from the columns' validity bitmaps once and kept as a mask; the filtered
+//! rows keep their source ids, so labels, bookmarks and added-row marks
+//! still refer to the dataset.
+
+use anyhow::Result;
+use arrow::compute::{filter_record_batch, is_null, or};
+use arrow_array::{Array, BooleanArray};
+
+use crate::display::ROWID_COLUMN;
+use crate::display::window::SourceWindow;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::functions::attach_row_ids;
+
+/// Key cycling the [`NullFilter`].
+pub(crate) const NULL_ROWS_KEY: char = 'u';
+
+/// Which rows `u` leaves in the view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullFilter {
+    /// Every row
+    #[default]
+    Off,
+    /// Rows with at least one NULL feature value
+    WithNulls,
+    /// Rows whose feature values are all valid
+    Complete,
+}
+
+impl NullFilter {
+    /// The filter after this one, for [`NULL_ROWS_KEY`].
+    pub(crate) fn next(self) -> Self {
+        match self {
+            NullFilter::Off => NullFilter::WithNulls,
+            NullFilter::WithNulls => NullFilter::Complete,
+            NullFilter::Complete => NullFilter::Off,
+        }
+    }
+
+    /// Title suffix of the row count, e.g. `"rows 1–20 of 57 with NULLs"`.
+    pub(crate) fn label(self) -> Option<&'static str> {
+        match self {
+            NullFilter::Off => None,
+            NullFilter::WithNulls => Some("with NULLs"),
+            NullFilter::Complete => Some("without NULLs"),
+        }
+    }
+
+    fn keeps(self, has_null: bool) -> bool {
+        match self {
+            NullFilter::Off => true,
+            NullFilter::WithNulls => has_null,
+            NullFilter::Complete => !has_null,
+        }
+    }
+}
+
+/// For each row of `data`, whether any of the columns `cols` is NULL in it.
+pub(crate) fn null_row_mask(data: &BatchSource, cols: &[usize]) -> Result<Vec<bool>> {
+    let mut mask = Vec::with_capacity(data.num_rows());
+    for (_, batch) in data.iter() {
+        let mut any = BooleanArray::from(vec![false; batch.num_rows()]);
+        for &c in cols {
+            let col = batch.column(c);
+            if col.logical_null_count() > 0 {
+                any = or(&any, &is_null(col)?)?;
+            }
+        }
+        mask.extend(any.values().iter());
+    }
+    Ok(mask)
+}
+
+/// The `u` state of one viewer: the filter and the mask it is taken from,
+/// computed on first use.
+#[derive(Debug, Default)]
+pub(crate) struct NullRows {
+    filter: NullFilter,
+    mask: Option<Vec<bool>>,
+}
+
+impl NullRows {
+    pub(crate) fn new(filter: NullFilter) -> Self {
+        Self { filter, mask: None }
+    }
+
+    /// Move to the next filter that leaves rows of `data` in the view,
+    /// given the feature columns `cols`; `Off` when neither does.
+    pub(crate) fn cycle(&mut self, data: &BatchSource, cols: &[usize]) -> Result<NullFilter> {
+        let mask = match self.mask.take() {
+            Some(mask) => mask,
+            None => null_row_mask(data, cols)?,
+        };
+        let mut next = self.filter.next();
+        while next != NullFilter::Off && !mask.iter().any(|&has_null| next.keeps(has_null)) {
+            next = next.next();
+        }
+        self.mask = Some(mask);
+        self.filter = next;
+        Ok(next)
+    }
+
+    /// Forget the NULL mask after rows were added; the next use reads it
+    /// again.
+    pub(crate) fn rows_changed(&mut self) {
+        self.mask = None;
+    }
+
+    /// The rows of `data` the filter keeps, tagged with their source ids;
+    /// `None` while it is off.
+    pub(crate) fn apply(
+        &mut self,
+        data: &BatchSource,
+        cols: &[usize],
+    ) -> Result<Option<BatchSource>> {
+        if self.filter == NullFilter::Off {
+            return Ok(None);
+        }
+        if self.mask.is_none() {
+            self.mask = Some(null_row_mask(data, cols)?);
+        }
+        let mask = self.mask.as_deref().unwrap_or_default();
+        let filter = self.filter;
+        let kept = data.try_map(|batch, start| {
+            let rows = start..start + batch.num_rows();
+            let keep: BooleanArray = mask[rows.clone()]
+                .iter()
+                .map(|&has_null| Some(filter.keeps(has_null)))
+                .collect();
+            if batch.column_by_name(ROWID_COLUMN).is_some() {
+                return Ok(filter_record_batch(batch, &keep)?);
+            }
+            let ids: Vec<u64> = rows
+                .filter(|&r| filter.keeps(mask[r]))
+                .map(|r| r as u64)
+                .collect();
+            attach_row_ids(&filter_record_batch(batch, &keep)?, ids)
+        })?;
+        Ok(Some(kept))
+    }
+
+    /// `source` as titles name the `kept` rows: counted from the first, with
+    /// the filter after the total.
+    pub(crate) fn window(&self, source: SourceWindow, kept: &BatchSource) -> SourceWindow {
+        SourceWindow {
+            row_offset: 0,
+            total_rows: kept.num_rows(),
+            row_filter: self.filter.label(),
+            ..source
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::{Float64Array, RecordBatch, StringArray, UInt64Array};
+    use std::sync::Arc;
+
+    /// The source row ids a filtered `data` holds, for tests.
+    fn ids(data: &BatchSource) -> Vec<u64> {
+        data.iter()
+            .flat_map(|(_, batch)| {
+                let ids = batch
+                    .column_by_name(ROWID_COLUMN)
+                    .unwrap()
+                    .as_any()
+                    .downcast_ref::<UInt64Array>()
+                    .unwrap()
+                    .clone();
+                ids.values().to_vec()
+            })
+            .collect()
+    }
+
+    fn batch(x: Vec<Option<f64>>, y: Vec<Option<f64>>) -> RecordBatch {
+        let n = x.len();
+        RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("x", DataType::Float64, true),
+                Field::new("y", DataType::Float64, true),
+                Field::new("note", DataType::Utf8, true),
+            ])),
+            vec![
+                Arc::new(Float64Array::from(x)),
+                Arc::new(Float64Array::from(y)),
+                Arc::new(StringArray::from(vec![None::<&str>; n])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn rows_are_split_by_their_null_features() {
+        let data = BatchSource::new(
+            batch(vec![], vec![]).schema(),
+            vec![
+                batch(vec![Some(1.0), None], vec![Some(2.0), Some(3.0)]),
+                batch(vec![Some(4.0), Some(5.0)], vec![None, Some(6.0)]),
+            ],
+        )
+        .unwrap();
+        // the note column is all NULL but is not a feature
+        assert_eq!(
+            null_row_mask(&data, &[0, 1]).unwrap(),
+            [false, true, true, false]
+        );
+
+        let mut rows = NullRows::default();
+        assert!(rows.apply(&data, &[0, 1]).unwrap().is_none());
+        assert_eq!(rows.cycle(&data, &[0, 1]).unwrap(), NullFilter::WithNulls);
+        let kept = rows.apply(&data, &[0, 1]).unwrap().unwrap();
+        assert_eq!(ids(&kept), [1, 2]);
+        let source = rows.window(SourceWindow::whole(4), &kept);
+        assert_eq!(source.span(0..2), "0–1 of 2 with NULLs");
+
+        assert_eq!(rows.cycle(&data, &[0, 1]).unwrap(), NullFilter::Complete);
+        assert_eq!(ids(&rows.apply(&data, &[0, 1]).unwrap().unwrap()), [0, 3]);
+        assert_eq!(rows.cycle(&data, &[0, 1]).unwrap(), NullFilter::Off);
+    }
+
+    #[test]
+    fn filters_leaving_no_rows_are_skipped() {
+        let data = BatchSource::from(batch(vec![Some(1.0)], vec![Some(2.0)]));
+        let mut rows = NullRows::default();
+        // no row has a NULL: straight to the complete rows
+        assert_eq!(rows.cycle(&data, &[0, 1]).unwrap(), NullFilter::Complete);
+        assert_eq!(rows.cycle(&data, &[0, 1]).unwrap(), NullFilter::Off);
+    }
+}
@@ -0,0 +1,109 @@
+//! Outlier highlighting of the N×F table (`!`): values outside the fences
+//! `outliers` uses with its defaults (`k = 1.5`, IQR) are drawn in the
+//! warning colour. The fences come from the viewer's [`ColumnStats`] of the
+//! loaded rows, computed the first time they are needed and kept for the
+//! session; features without spread are never highlighted. Cells are
+//! flagged by their raw values, so a `z` transform does not move them.
+
+use std::collections::HashSet;
+
+use crate::cache::ColumnStats;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::outliers::{DEFAULT_K, Fence, OutlierMethod};
+use crate::functions::stats::numeric_values;
+
+/// The outlying cells of the loaded rows.
+#[derive(Debug, Clone)]
+pub(crate) struct OutlierCells {
+    method: OutlierMethod,
+    k: f64,
+    /// (schema column, row) of each outlying value
+    cells: HashSet<(usize, usize)>,
+}
+
+impl OutlierCells {
+    /// Outliers of the schema columns `features` of `data`, whose `stats`
+    /// are given in the same order, with the default method and `k`.
+    pub(crate) fn new(data: &BatchSource, features: &[usize], stats: &[ColumnStats]) -> Self {
+        Self::with_method(data, features, stats, OutlierMethod::default(), DEFAULT_K)
+    }
+
+    pub(crate) fn with_method(
+        data: &BatchSource,
+        features: &[usize],
+        stats: &[ColumnStats],
+        method: OutlierMethod,
+        k: f64,
+    ) -> Self {
+        let mut cells = HashSet::new();
+        for (&col, stats) in features.iter().zip(stats) {
+            let Some(fence) = Fence::new(stats, method, k) else {
+                continue;
+            };
+            for (offset, batch) in data.iter() {
+                let Some(values) = numeric_values(batch.column(col)) else {
+                    continue;
+                };
+                for (r, v) in values.iter().enumerate() {
+                    if v.is_some_and(|v| fence.severity(v).is_some()) {
+                        cells.insert((col, offset + r));
+                    }
+                }
+            }
+        }
+        Self { method, k, cells }
+    }
+
+    /// Whether the value in schema column `col` of row `row` is an outlier.
+    pub(crate) fn is_outlier(&self, col: usize, row: usize) -> bool {
+        self.cells.contains(&(col, row))
+    }
+
+    /// Title note, e.g. `! 3 outliers (1.5×IQR)`.
+    pub(crate) fn note(&self) -> String {
+        format!(
+            "! {} outliers ({}×{})",
+            self.cells.len(),
+            self.k,
+            self.method.label()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functions::stats::source_column_stats;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::{Float64Array, RecordBatch};
+    use std::sync::Arc;
+
+    #[test]
+    fn only_values_past_the_fence_of_a_spread_column_are_flagged() {
+        let schema = Schema::new(vec![
+            Field::new("col_0", DataType::Float64, true),
+            Field::new("col_1", DataType::Float64, false),
+        ]);
+        let mut wide: Vec<Option<f64>> = (0..20).map(|i| Some(i as f64)).collect();
+        wide.push(Some(200.0));
+        wide.push(None);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float64Array::from(wide)),
+                Arc::new(Float64Array::from(vec![7.0; 22])),
+            ],
+        )
+        .unwrap();
+        let data = BatchSource::from(batch);
+
+        let stats = source_column_stats(&data, &[0, 1]);
+        let cells = OutlierCells::new(&data, &[0, 1], &stats);
+        assert!(cells.is_outlier(0, 20));
+        assert!(!cells.is_outlier(0, 19));
+        assert!(!cells.is_outlier(0, 21));
+        // A constant column has no fence.
+        assert!(!cells.is_outlier(1, 0));
+        assert_eq!(cells.note(), "! 1 outliers (1.5×IQR)");
+    }
+}
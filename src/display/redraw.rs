@@ -0,0 +1,188 @@
+//! Redraw only when something changed: every viewer loop keeps a [`Redraw`]
+//! gate that is marked dirty by input (keys, mouse, resize) or by data
+//! arriving in the background, and skips `terminal.draw` otherwise. Idle
+//! loops also poll less often.
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode};
+use ratatui::{Frame, Terminal, backend::Backend};
+use std::time::Duration;
+
+use crate::display::keys::{is_interrupt, pressed};
+use crate::display::screenshot::ScreenCapture;
+
+/// Poll timeout right after activity, keeping key repeat responsive
+pub(crate) const ACTIVE_POLL: Duration = Duration::from_millis(100);
+/// Poll timeout once the view has been idle for [`IDLE_AFTER`] polls
+pub(crate) const IDLE_POLL: Duration = Duration::from_millis(500);
+const IDLE_AFTER: u32 = 10;
+
+/// Dirty flag and idle counter of one viewer loop.
+pub(crate) struct Redraw {
+    dirty: bool,
+    idle_polls: u32,
+}
+
+impl Redraw {
+    /// A gate that draws the first frame.
+    pub(crate) fn new() -> Self {
+        Self {
+            dirty: true,
+            idle_polls: 0,
+        }
+    }
+
+    /// Something visible changed; draw on the next pass.
+    pub(crate) fn mark(&mut self) {
+        self.dirty = true;
+        self.idle_polls = 0;
+    }
+
+    /// Whether to draw now; clears the flag.
+    pub(crate) fn take(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// Record the outcome of one poll: any event (key press, mouse,
+    /// resize, …) marks the view dirty; a timeout or a key release counts
+    /// towards idling.
+    pub(crate) fn observe(&mut self, event: Option<&Event>) {
+        match event {
+            Some(Event::Key(_)) if event.and_then(pressed).is_none() => self.idle(),
+            Some(_) => self.mark(),
+            None => self.idle(),
+        }
+    }
+
+    fn idle(&mut self) {
+        self.idle_polls = self.idle_polls.saturating_add(1);
+    }
+
+    pub(crate) fn poll_timeout(&self) -> Duration {
+        if self.idle_polls >= IDLE_AFTER {
+            IDLE_POLL
+        } else {
+            ACTIVE_POLL
+        }
+    }
+}
+
+/// The next terminal event, or `None` after `timeout` without one.
+pub(crate) fn poll_event(timeout: Duration) -> Result<Option<Event>> {
+    Ok(if event::poll(timeout)? {
+        Some(event::read()?)
+    } else {
+        None
+    })
+}
+
+/// Drive a full-screen view until `handle_key` returns false or Ctrl-C is
+/// pressed; key releases are ignored and Ctrl-s saves the screen.
+/// `next_event` is [`poll_event`] outside tests. Returns the number of
+/// frames drawn.
+pub(crate) fn run_view<B: Backend, S>(
+    terminal: &mut Terminal<B>,
+    state: &mut S,
+    mut next_event: impl FnMut(Duration) -> Result<Option<Event>>,
+    mut render: impl FnMut(&mut Frame, &S),
+    mut handle_key: impl FnMut(&mut S, KeyCode) -> bool,
+) -> Result<usize>
+where
+    B::Error: Send + Sync + 'static,
+{
+    let mut redraw = Redraw::new();
+    let mut capture = ScreenCapture::new();
+    let mut frames = 0;
+    loop {
+        if redraw.take() {
+            let frame = terminal.draw(|f| {
+                render(f, state);
+                capture.render(f);
+            })?;
+            capture.keep(frame.buffer);
+            frames += 1;
+        }
+        let event = next_event(redraw.poll_timeout())?;
+        redraw.observe(event.as_ref());
+        if let Some(key) = event.as_ref().and_then(pressed)
+            && (is_interrupt(&key) || !capture.handle_key(&key) && !handle_key(state, key.code))
+        {
+            return Ok(frames);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
+    use ratatui::backend::TestBackend;
+    use std::collections::VecDeque;
+
+    fn key(c: char) -> Option<Event> {
+        Some(Event::Key(KeyEvent::new(
+            KeyCode::Char(c),
+            KeyModifiers::NONE,
+        )))
+    }
+
+    #[test]
+    fn idle_polls_draw_once_and_back_off() {
+        let mut terminal = Terminal::new(TestBackend::new(20, 4)).unwrap();
+        // Counts the keys seen; `q` quits.
+        let mut seen = 0usize;
+        let mut run = |seen: &mut usize, events: Vec<Option<Event>>| {
+            let mut events = VecDeque::from(events);
+            let mut timeouts = Vec::new();
+            let frames = run_view(
+                &mut terminal,
+                seen,
+                |timeout| {
+                    timeouts.push(timeout);
+                    Ok(events.pop_front().expect("view should have quit"))
+                },
+                |_, _| {},
+                |seen, code| {
+                    *seen += 1;
+                    code != KeyCode::Char('q')
+                },
+            )
+            .unwrap();
+            (frames, timeouts)
+        };
+
+        // 30 idle polls: only the first frame is drawn.
+        let mut events = vec![None; 30];
+        events.push(key('q'));
+        let (frames, timeouts) = run(&mut seen, events);
+        assert_eq!(frames, 1);
+        assert_eq!(timeouts[0], ACTIVE_POLL);
+        assert_eq!(timeouts[IDLE_AFTER as usize], IDLE_POLL);
+
+        // Each key or resize redraws once, and activity restores the short poll.
+        let mut events = vec![None; 12];
+        events.extend([key('j'), None, None, Some(Event::Resize(30, 5)), None]);
+        events.push(key('q'));
+        let (frames, timeouts) = run(&mut seen, events);
+        assert_eq!(frames, 3);
+        assert_eq!(timeouts[12], IDLE_POLL);
+        assert_eq!(timeouts[13], ACTIVE_POLL);
+
+        // Releases (as Windows reports them) neither reach the view nor
+        // redraw it, and Ctrl-C quits without the view's own quit key.
+        let release = KeyEvent::new_with_kind_and_state(
+            KeyCode::Char('j'),
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+            KeyEventState::NONE,
+        );
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        let before = seen;
+        let (frames, _) = run(
+            &mut seen,
+            vec![Some(Event::Key(release)), None, Some(Event::Key(ctrl_c))],
+        );
+        assert_eq!(frames, 1);
+        assert_eq!(seen, before);
+    }
+}
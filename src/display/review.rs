@@ -0,0 +1,191 @@
+//! Review mode (`V`) for stepping through rows by hand: Space moves the
+//! cursor (`▶` in the Row column) down one row and keeps it centred, `x`
+//! flags the cursor row (`⚑`), and `V` again ends the review with a prompt
+//! for the text file the flagged source row ids go to, optionally also
+//! written as a one-column Lance dataset. Flags and the cursor's row are
+//! kept in the dataset's session state, so a review picks up where it
+//! stopped.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use std::path::{Path, PathBuf};
+use unicode_width::UnicodeWidthStr;
+
+use crate::display::display_metadata::centered;
+use crate::display::*;
+
+/// Key starting and ending a review.
+pub(crate) const REVIEW_KEY: char = 'V';
+/// Key flagging the cursor row while reviewing.
+pub(crate) const FLAG_KEY: char = 'x';
+/// Marker of a flagged row in the Row column.
+pub(crate) const FLAG_MARK: char = '⚑';
+/// Marker of the review cursor in the Row column.
+pub(crate) const CURSOR_MARK: char = '▶';
+
+/// Default file for the flags of the dataset at `source`:
+/// `javelin_flags_<name>.txt` in the working directory.
+pub(crate) fn flags_file(source: &str) -> String {
+    let name = Path::new(source)
+        .file_stem()
+        .map_or("dataset".into(), |s| s.to_string_lossy());
+    format!("javelin_flags_{name}.txt")
+}
+
+/// Top row that puts `cursor` in the middle of a `page` of rows.
+pub(crate) fn centered_start(cursor: usize, page: usize) -> usize {
+    cursor.saturating_sub(page / 2)
+}
+
+/// What the viewer should do after a key press in the prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum FlagsAction {
+    Stay,
+    /// End the review without writing
+    Close,
+    /// Write the flags to `path`, and next to it as a `.lance` dataset when
+    /// `lance` is set
+    Write {
+        path: PathBuf,
+        lance: bool,
+    },
+}
+
+/// Path entry for the flags file, shown when a review ends.
+pub(crate) struct FlagsPrompt {
+    input: String,
+    lance: bool,
+    /// Flagged rows, shown in the title
+    count: usize,
+}
+
+impl FlagsPrompt {
+    pub(crate) fn new(default: &str, count: usize) -> Self {
+        Self {
+            input: default.to_string(),
+            lance: false,
+            count,
+        }
+    }
+
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> FlagsAction {
+        match code {
+            KeyCode::Esc => FlagsAction::Close,
+            KeyCode::Tab => {
+                self.lance = !self.lance;
+                FlagsAction::Stay
+            }
+            KeyCode::Enter => match self.input.trim() {
+                "" => FlagsAction::Stay,
+                path => FlagsAction::Write {
+                    path: PathBuf::from(path),
+                    lance: self.lance,
+                },
+            },
+            KeyCode::Backspace => {
+                self.input.pop();
+                FlagsAction::Stay
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                FlagsAction::Stay
+            }
+            _ => FlagsAction::Stay,
+        }
+    }
+}
+
+pub(crate) fn render_flags_prompt(f: &mut Frame, prompt: &FlagsPrompt) {
+    let area = centered(f.area(), 60);
+    let area = Rect {
+        height: area.height.min(4),
+        ..area
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(format!(" Write {} flagged rows to ", prompt.count))
+        .title_bottom(" Enter write | Tab also .lance | Esc skip ");
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(prompt.input.clone(), Style::default().fg(TEXT_PRIMARY)),
+            Span::styled("▏", Style::default().fg(TEXT_ACCENT)),
+        ]),
+        Line::from(Span::styled(
+            format!(
+                "[{}] also as a Lance dataset",
+                if prompt.lance { "x" } else { " " }
+            ),
+            Style::default().fg(TEXT_SECONDARY),
+        )),
+    ];
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// One-line badge in the bottom-left corner while reviewing, e.g.
+/// `review #41 ⚑ · 3 flagged · Space next · x flag · V done`.
+pub(crate) fn render_review_badge(f: &mut Frame, label: &str, flagged: bool, count: usize) {
+    let frame = f.area();
+    let text = format!(
+        " review {label}{} · {count} flagged · Space next · {FLAG_KEY} flag · {REVIEW_KEY} done ",
+        if flagged {
+            format!(" {FLAG_MARK}")
+        } else {
+            String::new()
+        }
+    );
+    let width = (text.width() as u16).min(frame.width.saturating_sub(2));
+    let area = Rect {
+        x: frame.x + 1,
+        y: frame.bottom().saturating_sub(1),
+        width,
+        height: 1.min(frame.height),
+    };
+    let style = Style::default()
+        .fg(TEXT_ACCENT)
+        .add_modifier(Modifier::BOLD);
+    f.render_widget(Paragraph::new(Span::styled(text, style)), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_defaults_to_the_dataset_file_and_toggles_lance() {
+        assert_eq!(
+            flags_file("/data/runs/embeddings.lance"),
+            "javelin_flags_embeddings.txt"
+        );
+        assert_eq!(centered_start(3, 20), 0);
+        assert_eq!(centered_start(30, 20), 20);
+
+        let mut prompt = FlagsPrompt::new(&flags_file("m.lance"), 2);
+        assert_eq!(prompt.handle_key(KeyCode::Tab), FlagsAction::Stay);
+        for _ in 0.."m.txt".len() {
+            prompt.handle_key(KeyCode::Backspace);
+        }
+        for c in "qa.txt".chars() {
+            prompt.handle_key(KeyCode::Char(c));
+        }
+        assert_eq!(
+            prompt.handle_key(KeyCode::Enter),
+            FlagsAction::Write {
+                path: PathBuf::from("javelin_flags_qa.txt"),
+                lance: true,
+            }
+        );
+
+        // an empty path writes nothing, Esc ends the review all the same
+        let mut prompt = FlagsPrompt::new("", 0);
+        assert_eq!(prompt.handle_key(KeyCode::Enter), FlagsAction::Stay);
+        assert_eq!(prompt.handle_key(KeyCode::Esc), FlagsAction::Close);
+    }
+}
@@ -0,0 +1,147 @@
+//! The row profile of the 1D viewer (`p`): the vector cut into consecutive
+//! row buckets, one per panel line up to [`MAX_BUCKETS`], each drawn as a
+//! horizontal bar of its mean, so values that drift over the file or
+//! cluster in some row range stand out where the histogram mixes them.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use std::ops::Range;
+
+use crate::display::index_base::index_base;
+use crate::display::{TEXT_ACCENT, TEXT_SECONDARY, TEXT_WARNING};
+use crate::functions::format::format_float;
+
+/// Key showing or hiding the row profile.
+pub(crate) const PROFILE_KEY: char = 'p';
+
+/// Buckets at most, however tall the panel.
+pub(crate) const MAX_BUCKETS: usize = 50;
+
+/// Mean of the values in one run of rows.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Bucket {
+    pub rows: Range<usize>,
+    /// `None` when every row of it is NULL
+    pub mean: Option<f64>,
+}
+
+/// `rows` (one value per row, `None` for NULL) cut into `buckets` runs of
+/// near-equal length, the longer ones first; fewer when there are fewer
+/// rows. Non-finite values are left out of the means.
+pub(crate) fn row_buckets(rows: &[Option<f64>], buckets: usize) -> Vec<Bucket> {
+    let buckets = buckets.min(rows.len()).max(1);
+    let (base, extra) = (rows.len() / buckets, rows.len() % buckets);
+    let mut start = 0;
+    (0..buckets)
+        .map(|b| {
+            let end = start + base + usize::from(b < extra);
+            let values: Vec<f64> = rows[start..end]
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|v| v.is_finite())
+                .collect();
+            let mean =
+                (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64);
+            let bucket = Bucket {
+                rows: start..end,
+                mean,
+            };
+            start = end;
+            bucket
+        })
+        .collect()
+}
+
+/// Draw the profile of `rows` in `area`, a bucket per inner line: its row
+/// range, a bar as long as its mean's share of the largest |mean|
+/// (negative means in the warning colour) and the mean.
+pub(crate) fn render_row_profile(f: &mut Frame, area: Rect, rows: &[Option<f64>]) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Row profile ({PROFILE_KEY} hides) "));
+    let (height, width) = (
+        area.height.saturating_sub(2) as usize,
+        area.width.saturating_sub(2) as usize,
+    );
+    if rows.is_empty() || height == 0 {
+        f.render_widget(block, area);
+        return;
+    }
+    let buckets = row_buckets(rows, height.min(MAX_BUCKETS));
+
+    let base = index_base();
+    let labels: Vec<String> = buckets.iter().map(|b| base.span(b.rows.clone())).collect();
+    let means: Vec<String> = buckets
+        .iter()
+        .map(|b| b.mean.map_or("NULL".to_string(), |m| format_float(m, 4)))
+        .collect();
+    let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let mean_width = means.iter().map(String::len).max().unwrap_or(0);
+    let bar_width = width.saturating_sub(label_width + mean_width + 2);
+    let largest = buckets
+        .iter()
+        .filter_map(|b| b.mean)
+        .fold(0.0f64, |acc, m| acc.max(m.abs()));
+
+    let lines: Vec<Line> = buckets
+        .iter()
+        .zip(labels.iter().zip(&means))
+        .map(|(bucket, (label, mean))| {
+            let m = bucket.mean.unwrap_or(0.0);
+            let len = if largest > 0.0 {
+                (m.abs() / largest * bar_width as f64).round() as usize
+            } else {
+                0
+            };
+            let colour = if m < 0.0 { TEXT_WARNING } else { TEXT_ACCENT };
+            Line::from(vec![
+                Span::styled(
+                    format!("{label:>label_width$} "),
+                    Style::default().fg(TEXT_SECONDARY),
+                ),
+                Span::styled(
+                    format!("{:<bar_width$}", "█".repeat(len)),
+                    Style::default().fg(colour),
+                ),
+                Span::raw(format!(" {mean:>mean_width$}")),
+            ])
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_split_rows_evenly_and_skip_nulls() {
+        let rows: Vec<Option<f64>> = vec![
+            Some(1.0),
+            Some(3.0),
+            None,
+            Some(f64::NAN),
+            Some(10.0),
+            Some(20.0),
+            Some(30.0),
+        ];
+        // 7 rows in 3 buckets: 3 + 2 + 2
+        let buckets = row_buckets(&rows, 3);
+        let spans: Vec<_> = buckets.iter().map(|b| b.rows.clone()).collect();
+        assert_eq!(spans, [0..3, 3..5, 5..7]);
+        let means: Vec<_> = buckets.iter().map(|b| b.mean).collect();
+        assert_eq!(means, [Some(2.0), Some(10.0), Some(25.0)]);
+
+        // no more buckets than rows, and an all-NULL bucket has no mean
+        let buckets = row_buckets(&[None, Some(4.0)], 50);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].mean, None);
+        assert_eq!(row_buckets(&[], 10).len(), 1);
+    }
+}
@@ -0,0 +1,500 @@
+//! The aggregate columns trailing each N×F row (and each F×N feature): which
+//! aggregates (`--row-stats`), whether they are shown (`--no-row-stats`, `a`
+//! toggles), their scope (all features or the visible window, `A` toggles),
+//! how NULLs enter them (`--null-policy`, `_` cycles), and a per-row cache
+//! for each scope so scrolling does not recompute them.
+
+use clap::ValueEnum;
+use ratatui::style::Color;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::display::display::{STAT_DECIMALS, mean_std};
+use crate::display::{TEXT_ACCENT, TEXT_WARNING};
+use crate::functions::format::format_float;
+
+/// Cell text of an aggregate the inputs do not explain: an infinity from
+/// finite values, or NaN from values without one (e.g. the mean of both
+/// infinities).
+pub(crate) const OVERFLOW_TEXT: &str = "overflow";
+
+/// Colour of an aggregate cell showing `text`.
+pub(crate) fn stat_color(text: &str) -> Color {
+    if text == OVERFLOW_TEXT {
+        TEXT_WARNING
+    } else {
+        TEXT_ACCENT
+    }
+}
+
+/// An aggregate over the numeric feature values of one row (or feature).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum Aggregate {
+    Mean,
+    Std,
+    Min,
+    Max,
+    Median,
+    /// Euclidean norm
+    L2,
+    /// Count of non-zero values
+    Nnz,
+}
+
+/// The aggregates shown when `--row-stats` is not given.
+pub const DEFAULT_AGGREGATES: &[Aggregate] = &[Aggregate::Mean, Aggregate::Std];
+
+impl Aggregate {
+    /// Column header.
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Aggregate::Mean => "avg",
+            Aggregate::Std => "std",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
+            Aggregate::Median => "median",
+            Aggregate::L2 => "l2",
+            Aggregate::Nnz => "nnz",
+        }
+    }
+
+    /// Cell text over `vals`; `NA` when there are none (`nnz` is then 0),
+    /// [`OVERFLOW_TEXT`] when the result is not defined or not
+    /// representable.
+    pub(crate) fn text(self, vals: &[f64]) -> String {
+        if self == Aggregate::Nnz {
+            return vals.iter().filter(|v| **v != 0.0).count().to_string();
+        }
+        let Some((mean, std)) = mean_std(vals) else {
+            return "NA".to_string();
+        };
+        let value = match self {
+            Aggregate::Mean => mean,
+            Aggregate::Std => std,
+            Aggregate::Min => vals.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregate::Max => vals.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Aggregate::Median => median(vals),
+            // hypot scales instead of squaring
+            Aggregate::L2 => vals.iter().fold(0.0f64, |acc, v| acc.hypot(*v)),
+            Aggregate::Nnz => unreachable!(),
+        };
+        let unexplained = if value.is_nan() {
+            !vals.iter().any(|v| v.is_nan())
+        } else {
+            value.is_infinite() && !vals.iter().any(|v| v.is_infinite())
+        };
+        if unexplained {
+            return OVERFLOW_TEXT.to_string();
+        }
+        format_float(value, STAT_DECIMALS)
+    }
+}
+
+/// How NULL values enter the aggregates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum NullPolicy {
+    /// Leave them out
+    #[default]
+    Skip,
+    /// Count them as 0.0, as in sparse-ish matrices where NULL means zero
+    Zero,
+    /// Make the aggregate NaN
+    Propagate,
+}
+
+/// Key cycling the [`NullPolicy`] in the viewers.
+pub(crate) const NULL_POLICY_KEY: char = '_';
+
+impl NullPolicy {
+    /// Header suffix, e.g. `avg[zero]`.
+    pub(crate) fn tag(self) -> &'static str {
+        match self {
+            NullPolicy::Skip => "skip",
+            NullPolicy::Zero => "zero",
+            NullPolicy::Propagate => "nan",
+        }
+    }
+
+    /// The policy after this one, for [`NULL_POLICY_KEY`].
+    pub(crate) fn next(self) -> Self {
+        match self {
+            NullPolicy::Skip => NullPolicy::Zero,
+            NullPolicy::Zero => NullPolicy::Propagate,
+            NullPolicy::Propagate => NullPolicy::Skip,
+        }
+    }
+
+    /// Texts of `aggregates` over `vals`, where `None` is a NULL.
+    pub(crate) fn texts(self, aggregates: &[Aggregate], vals: &[Option<f64>]) -> Vec<String> {
+        let vals: Vec<f64> = match self {
+            NullPolicy::Skip => vals.iter().flatten().copied().collect(),
+            NullPolicy::Zero => vals.iter().map(|v| v.unwrap_or(0.0)).collect(),
+            NullPolicy::Propagate if vals.iter().any(Option::is_none) => {
+                return aggregates.iter().map(|_| "NaN".to_string()).collect();
+            }
+            NullPolicy::Propagate => vals.iter().flatten().copied().collect(),
+        };
+        aggregates.iter().map(|a| a.text(&vals)).collect()
+    }
+}
+
+fn median(vals: &[f64]) -> f64 {
+    let mut sorted = vals.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        // halved first, so two values near f64::MAX do not overflow
+        sorted[mid - 1] / 2.0 + sorted[mid] / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Config {
+    aggregates: &'static [Aggregate],
+    shown: bool,
+    null_policy: NullPolicy,
+}
+
+static CONFIG: Mutex<Option<Config>> = Mutex::new(None);
+
+/// Set the aggregates of the viewers opened from now on (`--row-stats`),
+/// whether they start shown (`--no-row-stats`), and how they treat NULLs
+/// (`--null-policy`). An empty list keeps [`DEFAULT_AGGREGATES`]; any other
+/// list is leaked, as [`RenderOptions`](crate::display::RenderOptions)
+/// borrows it for good.
+pub fn configure_row_stats(aggregates: Vec<Aggregate>, shown: bool, null_policy: NullPolicy) {
+    let aggregates: &'static [Aggregate] =
+        if aggregates.is_empty() || aggregates == DEFAULT_AGGREGATES {
+            DEFAULT_AGGREGATES
+        } else {
+            Box::leak(aggregates.into_boxed_slice())
+        };
+    *CONFIG.lock().unwrap() = Some(Config {
+        aggregates,
+        shown,
+        null_policy,
+    });
+}
+
+/// Aggregate columns of one viewer, with the texts computed so far.
+pub(crate) struct RowStats {
+    aggregates: Vec<Aggregate>,
+    shown: bool,
+    null_policy: NullPolicy,
+    /// Aggregate over the visible window instead of all features (all rows
+    /// when transposed)
+    windowed: bool,
+    /// All features leave some hidden columns out (`d`)
+    excluding: bool,
+    /// Features of the N×F rows left out of the aggregates (`--stats-cols`)
+    skipped: Vec<usize>,
+    /// Texts over everything, per (transposed, row or feature index)
+    all: RefCell<HashMap<(bool, usize), Vec<String>>>,
+    /// Texts over the window they were computed for
+    window: RefCell<WindowTexts>,
+}
+
+/// Windowed texts; only valid while the same window is shown.
+#[derive(Default)]
+struct WindowTexts {
+    transposed: bool,
+    window: Vec<usize>,
+    texts: HashMap<usize, Vec<String>>,
+}
+
+impl RowStats {
+    pub(crate) fn new(aggregates: &[Aggregate], shown: bool) -> Self {
+        Self {
+            aggregates: aggregates.to_vec(),
+            shown,
+            null_policy: NullPolicy::default(),
+            windowed: false,
+            excluding: false,
+            skipped: Vec::new(),
+            all: RefCell::default(),
+            window: RefCell::default(),
+        }
+    }
+
+    /// The same aggregates scoped to the visible window.
+    pub(crate) fn windowed(mut self, windowed: bool) -> Self {
+        self.windowed = windowed;
+        self
+    }
+
+    /// The same aggregates treating NULLs as `policy` says.
+    pub(crate) fn with_null_policy(mut self, policy: NullPolicy) -> Self {
+        self.null_policy = policy;
+        self
+    }
+
+    /// The process-wide choice of [`configure_row_stats`].
+    pub(crate) fn configured() -> Self {
+        match *CONFIG.lock().unwrap() {
+            Some(config) => {
+                Self::new(config.aggregates, config.shown).with_null_policy(config.null_policy)
+            }
+            None => Self::new(DEFAULT_AGGREGATES, true),
+        }
+    }
+
+    /// The aggregates [`RowStats::configured`] starts with on screen; none
+    /// under `--no-row-stats`.
+    pub(crate) fn configured_columns() -> &'static [Aggregate] {
+        match *CONFIG.lock().unwrap() {
+            Some(config) if config.shown => config.aggregates,
+            Some(_) => &[],
+            None => DEFAULT_AGGREGATES,
+        }
+    }
+
+    /// Show or hide the columns (`a`).
+    pub(crate) fn toggle(&mut self) {
+        self.shown = !self.shown;
+    }
+
+    /// Switch between all features and the visible window (`A`).
+    pub(crate) fn toggle_scope(&mut self) {
+        self.windowed = !self.windowed;
+    }
+
+    /// Move to the next [`NullPolicy`] ([`NULL_POLICY_KEY`]), recomputing
+    /// the texts.
+    pub(crate) fn cycle_null_policy(&mut self) {
+        self.null_policy = self.null_policy.next();
+        self.invalidate();
+    }
+
+    pub(crate) fn null_policy(&self) -> NullPolicy {
+        self.null_policy
+    }
+
+    /// Forget the texts computed so far, after the values shown change.
+    pub(crate) fn invalidate(&self) {
+        self.all.borrow_mut().clear();
+        *self.window.borrow_mut() = WindowTexts::default();
+    }
+
+    pub(crate) fn is_windowed(&self) -> bool {
+        self.windowed
+    }
+
+    /// Whether hidden columns are left out of the features aggregated.
+    pub(crate) fn set_excluding(&mut self, excluding: bool) {
+        self.excluding = excluding;
+    }
+
+    /// Leave the `skipped` schema columns out of the N×F row aggregates,
+    /// recomputing the texts when they change.
+    pub(crate) fn set_skipped(&mut self, skipped: Vec<usize>) {
+        if skipped != self.skipped {
+            self.skipped = skipped;
+            self.invalidate();
+        }
+    }
+
+    pub(crate) fn skipped(&self) -> &[usize] {
+        &self.skipped
+    }
+
+    /// `cols` without the skipped ones.
+    pub(crate) fn aggregated(&self, cols: &[usize]) -> Vec<usize> {
+        cols.iter()
+            .copied()
+            .filter(|c| !self.skipped.contains(c))
+            .collect()
+    }
+
+    /// Header of an aggregate column with the NULL policy, e.g.
+    /// `avg[skip]`; windowed ones are starred (`avg*[skip]`), and ones over
+    /// all features that leave hidden or skipped columns out get a dagger
+    /// (`avg†[skip]`). Aggregates of the F×N table (`transposed`) run over
+    /// rows and never leave any out.
+    pub(crate) fn header(&self, transposed: bool, aggregate: Aggregate) -> String {
+        let mark = if self.windowed {
+            "*"
+        } else if (self.excluding || !self.skipped.is_empty()) && !transposed {
+            "†"
+        } else {
+            ""
+        };
+        format!("{}{mark}[{}]", aggregate.label(), self.null_policy.tag())
+    }
+
+    /// The aggregate columns to draw; none while hidden.
+    pub(crate) fn columns(&self) -> &[Aggregate] {
+        if self.shown { &self.aggregates } else { &[] }
+    }
+
+    /// Cell texts of row `idx` (feature `idx` when `transposed`), computed
+    /// the first time they are asked for in the current scope. `values`
+    /// gets the visible `window` when windowed and `None` for everything,
+    /// and gives `None` for each NULL.
+    pub(crate) fn texts(
+        &self,
+        transposed: bool,
+        idx: usize,
+        window: &[usize],
+        values: impl FnOnce(Option<&[usize]>) -> Vec<Option<f64>>,
+    ) -> Vec<String> {
+        let compute = |scope| self.null_policy.texts(&self.aggregates, &values(scope));
+        if !self.windowed {
+            return self
+                .all
+                .borrow_mut()
+                .entry((transposed, idx))
+                .or_insert_with(|| compute(None))
+                .clone();
+        }
+        let mut cached = self.window.borrow_mut();
+        if cached.transposed != transposed || cached.window != window {
+            *cached = WindowTexts {
+                transposed,
+                window: window.to_vec(),
+                texts: HashMap::new(),
+            };
+        }
+        cached
+            .texts
+            .entry(idx)
+            .or_insert_with(|| compute(Some(window)))
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_and_cached_texts() {
+        let vals = [3.0, 0.0, -4.0, 1.0];
+        let text = |a: Aggregate| a.text(&vals);
+        assert_eq!(text(Aggregate::Mean), "0.0000000");
+        assert_eq!(text(Aggregate::Min), "-4.0000000");
+        assert_eq!(text(Aggregate::Max), "3.0000000");
+        assert_eq!(text(Aggregate::Median), "0.5000000");
+        assert_eq!(
+            text(Aggregate::L2),
+            format_float(26f64.sqrt(), STAT_DECIMALS)
+        );
+        assert_eq!(text(Aggregate::Nnz), "3");
+        assert_eq!(Aggregate::Median.text(&[]), "NA");
+        assert_eq!(Aggregate::Nnz.text(&[]), "0");
+
+        let mut stats = RowStats::new(&[Aggregate::Max, Aggregate::Nnz], true);
+        // Values of row 0 over a window of its 4 features, or over all.
+        let row = |window: Option<&[usize]>| match window {
+            Some(w) => w.iter().map(|&i| Some(vals[i])).collect(),
+            None => vals.iter().copied().map(Some).collect(),
+        };
+        let window = [1, 2];
+        assert_eq!(stats.texts(false, 0, &window, row), ["3.0000000", "3"]);
+        // A cached row does not read its values again.
+        let cached = |_: Option<&[usize]>| -> Vec<Option<f64>> { unreachable!() };
+        assert_eq!(stats.texts(false, 0, &window, cached), ["3.0000000", "3"]);
+
+        // Each scope has its own cache; a new window recomputes.
+        stats.toggle_scope();
+        assert_eq!(stats.header(false, Aggregate::Max), "max*[skip]");
+        assert_eq!(stats.texts(false, 0, &window, row), ["0.0000000", "1"]);
+        assert_eq!(stats.texts(false, 0, &window, cached), ["0.0000000", "1"]);
+        assert_eq!(stats.texts(false, 0, &[2, 3], row), ["1.0000000", "2"]);
+        stats.toggle_scope();
+        assert_eq!(stats.texts(false, 0, &[2, 3], cached), ["3.0000000", "3"]);
+
+        stats.toggle();
+        assert!(stats.columns().is_empty());
+        stats.toggle();
+        assert_eq!(stats.columns(), [Aggregate::Max, Aggregate::Nnz]);
+    }
+
+    #[test]
+    fn extreme_values_aggregate_without_overflowing() {
+        let texts = |vals: &[f64]| -> Vec<String> {
+            [
+                Aggregate::Mean,
+                Aggregate::Std,
+                Aggregate::Median,
+                Aggregate::L2,
+            ]
+            .iter()
+            .map(|a| a.text(vals))
+            .collect()
+        };
+        // Squaring 1e200 overflows; the scaled update does not.
+        let (mean, std) = mean_std(&[1e200, 3e200]).unwrap();
+        assert!((mean / 2e200 - 1.0).abs() < 1e-12, "{mean}");
+        assert!((std / 1e200 - 1.0).abs() < 1e-12, "{std}");
+        let big = texts(&[1.7e308, 1.7e308]);
+        assert!(big[..3].iter().all(|t| t != OVERFLOW_TEXT), "{big:?}");
+        assert_eq!(Aggregate::Std.text(&[1.7e308, 1.7e308]), "0.0000000");
+        // ...but the norm of two of them is not representable.
+        assert_eq!(Aggregate::L2.text(&[1.7e308, 1.7e308]), OVERFLOW_TEXT);
+
+        // One infinity defines the mean, not the spread.
+        let inf = texts(&[1.0, f64::INFINITY]);
+        assert_eq!(inf, ["inf", OVERFLOW_TEXT, "inf", "inf"]);
+        let neg = texts(&[f64::NEG_INFINITY, 2.0]);
+        assert_eq!(neg, ["-inf", OVERFLOW_TEXT, "-inf", "inf"]);
+        // Both infinities leave the mean and median undefined.
+        let both = texts(&[f64::NEG_INFINITY, 0.0, f64::INFINITY]);
+        assert_eq!(both, [OVERFLOW_TEXT, OVERFLOW_TEXT, "0.0000000", "inf"]);
+        assert_eq!(stat_color(&both[0]), TEXT_WARNING);
+        assert_eq!(stat_color(&both[2]), TEXT_ACCENT);
+        // A NaN input explains a NaN result.
+        assert_eq!(Aggregate::Mean.text(&[1.0, f64::NAN]), "NaN");
+    }
+
+    #[test]
+    fn null_policies_on_an_all_null_row() {
+        let aggregates = [Aggregate::Mean, Aggregate::Std, Aggregate::Nnz];
+        let nulls = [None, None, None];
+        assert_eq!(
+            NullPolicy::Skip.texts(&aggregates, &nulls),
+            ["NA", "NA", "0"]
+        );
+        assert_eq!(
+            NullPolicy::Zero.texts(&aggregates, &nulls),
+            ["0.0000000", "0.0000000", "0"]
+        );
+        assert_eq!(
+            NullPolicy::Propagate.texts(&aggregates, &nulls),
+            ["NaN", "NaN", "NaN"]
+        );
+
+        // With some values: zero lowers the mean, propagate still wins
+        let some = [Some(3.0), None, Some(1.0)];
+        assert_eq!(
+            NullPolicy::Skip.texts(&[Aggregate::Mean], &some),
+            ["2.0000000"]
+        );
+        assert_eq!(
+            NullPolicy::Zero.texts(&[Aggregate::Mean], &some),
+            ["1.3333333"]
+        );
+        assert_eq!(
+            NullPolicy::Propagate.texts(&[Aggregate::Mean], &some),
+            ["NaN"]
+        );
+
+        let mut stats = RowStats::new(&[Aggregate::Mean], true);
+        assert_eq!(stats.header(false, Aggregate::Mean), "avg[skip]");
+        stats.set_excluding(true);
+        assert_eq!(stats.header(false, Aggregate::Mean), "avg†[skip]");
+        assert_eq!(stats.header(true, Aggregate::Mean), "avg[skip]");
+        stats.set_excluding(false);
+        let row = |_: Option<&[usize]>| some.to_vec();
+        assert_eq!(stats.texts(false, 0, &[], row), ["2.0000000"]);
+        stats.cycle_null_policy();
+        assert_eq!(stats.header(false, Aggregate::Mean), "avg[zero]");
+        // the cached text is recomputed under the new policy
+        assert_eq!(stats.texts(false, 0, &[], row), ["1.3333333"]);
+        stats.cycle_null_policy();
+        stats.cycle_null_policy();
+        assert_eq!(stats.null_policy(), NullPolicy::Skip);
+    }
+}
@@ -0,0 +1,394 @@
+//! Screen dumps: `Ctrl-s` in any interactive view asks for plain text or
+//! ANSI-coloured text and writes the last drawn frame, exactly as it is on
+//! screen, to `javelin-screen-<UTC timestamp>.txt` (or `.ansi`) in the
+//! working directory. `display --screenshot` writes one headless frame the
+//! same way.
+
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use unicode_width::UnicodeWidthStr;
+
+use crate::display::display_bookmarks::render_notice;
+use crate::display::display_metadata::centered;
+use crate::display::*;
+use crate::functions::format::utc_timestamp;
+
+/// How a screen is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ScreenFormat {
+    /// The symbols only, trailing spaces trimmed
+    Text,
+    /// The symbols with SGR escapes for colours and modifiers
+    Ansi,
+}
+
+const FORMATS: [ScreenFormat; 2] = [ScreenFormat::Text, ScreenFormat::Ansi];
+
+impl ScreenFormat {
+    /// `Ansi` for a `.ansi` path, `Text` otherwise.
+    pub(crate) fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("ansi") => ScreenFormat::Ansi,
+            _ => ScreenFormat::Text,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ScreenFormat::Text => "txt",
+            ScreenFormat::Ansi => "ansi",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ScreenFormat::Text => "text",
+            ScreenFormat::Ansi => "ANSI colours",
+        }
+    }
+
+    /// `buffer` in this format.
+    pub(crate) fn render(self, buffer: &Buffer) -> String {
+        match self {
+            ScreenFormat::Text => buffer_text(buffer),
+            ScreenFormat::Ansi => buffer_ansi(buffer),
+        }
+    }
+}
+
+/// The cells of one line, skipping those a wide symbol before them covers,
+/// so each line is as wide on screen as the terminal.
+fn visible_cells(line: &[ratatui::buffer::Cell]) -> impl Iterator<Item = &ratatui::buffer::Cell> {
+    let mut covered = 0;
+    line.iter().filter(move |cell| {
+        if covered > 0 {
+            covered -= 1;
+            return false;
+        }
+        covered = cell.symbol().width().saturating_sub(1);
+        true
+    })
+}
+
+fn lines(buffer: &Buffer) -> impl Iterator<Item = &[ratatui::buffer::Cell]> {
+    buffer.content.chunks(buffer.area.width.max(1) as usize)
+}
+
+/// `buffer` as text, one line per terminal row (trailing spaces trimmed).
+pub(crate) fn buffer_text(buffer: &Buffer) -> String {
+    lines(buffer)
+        .map(|line| {
+            let text: String = visible_cells(line).map(|c| c.symbol()).collect();
+            text.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `buffer` as text with an SGR escape wherever the style changes; each line
+/// ends with a reset.
+pub(crate) fn buffer_ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for line in lines(buffer) {
+        let mut style = None;
+        for cell in visible_cells(line) {
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(cell_style) {
+                out.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                style = Some(cell_style);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = String::from("\x1b[0");
+    for (flag, code) in [
+        (Modifier::BOLD, 1),
+        (Modifier::DIM, 2),
+        (Modifier::ITALIC, 3),
+        (Modifier::UNDERLINED, 4),
+        (Modifier::REVERSED, 7),
+        (Modifier::CROSSED_OUT, 9),
+    ] {
+        if modifier.contains(flag) {
+            let _ = write!(codes, ";{code}");
+        }
+    }
+    color_code(&mut codes, fg, 30);
+    color_code(&mut codes, bg, 40);
+    codes.push('m');
+    codes
+}
+
+/// Append the SGR parameters of `color` as a foreground (`base` 30) or
+/// background (40) colour; the terminal default needs none after a reset.
+fn color_code(codes: &mut String, color: Color, base: u8) {
+    let named = |n: u8| base + n;
+    let bright = |n: u8| base + 60 + n;
+    let code = match color {
+        Color::Reset => return,
+        Color::Black => named(0),
+        Color::Red => named(1),
+        Color::Green => named(2),
+        Color::Yellow => named(3),
+        Color::Blue => named(4),
+        Color::Magenta => named(5),
+        Color::Cyan => named(6),
+        Color::Gray => named(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Indexed(i) => {
+            let _ = write!(codes, ";{};5;{i}", base + 8);
+            return;
+        }
+        Color::Rgb(r, g, b) => {
+            let _ = write!(codes, ";{};2;{r};{g};{b}", base + 8);
+            return;
+        }
+    };
+    let _ = write!(codes, ";{code}");
+}
+
+/// Write `buffer` in `format` to `path`.
+pub(crate) fn write_screen(buffer: &Buffer, format: ScreenFormat, path: &Path) -> Result<()> {
+    std::fs::write(path, format.render(buffer))
+        .with_context(|| format!("writing screen to {:?}", path))
+}
+
+/// Write `buffer` to a new `javelin-screen-<UTC timestamp>` file in `dir`;
+/// a second dump within the same second gets a `-2`, `-3`, … suffix.
+pub(crate) fn save_screen(buffer: &Buffer, format: ScreenFormat, dir: &Path) -> Result<PathBuf> {
+    let stamp = utc_timestamp(SystemTime::now());
+    for n in 1.. {
+        let suffix = if n == 1 {
+            String::new()
+        } else {
+            format!("-{n}")
+        };
+        let path = dir.join(format!(
+            "javelin-screen-{stamp}{suffix}.{}",
+            format.extension()
+        ));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(format.render(buffer).as_bytes())
+                    .with_context(|| format!("writing screen to {:?}", path))?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e).with_context(|| format!("creating {:?}", path)),
+        }
+    }
+    unreachable!("some suffix is free")
+}
+
+/// `Ctrl-s` of one view: the last drawn frame, the frame being saved while
+/// the format prompt is open, and the toast naming the written file.
+pub(crate) struct ScreenCapture {
+    last: Buffer,
+    /// The frame as it was when `Ctrl-s` was pressed, and the highlighted
+    /// format
+    pending: Option<(Buffer, usize)>,
+    toast: Option<String>,
+}
+
+impl ScreenCapture {
+    pub(crate) fn new() -> Self {
+        Self {
+            last: Buffer::empty(Rect::default()),
+            pending: None,
+            toast: None,
+        }
+    }
+
+    /// Remember `frame` as what is on screen; call after every draw.
+    pub(crate) fn keep(&mut self, frame: &Buffer) {
+        if self.pending.is_none() {
+            self.last.clone_from(frame);
+        }
+    }
+
+    /// Handle `key` if it is `Ctrl-s` or goes to the open prompt; returns
+    /// whether it was used. Other keys clear the toast.
+    pub(crate) fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        self.handle_key_in(key, Path::new("."))
+    }
+
+    fn handle_key_in(&mut self, key: &KeyEvent, dir: &Path) -> bool {
+        self.toast = None;
+        if let Some((_, selected)) = self.pending.as_mut() {
+            let format = match key.code {
+                KeyCode::Char('t') => Some(ScreenFormat::Text),
+                KeyCode::Char('a') => Some(ScreenFormat::Ansi),
+                KeyCode::Enter => Some(FORMATS[*selected]),
+                KeyCode::Up | KeyCode::Down | KeyCode::Char('k') | KeyCode::Char('j') => {
+                    *selected = 1 - *selected;
+                    None
+                }
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.pending = None;
+                    None
+                }
+                _ => None,
+            };
+            if let Some(format) = format {
+                let (buffer, _) = self.pending.take().expect("prompt is open");
+                self.toast = Some(match save_screen(&buffer, format, dir) {
+                    Ok(path) => format!("screen saved to {}", path.display()),
+                    Err(e) => format!("{e:#}"),
+                });
+                log::info!("screenshot: {}", self.toast.as_deref().unwrap_or_default());
+            }
+            return true;
+        }
+        if key.code == KeyCode::Char('s') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.pending = Some((self.last.clone(), 0));
+            return true;
+        }
+        false
+    }
+
+    /// Draw the format prompt or the toast over the frame.
+    pub(crate) fn render(&self, f: &mut Frame) {
+        if let Some((_, selected)) = &self.pending {
+            let area = centered(f.area(), 40);
+            let area = Rect {
+                height: area.height.min(4),
+                ..area
+            };
+            let lines: Vec<Line> = FORMATS
+                .iter()
+                .enumerate()
+                .map(|(i, format)| {
+                    let style = if i == *selected {
+                        Style::default()
+                            .fg(TEXT_ACCENT)
+                            .add_modifier(Modifier::REVERSED)
+                    } else {
+                        Style::default().fg(TEXT_PRIMARY)
+                    };
+                    let key = &format.extension()[..1];
+                    Line::from(Span::styled(format!("[{key}] {}", format.label()), style))
+                })
+                .collect();
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_ACCENT))
+                .title(" Save screen as ");
+            f.render_widget(Clear, area);
+            f.render_widget(Paragraph::new(lines).block(block), area);
+        } else if let Some(text) = &self.toast {
+            render_notice(f, text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn saves_the_frame_from_before_the_prompt() {
+        let mut terminal = Terminal::new(TestBackend::new(24, 6)).unwrap();
+        let mut capture = ScreenCapture::new();
+        let mut draw = |capture: &mut ScreenCapture| {
+            let frame = terminal
+                .draw(|f| {
+                    f.render_widget(
+                        Paragraph::new(Span::styled(
+                            "héllo 表",
+                            Style::default().fg(Color::Rgb(1, 2, 3)).bold(),
+                        )),
+                        f.area(),
+                    );
+                    capture.render(f);
+                })
+                .unwrap();
+            capture.keep(frame.buffer);
+        };
+        draw(&mut capture);
+
+        let tmp = tempfile::tempdir().unwrap();
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        assert!(!capture.handle_key_in(&KeyEvent::from(KeyCode::Char('x')), tmp.path()));
+        assert!(capture.handle_key_in(&ctrl_s, tmp.path()));
+        // The prompt is drawn over the screen but not saved with it.
+        draw(&mut capture);
+        assert!(capture.handle_key_in(&KeyEvent::from(KeyCode::Char('a')), tmp.path()));
+
+        let files: Vec<PathBuf> = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].extension().unwrap(), "ansi");
+        let ansi = std::fs::read_to_string(&files[0]).unwrap();
+        assert!(
+            ansi.starts_with("\x1b[0;1;38;2;1;2;3mhéllo 表\x1b[0m"),
+            "{ansi:?}"
+        );
+        assert!(!ansi.contains("Save screen"));
+        assert!(
+            capture
+                .toast
+                .as_deref()
+                .unwrap()
+                .starts_with("screen saved to ")
+        );
+
+        // Plain text: the wide symbol covers the next cell, trailing blanks go.
+        capture.handle_key_in(&ctrl_s, tmp.path());
+        capture.handle_key_in(&KeyEvent::from(KeyCode::Enter), tmp.path());
+        let text = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .find(|p| p.extension().unwrap() == "txt")
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(text).unwrap(),
+            format!("héllo 表{}", "\n".repeat(5))
+        );
+    }
+
+    #[test]
+    fn timestamps_are_utc_calendar_dates() {
+        let at = |secs| utc_timestamp(UNIX_EPOCH + std::time::Duration::from_secs(secs));
+        assert_eq!(at(0), "19700101-000000");
+        // 2000-02-29 (leap day) 23:59:59
+        assert_eq!(at(951_868_799), "20000229-235959");
+        assert_eq!(at(1_790_000_000), "20260921-141320");
+        assert_eq!(
+            ScreenFormat::for_path(Path::new("x.ANSI")),
+            ScreenFormat::Ansi
+        );
+        assert_eq!(
+            ScreenFormat::for_path(Path::new("x.txt")),
+            ScreenFormat::Text
+        );
+    }
+}
@@ -0,0 +1,568 @@
+//! Navigation state of the spreadsheet viewer — scroll offsets, transpose
+//! and the pinned column — and the keys that move it, kept apart from the
+//! crossterm loop so scrolling and clamping can be tested without a
+//! terminal.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::display::LanceLayout;
+use crate::display::display::HorizontalOffsets;
+use crate::display::keys::step;
+
+/// What the viewer scrolls over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Dims {
+    /// Rows of the batch
+    pub rows: usize,
+    /// `col_*` feature columns
+    pub features: usize,
+    /// Feature (N×F) or row (F×N) columns shown at once
+    pub visible: usize,
+}
+
+/// Scroll position of one viewer session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SpreadsheetState {
+    layout: LanceLayout,
+    dims: Dims,
+    /// Horizontal scroll over features (N×F)
+    col_offset: usize,
+    /// Horizontal scroll over rows (F×N)
+    row_offset: usize,
+    /// Top visible row (N×F) or feature (F×N)
+    row_start: usize,
+    /// Horizontal scroll of the COO sparsity map, clamped when drawn
+    sparse_col_offset: usize,
+    /// Shade the band around the diagonal of the COO sparsity map
+    sparse_band: bool,
+    /// false = N×F, true = F×N
+    transposed: bool,
+    /// Feature kept leftmost in N×F
+    pinned_col: Option<usize>,
+    /// Exact position of the orientation left by the last `t`, and where
+    /// this one started; restored on toggling back without scrolling
+    flipped: Option<(View, View)>,
+}
+
+/// Top and horizontal offset of one orientation: (row, feature) in N×F,
+/// (feature, row) in F×N.
+type View = (usize, usize);
+
+impl SpreadsheetState {
+    pub(crate) fn new(layout: LanceLayout, dims: Dims) -> Self {
+        Self {
+            layout,
+            dims,
+            col_offset: 0,
+            row_offset: 0,
+            row_start: 0,
+            sparse_col_offset: 0,
+            sparse_band: false,
+            transposed: false,
+            pinned_col: None,
+            flipped: None,
+        }
+    }
+
+    pub(crate) fn row_start(&self) -> usize {
+        self.row_start
+    }
+
+    pub(crate) fn transposed(&self) -> bool {
+        self.transposed
+    }
+
+    pub(crate) fn visible(&self) -> usize {
+        self.dims.visible
+    }
+
+    /// The top row, or the leftmost sample column in F×N.
+    pub(crate) fn current_row(&self) -> usize {
+        if self.transposed {
+            self.row_offset
+        } else {
+            self.row_start
+        }
+    }
+
+    /// The pinned feature, or else the leftmost visible one (N×F).
+    pub(crate) fn current_feature(&self) -> usize {
+        self.pinned_col.unwrap_or(self.col_offset)
+    }
+
+    /// Offsets for [`render_frame`](crate::display::display::render_frame),
+    /// with the scatter-plot marks.
+    pub(crate) fn offsets(&self, marked: &[usize]) -> HorizontalOffsets {
+        HorizontalOffsets {
+            col_offset: self.col_offset,
+            row_offset: self.row_offset,
+            sparse_col_offset: self.sparse_col_offset,
+            sparse_band: self.sparse_band,
+            pinned_col: self.pinned_col,
+            marked: [marked.first().copied(), marked.get(1).copied()],
+        }
+    }
+
+    /// Last valid `row_start`: rows in N×F, features in F×N.
+    fn max_row_start(&self) -> usize {
+        let items = if self.transposed {
+            self.dims.features
+        } else {
+            self.dims.rows
+        };
+        items.saturating_sub(1)
+    }
+
+    fn max_col_offset(&self) -> usize {
+        self.dims.features.saturating_sub(self.dims.visible)
+    }
+
+    fn max_row_offset(&self) -> usize {
+        self.dims.rows.saturating_sub(self.dims.visible)
+    }
+
+    fn view(&self) -> View {
+        if self.transposed {
+            (self.row_start, self.row_offset)
+        } else {
+            (self.row_start, self.col_offset)
+        }
+    }
+
+    fn set_view(&mut self, (top, across): View) {
+        self.row_start = top.min(self.max_row_start());
+        if self.transposed {
+            self.row_offset = across.min(self.max_row_offset());
+        } else {
+            self.col_offset = across.min(self.max_col_offset());
+        }
+    }
+
+    /// Flip between N×F and F×N keeping the same rows and features in view:
+    /// the top row becomes the first sample column and the first feature
+    /// the top one, and back. Flipping back without scrolling in between
+    /// restores the exact position even where the mapping was clamped.
+    fn transpose(&mut self) {
+        let left = self.view();
+        self.transposed = !self.transposed;
+        match self.flipped {
+            Some((saved, entered)) if entered == left => self.set_view(saved),
+            _ => self.set_view((left.1, left.0)),
+        }
+        self.flipped = Some((left, self.view()));
+    }
+
+    /// Take new dimensions (e.g. after a resize) and pull every offset back
+    /// into range.
+    pub(crate) fn clamp(&mut self, dims: Dims) {
+        self.dims = dims;
+        if self.layout != LanceLayout::SparseCoo {
+            self.col_offset = self.col_offset.min(self.max_col_offset());
+            self.row_offset = self.row_offset.min(self.max_row_offset());
+        }
+        self.row_start = self.row_start.min(self.max_row_start());
+        self.pinned_col = self.pinned_col.filter(|&c| c < dims.features);
+    }
+
+    /// Scroll so `row` is on top and `feature` leftmost (F×N: `feature` on
+    /// top and `row` leftmost).
+    pub(crate) fn scroll_to(&mut self, row: usize, feature: usize) {
+        if self.transposed {
+            self.row_start = feature.min(self.max_row_start());
+            self.row_offset = row.min(self.max_row_offset());
+        } else {
+            self.row_start = row.min(self.max_row_start());
+            self.col_offset = feature.min(self.max_col_offset());
+        }
+    }
+
+    pub(crate) fn scroll_to_row(&mut self, row: usize) {
+        if self.transposed {
+            self.row_offset = row.min(self.max_row_offset());
+        } else {
+            self.row_start = row.min(self.max_row_start());
+        }
+    }
+
+    /// Scroll so `feature` is leftmost (F×N: on top), optionally pinning it
+    /// there.
+    pub(crate) fn show_feature(&mut self, feature: usize, pin: bool) {
+        if self.transposed {
+            self.row_start = feature.min(self.max_row_start());
+        } else {
+            self.col_offset = feature.min(self.max_col_offset());
+        }
+        self.pinned_col = pin.then_some(feature);
+    }
+
+    /// Move the current feature one place right (or left) in the display
+    /// order, pinning it so it stays in view with the columns after it.
+    /// Returns its (old, new) position; `None` at either end.
+    pub(crate) fn move_feature(&mut self, right: bool) -> Option<(usize, usize)> {
+        let from = self.current_feature();
+        let to = if right {
+            from + 1
+        } else {
+            from.checked_sub(1)?
+        };
+        if to >= self.dims.features {
+            return None;
+        }
+        self.pinned_col = Some(to);
+        self.col_offset = to.min(self.max_col_offset());
+        Some((from, to))
+    }
+
+    /// Apply a navigation key; returns false for keys it does not handle.
+    pub(crate) fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let n = step(&key);
+        let coo = self.layout == LanceLayout::SparseCoo;
+        match key.code {
+            KeyCode::Char('t') => {
+                // Transpose only for dense layouts
+                if matches!(self.layout, LanceLayout::DenseRowMajor | LanceLayout::Other) {
+                    self.transpose();
+                }
+            }
+            KeyCode::Char('P') => self.pinned_col = None,
+
+            // horizontal
+            KeyCode::Right | KeyCode::Char('l') if coo => {
+                self.sparse_col_offset = self.sparse_col_offset.saturating_add(n)
+            }
+            KeyCode::Right | KeyCode::Char('l') if self.transposed => {
+                self.row_offset = (self.row_offset + n).min(self.max_row_offset())
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.col_offset = (self.col_offset + n).min(self.max_col_offset())
+            }
+            KeyCode::Left | KeyCode::Char('h') if coo => {
+                self.sparse_col_offset = self.sparse_col_offset.saturating_sub(n)
+            }
+            KeyCode::Left | KeyCode::Char('h') if self.transposed => {
+                self.row_offset = self.row_offset.saturating_sub(n)
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.col_offset = self.col_offset.saturating_sub(n)
+            }
+
+            // jump first/last horizontally
+            KeyCode::Char('H') if coo => self.sparse_col_offset = 0,
+            KeyCode::Char('H') if self.transposed => self.row_offset = 0,
+            KeyCode::Char('H') => self.col_offset = 0,
+            KeyCode::Char('E') if coo => self.sparse_col_offset = usize::MAX,
+            KeyCode::Char('E') if self.transposed => self.row_offset = self.max_row_offset(),
+            KeyCode::Char('E') => self.col_offset = self.max_col_offset(),
+            KeyCode::Char('B') if coo => self.sparse_band = !self.sparse_band,
+
+            // vertical; Ctrl+Home/End jump to the ends
+            KeyCode::Up | KeyCode::Char('k') => self.row_start = self.row_start.saturating_sub(n),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.row_start = (self.row_start + n).min(self.max_row_start())
+            }
+            KeyCode::Home if key.modifiers.contains(KeyModifiers::CONTROL) => self.row_start = 0,
+            KeyCode::End if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.row_start = self.max_row_start()
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMS: Dims = Dims {
+        rows: 100,
+        features: 20,
+        visible: 8,
+    };
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn with(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    /// (row_start, col_offset, row_offset, sparse_col_offset, transposed)
+    type Position = (usize, usize, usize, usize, bool);
+
+    fn position(s: &SpreadsheetState) -> Position {
+        (
+            s.row_start,
+            s.col_offset,
+            s.row_offset,
+            s.sparse_col_offset,
+            s.transposed,
+        )
+    }
+
+    #[test]
+    fn keys_scroll_within_bounds() {
+        use KeyCode::*;
+        let shift = KeyModifiers::SHIFT;
+        let ctrl = KeyModifiers::CONTROL;
+        let dense = LanceLayout::DenseRowMajor;
+        let cases: Vec<(&str, LanceLayout, Vec<KeyEvent>, Position)> = vec![
+            (
+                "up at the top stays",
+                dense,
+                vec![key(Up)],
+                (0, 0, 0, 0, false),
+            ),
+            (
+                "down moves one row",
+                dense,
+                vec![key(Down), key(Char('j'))],
+                (2, 0, 0, 0, false),
+            ),
+            (
+                "shift+down moves ten",
+                dense,
+                vec![with(Down, shift)],
+                (10, 0, 0, 0, false),
+            ),
+            (
+                "ctrl+end is the last row",
+                dense,
+                vec![with(End, ctrl)],
+                (99, 0, 0, 0, false),
+            ),
+            (
+                "down stops at the last row",
+                dense,
+                vec![with(End, ctrl), key(Down)],
+                (99, 0, 0, 0, false),
+            ),
+            (
+                "ctrl+home is the first row",
+                dense,
+                vec![with(Down, shift), with(Home, ctrl)],
+                (0, 0, 0, 0, false),
+            ),
+            (
+                "right stops at the last window",
+                dense,
+                vec![with(Right, shift), with(Right, shift)],
+                (0, 12, 0, 0, false),
+            ),
+            (
+                "left at the first column stays",
+                dense,
+                vec![key(Left)],
+                (0, 0, 0, 0, false),
+            ),
+            (
+                "E then H",
+                dense,
+                vec![key(Char('E')), key(Left), key(Char('H'))],
+                (0, 0, 0, 0, false),
+            ),
+            (
+                "E jumps to the last window",
+                dense,
+                vec![key(Char('E'))],
+                (0, 12, 0, 0, false),
+            ),
+            (
+                "transpose maps the top row and first feature",
+                dense,
+                vec![key(Down), key(Right), key(Char('t'))],
+                (1, 1, 1, 0, true),
+            ),
+            (
+                "transposed right scrolls rows",
+                dense,
+                vec![key(Char('t')), key(Right), key(Char('l'))],
+                (0, 0, 2, 0, true),
+            ),
+            (
+                "transposed E is the last row window",
+                dense,
+                vec![key(Char('t')), key(Char('E'))],
+                (0, 0, 92, 0, true),
+            ),
+            (
+                "transposed down stops at the last feature",
+                dense,
+                vec![key(Char('t')), with(End, ctrl), key(Down)],
+                (19, 0, 0, 0, true),
+            ),
+            (
+                "transposed shift+down stops at the last feature",
+                dense,
+                vec![key(Char('t')), with(Down, shift), with(Down, shift)],
+                (19, 0, 0, 0, true),
+            ),
+            (
+                "transposed up from the last feature",
+                dense,
+                vec![key(Char('t')), with(End, ctrl), key(Up)],
+                (18, 0, 0, 0, true),
+            ),
+            (
+                "transposed right stops at the last row window",
+                dense,
+                vec![key(Char('t')), key(Char('E')), key(Right)],
+                (0, 0, 92, 0, true),
+            ),
+            (
+                "transposing back maps the first sample column",
+                dense,
+                vec![key(Char('t')), key(Right), key(Char('t'))],
+                (1, 0, 1, 0, false),
+            ),
+            (
+                "1D does not transpose",
+                LanceLayout::Vector1D,
+                vec![key(Char('t'))],
+                (0, 0, 0, 0, false),
+            ),
+            (
+                "COO scrolls the sparsity map",
+                LanceLayout::SparseCoo,
+                vec![with(Right, shift), key(Left)],
+                (0, 0, 0, 9, false),
+            ),
+            (
+                "COO E is clamped when drawn",
+                LanceLayout::SparseCoo,
+                vec![key(Char('E'))],
+                (0, 0, 0, usize::MAX, false),
+            ),
+        ];
+        for (name, layout, keys, want) in cases {
+            let mut state = SpreadsheetState::new(layout, DIMS);
+            for k in keys {
+                assert!(state.handle_key(k), "{name}: {k:?} not handled");
+            }
+            assert_eq!(position(&state), want, "{name}");
+        }
+    }
+
+    #[test]
+    fn other_keys_are_left_to_the_viewer() {
+        let mut state = SpreadsheetState::new(LanceLayout::DenseRowMajor, DIMS);
+        for code in [
+            KeyCode::Char('q'),
+            KeyCode::Char('i'),
+            KeyCode::Home,
+            KeyCode::End,
+        ] {
+            assert!(!state.handle_key(key(code)), "{code:?}");
+        }
+        assert_eq!(position(&state), (0, 0, 0, 0, false));
+    }
+
+    #[test]
+    fn pinning_and_jumps() {
+        let mut state = SpreadsheetState::new(LanceLayout::DenseRowMajor, DIMS);
+        state.show_feature(15, true);
+        assert_eq!((state.col_offset, state.current_feature()), (12, 15));
+        assert!(state.handle_key(key(KeyCode::Char('P'))));
+        assert_eq!(state.current_feature(), 12);
+
+        state.scroll_to(500, 3);
+        assert_eq!((state.row_start, state.col_offset), (99, 3));
+        state.scroll_to_row(42);
+        assert_eq!(state.row_start(), 42);
+        let offsets = state.offsets(&[1, 4]);
+        assert_eq!(
+            (offsets.col_offset, offsets.marked),
+            (3, [Some(1), Some(4)])
+        );
+    }
+
+    #[test]
+    fn transposing_keeps_the_region_in_view() {
+        let dims = Dims {
+            rows: 1000,
+            features: 50,
+            visible: 8,
+        };
+        let t = key(KeyCode::Char('t'));
+        let mut state = SpreadsheetState::new(LanceLayout::DenseRowMajor, dims);
+        state.scroll_to(500, 30);
+        // Rows 500.. and features 30..38 become features 30.. on top and
+        // rows 500..508 across.
+        state.handle_key(t);
+        assert_eq!(position(&state), (30, 30, 500, 0, true));
+        state.handle_key(t);
+        assert_eq!(position(&state), (500, 30, 500, 0, false));
+
+        // Scrolling in F×N carries over to N×F.
+        state.handle_key(t);
+        state.handle_key(key(KeyCode::Down));
+        state.handle_key(key(KeyCode::Left));
+        state.handle_key(t);
+        assert_eq!((state.row_start, state.col_offset), (499, 31));
+
+        // Near the ends the mapping is clamped, and flipping back without
+        // scrolling restores the exact position.
+        state.scroll_to(995, 49);
+        assert_eq!((state.row_start, state.col_offset), (995, 42));
+        state.handle_key(t);
+        assert_eq!((state.row_start, state.row_offset), (42, 992));
+        state.handle_key(t);
+        assert_eq!((state.row_start, state.col_offset), (995, 42));
+        state.handle_key(t);
+        state.handle_key(key(KeyCode::Left));
+        state.handle_key(t);
+        assert_eq!((state.row_start, state.col_offset), (991, 42));
+    }
+
+    #[test]
+    fn clamping_after_a_resize() {
+        let mut state = SpreadsheetState::new(LanceLayout::DenseRowMajor, DIMS);
+        state.show_feature(19, true);
+        state.scroll_to_row(90);
+        // Fewer rows and a wider window pull everything back in range.
+        state.clamp(Dims {
+            rows: 50,
+            features: 20,
+            visible: 16,
+        });
+        assert_eq!(position(&state), (49, 4, 0, 0, false));
+        assert_eq!(state.visible(), 16);
+        // A pinned feature that no longer exists is dropped.
+        state.clamp(Dims {
+            rows: 50,
+            features: 10,
+            visible: 16,
+        });
+        assert_eq!((state.col_offset, state.pinned_col), (0, None));
+
+        // Transposed, row_start counts features.
+        let mut state = SpreadsheetState::new(LanceLayout::DenseRowMajor, DIMS);
+        state.handle_key(key(KeyCode::Char('t')));
+        state.handle_key(key(KeyCode::Char('E')));
+        state.show_feature(15, false);
+        state.clamp(Dims {
+            rows: 30,
+            features: 5,
+            visible: 8,
+        });
+        assert_eq!(position(&state), (4, 0, 22, 0, true));
+        assert!(state.transposed());
+
+        // Jumps move the feature axis vertically and the row axis across.
+        state.clamp(DIMS);
+        state.scroll_to(95, 500);
+        assert_eq!(position(&state), (19, 0, 92, 0, true));
+        state.scroll_to_row(3);
+        state.show_feature(7, false);
+        assert_eq!(position(&state), (7, 0, 3, 0, true));
+
+        // Empty data clamps to zero.
+        state.clamp(Dims {
+            rows: 0,
+            features: 0,
+            visible: 8,
+        });
+        assert_eq!(position(&state), (0, 0, 0, 0, true));
+    }
+}
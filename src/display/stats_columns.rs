@@ -0,0 +1,245 @@
+//! Which features the row aggregates run over (`--stats-cols`). A generic
+//! table without `col_*` features shows every numeric column, and an `id`
+//! in the millions next to a score near 1.0 makes their row mean
+//! meaningless; by default (`auto`) index-like columns — named `id`,
+//! `index`, `row` or `_rowid`, or integers rising strictly from row to row
+//! — stay on screen but are left out of the aggregates. `all` keeps every
+//! feature and a list of names aggregates exactly those.
+
+use anyhow::{Result, bail};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Schema};
+use arrow_array::{Array, Int64Array};
+use log::warn;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use crate::display::ROWID_COLUMN;
+use crate::display::display::display_name;
+use crate::functions::batch_source::BatchSource;
+
+/// Names (compared case-insensitively) the `auto` choice leaves out.
+const INDEX_NAMES: &[&str] = &["id", "index", "row", ROWID_COLUMN];
+
+/// Which features the row aggregates run over.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum StatsColumns {
+    /// All but the index-like columns of a table without `col_*` features
+    #[default]
+    Auto,
+    /// Every feature
+    All,
+    /// Only the features with these names
+    Named(Vec<String>),
+}
+
+impl FromStr for StatsColumns {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "auto" => Ok(StatsColumns::Auto),
+            "all" => Ok(StatsColumns::All),
+            names => {
+                let names: Vec<String> = names
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                if names.is_empty() {
+                    return Err("expected `auto`, `all` or column names".to_string());
+                }
+                Ok(StatsColumns::Named(names))
+            }
+        }
+    }
+}
+
+static CONFIG: Mutex<Option<StatsColumns>> = Mutex::new(None);
+
+/// Set which features the row aggregates of the viewers opened from now on
+/// run over (`--stats-cols`).
+pub fn configure_stats_columns(choice: StatsColumns) {
+    *CONFIG.lock().unwrap() = Some(choice);
+}
+
+/// The schema indices among `features` that the configured choice leaves
+/// out of the row aggregates.
+pub(crate) fn skipped_columns(data: &BatchSource, features: &[usize]) -> Vec<usize> {
+    let choice = CONFIG.lock().unwrap().clone().unwrap_or_default();
+    skipped_by(&choice, data, features)
+}
+
+fn skipped_by(choice: &StatsColumns, data: &BatchSource, features: &[usize]) -> Vec<usize> {
+    let schema = data.schema();
+    match choice {
+        StatsColumns::All => Vec::new(),
+        StatsColumns::Named(names) => {
+            for name in names {
+                if !features.iter().any(|&c| schema.field(c).name() == name) {
+                    warn!("--stats-cols: no feature column named {name:?}");
+                }
+            }
+            features
+                .iter()
+                .copied()
+                .filter(|&c| !names.contains(schema.field(c).name()))
+                .collect()
+        }
+        // `col_*` features and a lone column are the data itself
+        StatsColumns::Auto
+            if features.len() < 2
+                || features
+                    .iter()
+                    .any(|&c| schema.field(c).name().starts_with("col_")) =>
+        {
+            Vec::new()
+        }
+        StatsColumns::Auto => features
+            .iter()
+            .copied()
+            .filter(|&c| is_index_like(data, c).unwrap_or(false))
+            .collect(),
+    }
+}
+
+/// Named like an index, or integers without NULLs rising strictly over at
+/// least two rows.
+fn is_index_like(data: &BatchSource, col: usize) -> Result<bool> {
+    let schema = data.schema();
+    let field = schema.field(col);
+    if INDEX_NAMES
+        .iter()
+        .any(|name| field.name().eq_ignore_ascii_case(name))
+    {
+        return Ok(true);
+    }
+    if !field.data_type().is_integer() || data.num_rows() < 2 || data.null_count(col) > 0 {
+        return Ok(false);
+    }
+    let mut last: Option<i64> = None;
+    for batch in data.batches() {
+        let values = cast(batch.column(col), &DataType::Int64)?;
+        let Some(values) = values.as_any().downcast_ref::<Int64Array>() else {
+            bail!("{} did not cast to Int64", field.name());
+        };
+        // a u64 past i64::MAX casts to NULL
+        if values.null_count() > 0 {
+            return Ok(false);
+        }
+        for v in values.values().iter().copied() {
+            if last.is_some_and(|last| v <= last) {
+                return Ok(false);
+            }
+            last = Some(v);
+        }
+    }
+    Ok(true)
+}
+
+/// Status-bar note naming the `skipped` columns, e.g. `, stats skip id`.
+pub(crate) fn skipped_note(schema: &Schema, skipped: &[usize]) -> String {
+    if skipped.is_empty() {
+        return String::new();
+    }
+    let names: Vec<&str> = skipped
+        .iter()
+        .filter(|&&c| c < schema.fields().len())
+        .map(|&c| display_name(schema.field(c)))
+        .collect();
+    format!(", stats skip {}", names.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::Field;
+    use arrow_array::{ArrayRef, Float64Array, Int32Array, RecordBatch, StringArray};
+    use std::sync::Arc;
+
+    fn table(columns: Vec<(&str, ArrayRef)>) -> BatchSource {
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        BatchSource::from(batch)
+    }
+
+    fn ints(values: &[i32]) -> ArrayRef {
+        Arc::new(Int32Array::from(values.to_vec()))
+    }
+
+    fn floats(values: &[f64]) -> ArrayRef {
+        Arc::new(Float64Array::from(values.to_vec()))
+    }
+
+    #[test]
+    fn auto_skips_index_like_columns() {
+        let data = table(vec![
+            ("ID", ints(&[7, 3, 5])),
+            ("score", floats(&[0.5, 0.9, 0.1])),
+            ("step", ints(&[10, 20, 30])),
+            ("count", ints(&[4, 4, 9])),
+            ("falling", ints(&[3, 2, 1])),
+            ("label", Arc::new(StringArray::from(vec!["a", "b", "c"]))),
+        ]);
+        // by name whatever the values; by values only when rising strictly
+        let features = [0, 1, 2, 3, 4, 5];
+        assert_eq!(skipped_by(&StatsColumns::Auto, &data, &features), [0, 2]);
+        assert!(skipped_by(&StatsColumns::All, &data, &features).is_empty());
+        let named = "score, count".parse().unwrap();
+        assert_eq!(skipped_by(&named, &data, &features), [0, 2, 4, 5]);
+
+        assert_eq!(
+            skipped_note(&data.schema(), &[0, 2]),
+            ", stats skip ID, step"
+        );
+    }
+
+    #[test]
+    fn auto_keeps_matrix_features_and_short_or_null_columns() {
+        // `col_*` features are the matrix, even when they rise
+        let matrix = table(vec![("col_0", ints(&[1, 2])), ("col_1", ints(&[5, 6]))]);
+        assert!(skipped_by(&StatsColumns::Auto, &matrix, &[0, 1]).is_empty());
+
+        let one_row = table(vec![("step", ints(&[1])), ("x", floats(&[2.0]))]);
+        assert!(skipped_by(&StatsColumns::Auto, &one_row, &[0, 1]).is_empty());
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("step", DataType::Int32, true),
+            Field::new("x", DataType::Float64, false),
+        ]));
+        let nulls = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(3)])),
+                floats(&[1.0, 2.0, 3.0]),
+            ],
+        )
+        .unwrap();
+        let nulls = BatchSource::from(nulls);
+        assert!(skipped_by(&StatsColumns::Auto, &nulls, &[0, 1]).is_empty());
+
+        // rising across batches, not just within each
+        let split = BatchSource::new(
+            matrix.schema(),
+            vec![
+                RecordBatch::try_new(matrix.schema(), vec![ints(&[1, 2]), ints(&[0, 0])]).unwrap(),
+                RecordBatch::try_new(matrix.schema(), vec![ints(&[2, 3]), ints(&[0, 0])]).unwrap(),
+            ],
+        )
+        .unwrap();
+        let renamed = table(vec![("a", ints(&[1, 2])), ("b", ints(&[0, 0]))]);
+        let split = split.with_schema(renamed.schema()).unwrap();
+        assert!(skipped_by(&StatsColumns::Auto, &split, &[0, 1]).is_empty());
+    }
+
+    #[test]
+    fn stats_cols_parse() {
+        assert_eq!("auto".parse(), Ok(StatsColumns::Auto));
+        assert_eq!("all".parse(), Ok(StatsColumns::All));
+        assert_eq!(
+            "id,score".parse(),
+            Ok(StatsColumns::Named(vec!["id".into(), "score".into()]))
+        );
+        assert!(" , ".parse::<StatsColumns>().is_err());
+    }
+}
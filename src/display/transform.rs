@@ -0,0 +1,253 @@
+//! Preview transform of the viewers (`z`): floating-point feature columns
+//! shown as z-scores, then min-max scaled to `[0, 1]`, then raw again. The
+//! parameters come from the viewer's [`ColumnStats`] of the loaded rows.
+//! Only what is drawn changes: cells, row aggregates and the overview
+//! heatmap. The data, the `!` outliers and the `Δ` column keep the raw
+//! values, and `W` exports them unless its prompt says otherwise.
+//!
+//! Integer columns are never transformed. A column without spread (zero
+//! std or range) shows 0 throughout; nulls stay null.
+
+use anyhow::Result;
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow_array::{ArrayRef, RecordBatch, cast::AsArray};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::cache::ColumnStats;
+use crate::functions::batch_source::BatchSource;
+
+/// Field metadata key carrying the tag of a transformed column (`z`, `mm`).
+pub const TRANSFORM_KEY: &str = "javelin.transform";
+
+/// How the values are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// `(v − mean) / std`
+    ZScore,
+    /// `(v − min) / (max − min)`
+    MinMax,
+}
+
+impl Transform {
+    /// The next mode of `z`: z-score, min-max, raw.
+    pub(crate) fn cycle(transform: Option<Transform>) -> Option<Transform> {
+        match transform {
+            None => Some(Transform::ZScore),
+            Some(Transform::ZScore) => Some(Transform::MinMax),
+            Some(Transform::MinMax) => None,
+        }
+    }
+
+    /// Header tag, as in `col_3 [z]`.
+    pub(crate) fn tag(self) -> &'static str {
+        match self {
+            Transform::ZScore => "z",
+            Transform::MinMax => "mm",
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Transform::ZScore => "z-score",
+            Transform::MinMax => "min-max",
+        }
+    }
+
+    pub(crate) fn from_tag(tag: &str) -> Option<Self> {
+        [Transform::ZScore, Transform::MinMax]
+            .into_iter()
+            .find(|t| t.tag() == tag)
+    }
+
+    /// `(offset, scale)` with the shown value `(v − offset) · scale`; the
+    /// scale is 0 for a column without spread. `None` without values.
+    fn params(self, stats: &ColumnStats) -> Option<(f64, f64)> {
+        let inverse = |spread: f64| {
+            if spread > 0.0 && spread.is_finite() {
+                1.0 / spread
+            } else {
+                0.0
+            }
+        };
+        match self {
+            Transform::ZScore => Some((stats.mean?, inverse(stats.std?))),
+            Transform::MinMax => Some((stats.min?, inverse(stats.max? - stats.min?))),
+        }
+    }
+}
+
+/// A transform with its parameters for the features of one viewer.
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnTransform {
+    pub(crate) kind: Transform,
+    /// Per feature (index into the viewer's features); `None` where the
+    /// column is left as is
+    params: Vec<Option<(f64, f64)>>,
+    /// Schema column → feature index of the transformed columns
+    columns: HashMap<usize, usize>,
+}
+
+impl ColumnTransform {
+    /// `kind` for the schema columns `features` of `data`, whose `stats`
+    /// are given in the same order.
+    pub(crate) fn new(
+        kind: Transform,
+        data: &BatchSource,
+        features: &[usize],
+        stats: &[ColumnStats],
+    ) -> Self {
+        let schema = data.schema();
+        let params: Vec<Option<(f64, f64)>> = features
+            .iter()
+            .zip(stats)
+            .map(|(&col, stats)| {
+                if schema.field(col).data_type().is_floating() {
+                    kind.params(stats)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let columns = features
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| params[*i].is_some())
+            .map(|(i, &col)| (col, i))
+            .collect();
+        Self {
+            kind,
+            params,
+            columns,
+        }
+    }
+
+    /// Feature `feature`'s value `v` as shown.
+    pub(crate) fn apply(&self, feature: usize, v: f64) -> f64 {
+        match self.params.get(feature).copied().flatten() {
+            Some((offset, scale)) => (v - offset) * scale,
+            None => v,
+        }
+    }
+
+    /// The rows of `data` as shown: transformed columns become `Float64`
+    /// and carry [`TRANSFORM_KEY`].
+    pub(crate) fn source(&self, data: &BatchSource) -> Result<BatchSource> {
+        let schema = data.schema();
+        let fields: Vec<Field> = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(col, field)| match self.columns.get(&col) {
+                Some(_) => {
+                    let mut metadata = field.metadata().clone();
+                    metadata.insert(TRANSFORM_KEY.to_string(), self.kind.tag().to_string());
+                    Field::new(field.name(), DataType::Float64, field.is_nullable())
+                        .with_metadata(metadata)
+                }
+                None => field.as_ref().clone(),
+            })
+            .collect();
+        let schema = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
+        data.try_map(|batch, _| {
+            let columns = batch
+                .columns()
+                .iter()
+                .enumerate()
+                .map(|(col, array)| match self.columns.get(&col) {
+                    Some(&feature) => self.column(feature, array),
+                    None => Ok(array.clone()),
+                })
+                .collect::<Result<Vec<ArrayRef>>>()?;
+            Ok(RecordBatch::try_new(schema.clone(), columns)?)
+        })
+    }
+
+    fn column(&self, feature: usize, array: &ArrayRef) -> Result<ArrayRef> {
+        let values = cast(array, &DataType::Float64)?;
+        let shown = values
+            .as_primitive::<Float64Type>()
+            .unary::<_, Float64Type>(|v| self.apply(feature, v));
+        Ok(Arc::new(shown))
+    }
+}
+
+/// Title note of a shown source, e.g. `, z-score view`; empty when no
+/// column is transformed.
+pub(crate) fn transform_note(schema: &Schema) -> String {
+    schema
+        .fields()
+        .iter()
+        .find_map(|f| f.metadata().get(TRANSFORM_KEY))
+        .and_then(|tag| Transform::from_tag(tag))
+        .map(|t| format!(", {} view", t.label()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functions::stats::source_column_stats;
+    use arrow_array::{Float32Array, Float64Array, Int64Array};
+
+    #[test]
+    fn floats_are_scaled_and_integers_and_constants_are_not() {
+        let schema = Schema::new(vec![
+            Field::new("col_0", DataType::Float32, true),
+            Field::new("col_1", DataType::Int64, false),
+            Field::new("col_2", DataType::Float64, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float32Array::from(vec![
+                    Some(2.0),
+                    None,
+                    Some(4.0),
+                    Some(6.0),
+                ])),
+                Arc::new(Int64Array::from(vec![1, 2, 3, 100])),
+                Arc::new(Float64Array::from(vec![5.0; 4])),
+            ],
+        )
+        .unwrap();
+        let data = BatchSource::from(batch);
+        let features = [0, 1, 2];
+        let stats = source_column_stats(&data, &features);
+        let values = |kind| {
+            let shown = ColumnTransform::new(kind, &data, &features, &stats)
+                .source(&data)
+                .unwrap();
+            let batch = shown.whole().clone();
+            assert_eq!(
+                batch.schema().field(0).metadata()[TRANSFORM_KEY],
+                kind.tag()
+            );
+            assert!(batch.schema().field(1).metadata().is_empty());
+            assert_eq!(batch.column(1).data_type(), &DataType::Int64);
+            let col = |c: usize| -> Vec<Option<f64>> {
+                batch
+                    .column(c)
+                    .as_primitive::<Float64Type>()
+                    .iter()
+                    .collect()
+            };
+            (col(0), col(2), transform_note(&batch.schema()))
+        };
+
+        let (scaled, constant, note) = values(Transform::MinMax);
+        assert_eq!(scaled, [Some(0.0), None, Some(0.5), Some(1.0)]);
+        assert_eq!(constant, [Some(0.0); 4]);
+        assert_eq!(note, ", min-max view");
+
+        // mean 4, population std √(8/3)
+        let (z, _, note) = values(Transform::ZScore);
+        let std = (8.0f64 / 3.0).sqrt();
+        assert_eq!(z[0], Some(-2.0 / std));
+        assert_eq!(z[2], Some(0.0));
+        assert_eq!(note, ", z-score view");
+
+        assert_eq!(Transform::cycle(Some(Transform::MinMax)), None);
+    }
+}
@@ -0,0 +1,318 @@
+//! Per-column display formats for integer columns, picked in the column
+//! picker (`|`, then **Ctrl-f**): raw, thousands-separated, humanized bytes
+//! (KiB/MiB/GiB…) or hex. The formats are kept by column name in the
+//! dataset's session state; the table shows them, and `W` writes the
+//! formatted columns as strings only when its prompt asks for them.
+
+use anyhow::Result;
+use arrow::array::AsArray;
+use arrow::datatypes::{
+    DataType, Field, Int8Type, Int16Type, Int32Type, Int64Type, Schema, UInt8Type, UInt16Type,
+    UInt32Type, UInt64Type,
+};
+use arrow_array::{Array, ArrayRef, RecordBatch, StringArray};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::display::display::format_value;
+
+/// Key (with Ctrl) cycling the highlighted column's format in the picker.
+pub(crate) const FORMAT_KEY: char = 'f';
+
+/// Binary units of [`ValueFormat::Bytes`], each 1024 of the one before.
+const BYTE_UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+/// How an integer column's values are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum ValueFormat {
+    /// `1234567`
+    #[default]
+    Raw,
+    /// `1,234,567`
+    Thousands,
+    /// `1.2 MiB`
+    Bytes,
+    /// `0x12d687`
+    Hex,
+}
+
+impl ValueFormat {
+    /// The format after this one, back to raw after hex.
+    pub(crate) fn next(self) -> Self {
+        match self {
+            ValueFormat::Raw => ValueFormat::Thousands,
+            ValueFormat::Thousands => ValueFormat::Bytes,
+            ValueFormat::Bytes => ValueFormat::Hex,
+            ValueFormat::Hex => ValueFormat::Raw,
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            ValueFormat::Raw => "raw",
+            ValueFormat::Thousands => "thousands",
+            ValueFormat::Bytes => "bytes",
+            ValueFormat::Hex => "hex",
+        }
+    }
+
+    /// `v` in this format.
+    pub(crate) fn render(self, v: i128) -> String {
+        let sign = if v < 0 { "-" } else { "" };
+        let magnitude = v.unsigned_abs();
+        match self {
+            ValueFormat::Raw => v.to_string(),
+            ValueFormat::Thousands => format!("{sign}{}", thousands(magnitude)),
+            ValueFormat::Bytes => format!("{sign}{}", bytes(magnitude)),
+            ValueFormat::Hex => format!("{sign}0x{magnitude:x}"),
+        }
+    }
+}
+
+impl fmt::Display for ValueFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for ValueFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "raw" => Ok(ValueFormat::Raw),
+            "thousands" => Ok(ValueFormat::Thousands),
+            "bytes" => Ok(ValueFormat::Bytes),
+            "hex" => Ok(ValueFormat::Hex),
+            other => Err(format!(
+                "unknown format {other:?}: expected raw, thousands, bytes or hex"
+            )),
+        }
+    }
+}
+
+/// `1234567` as `1,234,567`.
+fn thousands(v: u128) -> String {
+    let digits = v.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// `v` bytes in the largest binary unit that keeps it at least 1, with one
+/// decimal: `1023 B`, `1.0 KiB`, `16.0 EiB`.
+fn bytes(v: u128) -> String {
+    if v < 1024 {
+        return format!("{v} B");
+    }
+    let mut scaled = v as f64;
+    let mut unit = 0;
+    // 1023.96 would round up to `1024.0`; show it in the next unit
+    while scaled >= 1023.95 && unit + 1 < BYTE_UNITS.len() {
+        scaled /= 1024.0;
+        unit += 1;
+    }
+    format!("{scaled:.1} {}", BYTE_UNITS[unit])
+}
+
+/// Integer types a format applies to.
+pub(crate) fn is_formattable(data_type: &DataType) -> bool {
+    data_type.is_integer()
+}
+
+/// Value `row` of the integer column `array`; `None` for NULL or another
+/// type.
+fn integer_value(array: &ArrayRef, row: usize) -> Option<i128> {
+    if array.is_null(row) {
+        return None;
+    }
+    Some(match array.data_type() {
+        DataType::Int8 => array.as_primitive::<Int8Type>().value(row).into(),
+        DataType::Int16 => array.as_primitive::<Int16Type>().value(row).into(),
+        DataType::Int32 => array.as_primitive::<Int32Type>().value(row).into(),
+        DataType::Int64 => array.as_primitive::<Int64Type>().value(row).into(),
+        DataType::UInt8 => array.as_primitive::<UInt8Type>().value(row).into(),
+        DataType::UInt16 => array.as_primitive::<UInt16Type>().value(row).into(),
+        DataType::UInt32 => array.as_primitive::<UInt32Type>().value(row).into(),
+        DataType::UInt64 => array.as_primitive::<UInt64Type>().value(row).into(),
+        _ => return None,
+    })
+}
+
+/// The display format of each column that has one, by column name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ValueFormatter(BTreeMap<String, ValueFormat>);
+
+impl ValueFormatter {
+    /// Formats from `(column name, format label)` pairs, as the session
+    /// keeps them; unknown labels are dropped.
+    pub(crate) fn new(saved: impl IntoIterator<Item = (impl AsRef<str>, impl AsRef<str>)>) -> Self {
+        Self(
+            saved
+                .into_iter()
+                .filter_map(|(name, label)| {
+                    Some((name.as_ref().to_string(), label.as_ref().parse().ok()?))
+                })
+                .filter(|(_, format)| *format != ValueFormat::Raw)
+                .collect(),
+        )
+    }
+
+    /// The formats as the session keeps them.
+    pub(crate) fn labels(&self) -> BTreeMap<String, String> {
+        self.0
+            .iter()
+            .map(|(name, format)| (name.clone(), format.label().to_string()))
+            .collect()
+    }
+
+    /// Format of the column `name`, raw when none was picked.
+    pub(crate) fn get(&self, name: &str) -> ValueFormat {
+        self.0.get(name).copied().unwrap_or_default()
+    }
+
+    /// Move the column `name` on to its next format and return it.
+    pub(crate) fn cycle(&mut self, name: &str) -> ValueFormat {
+        let next = self.get(name).next();
+        if next == ValueFormat::Raw {
+            self.0.remove(name);
+        } else {
+            self.0.insert(name.to_string(), next);
+        }
+        next
+    }
+
+    /// Cell text of value `row` of the column `name`: formatted when it is
+    /// an integer column with a format, as [`format_value`] shows it
+    /// otherwise.
+    pub(crate) fn format(&self, name: &str, array: &ArrayRef, row: usize) -> String {
+        match (self.get(name), integer_value(array, row)) {
+            (ValueFormat::Raw, _) | (_, None) => format_value(array, row),
+            (format, Some(v)) => format.render(v),
+        }
+    }
+
+    /// `batch` with each integer column that has a format replaced by a
+    /// Utf8 column of the formatted values, as `W` writes it when asked to.
+    pub(crate) fn apply(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let schema = batch.schema();
+        let mut fields: Vec<Field> = Vec::with_capacity(schema.fields().len());
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+        for (field, array) in schema.fields().iter().zip(batch.columns()) {
+            let format = self.get(field.name());
+            if format == ValueFormat::Raw || !is_formattable(field.data_type()) {
+                fields.push(field.as_ref().clone());
+                arrays.push(array.clone());
+                continue;
+            }
+            let text: StringArray = (0..array.len())
+                .map(|r| integer_value(array, r).map(|v| format.render(v)))
+                .collect();
+            fields.push(field.as_ref().clone().with_data_type(DataType::Utf8));
+            arrays.push(Arc::new(text));
+        }
+        let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+        Ok(RecordBatch::try_new(Arc::new(schema), arrays)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Float64Array, Int32Array, UInt64Array};
+
+    #[test]
+    fn formats_render_boundary_values() {
+        let cases = [
+            (0, ["0", "0", "0 B", "0x0"]),
+            (1023, ["1023", "1,023", "1023 B", "0x3ff"]),
+            (1024, ["1024", "1,024", "1.0 KiB", "0x400"]),
+            (
+                u64::MAX as i128,
+                [
+                    "18446744073709551615",
+                    "18,446,744,073,709,551,615",
+                    "16.0 EiB",
+                    "0xffffffffffffffff",
+                ],
+            ),
+            (-1536, ["-1536", "-1,536", "-1.5 KiB", "-0x600"]),
+        ];
+        let formats = [
+            ValueFormat::Raw,
+            ValueFormat::Thousands,
+            ValueFormat::Bytes,
+            ValueFormat::Hex,
+        ];
+        for (v, expected) in cases {
+            for (format, text) in formats.iter().zip(expected) {
+                assert_eq!(format.render(v), text, "{v} as {format}");
+            }
+        }
+        // just under a unit boundary moves up rather than showing 1024.0
+        assert_eq!(ValueFormat::Bytes.render(1024 * 1024 - 1), "1.0 MiB");
+        assert_eq!(ValueFormat::Bytes.render(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
+    #[test]
+    fn formats_cycle_parse_and_round_trip_through_the_session() {
+        assert_eq!("hex".parse(), Ok(ValueFormat::Hex));
+        assert!("octal".parse::<ValueFormat>().is_err());
+
+        let mut formatter = ValueFormatter::default();
+        assert_eq!(formatter.cycle("size"), ValueFormat::Thousands);
+        assert_eq!(formatter.cycle("size"), ValueFormat::Bytes);
+        let labels = formatter.labels();
+        assert_eq!(labels["size"], "bytes");
+        assert_eq!(ValueFormatter::new(&labels), formatter);
+        assert_eq!(formatter.cycle("size"), ValueFormat::Hex);
+        // raw again drops the column
+        assert_eq!(formatter.cycle("size"), ValueFormat::Raw);
+        assert_eq!(formatter, ValueFormatter::default());
+    }
+
+    #[test]
+    fn formatted_columns_export_as_strings() {
+        let batch = RecordBatch::try_from_iter([
+            (
+                "size",
+                Arc::new(UInt64Array::from(vec![Some(2048), None])) as ArrayRef,
+            ),
+            ("n", Arc::new(Int32Array::from(vec![1000, -5])) as ArrayRef),
+            (
+                "x",
+                Arc::new(Float64Array::from(vec![1.5, 2.0])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let mut formatter = ValueFormatter::default();
+        formatter.cycle("size");
+        formatter.cycle("size");
+        formatter.cycle("x");
+
+        assert_eq!(formatter.format("size", batch.column(0), 0), "2.0 KiB");
+        assert_eq!(formatter.format("size", batch.column(0), 1), "NULL");
+        assert_eq!(formatter.format("n", batch.column(1), 0), "1000");
+        // a float column keeps its usual text
+        assert_eq!(
+            formatter.format("x", batch.column(2), 0),
+            format_value(batch.column(2), 0)
+        );
+
+        let written = formatter.apply(&batch).unwrap();
+        let schema = written.schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+        assert_eq!(schema.field(1).data_type(), &DataType::Int32);
+        assert_eq!(schema.field(2).data_type(), &DataType::Float64);
+        let sizes = written.column(0).as_string::<i32>();
+        assert_eq!(sizes.value(0), "2.0 KiB");
+        assert!(sizes.is_null(1));
+    }
+}
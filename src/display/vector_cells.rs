@@ -0,0 +1,253 @@
+//! Cells holding a whole numeric vector (a `FixedSizeList`), as `display
+//! --no-expand` keeps the dense column of an embedding dataset: a short
+//! preview in the table (`[0.12, -0.43, …] (768)`), `Enter` for every value
+//! with a sparkline, and row aggregates over the elements.
+
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::datatypes::DataType;
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Sparkline},
+};
+
+use crate::display::display::{display_name, extract_numeric_value, row_label};
+use crate::display::display_metadata::centered;
+use crate::display::index_base::index_base;
+use crate::display::*;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::format::format_float;
+use crate::functions::functions::is_vector_type;
+
+/// Elements a table cell previews before the ellipsis.
+const PREVIEW_VALUES: usize = 2;
+const PREVIEW_DECIMALS: usize = 2;
+/// Table width of a vector column, room for a preview of 768 values.
+pub(crate) const VECTOR_COL_WIDTH: u16 = 24;
+/// Values per line of the detail popup.
+const DETAIL_VALUES_PER_LINE: usize = 8;
+const DETAIL_DECIMALS: usize = 4;
+
+/// Width of the vectors of `data_type`, when it holds them.
+pub(crate) fn vector_width(data_type: &DataType) -> Option<usize> {
+    match data_type {
+        DataType::FixedSizeList(_, size) if is_vector_type(data_type) => {
+            Some((*size).max(0) as usize)
+        }
+        _ => None,
+    }
+}
+
+/// `FixedSizeList<Float64>[768]` for the column strip, as `info` names
+/// the dense layout.
+pub(crate) fn vector_type_label(data_type: &DataType) -> Option<String> {
+    match data_type {
+        DataType::FixedSizeList(inner, size) if is_vector_type(data_type) => {
+            Some(format!("FixedSizeList<{}>[{size}]", inner.data_type()))
+        }
+        _ => None,
+    }
+}
+
+/// The elements of the vector in `row` of `array`, `None` for each NULL; a
+/// NULL vector has none.
+pub(crate) fn vector_elements(array: &ArrayRef, row: usize) -> Vec<Option<f64>> {
+    if array.is_null(row) {
+        return Vec::new();
+    }
+    let values = array.as_fixed_size_list().value(row);
+    (0..values.len())
+        .map(|i| {
+            (!values.is_null(i))
+                .then(|| extract_numeric_value(&values, i))
+                .flatten()
+        })
+        .collect()
+}
+
+/// `[0.12, -0.43, …] (768)`: the first elements and the length.
+pub(crate) fn vector_preview(array: &ArrayRef, row: usize) -> String {
+    let vals = vector_elements(array, row);
+    let shown: Vec<String> = vals
+        .iter()
+        .take(PREVIEW_VALUES)
+        .map(|v| v.map_or("NULL".to_string(), |v| format_float(v, PREVIEW_DECIMALS)))
+        .collect();
+    let more = if vals.len() > PREVIEW_VALUES {
+        ", …"
+    } else {
+        ""
+    };
+    format!("[{}{more}] ({})", shown.join(", "), vals.len())
+}
+
+/// `values` averaged into `bins` equal runs and scaled to 0..=100 for a
+/// sparkline; NULL and non-finite values count as the minimum.
+fn sparkline_bars(values: &[Option<f64>], bins: usize) -> Vec<u64> {
+    let finite: Vec<f64> = values
+        .iter()
+        .map(|v| v.filter(|v| v.is_finite()).unwrap_or(f64::NAN))
+        .collect();
+    let lo = finite
+        .iter()
+        .copied()
+        .filter(|v| !v.is_nan())
+        .fold(f64::INFINITY, f64::min);
+    let hi = finite
+        .iter()
+        .copied()
+        .filter(|v| !v.is_nan())
+        .fold(f64::NEG_INFINITY, f64::max);
+    let bins = bins.min(values.len()).max(1);
+    (0..bins)
+        .map(|b| {
+            let run = &finite[b * finite.len() / bins..(b + 1) * finite.len() / bins];
+            let kept: Vec<f64> = run.iter().copied().filter(|v| !v.is_nan()).collect();
+            if kept.is_empty() || hi <= lo {
+                return 0;
+            }
+            let mean = kept.iter().sum::<f64>() / kept.len() as f64;
+            ((mean - lo) / (hi - lo) * 100.0).round() as u64
+        })
+        .collect()
+}
+
+/// State of the `Enter` popup on a vector cell: its values and a scroll
+/// position in lines.
+pub(crate) struct VectorDetail {
+    title: String,
+    values: Vec<Option<f64>>,
+    scroll: usize,
+}
+
+impl VectorDetail {
+    /// Popup for the vector in global row `row`, column `col` of `data`;
+    /// `None` when that cell holds no vector.
+    pub(crate) fn new(data: &BatchSource, row: usize, col: usize) -> Option<Self> {
+        let (batch, r) = data.locate(row)?;
+        let array = batch.column(col);
+        if !is_vector_type(array.data_type()) {
+            return None;
+        }
+        let values = vector_elements(array, r);
+        let schema = data.schema();
+        Some(Self {
+            title: format!(
+                " Row {}, {}: {} values ",
+                row_label(data, row),
+                display_name(schema.field(col)),
+                values.len()
+            ),
+            values,
+            scroll: 0,
+        })
+    }
+
+    fn lines(&self) -> usize {
+        self.values.len().div_ceil(DETAIL_VALUES_PER_LINE)
+    }
+
+    /// Apply a key press. Returns `false` when the popup should close.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll += 1,
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll += 10,
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll = usize::MAX,
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Draw the vector popup over the current frame: a sparkline of the values
+/// over 8 values per line, starting with the index of the first. The
+/// scroll offset is clamped once the popup height is known.
+pub(crate) fn render_vector_detail(f: &mut Frame, detail: &mut VectorDetail) {
+    let area = centered(f.area(), 80);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(detail.title.clone())
+        .title_bottom(" ↑↓ scroll | Enter/Esc close ");
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(inner);
+    let bars = sparkline_bars(&detail.values, chunks[0].width as usize);
+    f.render_widget(
+        Sparkline::default()
+            .data(&bars)
+            .max(100)
+            .style(Style::default().fg(TEXT_ACCENT)),
+        chunks[0],
+    );
+
+    let visible = chunks[1].height as usize;
+    detail.scroll = detail.scroll.min(detail.lines().saturating_sub(visible));
+    let lines: Vec<Line> = detail
+        .values
+        .chunks(DETAIL_VALUES_PER_LINE)
+        .enumerate()
+        .skip(detail.scroll)
+        .take(visible)
+        .map(|(k, chunk)| {
+            let mut spans = vec![Span::styled(
+                format!("{:>6} ", index_base().show(k * DETAIL_VALUES_PER_LINE)),
+                Style::default().fg(TEXT_SECONDARY),
+            )];
+            spans.extend(chunk.iter().map(|v| {
+                let text = v.map_or("NULL".to_string(), |v| format_float(v, DETAIL_DECIMALS));
+                Span::styled(format!("{text:>10}"), Style::default().fg(TEXT_PRIMARY))
+            }));
+            Line::from(spans)
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), chunks[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{FixedSizeListArray, Float64Array};
+    use arrow::datatypes::Field;
+    use std::sync::Arc;
+
+    #[test]
+    fn vectors_preview_their_head_and_length() {
+        let item = Arc::new(Field::new("item", DataType::Float64, true));
+        let values = Float64Array::from(vec![
+            Some(0.123),
+            Some(-0.43),
+            None,
+            Some(1.0),
+            Some(2.0),
+            Some(3.0),
+        ]);
+        let list: ArrayRef =
+            Arc::new(FixedSizeListArray::try_new(item, 3, Arc::new(values), None).unwrap());
+        assert!(is_vector_type(list.data_type()));
+        assert_eq!(vector_width(list.data_type()), Some(3));
+        assert!(!is_vector_type(&DataType::Float64));
+        assert_eq!(vector_preview(&list, 0), "[0.12, -0.43, …] (3)");
+        assert_eq!(vector_elements(&list, 0), [Some(0.123), Some(-0.43), None]);
+
+        // Runs are averaged into bins and scaled to the range.
+        let bars = sparkline_bars(&vector_elements(&list, 1), 3);
+        assert_eq!(bars, [0, 50, 100]);
+        assert_eq!(
+            sparkline_bars(&[Some(1.0), Some(2.0), Some(5.0), Some(6.0)], 2),
+            [10, 90]
+        );
+    }
+}
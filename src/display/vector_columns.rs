@@ -0,0 +1,236 @@
+//! Computed vector columns of the N×F table: `n` adds each row's L2 norm
+//! and `.` its dot product with the top row, after the visible features
+//! (and the `Δ` column). Both read the packed matrix of a dense dataset, or
+//! the `col_*` columns otherwise, over the features the viewer loaded, and
+//! use the raw values whatever `z` shows. A null dimension counts as 0 and
+//! flags its row with `*`; the footer counts the flagged rows.
+//!
+//! The norm is computed the first time it is shown and kept; the dot
+//! product is recomputed when `.` picks another reference row. Neither is
+//! part of the data: `W` writes them only when its prompt asks for them.
+
+use anyhow::Result;
+use arrow::array::AsArray;
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow_array::{Array, ArrayRef, Float64Array, RecordBatch};
+use std::sync::Arc;
+
+use crate::display::display::{row_id, row_label};
+use crate::functions::batch_source::BatchSource;
+use crate::functions::functions::DenseMatrix;
+use crate::functions::stats::numeric_values;
+
+/// Suffix of a value whose row has a null dimension.
+pub(crate) const PARTIAL_MARK: char = '*';
+
+/// What a computed column holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VectorKind {
+    /// `‖x‖₂`
+    Norm,
+    /// `x · r` with `r` the row `reference` of the viewer
+    Dot { reference: usize },
+}
+
+/// A computed column's value for every loaded row.
+#[derive(Debug, Clone)]
+pub(crate) struct VectorColumn {
+    pub(crate) kind: VectorKind,
+    values: Vec<f64>,
+    /// Per row: a dimension (of the row or the reference) was null
+    partial: Vec<bool>,
+}
+
+impl VectorColumn {
+    /// L2 norm of every row over the schema columns `features`.
+    pub(crate) fn norm(data: &BatchSource, dense: &[DenseMatrix], features: &[usize]) -> Self {
+        let mut column = Self::zeros(VectorKind::Norm, data.num_rows());
+        visit_values(data, dense, features, |row, _, v| {
+            column.add(row, v.map(|v| v * v));
+        });
+        for v in &mut column.values {
+            *v = v.sqrt();
+        }
+        column
+    }
+
+    /// Dot product of every row with row `reference`.
+    pub(crate) fn dot(
+        data: &BatchSource,
+        dense: &[DenseMatrix],
+        features: &[usize],
+        reference: usize,
+    ) -> Self {
+        let mut r = vec![None; features.len()];
+        visit_values(data, dense, features, |row, k, v| {
+            if row == reference {
+                r[k] = v;
+            }
+        });
+        let mut column = Self::zeros(VectorKind::Dot { reference }, data.num_rows());
+        visit_values(data, dense, features, |row, k, v| {
+            column.add(row, v.zip(r[k]).map(|(x, y)| x * y));
+        });
+        column
+    }
+
+    fn zeros(kind: VectorKind, rows: usize) -> Self {
+        Self {
+            kind,
+            values: vec![0.0; rows],
+            partial: vec![false; rows],
+        }
+    }
+
+    fn add(&mut self, row: usize, term: Option<f64>) {
+        match term {
+            Some(t) => self.values[row] += t,
+            None => self.partial[row] = true,
+        }
+    }
+
+    /// The value of `row`, and whether a null dimension was counted as 0.
+    pub(crate) fn value(&self, row: usize) -> Option<(f64, bool)> {
+        Some((*self.values.get(row)?, self.partial[row]))
+    }
+
+    /// Rows with a null dimension.
+    pub(crate) fn partial_rows(&self) -> usize {
+        self.partial.iter().filter(|&&p| p).count()
+    }
+
+    /// Header, e.g. `‖x‖` or `x·#12`.
+    pub(crate) fn header(&self, data: &BatchSource) -> String {
+        match self.kind {
+            VectorKind::Norm => "‖x‖".to_string(),
+            VectorKind::Dot { reference } => format!("x·{}", row_label(data, reference)),
+        }
+    }
+
+    /// Column name when written out, e.g. `l2_norm` or `dot_row_12` (by
+    /// source row id).
+    pub(crate) fn field_name(&self, data: &BatchSource) -> String {
+        match self.kind {
+            VectorKind::Norm => "l2_norm".to_string(),
+            VectorKind::Dot { reference } => format!("dot_row_{}", row_id(data, reference)),
+        }
+    }
+}
+
+/// Title note of the shown computed columns, e.g. `‖x‖ & x·#12
+/// (computed)`.
+pub(crate) fn vector_note(columns: &[&VectorColumn], data: &BatchSource) -> String {
+    let headers: Vec<String> = columns.iter().map(|c| c.header(data)).collect();
+    format!("{} (computed)", headers.join(" & "))
+}
+
+/// `batch` (all the rows of `data`) with `columns` appended as `Float64`.
+pub(crate) fn with_vector_columns(
+    batch: &RecordBatch,
+    data: &BatchSource,
+    columns: &[&VectorColumn],
+) -> Result<RecordBatch> {
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    let mut arrays = batch.columns().to_vec();
+    for column in columns {
+        fields.push(Field::new(
+            column.field_name(data),
+            DataType::Float64,
+            false,
+        ));
+        arrays.push(Arc::new(Float64Array::from(column.values.clone())) as ArrayRef);
+    }
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    Ok(RecordBatch::try_new(Arc::new(schema), arrays)?)
+}
+
+/// Call `visit(row, k, value)` for feature `k` of every loaded row: from the
+/// packed matrices in `dense` when each batch has one, else from the schema
+/// columns `features`. Nulls and non-numeric values are `None`.
+fn visit_values(
+    data: &BatchSource,
+    dense: &[DenseMatrix],
+    features: &[usize],
+    mut visit: impl FnMut(usize, usize, Option<f64>),
+) {
+    if dense.len() == data.batches().len() {
+        for ((offset, _), matrix) in data.iter().zip(dense) {
+            let list = &matrix.list;
+            let values = list.values().as_primitive::<Float64Type>();
+            for r in 0..list.len() {
+                let start = list.value_offset(r) as usize;
+                for (k, d) in matrix.features.clone().enumerate() {
+                    let i = start + d;
+                    let v = (!list.is_null(r) && !values.is_null(i)).then(|| values.value(i));
+                    visit(offset + r, k, v);
+                }
+            }
+        }
+        return;
+    }
+    for (offset, batch) in data.iter() {
+        for (k, &col) in features.iter().enumerate() {
+            let values = numeric_values(batch.column(col));
+            for r in 0..batch.num_rows() {
+                let v = values
+                    .as_ref()
+                    .and_then(|a| (!a.is_null(r)).then(|| a.value(r)));
+                visit(offset + r, k, v);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::dense_rows_to_batch;
+    use crate::functions::functions::expand_dense_row_major;
+    use arrow_array::Float32Array;
+
+    #[test]
+    fn packed_and_expanded_rows_agree_and_null_dims_are_flagged() {
+        let raw = dense_rows_to_batch(&[vec![3.0, 4.0], vec![1.0, 2.0], vec![0.0, -1.0]]).unwrap();
+        let data = BatchSource::from(expand_dense_row_major(&raw).unwrap());
+        let dense = DenseMatrix::from_source(&BatchSource::from(raw), 0..usize::MAX);
+        let features = [0, 1];
+
+        for dense in [&dense[..], &[]] {
+            let norm = VectorColumn::norm(&data, dense, &features);
+            assert_eq!(norm.value(0), Some((5.0, false)));
+            assert_eq!(norm.value(2), Some((1.0, false)));
+            let dot = VectorColumn::dot(&data, dense, &features, 1);
+            assert_eq!(dot.value(0), Some((11.0, false)));
+            assert_eq!(dot.value(2), Some((-2.0, false)));
+            assert_eq!(dot.header(&data), "x·1");
+            assert_eq!(dot.field_name(&data), "dot_row_1");
+        }
+
+        let schema = Schema::new(vec![
+            Field::new("col_0", DataType::Float32, true),
+            Field::new("col_1", DataType::Float32, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float32Array::from(vec![Some(3.0), None])),
+                Arc::new(Float32Array::from(vec![Some(4.0), Some(2.0)])),
+            ],
+        )
+        .unwrap();
+        let data = BatchSource::from(batch);
+        let norm = VectorColumn::norm(&data, &[], &features);
+        assert_eq!(norm.value(1), Some((2.0, true)));
+        assert_eq!(norm.partial_rows(), 1);
+        // The reference's null dimension flags every row.
+        let dot = VectorColumn::dot(&data, &[], &features, 1);
+        assert_eq!(dot.value(0), Some((8.0, true)));
+        assert_eq!(dot.partial_rows(), 2);
+
+        let out = with_vector_columns(data.whole(), &data, &[&norm, &dot]).unwrap();
+        assert_eq!(out.schema().field(2).name(), "l2_norm");
+        assert_eq!(out.schema().field(3).name(), "dot_row_1");
+        assert_eq!(out.column(2).as_primitive::<Float64Type>().value(0), 5.0);
+    }
+}
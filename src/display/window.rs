@@ -0,0 +1,232 @@
+//! Which slice of rows and feature columns a table shows, and how titles
+//! name it. Horizontal windows (feature columns, F×N sample columns) stay
+//! full where the data allows; vertical ones (rows, F×N features) keep
+//! their top item in range. An empty window is titled as such rather than
+//! as a backwards range like "1–0".
+
+use std::ops::Range;
+
+use crate::display::index_base::index_base;
+
+/// Where the viewed batch sits in its source dataset, so a window read at
+/// an offset is titled "rows K–K+n-1 of N" rather than "rows 0–n-1 of n", and
+/// a column-sliced batch counts feature columns in source terms too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceWindow {
+    /// Source index of the batch's first row
+    pub row_offset: usize,
+    /// Rows in the source dataset
+    pub total_rows: usize,
+    /// Source index of the batch's first feature column
+    pub col_offset: usize,
+    /// Feature columns in the source; `None` when the batch has them all
+    pub total_cols: Option<usize>,
+    /// Rows read and rows requested when `display --limit` cut the read
+    /// short; `None` for a full read
+    pub limited: Option<(usize, usize)>,
+    /// Rows deleted in the source's version, not counted in `total_rows`
+    pub deleted_rows: usize,
+    /// Which rows a viewer filter keeps, after the count (e.g. `"with
+    /// NULLs"`); `total_rows` then counts the kept rows
+    pub row_filter: Option<&'static str>,
+}
+
+impl SourceWindow {
+    /// The batch is the whole dataset.
+    pub fn whole(num_rows: usize) -> Self {
+        Self {
+            row_offset: 0,
+            total_rows: num_rows,
+            col_offset: 0,
+            total_cols: None,
+            limited: None,
+            deleted_rows: 0,
+            row_filter: None,
+        }
+    }
+
+    /// `"a–b of N"` for batch rows `rows`, in source numbering from the
+    /// index base; `"0 of N"` when there are none. A row filter follows the count and a limited
+    /// read adds `", limit L"`.
+    pub(crate) fn span(&self, rows: Range<usize>) -> String {
+        let mut suffix = match self.row_filter {
+            Some(filter) => format!(" {filter}"),
+            None => String::new(),
+        };
+        if let Some((read, _)) = self.limited {
+            suffix.push_str(&format!(", limit {read}"));
+        }
+        if rows.is_empty() {
+            return format!("0 of {}{suffix}", self.total_rows);
+        }
+        format!(
+            "{} of {}{suffix}",
+            index_base().span(self.row_offset + rows.start..self.row_offset + rows.end),
+            self.total_rows
+        )
+    }
+
+    /// The banner of a suffixed read, e.g. `"showing first 50000 of 120000
+    /// rows"`.
+    pub(crate) fn limit_banner(&self) -> Option<String> {
+        self.limited
+            .map(|(read, requested)| format!("showing first {read} of {requested} rows"))
+    }
+
+    /// The banner over the view: the limit of a limited read and the rows
+    /// deleted in this version, e.g. `"3 rows deleted in this version"`.
+    pub(crate) fn banner(&self) -> Option<String> {
+        let mut parts: Vec<String> = self
+            .limit_banner()
+            .map(|limit| format!("{limit} (--limit)"))
+            .into_iter()
+            .collect();
+        if self.deleted_rows > 0 {
+            parts.push(format!(
+                "{} rows deleted in this version",
+                self.deleted_rows
+            ));
+        }
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+
+    /// `"<noun> a–b of F"` for batch feature columns `cols` out of
+    /// `batch_cols`, in source numbering from the index base; `"no feature
+    /// columns"` when the window is empty.
+    pub(crate) fn col_span(&self, noun: &str, cols: Range<usize>, batch_cols: usize) -> String {
+        if cols.is_empty() {
+            return "no feature columns".to_string();
+        }
+        format!(
+            "{noun} {} of {}",
+            index_base().span(self.col_offset + cols.start..self.col_offset + cols.end),
+            self.total_cols.unwrap_or(batch_cols)
+        )
+    }
+}
+
+/// Up to `visible` items from `offset`, moved back so the window stays full
+/// when `offset` is past the last full window.
+fn full_window(offset: usize, visible: usize, total: usize) -> Range<usize> {
+    let start = offset.min(total.saturating_sub(visible));
+    start..(start + visible).min(total)
+}
+
+/// The feature columns shown from `col_offset`: their positions in
+/// `all_cols` and the batch columns themselves.
+pub(crate) fn feature_window(
+    all_cols: &[usize],
+    col_offset: usize,
+    visible: usize,
+) -> (Range<usize>, &[usize]) {
+    let cols = full_window(col_offset, visible, all_cols.len());
+    (cols.clone(), &all_cols[cols])
+}
+
+/// The sample rows shown as F×N columns from `row_offset`.
+pub(crate) fn row_window(row_offset: usize, visible: usize, num_rows: usize) -> Range<usize> {
+    full_window(row_offset, visible, num_rows)
+}
+
+/// Up to `visible` items from `start` down, `start` clamped to the last
+/// item: the N×F rows, or the F×N features.
+pub(crate) fn vertical_window(start: usize, visible: usize, total: usize) -> Range<usize> {
+    let start = start.min(total.saturating_sub(1));
+    start..(start + visible).min(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::index_base::{IndexBase, set_index_base};
+
+    #[test]
+    fn windows_at_and_past_the_ends() {
+        let cols: Vec<usize> = (10..30).collect();
+        let features = |offset| feature_window(&cols, offset, 8);
+        // (offset, window range)
+        for (offset, want) in [
+            (0, 0..8),
+            (5, 5..13),
+            (19, 12..20),
+            (20, 12..20),
+            (99, 12..20),
+        ] {
+            let (range, window) = features(offset);
+            assert_eq!(range, want, "offset {offset}");
+            assert_eq!(window, &cols[want], "offset {offset}");
+        }
+        // Fewer features than fit, and none at all.
+        assert_eq!(feature_window(&cols[..3], 2, 8).0, 0..3);
+        assert_eq!(feature_window(&[], 0, 8), (0..0, &[][..]));
+        assert_eq!(feature_window(&[], 5, 8).0, 0..0);
+
+        assert_eq!(row_window(0, 4, 10), 0..4);
+        assert_eq!(row_window(9, 4, 10), 6..10);
+        assert_eq!(row_window(10, 4, 10), 6..10);
+        assert_eq!(row_window(3, 4, 0), 0..0);
+
+        assert_eq!(vertical_window(0, 5, 20), 0..5);
+        assert_eq!(vertical_window(19, 5, 20), 19..20);
+        assert_eq!(vertical_window(20, 5, 20), 19..20);
+        assert_eq!(vertical_window(500, 5, 20), 19..20);
+        assert_eq!(vertical_window(3, 5, 0), 0..0);
+    }
+
+    #[test]
+    fn titles_name_the_window_in_source_terms() {
+        set_index_base(IndexBase::One);
+        let whole = SourceWindow::whole(100);
+        assert_eq!(whole.span(0..10), "1–10 of 100");
+        assert_eq!(whole.span(99..100), "100–100 of 100");
+        assert_eq!(SourceWindow::whole(0).span(0..0), "0 of 0");
+        assert_eq!(whole.col_span("features", 0..8, 20), "features 1–8 of 20");
+        assert_eq!(whole.col_span("features", 0..0, 0), "no feature columns");
+
+        let sliced = SourceWindow {
+            row_offset: 500,
+            total_rows: 1000,
+            col_offset: 30,
+            total_cols: Some(64),
+            limited: None,
+            deleted_rows: 0,
+            row_filter: None,
+        };
+        assert_eq!(sliced.span(0..20), "501–520 of 1000");
+        assert_eq!(sliced.col_span("cols", 2..4, 8), "cols 33–34 of 64");
+        assert_eq!(sliced.limit_banner(), None);
+
+        let limited = SourceWindow {
+            limited: Some((50, 1000)),
+            ..whole
+        };
+        assert_eq!(limited.span(0..10), "1–10 of 100, limit 50");
+        let filtered = SourceWindow {
+            row_filter: Some("with NULLs"),
+            ..limited
+        };
+        assert_eq!(filtered.span(0..10), "1–10 of 100 with NULLs, limit 50");
+        assert_eq!(
+            limited.limit_banner().as_deref(),
+            Some("showing first 50 of 1000 rows")
+        );
+        let deleted = SourceWindow {
+            deleted_rows: 3,
+            ..limited
+        };
+        assert_eq!(
+            deleted.banner().as_deref(),
+            Some("showing first 50 of 1000 rows (--limit) · 3 rows deleted in this version")
+        );
+        assert_eq!(sliced.banner(), None);
+
+        // From 0, with or without the Row column.
+        for base in [IndexBase::Zero, IndexBase::NoRowColumn] {
+            set_index_base(base);
+            assert_eq!(whole.span(0..10), "0–9 of 100");
+            assert_eq!(sliced.span(0..20), "500–519 of 1000");
+            assert_eq!(sliced.col_span("cols", 2..4, 8), "cols 32–33 of 64");
+            assert_eq!(SourceWindow::whole(0).span(0..0), "0 of 0");
+        }
+    }
+}
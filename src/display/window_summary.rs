@@ -0,0 +1,296 @@
+//! Side panel of the N×F table (`S`) comparing each drawn feature over the
+//! rows on screen with the same feature over all loaded rows: the window's
+//! mean ± std next to the column's (the viewer's column stats, shared with
+//! `!` and `z`) and how far the window's mean is off, as an arrow and a
+//! percentage. Windows more than two column standard deviations off are
+//! drawn in the warning colour. The panel drops the ± std parts when the
+//! terminal is narrow, and is left out when even that leaves too little of
+//! the table.
+
+use arrow::datatypes::Schema;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use std::ops::Range;
+
+use crate::cache::ColumnStats;
+use crate::display::display::display_name;
+use crate::display::index_base::index_base;
+use crate::display::*;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::format::format_float;
+use crate::functions::stats::{Welford, numeric_values};
+
+/// Key showing or hiding the panel.
+pub(crate) const SUMMARY_KEY: char = 'S';
+
+/// Panel width with the ± std parts, borders included.
+const FULL_WIDTH: u16 = 54;
+/// Panel width with the means only.
+const COMPACT_WIDTH: u16 = 34;
+/// Table width the panel always leaves.
+const MIN_TABLE_WIDTH: u16 = 40;
+
+/// Window means further off than this many column standard deviations are
+/// highlighted.
+const HIGHLIGHT_SIGMAS: f64 = 2.0;
+
+/// What the panel compares: the rows behind the table (before any `z`
+/// transform), its features and their stats over all of those rows.
+#[derive(Clone, Copy)]
+pub(crate) struct SummaryPanel<'a> {
+    pub rows: &'a BatchSource,
+    pub features: &'a [usize],
+    pub stats: &'a [ColumnStats],
+}
+
+/// One feature over the window and over all rows.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WindowSummary {
+    pub col: usize,
+    /// `None` when the window holds no finite value
+    pub window_mean: Option<f64>,
+    pub window_std: Option<f64>,
+    pub mean: Option<f64>,
+    pub std: Option<f64>,
+}
+
+impl WindowSummary {
+    /// The window mean's offset from the column mean in column standard
+    /// deviations; `None` without both means or a spread.
+    pub(crate) fn sigmas(&self) -> Option<f64> {
+        let (window, mean, std) = (self.window_mean?, self.mean?, self.std?);
+        (std > 0.0).then(|| (window - mean) / std)
+    }
+
+    /// More than [`HIGHLIGHT_SIGMAS`] off.
+    pub(crate) fn deviates(&self) -> bool {
+        self.sigmas().is_some_and(|s| s.abs() > HIGHLIGHT_SIGMAS)
+    }
+
+    /// Arrow and relative change of the window mean, e.g. `↑12%`; `=` when
+    /// they agree, `—` without both means.
+    fn change_label(&self) -> String {
+        let (Some(window), Some(mean)) = (self.window_mean, self.mean) else {
+            return "—".to_string();
+        };
+        let arrow = if window > mean {
+            "↑"
+        } else if window < mean {
+            "↓"
+        } else {
+            return "=".to_string();
+        };
+        if mean == 0.0 {
+            return arrow.to_string();
+        }
+        let pct = ((window - mean) / mean.abs() * 100.0).abs();
+        if pct >= 1000.0 {
+            format!("{arrow}>999%")
+        } else {
+            format!("{arrow}{pct:.0}%")
+        }
+    }
+}
+
+/// The summaries of `cols` (batch columns among `panel.features`) over the
+/// rows `window` of `panel.rows`.
+pub(crate) fn window_summaries(
+    panel: SummaryPanel,
+    cols: &[usize],
+    window: Range<usize>,
+) -> Vec<WindowSummary> {
+    cols.iter()
+        .map(|&col| {
+            let mut spread = Welford::default();
+            for (start, batch) in panel.rows.iter() {
+                let end = start + batch.num_rows();
+                let (from, to) = (window.start.max(start), window.end.min(end));
+                if from >= to {
+                    continue;
+                }
+                let values = batch.column(col).slice(from - start, to - from);
+                if let Some(values) = numeric_values(&values) {
+                    values
+                        .iter()
+                        .flatten()
+                        .filter(|v| v.is_finite())
+                        .for_each(|v| spread.push(v));
+                }
+            }
+            let stats = panel
+                .features
+                .iter()
+                .position(|&c| c == col)
+                .and_then(|i| panel.stats.get(i));
+            let has_values = spread.count() > 0;
+            WindowSummary {
+                col,
+                window_mean: has_values.then(|| spread.mean()),
+                window_std: has_values.then(|| spread.std()),
+                mean: stats.and_then(|s| s.mean),
+                std: stats.and_then(|s| s.std),
+            }
+        })
+        .collect()
+}
+
+/// `area` split into the table and the panel, as wide as the terminal
+/// allows; no panel when too narrow for even the means.
+pub(crate) fn summary_split(area: Rect) -> (Rect, Option<Rect>) {
+    let width = if area.width >= FULL_WIDTH + MIN_TABLE_WIDTH {
+        FULL_WIDTH
+    } else if area.width >= COMPACT_WIDTH + MIN_TABLE_WIDTH {
+        COMPACT_WIDTH
+    } else {
+        return (area, None);
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(width)])
+        .split(area);
+    (chunks[0], Some(chunks[1]))
+}
+
+/// `mean±std` (just the mean when `spread` is off) in `width` columns.
+fn moments(mean: Option<f64>, std: Option<f64>, spread: bool, width: usize) -> String {
+    let text = match (mean, std) {
+        (Some(m), Some(s)) if spread => format!("{}±{}", format_float(m, 2), format_float(s, 2)),
+        (Some(m), _) => format_float(m, 3),
+        (None, _) => "—".to_string(),
+    };
+    format!("{text:>width$}")
+}
+
+/// Draw the `summaries` of the window `rows` in `area`, one feature per
+/// line under a header; the ± std parts only when `area` has room.
+pub(crate) fn render_window_summary(
+    f: &mut Frame,
+    area: Rect,
+    rows: Range<usize>,
+    summaries: &[WindowSummary],
+    schema: &Schema,
+) {
+    let base = index_base();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(BORDER_ACCENT))
+        .title(format!(" Rows {} vs all ({SUMMARY_KEY}) ", base.span(rows)));
+    let width = area.width.saturating_sub(2) as usize;
+    let spread = area.width >= FULL_WIDTH;
+    let value_width = if spread { 15 } else { 8 };
+    let change_width = 6;
+    let name_width = width.saturating_sub(2 * (value_width + 1) + change_width + 1);
+
+    let header = format!(
+        "{:<name_width$} {:>value_width$} {:>value_width$} {:>change_width$}",
+        "column", "window", "all", "Δ"
+    );
+    let mut lines = vec![Line::from(Span::styled(
+        header,
+        Style::default()
+            .fg(TEXT_SECONDARY)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    for summary in summaries {
+        let name: String = display_name(schema.field(summary.col))
+            .chars()
+            .take(name_width)
+            .collect();
+        let style = if summary.deviates() {
+            Style::default().fg(TEXT_WARNING)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{name:<name_width$} "),
+                Style::default().fg(TEXT_ACCENT),
+            ),
+            Span::styled(
+                moments(summary.window_mean, summary.window_std, spread, value_width),
+                style,
+            ),
+            Span::raw(" "),
+            Span::raw(moments(summary.mean, summary.std, spread, value_width)),
+            Span::styled(format!(" {:>change_width$}", summary.change_label()), style),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{ArrayRef, Float64Array, Int32Array, RecordBatch};
+    use std::sync::Arc;
+
+    fn column(values: &[Option<f64>]) -> ArrayRef {
+        Arc::new(Float64Array::from(values.to_vec()))
+    }
+
+    #[test]
+    fn window_moments_span_batches_and_flag_two_sigma() {
+        let batch = |x: &[Option<f64>], n: Vec<i32>| {
+            RecordBatch::try_from_iter_with_nullable([
+                ("x", column(x), true),
+                ("n", Arc::new(Int32Array::from(n)) as ArrayRef, false),
+            ])
+            .unwrap()
+        };
+        let first = batch(&[Some(0.0), Some(0.0), Some(0.0)], vec![1, 2, 3]);
+        let second = batch(&[Some(10.0), None, Some(10.0)], vec![4, 5, 6]);
+        let rows = BatchSource::new(first.schema(), vec![first, second]).unwrap();
+        let features = [0, 1];
+        let stats = crate::functions::stats::source_column_stats(&rows, &features);
+        let panel = SummaryPanel {
+            rows: &rows,
+            features: &features,
+            stats: &stats,
+        };
+
+        // rows 2..5 straddle the batches: 0, 10 and a NULL
+        let summaries = window_summaries(panel, &features, 2..5);
+        assert_eq!(summaries[0].window_mean, Some(5.0));
+        assert_eq!(summaries[0].window_std, Some(5.0));
+        assert_eq!(summaries[0].mean, Some(4.0));
+        assert!(!summaries[0].deviates());
+        assert_eq!(summaries[0].change_label(), "↑25%");
+        assert_eq!(summaries[1].window_mean, Some(4.0));
+        assert_eq!(summaries[1].change_label(), "↑14%");
+
+        // the last row alone: n = 6 is 1.46σ off, x = 10 is 1.22σ off
+        let last = window_summaries(panel, &features, 5..6);
+        assert!(last.iter().all(|s| !s.deviates()));
+        // a near-constant column makes any change stand out
+        let flat = WindowSummary {
+            col: 0,
+            window_mean: Some(1.5),
+            window_std: Some(0.0),
+            mean: Some(1.0),
+            std: Some(0.1),
+        };
+        assert!(flat.deviates());
+        assert_eq!(flat.change_label(), "↑50%");
+
+        // a window of NULLs has no moments
+        let empty = window_summaries(panel, &[0], 4..5);
+        assert_eq!(empty[0].window_mean, None);
+        assert_eq!(empty[0].change_label(), "—");
+    }
+
+    #[test]
+    fn panel_narrows_then_collapses() {
+        let (table, panel) = summary_split(Rect::new(0, 0, 120, 30));
+        assert_eq!(panel.map(|p| p.width), Some(FULL_WIDTH));
+        assert_eq!(table.width, 120 - FULL_WIDTH);
+        let (_, panel) = summary_split(Rect::new(0, 0, 80, 30));
+        assert_eq!(panel.map(|p| p.width), Some(COMPACT_WIDTH));
+        let (table, panel) = summary_split(Rect::new(0, 0, 60, 30));
+        assert_eq!((table.width, panel), (60, None));
+    }
+}
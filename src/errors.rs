@@ -0,0 +1,142 @@
+//! Failure categories and the exit codes `javelin` reports them with, so
+//! scripts can tell a missing dataset from one of the wrong shape.
+//!
+//! A failure site can tag its error with [`ErrorCategory::error`]; otherwise
+//! the category is read off the error chain: Lance, I/O and Arrow errors
+//! have known classes and anything unrecognised is internal.
+
+use lance::Error as LanceError;
+use std::fmt;
+use std::io::ErrorKind;
+
+/// Text appended to `--help`.
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0   success
+  1   a check failed (validate, fingerprint --verify)
+  2   the dataset could not be opened (missing or unreadable path)
+  3   not a Lance dataset, or a layout the command does not support
+  4   invalid arguments
+  5   I/O error while reading or writing
+  10  internal error or panic";
+
+/// Broad class of a failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// The command ran and its check failed
+    CheckFailed,
+    /// The dataset path is missing or cannot be read
+    OpenFailure,
+    /// The path is not a Lance dataset, or its layout does not suit the
+    /// command
+    UnsupportedLayout,
+    /// Arguments that cannot be used as given
+    InvalidArgs,
+    /// Reading or writing failed part-way
+    Io,
+    /// Anything else, panics included
+    Internal,
+}
+
+impl ErrorCategory {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorCategory::CheckFailed => 1,
+            ErrorCategory::OpenFailure => 2,
+            ErrorCategory::UnsupportedLayout => 3,
+            ErrorCategory::InvalidArgs => 4,
+            ErrorCategory::Io => 5,
+            ErrorCategory::Internal => 10,
+        }
+    }
+
+    /// An error with `message` in this category.
+    pub fn error(self, message: impl Into<String>) -> anyhow::Error {
+        CategorizedError {
+            category: self,
+            message: message.into(),
+        }
+        .into()
+    }
+
+    /// The category of `err`: that of the outermost cause with a known one.
+    pub fn of(err: &anyhow::Error) -> Self {
+        err.chain()
+            .find_map(|cause| {
+                if let Some(e) = cause.downcast_ref::<CategorizedError>() {
+                    Some(e.category)
+                } else if let Some(e) = cause.downcast_ref::<LanceError>() {
+                    of_lance(e)
+                } else if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+                    Some(of_io(e.kind()))
+                } else if let Some(e) = cause.downcast_ref::<arrow::error::ArrowError>() {
+                    matches!(e, arrow::error::ArrowError::IoError(..)).then_some(Self::Io)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(Self::Internal)
+    }
+}
+
+fn of_lance(e: &LanceError) -> Option<ErrorCategory> {
+    match e {
+        LanceError::DatasetNotFound { .. }
+        | LanceError::NotFound { .. }
+        | LanceError::CorruptFile { .. } => Some(ErrorCategory::OpenFailure),
+        LanceError::NotSupported { .. }
+        | LanceError::Schema { .. }
+        | LanceError::SchemaMismatch { .. } => Some(ErrorCategory::UnsupportedLayout),
+        LanceError::InvalidInput { .. } | LanceError::DatasetAlreadyExists { .. } => {
+            Some(ErrorCategory::InvalidArgs)
+        }
+        LanceError::IO { .. } => Some(ErrorCategory::Io),
+        _ => None,
+    }
+}
+
+fn of_io(kind: ErrorKind) -> ErrorCategory {
+    match kind {
+        ErrorKind::NotFound | ErrorKind::PermissionDenied => ErrorCategory::OpenFailure,
+        _ => ErrorCategory::Io,
+    }
+}
+
+/// An error message tagged with its category.
+#[derive(Debug)]
+pub struct CategorizedError {
+    pub category: ErrorCategory,
+    pub message: String,
+}
+
+impl fmt::Display for CategorizedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CategorizedError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn tags_survive_context_and_unknown_errors_are_internal() {
+        let tagged: anyhow::Result<()> =
+            Err(ErrorCategory::UnsupportedLayout.error("not a matrix"));
+        let err = tagged.context("loading").unwrap_err();
+        assert_eq!(ErrorCategory::of(&err), ErrorCategory::UnsupportedLayout);
+        assert_eq!(format!("{err:#}"), "loading: not a matrix");
+
+        let io = anyhow::Error::from(std::io::Error::from(ErrorKind::UnexpectedEof));
+        assert_eq!(ErrorCategory::of(&io), ErrorCategory::Io);
+        let missing = anyhow::Error::from(std::io::Error::from(ErrorKind::NotFound));
+        assert_eq!(ErrorCategory::of(&missing).exit_code(), 2);
+        assert_eq!(
+            ErrorCategory::of(&anyhow::anyhow!("oops")),
+            ErrorCategory::Internal
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! A dataset (or a row window of one) as the sequence of RecordBatches the
+//! scanner produced, each at its global row offset, rather than one batch
+//! concatenated up front. Views locate the batch holding a row when they
+//! draw it; whole-column readers (popups, graph view) can still ask for the
+//! concatenation, which is built once on first use.
+
+use anyhow::{Result, bail};
+use arrow::compute::concat_batches;
+use arrow::datatypes::SchemaRef;
+use arrow_array::RecordBatch;
+use futures::TryStreamExt;
+use lance::Dataset;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use crate::functions::functions::attach_row_ids;
+
+#[derive(Debug)]
+pub(crate) struct BatchSource {
+    schema: SchemaRef,
+    /// Non-empty batches, in row order
+    batches: Vec<RecordBatch>,
+    /// Global index of each batch's first row
+    starts: Vec<usize>,
+    num_rows: usize,
+    /// The batches concatenated, built by [`BatchSource::whole`]
+    whole: OnceLock<RecordBatch>,
+}
+
+impl BatchSource {
+    /// A source over `batches`, which must all have `schema`. Empty batches
+    /// are dropped.
+    pub(crate) fn new(schema: SchemaRef, batches: Vec<RecordBatch>) -> Result<Self> {
+        let batches: Vec<RecordBatch> = batches.into_iter().filter(|b| b.num_rows() > 0).collect();
+        if let Some(other) = batches.iter().find(|b| b.schema() != schema) {
+            bail!(
+                "batch schema {:?} differs from the source schema {:?}",
+                other.schema(),
+                schema
+            );
+        }
+        let mut starts = Vec::with_capacity(batches.len());
+        let mut num_rows = 0;
+        for batch in &batches {
+            starts.push(num_rows);
+            num_rows += batch.num_rows();
+        }
+        Ok(Self {
+            schema,
+            batches,
+            starts,
+            num_rows,
+            whole: OnceLock::new(),
+        })
+    }
+
+    /// Source rows `rows` of `dataset`, batch by batch as the scanner
+    /// streams them, `batch_size` rows at a time when given. Like
+    /// `head_batch`, a window past the start carries its source row ids as
+    /// `_rowid`.
+    pub(crate) async fn scan(
+        dataset: &Dataset,
+        rows: Range<usize>,
+        batch_size: Option<usize>,
+    ) -> Result<Self> {
+        let mut scanner = dataset.scan();
+        if let Some(size) = batch_size {
+            scanner.batch_size(size);
+        }
+        if rows.start > 0 || rows.end < dataset.count_rows(None).await? {
+            let offset = (rows.start > 0).then_some(rows.start as i64);
+            scanner.limit(Some(rows.len() as i64), offset)?;
+        }
+        let schema = scanner.schema().await?;
+        let batches: Vec<RecordBatch> = scanner.try_into_stream().await?.try_collect().await?;
+        // The streamed batches' schema is authoritative (e.g. its metadata)
+        let schema = batches.first().map_or(schema, RecordBatch::schema);
+        let source = Self::new(schema, batches)?;
+        if rows.start == 0 {
+            return Ok(source);
+        }
+        source.try_map(|batch, start| {
+            let first = (rows.start + start) as u64;
+            attach_row_ids(batch, (first..first + batch.num_rows() as u64).collect())
+        })
+    }
+
+    pub(crate) fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    pub(crate) fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub(crate) fn num_columns(&self) -> usize {
+        self.schema.fields().len()
+    }
+
+    /// An empty batch of the schema, for helpers that only read that.
+    pub(crate) fn empty_batch(&self) -> RecordBatch {
+        RecordBatch::new_empty(self.schema.clone())
+    }
+
+    /// The batches, in row order; none is empty.
+    pub(crate) fn batches(&self) -> &[RecordBatch] {
+        &self.batches
+    }
+
+    /// Each batch with the global index of its first row.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (usize, &RecordBatch)> {
+        self.starts.iter().copied().zip(&self.batches)
+    }
+
+    /// The batch holding global row `row`, and the row's index in it.
+    pub(crate) fn locate(&self, row: usize) -> Option<(&RecordBatch, usize)> {
+        if row >= self.num_rows {
+            return None;
+        }
+        let i = self.starts.partition_point(|&start| start <= row) - 1;
+        Some((&self.batches[i], row - self.starts[i]))
+    }
+
+    /// Nulls in column `col` across all batches.
+    pub(crate) fn null_count(&self, col: usize) -> usize {
+        self.batches
+            .iter()
+            .map(|b| b.column(col).null_count())
+            .sum()
+    }
+
+    /// All rows as one batch, concatenated on the first call; a source of
+    /// one batch returns it as is.
+    pub(crate) fn whole(&self) -> &RecordBatch {
+        self.whole.get_or_init(|| match self.batches.as_slice() {
+            [one] => one.clone(),
+            parts => concat_batches(&self.schema, parts)
+                .expect("batches of one schema always concatenate"),
+        })
+    }
+
+    /// Each batch passed through `f` with its global start row. The new
+    /// schema is that of the first result (of an empty batch when there
+    /// are no rows).
+    pub(crate) fn try_map(
+        &self,
+        mut f: impl FnMut(&RecordBatch, usize) -> Result<RecordBatch>,
+    ) -> Result<Self> {
+        let batches = self
+            .iter()
+            .map(|(start, batch)| f(batch, start))
+            .collect::<Result<Vec<_>>>()?;
+        let schema = match batches.first() {
+            Some(first) => first.schema(),
+            None => f(&self.empty_batch(), 0)?.schema(),
+        };
+        Self::new(schema, batches)
+    }
+
+    /// The same rows under `schema`, which may only differ in metadata
+    /// (e.g. feature names).
+    pub(crate) fn with_schema(&self, schema: SchemaRef) -> Result<Self> {
+        self.try_map(|batch, _| {
+            Ok(RecordBatch::try_new(
+                schema.clone(),
+                batch.columns().to_vec(),
+            )?)
+        })
+        .map(|source| Self { schema, ..source })
+    }
+}
+
+impl From<RecordBatch> for BatchSource {
+    fn from(batch: RecordBatch) -> Self {
+        Self::new(batch.schema(), vec![batch]).expect("a batch has its own schema")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::{Array, Float64Array};
+    use std::sync::Arc;
+
+    /// Rows 0..9 of one `col_0` column split 3 + 0 + 4 + 2, with row 5 null.
+    fn source() -> BatchSource {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "col_0",
+            DataType::Float64,
+            true,
+        )]));
+        let batch = |rows: Range<usize>| {
+            let values: Float64Array = rows.map(|r| (r != 5).then_some(r as f64)).collect();
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(values)]).unwrap()
+        };
+        BatchSource::new(
+            schema.clone(),
+            vec![batch(0..3), batch(3..3), batch(3..7), batch(7..9)],
+        )
+        .unwrap()
+    }
+
+    fn values(batch: &RecordBatch) -> Vec<Option<f64>> {
+        let col = batch.column(0).as_any().downcast_ref::<Float64Array>();
+        col.unwrap().iter().collect()
+    }
+
+    #[test]
+    fn rows_are_located_across_the_seams() {
+        let source = source();
+        assert_eq!((source.num_rows(), source.batches().len()), (9, 3));
+        // (global row, row in its batch)
+        for (row, local) in [(0, 0), (2, 2), (3, 0), (6, 3), (7, 0), (8, 1)] {
+            let (batch, r) = source.locate(row).unwrap();
+            assert_eq!(r, local, "row {row}");
+            assert_eq!(values(batch)[r], Some(row as f64), "row {row}");
+        }
+        assert!(source.locate(9).is_none());
+        assert_eq!(source.null_count(0), 1);
+
+        let want: Vec<Option<f64>> = (0..9).map(|r| (r != 5).then_some(r as f64)).collect();
+        assert_eq!(values(source.whole()), want);
+    }
+
+    #[test]
+    fn mapping_keeps_offsets_and_schemas_must_agree() {
+        let source = source();
+        let mapped = source
+            .try_map(|batch, start| {
+                attach_row_ids(batch, (start as u64..).take(batch.num_rows()).collect())
+            })
+            .unwrap();
+        assert_eq!(mapped.num_columns(), 2);
+        let ids = mapped.whole().column(1).clone();
+        let ids = ids
+            .as_any()
+            .downcast_ref::<arrow_array::UInt64Array>()
+            .unwrap();
+        assert_eq!(ids.values().to_vec(), (0..9).collect::<Vec<u64>>());
+
+        let other = RecordBatch::new_empty(Arc::new(Schema::new(vec![Field::new(
+            "other",
+            DataType::Int32,
+            false,
+        )])));
+        let mixed = vec![source.batches()[0].clone(), other];
+        // An empty batch of another schema is dropped before the check.
+        assert!(BatchSource::new(source.schema(), mixed).is_ok());
+        let other = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new(
+                "other",
+                DataType::Int32,
+                false,
+            )])),
+            vec![Arc::new(arrow_array::Int32Array::from(vec![1]))],
+        )
+        .unwrap();
+        assert!(BatchSource::new(source.schema(), vec![other]).is_err());
+    }
+}
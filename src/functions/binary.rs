@@ -0,0 +1,260 @@
+//! Binary columns (hashes, packed ids, small blobs): hex rendering for the
+//! table and for CSV, and the length/distinct summary `stats` reports.
+//!
+//! A cell shows the first bytes and the length, `4fa1c3… 32 B`; the `i`
+//! popup and CSV export carry the complete hex string.
+
+use anyhow::Result;
+use arrow::array::{Array, ArrayRef, AsArray, RecordBatch, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use futures::TryStreamExt;
+use lance::Dataset;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::functions::frequencies::DistinctSketch;
+use crate::functions::progress::Progress;
+
+/// Bytes a table cell shows before the `…`.
+const CELL_BYTES: usize = 3;
+
+/// Binary, LargeBinary, BinaryView and FixedSizeBinary.
+pub(crate) fn is_binary_type(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Binary
+            | DataType::LargeBinary
+            | DataType::BinaryView
+            | DataType::FixedSizeBinary(_)
+    )
+}
+
+/// The bytes of row `row` of a binary column; `None` for a null or a column
+/// that is not binary.
+pub(crate) fn binary_value(array: &ArrayRef, row: usize) -> Option<&[u8]> {
+    if array.is_null(row) {
+        return None;
+    }
+    match array.data_type() {
+        DataType::Binary => Some(array.as_binary::<i32>().value(row)),
+        DataType::LargeBinary => Some(array.as_binary::<i64>().value(row)),
+        DataType::BinaryView => Some(array.as_binary_view().value(row)),
+        DataType::FixedSizeBinary(_) => Some(array.as_fixed_size_binary().value(row)),
+        _ => None,
+    }
+}
+
+/// Lowercase hex of every byte.
+pub(crate) fn full_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// The first bytes in hex and the length, e.g. `4fa1c3… 32 B`.
+pub(crate) fn short_hex(bytes: &[u8]) -> String {
+    let shown = &bytes[..bytes.len().min(CELL_BYTES)];
+    let more = if bytes.len() > CELL_BYTES { "…" } else { "" };
+    format!("{}{more} {} B", full_hex(shown), bytes.len())
+        .trim_start()
+        .to_string()
+}
+
+/// `batch` with each binary column replaced by a `Utf8` column of its full
+/// hex, nulls kept, for writers that would otherwise refuse or mangle bytes.
+pub(crate) fn hex_columns(batch: &RecordBatch) -> Result<RecordBatch> {
+    let schema = batch.schema();
+    if !schema
+        .fields()
+        .iter()
+        .any(|f| is_binary_type(f.data_type()))
+    {
+        return Ok(batch.clone());
+    }
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+    for (field, col) in schema.fields().iter().zip(batch.columns()) {
+        if is_binary_type(field.data_type()) {
+            let hex: StringArray = (0..col.len())
+                .map(|r| binary_value(col, r).map(full_hex))
+                .collect();
+            fields.push(Arc::new(
+                Field::new(field.name(), DataType::Utf8, field.is_nullable())
+                    .with_metadata(field.metadata().clone()),
+            ));
+            columns.push(Arc::new(hex) as ArrayRef);
+        } else {
+            fields.push(field.clone());
+            columns.push(col.clone());
+        }
+    }
+    let schema = Schema::new(fields).with_metadata(schema.metadata().clone());
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+/// Value lengths and distinct values of one binary column.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BinaryStats {
+    pub nulls: u64,
+    /// Smallest, mean and largest length in bytes; `None` when every value
+    /// is null
+    pub min_length: Option<usize>,
+    pub mean_length: Option<f64>,
+    pub max_length: Option<usize>,
+    /// Distinct non-null values, estimated past a few hundred
+    pub distinct: u64,
+    pub distinct_exact: bool,
+}
+
+impl BinaryStats {
+    /// `"32 B each, ~1200 distinct"` or `"3–40 B (mean 12.50), 7 distinct"`.
+    pub fn label(&self) -> String {
+        let lengths = match (self.min_length, self.mean_length, self.max_length) {
+            (Some(min), _, Some(max)) if min == max => format!("{min} B each"),
+            (Some(min), Some(mean), Some(max)) => format!("{min}–{max} B (mean {mean:.2})"),
+            _ => "no values".to_string(),
+        };
+        let approx = if self.distinct_exact { "" } else { "~" };
+        format!("{lengths}, {approx}{} distinct", self.distinct)
+    }
+}
+
+/// Streaming [`BinaryStats`] of one column.
+#[derive(Debug, Clone, Default)]
+struct BinaryStatsBuilder {
+    nulls: u64,
+    values: u64,
+    total_length: u64,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    sketch: DistinctSketch,
+}
+
+impl BinaryStatsBuilder {
+    fn add(&mut self, col: &ArrayRef) {
+        self.nulls += col.null_count() as u64;
+        for r in 0..col.len() {
+            let Some(bytes) = binary_value(col, r) else {
+                continue;
+            };
+            let len = bytes.len();
+            self.values += 1;
+            self.total_length += len as u64;
+            self.min_length = Some(self.min_length.map_or(len, |m| m.min(len)));
+            self.max_length = Some(self.max_length.map_or(len, |m| m.max(len)));
+            self.sketch.insert(bytes);
+        }
+    }
+
+    fn finish(self) -> BinaryStats {
+        BinaryStats {
+            nulls: self.nulls,
+            min_length: self.min_length,
+            mean_length: (self.values > 0).then(|| self.total_length as f64 / self.values as f64),
+            max_length: self.max_length,
+            distinct: self.sketch.estimate(),
+            distinct_exact: self.sketch.is_exact(),
+        }
+    }
+}
+
+/// [`BinaryStats`] of an in-memory column.
+pub fn column_binary_stats(col: &ArrayRef) -> BinaryStats {
+    let mut builder = BinaryStatsBuilder::default();
+    builder.add(col);
+    builder.finish()
+}
+
+/// [`BinaryStats`] of every binary column of the dataset, in one streaming
+/// pass.
+pub(crate) async fn dataset_binary_stats(
+    dataset: &Dataset,
+    progress: bool,
+) -> Result<Vec<(String, BinaryStats)>> {
+    let columns: Vec<String> = Schema::from(dataset.schema())
+        .fields()
+        .iter()
+        .filter(|f| is_binary_type(f.data_type()))
+        .map(|f| f.name().clone())
+        .collect();
+    if columns.is_empty() {
+        return Ok(Vec::new());
+    }
+    let total = dataset.count_rows(None).await?;
+    let mut builders = vec![BinaryStatsBuilder::default(); columns.len()];
+    let mut scanner = dataset.scan();
+    scanner.project(&columns)?;
+    let mut stream = scanner.try_into_stream().await?;
+    let mut bar = Progress::new("stats binary", total, progress);
+    while let Some(batch) = stream.try_next().await? {
+        bar.inc(batch.num_rows());
+        for (builder, col) in builders.iter_mut().zip(batch.columns()) {
+            builder.add(col);
+        }
+    }
+    Ok(columns
+        .into_iter()
+        .zip(builders.into_iter().map(BinaryStatsBuilder::finish))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{BinaryArray, FixedSizeBinaryArray, Float64Array};
+
+    #[test]
+    fn cells_show_a_hex_prefix_and_the_length() {
+        let hash = [0x4f, 0xa1, 0xc3, 0x07, 0x00];
+        assert_eq!(short_hex(&hash), "4fa1c3… 5 B");
+        assert_eq!(short_hex(&[0xff]), "ff 1 B");
+        assert_eq!(short_hex(&[]), "0 B");
+        assert_eq!(full_hex(&hash), "4fa1c30700");
+
+        let col: ArrayRef = Arc::new(
+            FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                vec![Some([1u8, 2]), None].into_iter(),
+                2,
+            )
+            .unwrap(),
+        );
+        assert_eq!(binary_value(&col, 0), Some(&[1u8, 2][..]));
+        assert_eq!(binary_value(&col, 1), None);
+    }
+
+    #[test]
+    fn hex_columns_replace_only_binary_columns() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "x",
+                Arc::new(Float64Array::from(vec![1.0, 2.0])) as ArrayRef,
+            ),
+            (
+                "blob",
+                Arc::new(BinaryArray::from(vec![Some(&[0xab, 0x01][..]), None])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let hex = hex_columns(&batch).unwrap();
+        assert_eq!(hex.schema().field(0).data_type(), &DataType::Float64);
+        let blob = hex.column(1).as_string::<i32>();
+        assert_eq!(blob.value(0), "ab01");
+        assert!(blob.is_null(1));
+    }
+
+    #[test]
+    fn stats_summarise_lengths_and_distinct_values() {
+        let col: ArrayRef = Arc::new(BinaryArray::from(vec![
+            Some(&b"abc"[..]),
+            Some(&b"abc"[..]),
+            None,
+            Some(&b"abcdefg"[..]),
+        ]));
+        let stats = column_binary_stats(&col);
+        assert_eq!(stats.nulls, 1);
+        assert_eq!((stats.min_length, stats.max_length), (Some(3), Some(7)));
+        assert_eq!(stats.distinct, 2);
+        assert_eq!(stats.label(), "3–7 B (mean 4.33), 2 distinct");
+
+        let empty: ArrayRef = Arc::new(BinaryArray::from(vec![None::<&[u8]>]));
+        assert_eq!(column_binary_stats(&empty).label(), "no values, 0 distinct");
+    }
+}
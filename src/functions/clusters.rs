@@ -0,0 +1,504 @@
+//! `clusters`: summarize a clustering of a dense matrix given as a 1D
+//! integer label dataset with one label per matrix row.
+//!
+//! The matrix is streamed twice: once for the cluster sizes and centroids
+//! (the per-cluster feature means), once for the mean distance of each row
+//! to its centroid and an evenly spaced sample for the silhouette estimate.
+//! All distances are euclidean.
+
+use anyhow::{Result, anyhow, bail};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Schema as ArrowSchema};
+use arrow_array::{Array, Int64Array};
+use futures::TryStreamExt;
+use lance::Dataset;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::datasets::open_dataset;
+use crate::display::display_clusters::display_clusters_interactive;
+use crate::display::{LanceLayout, ROWID_COLUMN, run_viewer};
+use crate::errors::ErrorCategory;
+use crate::functions::functions::DenseMatrix;
+use crate::functions::info::dataset_layout;
+use crate::functions::progress::Progress;
+
+/// Rows sampled for the silhouette estimate.
+pub const SILHOUETTE_SAMPLE: usize = 1000;
+/// Feature means printed per cluster in the text table; `--json` has all.
+const PRINTED_MEANS: usize = 6;
+
+/// Statistics of one cluster.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClusterStats {
+    pub label: i64,
+    pub size: usize,
+    /// Centroid: the mean of each feature over the cluster's rows
+    pub means: Vec<f64>,
+    /// Mean distance of the cluster's rows to its centroid
+    pub within_distance: f64,
+    /// Closest other centroid and its distance
+    pub nearest: Option<(i64, f64)>,
+    /// Mean silhouette of the cluster's sampled rows
+    pub silhouette: Option<f64>,
+}
+
+/// Summary of a clustering.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClusterReport {
+    pub rows: usize,
+    pub features: usize,
+    /// Null or partly null matrix rows, left out of every cluster
+    pub skipped_rows: usize,
+    pub clusters: Vec<ClusterStats>,
+    /// Smallest distance between two centroids: (label, label, distance)
+    pub min_centroid_distance: Option<(i64, i64, f64)>,
+    /// Mean silhouette over the sample; `None` with fewer than two clusters
+    pub silhouette: Option<f64>,
+    /// Rows the silhouette was estimated on
+    pub sample_size: usize,
+}
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[derive(Default)]
+struct Accumulator {
+    size: usize,
+    sums: Vec<f64>,
+    distance_sum: f64,
+}
+
+/// Two-pass construction of a [`ClusterReport`]: feed every chunk of the
+/// matrix to [`add_sums`](Self::add_sums), then every chunk again to
+/// [`add_distances`](Self::add_distances).
+pub(crate) struct ClusterBuilder {
+    labels: Vec<i64>,
+    features: usize,
+    sample_step: usize,
+    clusters: BTreeMap<i64, Accumulator>,
+    centroids: BTreeMap<i64, Vec<f64>>,
+    skipped_rows: usize,
+    /// Sampled rows and their labels
+    sample: Vec<(i64, Vec<f64>)>,
+}
+
+impl ClusterBuilder {
+    /// `labels[r]` is the cluster of source row `r`; up to `sample` evenly
+    /// spaced rows are kept for the silhouette.
+    pub(crate) fn new(labels: Vec<i64>, features: usize, sample: usize) -> Self {
+        let sample_step = labels.len().div_ceil(sample.max(1)).max(1);
+        Self {
+            labels,
+            features,
+            sample_step,
+            clusters: BTreeMap::new(),
+            centroids: BTreeMap::new(),
+            skipped_rows: 0,
+            sample: Vec::new(),
+        }
+    }
+
+    /// First pass: sizes and feature sums of `matrix`, whose first row is
+    /// source row `row_offset`.
+    pub(crate) fn add_sums(&mut self, matrix: &DenseMatrix, row_offset: usize) {
+        for r in 0..matrix.list.len() {
+            let label = self.labels[row_offset + r];
+            let Some(row) = matrix.row(r) else {
+                self.skipped_rows += 1;
+                continue;
+            };
+            let acc = self.clusters.entry(label).or_default();
+            if acc.sums.is_empty() {
+                acc.sums = vec![0.0; self.features];
+            }
+            acc.size += 1;
+            acc.sums.iter_mut().zip(row).for_each(|(s, v)| *s += v);
+        }
+    }
+
+    /// Second pass: distances to the centroids and the silhouette sample.
+    pub(crate) fn add_distances(&mut self, matrix: &DenseMatrix, row_offset: usize) {
+        if self.centroids.is_empty() {
+            self.centroids = self
+                .clusters
+                .iter()
+                .map(|(&label, acc)| {
+                    let n = acc.size as f64;
+                    (label, acc.sums.iter().map(|s| s / n).collect())
+                })
+                .collect();
+        }
+        for r in 0..matrix.list.len() {
+            let source_row = row_offset + r;
+            let label = self.labels[source_row];
+            let Some(row) = matrix.row(r) else {
+                continue;
+            };
+            if let (Some(acc), Some(centroid)) =
+                (self.clusters.get_mut(&label), self.centroids.get(&label))
+            {
+                acc.distance_sum += distance(row, centroid);
+            }
+            if source_row.is_multiple_of(self.sample_step) {
+                self.sample.push((label, row.to_vec()));
+            }
+        }
+    }
+
+    /// Silhouette `(b − a) / max(a, b)` of each sampled row, where `a` is its
+    /// mean distance to the other sampled rows of its cluster and `b` the
+    /// smallest mean distance to the sampled rows of another cluster. Rows
+    /// alone in their cluster's sample score 0.
+    fn silhouettes(&self) -> Vec<(i64, f64)> {
+        let mut scores = Vec::with_capacity(self.sample.len());
+        for (i, (label, row)) in self.sample.iter().enumerate() {
+            let mut sums: BTreeMap<i64, (f64, usize)> = BTreeMap::new();
+            for (j, (other_label, other)) in self.sample.iter().enumerate() {
+                if i != j {
+                    let entry = sums.entry(*other_label).or_default();
+                    entry.0 += distance(row, other);
+                    entry.1 += 1;
+                }
+            }
+            let Some(b) = sums
+                .iter()
+                .filter(|(l, _)| *l != label)
+                .map(|(_, (sum, n))| sum / *n as f64)
+                .min_by(f64::total_cmp)
+            else {
+                continue;
+            };
+            let score = match sums.get(label) {
+                Some(&(sum, n)) => {
+                    let a = sum / n as f64;
+                    let scale = a.max(b);
+                    if scale > 0.0 { (b - a) / scale } else { 0.0 }
+                }
+                None => 0.0,
+            };
+            scores.push((*label, score));
+        }
+        scores
+    }
+
+    pub(crate) fn finish(self) -> ClusterReport {
+        let scores = self.silhouettes();
+        let mean = |values: &mut dyn Iterator<Item = f64>| {
+            let (sum, n) = values.fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+            (n > 0).then(|| sum / n as f64)
+        };
+
+        let mut min_centroid_distance: Option<(i64, i64, f64)> = None;
+        let mut clusters = Vec::with_capacity(self.clusters.len());
+        for (&label, acc) in &self.clusters {
+            let centroid = &self.centroids[&label];
+            let nearest = self
+                .centroids
+                .iter()
+                .filter(|(other, _)| **other != label)
+                .map(|(&other, c)| (other, distance(centroid, c)))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((other, d)) = nearest
+                && min_centroid_distance.is_none_or(|(_, _, best)| d < best)
+            {
+                min_centroid_distance = Some((label.min(other), label.max(other), d));
+            }
+            clusters.push(ClusterStats {
+                label,
+                size: acc.size,
+                means: centroid.clone(),
+                within_distance: acc.distance_sum / acc.size as f64,
+                nearest,
+                silhouette: mean(&mut scores.iter().filter(|(l, _)| *l == label).map(|(_, s)| *s)),
+            });
+        }
+
+        ClusterReport {
+            rows: self.labels.len(),
+            features: self.features,
+            skipped_rows: self.skipped_rows,
+            clusters,
+            min_centroid_distance,
+            silhouette: mean(&mut scores.iter().map(|(_, s)| *s)),
+            sample_size: self.sample.len(),
+        }
+    }
+}
+
+/// Labels of a 1D integer dataset, one per row. Null labels are an error.
+pub(crate) async fn load_labels(dataset: &Dataset) -> Result<Vec<i64>> {
+    let (layout, _) = dataset_layout(dataset).await?;
+    if layout != LanceLayout::Vector1D {
+        return Err(ErrorCategory::UnsupportedLayout.error(format!(
+            "labels must be a 1D dataset (one integer column), found {layout:?}"
+        )));
+    }
+    let schema = ArrowSchema::from(dataset.schema());
+    let Some(field) = schema.fields().iter().find(|f| f.name() != ROWID_COLUMN) else {
+        bail!("labels dataset has no columns");
+    };
+    if !field.data_type().is_integer() {
+        return Err(ErrorCategory::InvalidArgs.error(format!(
+            "labels must be integers, column '{}' is {}",
+            field.name(),
+            field.data_type()
+        )));
+    }
+
+    let mut scanner = dataset.scan();
+    scanner.project(&[field.name().as_str()])?;
+    let mut stream = scanner.try_into_stream().await?;
+    let mut labels = Vec::new();
+    while let Some(batch) = stream.try_next().await? {
+        let col = cast(batch.column(0), &DataType::Int64)?;
+        let col = col
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .expect("cast to Int64");
+        if col.null_count() > 0 {
+            let row = (0..col.len()).find(|&i| col.is_null(i)).unwrap_or(0);
+            bail!("label of row {} is null", labels.len() + row);
+        }
+        labels.extend(col.values().iter().copied());
+    }
+    Ok(labels)
+}
+
+/// Cluster summary of a DenseRowMajor `dataset` labelled by `labels`
+/// (one per row).
+pub(crate) async fn cluster_report(
+    dataset: &Dataset,
+    labels: Vec<i64>,
+    sample: usize,
+    progress: bool,
+) -> Result<ClusterReport> {
+    let (layout, _) = dataset_layout(dataset).await?;
+    if layout != LanceLayout::DenseRowMajor {
+        return Err(ErrorCategory::UnsupportedLayout.error(format!(
+            "clusters need a DenseRowMajor dataset (FixedSizeList<Float64>), found {layout:?}"
+        )));
+    }
+    let total = dataset.count_rows(None).await?;
+    if labels.len() != total {
+        return Err(ErrorCategory::InvalidArgs.error(format!(
+            "the matrix has {total} rows but the labels dataset has {}; they must match",
+            labels.len()
+        )));
+    }
+    let features = ArrowSchema::from(dataset.schema())
+        .fields()
+        .iter()
+        .find_map(|f| match f.data_type() {
+            DataType::FixedSizeList(_, size) => Some(*size as usize),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("no FixedSizeList column"))?;
+
+    let mut builder = ClusterBuilder::new(labels, features, sample);
+    let mut bar = Progress::new("clusters", 2 * total, progress);
+    for second_pass in [false, true] {
+        let mut stream = dataset.scan().try_into_stream().await?;
+        let mut offset = 0;
+        while let Some(batch) = stream.try_next().await? {
+            let rows = batch.num_rows();
+            bar.inc(rows);
+            if let Some(matrix) = DenseMatrix::from_batch(&batch, 0..0) {
+                if second_pass {
+                    builder.add_distances(&matrix, offset);
+                } else {
+                    builder.add_sums(&matrix, offset);
+                }
+            }
+            offset += rows;
+        }
+    }
+    bar.finish();
+    Ok(builder.finish())
+}
+
+fn format_means(means: &[f64]) -> String {
+    let mut text = means
+        .iter()
+        .take(PRINTED_MEANS)
+        .map(|m| format!("{m:.4}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if means.len() > PRINTED_MEANS {
+        text.push_str(&format!(", … ({} features)", means.len()));
+    }
+    text
+}
+
+/// Report lines shared by the text output and the viewer's summary.
+pub(crate) fn summary_lines(report: &ClusterReport) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{} rows, {} features, {} clusters",
+        report.rows,
+        report.features,
+        report.clusters.len()
+    )];
+    if report.skipped_rows > 0 {
+        lines.push(format!("{} null rows skipped", report.skipped_rows));
+    }
+    if let Some((a, b, d)) = report.min_centroid_distance {
+        lines.push(format!("closest centroids: {a} and {b} at distance {d:.6}"));
+    }
+    lines.push(match report.silhouette {
+        Some(s) => format!(
+            "silhouette: {s:.4} (estimated on {} sampled rows)",
+            report.sample_size
+        ),
+        None => "silhouette: needs at least two clusters".to_string(),
+    });
+    lines
+}
+
+/// Detail lines of one cluster, for the text output and the viewer's
+/// detail panel.
+pub(crate) fn cluster_lines(report: &ClusterReport, cluster: &ClusterStats) -> Vec<String> {
+    let share = cluster.size as f64 * 100.0 / report.rows.max(1) as f64;
+    let mut lines = vec![
+        format!("size: {} ({share:.1}% of rows)", cluster.size),
+        format!("mean distance to centroid: {:.6}", cluster.within_distance),
+    ];
+    lines.push(match cluster.nearest {
+        Some((other, d)) => format!("nearest centroid: cluster {other} at {d:.6}"),
+        None => "nearest centroid: none (single cluster)".to_string(),
+    });
+    lines.push(match cluster.silhouette {
+        Some(s) => format!("silhouette: {s:.4}"),
+        None => "silhouette: no sampled rows".to_string(),
+    });
+    lines
+}
+
+fn print_report(report: &ClusterReport) {
+    println!("=== Clusters ===");
+    for line in summary_lines(report) {
+        println!("{line}");
+    }
+    println!();
+    println!(
+        "{:>8}  {:>8}  {:>7}  {:>12}  {:>8}  {:>12}  {:>10}",
+        "cluster", "size", "share", "within", "nearest", "distance", "silhouette"
+    );
+    for c in &report.clusters {
+        let (nearest, d) = match c.nearest {
+            Some((l, d)) => (l.to_string(), format!("{d:.6}")),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        let silhouette = c.silhouette.map_or("-".to_string(), |s| format!("{s:.4}"));
+        println!(
+            "{:>8}  {:>8}  {:>6.1}%  {:>12.6}  {:>8}  {:>12}  {:>10}",
+            c.label,
+            c.size,
+            c.size as f64 * 100.0 / report.rows.max(1) as f64,
+            c.within_distance,
+            nearest,
+            d,
+            silhouette
+        );
+    }
+    println!();
+    println!("=== Feature means ===");
+    for c in &report.clusters {
+        println!("{:>8}  {}", c.label, format_means(&c.means));
+    }
+}
+
+/// Summarize the clustering of the dataset given by the `labels` dataset,
+/// as a table, JSON (`json`) or in the cluster viewer (`tui`).
+pub async fn cmd_clusters(filepath: &Path, labels: &Path, json: bool, tui: bool) -> Result<()> {
+    let dataset = open_dataset(filepath).await?;
+    let labels_dataset = open_dataset(labels).await?;
+    let labels = load_labels(&labels_dataset).await?;
+    let report = cluster_report(&dataset, labels, SILHOUETTE_SAMPLE, !json).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if tui {
+        run_viewer(move || display_clusters_interactive(&report)).await?;
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::Field;
+    use arrow_array::{FixedSizeListArray, Float64Array};
+    use std::sync::Arc;
+
+    fn matrix(rows: &[[f64; 2]]) -> DenseMatrix {
+        let values: Vec<f64> = rows.iter().flatten().copied().collect();
+        let list = FixedSizeListArray::try_new(
+            Arc::new(Field::new("item", DataType::Float64, true)),
+            2,
+            Arc::new(Float64Array::from(values)),
+            None,
+        )
+        .unwrap();
+        DenseMatrix {
+            list,
+            features: 0..2,
+        }
+    }
+
+    #[test]
+    fn two_separated_clusters() {
+        // Cluster 0 around (0, 0), cluster 7 around (10, 0).
+        let rows = [
+            [-1.0, 0.0],
+            [1.0, 0.0],
+            [10.0, 1.0],
+            [10.0, -1.0],
+            [11.0, 0.0],
+            [9.0, 0.0],
+        ];
+        let labels = vec![0, 0, 7, 7, 7, 7];
+        let m = matrix(&rows);
+        let mut builder = ClusterBuilder::new(labels, 2, 100);
+        // Chunked like a streamed dataset: rows 0..6 in one chunk.
+        builder.add_sums(&m, 0);
+        builder.add_distances(&m, 0);
+        let report = builder.finish();
+
+        assert_eq!(report.clusters.len(), 2);
+        let (a, b) = (&report.clusters[0], &report.clusters[1]);
+        assert_eq!((a.label, a.size, b.label, b.size), (0, 2, 7, 4));
+        assert_eq!(a.means, [0.0, 0.0]);
+        assert_eq!(b.means, [10.0, 0.0]);
+        assert_eq!(a.within_distance, 1.0);
+        assert_eq!(b.within_distance, 1.0);
+        assert_eq!(a.nearest, Some((7, 10.0)));
+        assert_eq!(report.min_centroid_distance, Some((0, 7, 10.0)));
+        assert_eq!(report.sample_size, 6);
+        let s = report.silhouette.unwrap();
+        assert!(s > 0.8 && s <= 1.0, "{s}");
+
+        let lines = summary_lines(&report).join("\n");
+        assert!(lines.contains("closest centroids: 0 and 7 at distance 10.000000"));
+    }
+
+    #[test]
+    fn single_cluster_has_no_silhouette() {
+        let m = matrix(&[[0.0, 0.0], [2.0, 0.0]]);
+        let mut builder = ClusterBuilder::new(vec![3, 3], 2, 1);
+        builder.add_sums(&m, 0);
+        builder.add_distances(&m, 0);
+        let report = builder.finish();
+        assert_eq!(report.silhouette, None);
+        assert_eq!(report.sample_size, 1);
+        assert_eq!(report.clusters[0].nearest, None);
+        assert_eq!(report.min_centroid_distance, None);
+    }
+}
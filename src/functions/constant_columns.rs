@@ -0,0 +1,192 @@
+//! Dead features: columns whose values (nearly) never change, common in
+//! wide embedding matrices. A column is all zero when its only value is 0,
+//! constant when it has one other value, and near-constant when its std is
+//! at most `--constant-threshold` times |mean|. The viewer checks its
+//! features once their column stats are in and offers to hide them all
+//! with `Z` (as `d` would, one by one); `stats` lists
+//! the same columns in their own section.
+
+use std::sync::Mutex;
+
+use crate::cache::ColumnStats;
+
+/// Key hiding every near-constant feature at once.
+pub(crate) const HIDE_CONSTANT_KEY: char = 'Z';
+
+/// Std, relative to |mean|, at or below which a column is near-constant
+/// unless `--constant-threshold` says otherwise.
+pub const DEFAULT_CONSTANT_THRESHOLD: f64 = 1e-3;
+
+static THRESHOLD: Mutex<Option<f64>> = Mutex::new(None);
+
+/// Set the relative std below which columns count as near-constant in the
+/// viewers opened from now on (`--constant-threshold`).
+pub fn configure_constant_threshold(threshold: f64) {
+    *THRESHOLD.lock().unwrap() = Some(threshold);
+}
+
+/// Parse a `--constant-threshold` value: a finite fraction, 0 or above.
+pub fn parse_constant_threshold(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(t) if t.is_finite() && t >= 0.0 => Ok(t),
+        _ => Err(format!(
+            "invalid threshold {s:?}, expected a fraction of 0 or more, e.g. 1e-3"
+        )),
+    }
+}
+
+pub(crate) fn constant_threshold() -> f64 {
+    THRESHOLD
+        .lock()
+        .unwrap()
+        .unwrap_or(DEFAULT_CONSTANT_THRESHOLD)
+}
+
+/// How a column fails to vary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Constancy {
+    /// Every value is 0
+    Zero,
+    /// Every value is this one
+    Constant(f64),
+    /// Some spread, but no more than the threshold allows
+    NearConstant { mean: f64, std: f64 },
+}
+
+impl Constancy {
+    /// How a column with `stats` fails to vary, if it does: `None` for a
+    /// varying, non-numeric or all-NULL column.
+    pub(crate) fn of(stats: &ColumnStats, threshold: f64) -> Option<Self> {
+        let (min, max, mean, std) = (stats.min?, stats.max?, stats.mean?, stats.std?);
+        if min == max {
+            return Some(if min == 0.0 {
+                Constancy::Zero
+            } else {
+                Constancy::Constant(min)
+            });
+        }
+        (std <= threshold * mean.abs()).then_some(Constancy::NearConstant { mean, std })
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        matches!(self, Constancy::Zero)
+    }
+
+    /// `all zero`, `constant 3.5` or `near-constant 2.000000 ± 1.000e-4`.
+    pub(crate) fn label(&self) -> String {
+        match self {
+            Constancy::Zero => "all zero".to_string(),
+            Constancy::Constant(v) => format!("constant {v}"),
+            Constancy::NearConstant { mean, std } => {
+                format!("near-constant {mean:.6} ± {std:.3e}")
+            }
+        }
+    }
+}
+
+/// The positions in `stats` of the columns that fail to vary under
+/// `threshold`, with how.
+pub(crate) fn constant_columns<'a>(
+    stats: impl IntoIterator<Item = &'a ColumnStats>,
+    threshold: f64,
+) -> Vec<(usize, Constancy)> {
+    stats
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, s)| Some((i, Constancy::of(s, threshold)?)))
+        .collect()
+}
+
+/// `"17 near-constant columns detected (5 all zero) — press Z to hide
+/// them"`, or `None` when there are none.
+pub(crate) fn constant_notice(found: &[(usize, Constancy)]) -> Option<String> {
+    if found.is_empty() {
+        return None;
+    }
+    let zeros = found.iter().filter(|(_, c)| c.is_zero()).count();
+    let (noun, them) = if found.len() == 1 {
+        ("column", "it")
+    } else {
+        ("columns", "them")
+    };
+    let zeros = match zeros {
+        0 => String::new(),
+        n if n == found.len() => " (all zero)".to_string(),
+        n => format!(" ({n} all zero)"),
+    };
+    Some(format!(
+        "{} near-constant {noun} detected{zeros} — press {HIDE_CONSTANT_KEY} to hide {them}",
+        found.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(values: &[f64]) -> ColumnStats {
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        ColumnStats {
+            count: values.len() as u64,
+            null_count: 0,
+            min: values.iter().copied().reduce(f64::min),
+            max: values.iter().copied().reduce(f64::max),
+            mean: Some(mean),
+            std: Some(var.sqrt()),
+            histogram: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn zero_constant_and_near_constant_columns_are_told_apart() {
+        let columns = [
+            stats(&[0.0, 0.0, 0.0]),
+            stats(&[1.0, 2.0, 3.0]),
+            stats(&[7.5, 7.5]),
+            stats(&[1000.0, 1000.001, 999.999]),
+            // tiny values around 0 have no mean to be small against
+            stats(&[-1e-9, 1e-9]),
+            // all NULL
+            ColumnStats {
+                null_count: 2,
+                min: None,
+                max: None,
+                mean: None,
+                std: None,
+                ..stats(&[])
+            },
+        ];
+        let found = constant_columns(&columns, DEFAULT_CONSTANT_THRESHOLD);
+        let positions: Vec<usize> = found.iter().map(|(i, _)| *i).collect();
+        assert_eq!(positions, [0, 2, 3]);
+        assert_eq!(found[0].1, Constancy::Zero);
+        assert_eq!(found[1].1, Constancy::Constant(7.5));
+        assert!(matches!(found[2].1, Constancy::NearConstant { .. }));
+        assert_eq!(found[1].1.label(), "constant 7.5");
+
+        // a looser threshold takes in more, a zero one only exact constants
+        assert_eq!(constant_columns(&columns[1..2], 0.5).len(), 1);
+        assert_eq!(constant_columns(&columns, 0.0).len(), 2);
+
+        assert_eq!(
+            constant_notice(&found).as_deref(),
+            Some("3 near-constant columns detected (1 all zero) — press Z to hide them")
+        );
+        assert_eq!(
+            constant_notice(&found[..1]).as_deref(),
+            Some("1 near-constant column detected (all zero) — press Z to hide it")
+        );
+        assert_eq!(constant_notice(&[]), None);
+    }
+
+    #[test]
+    fn thresholds_must_be_finite_and_not_negative() {
+        assert_eq!(parse_constant_threshold("1e-3"), Ok(1e-3));
+        assert_eq!(parse_constant_threshold("0"), Ok(0.0));
+        for bad in ["-0.1", "NaN", "inf", "x"] {
+            assert!(parse_constant_threshold(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! `convert`: between a dense `{ vector: FixedSizeList<Float64>[F] }`
+//! dataset and a `{ row, col, value }` COO one.
+//!
+//! Dense → COO streams: each scanned batch becomes one COO batch, keeping
+//! the entries whose magnitude exceeds `--threshold` (by default every
+//! non-zero; NaN is kept). The output carries `rows` / `cols` metadata.
+//!
+//! COO → dense reads the triplets batch by batch into an `rows × cols`
+//! buffer, refused up front when that exceeds `--max-memory`; duplicate
+//! coordinates are summed and null values skipped, as in the COO view. The
+//! matrix is then written [`DENSE_CHUNK_ROWS`] rows at a time.
+
+use anyhow::{Context, Result, bail};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, UInt32Type};
+use arrow_array::{Array, ArrayRef, FixedSizeListArray, Float64Array, RecordBatch, UInt32Array};
+use futures::TryStreamExt;
+use lance::Dataset;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::datasets::{BatchWriter, open_dataset, path_to_uri};
+use crate::display::LanceLayout;
+use crate::errors::ErrorCategory;
+use crate::functions::format::format_bytes;
+use crate::functions::functions::canonical_coo;
+use crate::functions::info::{coo_max_indices, dataset_layout};
+use crate::functions::progress::Progress;
+
+/// Rows per batch of a dense output.
+pub const DENSE_CHUNK_ROWS: usize = 4096;
+
+/// Layout `convert --to` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConvertTarget {
+    /// `{ vector: FixedSizeList<Float64>[cols] }`
+    Dense,
+    /// `{ row: UInt32, col: UInt32, value: Float64 }`
+    Coo,
+}
+
+/// Convert the dataset at `filepath` to `to`, written as a new dataset at
+/// `output`. `threshold` applies to dense → COO, `max_memory` to COO →
+/// dense.
+pub async fn cmd_convert(
+    filepath: &Path,
+    to: ConvertTarget,
+    output: &Path,
+    threshold: f64,
+    max_memory: u64,
+) -> Result<()> {
+    if output.exists() {
+        return Err(ErrorCategory::InvalidArgs.error(format!(
+            "output {:?} already exists; remove it or choose another path",
+            output
+        )));
+    }
+    let dataset = open_dataset(filepath).await?;
+    let (layout, _) = dataset_layout(&dataset).await?;
+    let uri = path_to_uri(output);
+    match (layout, to) {
+        (LanceLayout::DenseRowMajor, ConvertTarget::Coo) => {
+            let (rows, cols, nnz) = dense_to_coo(&dataset, &uri, threshold).await?;
+            println!(
+                "Converted {rows} × {cols} dense matrix to {nnz} COO triples in {:?}",
+                output
+            );
+        }
+        (LanceLayout::SparseCoo, ConvertTarget::Dense) => {
+            let (rows, cols) = coo_to_dense(&dataset, &uri, max_memory).await?;
+            println!(
+                "Converted COO matrix to a {rows} × {cols} dense matrix in {:?}",
+                output
+            );
+        }
+        (LanceLayout::DenseRowMajor, ConvertTarget::Dense)
+        | (LanceLayout::SparseCoo, ConvertTarget::Coo) => {
+            return Err(ErrorCategory::InvalidArgs.error(format!(
+                "the dataset already is {layout:?}; nothing to convert"
+            )));
+        }
+        (layout, _) => {
+            return Err(ErrorCategory::UnsupportedLayout.error(format!(
+                "convert reads DenseRowMajor (vector: FixedSizeList<Float64>) or SparseCoo \
+                 (row, col, value) datasets, found {layout:?}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Stream the dense matrix of `dataset` into a COO dataset at `uri`;
+/// returns its rows, columns and triples written.
+async fn dense_to_coo(
+    dataset: &Dataset,
+    uri: &str,
+    threshold: f64,
+) -> Result<(usize, usize, usize)> {
+    let source = Schema::from(dataset.schema());
+    let cols = match source.field(0).data_type() {
+        DataType::FixedSizeList(_, width) => *width as usize,
+        dt => bail!("expected a FixedSizeList column, found {dt}"),
+    };
+    let rows = dataset.count_rows(None).await?;
+    if u32::try_from(rows.max(cols)).is_err() {
+        bail!("a {rows} × {cols} matrix does not fit UInt32 COO indices");
+    }
+
+    let mut metadata = source.metadata().clone();
+    metadata.insert("rows".to_string(), rows.to_string());
+    metadata.insert("cols".to_string(), cols.to_string());
+    let schema = Arc::new(coo_schema(metadata));
+    let mut writer = BatchWriter::new(uri, schema.clone());
+
+    let mut stream = dataset.scan().try_into_stream().await?;
+    let mut bar = Progress::new("rows", rows, true);
+    let mut first_row = 0;
+    while let Some(batch) = stream.try_next().await? {
+        let coo = dense_batch_to_coo(&batch, first_row, threshold, schema.clone())?;
+        first_row += batch.num_rows();
+        bar.inc(batch.num_rows());
+        if coo.num_rows() > 0 {
+            writer.write(coo).await?;
+        }
+    }
+    bar.finish();
+    let nnz = writer.finish().await?;
+    Ok((rows, cols, nnz))
+}
+
+/// `{ row, col, value }` with `metadata`.
+fn coo_schema(metadata: HashMap<String, String>) -> Schema {
+    Schema::new(vec![
+        Field::new("row", DataType::UInt32, false),
+        Field::new("col", DataType::UInt32, false),
+        Field::new("value", DataType::Float64, false),
+    ])
+    .with_metadata(metadata)
+}
+
+/// The entries of a DenseRowMajor `batch` whose first row is `first_row`,
+/// as COO triples with `schema`; null rows have none.
+pub(crate) fn dense_batch_to_coo(
+    batch: &RecordBatch,
+    first_row: usize,
+    threshold: f64,
+    schema: Arc<Schema>,
+) -> Result<RecordBatch> {
+    let list = batch
+        .column(0)
+        .as_fixed_size_list_opt()
+        .context("expected a FixedSizeList column")?;
+    let width = list.value_length() as usize;
+    let values = cast(list.values(), &DataType::Float64)?;
+    let values = values.as_primitive::<Float64Type>();
+
+    let (mut row, mut col, mut value) = (Vec::new(), Vec::new(), Vec::new());
+    for r in (0..list.len()).filter(|&r| list.is_valid(r)) {
+        let start = list.value_offset(r) as usize;
+        for c in 0..width {
+            let i = start + c;
+            if values.is_null(i) {
+                continue;
+            }
+            let v = values.value(i);
+            if v.abs() > threshold || v.is_nan() {
+                row.push((first_row + r) as u32);
+                col.push(c as u32);
+                value.push(v);
+            }
+        }
+    }
+    Ok(RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(UInt32Array::from(row)) as ArrayRef,
+            Arc::new(UInt32Array::from(col)) as ArrayRef,
+            Arc::new(Float64Array::from(value)) as ArrayRef,
+        ],
+    )?)
+}
+
+/// Materialize the COO matrix of `dataset` as a dense dataset at `uri`;
+/// returns its shape.
+async fn coo_to_dense(dataset: &Dataset, uri: &str, max_memory: u64) -> Result<(usize, usize)> {
+    let metadata = dataset.schema().metadata.clone();
+    let dim = |key: &str| metadata.get(key).and_then(|v| v.parse::<usize>().ok());
+    let (rows, cols) = match (dim("rows"), dim("cols")) {
+        (Some(rows), Some(cols)) => (rows, cols),
+        _ => match coo_max_indices(dataset).await? {
+            Some((r, c)) => (r as usize + 1, c as usize + 1),
+            None => bail!("no rows/cols metadata and no triples to infer the shape from"),
+        },
+    };
+    if cols == 0 {
+        bail!("cannot build a dense matrix with zero columns");
+    }
+    let bytes = (rows as u64)
+        .saturating_mul(cols as u64)
+        .saturating_mul(std::mem::size_of::<f64>() as u64);
+    if bytes > max_memory {
+        bail!(
+            "a dense {rows} × {cols} matrix needs {}, over the {} limit; raise --max-memory",
+            format_bytes(bytes),
+            format_bytes(max_memory)
+        );
+    }
+
+    let mut dense = vec![0.0; rows * cols];
+    let total = dataset.count_rows(None).await?;
+    let mut stream = dataset.scan().try_into_stream().await?;
+    let mut bar = Progress::new("triples", total, true);
+    while let Some(batch) = stream.try_next().await? {
+        bar.inc(batch.num_rows());
+        let batch = canonical_coo(&batch)?;
+        let row = batch.column(0).as_primitive::<UInt32Type>();
+        let col = batch.column(1).as_primitive::<UInt32Type>();
+        let value = batch.column(2).as_primitive::<Float64Type>();
+        for i in (0..batch.num_rows()).filter(|&i| value.is_valid(i)) {
+            let (r, c) = (row.value(i) as usize, col.value(i) as usize);
+            if r >= rows || c >= cols {
+                bail!("triple ({r}, {c}) lies outside the {rows} × {cols} matrix");
+            }
+            dense[r * cols + c] += value.value(i);
+        }
+    }
+    bar.finish();
+
+    let item = Arc::new(Field::new("item", DataType::Float64, false));
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "vector",
+        DataType::FixedSizeList(item.clone(), cols as i32),
+        false,
+    )]));
+    let mut writer = BatchWriter::new(uri, schema.clone());
+    for chunk in dense.chunks(DENSE_CHUNK_ROWS * cols) {
+        let list = FixedSizeListArray::new(
+            item.clone(),
+            cols as i32,
+            Arc::new(Float64Array::from(chunk.to_vec())),
+            None,
+        );
+        writer
+            .write(RecordBatch::try_new(schema.clone(), vec![Arc::new(list)])?)
+            .await?;
+    }
+    writer.finish().await?;
+    Ok((rows, cols))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::dense_rows_to_batch;
+
+    #[test]
+    fn thresholds_drop_small_entries_and_keep_nan() {
+        let batch =
+            dense_rows_to_batch(&[vec![0.0, 0.5, -2.0], vec![f64::NAN, 0.01, 0.0]]).unwrap();
+        let schema = Arc::new(coo_schema(HashMap::new()));
+
+        let exact = dense_batch_to_coo(&batch, 10, 0.0, schema.clone()).unwrap();
+        let rows = exact.column(0).as_primitive::<UInt32Type>();
+        let cols = exact.column(1).as_primitive::<UInt32Type>();
+        assert_eq!(rows.values(), &[10, 10, 11, 11]);
+        assert_eq!(cols.values(), &[1, 2, 0, 1]);
+
+        let coarse = dense_batch_to_coo(&batch, 0, 0.1, schema).unwrap();
+        let values = coarse.column(2).as_primitive::<Float64Type>();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values.value(0), 0.5);
+        assert!(values.value(2).is_nan());
+    }
+}
@@ -0,0 +1,272 @@
+//! `dedup`: find rows that repeat an earlier row exactly, and optionally
+//! write the dataset without them.
+//!
+//! Each row's values across all columns are hashed with the canonical
+//! encoding of `fingerprint` into a 128-bit xxh3 digest, and rows with equal
+//! digests form a group. That encoding decides what counts as equal:
+//! - a null (row or element) only equals a null;
+//! - every NaN equals every other NaN;
+//! - `-0.0` equals `0.0`;
+//! - with `--round k`, floats that agree to `k` decimals are equal.
+//!
+//! Row ids are zero-based positions in scan order. The output keeps the first
+//! row of each group, in the original order.
+
+use anyhow::Result;
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::Schema;
+use arrow_array::{BooleanArray, RecordBatch};
+use futures::TryStreamExt;
+use lance::Dataset;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::datasets::{BatchWriter, open_dataset, path_to_uri};
+use crate::errors::ErrorCategory;
+use crate::functions::export::Provenance;
+use crate::functions::fingerprint::hash_array;
+use crate::functions::progress::Progress;
+
+/// Groups listed by `dedup`, largest first.
+pub const TOP_GROUPS: usize = 10;
+/// Row ids kept per group as examples.
+pub const EXAMPLE_ROWS: usize = 5;
+
+/// Rows sharing one set of values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateGroup {
+    /// Rows in the group, the first one included
+    pub count: usize,
+    /// The first [`EXAMPLE_ROWS`] row ids, ascending
+    pub rows: Vec<u64>,
+}
+
+/// What `dedup` reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DedupReport {
+    pub rows: u64,
+    pub distinct_rows: u64,
+    /// Groups of two or more equal rows
+    pub duplicate_groups: usize,
+    /// Rows repeating an earlier one: `rows − distinct_rows`
+    pub redundant_rows: u64,
+    /// The largest groups (by size, then first row), at most [`TOP_GROUPS`]
+    pub largest: Vec<DuplicateGroup>,
+}
+
+/// Streaming duplicate detection over the batches of one dataset.
+pub struct RowDeduper {
+    decimals: Option<u32>,
+    groups: HashMap<u128, DuplicateGroup>,
+    rows: u64,
+}
+
+impl RowDeduper {
+    pub fn new(decimals: Option<u32>) -> Self {
+        Self {
+            decimals,
+            groups: HashMap::new(),
+            rows: 0,
+        }
+    }
+
+    /// Record the rows of `batch`; the mask is `true` for the rows seen for
+    /// the first time.
+    pub fn push(&mut self, batch: &RecordBatch) -> Result<BooleanArray> {
+        let mut first = Vec::with_capacity(batch.num_rows());
+        for r in 0..batch.num_rows() {
+            let digest = row_digest(batch, r, self.decimals)?;
+            let id = self.rows;
+            self.rows += 1;
+            let group = self.groups.entry(digest).or_insert_with(|| DuplicateGroup {
+                count: 0,
+                rows: Vec::new(),
+            });
+            first.push(group.count == 0);
+            group.count += 1;
+            if group.rows.len() < EXAMPLE_ROWS {
+                group.rows.push(id);
+            }
+        }
+        Ok(BooleanArray::from(first))
+    }
+
+    pub fn report(self) -> DedupReport {
+        let distinct_rows = self.groups.len() as u64;
+        let mut duplicates: Vec<DuplicateGroup> =
+            self.groups.into_values().filter(|g| g.count > 1).collect();
+        duplicates.sort_by(|a, b| b.count.cmp(&a.count).then(a.rows[0].cmp(&b.rows[0])));
+        let duplicate_groups = duplicates.len();
+        duplicates.truncate(TOP_GROUPS);
+        DedupReport {
+            rows: self.rows,
+            distinct_rows,
+            duplicate_groups,
+            redundant_rows: self.rows - distinct_rows,
+            largest: duplicates,
+        }
+    }
+}
+
+/// The 128-bit digest of row `r` of `batch`: its values across all columns
+/// in the canonical encoding of `fingerprint`.
+pub(crate) fn row_digest(batch: &RecordBatch, r: usize, decimals: Option<u32>) -> Result<u128> {
+    let mut h = Xxh3::new();
+    for column in batch.columns() {
+        hash_array(&mut h, column.slice(r, 1).as_ref(), decimals)?;
+    }
+    Ok(h.digest128())
+}
+
+impl DedupReport {
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Rows: {}", self.rows),
+            format!("Distinct rows: {}", self.distinct_rows),
+            format!(
+                "Duplicate groups: {} ({} redundant rows)",
+                self.duplicate_groups, self.redundant_rows
+            ),
+        ];
+        if !self.largest.is_empty() {
+            lines.push("Largest groups:".to_string());
+        }
+        for group in &self.largest {
+            let ids: Vec<String> = group.rows.iter().map(u64::to_string).collect();
+            let more = if group.count > group.rows.len() {
+                ", …"
+            } else {
+                ""
+            };
+            lines.push(format!("  {} rows: {}{more}", group.count, ids.join(", ")));
+        }
+        lines
+    }
+}
+
+/// Report the duplicate rows of `dataset`, writing the distinct ones to a
+/// new dataset at `output` when given.
+pub async fn dedup_dataset(
+    dataset: &Dataset,
+    decimals: Option<u32>,
+    output: Option<(&str, &Provenance)>,
+    progress: bool,
+) -> Result<DedupReport> {
+    let mut writer = output.map(|(uri, provenance)| {
+        let schema = Schema::from(dataset.schema());
+        let mut metadata = schema.metadata().clone();
+        for (key, value) in provenance.entries() {
+            metadata.insert(key.to_string(), value);
+        }
+        let schema = Arc::new(schema.with_metadata(metadata));
+        (BatchWriter::new(uri, schema.clone()), schema)
+    });
+
+    let mut deduper = RowDeduper::new(decimals);
+    let total = dataset.count_rows(None).await?;
+    let mut bar = Progress::new("rows", total, progress);
+    let mut stream = dataset.scan().try_into_stream().await?;
+    while let Some(batch) = stream.try_next().await? {
+        let first = deduper.push(&batch)?;
+        bar.inc(batch.num_rows());
+        if let Some((writer, schema)) = writer.as_mut() {
+            let kept = filter_record_batch(&batch, &first)?;
+            if kept.num_rows() > 0 {
+                let kept = RecordBatch::try_new(schema.clone(), kept.columns().to_vec())?;
+                writer.write(kept).await?;
+            }
+        }
+    }
+    bar.finish();
+    if let Some((writer, _)) = writer {
+        writer.finish().await?;
+    }
+    Ok(deduper.report())
+}
+
+/// `dedup`: print the report, as JSON with `json`.
+pub async fn cmd_dedup(
+    filepath: &Path,
+    output: Option<&Path>,
+    decimals: Option<u32>,
+    json: bool,
+) -> Result<()> {
+    if let Some(output) = output
+        && output.exists()
+    {
+        return Err(ErrorCategory::InvalidArgs.error(format!(
+            "output {:?} already exists; remove it or choose another path",
+            output
+        )));
+    }
+    let dataset = open_dataset(filepath).await?;
+    let uri = output.map(path_to_uri);
+    let provenance = Provenance::new(filepath, "dedup")
+        .with_filter(decimals.map_or(String::new(), |k| format!("round {k}")));
+    let report = dedup_dataset(
+        &dataset,
+        decimals,
+        uri.as_deref().map(|uri| (uri, &provenance)),
+        !json,
+    )
+    .await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("=== Duplicate rows in {} ===", filepath.display());
+        for line in report.lines() {
+            println!("{line}");
+        }
+    }
+    if let Some(output) = output {
+        eprintln!(
+            "{} distinct rows written to {}",
+            report.distinct_rows,
+            output.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::dense_rows_to_batch;
+
+    #[test]
+    fn nan_and_signed_zeros_group_and_rounding_merges() {
+        let nan = f64::NAN;
+        let batch = dense_rows_to_batch(&[
+            vec![1.0, nan],
+            vec![0.0, 2.0],
+            vec![1.0, f64::from_bits(nan.to_bits() | 1)],
+            vec![-0.0, 2.0],
+            vec![0.0, 2.000_000_1],
+        ])
+        .unwrap();
+
+        let mut exact = RowDeduper::new(None);
+        let first = exact.push(&batch.slice(0, 2)).unwrap();
+        assert_eq!(first, BooleanArray::from(vec![true, true]));
+        let first = exact.push(&batch.slice(2, 3)).unwrap();
+        assert_eq!(first, BooleanArray::from(vec![false, false, true]));
+        let report = exact.report();
+        assert_eq!((report.rows, report.distinct_rows), (5, 3));
+        assert_eq!((report.duplicate_groups, report.redundant_rows), (2, 2));
+        assert_eq!(report.largest[0].rows, [0, 2]);
+        assert_eq!(report.largest[1].rows, [1, 3]);
+
+        let mut rounded = RowDeduper::new(Some(3));
+        rounded.push(&batch).unwrap();
+        let report = rounded.report();
+        assert_eq!(report.largest[0].count, 3);
+        assert_eq!(
+            report.lines()[3..],
+            ["Largest groups:", "  3 rows: 1, 3, 4", "  2 rows: 0, 2"]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! Deleted rows of a Lance version: per-fragment deletion counts for `info
+//! --verbose` and the viewer's banner, and the `--include-deleted` read of
+//! `head`/`display`, which keeps tombstoned rows in place with a null
+//! [`ROWID_COLUMN`] (Lance's own marking) for the viewer to strike through.
+
+use anyhow::Result;
+use arrow::compute::{filter_record_batch, is_not_null, take_record_batch};
+use arrow_array::{Array, RecordBatch, UInt64Array};
+use futures::TryStreamExt;
+use lance::Dataset;
+use serde::Serialize;
+use std::ops::Range;
+
+use crate::functions::batch_source::BatchSource;
+use crate::functions::functions::ROWID_COLUMN;
+use crate::functions::functions::attach_tombstoned_row_ids;
+
+/// Rows of one fragment, deleted ones included.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FragmentRows {
+    pub id: usize,
+    /// Rows in the fragment's data files
+    pub physical: usize,
+    /// Rows its deletion vector removes
+    pub deleted: usize,
+}
+
+/// Deleted rows of every fragment of a dataset version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeletionReport {
+    pub fragments: Vec<FragmentRows>,
+}
+
+impl DeletionReport {
+    pub fn physical_rows(&self) -> usize {
+        self.fragments.iter().map(|f| f.physical).sum()
+    }
+
+    pub fn deleted_rows(&self) -> usize {
+        self.fragments.iter().map(|f| f.deleted).sum()
+    }
+
+    /// Rows a scan returns: the physical rows less the deleted ones.
+    pub fn logical_rows(&self) -> usize {
+        self.physical_rows() - self.deleted_rows()
+    }
+}
+
+/// Physical and deleted row counts of each fragment of `dataset`.
+pub(crate) async fn deletion_report(dataset: &Dataset) -> Result<DeletionReport> {
+    let mut fragments = Vec::new();
+    for fragment in dataset.get_fragments() {
+        fragments.push(FragmentRows {
+            id: fragment.id(),
+            physical: fragment.physical_rows().await?,
+            deleted: fragment.count_deletions().await?,
+        });
+    }
+    Ok(DeletionReport { fragments })
+}
+
+/// Rows deleted in this version of `dataset`, from the deletion files alone.
+pub(crate) async fn deleted_rows(dataset: &Dataset) -> Result<usize> {
+    let mut deleted = 0;
+    for fragment in dataset.get_fragments() {
+        deleted += fragment.count_deletions().await?;
+    }
+    Ok(deleted)
+}
+
+/// The `Deletions` section of `info --verbose`.
+pub(crate) fn deletion_lines(report: &DeletionReport) -> Vec<String> {
+    let mut out = vec![format!(
+        "Deletions: {} of {} physical row(s) deleted, {} logical row(s)",
+        report.deleted_rows(),
+        report.physical_rows(),
+        report.logical_rows()
+    )];
+    out.push(format!(
+        "  {:>8}  {:>10}  {:>10}",
+        "fragment", "physical", "deleted"
+    ));
+    for f in &report.fragments {
+        out.push(format!(
+            "  {:>8}  {:>10}  {:>10}",
+            f.id, f.physical, f.deleted
+        ));
+    }
+    out
+}
+
+/// The live rows `rows` (logical numbering) of `dataset` with the deleted
+/// rows among them kept in place. Every row carries [`ROWID_COLUMN`]: its
+/// logical index, or null for a tombstone. Tombstones before the first live
+/// row of the window are included, those after its last are not.
+pub(crate) async fn scan_with_deleted(
+    dataset: &Dataset,
+    rows: Range<usize>,
+    batch_size: Option<usize>,
+) -> Result<BatchSource> {
+    let mut scanner = dataset.scan();
+    scanner.with_row_id().include_deleted_rows();
+    if let Some(size) = batch_size {
+        scanner.batch_size(size);
+    }
+    let empty = RecordBatch::new_empty(scanner.schema().await?);
+    let mut stream = scanner.try_into_stream().await?;
+    let mut live = 0;
+    let mut batches = Vec::new();
+    while live < rows.end
+        && let Some(batch) = stream.try_next().await?
+    {
+        let lance_ids = batch
+            .column_by_name(ROWID_COLUMN)
+            .expect("the scan adds row ids")
+            .clone();
+        let mut keep = Vec::new();
+        let mut ids = Vec::new();
+        for r in 0..batch.num_rows() {
+            if lance_ids.is_null(r) {
+                if rows.start <= live && live < rows.end {
+                    keep.push(r as u64);
+                    ids.push(None);
+                }
+            } else {
+                if rows.contains(&live) {
+                    keep.push(r as u64);
+                    ids.push(Some(live as u64));
+                }
+                live += 1;
+            }
+        }
+        let taken = take_record_batch(&batch, &UInt64Array::from(keep))?;
+        batches.push(attach_tombstoned_row_ids(&taken, ids)?);
+    }
+    // The streamed batches' schema is authoritative (e.g. its metadata)
+    let schema = match batches.first() {
+        Some(batch) => batch.schema(),
+        None => attach_tombstoned_row_ids(&empty, Vec::new())?.schema(),
+    };
+    BatchSource::new(schema, batches)
+}
+
+/// Whether `row` is a deleted row read by `--include-deleted`: its
+/// [`ROWID_COLUMN`] is null.
+pub(crate) fn is_tombstone(data: &BatchSource, row: usize) -> bool {
+    data.locate(row).is_some_and(|(batch, r)| {
+        batch
+            .column_by_name(ROWID_COLUMN)
+            .is_some_and(|ids| ids.is_null(r))
+    })
+}
+
+/// `batch` without its deleted rows, so `W` never writes them back.
+pub(crate) fn drop_tombstones(batch: &RecordBatch) -> Result<RecordBatch> {
+    match batch.column_by_name(ROWID_COLUMN) {
+        Some(ids) if ids.null_count() > 0 => Ok(filter_record_batch(batch, &is_not_null(ids)?)?),
+        _ => Ok(batch.clone()),
+    }
+}
@@ -0,0 +1,238 @@
+//! Comparing a dataset with an earlier version of itself: which rows of the
+//! opened version did not exist at version K, for `display --since-version`.
+//!
+//! Lance fragments are immutable: appends and updates write new fragments,
+//! deletes only add deletion vectors. While every fragment of version K is
+//! still there (same id, same data files), the added rows are exactly those
+//! of the fragments K lacks, found from the manifests without reading any
+//! data. Once a fragment of K
+//! is gone (compaction, an overwrite, a fragment deleted whole) rows are
+//! compared by value instead: each row's `dedup` digest over the columns
+//! both versions share, counted, so a row repeated more often than at K is
+//! new too.
+
+use anyhow::{Context, Result};
+use arrow::compute::filter_record_batch;
+use arrow_array::{BooleanArray, UInt64Array};
+use futures::TryStreamExt;
+use lance::Dataset;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use crate::display::ROWID_COLUMN;
+use crate::errors::ErrorCategory;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::dedup::row_digest;
+use crate::functions::deletions::deletion_report;
+use crate::functions::functions::attach_row_ids;
+
+/// How the rows of two versions were matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// By fragment: rows of fragments the base version lacks are new
+    Lineage,
+    /// By the digest of each row's values
+    Hashed,
+}
+
+/// Rows of a version that were not in version `base`, by logical row index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddedRows {
+    pub base: u64,
+    pub comparison: Comparison,
+    /// Sorted, disjoint and never adjacent
+    ranges: Vec<Range<u64>>,
+}
+
+impl AddedRows {
+    pub fn new(base: u64, comparison: Comparison, rows: impl IntoIterator<Item = u64>) -> Self {
+        let mut rows: Vec<u64> = rows.into_iter().collect();
+        rows.sort_unstable();
+        let mut ranges: Vec<Range<u64>> = Vec::new();
+        for row in rows {
+            match ranges.last_mut() {
+                Some(last) if row <= last.end => last.end = last.end.max(row + 1),
+                _ => ranges.push(row..row + 1),
+            }
+        }
+        Self {
+            base,
+            comparison,
+            ranges,
+        }
+    }
+
+    pub fn contains(&self, row: u64) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= row);
+        self.ranges.get(i).is_some_and(|r| r.contains(&row))
+    }
+
+    pub fn count(&self) -> u64 {
+        self.ranges.iter().map(|r| r.end - r.start).sum()
+    }
+
+    /// The metadata panel's summary, e.g. `"3 rows new since v2"`.
+    pub fn note(&self) -> String {
+        let by = match self.comparison {
+            Comparison::Lineage => "",
+            Comparison::Hashed => " (by value)",
+        };
+        format!("{} rows new since v{}{by}", self.count(), self.base)
+    }
+}
+
+/// Version `version` of the dataset `dataset` was opened from, which must
+/// not be newer than it.
+pub(crate) async fn open_version(dataset: &Dataset, version: u64) -> Result<Dataset> {
+    let current = dataset.version().version;
+    if version > current {
+        return Err(ErrorCategory::InvalidArgs.error(format!(
+            "--since-version {version} is newer than the opened version {current}"
+        )));
+    }
+    dataset
+        .checkout_version(version)
+        .await
+        .with_context(|| format!("cannot open version {version}"))
+}
+
+/// The rows of `dataset` that were not in `base`, an earlier version of it.
+pub(crate) async fn added_rows(dataset: &Dataset, base: &Dataset) -> Result<AddedRows> {
+    let version = base.version().version;
+    let before = fragment_files(base);
+    let current = fragment_files(dataset);
+    // a fragment of `base`, possibly with columns added since
+    let existed = |(id, files): &(usize, HashSet<String>)| {
+        before
+            .iter()
+            .any(|(before_id, before_files)| before_id == id && before_files.is_subset(files))
+    };
+    let kept = current.iter().filter(|f| existed(f)).count();
+    if kept == before.len() {
+        let report = deletion_report(dataset).await?;
+        let mut rows = Vec::new();
+        let mut start = 0;
+        for (fragment, rows_of) in current.iter().zip(&report.fragments) {
+            let live = (rows_of.physical - rows_of.deleted) as u64;
+            if !existed(fragment) {
+                rows.extend(start..start + live);
+            }
+            start += live;
+        }
+        return Ok(AddedRows::new(version, Comparison::Lineage, rows));
+    }
+    hashed_added_rows(dataset, base).await
+}
+
+/// Each fragment's id and data files. An overwrite starts the ids afresh,
+/// but never reuses a file name.
+fn fragment_files(dataset: &Dataset) -> Vec<(usize, HashSet<String>)> {
+    dataset
+        .get_fragments()
+        .iter()
+        .map(|f| {
+            let files = f.metadata().files.iter().map(|d| d.path.clone()).collect();
+            (f.id(), files)
+        })
+        .collect()
+}
+
+/// [`added_rows`] by value, over the columns both versions have.
+async fn hashed_added_rows(dataset: &Dataset, base: &Dataset) -> Result<AddedRows> {
+    let version = base.version().version;
+    let shared: Vec<String> = base
+        .schema()
+        .fields
+        .iter()
+        .map(|f| f.name.clone())
+        .filter(|name| dataset.schema().field(name).is_some())
+        .collect();
+    if shared.is_empty() {
+        let rows = dataset.count_rows(None).await? as u64;
+        return Ok(AddedRows::new(version, Comparison::Hashed, 0..rows));
+    }
+
+    let mut before: HashMap<u128, usize> = HashMap::new();
+    let mut scanner = base.scan();
+    scanner.project(&shared)?;
+    let mut stream = scanner.try_into_stream().await?;
+    while let Some(batch) = stream.try_next().await? {
+        for r in 0..batch.num_rows() {
+            *before.entry(row_digest(&batch, r, None)?).or_default() += 1;
+        }
+    }
+
+    let mut rows = Vec::new();
+    let mut row = 0;
+    let mut scanner = dataset.scan();
+    scanner.project(&shared)?;
+    let mut stream = scanner.try_into_stream().await?;
+    while let Some(batch) = stream.try_next().await? {
+        for r in 0..batch.num_rows() {
+            match before.get_mut(&row_digest(&batch, r, None)?) {
+                Some(seen) if *seen > 0 => *seen -= 1,
+                _ => rows.push(row),
+            }
+            row += 1;
+        }
+    }
+    Ok(AddedRows::new(version, Comparison::Hashed, rows))
+}
+
+/// The rows of `data` that `added` lists, for `--only-new`. Rows are matched
+/// by their [`ROWID_COLUMN`] id, or their position when `data` starts at
+/// the first row and carries none; the kept rows carry their ids.
+pub(crate) fn keep_added(data: &BatchSource, added: &AddedRows) -> Result<BatchSource> {
+    data.try_map(|batch, start| {
+        let ids: Vec<Option<u64>> = match batch.column_by_name(ROWID_COLUMN) {
+            Some(ids) => ids
+                .as_any()
+                .downcast_ref::<UInt64Array>()
+                .expect("row ids are UInt64")
+                .iter()
+                .collect(),
+            None => (start as u64..(start + batch.num_rows()) as u64)
+                .map(Some)
+                .collect(),
+        };
+        let keep: BooleanArray = ids
+            .iter()
+            .map(|id| Some(id.is_some_and(|id| added.contains(id))))
+            .collect();
+        if batch.column_by_name(ROWID_COLUMN).is_some() {
+            return Ok(filter_record_batch(batch, &keep)?);
+        }
+        let kept: Vec<u64> = ids
+            .into_iter()
+            .flatten()
+            .filter(|&id| added.contains(id))
+            .collect();
+        attach_row_ids(&filter_record_batch(batch, &keep)?, kept)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_merge_into_ranges_and_count() {
+        let added = AddedRows::new(2, Comparison::Lineage, [7, 3, 4, 5, 9, 4]);
+        assert_eq!(added.ranges, [3..6, 7..8, 9..10]);
+        assert_eq!(added.count(), 5);
+        for (row, want) in [
+            (2, false),
+            (3, true),
+            (5, true),
+            (6, false),
+            (9, true),
+            (10, false),
+        ] {
+            assert_eq!(added.contains(row), want, "row {row}");
+        }
+        assert_eq!(added.note(), "5 rows new since v2");
+        let hashed = AddedRows::new(1, Comparison::Hashed, []);
+        assert_eq!(hashed.note(), "0 rows new since v1 (by value)");
+        assert!(!hashed.contains(0));
+    }
+}
@@ -1,50 +1,552 @@
 use anyhow::Result;
 use anyhow::anyhow;
 
-use arrow_array::RecordBatch;
+use arrow::datatypes::Schema as ArrowSchema;
 use lance::dataset::Dataset;
-use log::{debug, info};
+use log::{debug, info, warn};
 
-use std::path::PathBuf;
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use crate::datasets::path_to_uri;
-use crate::display::display::display_spreadsheet_interactive;
-use crate::functions::functions::normalize_for_display;
+use crate::datasets::{path_to_uri, read_dataset};
+use crate::display::display::{
+    DEFAULT_VISIBLE_COLS, collect_feature_cols, display_spreadsheet_window, render_source_to_buffer,
+};
+use crate::display::follow::Follow;
+use crate::display::row_stats::RowStats;
+use crate::display::screenshot::{ScreenFormat, write_screen};
+use crate::display::{RenderOptions, SourceWindow, run_viewer};
+use crate::functions::batch_source::BatchSource;
+use crate::functions::deletions::{deleted_rows, scan_with_deleted};
+use crate::functions::diff::{AddedRows, added_rows, keep_added, open_version};
+use crate::functions::export::Provenance;
+use crate::functions::follow::spawn_follow;
+use crate::functions::format::format_bytes;
+use crate::functions::functions::{
+    DenseMatrix, LanceLayout, detect_lance_layout, normalize_for_display,
+};
+use crate::functions::names::apply_names;
+use crate::functions::storage::estimate_value_width;
+use crate::session::SessionStore;
 
-pub async fn cmd_display(filepath: &PathBuf) -> Result<()> {
-    info!("cmd_display: opening full dataset at {:?}", filepath);
+/// Default `--max-memory` budget for loading a whole dataset (2 GiB).
+pub const DEFAULT_MAX_MEMORY: u64 = 2 << 30;
+
+/// Approximate bytes needed to hold `rows` rows of `schema` in one batch.
+pub fn estimate_batch_bytes(schema: &ArrowSchema, rows: usize) -> u64 {
+    let row_width: u64 = schema
+        .fields()
+        .iter()
+        .map(|f| estimate_value_width(f.data_type()))
+        .sum();
+    row_width.saturating_mul(rows as u64)
+}
+
+/// Parse a `--max-memory` value: a byte count with an optional `K`/`M`/`G`/`T`
+/// suffix (binary multiples, `iB`/`B` optional, case-insensitive).
+pub fn parse_byte_size(s: &str) -> std::result::Result<u64, String> {
+    let lower = s.trim().to_ascii_lowercase();
+    let digits_end = lower
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(lower.len());
+    let (number, unit) = lower.split_at(digits_end);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid size {s:?}, expected e.g. 512MiB or 2G"))?;
+    let shift = match unit.trim() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 10,
+        "m" | "mb" | "mib" => 20,
+        "g" | "gb" | "gib" => 30,
+        "t" | "tb" | "tib" => 40,
+        other => return Err(format!("unknown size unit {other:?} in {s:?}")),
+    };
+    Ok((number * (1u64 << shift) as f64) as u64)
+}
+
+/// A half-open `A..B` range given on the command line; either end may be
+/// omitted (`A..`, `..B`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SliceRange {
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+}
+
+impl SliceRange {
+    /// This range clamped to `0..len`, and whether clamping changed an
+    /// explicitly given bound.
+    pub fn clamp(&self, len: usize) -> (Range<usize>, bool) {
+        let start = self.start.unwrap_or(0);
+        let end = self.end.unwrap_or(len);
+        let clamped_end = end.min(len);
+        let clamped_start = start.min(clamped_end);
+        (
+            clamped_start..clamped_end,
+            clamped_start != start || clamped_end != end,
+        )
+    }
+}
+
+impl fmt::Display for SliceRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(start) = self.start {
+            write!(f, "{start}")?;
+        }
+        write!(f, "..")?;
+        if let Some(end) = self.end {
+            write!(f, "{end}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse `A..B`, `A..` or `..B` (zero-based, end exclusive).
+pub fn parse_slice_range(s: &str) -> std::result::Result<SliceRange, String> {
+    let (start, end) = s
+        .split_once("..")
+        .ok_or_else(|| format!("invalid range {s:?}, expected A..B"))?;
+    let bound = |b: &str| -> std::result::Result<Option<usize>, String> {
+        let b = b.trim();
+        if b.is_empty() {
+            return Ok(None);
+        }
+        b.parse()
+            .map(Some)
+            .map_err(|_| format!("invalid range bound {b:?} in {s:?}"))
+    };
+    Ok(SliceRange {
+        start: bound(start)?,
+        end: bound(end)?,
+    })
+}
+
+/// Options for [`cmd_display`].
+#[derive(Debug, Clone)]
+pub struct DisplayOptions {
+    /// Refuse datasets (or row windows) estimated above this many bytes
+    pub max_memory: u64,
+    /// Only load these source rows
+    pub rows: Option<SliceRange>,
+    /// Read at most this many rows (of `rows`, when given)
+    pub limit: Option<usize>,
+    /// Rows per batch the scanner reads; Lance's default when `None`
+    pub batch_size: Option<usize>,
+    /// Only show these feature columns (indices into the `col_*` features)
+    pub cols: Option<SliceRange>,
+    /// Display names of the feature columns, in source feature order
+    pub names: Option<Vec<String>>,
+    /// Feature columns shown at once instead of the session's or the default
+    pub visible_cols: Option<usize>,
+    /// Also read the rows deleted in this version (see
+    /// [`scan_with_deleted`])
+    pub include_deleted: bool,
+    /// Mark the rows that were not in this earlier version
+    pub since_version: Option<u64>,
+    /// Only show the rows added since `since_version`
+    pub only_new: bool,
+    /// Keep the vector column of a dense dataset whole instead of expanding
+    /// it to `col_*` features
+    pub no_expand: bool,
+    /// Poll the dataset this often for versions that append rows and keep
+    /// the view on its last page (`--follow`)
+    pub follow: Option<Duration>,
+    /// Write the first frame here instead of opening the viewer
+    pub screenshot: Option<Screenshot>,
+}
+
+/// A headless `display --screenshot`: the first frame at `width`×`height`,
+/// written as ANSI-coloured text to a `.ansi` path and as plain text
+/// otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screenshot {
+    pub path: PathBuf,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Parse a `--screenshot-size` value `WxH`, e.g. `120x40`.
+pub fn parse_screen_size(s: &str) -> std::result::Result<(u16, u16), String> {
+    let (w, h) = s
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("invalid size {s:?}, expected WIDTHxHEIGHT"))?;
+    let dim = |d: &str| -> std::result::Result<u16, String> {
+        match d.trim().parse() {
+            Ok(0) | Err(_) => Err(format!("invalid size {s:?}, expected e.g. 100x30")),
+            Ok(n) => Ok(n),
+        }
+    };
+    Ok((dim(w)?, dim(h)?))
+}
+
+impl DisplayOptions {
+    /// The row / column selection, e.g. `rows 0..100, limit 50`; empty for
+    /// the whole dataset.
+    pub fn filter(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(rows) = &self.rows {
+            parts.push(format!("rows {rows}"));
+        }
+        if let Some(limit) = self.limit {
+            parts.push(format!("limit {limit}"));
+        }
+        if let Some(cols) = &self.cols {
+            parts.push(format!("cols {cols}"));
+        }
+        if let (true, Some(version)) = (self.only_new, self.since_version) {
+            parts.push(format!("new since version {version}"));
+        }
+        parts.join(", ")
+    }
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            max_memory: DEFAULT_MAX_MEMORY,
+            rows: None,
+            limit: None,
+            batch_size: None,
+            cols: None,
+            names: None,
+            visible_cols: None,
+            include_deleted: false,
+            since_version: None,
+            only_new: false,
+            no_expand: false,
+            follow: None,
+            screenshot: None,
+        }
+    }
+}
+
+/// Open the dataset (or the `--rows`/`--cols` block of it) in the interactive
+/// viewer, refusing up front when the estimated batch size exceeds the
+/// memory budget.
+pub async fn cmd_display(filepath: &Path, opts: &DisplayOptions) -> Result<()> {
+    info!("cmd_display: opening dataset at {:?}", filepath);
 
     let uri = path_to_uri(filepath);
     debug!("cmd_display: Lance URI = {}", uri);
 
-    let dataset = Dataset::open(&uri).await?;
-    // Load the entire dataset into a single RecordBatch.
-    // For large datasets you may want to stream or limit rows instead.
-    let scanner = dataset.scan();
-    let batch: RecordBatch = scanner
-        .try_into_batch()
-        .await
-        .map_err(|e| anyhow!("cmd_display: failed to read full batch: {e}"))?;
-
-    let num_rows = batch.num_rows();
-    let num_cols = batch.num_columns();
+    let (window, version) = read_dataset(filepath, |dataset| async move {
+        let window = load_display_window(&dataset, filepath, opts).await?;
+        Ok((window, dataset.version().version))
+    })
+    .await?;
+    let Some((data, source, dense, added)) = window else {
+        return Ok(());
+    };
+
+    if let Some(shot) = &opts.screenshot {
+        let frame = render_source_to_buffer(
+            &data,
+            &RenderOptions {
+                width: shot.width,
+                height: shot.height,
+                source: Some(source),
+                visible_cols: opts.visible_cols.unwrap_or(DEFAULT_VISIBLE_COLS),
+                row_stats: RowStats::configured_columns(),
+                added,
+                ..RenderOptions::default()
+            },
+        );
+        write_screen(&frame, ScreenFormat::for_path(&shot.path), &shot.path)?;
+        println!("Screen written to {}", shot.path.display());
+        return Ok(());
+    }
+
+    // Reuse the interactive viewer.
+    let session = SessionStore::user(filepath);
+    let provenance = Provenance::new(filepath, "display").with_filter(opts.filter());
+    let visible_cols = opts.visible_cols;
+    let (follow, poller) = match opts.follow {
+        Some(interval) => {
+            let (updates, poller) = spawn_follow(
+                filepath,
+                opts,
+                data.schema(),
+                source.total_rows,
+                version,
+                interval,
+            );
+            (Some(Follow::new(updates, version)), Some(poller))
+        }
+        None => (None, None),
+    };
+    let result = run_viewer(move || {
+        display_spreadsheet_window(
+            &data,
+            source,
+            dense,
+            None,
+            added.as_ref(),
+            visible_cols,
+            &session,
+            &provenance,
+            follow,
+        )
+    })
+    .await;
+    if let Some(poller) = poller {
+        poller.abort();
+    }
+    result
+}
+
+/// Read and normalize the batches `cmd_display` shows, sliced to the
+/// requested block and capped at `--limit` rows, plus the packed matrix
+/// behind each dense batch. The batches are kept as the scanner streams them
+/// rather than concatenated. Out-of-range bounds are clamped with a warning
+/// on stderr; a limit that cuts the read short is logged and recorded in the
+/// returned window for the viewer's banner. With `--since-version` the rows
+/// added since that version come back too, and `--only-new` keeps only
+/// them. Returns `None` (after printing why) when there is nothing to show.
+pub(crate) async fn load_display_window(
+    dataset: &Dataset,
+    filepath: &Path,
+    opts: &DisplayOptions,
+) -> Result<
+    Option<(
+        BatchSource,
+        SourceWindow,
+        Vec<DenseMatrix>,
+        Option<AddedRows>,
+    )>,
+> {
+    let added = match opts.since_version {
+        Some(version) => {
+            let base = open_version(dataset, version).await?;
+            let added = added_rows(dataset, &base).await?;
+            info!("cmd_display: {}", added.note());
+            Some(added)
+        }
+        None => None,
+    };
+    let total_rows = dataset.count_rows(None).await?;
+    let mut row_range = match opts.rows {
+        Some(range) => {
+            let (clamped, changed) = range.clamp(total_rows);
+            if changed {
+                eprintln!(
+                    "warning: --rows {range} clamped to {}..{} (dataset has {total_rows} rows)",
+                    clamped.start, clamped.end
+                );
+            }
+            clamped
+        }
+        None => 0..total_rows,
+    };
+    let requested = row_range.len();
+    let limited = opts.limit.filter(|&limit| limit < requested);
+    if let Some(limit) = limited {
+        warn!("cmd_display: --limit {limit} shows the first {limit} of {requested} rows");
+        row_range.end = row_range.start + limit;
+    }
+
+    let estimate = estimate_batch_bytes(&ArrowSchema::from(dataset.schema()), row_range.len());
+    let fits = estimate <= opts.max_memory;
     info!(
-        "cmd_display: loaded full batch with {} rows × {} cols",
-        num_rows, num_cols
+        "cmd_display: estimated {} for {} rows (budget {}): {}",
+        format_bytes(estimate),
+        row_range.len(),
+        format_bytes(opts.max_memory),
+        if fits { "loading" } else { "refusing" }
     );
+    if !fits {
+        return Err(anyhow!(
+            "{:?} would need about {} in memory, over the --max-memory budget of {}; \
+             view part of it with `head <N>`, `sample <N>`, `display --limit N` or \
+             `display --rows A..B`, or raise --max-memory",
+            filepath,
+            format_bytes(estimate),
+            format_bytes(opts.max_memory)
+        ));
+    }
 
-    if num_cols == 0 {
-        println!("No columns to display");
-        return Err(anyhow!("cmd_display: abort, no columns in dataset"));
+    let raw = if opts.include_deleted {
+        scan_with_deleted(dataset, row_range.clone(), opts.batch_size).await
+    } else {
+        BatchSource::scan(dataset, row_range.clone(), opts.batch_size).await
     }
+    .map_err(|e| anyhow!("cmd_display: failed to read rows {row_range:?}: {e}"))?;
+    let only_new = added.as_ref().filter(|_| opts.only_new);
+    let raw = match only_new {
+        Some(added) => keep_added(&raw, added)?,
+        None => raw,
+    };
 
-    if num_rows == 0 {
-        println!("Dataset is empty");
-        return Ok(());
+    let num_rows = raw.num_rows();
+    let num_cols = raw.num_columns();
+    info!(
+        "cmd_display: loaded {} batches with {} rows × {} cols",
+        raw.batches().len(),
+        num_rows,
+        num_cols
+    );
+
+    // An empty dataset opens the viewer's placeholder; an empty slice of a
+    // non-empty one has nothing to show
+    if num_rows == 0 && total_rows > 0 {
+        match only_new {
+            Some(added) => println!("No rows added since version {}", added.base),
+            None => println!("No rows in the requested range"),
+        }
+        return Ok(None);
     }
 
-    let batch = normalize_for_display(&batch)?;
-    // Reuse the interactive viewer.
-    display_spreadsheet_interactive(&batch)?;
-    Ok(())
+    let mut data = display_rows(&raw, opts.no_expand)?;
+    let mut feature_range = 0..usize::MAX;
+    let mut source = SourceWindow {
+        row_offset: row_range.start,
+        total_rows,
+        limited: limited.map(|limit| (limit, requested)),
+        deleted_rows: deleted_rows(dataset).await?,
+        ..SourceWindow::whole(total_rows)
+    };
+    if let Some(added) = only_new {
+        // the kept rows carry their source ids; number the window by them
+        source.row_offset = 0;
+        source.total_rows = added.count() as usize;
+    }
+
+    if let Some(range) = opts.cols {
+        let features = collect_feature_cols(&data.empty_batch())?;
+        let (clamped, changed) = range.clamp(features.len());
+        if changed {
+            eprintln!(
+                "warning: --cols {range} clamped to {}..{} ({} feature columns)",
+                clamped.start,
+                clamped.end,
+                features.len()
+            );
+        }
+        if clamped.is_empty() {
+            println!("No feature columns in the requested range");
+            return Ok(None);
+        }
+        let keep = kept_columns(data.num_columns(), &features, clamped.clone());
+        data = data.try_map(|batch, _| Ok(batch.project(&keep)?))?;
+        source.col_offset = clamped.start;
+        source.total_cols = Some(features.len());
+        feature_range = clamped;
+    }
+
+    if let Some(names) = &opts.names {
+        // Names only change the schema, so they are applied once to it
+        let schema = data.empty_batch();
+        let total = source
+            .total_cols
+            .unwrap_or(collect_feature_cols(&schema)?.len());
+        let named = apply_names(&schema, names, source.col_offset, total)?;
+        data = data.with_schema(named.schema())?;
+    }
+
+    let dense = DenseMatrix::from_source(&raw, feature_range);
+    Ok(Some((data, source, dense, added)))
+}
+
+/// `raw` as the viewer shows it: normalized for display, with a dense
+/// vector column kept whole under `no_expand`.
+pub(crate) fn display_rows(raw: &BatchSource, no_expand: bool) -> Result<BatchSource> {
+    raw.try_map(|batch, _| match detect_lance_layout(batch) {
+        LanceLayout::DenseRowMajor if no_expand => Ok(batch.clone()),
+        _ => normalize_for_display(batch),
+    })
+}
+
+/// The columns of `num_cols` kept for `--cols`: the `selected` range of
+/// the feature columns `features` plus every non-feature column (metadata,
+/// row ids), in schema order.
+pub(crate) fn kept_columns(
+    num_cols: usize,
+    features: &[usize],
+    selected: Range<usize>,
+) -> Vec<usize> {
+    (0..num_cols)
+        .filter(|i| !features.contains(i) || features[selected.clone()].contains(i))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functions::storage::{LIST_LENGTH_GUESS, VARIABLE_WIDTH_GUESS};
+    use arrow::datatypes::{DataType, Field};
+    use std::sync::Arc;
+
+    fn schema(fields: Vec<Field>) -> ArrowSchema {
+        ArrowSchema::new(fields)
+    }
+
+    #[test]
+    fn estimates_cover_each_layout() {
+        let item = Arc::new(Field::new("item", DataType::Float64, true));
+
+        // Dense row-major: one FixedSizeList<Float64, 300> per row.
+        let dense = schema(vec![Field::new(
+            "vector",
+            DataType::FixedSizeList(item.clone(), 300),
+            false,
+        )]);
+        assert_eq!(estimate_batch_bytes(&dense, 1_000), 300 * 8 * 1_000);
+
+        // Sparse COO triplets.
+        let coo = schema(vec![
+            Field::new("row", DataType::UInt32, false),
+            Field::new("col", DataType::UInt32, false),
+            Field::new("value", DataType::Float64, false),
+        ]);
+        assert_eq!(estimate_batch_bytes(&coo, 10), 16 * 10);
+
+        // 1D vector and a row-index column.
+        let vector = schema(vec![
+            Field::new("row_idx", DataType::UInt64, false),
+            Field::new("norm", DataType::Float32, false),
+        ]);
+        assert_eq!(estimate_batch_bytes(&vector, 4), 12 * 4);
+
+        // Variable-width columns use the fixed guesses.
+        let other = schema(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("tags", DataType::List(item), true),
+        ]);
+        assert_eq!(
+            estimate_batch_bytes(&other, 1),
+            VARIABLE_WIDTH_GUESS + 4 + LIST_LENGTH_GUESS * 8 + 4
+        );
+
+        assert_eq!(estimate_batch_bytes(&dense, 0), 0);
+    }
+
+    #[test]
+    fn byte_sizes_parse_with_binary_units() {
+        assert_eq!(parse_byte_size("1024"), Ok(1024));
+        assert_eq!(parse_byte_size("2GiB"), Ok(DEFAULT_MAX_MEMORY));
+        assert_eq!(parse_byte_size("512m"), Ok(512 << 20));
+        assert_eq!(parse_byte_size("1.5K"), Ok(1536));
+        assert!(parse_byte_size("lots").is_err());
+        assert!(parse_byte_size("3 parsecs").is_err());
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(12), "12 B");
+    }
+
+    #[test]
+    fn slice_ranges_parse_and_clamp() {
+        let r = parse_slice_range("10..20").unwrap();
+        assert_eq!((r.start, r.end), (Some(10), Some(20)));
+        assert_eq!(r.to_string(), "10..20");
+        assert_eq!(r.clamp(100), (10..20, false));
+        assert_eq!(r.clamp(15), (10..15, true));
+        assert_eq!(r.clamp(5), (5..5, true));
+
+        let open = parse_slice_range("..3").unwrap();
+        assert_eq!(open.clamp(10), (0..3, false));
+        assert_eq!(parse_slice_range("4..").unwrap().clamp(10), (4..10, false));
+        assert_eq!(parse_slice_range("..").unwrap().clamp(7), (0..7, false));
+
+        assert!(parse_slice_range("10").is_err());
+        assert!(parse_slice_range("a..b").is_err());
+    }
 }
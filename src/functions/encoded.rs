@@ -0,0 +1,177 @@
+//! Dictionary and run-end encoded columns, as some writers store categorical
+//! labels. Values are looked up through the keys or runs row by row, and
+//! counted per distinct value, without decoding the whole column.
+
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::datatypes::{
+    ArrowNativeType, DataType, Int8Type, Int16Type, Int32Type, Int64Type, RunEndIndexType,
+    UInt8Type, UInt16Type, UInt32Type, UInt64Type,
+};
+
+/// The type of the values behind a Dictionary or RunEndEncoded type.
+pub(crate) fn encoded_value_type(data_type: &DataType) -> Option<&DataType> {
+    match data_type {
+        DataType::Dictionary(_, values) => Some(values),
+        DataType::RunEndEncoded(_, values) => Some(values.data_type()),
+        _ => None,
+    }
+}
+
+/// The values array of an encoded column and the index in it of row `row`;
+/// `None` for a null key or a column that is not encoded.
+pub(crate) fn resolve(array: &ArrayRef, row: usize) -> Option<(ArrayRef, usize)> {
+    match array.data_type() {
+        DataType::Dictionary(key, _) => {
+            let (values, index) = match key.as_ref() {
+                DataType::Int8 => dictionary_key::<Int8Type>(array, row),
+                DataType::Int16 => dictionary_key::<Int16Type>(array, row),
+                DataType::Int32 => dictionary_key::<Int32Type>(array, row),
+                DataType::Int64 => dictionary_key::<Int64Type>(array, row),
+                DataType::UInt8 => dictionary_key::<UInt8Type>(array, row),
+                DataType::UInt16 => dictionary_key::<UInt16Type>(array, row),
+                DataType::UInt32 => dictionary_key::<UInt32Type>(array, row),
+                DataType::UInt64 => dictionary_key::<UInt64Type>(array, row),
+                _ => return None,
+            };
+            Some((values, index?))
+        }
+        DataType::RunEndEncoded(ends, _) => match ends.data_type() {
+            DataType::Int16 => Some(run_value::<Int16Type>(array, row)),
+            DataType::Int32 => Some(run_value::<Int32Type>(array, row)),
+            DataType::Int64 => Some(run_value::<Int64Type>(array, row)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn dictionary_key<K: arrow::datatypes::ArrowDictionaryKeyType>(
+    array: &ArrayRef,
+    row: usize,
+) -> (ArrayRef, Option<usize>) {
+    let dict = array.as_dictionary::<K>();
+    (dict.values().clone(), dict.key(row))
+}
+
+fn run_value<R: RunEndIndexType>(array: &ArrayRef, row: usize) -> (ArrayRef, usize) {
+    let run = array.as_run::<R>();
+    (run.values().clone(), run.get_physical_index(row))
+}
+
+/// Rows per value of an encoded column, from its keys or run lengths.
+#[derive(Debug, Clone)]
+pub(crate) struct EncodedCounts {
+    pub values: ArrayRef,
+    /// Rows of each entry of `values`
+    pub counts: Vec<u64>,
+    /// Rows with a null key
+    pub null_keys: u64,
+}
+
+/// [`EncodedCounts`] of `array`; `None` when it is not encoded.
+pub(crate) fn encoded_counts(array: &ArrayRef) -> Option<EncodedCounts> {
+    match array.data_type() {
+        DataType::Dictionary(key, _) => match key.as_ref() {
+            DataType::Int8 => Some(dictionary_counts::<Int8Type>(array)),
+            DataType::Int16 => Some(dictionary_counts::<Int16Type>(array)),
+            DataType::Int32 => Some(dictionary_counts::<Int32Type>(array)),
+            DataType::Int64 => Some(dictionary_counts::<Int64Type>(array)),
+            DataType::UInt8 => Some(dictionary_counts::<UInt8Type>(array)),
+            DataType::UInt16 => Some(dictionary_counts::<UInt16Type>(array)),
+            DataType::UInt32 => Some(dictionary_counts::<UInt32Type>(array)),
+            DataType::UInt64 => Some(dictionary_counts::<UInt64Type>(array)),
+            _ => None,
+        },
+        DataType::RunEndEncoded(ends, _) => match ends.data_type() {
+            DataType::Int16 => Some(run_counts::<Int16Type>(array)),
+            DataType::Int32 => Some(run_counts::<Int32Type>(array)),
+            DataType::Int64 => Some(run_counts::<Int64Type>(array)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn dictionary_counts<K: arrow::datatypes::ArrowDictionaryKeyType>(
+    array: &ArrayRef,
+) -> EncodedCounts {
+    let dict = array.as_dictionary::<K>();
+    let mut counts = vec![0; dict.values().len()];
+    let mut null_keys = 0;
+    for row in 0..dict.len() {
+        match dict.key(row) {
+            Some(key) => counts[key] += 1,
+            None => null_keys += 1,
+        }
+    }
+    EncodedCounts {
+        values: dict.values().clone(),
+        counts,
+        null_keys,
+    }
+}
+
+/// Each run's length, clipped to the slice `array` covers.
+fn run_counts<R: RunEndIndexType>(array: &ArrayRef) -> EncodedCounts {
+    let run = array.as_run::<R>();
+    let ends = run.run_ends();
+    let (first, last) = (ends.offset(), ends.offset() + ends.len());
+    let mut counts = vec![0; run.values().len()];
+    let mut start = 0;
+    for (i, end) in ends.values().iter().enumerate() {
+        let end = end.as_usize();
+        let rows = end.min(last).saturating_sub(start.max(first));
+        counts[i] += rows as u64;
+        if end >= last {
+            break;
+        }
+        start = end;
+    }
+    EncodedCounts {
+        values: run.values().clone(),
+        counts,
+        null_keys: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{DictionaryArray, Int32Array, RunArray, StringArray};
+    use std::sync::Arc;
+
+    #[test]
+    fn dictionary_keys_resolve_to_their_values() {
+        let dict: DictionaryArray<Int32Type> = DictionaryArray::new(
+            Int32Array::from(vec![Some(1), None, Some(0), Some(1)]),
+            Arc::new(StringArray::from(vec!["b-cell", "t-cell"])),
+        );
+        let array: ArrayRef = Arc::new(dict);
+        let (values, index) = resolve(&array, 3).unwrap();
+        assert_eq!(values.as_string::<i32>().value(index), "t-cell");
+        assert!(resolve(&array, 1).is_none());
+
+        let counts = encoded_counts(&array).unwrap();
+        assert_eq!(counts.counts, [1, 2]);
+        assert_eq!(counts.null_keys, 1);
+    }
+
+    #[test]
+    fn runs_expand_on_access_and_count_within_a_slice() {
+        let run = RunArray::<Int32Type>::try_new(
+            &Int32Array::from(vec![2, 5, 6]),
+            &StringArray::from(vec!["a", "b", "c"]),
+        )
+        .unwrap();
+        let array: ArrayRef = Arc::new(run);
+        let (values, index) = resolve(&array, 4).unwrap();
+        assert_eq!(values.as_string::<i32>().value(index), "b");
+        assert_eq!(encoded_counts(&array).unwrap().counts, [2, 3, 1]);
+
+        // rows 1..4: one "a", two "b"
+        let slice = array.slice(1, 3);
+        let (values, index) = resolve(&slice, 0).unwrap();
+        assert_eq!(values.as_string::<i32>().value(index), "a");
+        assert_eq!(encoded_counts(&slice).unwrap().counts, [1, 2, 0]);
+    }
+}
@@ -0,0 +1,583 @@
+//! Saving a subset of a dataset as a new Lance dataset (or a CSV / Parquet
+//! file): `sample --output`, or `W` in the viewer for the rows it has
+//! loaded. `--select` keeps some columns, slicing a vector column to the
+//! chosen `col_*` dimensions, and `--cast f32` narrows Float64 values,
+//! reporting the largest rounding error; integer columns are never cast.
+//!
+//! The schema metadata is kept and `javelin.*` keys record where the rows
+//! came from ([`Provenance`]). The viewer's synthetic `_rowid` column is not
+//! written (Lance reserves the name); the source and selection keys say how
+//! to get the rows again.
+
+use anyhow::{Context, Result};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow_array::cast::AsArray;
+use arrow_array::types::Float64Type;
+use arrow_array::{Array, FixedSizeListArray, RecordBatch, UInt64Array};
+use std::fmt;
+use std::fs::File;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::datasets::{path_to_uri, write_lance_batch};
+use crate::errors::ErrorCategory;
+use crate::functions::binary::hex_columns;
+use crate::functions::functions::{
+    LanceLayout, ROWID_COLUMN, detect_lance_layout, normalize_for_display,
+};
+
+/// Schema metadata key of the dataset the rows were read from.
+pub const SOURCE_KEY: &str = "javelin.source";
+/// Schema metadata key of the command that selected the rows.
+pub const COMMAND_KEY: &str = "javelin.command";
+/// Schema metadata key of the random seed of a sample.
+pub const SEED_KEY: &str = "javelin.seed";
+/// Schema metadata key of the row / column selection, e.g. `rows 0..100`.
+pub const FILTER_KEY: &str = "javelin.filter";
+
+/// Where exported rows came from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    /// Canonical path of the source dataset
+    pub source: String,
+    /// The selecting command, e.g. `sample 100`
+    pub command: String,
+    pub seed: Option<u64>,
+    pub filter: Option<String>,
+}
+
+impl Provenance {
+    pub fn new(source: &Path, command: impl Into<String>) -> Self {
+        let source = source
+            .canonicalize()
+            .unwrap_or_else(|_| source.to_path_buf());
+        Self {
+            source: source.to_string_lossy().into_owned(),
+            command: command.into(),
+            seed: None,
+            filter: None,
+        }
+    }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            ..self
+        }
+    }
+
+    /// With the selection `filter`; an empty one is left out.
+    pub fn with_filter(self, filter: impl Into<String>) -> Self {
+        let filter = filter.into();
+        Self {
+            filter: (!filter.is_empty()).then_some(filter),
+            ..self
+        }
+    }
+
+    /// The metadata entries, the optional ones only when set.
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = vec![
+            (SOURCE_KEY, self.source.clone()),
+            (COMMAND_KEY, self.command.clone()),
+        ];
+        if let Some(seed) = self.seed {
+            entries.push((SEED_KEY, seed.to_string()));
+        }
+        if let Some(filter) = &self.filter {
+            entries.push((FILTER_KEY, filter.clone()));
+        }
+        entries
+    }
+}
+
+/// File format of an export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// A Lance dataset directory
+    #[default]
+    Lance,
+    /// Comma-separated text with a header row; dense vectors are written as
+    /// `col_*` columns and the provenance is not kept
+    Csv,
+    Parquet,
+}
+
+/// Narrower type for `--cast`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CastTarget {
+    /// Float64 columns and vectors to Float32
+    F32,
+}
+
+/// One item of `--select`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnSelector {
+    /// A column by name; `col_N` also names dimension N of a vector column
+    Name(String),
+    /// `col_A..col_B`: features A through B, both included
+    Features(RangeInclusive<usize>),
+}
+
+impl fmt::Display for ColumnSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnSelector::Name(name) => write!(f, "{name}"),
+            ColumnSelector::Features(range) => {
+                write!(f, "col_{}..col_{}", range.start(), range.end())
+            }
+        }
+    }
+}
+
+/// Parse a `--select` list such as `col_0,col_5..col_9,label`.
+pub fn parse_select(s: &str) -> std::result::Result<Vec<ColumnSelector>, String> {
+    let feature = |name: &str| -> std::result::Result<usize, String> {
+        name.trim()
+            .strip_prefix("col_")
+            .and_then(|i| i.parse().ok())
+            .ok_or_else(|| format!("invalid feature {name:?} in a range, expected col_N"))
+    };
+    s.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| match item.split_once("..") {
+            Some((a, b)) => {
+                let (a, b) = (feature(a)?, feature(b)?);
+                if a > b {
+                    return Err(format!("empty feature range {item:?}"));
+                }
+                Ok(ColumnSelector::Features(a..=b))
+            }
+            None => Ok(ColumnSelector::Name(item.to_string())),
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .and_then(|selectors| {
+            if selectors.is_empty() {
+                Err("--select needs at least one column".to_string())
+            } else {
+                Ok(selectors)
+            }
+        })
+}
+
+/// The feature index `col_N` names, if it is one.
+fn feature_index(name: &str) -> Option<usize> {
+    name.strip_prefix("col_")?.parse().ok()
+}
+
+/// Where and how `sample --output` writes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// New dataset directory or file
+    pub out: PathBuf,
+    /// Replace `out` if it already exists
+    pub force: bool,
+    /// Write the rows as the viewer shows them (`col_*` columns for a dense
+    /// matrix) instead of in the source schema
+    pub normalized: bool,
+    pub format: ExportFormat,
+    /// Columns to keep, in order; all of them when empty
+    pub select: Vec<ColumnSelector>,
+    pub cast: Option<CastTarget>,
+}
+
+impl ExportOptions {
+    /// The column selection and cast, e.g. `select col_0,col_5..col_9, cast
+    /// f32`; empty when neither is set.
+    pub fn filter(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.select.is_empty() {
+            let select: Vec<String> = self.select.iter().map(ToString::to_string).collect();
+            parts.push(format!("select {}", select.join(",")));
+        }
+        if let Some(CastTarget::F32) = self.cast {
+            parts.push("cast f32".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+/// What an export wrote.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportSummary {
+    pub rows: usize,
+    pub columns: usize,
+    /// Largest `|x − cast(x)|` over the finite values cast, when any were
+    pub max_cast_error: Option<f64>,
+}
+
+/// Keep the `select`ed columns of `batch`, in order. A vector column is
+/// sliced to the selected `col_*` dimensions (it is kept whole when named
+/// itself); other columns are picked by name.
+pub(crate) fn select_columns(
+    batch: &RecordBatch,
+    select: &[ColumnSelector],
+) -> Result<RecordBatch> {
+    if select.is_empty() {
+        return Ok(batch.clone());
+    }
+    let schema = batch.schema();
+    if detect_lance_layout(batch) == LanceLayout::DenseRowMajor {
+        let idx = schema
+            .fields()
+            .iter()
+            .position(|f| f.name() != ROWID_COLUMN)
+            .expect("a dense batch has its vector column");
+        let name = schema.field(idx).name();
+        let list = batch.column(idx).as_fixed_size_list();
+        let width = list.value_length() as usize;
+        let mut dims = Vec::new();
+        for selector in select {
+            match selector {
+                ColumnSelector::Name(n) if n == name => dims.extend(0..width),
+                ColumnSelector::Name(n) => dims.push(feature_index(n).with_context(|| {
+                    format!(
+                        "no column {n:?}; the vector has col_0..col_{}",
+                        width.saturating_sub(1)
+                    )
+                })?),
+                ColumnSelector::Features(range) => dims.extend(range.clone()),
+            }
+        }
+        dedup_in_order(&mut dims);
+        if let Some(&bad) = dims.iter().find(|&&d| d >= width) {
+            return Err(ErrorCategory::InvalidArgs.error(format!(
+                "col_{bad} is out of range; the vector has {width} features"
+            )));
+        }
+        let take: Vec<u64> = (0..list.len())
+            .flat_map(|r| {
+                let start = list.value_offset(r) as usize;
+                dims.iter().map(move |&d| (start + d) as u64)
+            })
+            .collect();
+        let values = arrow::compute::take(list.values(), &UInt64Array::from(take), None)?;
+        let item = match list.data_type() {
+            DataType::FixedSizeList(item, _) => item.clone(),
+            _ => unreachable!("checked by the layout"),
+        };
+        let sliced = FixedSizeListArray::try_new(
+            item.clone(),
+            dims.len() as i32,
+            values,
+            list.nulls().cloned(),
+        )?;
+        let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+        fields[idx] = fields[idx]
+            .clone()
+            .with_data_type(DataType::FixedSizeList(item, dims.len() as i32));
+        let mut columns = batch.columns().to_vec();
+        columns[idx] = Arc::new(sliced);
+        let schema = Schema::new(fields).with_metadata(schema.metadata().clone());
+        return Ok(RecordBatch::try_new(Arc::new(schema), columns)?);
+    }
+
+    let mut names = Vec::new();
+    for selector in select {
+        match selector {
+            ColumnSelector::Name(n) => names.push(n.clone()),
+            ColumnSelector::Features(range) => {
+                names.extend(range.clone().map(|i| format!("col_{i}")))
+            }
+        }
+    }
+    dedup_in_order(&mut names);
+    let mut keep = names
+        .iter()
+        .map(|n| {
+            schema
+                .index_of(n)
+                .with_context(|| format!("no column {n:?}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    // The row ids go along with whatever is selected.
+    keep.extend(schema.index_of(ROWID_COLUMN).ok());
+    Ok(batch.project(&keep)?)
+}
+
+fn dedup_in_order<T: PartialEq + Clone>(items: &mut Vec<T>) {
+    let mut seen = Vec::with_capacity(items.len());
+    items.retain(|item| {
+        let new = !seen.contains(item);
+        if new {
+            seen.push(item.clone());
+        }
+        new
+    });
+}
+
+/// `batch` with its Float64 columns and `FixedSizeList<Float64>` vectors
+/// cast to `target`, and the largest rounding error over the finite values
+/// cast (`None` when nothing was). Integer and other columns are left as
+/// they are.
+pub(crate) fn cast_floats(
+    batch: &RecordBatch,
+    target: CastTarget,
+) -> Result<(RecordBatch, Option<f64>)> {
+    let CastTarget::F32 = target;
+    let schema = batch.schema();
+    let mut max_error: Option<f64> = None;
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        let (values, to) = match field.data_type() {
+            DataType::Float64 => (column.clone(), DataType::Float32),
+            DataType::FixedSizeList(item, width) if item.data_type() == &DataType::Float64 => (
+                column.as_fixed_size_list().values().clone(),
+                DataType::FixedSizeList(
+                    Arc::new(item.as_ref().clone().with_data_type(DataType::Float32)),
+                    *width,
+                ),
+            ),
+            _ => {
+                fields.push(field.as_ref().clone());
+                columns.push(column.clone());
+                continue;
+            }
+        };
+        for (x, y) in values
+            .as_primitive::<Float64Type>()
+            .iter()
+            .flatten()
+            .filter(|x| x.is_finite())
+            .map(|x| (x, x as f32 as f64))
+        {
+            let error = (x - y).abs();
+            max_error = Some(max_error.map_or(error, |m| m.max(error)));
+        }
+        columns.push(arrow::compute::cast(column, &to)?);
+        fields.push(field.as_ref().clone().with_data_type(to));
+    }
+    let schema = Schema::new(fields).with_metadata(schema.metadata().clone());
+    Ok((RecordBatch::try_new(Arc::new(schema), columns)?, max_error))
+}
+
+/// `batch` as it is written: normalized for display when asked (always for
+/// CSV), the selected columns only, cast when asked, without the `_rowid`
+/// column, and with `provenance` added to the schema metadata. Also returns
+/// the largest rounding error of the cast.
+pub(crate) fn export_batch(
+    batch: &RecordBatch,
+    opts: &ExportOptions,
+    provenance: &Provenance,
+) -> Result<(RecordBatch, Option<f64>)> {
+    let batch = if opts.normalized || opts.format == ExportFormat::Csv {
+        normalize_for_display(batch)?
+    } else {
+        batch.clone()
+    };
+    let batch = select_columns(&batch, &opts.select)?;
+    let (batch, max_error) = match opts.cast {
+        Some(target) => cast_floats(&batch, target)?,
+        None => (batch, None),
+    };
+    let schema = batch.schema();
+    let keep: Vec<usize> = (0..batch.num_columns())
+        .filter(|&i| schema.field(i).name() != ROWID_COLUMN)
+        .collect();
+    let batch = batch.project(&keep)?;
+
+    let mut metadata = schema.metadata().clone();
+    for (key, value) in provenance.entries() {
+        metadata.insert(key.to_string(), value);
+    }
+    let schema = Schema::new(batch.schema().fields().clone()).with_metadata(metadata);
+    Ok((
+        RecordBatch::try_new(Arc::new(schema), batch.columns().to_vec())?,
+        max_error,
+    ))
+}
+
+/// Write `batch` (see [`export_batch`]) to `opts.out` in `opts.format`,
+/// refusing to replace an existing output unless `opts.force`.
+pub async fn write_export(
+    batch: &RecordBatch,
+    opts: &ExportOptions,
+    provenance: &Provenance,
+) -> Result<ExportSummary> {
+    let (batch, max_cast_error) = export_batch(batch, opts, provenance)?;
+    let out = &opts.out;
+    if out.exists() {
+        if !opts.force {
+            return Err(ErrorCategory::InvalidArgs.error(format!(
+                "output {:?} already exists; pass --force to overwrite it",
+                out
+            )));
+        }
+        if out.is_dir() {
+            std::fs::remove_dir_all(out)
+        } else {
+            std::fs::remove_file(out)
+        }
+        .with_context(|| format!("removing {:?}", out))?;
+    }
+    let summary = ExportSummary {
+        rows: batch.num_rows(),
+        columns: batch.num_columns(),
+        max_cast_error,
+    };
+    match opts.format {
+        ExportFormat::Lance => write_lance_batch(&path_to_uri(out), batch).await?,
+        ExportFormat::Csv => {
+            let file = File::create(out).with_context(|| format!("creating {:?}", out))?;
+            arrow::csv::Writer::new(file).write(&hex_columns(&batch)?)?;
+        }
+        ExportFormat::Parquet => {
+            let file = File::create(out).with_context(|| format!("creating {:?}", out))?;
+            let mut writer = parquet::arrow::ArrowWriter::try_new(file, batch.schema(), None)?;
+            writer.write(&batch)?;
+            writer.close()?;
+        }
+    }
+    Ok(summary)
+}
+
+/// `W` in the viewer: write the loaded rows, as shown, to a new
+/// `javelin-export-<UTC timestamp>.lance` in `dir`. Must run on a blocking
+/// thread of the runtime, as the viewer does.
+pub(crate) fn export_view(
+    batch: &RecordBatch,
+    provenance: &Provenance,
+    dir: &Path,
+) -> Result<PathBuf> {
+    let stamp = crate::functions::format::utc_timestamp(std::time::SystemTime::now());
+    let out = (1..)
+        .map(|n| match n {
+            1 => dir.join(format!("javelin-export-{stamp}.lance")),
+            n => dir.join(format!("javelin-export-{stamp}-{n}.lance")),
+        })
+        .find(|path| !path.exists())
+        .expect("some suffix is free");
+    let opts = ExportOptions {
+        out: out.clone(),
+        ..ExportOptions::default()
+    };
+    tokio::runtime::Handle::current().block_on(write_export(batch, &opts, provenance))?;
+    Ok(out)
+}
+
+/// Review mode in the viewer: write the flagged source row ids to `out` as
+/// a one-column (`row_id`) Lance dataset, replacing an earlier one. Must
+/// run on a blocking thread of the runtime, as [`export_view`].
+pub(crate) fn export_row_ids(ids: &[u64], provenance: &Provenance, out: &Path) -> Result<()> {
+    let batch = RecordBatch::try_from_iter([(
+        "row_id",
+        Arc::new(UInt64Array::from(ids.to_vec())) as Arc<dyn Array>,
+    )])?;
+    let opts = ExportOptions {
+        out: out.to_path_buf(),
+        force: true,
+        ..ExportOptions::default()
+    };
+    tokio::runtime::Handle::current().block_on(write_export(&batch, &opts, provenance))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::dense_rows_to_batch;
+    use crate::functions::functions::attach_row_ids;
+    use arrow_array::{Float64Array, Int64Array};
+    use std::collections::HashMap;
+
+    #[test]
+    fn export_drops_row_ids_and_records_provenance() {
+        let schema = Schema::new(vec![Field::new("x", DataType::Float64, false)])
+            .with_metadata(HashMap::from([("rows".to_string(), "3".to_string())]));
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Float64Array::from(vec![1.0, 2.0]))],
+        )
+        .unwrap();
+        let batch = attach_row_ids(&batch, vec![4, 9]).unwrap();
+
+        let provenance = Provenance::new(Path::new("/data/m.lance"), "sample 2")
+            .with_seed(7)
+            .with_filter("");
+        let (out, _) = export_batch(&batch, &ExportOptions::default(), &provenance).unwrap();
+        assert_eq!(out.num_columns(), 1);
+        assert_eq!(out.schema().field(0).name(), "x");
+        let md = out.schema().metadata().clone();
+        assert_eq!(md["rows"], "3");
+        assert_eq!(md[SOURCE_KEY], "/data/m.lance");
+        assert_eq!(md[COMMAND_KEY], "sample 2");
+        assert_eq!(md[SEED_KEY], "7");
+        assert!(!md.contains_key(FILTER_KEY));
+    }
+
+    #[test]
+    fn select_lists_parse_names_and_inclusive_ranges() {
+        assert_eq!(
+            parse_select("col_0, col_5..col_9,label").unwrap(),
+            [
+                ColumnSelector::Name("col_0".into()),
+                ColumnSelector::Features(5..=9),
+                ColumnSelector::Name("label".into()),
+            ]
+        );
+        assert!(parse_select("col_9..col_5").is_err());
+        assert!(parse_select("a..b").is_err());
+        assert!(parse_select(" , ").is_err());
+    }
+
+    #[test]
+    fn dense_vectors_are_sliced_by_dimension() {
+        let batch =
+            dense_rows_to_batch(&[vec![0.0, 1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0, 7.0]]).unwrap();
+        let select = parse_select("col_3,col_0..col_1").unwrap();
+        let sliced = select_columns(&batch, &select).unwrap();
+        let list = sliced.column(0).as_fixed_size_list();
+        assert_eq!(list.value_length(), 3);
+        let values = list.values().as_primitive::<Float64Type>();
+        assert_eq!(values.values(), &[3.0, 0.0, 1.0, 7.0, 4.0, 5.0]);
+
+        // The same selection of the expanded columns.
+        let wide = normalize_for_display(&batch).unwrap();
+        let picked = select_columns(&wide, &select).unwrap();
+        let names: Vec<&str> = picked
+            .schema_ref()
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect();
+        assert_eq!(names, ["col_3", "col_0", "col_1"]);
+
+        assert!(select_columns(&batch, &parse_select("col_4").unwrap()).is_err());
+        assert!(select_columns(&wide, &parse_select("label").unwrap()).is_err());
+    }
+
+    #[test]
+    fn casting_reports_the_largest_rounding_error_and_keeps_integers() {
+        let values = vec![0.1, 1.0 / 3.0, 1e10 + 0.123, f64::NAN, -2.5];
+        let schema = Schema::new(vec![
+            Field::new("x", DataType::Float64, true),
+            Field::new("n", DataType::Int64, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float64Array::from(values.clone())),
+                Arc::new(Int64Array::from(vec![i64::MAX, 1, 2, 3, 4])),
+            ],
+        )
+        .unwrap();
+
+        let (cast, error) = cast_floats(&batch, CastTarget::F32).unwrap();
+        let expected = values
+            .iter()
+            .filter(|v| v.is_finite())
+            .map(|&v| (v - v as f32 as f64).abs())
+            .fold(0.0, f64::max);
+        assert_eq!(error, Some(expected));
+        assert!(expected > 0.0);
+        assert_eq!(cast.schema().field(0).data_type(), &DataType::Float32);
+        assert_eq!(cast.column(1), batch.column(1));
+
+        // Nothing to cast: no error reported.
+        let ints = batch.project(&[1]).unwrap();
+        assert_eq!(cast_floats(&ints, CastTarget::F32).unwrap().1, None);
+    }
+}
@@ -0,0 +1,350 @@
+//! `fingerprint`: a deterministic hash of a dataset's schema and logical
+//! values, for asserting in CI that a generated dataset matches a golden one.
+//!
+//! The hash is computed over values, not files. Every top-level column feeds
+//! its own xxh3 stream row by row. Each value is written as a validity tag
+//! followed by a canonical encoding:
+//! - integers as little-endian bytes;
+//! - floats as `f64` bits, with `-0.0` folded into `0.0` and a single NaN;
+//! - strings and binaries as length-prefixed bytes;
+//! - lists and structs element by element.
+//!
+//! Batch and fragment boundaries therefore don't move the digest. The column
+//! digests are combined with a header covering the schema:
+//! - field names, types and nullability;
+//! - field and schema metadata, sorted by key, without the `javelin.*`
+//!   provenance keys exports add;
+//! - the `--round` setting.
+//!
+//! With `--round k` floats are rounded to `k` decimals before hashing, so
+//! datasets differing by less than that tolerance (away from rounding
+//! boundaries) share a fingerprint.
+
+use anyhow::{Result, bail};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    Float64Type, Int8Type, Int16Type, Int32Type, Int64Type, UInt8Type, UInt16Type, UInt32Type,
+    UInt64Type,
+};
+use arrow_array::{Array, RecordBatch};
+use futures::TryStreamExt;
+use lance::Dataset;
+use std::collections::HashMap;
+use std::path::Path;
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::datasets::open_dataset;
+use crate::errors::ErrorCategory;
+use crate::functions::progress::Progress;
+
+/// Prefix of the schema metadata keys left out of the hash.
+const PROVENANCE_PREFIX: &str = "javelin.";
+
+/// Streaming fingerprint of record batches sharing one schema.
+pub struct Fingerprinter {
+    header: Xxh3,
+    columns: Vec<Xxh3>,
+    decimals: Option<u32>,
+    rows: usize,
+}
+
+impl Fingerprinter {
+    pub fn new(schema: &Schema, decimals: Option<u32>) -> Self {
+        let mut header = Xxh3::new();
+        write_str(&mut header, "javelin-fingerprint-v1");
+        write_str(&mut header, &format!("round={decimals:?}"));
+        write_metadata(&mut header, schema.metadata());
+        header.update(&(schema.fields().len() as u64).to_le_bytes());
+        for field in schema.fields() {
+            write_field(&mut header, field);
+        }
+        Self {
+            header,
+            columns: vec![Xxh3::new(); schema.fields().len()],
+            decimals,
+            rows: 0,
+        }
+    }
+
+    /// Feed the next `batch` of rows.
+    pub fn update(&mut self, batch: &RecordBatch) -> Result<()> {
+        if batch.num_columns() != self.columns.len() {
+            bail!(
+                "batch has {} columns, the schema {}",
+                batch.num_columns(),
+                self.columns.len()
+            );
+        }
+        for (hasher, column) in self.columns.iter_mut().zip(batch.columns()) {
+            hash_array(hasher, column.as_ref(), self.decimals)?;
+        }
+        self.rows += batch.num_rows();
+        Ok(())
+    }
+
+    /// The fingerprint as 32 hex digits.
+    pub fn finish(mut self) -> String {
+        self.header.update(&(self.rows as u64).to_le_bytes());
+        for column in &self.columns {
+            self.header.update(&column.digest128().to_le_bytes());
+        }
+        format!("{:032x}", self.header.digest128())
+    }
+}
+
+/// Fingerprint of `dataset`, scanned in its stored order.
+pub async fn fingerprint_dataset(
+    dataset: &Dataset,
+    decimals: Option<u32>,
+    progress: bool,
+) -> Result<String> {
+    let schema = Schema::from(dataset.schema());
+    let mut fingerprinter = Fingerprinter::new(&schema, decimals);
+    let total = dataset.count_rows(None).await?;
+    let mut bar = Progress::new("rows", total, progress);
+    let mut stream = dataset.scan().try_into_stream().await?;
+    while let Some(batch) = stream.try_next().await? {
+        fingerprinter.update(&batch)?;
+        bar.inc(batch.num_rows());
+    }
+    bar.finish();
+    Ok(fingerprinter.finish())
+}
+
+/// Print the fingerprint of the dataset at `filepath`; with `verify`, fail
+/// unless it equals that hash.
+pub async fn cmd_fingerprint(
+    filepath: &Path,
+    decimals: Option<u32>,
+    verify: Option<&str>,
+) -> Result<()> {
+    let dataset = open_dataset(filepath).await?;
+    let hash = fingerprint_dataset(&dataset, decimals, true).await?;
+    println!("{hash}");
+    if let Some(expected) = verify {
+        let expected = expected.trim().to_ascii_lowercase();
+        if expected != hash {
+            return Err(ErrorCategory::CheckFailed.error(format!(
+                "fingerprint mismatch: expected {expected}, found {hash}"
+            )));
+        }
+        eprintln!("Fingerprint matches");
+    }
+    Ok(())
+}
+
+fn write_str(h: &mut Xxh3, s: &str) {
+    h.update(&(s.len() as u64).to_le_bytes());
+    h.update(s.as_bytes());
+}
+
+fn write_metadata(h: &mut Xxh3, metadata: &HashMap<String, String>) {
+    let mut entries: Vec<_> = metadata
+        .iter()
+        .filter(|(k, _)| !k.starts_with(PROVENANCE_PREFIX))
+        .collect();
+    entries.sort();
+    h.update(&(entries.len() as u64).to_le_bytes());
+    for (key, value) in entries {
+        write_str(h, key);
+        write_str(h, value);
+    }
+}
+
+fn write_field(h: &mut Xxh3, field: &Field) {
+    write_str(h, field.name());
+    write_str(h, &field.data_type().to_string());
+    h.update(&[field.is_nullable() as u8]);
+    write_metadata(h, field.metadata());
+}
+
+/// `x` rounded to `decimals`, with the sign of zero and the NaN payload
+/// dropped.
+fn canonical_float(x: f64, decimals: Option<u32>) -> f64 {
+    let x = match decimals {
+        Some(k) if x.is_finite() => {
+            let scale = 10f64.powi(k as i32);
+            let rounded = (x * scale).round() / scale;
+            if rounded.is_finite() { rounded } else { x }
+        }
+        _ => x,
+    };
+    if x.is_nan() {
+        f64::NAN
+    } else if x == 0.0 {
+        0.0
+    } else {
+        x
+    }
+}
+
+macro_rules! hash_primitive {
+    ($h:expr, $array:expr, $t:ty) => {{
+        let array = $array.as_primitive::<$t>();
+        for i in 0..array.len() {
+            if array.is_null(i) {
+                $h.update(&[0]);
+            } else {
+                $h.update(&[1]);
+                $h.update(&array.value(i).to_le_bytes());
+            }
+        }
+    }};
+}
+
+/// Feed the canonical encoding of each value of `array` to `h`.
+pub(crate) fn hash_array(h: &mut Xxh3, array: &dyn Array, decimals: Option<u32>) -> Result<()> {
+    let tag = |h: &mut Xxh3, i: usize| {
+        let valid = array.is_valid(i);
+        h.update(&[valid as u8]);
+        valid
+    };
+    match array.data_type() {
+        DataType::Null => h.update(&vec![0; array.len()]),
+        DataType::Boolean => {
+            let array = array.as_boolean();
+            for i in 0..array.len() {
+                if tag(h, i) {
+                    h.update(&[array.value(i) as u8]);
+                }
+            }
+        }
+        DataType::Int8 => hash_primitive!(h, array, Int8Type),
+        DataType::Int16 => hash_primitive!(h, array, Int16Type),
+        DataType::Int32 => hash_primitive!(h, array, Int32Type),
+        DataType::Int64 => hash_primitive!(h, array, Int64Type),
+        DataType::UInt8 => hash_primitive!(h, array, UInt8Type),
+        DataType::UInt16 => hash_primitive!(h, array, UInt16Type),
+        DataType::UInt32 => hash_primitive!(h, array, UInt32Type),
+        DataType::UInt64 => hash_primitive!(h, array, UInt64Type),
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => {
+            let values = cast(array, &DataType::Float64)?;
+            let values = values.as_primitive::<Float64Type>();
+            for i in 0..values.len() {
+                if tag(h, i) {
+                    h.update(&canonical_float(values.value(i), decimals).to_le_bytes());
+                }
+            }
+        }
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+            let values = cast(array, &DataType::LargeUtf8)?;
+            let values = values.as_string::<i64>();
+            for i in 0..values.len() {
+                if tag(h, i) {
+                    write_str(h, values.value(i));
+                }
+            }
+        }
+        DataType::Binary | DataType::LargeBinary | DataType::BinaryView => {
+            let values = cast(array, &DataType::LargeBinary)?;
+            let values = values.as_binary::<i64>();
+            for i in 0..values.len() {
+                if tag(h, i) {
+                    let bytes = values.value(i);
+                    h.update(&(bytes.len() as u64).to_le_bytes());
+                    h.update(bytes);
+                }
+            }
+        }
+        DataType::FixedSizeList(_, _) => {
+            let list = array.as_fixed_size_list();
+            for i in 0..list.len() {
+                if tag(h, i) {
+                    hash_array(h, list.value(i).as_ref(), decimals)?;
+                }
+            }
+        }
+        DataType::List(_) => {
+            let list = array.as_list::<i32>();
+            for i in 0..list.len() {
+                if tag(h, i) {
+                    let row = list.value(i);
+                    h.update(&(row.len() as u64).to_le_bytes());
+                    hash_array(h, row.as_ref(), decimals)?;
+                }
+            }
+        }
+        DataType::LargeList(_) => {
+            let list = array.as_list::<i64>();
+            for i in 0..list.len() {
+                if tag(h, i) {
+                    let row = list.value(i);
+                    h.update(&(row.len() as u64).to_le_bytes());
+                    hash_array(h, row.as_ref(), decimals)?;
+                }
+            }
+        }
+        DataType::Struct(_) => {
+            let array = array.as_struct();
+            for i in 0..array.len() {
+                if tag(h, i) {
+                    for child in array.columns() {
+                        hash_array(h, child.slice(i, 1).as_ref(), decimals)?;
+                    }
+                }
+            }
+        }
+        // Dates, decimals, dictionaries and the rest by their display form.
+        _ => {
+            let formatter = ArrayFormatter::try_new(array, &FormatOptions::default())?;
+            for i in 0..array.len() {
+                if tag(h, i) {
+                    write_str(h, &formatter.value(i).to_string());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::dense_rows_to_batch;
+
+    fn fingerprint(batches: &[&RecordBatch], decimals: Option<u32>) -> String {
+        let mut fingerprinter = Fingerprinter::new(&batches[0].schema(), decimals);
+        for batch in batches {
+            fingerprinter.update(batch).unwrap();
+        }
+        fingerprinter.finish()
+    }
+
+    #[test]
+    fn batch_boundaries_do_not_change_the_hash() {
+        let rows: Vec<Vec<f64>> = (0..10)
+            .map(|r| (0..3).map(|c| (r * 3 + c) as f64 * 0.5).collect())
+            .collect();
+        let whole = dense_rows_to_batch(&rows).unwrap();
+        let split = [whole.slice(0, 3), whole.slice(3, 6), whole.slice(9, 1)];
+        let split: Vec<&RecordBatch> = split.iter().collect();
+        assert_eq!(fingerprint(&[&whole], None), fingerprint(&split, None));
+
+        let mut changed = rows.clone();
+        changed[9][2] += 1e-9;
+        let changed = dense_rows_to_batch(&changed).unwrap();
+        assert_ne!(fingerprint(&[&whole], None), fingerprint(&[&changed], None));
+        assert_eq!(
+            fingerprint(&[&whole], Some(6)),
+            fingerprint(&[&changed], Some(6))
+        );
+        assert_ne!(
+            fingerprint(&[&whole], None),
+            fingerprint(&[&whole], Some(6))
+        );
+    }
+
+    #[test]
+    fn floats_are_canonicalized() {
+        assert_eq!(canonical_float(-0.0, None).to_bits(), 0.0f64.to_bits());
+        assert_eq!(
+            canonical_float(f64::from_bits(0x7ff8_0000_0000_0001), None).to_bits(),
+            f64::NAN.to_bits()
+        );
+        assert_eq!(canonical_float(1.23456, Some(2)), 1.23);
+        assert_eq!(canonical_float(f64::INFINITY, Some(2)), f64::INFINITY);
+    }
+}
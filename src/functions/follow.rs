@@ -0,0 +1,176 @@
+//! The background half of `display --follow`: poll the dataset for new
+//! versions and read the rows they append, shaped like the rows the viewer
+//! shows, for [`Follow`](crate::display::follow::Follow) to add. A version
+//! with fewer rows than the viewer holds (deletes, an overwrite) or with
+//! other columns stops following; rows rewritten in place without changing
+//! the count are not noticed.
+
+use anyhow::{Result, bail};
+use arrow::datatypes::SchemaRef;
+use log::{debug, info, warn};
+use std::path::Path;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::datasets::open_dataset;
+use crate::display::ROWID_COLUMN;
+use crate::display::display::collect_feature_cols;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::display::{DisplayOptions, display_rows, kept_columns};
+use crate::functions::functions::DenseMatrix;
+
+/// Rows a newer version appended after the ones the viewer holds.
+pub(crate) struct FollowedRows {
+    /// The appended rows, under the viewer's schema
+    pub rows: BatchSource,
+    /// The packed matrix behind each batch of `rows`; empty unless they
+    /// are all dense
+    pub dense: Vec<DenseMatrix>,
+    /// Rows in the new version
+    pub total_rows: usize,
+    pub version: u64,
+}
+
+/// What the poller found in a new version.
+pub(crate) enum FollowUpdate {
+    Appended(FollowedRows),
+    /// The version does not extend the rows shown; the poller stops
+    Stopped {
+        version: u64,
+        reason: String,
+    },
+}
+
+/// Poll `filepath` every `interval` from `version`, whose `seen` rows the
+/// viewer holds under `schema`, on a tokio task. The task ends when the
+/// receiver is dropped (after the next new version) or following stops;
+/// abort the handle to end it sooner.
+pub(crate) fn spawn_follow(
+    filepath: &Path,
+    opts: &DisplayOptions,
+    schema: SchemaRef,
+    seen: usize,
+    version: u64,
+    interval: Duration,
+) -> (Receiver<FollowUpdate>, JoinHandle<()>) {
+    let (updates, receiver) = channel();
+    let (filepath, opts) = (filepath.to_path_buf(), opts.clone());
+    let task = tokio::spawn(async move {
+        info!("follow: polling {filepath:?} every {interval:?} from version {version}");
+        poll_loop(&filepath, &opts, schema, seen, version, interval, updates).await;
+    });
+    (receiver, task)
+}
+
+async fn poll_loop(
+    filepath: &Path,
+    opts: &DisplayOptions,
+    schema: SchemaRef,
+    mut seen: usize,
+    mut version: u64,
+    interval: Duration,
+    updates: Sender<FollowUpdate>,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+        let update = match read_appended(filepath, opts, &schema, seen, version).await {
+            Ok(Some(update)) => update,
+            Ok(None) => continue,
+            // e.g. a commit in progress; the next poll tries again
+            Err(e) => {
+                warn!("follow: {e:#}");
+                continue;
+            }
+        };
+        let stop = match &update {
+            FollowUpdate::Appended(rows) => {
+                (seen, version) = (rows.total_rows, rows.version);
+                false
+            }
+            FollowUpdate::Stopped { version, reason } => {
+                info!("follow: stopped at version {version}: {reason}");
+                true
+            }
+        };
+        if updates.send(update).is_err() || stop {
+            return;
+        }
+    }
+}
+
+/// The rows the latest version of `filepath` holds past the `seen` ones of
+/// `version`, shaped under `schema`; `None` while `version` is the latest.
+pub(crate) async fn read_appended(
+    filepath: &Path,
+    opts: &DisplayOptions,
+    schema: &SchemaRef,
+    seen: usize,
+    version: u64,
+) -> Result<Option<FollowUpdate>> {
+    let dataset = open_dataset(filepath).await?;
+    let latest = dataset.version().version;
+    if latest == version {
+        return Ok(None);
+    }
+    let total_rows = dataset.count_rows(None).await?;
+    debug!("follow: version {latest} has {total_rows} rows, {seen} shown");
+    if total_rows < seen {
+        return Ok(Some(FollowUpdate::Stopped {
+            version: latest,
+            reason: format!("version {latest} has {total_rows} rows, fewer than the {seen} shown"),
+        }));
+    }
+    let raw = BatchSource::scan(&dataset, seen..total_rows, opts.batch_size).await?;
+    Ok(Some(match shape_appended(&raw, opts, schema) {
+        Ok((rows, dense)) => FollowUpdate::Appended(FollowedRows {
+            rows,
+            dense,
+            total_rows,
+            version: latest,
+        }),
+        Err(e) => FollowUpdate::Stopped {
+            version: latest,
+            reason: format!("version {latest} does not fit the rows shown: {e:#}"),
+        },
+    }))
+}
+
+/// `raw` normalized, cut to the `--cols` features and named like the rows
+/// under `schema`, with the packed matrix behind each dense batch. The
+/// `_rowid` column a scan past the first row adds is dropped, as the
+/// viewer's rows (read from the first) have none.
+fn shape_appended(
+    raw: &BatchSource,
+    opts: &DisplayOptions,
+    schema: &SchemaRef,
+) -> Result<(BatchSource, Vec<DenseMatrix>)> {
+    let data = display_rows(raw, opts.no_expand)?;
+    let (keep, feature_range) = match opts.cols {
+        Some(range) => {
+            let features = collect_feature_cols(&data.empty_batch())?;
+            let selected = range.clamp(features.len()).0;
+            (
+                kept_columns(data.num_columns(), &features, selected.clone()),
+                selected,
+            )
+        }
+        None => ((0..data.num_columns()).collect(), 0..usize::MAX),
+    };
+    let names = data.schema();
+    let keep: Vec<usize> = keep
+        .into_iter()
+        .filter(|&i| names.field(i).name() != ROWID_COLUMN)
+        .collect();
+    if keep.len() != schema.fields().len() {
+        bail!(
+            "{} columns where the view has {}",
+            keep.len(),
+            schema.fields().len()
+        );
+    }
+    let rows = data
+        .try_map(|batch, _| Ok(batch.project(&keep)?))?
+        .with_schema(schema.clone())?;
+    Ok((rows, DenseMatrix::from_source(raw, feature_range)))
+}
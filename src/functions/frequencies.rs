@@ -0,0 +1,355 @@
+//! Frequency tables for Boolean, string and integer columns, where mean/std
+//! say nothing useful. Counting stops at a distinct-value cap; past it only
+//! an estimate of the number of distinct values is kept.
+
+use anyhow::Result;
+use arrow::array::{Array, ArrayRef};
+use arrow::datatypes::DataType;
+use arrow::util::display::array_value_to_string;
+use futures::TryStreamExt;
+use lance::Dataset;
+use serde_json::{Value, json};
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::functions::encoded::{encoded_counts, encoded_value_type};
+use crate::functions::progress::Progress;
+
+/// Distinct values counted exactly before a column is reported as high
+/// cardinality.
+pub const DEFAULT_MAX_DISTINCT: usize = 1000;
+
+/// Values listed per column by `stats`.
+pub(crate) const TOP_VALUES: usize = 10;
+
+/// Smallest value hashes kept for the distinct-count estimate.
+const SKETCH_SIZE: usize = 256;
+
+/// Column types that get a frequency table: Boolean, strings and integers,
+/// also dictionary or run-end encoded.
+pub(crate) fn is_categorical_type(data_type: &DataType) -> bool {
+    if let Some(values) = encoded_value_type(data_type) {
+        return is_categorical_type(values);
+    }
+    matches!(
+        data_type,
+        DataType::Boolean | DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+    ) || data_type.is_integer()
+}
+
+/// Boolean and string columns; integers are only categorical when they turn
+/// out to have few distinct values.
+pub(crate) fn is_always_categorical(data_type: &DataType) -> bool {
+    let values = encoded_value_type(data_type).unwrap_or(data_type);
+    is_categorical_type(values) && !values.is_integer()
+}
+
+/// One distinct value and how often it occurs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: u64,
+}
+
+/// Value counts of one column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyTable {
+    /// Rows seen, nulls included
+    pub rows: u64,
+    pub nulls: u64,
+    /// Exact distinct non-null values; `None` past the cap
+    pub distinct: Option<usize>,
+    /// Estimated distinct values when the cap was exceeded
+    pub estimated_distinct: Option<u64>,
+    /// Most frequent first, ties by value; empty past the cap
+    pub values: Vec<ValueCount>,
+}
+
+impl FrequencyTable {
+    pub fn is_high_cardinality(&self) -> bool {
+        self.distinct.is_none()
+    }
+
+    /// Share of all rows (nulls included) with `count` occurrences.
+    pub fn fraction(&self, count: u64) -> f64 {
+        if self.rows == 0 {
+            0.0
+        } else {
+            count as f64 / self.rows as f64
+        }
+    }
+
+    /// `"3 distinct"` or `"high cardinality (~5400 distinct)"`.
+    pub fn cardinality_label(&self) -> String {
+        match (self.distinct, self.estimated_distinct) {
+            (Some(n), _) => format!("{n} distinct"),
+            (None, Some(n)) => format!("high cardinality (~{n} distinct)"),
+            (None, None) => "high cardinality".to_string(),
+        }
+    }
+
+    pub(crate) fn to_json(&self, top: usize) -> Value {
+        let values: Vec<Value> = self
+            .values
+            .iter()
+            .take(top)
+            .map(|v| json!({ "value": v.value, "count": v.count, "fraction": self.fraction(v.count) }))
+            .collect();
+        json!({
+            "rows": self.rows,
+            "nulls": self.nulls,
+            "distinct": self.distinct,
+            "high_cardinality": self.is_high_cardinality(),
+            "estimated_distinct": self.estimated_distinct,
+            "top": values,
+        })
+    }
+}
+
+/// Streaming value counter for one column.
+pub struct FrequencyCounter {
+    max_distinct: usize,
+    counts: HashMap<String, u64>,
+    overflowed: bool,
+    rows: u64,
+    nulls: u64,
+    sketch: DistinctSketch,
+}
+
+/// Distinct-value estimate from the `SKETCH_SIZE` smallest value hashes (a
+/// k-minimum-values sketch); exact while fewer values have been seen.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DistinctSketch {
+    hashes: BTreeSet<u64>,
+}
+
+impl DistinctSketch {
+    pub(crate) fn insert<T: Hash + ?Sized>(&mut self, value: &T) {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let h = hasher.finish();
+        if self.hashes.len() < SKETCH_SIZE {
+            self.hashes.insert(h);
+        } else if self.hashes.last().is_some_and(|&max| h < max) && self.hashes.insert(h) {
+            self.hashes.pop_last();
+        }
+    }
+
+    /// Whether [`estimate`](Self::estimate) is an exact count.
+    pub(crate) fn is_exact(&self) -> bool {
+        self.hashes.len() < SKETCH_SIZE
+    }
+
+    /// Distinct values: exact while the sketch is not full, otherwise
+    /// estimated from how densely the smallest hashes are packed.
+    pub(crate) fn estimate(&self) -> u64 {
+        match self.hashes.last() {
+            Some(&kth) if !self.is_exact() => {
+                let spacing = kth as f64 / u64::MAX as f64;
+                ((SKETCH_SIZE - 1) as f64 / spacing).round() as u64
+            }
+            _ => self.hashes.len() as u64,
+        }
+    }
+}
+
+impl FrequencyCounter {
+    pub fn new(max_distinct: usize) -> Self {
+        Self {
+            max_distinct,
+            counts: HashMap::new(),
+            overflowed: false,
+            rows: 0,
+            nulls: 0,
+            sketch: DistinctSketch::default(),
+        }
+    }
+
+    pub fn add(&mut self, col: &ArrayRef) {
+        self.rows += col.len() as u64;
+        // Dictionary and run-end encoded columns are counted per key or
+        // run, and only their distinct values turned into text.
+        if let Some(encoded) = encoded_counts(col) {
+            self.nulls += encoded.null_keys;
+            for (i, &count) in encoded.counts.iter().enumerate() {
+                if count == 0 {
+                    continue;
+                }
+                if encoded.values.is_null(i) {
+                    self.nulls += count;
+                } else if let Ok(value) = array_value_to_string(&encoded.values, i) {
+                    self.count(value, count);
+                }
+            }
+            return;
+        }
+        self.nulls += col.null_count() as u64;
+        for i in 0..col.len() {
+            if col.is_null(i) {
+                continue;
+            }
+            let Ok(value) = array_value_to_string(col, i) else {
+                continue;
+            };
+            self.count(value, 1);
+        }
+    }
+
+    fn count(&mut self, value: String, n: u64) {
+        self.sketch.insert(value.as_str());
+        if self.overflowed {
+            return;
+        }
+        *self.counts.entry(value).or_insert(0) += n;
+        if self.counts.len() > self.max_distinct {
+            self.overflowed = true;
+            self.counts = HashMap::new();
+        }
+    }
+
+    pub fn finish(self) -> FrequencyTable {
+        if self.overflowed {
+            return FrequencyTable {
+                rows: self.rows,
+                nulls: self.nulls,
+                distinct: None,
+                // Never below what was already counted exactly.
+                estimated_distinct: Some(self.sketch.estimate().max(self.max_distinct as u64 + 1)),
+                values: Vec::new(),
+            };
+        }
+        let mut values: Vec<ValueCount> = self
+            .counts
+            .into_iter()
+            .map(|(value, count)| ValueCount { value, count })
+            .collect();
+        values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        FrequencyTable {
+            rows: self.rows,
+            nulls: self.nulls,
+            distinct: Some(values.len()),
+            estimated_distinct: None,
+            values,
+        }
+    }
+}
+
+/// Frequency table of an in-memory column.
+pub fn column_frequencies(col: &ArrayRef, max_distinct: usize) -> FrequencyTable {
+    let mut counter = FrequencyCounter::new(max_distinct);
+    counter.add(col);
+    counter.finish()
+}
+
+/// Frequency tables over the whole dataset for `columns`, in one streaming
+/// pass.
+pub(crate) async fn dataset_frequencies(
+    dataset: &Dataset,
+    columns: &[String],
+    max_distinct: usize,
+    progress: bool,
+) -> Result<Vec<(String, FrequencyTable)>> {
+    if columns.is_empty() {
+        return Ok(Vec::new());
+    }
+    let total = dataset.count_rows(None).await?;
+    let mut counters: Vec<FrequencyCounter> = columns
+        .iter()
+        .map(|_| FrequencyCounter::new(max_distinct))
+        .collect();
+
+    let mut scanner = dataset.scan();
+    scanner.project(columns)?;
+    let mut stream = scanner.try_into_stream().await?;
+    let mut bar = Progress::new("stats frequencies", total, progress);
+    while let Some(batch) = stream.try_next().await? {
+        bar.inc(batch.num_rows());
+        for (counter, col) in counters.iter_mut().zip(batch.columns()) {
+            counter.add(col);
+        }
+    }
+
+    Ok(columns
+        .iter()
+        .cloned()
+        .zip(counters.into_iter().map(FrequencyCounter::finish))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{BooleanArray, StringArray, UInt32Array};
+    use std::sync::Arc;
+
+    #[test]
+    fn counts_sort_by_frequency_then_value() {
+        let col: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("b"),
+            Some("a"),
+            None,
+            Some("b"),
+            Some("c"),
+        ]));
+        let table = column_frequencies(&col, 10);
+        assert_eq!(table.distinct, Some(3));
+        assert_eq!(table.nulls, 1);
+        let order: Vec<_> = table.values.iter().map(|v| v.value.as_str()).collect();
+        assert_eq!(order, ["b", "a", "c"]);
+        assert_eq!(table.fraction(table.values[0].count), 0.4);
+
+        let flags: ArrayRef = Arc::new(BooleanArray::from(vec![true, false, true]));
+        assert_eq!(column_frequencies(&flags, 10).values[0].value, "true");
+    }
+
+    #[test]
+    fn columns_past_the_cap_report_an_estimate() {
+        let col: ArrayRef = Arc::new(UInt32Array::from((0..5000).collect::<Vec<u32>>()));
+        let table = column_frequencies(&col, 100);
+        assert!(table.is_high_cardinality());
+        assert!(table.values.is_empty());
+        let estimate = table.estimated_distinct.unwrap();
+        assert!((3500..=6500).contains(&estimate), "{estimate}");
+        assert!(table.cardinality_label().starts_with("high cardinality (~"));
+
+        // Below the sketch size the estimate is exact.
+        let small: ArrayRef = Arc::new(UInt32Array::from((0..50).collect::<Vec<u32>>()));
+        assert_eq!(column_frequencies(&small, 10).estimated_distinct, Some(50));
+    }
+
+    #[test]
+    fn encoded_columns_are_counted_per_key_and_run() {
+        use arrow::array::{DictionaryArray, Int32Array, RunArray};
+        use arrow::datatypes::Int32Type;
+
+        let dict: ArrayRef = Arc::new(DictionaryArray::<Int32Type>::new(
+            Int32Array::from(vec![Some(0), Some(1), None, Some(1), Some(2)]),
+            Arc::new(StringArray::from(vec![Some("b"), Some("a"), None])),
+        ));
+        let table = column_frequencies(&dict, 10);
+        assert_eq!((table.rows, table.nulls, table.distinct), (5, 2, Some(2)));
+        let order: Vec<_> = table
+            .values
+            .iter()
+            .map(|v| (v.value.as_str(), v.count))
+            .collect();
+        assert_eq!(order, [("a", 2), ("b", 1)]);
+
+        let runs: ArrayRef = Arc::new(
+            RunArray::<Int32Type>::try_new(
+                &Int32Array::from(vec![3, 4, 10]),
+                &StringArray::from(vec![Some("x"), None, Some("y")]),
+            )
+            .unwrap(),
+        );
+        let table = column_frequencies(&runs, 10);
+        assert_eq!((table.rows, table.nulls), (10, 1));
+        let order: Vec<_> = table
+            .values
+            .iter()
+            .map(|v| (v.value.as_str(), v.count))
+            .collect();
+        assert_eq!(order, [("y", 6), ("x", 3)]);
+        assert!(is_always_categorical(runs.data_type()));
+    }
+}
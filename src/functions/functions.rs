@@ -1,24 +1,59 @@
 use anyhow::anyhow;
 use anyhow::{Context, Result};
+use arrow::compute::{CastOptions, cast_with_options};
 
 use arrow::datatypes::{DataType, Field, Schema};
-use arrow_array::{Array as ArrowArray, ArrayRef, FixedSizeListArray, Float64Array, RecordBatch};
-use std::path::PathBuf;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, Int64Type, UInt32Type};
+use arrow_array::{
+    Array as ArrowArray, ArrayRef, FixedSizeListArray, Float64Array, GenericListArray,
+    OffsetSizeTrait, RecordBatch, StructArray, UInt32Array, UInt64Array,
+};
+use std::ops::Range;
 use std::sync::Arc;
 
-use crate::display::LanceLayout;
+use crate::functions::batch_source::BatchSource;
 
-/// Detect the Lance layout type from a RecordBatch schema.
+/// Logical view of how a Lance dataset is stored.
+///
+/// - DenseRowMajor: { vector: FixedSizeList<Float64>[F] } – each row is a dense vector
+/// - SparseCoo:     { row: UInt32, col: UInt32, value: Float64 } – COO triplets
+/// - Vector1D:      single primitive column (e.g. lambdas, norms, indices)
+/// - RaggedList:    { vector: List<numeric> } – variable-length rows
+/// - SparsePerRow:  { record: Struct<row_indices, col_indices, values | indptr, indices, data> }
+///   – one sparse record (COO entries or a CSR block) per row
+/// - Other:         anything else; shown as‑is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanceLayout {
+    DenseRowMajor,
+    SparseCoo,
+    Vector1D,
+    RaggedList,
+    SparsePerRow,
+    Other,
+}
+
+/// Synthetic UInt64 column holding each row's index in the source dataset
+/// (attached by `sample`). It is rendered in the Row column as `#id` and is
+/// never treated as a feature. A null id marks a row deleted in this
+/// version, read by `--include-deleted`.
+pub const ROWID_COLUMN: &str = "_rowid";
+
+/// Detect the Lance layout type from a RecordBatch schema. A synthetic
+/// [`ROWID_COLUMN`] is ignored. Schemas fitting no known layout (including a
+/// single column of an unsupported type) are [`LanceLayout::Other`];
+/// [`layout_mismatches`] says why.
 pub(crate) fn detect_lance_layout(batch: &RecordBatch) -> LanceLayout {
     let schema = batch.schema();
-    let fields = schema.fields();
+    let fields: Vec<_> = schema
+        .fields()
+        .iter()
+        .filter(|f| f.name() != ROWID_COLUMN)
+        .collect();
 
-    // Sparse COO: row/col/value with expected types
-    if fields.len() == 3 {
-        let names: Vec<_> = fields.iter().map(|f| f.name().as_str()).collect();
-        if names == ["row", "col", "value"] {
-            return LanceLayout::SparseCoo;
-        }
+    // Sparse COO: row/col/value with expected types, in any order
+    if coo_field_indices(&schema).is_some() {
+        return LanceLayout::SparseCoo;
     }
 
     // Single-column cases: dense row-major or 1D vector
@@ -30,6 +65,14 @@ pub(crate) fn detect_lance_layout(batch: &RecordBatch) -> LanceLayout {
                     return LanceLayout::DenseRowMajor;
                 }
             }
+            DataType::List(inner) | DataType::LargeList(inner)
+                if inner.data_type().is_numeric() =>
+            {
+                return LanceLayout::RaggedList;
+            }
+            dt if sparse_struct_kind(dt).is_some() => {
+                return LanceLayout::SparsePerRow;
+            }
             DataType::Float64
             | DataType::Int64
             | DataType::UInt32
@@ -41,27 +84,215 @@ pub(crate) fn detect_lance_layout(batch: &RecordBatch) -> LanceLayout {
             | DataType::UInt8 => {
                 return LanceLayout::Vector1D;
             }
-            _ => panic!("Single column file, datatype not recognised {:?}", f),
+            _ => {}
         }
     }
 
     LanceLayout::Other
 }
 
+/// Why a schema with these fields fits none of the known layouts, one line
+/// per layout. Empty when [`detect_lance_layout`] would recognise it.
+pub(crate) fn layout_mismatches(schema: &Schema) -> Vec<String> {
+    let fields: Vec<_> = schema
+        .fields()
+        .iter()
+        .filter(|f| f.name() != ROWID_COLUMN)
+        .collect();
+    if coo_field_indices(schema).is_some() {
+        return Vec::new();
+    }
+    let mut reasons = vec![format!(
+        "SparseCoo: expected integer row and col columns and a numeric value column, found [{}]",
+        fields
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )];
+
+    if fields.len() != 1 {
+        reasons.push(format!(
+            "DenseRowMajor / Vector1D: expected a single column, found {}",
+            fields.len()
+        ));
+        return reasons;
+    }
+
+    let dt = fields[0].data_type();
+    match dt {
+        DataType::FixedSizeList(inner, _) if matches!(inner.data_type(), DataType::Float64) => {
+            return Vec::new();
+        }
+        DataType::List(inner) | DataType::LargeList(inner) if inner.data_type().is_numeric() => {
+            return Vec::new();
+        }
+        dt if sparse_struct_kind(dt).is_some() => return Vec::new(),
+        DataType::Float64
+        | DataType::Int64
+        | DataType::UInt32
+        | DataType::Int32
+        | DataType::UInt64
+        | DataType::Int16
+        | DataType::UInt16
+        | DataType::Int8
+        | DataType::UInt8 => return Vec::new(),
+        _ => {}
+    }
+    reasons.push(format!(
+        "DenseRowMajor: expected FixedSizeList<Float64>, found {dt}"
+    ));
+    reasons.push(format!(
+        "Vector1D: expected a Float64 or integer column, found {dt}"
+    ));
+    reasons.push(format!("RaggedList: expected List<numeric>, found {dt}"));
+    reasons.push(format!(
+        "SparsePerRow: expected a Struct of row_indices/col_indices/values or \
+         indptr/indices/data lists, found {dt}"
+    ));
+    reasons
+}
+
+/// Positions of the `row`, `col` and `value` columns of a SparseCoo schema:
+/// integer indices and a numeric value, in any order and next to any other
+/// columns.
+pub(crate) fn coo_field_indices(schema: &Schema) -> Option<[usize; 3]> {
+    let find = |name: &str, fits: fn(&DataType) -> bool| {
+        let (i, field) = schema.column_with_name(name)?;
+        fits(field.data_type()).then_some(i)
+    };
+    Some([
+        find("row", DataType::is_integer)?,
+        find("col", DataType::is_integer)?,
+        find("value", DataType::is_numeric)?,
+    ])
+}
+
+/// A COO index column as UInt32; fails on negative or too large indices.
+pub(crate) fn coo_index_column(col: &ArrayRef) -> Result<UInt32Array> {
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let cast = cast_with_options(col, &DataType::UInt32, &options)
+        .with_context(|| format!("COO indices of type {} must fit in UInt32", col.data_type()))?;
+    Ok(cast.as_primitive::<UInt32Type>().clone())
+}
+
+/// A SparseCoo batch as the COO readers take it: `row: UInt32`,
+/// `col: UInt32`, `value: Float64` first, then any other columns unchanged.
+/// Schema metadata (the matrix shape) is kept.
+pub(crate) fn canonical_coo(batch: &RecordBatch) -> Result<RecordBatch> {
+    let schema = batch.schema();
+    let [row_i, col_i, val_i] = coo_field_indices(&schema)
+        .context("COO schema must contain integer 'row'/'col' and numeric 'value' columns")?;
+    let canonical = [row_i, col_i, val_i] == [0, 1, 2]
+        && *schema.field(0).data_type() == DataType::UInt32
+        && *schema.field(1).data_type() == DataType::UInt32
+        && *schema.field(2).data_type() == DataType::Float64;
+    if canonical {
+        return Ok(batch.clone());
+    }
+
+    let value = cast_with_options(
+        batch.column(val_i),
+        &DataType::Float64,
+        &CastOptions {
+            safe: false,
+            ..Default::default()
+        },
+    )?;
+    let mut fields = vec![
+        Field::new("row", DataType::UInt32, schema.field(row_i).is_nullable()),
+        Field::new("col", DataType::UInt32, schema.field(col_i).is_nullable()),
+        Field::new(
+            "value",
+            DataType::Float64,
+            schema.field(val_i).is_nullable(),
+        ),
+    ];
+    let mut cols: Vec<ArrayRef> = vec![
+        Arc::new(coo_index_column(batch.column(row_i))?),
+        Arc::new(coo_index_column(batch.column(col_i))?),
+        value,
+    ];
+    for (i, (field, col)) in schema.fields().iter().zip(batch.columns()).enumerate() {
+        if ![row_i, col_i, val_i].contains(&i) {
+            fields.push(field.as_ref().clone());
+            cols.push(col.clone());
+        }
+    }
+    let schema = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
+    Ok(RecordBatch::try_new(schema, cols)?)
+}
+
+/// Append `ids` (source-dataset row indices) as a [`ROWID_COLUMN`], replacing
+/// any existing one.
+pub(crate) fn attach_row_ids(batch: &RecordBatch, ids: Vec<u64>) -> Result<RecordBatch> {
+    with_row_id_column(batch, UInt64Array::from(ids), false)
+}
+
+/// [`attach_row_ids`] for rows read with their deleted ones: `None` marks a
+/// tombstone.
+pub(crate) fn attach_tombstoned_row_ids(
+    batch: &RecordBatch,
+    ids: Vec<Option<u64>>,
+) -> Result<RecordBatch> {
+    with_row_id_column(batch, UInt64Array::from(ids), true)
+}
+
+fn with_row_id_column(
+    batch: &RecordBatch,
+    ids: UInt64Array,
+    nullable: bool,
+) -> Result<RecordBatch> {
+    if ids.len() != batch.num_rows() {
+        return Err(anyhow!(
+            "attach_row_ids: {} ids for {} rows",
+            ids.len(),
+            batch.num_rows()
+        ));
+    }
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = Vec::with_capacity(batch.num_columns() + 1);
+    let mut cols: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns() + 1);
+    for (field, col) in schema.fields().iter().zip(batch.columns()) {
+        if field.name() != ROWID_COLUMN {
+            fields.push(field.as_ref().clone());
+            cols.push(col.clone());
+        }
+    }
+    fields.push(Field::new(ROWID_COLUMN, DataType::UInt64, nullable));
+    cols.push(Arc::new(ids) as ArrayRef);
+
+    let schema = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
+    Ok(RecordBatch::try_new(schema, cols)?)
+}
+
 /// Expand a dense row‑major FixedSizeList<Float64> column into scalar Float64
 /// columns col_0, col_1, ..., col_(F-1) for nicer display and sampling.
 ///
 /// Input schema:  { vector: FixedSizeList<Float64>[F] }
 /// Output schema: { col_0: Float64, ..., col_(F-1): Float64 }
+///
+/// A [`ROWID_COLUMN`] next to the vector column is carried over unchanged.
 pub(crate) fn expand_dense_row_major(batch: &RecordBatch) -> Result<RecordBatch> {
-    if batch.num_columns() != 1 {
+    let row_ids = batch.column_by_name(ROWID_COLUMN).cloned();
+    let data_cols = batch.num_columns() - usize::from(row_ids.is_some());
+    if data_cols != 1 {
         return Err(anyhow!(
             "expand_dense_row_major: expected 1 column, got {}",
-            batch.num_columns()
+            data_cols
         ));
     }
 
-    let col = batch.column(0);
+    let schema = batch.schema();
+    let vector_idx = schema
+        .fields()
+        .iter()
+        .position(|f| f.name() != ROWID_COLUMN)
+        .expect("one data column");
+    let col = batch.column(vector_idx);
     let list = col
         .as_any()
         .downcast_ref::<FixedSizeListArray>()
@@ -89,21 +320,494 @@ pub(crate) fn expand_dense_row_major(batch: &RecordBatch) -> Result<RecordBatch>
             .collect();
 
         cols.push(Arc::new(Float64Array::from(data)) as ArrayRef);
-        fields.push(Field::new(&format!("col_{dim}"), DataType::Float64, false));
+        fields.push(Field::new(format!("col_{dim}"), DataType::Float64, false));
+    }
+    if let Some(ids) = row_ids {
+        fields.push(Field::new(ROWID_COLUMN, DataType::UInt64, true));
+        cols.push(ids);
     }
 
-    let schema = Arc::new(Schema::new(fields));
+    // Keep schema-level metadata (e.g. pipeline provenance) visible after expansion.
+    let schema = Arc::new(Schema::new_with_metadata(
+        fields,
+        batch.schema().metadata().clone(),
+    ));
     let out = RecordBatch::try_new(schema, cols)?;
     Ok(out)
 }
 
+/// The packed `FixedSizeList<Float64>` column of a DenseRowMajor batch,
+/// kept next to its `col_*` expansion for views that read the matrix values
+/// directly (overview thumbnail, nearest rows).
+#[derive(Debug, Clone)]
+pub(crate) struct DenseMatrix {
+    pub list: FixedSizeListArray,
+    /// Features shown in the viewer (`display --cols`)
+    pub features: Range<usize>,
+}
+
+impl DenseMatrix {
+    /// `None` unless `batch` holds one `FixedSizeList<Float64>` column (next
+    /// to an optional [`ROWID_COLUMN`]). `features` is clamped to the width.
+    pub(crate) fn from_batch(batch: &RecordBatch, features: Range<usize>) -> Option<Self> {
+        if detect_lance_layout(batch) != LanceLayout::DenseRowMajor {
+            return None;
+        }
+        let schema = batch.schema();
+        let idx = schema
+            .fields()
+            .iter()
+            .position(|f| f.name() != ROWID_COLUMN)?;
+        let list = batch.column(idx).as_fixed_size_list_opt()?.clone();
+        let width = list.value_length() as usize;
+        Some(Self {
+            list,
+            features: features.start.min(width)..features.end.min(width),
+        })
+    }
+
+    /// The packed matrix of each batch of `source`, in row order; empty
+    /// unless every batch is DenseRowMajor.
+    pub(crate) fn from_source(source: &BatchSource, features: Range<usize>) -> Vec<Self> {
+        source
+            .batches()
+            .iter()
+            .map(|batch| Self::from_batch(batch, features.clone()))
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default()
+    }
+
+    /// Packed values of `row`; `None` for a null row or one with null values.
+    pub(crate) fn row(&self, row: usize) -> Option<&[f64]> {
+        if self.list.is_null(row) {
+            return None;
+        }
+        let values = self.list.values().as_primitive_opt::<Float64Type>()?;
+        let width = self.list.value_length() as usize;
+        let start = self.list.value_offset(row) as usize;
+        let range = start..start + width;
+        if values.null_count() > 0 && range.clone().any(|i| values.is_null(i)) {
+            return None;
+        }
+        Some(&values.values()[range])
+    }
+}
+
+/// Convert a RaggedList batch whose lists all share one length into the
+/// DenseRowMajor form (`FixedSizeList<Float64>`). `None` when lengths differ
+/// or a row is null, i.e. the batch really is ragged.
+pub(crate) fn uniform_lists_to_dense(batch: &RecordBatch) -> Result<Option<RecordBatch>> {
+    let schema = batch.schema();
+    let Some(list_idx) = schema
+        .fields()
+        .iter()
+        .position(|f| f.name() != ROWID_COLUMN)
+    else {
+        return Ok(None);
+    };
+    let col = batch.column(list_idx);
+    let fixed = match col.data_type() {
+        DataType::List(_) => uniform_to_fixed(col.as_list::<i32>())?,
+        DataType::LargeList(_) => uniform_to_fixed(col.as_list::<i64>())?,
+        _ => None,
+    };
+    let Some(fixed) = fixed else {
+        return Ok(None);
+    };
+
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields[list_idx] = Field::new(
+        schema.field(list_idx).name(),
+        fixed.data_type().clone(),
+        schema.field(list_idx).is_nullable(),
+    );
+    let mut cols = batch.columns().to_vec();
+    cols[list_idx] = Arc::new(fixed);
+    let schema = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
+    Ok(Some(RecordBatch::try_new(schema, cols)?))
+}
+
+fn uniform_to_fixed<O: OffsetSizeTrait>(
+    list: &GenericListArray<O>,
+) -> Result<Option<FixedSizeListArray>> {
+    if list.null_count() > 0 {
+        return Ok(None);
+    }
+    if list.is_empty() {
+        return Ok(None);
+    }
+    let offsets = list.value_offsets();
+    let width = list.value_length(0).as_usize();
+    if (1..list.len()).any(|i| list.value_length(i).as_usize() != width) {
+        return Ok(None);
+    }
+    let start = offsets[0].as_usize();
+    let values = list
+        .values()
+        .slice(start, offsets[list.len()].as_usize() - start);
+    let values = arrow::compute::cast(&values, &DataType::Float64)?;
+    let item = Arc::new(Field::new("item", DataType::Float64, true));
+    Ok(Some(FixedSizeListArray::try_new(
+        item,
+        width as i32,
+        values,
+        None,
+    )?))
+}
+
 /// Normalize a RecordBatch into a form suitable for display / sampling:
 ///
 /// - DenseRowMajor → expanded scalar columns
-/// - SparseCoo, Vector1D, Other → returned unchanged
+/// - RaggedList → expanded like DenseRowMajor when every row has the same
+///   length, otherwise unchanged (shown as ragged rows)
+/// - SparseCoo → `row`, `col`, `value` first with UInt32/Float64 types
+///   ([`canonical_coo`])
+/// - SparsePerRow, Vector1D, Other → returned unchanged
 pub(crate) fn normalize_for_display(batch: &RecordBatch) -> Result<RecordBatch> {
     match detect_lance_layout(batch) {
         LanceLayout::DenseRowMajor => expand_dense_row_major(batch),
-        LanceLayout::SparseCoo | LanceLayout::Vector1D | LanceLayout::Other => Ok(batch.clone()),
+        LanceLayout::RaggedList => match uniform_lists_to_dense(batch)? {
+            Some(dense) => expand_dense_row_major(&dense),
+            None => Ok(batch.clone()),
+        },
+        LanceLayout::SparseCoo => canonical_coo(batch),
+        LanceLayout::SparsePerRow | LanceLayout::Vector1D | LanceLayout::Other => Ok(batch.clone()),
+    }
+}
+
+// === Per-record sparse structs ==============================================
+
+/// Child layout of a [`LanceLayout::SparsePerRow`] struct column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SparseRecordKind {
+    /// `row_indices`, `col_indices`, `values`: entries with their coordinates
+    Coo,
+    /// `indptr`, `indices`, `data`: a CSR block of `indptr.len() - 1` rows
+    Csr,
+}
+
+impl SparseRecordKind {
+    pub(crate) fn children(self) -> [&'static str; 3] {
+        match self {
+            SparseRecordKind::Coo => ["row_indices", "col_indices", "values"],
+            SparseRecordKind::Csr => ["indptr", "indices", "data"],
+        }
+    }
+}
+
+/// Which sparse record layout a struct type holds, if any: all three children
+/// of one [`SparseRecordKind`] must be present and be lists.
+pub(crate) fn sparse_struct_kind(data_type: &DataType) -> Option<SparseRecordKind> {
+    let DataType::Struct(fields) = data_type else {
+        return None;
+    };
+    let is_list = |name: &str| {
+        fields.iter().any(|f| {
+            f.name() == name && matches!(f.data_type(), DataType::List(_) | DataType::LargeList(_))
+        })
+    };
+    [SparseRecordKind::Coo, SparseRecordKind::Csr]
+        .into_iter()
+        .find(|kind| kind.children().iter().all(|name| is_list(name)))
+}
+
+/// One stored entry of a sparse record. For CSR records `row` is local to the
+/// record's block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SparseEntry {
+    pub row: usize,
+    pub col: usize,
+    pub value: Option<f64>,
+}
+
+/// The entries of one sparse record.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SparseRecord {
+    pub entries: Vec<SparseEntry>,
+    /// Rows in the block for CSR records; `None` for COO records
+    pub block_rows: Option<usize>,
+}
+
+/// The struct column of a SparsePerRow batch and its record kind.
+pub(crate) fn sparse_records(batch: &RecordBatch) -> Result<(&StructArray, SparseRecordKind)> {
+    let schema = batch.schema();
+    let idx = schema
+        .fields()
+        .iter()
+        .position(|f| f.name() != ROWID_COLUMN)
+        .context("sparse records: no data column")?;
+    let col = batch.column(idx);
+    let kind = sparse_struct_kind(col.data_type()).with_context(|| {
+        format!(
+            "sparse records: {} is not a sparse struct column",
+            schema.field(idx).name()
+        )
+    })?;
+    Ok((col.as_struct(), kind))
+}
+
+/// Decode record `r`; `None` for a null record, a null child list counts as
+/// empty. Errors when the child lists disagree in length, an index is
+/// negative, or a CSR `indptr` is not a non-decreasing range into `indices`.
+pub(crate) fn sparse_record(
+    records: &StructArray,
+    kind: SparseRecordKind,
+    r: usize,
+) -> Result<Option<SparseRecord>> {
+    if records.is_null(r) {
+        return Ok(None);
+    }
+    let [a, b, c] = kind.children();
+    let child = |name: &str| -> Result<ArrayRef> {
+        let array = records
+            .column_by_name(name)
+            .with_context(|| format!("missing child {name}"))?;
+        list_cell(array, r)
+    };
+    let indices = |name: &str| -> Result<Vec<usize>> {
+        let cell = arrow::compute::cast(&child(name)?, &DataType::Int64)?;
+        cell.as_primitive::<Int64Type>()
+            .iter()
+            .map(|v| match v {
+                Some(v) if v >= 0 => Ok(v as usize),
+                Some(v) => Err(anyhow!("{name} contains negative index {v}")),
+                None => Err(anyhow!("{name} contains a null index")),
+            })
+            .collect()
+    };
+    let values = arrow::compute::cast(&child(c)?, &DataType::Float64)?;
+    let values = values.as_primitive::<Float64Type>();
+
+    match kind {
+        SparseRecordKind::Coo => {
+            let (rows, cols) = (indices(a)?, indices(b)?);
+            if rows.len() != cols.len() || rows.len() != values.len() {
+                return Err(anyhow!(
+                    "{a}/{b}/{c} lengths differ ({}/{}/{})",
+                    rows.len(),
+                    cols.len(),
+                    values.len()
+                ));
+            }
+            let entries = rows
+                .into_iter()
+                .zip(cols)
+                .zip(values.iter())
+                .map(|((row, col), value)| SparseEntry { row, col, value })
+                .collect();
+            Ok(Some(SparseRecord {
+                entries,
+                block_rows: None,
+            }))
+        }
+        SparseRecordKind::Csr => {
+            let (indptr, cols) = (indices(a)?, indices(b)?);
+            if cols.len() != values.len() {
+                return Err(anyhow!(
+                    "{b}/{c} lengths differ ({}/{})",
+                    cols.len(),
+                    values.len()
+                ));
+            }
+            if indptr.windows(2).any(|w| w[0] > w[1])
+                || indptr.last().is_some_and(|&e| e > cols.len())
+            {
+                return Err(anyhow!(
+                    "{a} must be non-decreasing and end within {b} ({} entries)",
+                    cols.len()
+                ));
+            }
+            let mut entries = Vec::new();
+            for (row, w) in indptr.windows(2).enumerate() {
+                entries.extend((w[0]..w[1]).map(|k| SparseEntry {
+                    row,
+                    col: cols[k],
+                    value: values.is_valid(k).then(|| values.value(k)),
+                }));
+            }
+            Ok(Some(SparseRecord {
+                entries,
+                block_rows: Some(indptr.len().saturating_sub(1)),
+            }))
+        }
+    }
+}
+
+/// Row `r` of a List/LargeList array; empty for a null row.
+fn list_cell(array: &ArrayRef, r: usize) -> Result<ArrayRef> {
+    Ok(match array.data_type() {
+        DataType::List(inner) | DataType::LargeList(inner) if array.is_null(r) => {
+            arrow::array::new_empty_array(inner.data_type())
+        }
+        DataType::List(_) => array.as_list::<i32>().value(r),
+        DataType::LargeList(_) => array.as_list::<i64>().value(r),
+        dt => return Err(anyhow!("expected a list column, found {dt}")),
+    })
+}
+
+/// Flatten a SparsePerRow batch into the SparseCoo form
+/// `{ row: UInt32, col: UInt32, value: Float64 }`.
+///
+/// COO records keep their coordinates; CSR records are stacked, each block's
+/// rows following the previous block's. For CSR input without a `rows`
+/// schema entry the stacked row count is recorded there.
+pub(crate) fn sparse_per_row_to_coo(batch: &RecordBatch) -> Result<RecordBatch> {
+    let (records, kind) = sparse_records(batch)?;
+    let mut rows: Vec<u32> = Vec::new();
+    let mut cols: Vec<u32> = Vec::new();
+    let mut values: Vec<Option<f64>> = Vec::new();
+    let mut base = 0usize;
+    for r in 0..records.len() {
+        let Some(record) =
+            sparse_record(records, kind, r).with_context(|| format!("record {r}"))?
+        else {
+            continue;
+        };
+        for e in &record.entries {
+            rows.push(u32::try_from(base + e.row).context("row index exceeds UInt32")?);
+            cols.push(u32::try_from(e.col).context("col index exceeds UInt32")?);
+            values.push(e.value);
+        }
+        base += record.block_rows.unwrap_or(0);
+    }
+
+    let mut metadata = batch.schema().metadata().clone();
+    if kind == SparseRecordKind::Csr {
+        metadata
+            .entry("rows".to_string())
+            .or_insert_with(|| base.to_string());
+    }
+    let schema = Schema::new_with_metadata(
+        vec![
+            Field::new("row", DataType::UInt32, false),
+            Field::new("col", DataType::UInt32, false),
+            Field::new("value", DataType::Float64, true),
+        ],
+        metadata,
+    );
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt32Array::from(rows)) as ArrayRef,
+            Arc::new(UInt32Array::from(cols)) as ArrayRef,
+            Arc::new(Float64Array::from(values)) as ArrayRef,
+        ],
+    )?)
+}
+
+// === Column types ==========================================================
+
+/// Types shown, aggregated and compared as numbers.
+pub(crate) fn is_numeric(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Float32
+            | DataType::Float64
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt32
+            | DataType::UInt64
+    )
+}
+
+/// Whether cells of `data_type` are numeric vectors.
+pub(crate) fn is_vector_type(data_type: &DataType) -> bool {
+    matches!(data_type, DataType::FixedSizeList(inner, _) if is_numeric(inner.data_type()))
+}
+
+// === Metadata columns ======================================================
+
+/// Columns that describe the dataset as a whole rather than holding data.
+pub(crate) const METADATA_COLUMNS: [&str; 3] = ["name_id", "n_rows", "n_cols"];
+
+pub(crate) fn is_metadata_column(name: &str) -> bool {
+    METADATA_COLUMNS.contains(&name)
+}
+
+/// The value of metadata column `name` at its first non-null row in any
+/// batch, as `read` gives it.
+pub(crate) fn first_metadata_value<T>(
+    data: &BatchSource,
+    name: &str,
+    read: impl Fn(&ArrayRef, usize) -> Option<T>,
+) -> Option<T> {
+    data.batches().iter().find_map(|batch| {
+        let col = batch.column_by_name(name)?;
+        let row = (0..col.len()).find(|&i| !col.is_null(i))?;
+        read(col, row)
+    })
+}
+
+/// Dimensions declared by the `n_rows`/`n_cols` metadata columns, read at
+/// their first non-null row like the metadata panel reads them.
+pub(crate) fn declared_dims(data: &BatchSource) -> Option<(usize, usize)> {
+    let value_of = |name: &str| {
+        first_metadata_value(data, name, |col, row| {
+            let value = arrow::compute::cast(&col.slice(row, 1), &DataType::UInt64).ok()?;
+            Some(value.as_any().downcast_ref::<UInt64Array>()?.value(0) as usize)
+        })
+    };
+    Some((value_of("n_rows")?, value_of("n_cols")?))
+}
+
+/// Rows that only carry metadata: `n_rows` is set and every data column is
+/// null, as in a metadata batch concatenated onto the data.
+pub(crate) fn metadata_only_rows(data: &BatchSource) -> usize {
+    let schema = data.schema();
+    data.batches()
+        .iter()
+        .map(|batch| {
+            let Some(n_rows) = batch.column_by_name("n_rows") else {
+                return 0;
+            };
+            let columns: Vec<&ArrayRef> = schema
+                .fields()
+                .iter()
+                .zip(batch.columns())
+                .filter(|(f, _)| !is_metadata_column(f.name()) && f.name() != ROWID_COLUMN)
+                .map(|(_, c)| c)
+                .collect();
+            (0..batch.num_rows())
+                .filter(|&r| !n_rows.is_null(r) && columns.iter().all(|c| c.is_null(r)))
+                .count()
+        })
+        .sum()
+}
+
+// === Ragged lists ==========================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LengthSummary {
+    pub min: usize,
+    pub mean: f64,
+    pub max: usize,
+}
+
+pub(crate) fn length_summary(lengths: &[usize]) -> Option<LengthSummary> {
+    Some(LengthSummary {
+        min: *lengths.iter().min()?,
+        mean: lengths.iter().sum::<usize>() as f64 / lengths.len() as f64,
+        max: *lengths.iter().max()?,
+    })
+}
+
+/// `List<Float64>`-style name; Arrow's Display spells out the item field.
+pub(crate) fn list_type_name(data_type: &DataType) -> String {
+    match data_type {
+        DataType::List(inner) => format!("List<{}>", inner.data_type()),
+        DataType::LargeList(inner) => format!("LargeList<{}>", inner.data_type()),
+        other => other.to_string(),
+    }
+}
+
+/// Length of row `r` of a List/LargeList column; `None` for a null row.
+pub(crate) fn row_length(list: &ArrayRef, r: usize) -> Option<usize> {
+    if list.is_null(r) {
+        return None;
+    }
+    match list.data_type() {
+        DataType::List(_) => Some(list.as_list::<i32>().value_length(r) as usize),
+        DataType::LargeList(_) => Some(list.as_list::<i64>().value_length(r) as usize),
+        _ => None,
     }
 }
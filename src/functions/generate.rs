@@ -1,92 +1,349 @@
-use crate::datasets::make_gaussian_cliques_multi;
-use genegraph_storage::lance_storage_graph::LanceStorageGraph;
-use genegraph_storage::metadata::GeneMetadata;
-use genegraph_storage::traits::backend::StorageBackend;
-use genegraph_storage::traits::metadata::Metadata;
-use log::{debug, info};
-use smartcore::linalg::basic::arrays::Array2;
-use smartcore::linalg::basic::matrix::DenseMatrix;
 use std::path::PathBuf;
+use std::time::Duration;
 
-/// Generate a toy dataset to showcase
-pub async fn cmd_generate(n_items: usize, n_dims: usize, seed: u64) -> anyhow::Result<()> {
-    // 1) Prepare storage directory and minimal metadata
-    let name_id = "javelin_test";
-    #[cfg(test)]
-    fn prepare_dir(n_id: &str) -> PathBuf {
-        let mut out_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        out_dir.push("target");
-        out_dir.push("debug");
-        out_dir.push(n_id);
-        out_dir
+#[cfg(feature = "generate")]
+pub use synthetic::{append_round, cmd_generate};
+
+/// Shape of the synthetic dataset written by `cmd_generate`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum GenerateKind {
+    /// Gaussian cliques: dense points plus their clique adjacency
+    #[default]
+    Cliques,
+    /// Symmetric banded matrix (`--bandwidth`)
+    Banded,
+    /// Block-diagonal matrix (`--blocks`)
+    Block,
+    /// Power-law degree graph (`--alpha`)
+    Powerlaw,
+}
+
+/// Knobs for `cmd_generate`, mirroring the `generate` subcommand flags.
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+    /// Output directory for the generated datasets
+    pub out: PathBuf,
+    /// Delete `out` first if it already exists
+    pub force: bool,
+    pub kind: GenerateKind,
+    pub n_items: usize,
+    pub n_dims: usize,
+    pub n_cliques: usize,
+    /// Standard deviation of points around their clique center
+    pub noise: f64,
+    /// Fraction of points drawn uniformly outside any clique
+    pub outlier_frac: f64,
+    /// Half-width of the band for `GenerateKind::Banded`
+    pub bandwidth: usize,
+    /// Number of diagonal blocks for `GenerateKind::Block`
+    pub blocks: usize,
+    /// Power-law exponent for `GenerateKind::Powerlaw`
+    pub alpha: f64,
+    pub seed: u64,
+    /// Write through genegraph_storage's `LanceStorageGraph` instead of
+    /// plain `dense.lance` / `adjacency.lance` / `norms.lance` datasets
+    pub genegraph: bool,
+    /// Grow the datasets after writing them, one version per round
+    pub append: Option<AppendOptions>,
+}
+
+/// `generate --append`: rounds of rows added to the plain datasets after
+/// they are written, for exercising readers of growing datasets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppendOptions {
+    /// Batches appended, each committed as a new version
+    pub rounds: usize,
+    /// Pause before each round's commits
+    pub interval: Duration,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self {
+            out: PathBuf::from("javelin_test"),
+            force: false,
+            kind: GenerateKind::Cliques,
+            n_items: 200,
+            n_dims: 300,
+            n_cliques: 5,
+            noise: 0.3,
+            outlier_frac: 0.05,
+            bandwidth: 3,
+            blocks: 4,
+            alpha: 2.5,
+            seed: 42,
+            genegraph: false,
+            append: None,
+        }
+    }
+}
+
+/// Without the `generate` feature the generators are compiled out and
+/// `generate` only reports so.
+#[cfg(not(feature = "generate"))]
+pub async fn cmd_generate(_opts: &GenerateOptions) -> anyhow::Result<()> {
+    Err(crate::errors::ErrorCategory::InvalidArgs.error(
+        "javelin was built without the generate feature; rebuild with `--features generate`",
+    ))
+}
+
+/// Rows each `--append` round adds: a tenth of `--n-items`, at least one.
+pub fn append_rows(n_items: usize) -> usize {
+    (n_items / 10).max(1)
+}
+
+/// The generators and writers behind `generate`, compiled only with the
+/// `generate` feature.
+#[cfg(feature = "generate")]
+mod synthetic {
+    use super::{AppendOptions, GenerateKind, GenerateOptions, append_rows};
+    use crate::datasets::{
+        append_lance_batch, csr_to_coo_batch, dense_rows_to_batch, make_banded_matrix,
+        make_block_diagonal, make_gaussian_cliques_multi, make_powerlaw_graph, path_to_uri,
+        vector_to_batch, write_lance_batch,
+    };
+    use crate::errors::ErrorCategory;
+    use genegraph_storage::lance_storage_graph::LanceStorageGraph;
+    use genegraph_storage::metadata::GeneMetadata;
+    use genegraph_storage::traits::backend::StorageBackend;
+    use genegraph_storage::traits::metadata::Metadata;
+    use log::{debug, info};
+    use smartcore::linalg::basic::arrays::Array2;
+    use smartcore::linalg::basic::matrix::DenseMatrix;
+    use sprs::{CsMat, TriMat};
+    use std::path::Path;
+
+    /// Generate a toy dataset to showcase
+    pub async fn cmd_generate(opts: &GenerateOptions) -> anyhow::Result<()> {
+        println!("Generating sample dataset in {}", opts.out.display());
+        if opts.n_items == 0 || opts.n_dims == 0 {
+            return Err(ErrorCategory::InvalidArgs
+                .error("--n-items and --n-dims must both be greater than zero"));
+        }
+        if opts.n_cliques == 0 {
+            return Err(ErrorCategory::InvalidArgs.error("--n-cliques must be greater than zero"));
+        }
+        if !opts.noise.is_finite() || opts.noise < 0.0 {
+            return Err(
+                ErrorCategory::InvalidArgs.error("--noise must be a finite, non-negative number")
+            );
+        }
+        if !(0.0..=1.0).contains(&opts.outlier_frac) {
+            return Err(ErrorCategory::InvalidArgs.error("--outlier-frac must be between 0 and 1"));
+        }
+        if opts.kind == GenerateKind::Block && opts.blocks == 0 {
+            return Err(ErrorCategory::InvalidArgs.error("--blocks must be greater than zero"));
+        }
+        if opts.kind == GenerateKind::Powerlaw && !(opts.alpha > 1.0 && opts.alpha.is_finite()) {
+            return Err(
+                ErrorCategory::InvalidArgs.error("--alpha must be a finite number greater than 1")
+            );
+        }
+        if let Some(append) = &opts.append {
+            if append.rounds == 0 {
+                return Err(ErrorCategory::InvalidArgs.error("--rounds must be greater than zero"));
+            }
+            if opts.genegraph || opts.kind != GenerateKind::Cliques {
+                return Err(ErrorCategory::InvalidArgs
+                    .error("--append only grows the plain datasets of --kind cliques"));
+            }
+        }
+
+        // 1) Prepare the output directory
+        let out_dir = &opts.out;
+        if out_dir.exists() {
+            if !opts.force {
+                return Err(ErrorCategory::InvalidArgs.error(format!(
+                    "output directory {:?} already exists; pass --force to overwrite it",
+                    out_dir
+                )));
+            }
+            std::fs::remove_dir_all(out_dir)?;
+        }
+
+        // 2) Generate dense “full” dataset; structured kinds are n × n, so
+        //    `n_dims` only applies to cliques.
+        let (dense, sparse, vector) = match opts.kind {
+            GenerateKind::Cliques => make_gaussian_cliques_multi(
+                opts.n_items,
+                opts.noise,
+                opts.n_cliques,
+                opts.n_dims,
+                opts.outlier_frac,
+                opts.seed,
+            ),
+            GenerateKind::Banded => make_banded_matrix(opts.n_items, opts.bandwidth, opts.seed),
+            GenerateKind::Block => make_block_diagonal(opts.n_items, opts.blocks, opts.seed),
+            GenerateKind::Powerlaw => make_powerlaw_graph(opts.n_items, opts.alpha, opts.seed),
+        };
+        let (nitems, nfeatures) = (dense.len(), dense[0].len());
+
+        // 3) Save the three artifacts
+        if opts.genegraph {
+            save_genegraph(out_dir, &dense, &sparse, &vector).await?;
+            println!(
+                "Generated {:?} example datasets in {:?} (genegraph layout):
+      - dense Lance:   {} rows × {} cols (raw_input)
+      - sparse Lance:  (adjacency)
+      - 1D vector Lance: (norms)",
+                opts.kind, out_dir, nitems, nfeatures,
+            );
+        } else {
+            save_plain_lance(out_dir, &dense, &sparse, &vector).await?;
+            println!(
+                "Generated {:?} example datasets in {:?}:
+      - dense.lance:     {} rows × {} cols (vector)
+      - adjacency.lance: {} × {} COO, {} nnz
+      - norms.lance:     {} values",
+                opts.kind,
+                out_dir,
+                nitems,
+                nfeatures,
+                sparse.rows(),
+                sparse.cols(),
+                sparse.nnz(),
+                vector.len(),
+            );
+        }
+        if let Some(append) = &opts.append {
+            append_rounds(opts, append, nitems, sparse.nnz()).await?;
+        }
+        info!("Try now `javelin --filepath {}`", out_dir.display());
+
+        Ok(())
+    }
+
+    /// The points, the adjacency among them and their norms that `--append`
+    /// round `round` (from 1) adds: fresh cliques of [`append_rows`] points
+    /// from the seed offset by the round, so every version can be rebuilt.
+    pub fn append_round(
+        opts: &GenerateOptions,
+        round: usize,
+    ) -> (Vec<Vec<f64>>, CsMat<f64>, Vec<f64>) {
+        make_gaussian_cliques_multi(
+            append_rows(opts.n_items),
+            opts.noise,
+            opts.n_cliques,
+            opts.n_dims,
+            opts.outlier_frac,
+            opts.seed.wrapping_add(round as u64),
+        )
     }
-    #[cfg(not(test))]
-    fn prepare_dir(n_id: &str) -> PathBuf {
-        let mut out_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        out_dir.push(n_id);
-        out_dir
+
+    /// Append the [`append_round`] rows to `dense.lance` and `norms.lance`, and
+    /// their adjacency, numbered after the `items` rows already written, to
+    /// `adjacency.lance`, whose `rows`/`cols`/`nnz` metadata then follows in a
+    /// version of its own.
+    async fn append_rounds(
+        opts: &GenerateOptions,
+        append: &AppendOptions,
+        mut items: usize,
+        mut nnz: usize,
+    ) -> anyhow::Result<()> {
+        let uri = |name: &str| path_to_uri(&opts.out.join(name));
+        for round in 1..=append.rounds {
+            tokio::time::sleep(append.interval).await;
+            let (dense, sparse, norms) = append_round(opts, round);
+            let version =
+                append_lance_batch(&uri("dense.lance"), dense_rows_to_batch(&dense)?).await?;
+            append_lance_batch(&uri("norms.lance"), vector_to_batch("norm", &norms)?).await?;
+
+            let total = items + dense.len();
+            let mut shifted = TriMat::with_capacity((total, total), sparse.nnz());
+            for (&v, (r, c)) in sparse.iter() {
+                shifted.add_triplet(items + r, items + c, v);
+            }
+            append_lance_batch(
+                &uri("adjacency.lance"),
+                csr_to_coo_batch(&shifted.to_csr())?,
+            )
+            .await?;
+            nnz += sparse.nnz();
+            let (size, entries) = (total.to_string(), nnz.to_string());
+            let mut adjacency = lance::Dataset::open(&uri("adjacency.lance")).await?;
+            adjacency
+                .update_schema_metadata([
+                    ("rows", size.as_str()),
+                    ("cols", size.as_str()),
+                    ("nnz", entries.as_str()),
+                ])
+                .await?;
+            items = total;
+            println!(
+                "Round {round}/{}: appended {} rows (dense.lance v{version}, {items} rows)",
+                append.rounds,
+                dense.len()
+            );
+        }
+        Ok(())
     }
-    let out_dir = prepare_dir(name_id);
-    if out_dir.exists() {
-        std::fs::remove_dir_all(&out_dir).unwrap();
+
+    /// Write `dense.lance`, `adjacency.lance` and `norms.lance` into `out_dir`.
+    async fn save_plain_lance(
+        out_dir: &Path,
+        dense: &[Vec<f64>],
+        sparse: &CsMat<f64>,
+        norms: &[f64],
+    ) -> anyhow::Result<()> {
+        std::fs::create_dir_all(out_dir)?;
+        let write = |name: &str| path_to_uri(&out_dir.join(name));
+
+        write_lance_batch(&write("dense.lance"), dense_rows_to_batch(dense)?).await?;
+        write_lance_batch(&write("adjacency.lance"), csr_to_coo_batch(sparse)?).await?;
+        write_lance_batch(&write("norms.lance"), vector_to_batch("norm", norms)?).await?;
+        Ok(())
     }
 
-    let storage = LanceStorageGraph::new(
-        out_dir.to_str().expect("non-UTF8 test path").to_string(),
-        "javelin_test".to_string(),
-    );
-
-    // 2) Generate dense “full” dataset
-    let (dense, sparse, vector) = make_gaussian_cliques_multi(n_items, 0.3, 5, n_dims, seed);
-    let (nitems, nfeatures) = (dense.len(), dense[0].len());
-
-    // Create metadata
-    GeneMetadata::seed_metadata(&name_id, nitems, nfeatures, &storage)
-        .await
-        .unwrap();
-    debug!("Saving metadata first to initialize storage directory");
-
-    // add data to the storage
-    let dense_matrix =
-        DenseMatrix::<f64>::from_iterator(dense.iter().flatten().map(|x| *x), nitems, nfeatures, 0);
-    storage
-        .save_dense("raw_input", &dense_matrix, &storage.metadata_path())
-        .await?;
-
-    // the adjacency file and the norms file
-    let mut md: GeneMetadata = storage.load_metadata().await.unwrap();
-    let mock_info_adj = md.new_fileinfo(
-        "adjacency",
-        "sparse",
-        (nitems, nitems),
-        Some(sparse.nnz()),
-        None,
-    );
-    let mock_info_norms = md.new_fileinfo("norms", "vector", (nitems, 1), None, None);
-
-    md = md.add_file("adjacency", mock_info_adj);
-    md = md.add_file("norms", mock_info_norms);
-
-    storage
-        .save_sparse("adjacency", &sparse, &storage.metadata_path())
-        .await
-        .unwrap();
-    storage
-        .save_vector("norms", &vector.as_slice(), &storage.metadata_path())
-        .await
-        .unwrap();
-
-    // Fill any required fields on md.aspace_config, etc.
-    storage.save_metadata(&md).await?;
-
-    println!(
-        "Generated example datasets in {:?}:
-  - dense Lance:   {} rows × {} cols (raw_input)
-  - sparse Lance:  (adjacency)
-  - 1D vector Lance: (norms)",
-        out_dir, nitems, nfeatures,
-    );
-    info!("Try now `javelin --filepath ./javelin_test`");
-
-    Ok(())
+    /// Write the artifacts through genegraph_storage, with its metadata file and
+    /// `raw_input` / `adjacency` / `norms` entries.
+    async fn save_genegraph(
+        out_dir: &Path,
+        dense: &[Vec<f64>],
+        sparse: &CsMat<f64>,
+        norms: &[f64],
+    ) -> anyhow::Result<()> {
+        let name_id = "javelin_test";
+        let storage = LanceStorageGraph::new(path_to_uri(out_dir), name_id.to_string());
+        let (nitems, nfeatures) = (dense.len(), dense[0].len());
+
+        // Create metadata
+        GeneMetadata::seed_metadata(name_id, nitems, nfeatures, &storage).await?;
+        debug!("Saving metadata first to initialize storage directory");
+
+        // add data to the storage
+        let dense_matrix = DenseMatrix::<f64>::from_iterator(
+            dense.iter().flatten().copied(),
+            nitems,
+            nfeatures,
+            0,
+        );
+        storage
+            .save_dense("raw_input", &dense_matrix, &storage.metadata_path())
+            .await?;
+
+        // the adjacency file and the norms file
+        let mut md: GeneMetadata = storage.load_metadata().await?;
+        let mock_info_adj = md.new_fileinfo(
+            "adjacency",
+            "sparse",
+            (nitems, nitems),
+            Some(sparse.nnz()),
+            None,
+        );
+        let mock_info_norms = md.new_fileinfo("norms", "vector", (nitems, 1), None, None);
+
+        md = md.add_file("adjacency", mock_info_adj);
+        md = md.add_file("norms", mock_info_norms);
+
+        storage
+            .save_sparse("adjacency", sparse, &storage.metadata_path())
+            .await?;
+        storage
+            .save_vector("norms", norms, &storage.metadata_path())
+            .await?;
+
+        // Fill any required fields on md.aspace_config, etc.
+        storage.save_metadata(&md).await?;
+        Ok(())
+    }
 }
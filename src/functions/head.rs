@@ -1,27 +1,85 @@
 use anyhow::Result;
+use arrow_array::RecordBatch;
 use lance::Dataset;
-use std::path::PathBuf;
+use std::path::Path;
 
-use crate::datasets::path_to_uri;
-use crate::display::display::display_spreadsheet_interactive;
-use crate::functions::functions::normalize_for_display;
+use crate::datasets::open_dataset;
+use crate::display::display::display_spreadsheet_window;
+use crate::display::{SourceWindow, run_viewer};
+use crate::functions::batch_source::BatchSource;
+use crate::functions::deletions::{deleted_rows, scan_with_deleted};
+use crate::functions::export::Provenance;
+use crate::functions::functions::{DenseMatrix, attach_row_ids, normalize_for_display};
+use crate::session::SessionStore;
 
-pub async fn cmd_head(filepath: &PathBuf, n: usize) -> Result<()> {
-    let uri = path_to_uri(filepath);
-    let dataset = Dataset::open(&uri).await?;
-    let mut scanner = dataset.scan();
+/// Show `n` rows starting at row `offset` in the interactive viewer, with
+/// `visible_cols` feature columns at once when given. `include_deleted`
+/// also reads the rows deleted among them (see [`scan_with_deleted`]).
+pub async fn cmd_head(
+    filepath: &Path,
+    n: usize,
+    offset: usize,
+    visible_cols: Option<usize>,
+    include_deleted: bool,
+) -> Result<()> {
+    let dataset = open_dataset(filepath).await?;
+    let total = dataset.count_rows(None).await?;
+
+    let batch = if include_deleted {
+        scan_with_deleted(&dataset, offset..offset.saturating_add(n), None)
+            .await?
+            .whole()
+            .clone()
+    } else {
+        head_batch(&dataset, n, offset).await?
+    };
+
+    // An empty dataset opens the viewer's placeholder
+    if batch.num_rows() == 0 && total > 0 && offset >= total {
+        println!("No rows at offset {offset}: dataset has {total} rows");
+        return Ok(());
+    }
 
+    let dense = DenseMatrix::from_batch(&batch, 0..usize::MAX);
+    let data = BatchSource::from(normalize_for_display(&batch)?);
+    let source = SourceWindow {
+        row_offset: offset,
+        total_rows: total,
+        deleted_rows: deleted_rows(&dataset).await?,
+        ..SourceWindow::whole(total)
+    };
+    let session = SessionStore::user(filepath);
+    let provenance = Provenance::new(filepath, format!("head {n} --offset {offset}"))
+        .with_filter(format!("rows {offset}..{}", offset + batch.num_rows()));
+    run_viewer(move || {
+        display_spreadsheet_window(
+            &data,
+            source,
+            dense.into_iter().collect(),
+            None,
+            None,
+            visible_cols,
+            &session,
+            &provenance,
+            None,
+        )
+    })
+    .await
+}
+
+/// Up to `n` rows starting at `offset`. Windows past the start carry their
+/// source row ids as `_rowid` so the Row column shows them.
+pub(crate) async fn head_batch(dataset: &Dataset, n: usize, offset: usize) -> Result<RecordBatch> {
+    let mut scanner = dataset.scan();
+    let offset_arg = (offset > 0).then_some(offset as i64);
     let batch = scanner
-        .limit(Some(n as i64), None)?
+        .limit(Some(n as i64), offset_arg)?
         .try_into_batch()
         .await?;
 
-    if batch.num_rows() == 0 {
-        println!("No data to display");
-        return Ok(());
+    if offset == 0 {
+        return Ok(batch);
     }
-
-    let batch = normalize_for_display(&batch)?;
-    display_spreadsheet_interactive(&batch)?;
-    Ok(())
+    let ids = (offset as u64..).take(batch.num_rows()).collect();
+    attach_row_ids(&batch, ids)
 }
@@ -0,0 +1,640 @@
+//! Import external matrix files into Lance datasets
+//!
+//! Supported inputs:
+//! - CSV: header row detected automatically, every column must be numeric
+//! - Parquet: numeric columns (wide `col_*` style) or a single
+//!   `FixedSizeList<Float64>` vector column
+//! - NumPy `.npy`: 1D or 2D float arrays (`f4`/`f8`, C or Fortran order)
+//!
+//! The output uses the layouts javelin already understands:
+//! - 2D → `{ vector: FixedSizeList<Float64>[F] }` (DenseRowMajor)
+//! - 1D → `{ element: Float64 }` (Vector1D)
+
+use anyhow::{Context, Result, anyhow, bail};
+use arrow::compute::{cast, concat_batches};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow_array::{Array, ArrayRef, FixedSizeListArray, Float64Array, RecordBatch};
+use log::{debug, info, warn};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::datasets::{path_to_uri, write_lance_batch};
+use crate::errors::ErrorCategory;
+
+/// Input formats accepted by `javelin import`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    Csv,
+    Parquet,
+    Npy,
+}
+
+impl ImportFormat {
+    /// Guess the format from the file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "csv" | "tsv" | "txt" => Some(ImportFormat::Csv),
+            "parquet" | "pq" => Some(ImportFormat::Parquet),
+            "npy" => Some(ImportFormat::Npy),
+            _ => None,
+        }
+    }
+}
+
+/// Row-major matrix of f64 values read from an input file.
+///
+/// `one_d` marks inputs that were genuinely one-dimensional (single CSV
+/// column, 1D `.npy`, single numeric Parquet column); they are written as a
+/// plain Float64 column instead of a FixedSizeList.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImportedMatrix {
+    pub n_rows: usize,
+    pub n_cols: usize,
+    pub values: Vec<f64>,
+    pub one_d: bool,
+}
+
+impl ImportedMatrix {
+    /// Convert into a RecordBatch in one of javelin's native layouts.
+    pub(crate) fn to_record_batch(&self) -> Result<RecordBatch> {
+        if self.one_d {
+            let schema = Schema::new(vec![Field::new("element", DataType::Float64, false)]);
+            let col = Arc::new(Float64Array::from(self.values.clone())) as ArrayRef;
+            return Ok(RecordBatch::try_new(Arc::new(schema), vec![col])?);
+        }
+
+        if self.n_cols == 0 {
+            bail!("cannot import a matrix with zero columns");
+        }
+
+        let item = Arc::new(Field::new("item", DataType::Float64, false));
+        let list = FixedSizeListArray::new(
+            item.clone(),
+            self.n_cols as i32,
+            Arc::new(Float64Array::from(self.values.clone())),
+            None,
+        );
+        let schema = Schema::new(vec![Field::new(
+            "vector",
+            DataType::FixedSizeList(item, self.n_cols as i32),
+            false,
+        )]);
+        Ok(RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(list) as ArrayRef],
+        )?)
+    }
+}
+
+/// Convert a CSV, Parquet or `.npy` file into a Lance dataset at `output`.
+pub async fn cmd_import(input: &Path, output: &Path, format: Option<ImportFormat>) -> Result<()> {
+    let format = match format.or_else(|| ImportFormat::from_path(input)) {
+        Some(f) => f,
+        None => {
+            return Err(ErrorCategory::InvalidArgs.error(format!(
+                "cannot infer input format from {:?}; pass --format csv|parquet|npy",
+                input
+            )));
+        }
+    };
+    info!("cmd_import: reading {:?} as {:?}", input, format);
+
+    if output.exists() {
+        return Err(ErrorCategory::InvalidArgs.error(format!(
+            "output {:?} already exists; remove it or choose another path",
+            output
+        )));
+    }
+
+    let matrix = match format {
+        ImportFormat::Csv => read_csv(input)?,
+        ImportFormat::Parquet => read_parquet(input)?,
+        ImportFormat::Npy => read_npy(input)?,
+    };
+    debug!(
+        "cmd_import: parsed {} rows × {} cols (1D: {})",
+        matrix.n_rows, matrix.n_cols, matrix.one_d
+    );
+
+    let batch = matrix.to_record_batch()?;
+    write_lance_batch(&path_to_uri(output), batch).await?;
+
+    println!(
+        "Imported {} rows × {} cols from {:?} into {:?} ({})",
+        matrix.n_rows,
+        matrix.n_cols,
+        input,
+        output,
+        if matrix.one_d {
+            "1D vector"
+        } else {
+            "dense row-major"
+        }
+    );
+    println!("Try now `javelin --filepath {} display`", output.display());
+    Ok(())
+}
+
+// ================================ CSV ======================================
+
+/// Field delimiter of a delimited text file: tabs for `.tsv`, commas
+/// otherwise.
+fn csv_delimiter(path: &Path) -> u8 {
+    let tsv = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("tsv"));
+    if tsv { b'\t' } else { b',' }
+}
+
+/// A CSV first line is treated as a header when any field is not numeric.
+fn csv_has_header(first_line: &str, delimiter: u8) -> bool {
+    first_line
+        .split(char::from(delimiter))
+        .any(|field| field.trim().parse::<f64>().is_err())
+}
+
+pub(crate) fn read_csv(path: &Path) -> Result<ImportedMatrix> {
+    let mut file = File::open(path).with_context(|| format!("failed to open {:?}", path))?;
+
+    let mut first_line = String::new();
+    BufReader::new(&file).read_line(&mut first_line)?;
+    if first_line.trim().is_empty() {
+        bail!("CSV file {:?} is empty", path);
+    }
+    let delimiter = csv_delimiter(path);
+    let has_header = csv_has_header(first_line.trim_end(), delimiter);
+    debug!("read_csv: header detected = {}", has_header);
+
+    file.seek(SeekFrom::Start(0))?;
+    let format = arrow::csv::reader::Format::default()
+        .with_header(has_header)
+        .with_delimiter(delimiter);
+    let (schema, _) = format
+        .infer_schema(&mut file, Some(1000))
+        .context("failed to infer CSV schema")?;
+    file.seek(SeekFrom::Start(0))?;
+
+    let schema = Arc::new(schema);
+    let reader = arrow::csv::ReaderBuilder::new(schema.clone())
+        .with_format(format)
+        .build(file)?;
+
+    let mut batches = Vec::new();
+    for batch in reader {
+        batches.push(batch?);
+    }
+    let batch = concat_batches(&schema, &batches)?;
+    numeric_batch_to_matrix(&batch)
+}
+
+// ============================== Parquet ====================================
+
+pub(crate) fn read_parquet(path: &Path) -> Result<ImportedMatrix> {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let file = File::open(path).with_context(|| format!("failed to open {:?}", path))?;
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+    let schema = builder.schema().clone();
+    let reader = builder.build()?;
+
+    let mut batches = Vec::new();
+    for batch in reader {
+        batches.push(batch?);
+    }
+    let batch = concat_batches(&schema, &batches)?;
+
+    // Already in vector layout: a single FixedSizeList column.
+    if batch.num_columns() == 1
+        && let DataType::FixedSizeList(_, width) = batch.schema().field(0).data_type()
+    {
+        let width = *width as usize;
+        let list = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<FixedSizeListArray>()
+            .context("expected FixedSizeList column")?;
+        let values = to_f64_values(list.values(), "vector")?;
+        return Ok(ImportedMatrix {
+            n_rows: list.len(),
+            n_cols: width,
+            values,
+            one_d: false,
+        });
+    }
+
+    numeric_batch_to_matrix(&batch)
+}
+
+// =============================== NumPy =====================================
+
+/// Parse a value `'key': <value>` from the python-dict header of a `.npy` file.
+fn npy_header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let pat = format!("'{key}':");
+    let start = header.find(&pat)? + pat.len();
+    let rest = header[start..].trim_start();
+    let end = if rest.starts_with('(') {
+        rest.find(')')? + 1
+    } else if let Some(stripped) = rest.strip_prefix('\'') {
+        stripped.find('\'')? + 2
+    } else {
+        rest.find([',', '}']).unwrap_or(rest.len())
+    };
+    Some(rest[..end].trim())
+}
+
+pub(crate) fn read_npy(path: &Path) -> Result<ImportedMatrix> {
+    let bytes = std::fs::read(path).with_context(|| format!("failed to read {:?}", path))?;
+    parse_npy(&bytes)
+}
+
+pub(crate) fn parse_npy(bytes: &[u8]) -> Result<ImportedMatrix> {
+    if bytes.len() < 10 || &bytes[0..6] != b"\x93NUMPY" {
+        bail!("not a NumPy .npy file (bad magic)");
+    }
+    let major = bytes[6];
+    let (header_len, header_start) = match major {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 => {
+            if bytes.len() < 12 {
+                bail!("truncated .npy header");
+            }
+            (
+                u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize,
+                12,
+            )
+        }
+        v => bail!("unsupported .npy format version {v}"),
+    };
+    let data_start = header_start + header_len;
+    if bytes.len() < data_start {
+        bail!("truncated .npy header");
+    }
+    let header = std::str::from_utf8(&bytes[header_start..data_start])?;
+
+    let descr = npy_header_value(header, "descr")
+        .ok_or_else(|| anyhow!("missing 'descr' in .npy header"))?
+        .trim_matches('\'');
+    let fortran = npy_header_value(header, "fortran_order")
+        .map(|v| v == "True")
+        .unwrap_or(false);
+    let shape_str = npy_header_value(header, "shape")
+        .ok_or_else(|| anyhow!("missing 'shape' in .npy header"))?;
+    let shape: Vec<usize> = shape_str
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<usize>())
+        .collect::<std::result::Result<_, _>>()
+        .context("invalid 'shape' in .npy header")?;
+
+    let (n_rows, n_cols, one_d) = match shape.as_slice() {
+        [n] => (*n, 1, true),
+        [n, m] => (*n, *m, false),
+        _ => bail!(
+            "only 1D and 2D .npy arrays are supported, got shape {:?}",
+            shape
+        ),
+    };
+
+    let little = match descr.chars().next() {
+        Some('<') | Some('|') | Some('=') => true,
+        Some('>') => false,
+        _ => bail!("unsupported .npy dtype {descr}"),
+    };
+    let width = match &descr[1..] {
+        "f8" => 8,
+        "f4" => 4,
+        other => bail!("unsupported .npy dtype {other}; only f4/f8 floats are supported"),
+    };
+
+    let (total, expected) = n_rows
+        .checked_mul(n_cols)
+        .and_then(|total| Some((total, total.checked_mul(width)?)))
+        .ok_or_else(|| anyhow!("invalid 'shape' in .npy header: {:?} is too large", shape))?;
+    let data = &bytes[data_start..];
+    if data.len() < expected {
+        bail!(
+            "truncated .npy data: expected {} bytes, found {}",
+            expected,
+            data.len()
+        );
+    }
+
+    let raw: Vec<f64> = data
+        .chunks_exact(width)
+        .take(total)
+        .map(|chunk| match (width, little) {
+            (8, true) => f64::from_le_bytes(chunk.try_into().unwrap()),
+            (8, false) => f64::from_be_bytes(chunk.try_into().unwrap()),
+            (_, true) => f32::from_le_bytes(chunk.try_into().unwrap()) as f64,
+            (_, false) => f32::from_be_bytes(chunk.try_into().unwrap()) as f64,
+        })
+        .collect();
+
+    // Fortran order stores columns contiguously; convert to row-major.
+    let values = if fortran && !one_d {
+        let mut out = vec![0.0; total];
+        for c in 0..n_cols {
+            for r in 0..n_rows {
+                out[r * n_cols + c] = raw[c * n_rows + r];
+            }
+        }
+        out
+    } else {
+        raw
+    };
+
+    Ok(ImportedMatrix {
+        n_rows,
+        n_cols,
+        values,
+        one_d,
+    })
+}
+
+// ============================== Helpers ====================================
+
+/// Cast an array to Float64 and collect its values, mapping NULLs to NaN.
+fn to_f64_values(array: &ArrayRef, name: &str) -> Result<Vec<f64>> {
+    let casted = cast(array, &DataType::Float64)
+        .with_context(|| format!("column '{name}' cannot be converted to Float64"))?;
+    let floats = casted
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .context("cast to Float64 failed")?;
+    if floats.null_count() > 0 {
+        warn!(
+            "column '{}' has {} NULL values; importing them as NaN",
+            name,
+            floats.null_count()
+        );
+    }
+    Ok((0..floats.len())
+        .map(|i| {
+            if floats.is_null(i) {
+                f64::NAN
+            } else {
+                floats.value(i)
+            }
+        })
+        .collect())
+}
+
+/// Flatten a table of numeric columns into a row-major matrix.
+fn numeric_batch_to_matrix(batch: &RecordBatch) -> Result<ImportedMatrix> {
+    let schema = batch.schema();
+    let mut columns = Vec::with_capacity(batch.num_columns());
+    for (i, field) in schema.fields().iter().enumerate() {
+        if !field.data_type().is_numeric() {
+            bail!(
+                "column '{}' has non-numeric type {:?}; only numeric columns can be imported",
+                field.name(),
+                field.data_type()
+            );
+        }
+        columns.push(to_f64_values(batch.column(i), field.name())?);
+    }
+
+    let n_rows = batch.num_rows();
+    let n_cols = columns.len();
+    let mut values = Vec::with_capacity(n_rows * n_cols);
+    for r in 0..n_rows {
+        for col in &columns {
+            values.push(col[r]);
+        }
+    }
+
+    Ok(ImportedMatrix {
+        n_rows,
+        n_cols,
+        values,
+        one_d: n_cols == 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::LanceLayout;
+    use crate::functions::functions::detect_lance_layout;
+    use lance::Dataset;
+    use std::io::Write;
+
+    async fn read_back(path: &Path) -> RecordBatch {
+        let dataset = Dataset::open(&path_to_uri(path)).await.unwrap();
+        dataset.scan().try_into_batch().await.unwrap()
+    }
+
+    fn dense_values(batch: &RecordBatch) -> (usize, Vec<f64>) {
+        let list = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<FixedSizeListArray>()
+            .unwrap();
+        let values = list
+            .values()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        (list.value_length() as usize, values.values().to_vec())
+    }
+
+    fn npy_bytes(descr: &str, fortran: bool, shape: &str, data: &[u8]) -> Vec<u8> {
+        let mut header = format!(
+            "{{'descr': '{descr}', 'fortran_order': {}, 'shape': {shape}, }}",
+            if fortran { "True" } else { "False" }
+        );
+        // Pad so that magic + version + len + header is a multiple of 64 and ends in '\n'.
+        while (10 + header.len() + 1) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+
+        let mut out = b"\x93NUMPY\x01\x00".to_vec();
+        out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        out.extend_from_slice(header.as_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    #[test]
+    fn header_detection() {
+        assert!(csv_has_header("a,b,c", b','));
+        assert!(csv_has_header("col_0, 1.0", b','));
+        assert!(!csv_has_header("1.0,2,-3e4", b','));
+        assert!(!csv_has_header("1.0\t2", b'\t'));
+        assert!(csv_has_header("a\tb", b'\t'));
+    }
+
+    #[test]
+    fn npy_header_parsing() {
+        let h = "{'descr': '<f8', 'fortran_order': False, 'shape': (3, 2), }";
+        assert_eq!(npy_header_value(h, "descr"), Some("'<f8'"));
+        assert_eq!(npy_header_value(h, "fortran_order"), Some("False"));
+        assert_eq!(npy_header_value(h, "shape"), Some("(3, 2)"));
+    }
+
+    #[test]
+    fn npy_fortran_order_is_converted_to_row_major() {
+        // Column-major storage of [[1, 2], [3, 4], [5, 6]]
+        let data: Vec<u8> = [1.0f64, 3.0, 5.0, 2.0, 4.0, 6.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let m = parse_npy(&npy_bytes("<f8", true, "(3, 2)", &data)).unwrap();
+        assert_eq!((m.n_rows, m.n_cols, m.one_d), (3, 2, false));
+        assert_eq!(m.values, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    fn npy_rejects_unsupported_dtype() {
+        let m = parse_npy(&npy_bytes("<i8", false, "(1,)", &1i64.to_le_bytes()));
+        assert!(m.is_err());
+    }
+
+    #[test]
+    fn npy_rejects_a_shape_whose_size_overflows() {
+        let shape = format!("({}, 3)", usize::MAX / 2);
+        let err = parse_npy(&npy_bytes("<f8", false, &shape, &[])).unwrap_err();
+        assert!(err.to_string().contains("invalid 'shape'"), "{err}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn csv_round_trip_with_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("m.csv");
+        let mut f = File::create(&input).unwrap();
+        writeln!(f, "a,b,c").unwrap();
+        writeln!(f, "1.5,2,3").unwrap();
+        writeln!(f, "4,5.25,-6").unwrap();
+        drop(f);
+
+        let output = dir.path().join("m.lance");
+        cmd_import(&input, &output, None).await.unwrap();
+
+        let batch = read_back(&output).await;
+        assert!(matches!(
+            detect_lance_layout(&batch),
+            LanceLayout::DenseRowMajor
+        ));
+        let (width, values) = dense_values(&batch);
+        assert_eq!(width, 3);
+        assert_eq!(values, vec![1.5, 2.0, 3.0, 4.0, 5.25, -6.0]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tsv_round_trip_with_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("m.tsv");
+        std::fs::write(&input, "a\tb\n1.5\t2\n-3\t4.25\n").unwrap();
+
+        let output = dir.path().join("m.lance");
+        cmd_import(&input, &output, None).await.unwrap();
+
+        let batch = read_back(&output).await;
+        let (width, values) = dense_values(&batch);
+        assert_eq!(width, 2);
+        assert_eq!(values, vec![1.5, 2.0, -3.0, 4.25]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn csv_round_trip_single_column_without_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("v.csv");
+        std::fs::write(&input, "0.5\n1.5\n2.5\n").unwrap();
+
+        let output = dir.path().join("v.lance");
+        cmd_import(&input, &output, Some(ImportFormat::Csv))
+            .await
+            .unwrap();
+
+        let batch = read_back(&output).await;
+        assert!(matches!(detect_lance_layout(&batch), LanceLayout::Vector1D));
+        let col = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(col.values().to_vec(), vec![0.5, 1.5, 2.5]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn csv_rejects_non_numeric_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("bad.csv");
+        std::fs::write(&input, "name,x\nfoo,1\nbar,2\n").unwrap();
+        let output = dir.path().join("bad.lance");
+        assert!(cmd_import(&input, &output, None).await.is_err());
+        assert!(!output.exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn parquet_round_trip() {
+        use parquet::arrow::ArrowWriter;
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("m.parquet");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("col_0", DataType::Float64, false),
+            Field::new("col_1", DataType::Float32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Float64Array::from(vec![1.0, 2.0])) as ArrayRef,
+                Arc::new(arrow_array::Float32Array::from(vec![0.5f32, -0.25])) as ArrayRef,
+            ],
+        )
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(File::create(&input).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let output = dir.path().join("m.lance");
+        cmd_import(&input, &output, None).await.unwrap();
+
+        let batch = read_back(&output).await;
+        let (width, values) = dense_values(&batch);
+        assert_eq!(width, 2);
+        assert_eq!(values, vec![1.0, 0.5, 2.0, -0.25]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn npy_round_trip_2d_and_1d() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let data: Vec<u8> = [1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let input = dir.path().join("m.npy");
+        std::fs::write(&input, npy_bytes("<f8", false, "(2, 3)", &data)).unwrap();
+        let output = dir.path().join("m.lance");
+        cmd_import(&input, &output, None).await.unwrap();
+        let (width, values) = dense_values(&read_back(&output).await);
+        assert_eq!(width, 3);
+        assert_eq!(values, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        let data: Vec<u8> = [0.5f32, 1.5, 2.5]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let input = dir.path().join("v.npy");
+        std::fs::write(&input, npy_bytes("<f4", false, "(3,)", &data)).unwrap();
+        let output = dir.path().join("v.lance");
+        cmd_import(&input, &output, None).await.unwrap();
+        let batch = read_back(&output).await;
+        assert!(matches!(detect_lance_layout(&batch), LanceLayout::Vector1D));
+        let col = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(col.values().to_vec(), vec![0.5, 1.5, 2.5]);
+    }
+}
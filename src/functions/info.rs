@@ -1,31 +1,494 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
+use arrow::datatypes::{DataType, Schema as ArrowSchema};
+use arrow_array::RecordBatch;
+use futures::TryStreamExt;
 use lance::Dataset;
-use std::path::PathBuf;
+use serde_json::{Value, json};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Arc;
 
-use crate::datasets::path_to_uri;
+use crate::datasets::open_dataset;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::deletions::{DeletionReport, deletion_lines, deletion_report};
+use crate::functions::format::format_bytes;
+use crate::functions::functions::{
+    LanceLayout, ROWID_COLUMN, coo_index_column, declared_dims, detect_lance_layout,
+    is_metadata_column, layout_mismatches, list_type_name, metadata_only_rows,
+    normalize_for_display, sparse_struct_kind,
+};
+use crate::functions::storage::{StorageReport, storage_report};
+use crate::functions::unsupported::{UnsupportedColumn, column_support, hidden_columns};
 
-pub async fn cmd_info(filepath: &PathBuf) -> Result<()> {
-    println!("=== Lance File Info ===");
-    println!("Path: {}", filepath.display());
+/// Rows read to classify the layout.
+const LAYOUT_SAMPLE_ROWS: i64 = 16;
 
+/// One metadata key/value pair; `scope` is `schema` or the owning field name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataEntry {
+    pub scope: String,
+    pub key: String,
+    pub value: String,
+}
+
+/// All metadata on `schema`: schema-level keys first, then each top-level
+/// field in schema order. Keys are sorted within each scope.
+pub fn schema_metadata_entries(schema: &ArrowSchema) -> Vec<MetadataEntry> {
+    let mut out = Vec::new();
+    let mut push_sorted = |scope: &str, map: &std::collections::HashMap<String, String>| {
+        let mut pairs: Vec<_> = map.iter().collect();
+        pairs.sort();
+        for (key, value) in pairs {
+            out.push(MetadataEntry {
+                scope: scope.to_string(),
+                key: key.clone(),
+                value: value.clone(),
+            });
+        }
+    };
+
+    push_sorted("schema", schema.metadata());
+    for field in schema.fields() {
+        push_sorted(field.name(), field.metadata());
+    }
+    out
+}
+
+/// Print dataset information; `verbose` adds the per-column storage
+/// breakdown and the deleted rows of each fragment, and `json` prints
+/// everything as one JSON object.
+pub async fn cmd_info(filepath: &Path, verbose: bool, json: bool) -> Result<()> {
+    write_info(&mut io::stdout(), filepath, verbose, json).await
+}
+
+/// [`cmd_info`] writing its report to `out`.
+pub async fn write_info(
+    out: &mut dyn Write,
+    filepath: &Path,
+    verbose: bool,
+    json: bool,
+) -> Result<()> {
     // Open the Lance dataset
-    let uri = path_to_uri(filepath);
-    let dataset = Dataset::open(&uri)
-        .await
-        .context("Failed to open Lance dataset")?;
+    let dataset = open_dataset(filepath).await?;
+
+    let (storage, deletions) = if verbose {
+        (
+            Some(storage_report(&dataset).await?),
+            Some(deletion_report(&dataset).await?),
+        )
+    } else {
+        (None, None)
+    };
+    if json {
+        let report = info_json(&dataset, filepath, storage.as_ref(), deletions.as_ref()).await?;
+        writeln!(out, "{}", serde_json::to_string_pretty(&report)?)?;
+        return Ok(());
+    }
+
+    writeln!(out, "=== Lance File Info ===")?;
+    writeln!(out, "Path: {}", filepath.display())?;
 
     let schema = dataset.schema();
-    let count = dataset.count_rows(None).await;
+    let count = dataset.count_rows(None).await?;
     let version = dataset.version();
 
-    println!("Version: {}", version.version);
-    println!("Rows: {:?}", count);
+    writeln!(out, "Version: {}", version.version)?;
+    writeln!(out, "Rows: {count}")?;
+
+    for line in layout_report(&dataset).await? {
+        writeln!(out, "{line}")?;
+    }
+    if let Some(dims) = metadata_dims(&dataset).await? {
+        if dims.mismatch() {
+            log::warn!(
+                "cmd_info: metadata declares n_rows={}, n_cols={} but the data is {}×{}",
+                dims.declared.0,
+                dims.declared.1,
+                dims.actual.0,
+                dims.actual.1
+            );
+        }
+        writeln!(out, "{}", dims.line())?;
+    }
+    let unsupported = unsupported_report(&dataset).await?;
+    if !unsupported.is_empty() {
+        let names: Vec<String> = unsupported
+            .iter()
+            .map(|c| format!("{} ({})", c.name, c.kind))
+            .collect();
+        writeln!(
+            out,
+            "Unsupported columns (hidden in the viewer, U shows them): {}",
+            names.join(", ")
+        )?;
+    }
 
-    println!("\nSchema:");
+    writeln!(out, "\nSchema:")?;
     for idx in schema.field_ids() {
         let f = schema.field_by_id(idx);
-        println!(" - {} : {:?}", idx, f);
+        writeln!(out, " - {} : {:?}", idx, f)?;
+    }
+
+    // Same listing as the `M` popup in the viewer.
+    let entries = schema_metadata_entries(&ArrowSchema::from(schema));
+    let (schema_level, field_level): (Vec<_>, Vec<_>) =
+        entries.iter().partition(|e| e.scope == "schema");
+
+    writeln!(out, "\nSchema metadata:")?;
+    if schema_level.is_empty() {
+        writeln!(out, " (none)")?;
+    }
+    for e in schema_level {
+        writeln!(out, " - {} = {}", e.key, e.value)?;
+    }
+
+    writeln!(out, "\nField metadata:")?;
+    if field_level.is_empty() {
+        writeln!(out, " (none)")?;
+    }
+    for e in field_level {
+        writeln!(out, " - {}.{} = {}", e.scope, e.key, e.value)?;
+    }
+
+    if let Some(storage) = &storage {
+        writeln!(out)?;
+        for line in storage_lines(storage) {
+            writeln!(out, "{line}")?;
+        }
+    }
+    if let Some(deletions) = &deletions {
+        writeln!(out)?;
+        for line in deletion_lines(deletions) {
+            writeln!(out, "{line}")?;
+        }
     }
 
     Ok(())
 }
+
+/// The `Storage` section of `info --verbose`.
+pub(crate) fn storage_lines(storage: &StorageReport) -> Vec<String> {
+    let mut out = vec![format!(
+        "Storage (file format {}, {} fragment(s), {} data file(s)){}:",
+        if storage.file_versions.is_empty() {
+            "-".to_string()
+        } else {
+            storage.file_versions.join(", ")
+        },
+        storage.fragments,
+        storage.data_files,
+        if storage.estimated {
+            " — ESTIMATED from field widths × rows; legacy files record no per-column sizes"
+        } else {
+            ""
+        }
+    )];
+    let name_width = storage
+        .columns
+        .iter()
+        .map(|c| c.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("column".len());
+    out.push(format!(
+        "  {:<name_width$}  {:>10}  {:>6}  encoding",
+        "column", "bytes", "pages"
+    ));
+    for c in &storage.columns {
+        out.push(format!(
+            "  {:<name_width$}  {:>10}  {:>6}  {}",
+            c.name,
+            format_bytes(c.bytes),
+            c.pages.map_or("-".to_string(), |p| p.to_string()),
+            if c.encodings.is_empty() {
+                "-".to_string()
+            } else {
+                c.encodings.join(", ")
+            }
+        ));
+    }
+
+    let total = storage.total_bytes();
+    out.push(format!("Largest columns (total {}):", format_bytes(total)));
+    for (rank, c) in storage.largest().into_iter().enumerate() {
+        let share = if total == 0 {
+            0.0
+        } else {
+            c.bytes as f64 * 100.0 / total as f64
+        };
+        out.push(format!(
+            "  {}. {} {} ({share:.1}%)",
+            rank + 1,
+            c.name,
+            format_bytes(c.bytes)
+        ));
+    }
+    out
+}
+
+/// Machine-readable summary used by `info --json`.
+pub(crate) async fn info_json(
+    dataset: &Dataset,
+    filepath: &Path,
+    storage: Option<&StorageReport>,
+    deletions: Option<&DeletionReport>,
+) -> Result<Value> {
+    let arrow_schema = ArrowSchema::from(dataset.schema());
+    let fields: Vec<Value> = arrow_schema
+        .fields()
+        .iter()
+        .map(|f| {
+            json!({
+                "name": f.name(),
+                "type": f.data_type().to_string(),
+                "nullable": f.is_nullable(),
+                "metadata": f.metadata(),
+            })
+        })
+        .collect();
+    let (layout, mismatches) = dataset_layout(dataset).await?;
+    let columns = column_support(&arrow_schema, &unsupported_report(dataset).await?);
+    let mut report = json!({
+        "path": filepath.display().to_string(),
+        "version": dataset.version().version,
+        "rows": dataset.count_rows(None).await?,
+        "layout": format!("{layout:?}"),
+        "columns": columns,
+        "schema": fields,
+        "schema_metadata": arrow_schema.metadata(),
+    });
+    if !mismatches.is_empty() {
+        report["layout_mismatches"] = json!(mismatches);
+    }
+    if let Some(dims) = metadata_dims(dataset).await? {
+        report["metadata_dims"] = json!({
+            "declared": [dims.declared.0, dims.declared.1],
+            "actual": [dims.actual.0, dims.actual.1],
+            "mismatch": dims.mismatch(),
+        });
+    }
+    if let Some(storage) = storage {
+        report["storage"] = serde_json::to_value(storage)?;
+        report["storage"]["largest"] = json!(
+            storage
+                .largest()
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+    if let Some(deletions) = deletions {
+        report["deletions"] = serde_json::to_value(deletions)?;
+        report["deletions"]["physical_rows"] = json!(deletions.physical_rows());
+        report["deletions"]["deleted_rows"] = json!(deletions.deleted_rows());
+        report["deletions"]["logical_rows"] = json!(deletions.logical_rows());
+    }
+    Ok(report)
+}
+
+/// The `Layout:` section of `info`: javelin's name for the dataset's shape,
+/// a hint on how to view it, its dimensions, or — for unrecognised
+/// layouts — which expectations failed.
+pub(crate) async fn layout_report(dataset: &Dataset) -> Result<Vec<String>> {
+    let schema = ArrowSchema::from(dataset.schema());
+    let (layout, mismatches) = dataset_layout(dataset).await?;
+    let rows = dataset.count_rows(None).await?;
+
+    let mut out = Vec::new();
+    match layout {
+        LanceLayout::DenseRowMajor => {
+            let width = match schema.field(0).data_type() {
+                DataType::FixedSizeList(_, w) => *w,
+                _ => 0,
+            };
+            out.push(format!(
+                "Layout: DenseRowMajor ({}: FixedSizeList<Float64>[{width}]) — try 'javelin display' or 'javelin head <N>'",
+                schema.field(0).name()
+            ));
+            out.push(format!("  Vector width: {width}"));
+            out.push(format!("  Matrix: {rows} × {width}"));
+        }
+        LanceLayout::SparseCoo => {
+            out.push(
+                "Layout: SparseCoo (row/col/value) — try 'javelin display' (v opens the connectivity view)"
+                    .to_string(),
+            );
+            match coo_shape(dataset).await? {
+                Some(((r, c), true)) => {
+                    out.push(format!("  Matrix: {r} × {c} (from schema metadata)"))
+                }
+                Some(((r, c), false)) => out.push(format!(
+                    "  Matrix: {r} × {c} (inferred from max indices; add rows/cols metadata to be exact)"
+                )),
+                None => out.push(
+                    "  Matrix: unknown (no rows/cols metadata and row/col do not fit in UInt32)"
+                        .to_string(),
+                ),
+            }
+            out.push(format!("  Non-zeros: {rows}"));
+        }
+        LanceLayout::Vector1D => {
+            let field = schema.field(0);
+            out.push(format!(
+                "Layout: Vector1D ({}: {}) — try 'javelin display' or 'javelin stats'",
+                field.name(),
+                field.data_type()
+            ));
+            out.push(format!("  Length: {rows}"));
+        }
+        LanceLayout::RaggedList => {
+            let field = schema.field(0);
+            out.push(format!(
+                "Layout: RaggedList ({}: {}) — try 'javelin display' or 'javelin head <N>'",
+                field.name(),
+                list_type_name(field.data_type())
+            ));
+            out.push(format!("  Rows: {rows} (variable length; expanded to col_* only when all rows share one length)"));
+        }
+        LanceLayout::SparsePerRow => {
+            let field = schema.field(0);
+            let children = sparse_struct_kind(field.data_type())
+                .map(|kind| kind.children().join("/"))
+                .unwrap_or_default();
+            out.push(format!(
+                "Layout: SparsePerRow ({}: Struct<{children}>) — try 'javelin display' (Enter expands a record, v opens the connectivity view)",
+                field.name()
+            ));
+            out.push(format!("  Records: {rows}"));
+        }
+        LanceLayout::Other => {
+            out.push(
+                "Layout: Other — shown as a plain table; no known layout matched:".to_string(),
+            );
+            out.extend(mismatches.into_iter().map(|m| format!("  - {m}")));
+        }
+    }
+    Ok(out)
+}
+
+/// Dimensions declared by `n_rows`/`n_cols` metadata columns and the
+/// actual shape of the data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MetadataDims {
+    pub declared: (usize, usize),
+    /// Rows without metadata-only rows × data columns (list widths counted)
+    pub actual: (usize, usize),
+}
+
+impl MetadataDims {
+    pub(crate) fn mismatch(&self) -> bool {
+        self.declared != self.actual
+    }
+
+    /// `Declared dims` line of `info`, with the actual shape on a mismatch.
+    pub(crate) fn line(&self) -> String {
+        let (r, c) = self.declared;
+        let mut line = format!("Declared dims (n_rows/n_cols): {r} × {c}");
+        if self.mismatch() {
+            let (ar, ac) = self.actual;
+            line.push_str(&format!(" (mismatch: actual {ar}×{ac})"));
+        }
+        line
+    }
+}
+
+/// Compare the `n_rows`/`n_cols` metadata columns with the data; `None`
+/// when the dataset has no such columns or they hold no values.
+pub(crate) async fn metadata_dims(dataset: &Dataset) -> Result<Option<MetadataDims>> {
+    let schema = ArrowSchema::from(dataset.schema());
+    if schema.field_with_name("n_rows").is_err() || schema.field_with_name("n_cols").is_err() {
+        return Ok(None);
+    }
+    let metadata_rows = dataset
+        .scan()
+        .filter("n_rows IS NOT NULL")?
+        .try_into_batch()
+        .await?;
+    let metadata_rows = BatchSource::from(metadata_rows);
+    let Some(declared) = declared_dims(&metadata_rows) else {
+        return Ok(None);
+    };
+    let rows = dataset.count_rows(None).await? - metadata_only_rows(&metadata_rows);
+    let cols = schema
+        .fields()
+        .iter()
+        .filter(|f| !is_metadata_column(f.name()) && f.name() != ROWID_COLUMN)
+        .map(|f| match f.data_type() {
+            DataType::FixedSizeList(_, w) => (*w).max(0) as usize,
+            _ => 1,
+        })
+        .sum();
+    Ok(Some(MetadataDims {
+        declared,
+        actual: (rows, cols),
+    }))
+}
+
+/// The dataset's layout, classified from a few leading rows, and — for
+/// `Other` — the layout expectations its schema fails.
+pub(crate) async fn dataset_layout(dataset: &Dataset) -> Result<(LanceLayout, Vec<String>)> {
+    let mismatches = layout_mismatches(&ArrowSchema::from(dataset.schema()));
+    if !mismatches.is_empty() {
+        return Ok((LanceLayout::Other, mismatches));
+    }
+    let sample = dataset
+        .scan()
+        .limit(Some(LAYOUT_SAMPLE_ROWS), None)?
+        .try_into_batch()
+        .await?;
+    Ok((detect_lance_layout(&sample), mismatches))
+}
+
+/// The columns the viewer's table hides as unsupported, from the schema it
+/// shows (dense vectors expanded into `col_*` features).
+pub(crate) async fn unsupported_report(dataset: &Dataset) -> Result<Vec<UnsupportedColumn>> {
+    let (layout, _) = dataset_layout(dataset).await?;
+    let schema = Arc::new(ArrowSchema::from(dataset.schema()));
+    let shown = normalize_for_display(&RecordBatch::new_empty(schema))?.schema();
+    Ok(hidden_columns(&shown, layout))
+}
+
+/// Shape of a SparseCoo dataset and whether it comes from the `rows`/`cols`
+/// schema metadata rather than the largest indices; `None` when neither
+/// gives one.
+pub(crate) async fn coo_shape(dataset: &Dataset) -> Result<Option<((usize, usize), bool)>> {
+    let schema = ArrowSchema::from(dataset.schema());
+    let md = schema.metadata();
+    let from_metadata = md
+        .get("rows")
+        .and_then(|r| r.parse::<usize>().ok())
+        .zip(md.get("cols").and_then(|c| c.parse::<usize>().ok()));
+    if let Some(shape) = from_metadata {
+        return Ok(Some((shape, true)));
+    }
+    Ok(coo_max_indices(dataset)
+        .await?
+        .map(|(r, c)| ((r as usize + 1, c as usize + 1), false)))
+}
+
+/// Largest `row` and `col` index over the whole dataset, or `None` when
+/// they do not fit in UInt32 or the dataset is empty.
+pub(crate) async fn coo_max_indices(dataset: &Dataset) -> Result<Option<(u32, u32)>> {
+    let mut scanner = dataset.scan();
+    scanner.project(&["row", "col"])?;
+    let mut stream = scanner.try_into_stream().await?;
+    let mut max: Option<(u32, u32)> = None;
+    while let Some(batch) = stream.try_next().await? {
+        let (Ok(row), Ok(col)) = (
+            coo_index_column(batch.column(0)),
+            coo_index_column(batch.column(1)),
+        ) else {
+            return Ok(None);
+        };
+        if row.is_empty() {
+            continue;
+        }
+        let r = arrow::compute::max(&row).unwrap_or(0);
+        let c = arrow::compute::max(&col).unwrap_or(0);
+        max = Some(match max {
+            Some((mr, mc)) => (mr.max(r), mc.max(c)),
+            None => (r, c),
+        });
+    }
+    Ok(max)
+}
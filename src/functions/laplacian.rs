@@ -0,0 +1,482 @@
+//! `laplacian`: structure checks and a spectrum estimate for a graph
+//! Laplacian stored as COO triplets.
+//!
+//! A combinatorial Laplacian `L = D − A` has zero row sums; a normalized one
+//! `I − D^-1/2 A D^-1/2` has a unit diagonal (zero for isolated nodes). Both
+//! have no positive off-diagonal entries. The extreme eigenvalues are
+//! estimated by power iteration with deflation, which is only attempted up
+//! to `--max-n` nodes.
+
+use anyhow::{Result, anyhow, bail};
+use arrow_array::{Array, Float64Array, RecordBatch, UInt32Array};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sprs::{CsMat, TriMat};
+use std::path::Path;
+
+use crate::datasets::open_dataset;
+use crate::display::display::display_spreadsheet_window;
+use crate::display::{SourceWindow, run_viewer};
+use crate::functions::batch_source::BatchSource;
+use crate::functions::export::Provenance;
+use crate::functions::functions::canonical_coo;
+use crate::functions::sparse_stats::load_coo;
+use crate::session::SessionStore;
+
+/// Largest node count `laplacian` estimates eigenvalues for by default.
+pub const DEFAULT_MAX_N: usize = 2000;
+
+/// Eigenvalues reported at each end of the spectrum.
+const EIGENVALUES: usize = 3;
+/// Allowed deviation of a row sum (or diagonal) from its expected value.
+const TOLERANCE: f64 = 1e-8;
+/// Residual `‖Lv − λv‖` at which power iteration stops.
+const RESIDUAL: f64 = 1e-9;
+const MAX_ITERATIONS: usize = 20_000;
+/// Rows listed when a structure check fails.
+const LISTED_ROWS: usize = 5;
+
+/// Which Laplacian the matrix is expected to be.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LaplacianMode {
+    /// `D − A`: row sums are zero
+    #[default]
+    Combinatorial,
+    /// `I − D^-1/2 A D^-1/2`: the diagonal is one
+    Normalized,
+}
+
+impl LaplacianMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            LaplacianMode::Combinatorial => "combinatorial",
+            LaplacianMode::Normalized => "normalized",
+        }
+    }
+}
+
+/// Extreme eigenvalues from power iteration, ascending.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrum {
+    pub smallest: Vec<f64>,
+    pub largest: Vec<f64>,
+    /// Every eigenpair reached the residual tolerance
+    pub converged: bool,
+}
+
+/// Structure checks of a Laplacian matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaplacianReport {
+    pub mode: LaplacianMode,
+    pub n: usize,
+    /// Stored entries after summing duplicates
+    pub nnz: usize,
+    /// Weighted degree of each node: minus the off-diagonal row sum
+    pub degrees: Vec<f64>,
+    /// Largest |row sum| (combinatorial) or |diagonal − 1| (normalized)
+    pub max_deviation: f64,
+    /// Rows whose row sum or diagonal is off by more than the tolerance
+    pub deviating_rows: Vec<usize>,
+    pub positive_off_diagonal: usize,
+    /// Why the spectrum was not estimated, e.g. `n` above `--max-n`
+    pub spectrum: Result<Spectrum, String>,
+}
+
+impl LaplacianReport {
+    /// Check `matrix` (square) as a `mode` Laplacian, estimating its
+    /// spectrum when it has at most `max_n` nodes.
+    pub fn new(matrix: &CsMat<f64>, mode: LaplacianMode, max_n: usize) -> Self {
+        let n = matrix.rows();
+        let mut degrees = vec![0.0; n];
+        let mut max_deviation: f64 = 0.0;
+        let mut deviating_rows = Vec::new();
+        let mut positive_off_diagonal = 0;
+
+        for (i, row) in matrix.outer_iterator().enumerate() {
+            let mut diagonal = 0.0;
+            let mut row_sum = 0.0;
+            for (j, &v) in row.iter() {
+                row_sum += v;
+                if i == j {
+                    diagonal += v;
+                } else {
+                    degrees[i] -= v;
+                    if v > 0.0 {
+                        positive_off_diagonal += 1;
+                    }
+                }
+            }
+            let deviation = match mode {
+                LaplacianMode::Combinatorial => row_sum.abs(),
+                // Isolated nodes have a zero row, diagonal included.
+                LaplacianMode::Normalized if degrees[i] == 0.0 && diagonal == 0.0 => 0.0,
+                LaplacianMode::Normalized => (diagonal - 1.0).abs(),
+            };
+            max_deviation = max_deviation.max(deviation);
+            if deviation > TOLERANCE {
+                deviating_rows.push(i);
+            }
+        }
+
+        let spectrum = if n > max_n {
+            Err(format!(
+                "n = {n} exceeds --max-n {max_n}; spectrum not estimated"
+            ))
+        } else if n == 0 {
+            Err("empty matrix; no spectrum".to_string())
+        } else {
+            Ok(estimate_spectrum(matrix, EIGENVALUES.min(n)))
+        };
+
+        Self {
+            mode,
+            n,
+            nnz: matrix.nnz(),
+            degrees,
+            max_deviation,
+            deviating_rows,
+            positive_off_diagonal,
+            spectrum,
+        }
+    }
+
+    /// Degree and structure check lines, shared by the printed report and
+    /// the viewer's Laplacian panel.
+    pub fn structure_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{} Laplacian, n = {}, nnz = {}",
+            self.mode.label(),
+            self.n,
+            self.nnz
+        )];
+        if !self.degrees.is_empty() {
+            let min = self.degrees.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = self
+                .degrees
+                .iter()
+                .copied()
+                .fold(f64::NEG_INFINITY, f64::max);
+            let mean = self.degrees.iter().sum::<f64>() / self.n as f64;
+            let isolated = self.degrees.iter().filter(|d| **d == 0.0).count();
+            lines.push(format!(
+                "degree min {min:.4}  mean {mean:.4}  max {max:.4}  isolated {isolated}"
+            ));
+        }
+        let check = match self.mode {
+            LaplacianMode::Combinatorial => "row sums ≈ 0",
+            LaplacianMode::Normalized => "diagonal ≈ 1",
+        };
+        if self.deviating_rows.is_empty() {
+            lines.push(format!(
+                "{check}: ok (max deviation {:.2e})",
+                self.max_deviation
+            ));
+        } else {
+            let listed: Vec<String> = self
+                .deviating_rows
+                .iter()
+                .take(LISTED_ROWS)
+                .map(usize::to_string)
+                .collect();
+            let more = if self.deviating_rows.len() > LISTED_ROWS {
+                ", …"
+            } else {
+                ""
+            };
+            lines.push(format!(
+                "{check}: FAILED in {} row(s) [{}{more}] (max deviation {:.4e})",
+                self.deviating_rows.len(),
+                listed.join(", "),
+                self.max_deviation
+            ));
+        }
+        lines.push(if self.positive_off_diagonal == 0 {
+            "positive off-diagonal entries: none".to_string()
+        } else {
+            format!(
+                "positive off-diagonal entries: {} (not a Laplacian)",
+                self.positive_off_diagonal
+            )
+        });
+        lines
+    }
+
+    /// Eigenvalue estimate lines, for the printed report and the viewer's
+    /// Spectrum panel.
+    pub fn spectrum_lines(&self) -> Vec<String> {
+        let spectrum = match &self.spectrum {
+            Ok(spectrum) => spectrum,
+            Err(reason) => return vec![reason.clone()],
+        };
+        let list = |values: &[f64]| {
+            values
+                .iter()
+                .map(|v| format!("{v:.6}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut lines = vec![
+            format!("smallest: {}", list(&spectrum.smallest)),
+            format!("largest:  {}", list(&spectrum.largest)),
+        ];
+        if !spectrum.converged {
+            lines.push(format!(
+                "power iteration did not converge in {MAX_ITERATIONS} steps; values are approximate"
+            ));
+        }
+        lines
+    }
+}
+
+/// `y = m · x`
+fn multiply(m: &CsMat<f64>, x: &[f64], y: &mut [f64]) {
+    for (i, row) in m.outer_iterator().enumerate() {
+        y[i] = row.iter().map(|(j, &v)| v * x[j]).sum();
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Scale `v` to unit length; false if it is (numerically) zero.
+fn normalize(v: &mut [f64]) -> bool {
+    let norm = dot(v, v).sqrt();
+    if norm < f64::EPSILON {
+        return false;
+    }
+    v.iter_mut().for_each(|x| *x /= norm);
+    true
+}
+
+/// Remove the components of `v` along the (orthonormal) `basis`.
+fn deflate(v: &mut [f64], basis: &[Vec<f64>]) {
+    for b in basis {
+        let p = dot(v, b);
+        v.iter_mut().zip(b).for_each(|(x, y)| *x -= p * y);
+    }
+}
+
+/// The `k` eigenpairs of largest magnitude of `shift·I − m` (plain `m` when
+/// `shift` is `None`), largest first, by power iteration orthogonal to the
+/// pairs already found. The second value is false if any pair hit the
+/// iteration limit.
+fn power_iteration(m: &CsMat<f64>, k: usize, shift: Option<f64>) -> (Vec<f64>, bool) {
+    let n = m.rows();
+    let mut basis: Vec<Vec<f64>> = Vec::with_capacity(k);
+    let mut values = Vec::with_capacity(k);
+    let mut converged = true;
+    let mut mv = vec![0.0; n];
+
+    // Seeded random start vectors keep the estimate reproducible while
+    // avoiding the symmetries of structured (path, ring) graphs.
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..k {
+        let mut v: Vec<f64> = (0..n).map(|_| rng.random::<f64>() - 0.5).collect();
+        deflate(&mut v, &basis);
+        if !normalize(&mut v) {
+            break;
+        }
+        let mut lambda = 0.0;
+        let mut done = false;
+        for _ in 0..MAX_ITERATIONS {
+            multiply(m, &v, &mut mv);
+            if let Some(s) = shift {
+                mv.iter_mut().zip(&v).for_each(|(y, x)| *y = s * x - *y);
+            }
+            deflate(&mut mv, &basis);
+            lambda = dot(&v, &mv);
+            let residual = mv
+                .iter()
+                .zip(&v)
+                .map(|(y, x)| (y - lambda * x).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            if residual < RESIDUAL || !normalize(&mut mv) {
+                done = true;
+                break;
+            }
+            std::mem::swap(&mut v, &mut mv);
+        }
+        converged &= done;
+        values.push(lambda);
+        basis.push(v);
+    }
+    (values, converged)
+}
+
+/// Smallest and largest `k` eigenvalues of the symmetric positive
+/// semi-definite `m`: the largest directly, the smallest as the largest of
+/// `λmax·I − m`.
+fn estimate_spectrum(m: &CsMat<f64>, k: usize) -> Spectrum {
+    let (mut largest, converged_top) = power_iteration(m, k, None);
+    let lambda_max = largest.first().copied().unwrap_or(0.0);
+    let (shifted, converged_bottom) = power_iteration(m, k, Some(lambda_max));
+    let smallest = shifted.iter().map(|mu| lambda_max - mu).collect();
+    largest.reverse();
+    Spectrum {
+        smallest,
+        largest,
+        converged: converged_top && converged_bottom,
+    }
+}
+
+/// Square CSR matrix of a SparseCoo batch; duplicate triplets are summed and
+/// null values skipped. The size comes from the `rows`/`cols` schema
+/// metadata when present, otherwise from the largest index.
+pub(crate) fn coo_batch_to_csr(batch: &RecordBatch) -> Result<CsMat<f64>> {
+    let batch = &canonical_coo(batch)?;
+    let column = |name: &str| {
+        batch
+            .column_by_name(name)
+            .ok_or_else(|| anyhow!("COO batch has no '{name}' column"))
+    };
+    let row = column("row")?
+        .as_any()
+        .downcast_ref::<UInt32Array>()
+        .ok_or_else(|| anyhow!("row must be UInt32"))?;
+    let col = column("col")?
+        .as_any()
+        .downcast_ref::<UInt32Array>()
+        .ok_or_else(|| anyhow!("col must be UInt32"))?;
+    let value = column("value")?
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .ok_or_else(|| anyhow!("value must be Float64"))?;
+
+    let schema = batch.schema();
+    let md = schema.metadata();
+    let dim = |key: &str, indices: &UInt32Array| {
+        md.get(key)
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or_else(|| indices.iter().flatten().max().map_or(0, |m| m as usize + 1))
+    };
+    let (n_rows, n_cols) = (dim("rows", row), dim("cols", col));
+    if n_rows != n_cols {
+        bail!("a Laplacian must be square, found {n_rows}×{n_cols}");
+    }
+
+    let mut tri = TriMat::new((n_rows, n_cols));
+    for i in 0..row.len() {
+        if row.is_null(i) || col.is_null(i) || value.is_null(i) {
+            continue;
+        }
+        let (r, c) = (row.value(i) as usize, col.value(i) as usize);
+        if r >= n_rows || c >= n_cols {
+            bail!("entry ({r}, {c}) is outside the {n_rows}×{n_cols} matrix");
+        }
+        tri.add_triplet(r, c, value.value(i));
+    }
+    Ok(tri.to_csr())
+}
+
+/// Check the dataset as a `mode` Laplacian and print the report, or show it
+/// in the Structure section of the COO viewer with `tui`.
+pub async fn cmd_laplacian(
+    filepath: &Path,
+    mode: LaplacianMode,
+    max_n: usize,
+    tui: bool,
+) -> Result<()> {
+    let dataset = open_dataset(filepath).await?;
+    let batch = load_coo(&dataset, true).await?;
+    let report = LaplacianReport::new(&coo_batch_to_csr(&batch)?, mode, max_n);
+
+    if tui {
+        let source = SourceWindow::whole(batch.num_rows());
+        let data = BatchSource::from(batch);
+        let provenance = Provenance::new(filepath, "laplacian --tui");
+        return run_viewer(move || {
+            // COO triples are not bookmarked, so nothing is saved
+            let session = SessionStore::disabled();
+            display_spreadsheet_window(
+                &data,
+                source,
+                Vec::new(),
+                Some(&report),
+                None,
+                None,
+                &session,
+                &provenance,
+                None,
+            )
+        })
+        .await;
+    }
+
+    println!("=== Laplacian structure ===");
+    for line in report.structure_lines() {
+        println!("{line}");
+    }
+    println!();
+    println!("=== Spectrum (power iteration) ===");
+    for line in report.spectrum_lines() {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    /// Combinatorial Laplacian of the path graph on `n` nodes.
+    fn path_laplacian(n: usize) -> CsMat<f64> {
+        let mut tri = TriMat::new((n, n));
+        for i in 0..n - 1 {
+            tri.add_triplet(i, i + 1, -1.0);
+            tri.add_triplet(i + 1, i, -1.0);
+            tri.add_triplet(i, i, 1.0);
+            tri.add_triplet(i + 1, i + 1, 1.0);
+        }
+        tri.to_csr()
+    }
+
+    #[test]
+    fn path_graph_spectrum_matches_the_closed_form() {
+        // Eigenvalues of the path Laplacian are 2 − 2cos(kπ/n), k = 0..n.
+        let n = 10;
+        let exact: Vec<f64> = (0..n)
+            .map(|k| 2.0 - 2.0 * (k as f64 * PI / n as f64).cos())
+            .collect();
+        let report = LaplacianReport::new(&path_laplacian(n), LaplacianMode::Combinatorial, 100);
+
+        assert_eq!(report.n, n);
+        assert_eq!(report.degrees[0], 1.0);
+        assert_eq!(report.degrees[5], 2.0);
+        assert!(report.deviating_rows.is_empty());
+        assert_eq!(report.positive_off_diagonal, 0);
+
+        let spectrum = report.spectrum.as_ref().unwrap();
+        assert!(spectrum.converged);
+        for (got, want) in spectrum.smallest.iter().zip(&exact[..3]) {
+            assert!((got - want).abs() < 1e-6, "{got} vs {want}");
+        }
+        for (got, want) in spectrum.largest.iter().zip(&exact[n - 3..]) {
+            assert!((got - want).abs() < 1e-6, "{got} vs {want}");
+        }
+    }
+
+    #[test]
+    fn checks_flag_a_matrix_that_is_not_a_laplacian() {
+        let mut tri = TriMat::new((3, 3));
+        tri.add_triplet(0, 0, 1.0);
+        tri.add_triplet(0, 1, 0.5);
+        tri.add_triplet(1, 1, 1.0);
+        tri.add_triplet(1, 0, 0.5);
+        let report = LaplacianReport::new(&tri.to_csr(), LaplacianMode::Combinatorial, 2);
+
+        assert_eq!(report.deviating_rows, [0, 1]);
+        assert_eq!(report.positive_off_diagonal, 2);
+        assert!(report.spectrum.is_err());
+        let lines = report.structure_lines().join("\n");
+        assert!(lines.contains("FAILED in 2 row(s) [0, 1]"), "{lines}");
+        assert!(report.spectrum_lines()[0].contains("exceeds --max-n 2"));
+
+        // As a normalized Laplacian only the isolated node 2 has no unit
+        // diagonal, which is expected.
+        let report = LaplacianReport::new(&tri.to_csr(), LaplacianMode::Normalized, 2);
+        assert!(report.deviating_rows.is_empty());
+    }
+}
@@ -1,9 +1,54 @@
+pub mod batch_source;
+pub mod binary;
+#[cfg(feature = "tui")]
+pub mod clusters;
+pub mod constant_columns;
+#[cfg(feature = "tui")]
+pub mod convert;
+pub mod dedup;
+pub mod deletions;
+#[cfg(feature = "tui")]
+pub mod diff;
+#[cfg(feature = "tui")]
 pub mod display;
+pub mod encoded;
+pub mod export;
+pub mod fingerprint;
+#[cfg(feature = "tui")]
+pub mod follow;
+pub mod format;
+pub mod frequencies;
+#[allow(clippy::module_inception)]
 pub mod functions;
 pub mod generate;
+#[cfg(feature = "tui")]
 pub mod head;
+#[cfg(feature = "tui")]
+pub mod import;
 pub mod info;
+#[cfg(feature = "tui")]
+pub mod laplacian;
+#[cfg(feature = "tui")]
+pub mod names;
+#[cfg(feature = "tui")]
+pub mod neighbors;
+pub mod normalized;
+pub mod outliers;
+#[cfg(feature = "tui")]
+pub mod peek;
+#[cfg(feature = "tui")]
+pub mod plot;
+pub mod progress;
+pub mod reorder;
+#[cfg(feature = "tui")]
 pub mod sample;
+pub mod set_meta;
+pub mod sparse_stats;
+#[cfg(feature = "tui")]
 pub mod sparse_viz;
 pub mod stats;
+pub mod storage;
+#[cfg(feature = "tui")]
 pub mod tui;
+pub mod unsupported;
+pub mod validate;
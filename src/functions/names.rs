@@ -0,0 +1,89 @@
+//! `--names`: display names for the `col_*` feature columns, read from a
+//! sidecar file and stored as `feature_name` field metadata on the viewed
+//! batch. The dataset itself is never modified.
+
+use anyhow::{Context, Result};
+use arrow_array::RecordBatch;
+use std::path::Path;
+
+use crate::display::display::{collect_feature_cols, set_feature_names};
+
+/// Feature names from `path`: a JSON array of strings, or one name per line
+/// (trailing blank lines ignored).
+pub fn load_names(path: &Path) -> Result<Vec<String>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("cannot read names file {path:?}"))?;
+    if text.trim_start().starts_with('[') {
+        return serde_json::from_str(&text)
+            .with_context(|| format!("{path:?} is not a JSON array of strings"));
+    }
+    let mut names: Vec<String> = text.lines().map(|l| l.trim().to_string()).collect();
+    while names.last().is_some_and(String::is_empty) {
+        names.pop();
+    }
+    Ok(names)
+}
+
+/// Name the feature columns of `batch`, whose first feature is source
+/// feature `first_feature` of `total_features`. A names list of another
+/// length is applied as far as it goes, with a warning.
+pub(crate) fn apply_names(
+    batch: &RecordBatch,
+    names: &[String],
+    first_feature: usize,
+    total_features: usize,
+) -> Result<RecordBatch> {
+    if names.len() != total_features {
+        let applied = names.len().min(total_features);
+        log::warn!(
+            "names file has {} names for {total_features} feature columns; naming the first {applied}",
+            names.len()
+        );
+        eprintln!(
+            "warning: names file has {} names for {total_features} feature columns; naming the first {applied}",
+            names.len()
+        );
+    }
+    let renames: Vec<(usize, Option<String>)> = collect_feature_cols(batch)?
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, col_idx)| {
+            let name = names.get(first_feature + i)?;
+            Some((col_idx, Some(name.clone())))
+        })
+        .collect();
+    set_feature_names(batch, &renames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::dense_rows_to_batch;
+    use crate::display::display::display_name;
+    use crate::functions::functions::normalize_for_display;
+
+    #[test]
+    fn names_from_json_or_lines_and_prefix_on_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("names.json");
+        std::fs::write(&json, r#"["age", "height"]"#).unwrap();
+        let text = dir.path().join("names.txt");
+        std::fs::write(&text, "age\nheight\n\n").unwrap();
+        assert_eq!(load_names(&json).unwrap(), ["age", "height"]);
+        assert_eq!(load_names(&text).unwrap(), ["age", "height"]);
+
+        // Three features, two names: only col_0 and col_1 are renamed.
+        let raw = dense_rows_to_batch(&[vec![1.0, 2.0, 3.0]]).unwrap();
+        let batch = normalize_for_display(&raw).unwrap();
+        let named = apply_names(&batch, &load_names(&json).unwrap(), 0, 3).unwrap();
+        let schema = named.schema();
+        let shown: Vec<&str> = schema.fields().iter().map(|f| display_name(f)).collect();
+        assert_eq!(shown, ["age", "height", "col_2"]);
+        // The column names themselves are untouched.
+        assert_eq!(schema.field(0).name(), "col_0");
+
+        // A window starting at feature 1 takes names from index 1.
+        let named = apply_names(&batch, &load_names(&json).unwrap(), 1, 4).unwrap();
+        assert_eq!(display_name(named.schema().field(0)), "height");
+    }
+}
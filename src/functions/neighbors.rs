@@ -0,0 +1,326 @@
+//! Nearest rows of a dense (embedding) matrix to one of its rows, by cosine
+//! or euclidean distance. Distances are computed over the packed
+//! FixedSizeList values, never the `col_*` expansion.
+
+use anyhow::{Result, anyhow, bail};
+use arrow_array::Array;
+use futures::TryStreamExt;
+use lance::Dataset;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::datasets::open_dataset;
+use crate::display::LanceLayout;
+use crate::errors::ErrorCategory;
+use crate::functions::functions::DenseMatrix;
+use crate::functions::head::head_batch;
+use crate::functions::info::dataset_layout;
+use crate::functions::progress::Progress;
+
+/// Neighbours listed by `neighbors` and the viewer's `N` popup.
+pub const DEFAULT_NEIGHBORS: usize = 20;
+
+/// Distance between two rows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Metric {
+    /// 1 − cosine similarity
+    #[default]
+    Cosine,
+    /// L2 distance
+    Euclidean,
+}
+
+impl Metric {
+    pub fn label(self) -> &'static str {
+        match self {
+            Metric::Cosine => "cosine",
+            Metric::Euclidean => "euclidean",
+        }
+    }
+}
+
+/// One row and its distance to the query row.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbor {
+    pub row: usize,
+    pub distance: f64,
+}
+
+impl Eq for Neighbor {}
+
+impl Ord for Neighbor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.row.cmp(&other.row))
+    }
+}
+
+impl PartialOrd for Neighbor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Streaming top-`k` search: rows are fed in chunks and only the `k`
+/// closest are kept.
+pub(crate) struct NeighborSearch {
+    query: Vec<f64>,
+    query_norm: f64,
+    /// Source index of the query row, left out of the results
+    query_row: usize,
+    metric: Metric,
+    k: usize,
+    /// Max-heap of the best `k` so far, worst on top
+    best: BinaryHeap<Neighbor>,
+}
+
+impl NeighborSearch {
+    /// Fails for an all-zero query under cosine distance, which has no
+    /// direction to compare against.
+    pub(crate) fn new(query: &[f64], query_row: usize, metric: Metric, k: usize) -> Result<Self> {
+        let query_norm = norm(query);
+        if metric == Metric::Cosine && query_norm == 0.0 {
+            bail!("row {query_row} is all zeros; cosine distance is undefined");
+        }
+        Ok(Self {
+            query: query.to_vec(),
+            query_norm,
+            query_row,
+            metric,
+            k,
+            best: BinaryHeap::with_capacity(k + 1),
+        })
+    }
+
+    /// Distance from the query to `row`; `None` when undefined (zero vector
+    /// under cosine, NaN values).
+    fn distance(&self, row: &[f64]) -> Option<f64> {
+        let d = match self.metric {
+            Metric::Euclidean => self
+                .query
+                .iter()
+                .zip(row)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<f64>()
+                .sqrt(),
+            Metric::Cosine => {
+                let row_norm = norm(row);
+                if row_norm == 0.0 {
+                    return None;
+                }
+                let dot: f64 = self.query.iter().zip(row).map(|(a, b)| a * b).sum();
+                1.0 - dot / (self.query_norm * row_norm)
+            }
+        };
+        (!d.is_nan()).then_some(d)
+    }
+
+    /// Compare `rows` of `matrix`, whose first row is source row
+    /// `row_offset`. Null rows are skipped.
+    pub(crate) fn add(&mut self, matrix: &DenseMatrix, rows: Range<usize>, row_offset: usize) {
+        for r in rows {
+            let source_row = row_offset + r;
+            if source_row == self.query_row {
+                continue;
+            }
+            let Some(distance) = matrix.row(r).and_then(|row| self.distance(row)) else {
+                continue;
+            };
+            self.best.push(Neighbor {
+                row: source_row,
+                distance,
+            });
+            if self.best.len() > self.k {
+                self.best.pop();
+            }
+        }
+    }
+
+    /// Closest first; ties by row.
+    pub(crate) fn finish(self) -> Vec<Neighbor> {
+        self.best.into_sorted_vec()
+    }
+}
+
+fn norm(v: &[f64]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+/// Packed values of `row` of a matrix held as consecutive row blocks.
+fn matrix_row(matrices: &[DenseMatrix], mut row: usize) -> Option<&[f64]> {
+    for matrix in matrices {
+        if row < matrix.list.len() {
+            return matrix.row(row);
+        }
+        row -= matrix.list.len();
+    }
+    None
+}
+
+/// The `k` rows closest to `row` of a matrix held as consecutive row blocks
+/// (one per batch), calling `progress` with the rows compared so far after
+/// every chunk.
+pub(crate) fn matrix_neighbors(
+    matrices: &[DenseMatrix],
+    row: usize,
+    k: usize,
+    metric: Metric,
+    mut progress: impl FnMut(usize),
+) -> Result<Vec<Neighbor>> {
+    const CHUNK: usize = 4096;
+    let query =
+        matrix_row(matrices, row).ok_or_else(|| anyhow!("row {row} is null or has null values"))?;
+    let mut search = NeighborSearch::new(query, row, metric, k)?;
+    let mut offset = 0;
+    for matrix in matrices {
+        let rows = matrix.list.len();
+        for start in (0..rows).step_by(CHUNK) {
+            let end = (start + CHUNK).min(rows);
+            search.add(matrix, start..end, offset);
+            progress(offset + end);
+        }
+        offset += rows;
+    }
+    Ok(search.finish())
+}
+
+/// The `k` rows of a DenseRowMajor dataset closest to source row `row`, in
+/// one streaming pass.
+pub(crate) async fn dataset_neighbors(
+    dataset: &Dataset,
+    row: usize,
+    k: usize,
+    metric: Metric,
+    progress: bool,
+) -> Result<Vec<Neighbor>> {
+    let (layout, _) = dataset_layout(dataset).await?;
+    if layout != LanceLayout::DenseRowMajor {
+        return Err(ErrorCategory::UnsupportedLayout.error(format!(
+            "nearest rows need a DenseRowMajor dataset (FixedSizeList<Float64>), found {layout:?}"
+        )));
+    }
+    let total = dataset.count_rows(None).await?;
+    if row >= total {
+        return Err(ErrorCategory::InvalidArgs.error(format!(
+            "row {row} is out of range: dataset has {total} rows"
+        )));
+    }
+
+    let query = head_batch(dataset, 1, row).await?;
+    let query = DenseMatrix::from_batch(&query, 0..0)
+        .ok_or_else(|| anyhow!("row {row} is not a dense vector"))?;
+    let query = query
+        .row(0)
+        .ok_or_else(|| anyhow!("row {row} is null or has null values"))?;
+    let mut search = NeighborSearch::new(query, row, metric, k)?;
+
+    let mut stream = dataset.scan().try_into_stream().await?;
+    let mut bar = Progress::new("neighbors", total, progress);
+    let mut offset = 0;
+    while let Some(batch) = stream.try_next().await? {
+        let rows = batch.num_rows();
+        bar.inc(rows);
+        if let Some(matrix) = DenseMatrix::from_batch(&batch, 0..0) {
+            search.add(&matrix, 0..rows, offset);
+        }
+        offset += rows;
+    }
+    bar.finish();
+    Ok(search.finish())
+}
+
+/// Print the `k` rows nearest to `row`.
+pub async fn cmd_neighbors(filepath: &Path, row: usize, k: usize, metric: Metric) -> Result<()> {
+    let dataset = open_dataset(filepath).await?;
+    let neighbors = dataset_neighbors(&dataset, row, k, metric, true).await?;
+
+    println!(
+        "=== {} nearest rows to row {row} ({} distance) ===",
+        neighbors.len(),
+        metric.label()
+    );
+    let width = neighbors
+        .iter()
+        .map(|n| n.row.to_string().len())
+        .max()
+        .unwrap_or(0)
+        .max(3);
+    println!("{:>4}  {:>width$}  distance", "rank", "row");
+    for (rank, n) in neighbors.iter().enumerate() {
+        println!("{:>4}  {:>width$}  {:.6}", rank + 1, n.row, n.distance);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field};
+    use arrow_array::{FixedSizeListArray, Float64Array};
+    use std::sync::Arc;
+
+    fn matrix(rows: &[Option<[f64; 2]>]) -> DenseMatrix {
+        let values: Vec<f64> = rows.iter().flat_map(|r| r.unwrap_or([0.0; 2])).collect();
+        let nulls = rows.iter().map(Option::is_some).collect::<Vec<_>>();
+        let list = FixedSizeListArray::try_new(
+            Arc::new(Field::new("item", DataType::Float64, true)),
+            2,
+            Arc::new(Float64Array::from(values)),
+            Some(nulls.into()),
+        )
+        .unwrap();
+        DenseMatrix {
+            list,
+            features: 0..2,
+        }
+    }
+
+    #[test]
+    fn metrics_rank_rows_differently() {
+        let m = [matrix(&[
+            Some([1.0, 0.0]),
+            Some([10.0, 0.0]), // same direction, far away
+            Some([0.0, 1.5]),  // orthogonal, close
+            None,
+            Some([0.0, 0.0]), // no direction
+        ])];
+
+        let cosine = matrix_neighbors(&m, 0, 10, Metric::Cosine, |_| {}).unwrap();
+        let rows: Vec<_> = cosine.iter().map(|n| n.row).collect();
+        assert_eq!(rows, [1, 2]);
+        assert!(cosine[0].distance.abs() < 1e-12);
+
+        let euclidean = matrix_neighbors(&m, 0, 2, Metric::Euclidean, |_| {}).unwrap();
+        let rows: Vec<_> = euclidean.iter().map(|n| n.row).collect();
+        assert_eq!(rows, [4, 2]);
+        assert_eq!(euclidean[0].distance, 1.0);
+
+        assert!(matrix_neighbors(&m, 4, 2, Metric::Cosine, |_| {}).is_err());
+        assert!(matrix_neighbors(&m, 3, 2, Metric::Euclidean, |_| {}).is_err());
+    }
+
+    #[test]
+    fn split_matrices_rank_like_one() {
+        let rows: Vec<Option<[f64; 2]>> = (0..9)
+            .map(|r| (r != 4).then_some([r as f64 + 1.0, (r % 3) as f64]))
+            .collect();
+        let whole = [matrix(&rows)];
+        let split = [matrix(&rows[..3]), matrix(&rows[3..7]), matrix(&rows[7..])];
+        let mut progress = Vec::new();
+        for query in [0, 3, 8] {
+            for metric in [Metric::Cosine, Metric::Euclidean] {
+                let want = matrix_neighbors(&whole, query, 4, metric, |_| {}).unwrap();
+                let got =
+                    matrix_neighbors(&split, query, 4, metric, |done| progress.push(done)).unwrap();
+                let rows = |found: &[Neighbor]| found.iter().map(|n| n.row).collect::<Vec<_>>();
+                assert_eq!(rows(&got), rows(&want), "query {query}, {metric:?}");
+            }
+        }
+        // Progress counts rows across all the blocks.
+        assert_eq!(progress.last(), Some(&9));
+        assert!(matrix_neighbors(&split, 4, 2, Metric::Cosine, |_| {}).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! Reading a dataset the way the viewer sees it, for use as a library: the
+//! layout of the stored schema and the batch in display form (dense vectors
+//! expanded to scalar columns, COO triplets in canonical order). Available
+//! without the `tui` feature.
+
+use anyhow::Result;
+use arrow_array::RecordBatch;
+use std::path::Path;
+
+use crate::datasets::open_dataset;
+use crate::errors::ErrorCategory;
+use crate::functions::functions::{LanceLayout, detect_lance_layout, normalize_for_display};
+
+/// Which part of a dataset [`open_normalized_with`] reads.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    /// Read at most this many rows, from the first
+    pub limit: Option<usize>,
+    /// Read only these columns, in this order; the layout is detected on
+    /// them alone
+    pub columns: Option<Vec<String>>,
+}
+
+/// Open the dataset at `path`, detect its [`LanceLayout`] and return it with
+/// every row normalized for display.
+///
+/// ```no_run
+/// use javelin_tui::{LanceLayout, open_normalized};
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let (layout, batch) = open_normalized("data/dense.lance").await?;
+/// if layout == LanceLayout::DenseRowMajor {
+///     println!("{} rows of {} features", batch.num_rows(), batch.num_columns());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn open_normalized(path: impl AsRef<Path>) -> Result<(LanceLayout, RecordBatch)> {
+    open_normalized_with(path, &ReadOptions::default()).await
+}
+
+/// [`open_normalized`] reading only the rows and columns `options` select.
+///
+/// ```no_run
+/// use javelin_tui::{ReadOptions, open_normalized_with};
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let options = ReadOptions {
+///     limit: Some(100),
+///     columns: Some(vec!["vector".into()]),
+/// };
+/// let (layout, batch) = open_normalized_with("data/dense.lance", &options).await?;
+/// println!("{layout:?}: {} rows", batch.num_rows());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn open_normalized_with(
+    path: impl AsRef<Path>,
+    options: &ReadOptions,
+) -> Result<(LanceLayout, RecordBatch)> {
+    let dataset = open_dataset(path.as_ref()).await?;
+    let mut scanner = dataset.scan();
+    if let Some(columns) = &options.columns {
+        let schema = dataset.schema();
+        if let Some(missing) = columns.iter().find(|c| schema.field(c).is_none()) {
+            return Err(ErrorCategory::InvalidArgs.error(format!(
+                "{} has no column {missing:?}",
+                path.as_ref().display()
+            )));
+        }
+        scanner.project(columns)?;
+    }
+    if let Some(limit) = options.limit {
+        scanner.limit(Some(limit as i64), None)?;
+    }
+    let batch = scanner.try_into_batch().await?;
+    let layout = detect_lance_layout(&batch);
+    Ok((layout, normalize_for_display(&batch)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::{dense_rows_to_batch, path_to_uri, write_lance_batch};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dense_datasets_open_expanded_within_the_limit() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("dense.lance");
+        let rows: Vec<Vec<f64>> = (0..6).map(|r| vec![r as f64, 0.5, -1.0]).collect();
+        write_lance_batch(&path_to_uri(&path), dense_rows_to_batch(&rows).unwrap())
+            .await
+            .unwrap();
+
+        let (layout, batch) = open_normalized(&path).await.unwrap();
+        assert_eq!(layout, LanceLayout::DenseRowMajor);
+        assert_eq!((batch.num_rows(), batch.num_columns()), (6, 3));
+
+        let options = ReadOptions {
+            limit: Some(2),
+            columns: Some(vec!["vector".into()]),
+        };
+        let (layout, batch) = open_normalized_with(&path, &options).await.unwrap();
+        assert_eq!(layout, LanceLayout::DenseRowMajor);
+        assert_eq!(batch.num_rows(), 2);
+
+        let options = ReadOptions {
+            columns: Some(vec!["label".into()]),
+            ..ReadOptions::default()
+        };
+        let err = open_normalized_with(&path, &options).await.unwrap_err();
+        assert_eq!(ErrorCategory::of(&err), ErrorCategory::InvalidArgs);
+    }
+}
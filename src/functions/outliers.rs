@@ -0,0 +1,516 @@
+//! Rows whose values fall outside the usual range of their feature: below
+//! `Q1 − k·IQR` or above `Q3 + k·IQR`, or (`--method std`) further than
+//! `k·std` from the mean.
+//!
+//! Features are the numeric columns and every dimension of a numeric
+//! FixedSizeList column (named `col_<d>` as in the viewer). Their fences
+//! come from [`ColumnStats`]: the cached full-dataset stats for columns,
+//! cached the same way under `<column>[<d>]` for dimensions. Quartiles
+//! are interpolated within the stats' histogram, so IQR fences are
+//! approximate. A feature with no spread (zero IQR or std) is skipped.
+//!
+//! A value's severity is its distance past the fence in IQRs (or stds); a
+//! row's is that of its worst value.
+
+use anyhow::Result;
+use arrow::array::AsArray;
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float64Type, Schema};
+use arrow_array::{Array, RecordBatch};
+use futures::TryStreamExt;
+use lance::Dataset;
+use std::path::Path;
+
+use crate::cache::{ColumnStats, StatsCache};
+use crate::datasets::open_dataset;
+use crate::errors::ErrorCategory;
+use crate::functions::format::format_float;
+use crate::functions::progress::Progress;
+use crate::functions::stats::{ColumnStatsBuilder, cached_column_stats, numeric_values};
+
+/// Multiple of the spread beyond which a value is an outlier.
+pub const DEFAULT_K: f64 = 1.5;
+/// Rows listed by `outliers`, most severe first.
+pub const TOP_OUTLIERS: usize = 20;
+/// Triggering columns listed per row.
+const TRIGGERS_SHOWN: usize = 5;
+
+/// How the accepted range of a feature is measured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutlierMethod {
+    /// `[Q1 − k·IQR, Q3 + k·IQR]`
+    #[default]
+    Iqr,
+    /// `[mean − k·std, mean + k·std]`
+    Std,
+}
+
+impl OutlierMethod {
+    pub fn label(self) -> &'static str {
+        match self {
+            OutlierMethod::Iqr => "IQR",
+            OutlierMethod::Std => "std",
+        }
+    }
+}
+
+/// Accepted range of one feature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fence {
+    pub low: f64,
+    pub high: f64,
+    /// The IQR or std the fence is measured in
+    pub spread: f64,
+}
+
+impl Fence {
+    /// The fence of a feature with `stats`; `None` when it has no values or
+    /// no spread.
+    pub fn new(stats: &ColumnStats, method: OutlierMethod, k: f64) -> Option<Self> {
+        let (low, high, spread) = match method {
+            OutlierMethod::Iqr => {
+                let (q1, q3) = (quantile(stats, 0.25)?, quantile(stats, 0.75)?);
+                (q1, q3, q3 - q1)
+            }
+            OutlierMethod::Std => {
+                let (mean, std) = (stats.mean?, stats.std?);
+                (mean, mean, std)
+            }
+        };
+        (spread > 0.0 && spread.is_finite()).then_some(Fence {
+            low: low - k * spread,
+            high: high + k * spread,
+            spread,
+        })
+    }
+
+    /// How far `v` lies outside the fence, in spreads; `None` inside it.
+    /// NaN is never an outlier.
+    pub fn severity(&self, v: f64) -> Option<f64> {
+        if v < self.low {
+            Some((self.low - v) / self.spread)
+        } else if v > self.high {
+            Some((v - self.high) / self.spread)
+        } else {
+            None
+        }
+    }
+}
+
+/// Quantile `p` of the values summarised by `stats`, assuming values are
+/// spread evenly within each histogram bin; a column without a histogram
+/// has a single value.
+pub fn quantile(stats: &ColumnStats, p: f64) -> Option<f64> {
+    let (min, max) = (stats.min?, stats.max?);
+    let total: u64 = stats.histogram.iter().sum();
+    if total == 0 {
+        return Some(min);
+    }
+    let width = (max - min) / stats.histogram.len() as f64;
+    let target = p * total as f64;
+    let mut seen = 0.0;
+    for (i, &count) in stats.histogram.iter().enumerate() {
+        let count = count as f64;
+        if count > 0.0 && seen + count >= target {
+            return Some(min + (i as f64 + (target - seen) / count) * width);
+        }
+        seen += count;
+    }
+    Some(max)
+}
+
+/// A feature: a numeric column, or one dimension of a vector column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feature {
+    pub name: String,
+    column: usize,
+    dim: Option<usize>,
+}
+
+impl Feature {
+    /// One dimension of a vector column, rather than a column of its own.
+    pub fn is_dimension(&self) -> bool {
+        self.dim.is_some()
+    }
+}
+
+/// The features of `schema`, in column order.
+pub fn features(schema: &Schema) -> Vec<Feature> {
+    let vectors = schema
+        .fields()
+        .iter()
+        .filter(|f| numeric_list_width(f.data_type()).is_some())
+        .count();
+    let mut out = Vec::new();
+    for (column, field) in schema.fields().iter().enumerate() {
+        if let Some(width) = numeric_list_width(field.data_type()) {
+            out.extend((0..width).map(|d| Feature {
+                name: if vectors == 1 {
+                    format!("col_{d}")
+                } else {
+                    format!("{}[{d}]", field.name())
+                },
+                column,
+                dim: Some(d),
+            }));
+        } else if field.data_type().is_numeric() {
+            out.push(Feature {
+                name: field.name().clone(),
+                column,
+                dim: None,
+            });
+        }
+    }
+    out
+}
+
+fn numeric_list_width(data_type: &DataType) -> Option<usize> {
+    match data_type {
+        DataType::FixedSizeList(item, width) if item.data_type().is_numeric() => {
+            Some(*width as usize)
+        }
+        _ => None,
+    }
+}
+
+/// Values of `features` in `batch` as `Float64`, one vector per feature
+/// (a null row or value is `None`).
+fn feature_values(batch: &RecordBatch, features: &[Feature]) -> Result<Vec<Vec<Option<f64>>>> {
+    let mut out = Vec::with_capacity(features.len());
+    for feature in features {
+        let col = batch.column(feature.column);
+        let values = match feature.dim {
+            None => numeric_values(col)
+                .map(|v| v.iter().collect())
+                .unwrap_or_else(|| vec![None; batch.num_rows()]),
+            Some(d) => {
+                let list = col.as_fixed_size_list();
+                let items = cast(list.values(), &DataType::Float64)?;
+                let items = items.as_primitive::<Float64Type>();
+                (0..list.len())
+                    .map(|r| {
+                        let i = list.value_offset(r) as usize + d;
+                        (list.is_valid(r) && items.is_valid(i)).then(|| items.value(i))
+                    })
+                    .collect()
+            }
+        };
+        out.push(values);
+    }
+    Ok(out)
+}
+
+/// Full-dataset [`ColumnStats`] of every feature, from `cache` where
+/// possible; dimensions are computed in two streaming passes when missing.
+pub async fn feature_stats(
+    dataset: &Dataset,
+    filepath: &Path,
+    cache: &StatsCache,
+    features: &[Feature],
+    progress: bool,
+) -> Result<Vec<ColumnStats>> {
+    let columns = cached_column_stats(dataset, filepath, cache, progress).await?;
+    let version = dataset.version().version;
+    let key = |f: &Feature| format!("{}[{}]", columns[f.column].0, f.dim.unwrap_or_default());
+
+    let mut found: Vec<Option<ColumnStats>> = features
+        .iter()
+        .map(|f| match f.dim {
+            None => Some(columns[f.column].1.clone()),
+            Some(_) => cache.get(filepath, version, &key(f)),
+        })
+        .collect();
+    let missing: Vec<Feature> = features
+        .iter()
+        .zip(&found)
+        .filter(|(_, s)| s.is_none())
+        .map(|(f, _)| f.clone())
+        .collect();
+    if !missing.is_empty() {
+        log::debug!("stats cache: computing {} dimension(s)", missing.len());
+        let mut builders = vec![ColumnStatsBuilder::default(); missing.len()];
+        let total = dataset.count_rows(None).await?;
+        for pass in 1..=2 {
+            let binned: Vec<bool> = match pass {
+                1 => vec![true; missing.len()],
+                _ => builders.iter_mut().map(|b| b.needs_histogram()).collect(),
+            };
+            if !binned.contains(&true) {
+                break;
+            }
+            let mut bar = Progress::new(format!("stats pass {pass}/2"), total, progress);
+            let mut stream = dataset.scan().try_into_stream().await?;
+            while let Some(batch) = stream.try_next().await? {
+                bar.inc(batch.num_rows());
+                let values = feature_values(&batch, &missing)?;
+                for ((b, values), _) in builders
+                    .iter_mut()
+                    .zip(values)
+                    .zip(&binned)
+                    .filter(|(_, binned)| **binned)
+                {
+                    for v in values {
+                        match (pass, v) {
+                            (1, Some(v)) => b.push(v),
+                            (1, None) => b.add_nulls(1),
+                            (_, Some(v)) => b.bin(v),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            bar.finish();
+        }
+        for (feature, builder) in missing.iter().zip(builders) {
+            let stats = builder.finish();
+            if let Err(e) = cache.put(filepath, version, &key(feature), &stats) {
+                log::warn!("stats cache: could not store {}: {e:#}", key(feature));
+            }
+            let idx = features.iter().position(|f| f == feature).unwrap();
+            found[idx] = Some(stats);
+        }
+    }
+    Ok(found.into_iter().map(Option::unwrap).collect())
+}
+
+/// One value outside its feature's fence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trigger {
+    pub feature: String,
+    pub value: f64,
+    pub severity: f64,
+}
+
+/// A row with at least one outlying value; triggers most severe first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlierRow {
+    pub row: u64,
+    pub severity: f64,
+    pub triggers: Vec<Trigger>,
+}
+
+/// What `outliers` reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlierReport {
+    pub method: OutlierMethod,
+    pub k: f64,
+    pub rows: u64,
+    /// Features with a fence
+    pub checked: usize,
+    /// Features without spread, left out
+    pub skipped: Vec<String>,
+    pub flagged_rows: u64,
+    /// The most severe rows, at most `top`
+    pub top: Vec<OutlierRow>,
+}
+
+impl OutlierReport {
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("Features checked: {}", self.checked)];
+        if !self.skipped.is_empty() {
+            lines.push(format!("Skipped (no spread): {}", self.skipped.join(", ")));
+        }
+        lines.push(format!(
+            "Rows flagged: {} of {}",
+            self.flagged_rows, self.rows
+        ));
+        if self.top.is_empty() {
+            return lines;
+        }
+        lines.push(format!(
+            "Most severe (in {}s past the fence):",
+            self.method.label()
+        ));
+        let width = self
+            .top
+            .iter()
+            .map(|r| r.row.to_string().len())
+            .max()
+            .unwrap_or(1);
+        for row in &self.top {
+            let mut triggers: Vec<String> = row
+                .triggers
+                .iter()
+                .take(TRIGGERS_SHOWN)
+                .map(|t| {
+                    format!(
+                        "{}={} ({:.2})",
+                        t.feature,
+                        format_float(t.value, 4),
+                        t.severity
+                    )
+                })
+                .collect();
+            if row.triggers.len() > TRIGGERS_SHOWN {
+                triggers.push(format!("+{} more", row.triggers.len() - TRIGGERS_SHOWN));
+            }
+            lines.push(format!(
+                "  row {:>width$}  {:>8.2}  {}",
+                row.row,
+                row.severity,
+                triggers.join(", ")
+            ));
+        }
+        lines
+    }
+}
+
+/// Flag the rows of `dataset` outside the fences of its features, keeping
+/// the `top` most severe.
+pub async fn find_outliers(
+    dataset: &Dataset,
+    filepath: &Path,
+    cache: &StatsCache,
+    method: OutlierMethod,
+    k: f64,
+    top: usize,
+    progress: bool,
+) -> Result<OutlierReport> {
+    let schema = Schema::from(dataset.schema());
+    let all = features(&schema);
+    if all.is_empty() {
+        return Err(ErrorCategory::UnsupportedLayout
+            .error("no numeric columns or vector columns to check for outliers"));
+    }
+    let stats = feature_stats(dataset, filepath, cache, &all, progress).await?;
+    let (mut checked, mut fences, mut skipped) = (Vec::new(), Vec::new(), Vec::new());
+    for (feature, stats) in all.into_iter().zip(&stats) {
+        match Fence::new(stats, method, k) {
+            Some(fence) => {
+                checked.push(feature);
+                fences.push(fence);
+            }
+            None => skipped.push(feature.name),
+        }
+    }
+
+    let mut report = OutlierReport {
+        method,
+        k,
+        rows: 0,
+        checked: checked.len(),
+        skipped,
+        flagged_rows: 0,
+        top: Vec::new(),
+    };
+    let total = dataset.count_rows(None).await?;
+    let mut bar = Progress::new("outliers", total, progress);
+    let mut stream = dataset.scan().try_into_stream().await?;
+    while let Some(batch) = stream.try_next().await? {
+        bar.inc(batch.num_rows());
+        let values = feature_values(&batch, &checked)?;
+        for r in 0..batch.num_rows() {
+            let mut triggers: Vec<Trigger> = checked
+                .iter()
+                .zip(&fences)
+                .zip(&values)
+                .filter_map(|((feature, fence), values)| {
+                    let value = values[r]?;
+                    Some(Trigger {
+                        feature: feature.name.clone(),
+                        value,
+                        severity: fence.severity(value)?,
+                    })
+                })
+                .collect();
+            let row = report.rows + r as u64;
+            if triggers.is_empty() {
+                continue;
+            }
+            report.flagged_rows += 1;
+            triggers.sort_by(|a, b| b.severity.total_cmp(&a.severity));
+            report.top.push(OutlierRow {
+                row,
+                severity: triggers[0].severity,
+                triggers,
+            });
+            if report.top.len() >= 2 * top.max(1) {
+                keep_most_severe(&mut report.top, top);
+            }
+        }
+        report.rows += batch.num_rows() as u64;
+    }
+    bar.finish();
+    keep_most_severe(&mut report.top, top);
+    Ok(report)
+}
+
+/// Sort `rows` most severe first (earlier rows first on ties) and keep `top`.
+fn keep_most_severe(rows: &mut Vec<OutlierRow>, top: usize) {
+    rows.sort_by(|a, b| b.severity.total_cmp(&a.severity).then(a.row.cmp(&b.row)));
+    rows.truncate(top);
+}
+
+/// `outliers`: print the rows outside `k` spreads of their features.
+pub async fn cmd_outliers(
+    filepath: &Path,
+    k: f64,
+    method: OutlierMethod,
+    top: usize,
+) -> Result<()> {
+    if !(k.is_finite() && k >= 0.0) {
+        return Err(
+            ErrorCategory::InvalidArgs.error(format!("--k must be a non-negative number, got {k}"))
+        );
+    }
+    let dataset = open_dataset(filepath).await?;
+    let report = find_outliers(
+        &dataset,
+        filepath,
+        &StatsCache::user(),
+        method,
+        k,
+        top,
+        true,
+    )
+    .await?;
+    println!(
+        "=== Outliers in {} (k = {} × {}) ===",
+        filepath.display(),
+        k,
+        method.label()
+    );
+    for line in report.lines() {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(min: f64, max: f64, histogram: Vec<u64>) -> ColumnStats {
+        ColumnStats {
+            count: histogram.iter().sum(),
+            null_count: 0,
+            min: Some(min),
+            max: Some(max),
+            mean: Some((min + max) / 2.0),
+            std: Some(if max > min { 1.0 } else { 0.0 }),
+            histogram,
+        }
+    }
+
+    #[test]
+    fn fences_come_from_interpolated_quartiles_and_skip_constants() {
+        // 40 values spread evenly over [0, 4).
+        let even = stats(0.0, 4.0, vec![10, 10, 10, 10]);
+        assert_eq!(quantile(&even, 0.25), Some(1.0));
+        assert_eq!(quantile(&even, 0.75), Some(3.0));
+        let fence = Fence::new(&even, OutlierMethod::Iqr, 1.5).unwrap();
+        assert_eq!((fence.low, fence.high, fence.spread), (-2.0, 6.0, 2.0));
+        assert_eq!(fence.severity(5.0), None);
+        assert_eq!(fence.severity(7.0), Some(0.5));
+        assert_eq!(fence.severity(-4.0), Some(1.0));
+        assert_eq!(fence.severity(f64::NAN), None);
+
+        let fence = Fence::new(&even, OutlierMethod::Std, 2.0).unwrap();
+        assert_eq!((fence.low, fence.high), (0.0, 4.0));
+
+        let constant = stats(3.0, 3.0, Vec::new());
+        assert_eq!(quantile(&constant, 0.75), Some(3.0));
+        assert_eq!(Fence::new(&constant, OutlierMethod::Iqr, 1.5), None);
+        assert_eq!(Fence::new(&constant, OutlierMethod::Std, 1.5), None);
+    }
+}
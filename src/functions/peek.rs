@@ -0,0 +1,369 @@
+//! `peek`: a one-screen summary of a dataset — its layout and dimensions,
+//! column types, nulls and the first rows — built from the same pieces as
+//! `info`, `stats` and the viewer's cell formatting. By default the column
+//! figures come from the leading rows only; `--full` reads everything.
+
+use anyhow::Result;
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::UInt32Type;
+use arrow_array::RecordBatch;
+use lance::Dataset;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::datasets::open_dataset;
+use crate::display::display::{display_name, format_value, right};
+use crate::display::{LanceLayout, ROWID_COLUMN};
+use crate::functions::functions::{canonical_coo, is_metadata_column, normalize_for_display};
+use crate::functions::head::head_batch;
+use crate::functions::info::{coo_shape, dataset_layout, layout_report, metadata_dims};
+use crate::functions::sparse_stats::{SparseStats, load_coo};
+use crate::functions::stats::format_data_type;
+
+/// Rows (or COO triples) the column figures are taken from without `--full`.
+pub(crate) const PEEK_SAMPLE_ROWS: usize = 10_000;
+/// Rows of the value preview.
+const PREVIEW_ROWS: usize = 5;
+/// Widest preview table line; columns past it are counted, not shown.
+const PREVIEW_WIDTH: usize = 100;
+/// Widest preview cell; longer values are cut with `…`.
+const PREVIEW_CELL_WIDTH: usize = 14;
+/// Layout lines kept, so an `Other` dataset's mismatch list stays short.
+const LAYOUT_LINES: usize = 5;
+/// Rows listed under `Top row degrees`.
+const TOP_DEGREES: usize = 5;
+
+/// Print a compact summary of the dataset; `full` computes the column
+/// figures over every row instead of the leading ones, and `json` prints
+/// the summary as one JSON object.
+pub async fn cmd_peek(filepath: &Path, json: bool, full: bool) -> Result<()> {
+    write_peek(&mut io::stdout(), filepath, json, full).await
+}
+
+/// [`cmd_peek`] writing its summary to `out`.
+pub async fn write_peek(
+    out: &mut dyn Write,
+    filepath: &Path,
+    json: bool,
+    full: bool,
+) -> Result<()> {
+    let dataset = open_dataset(filepath).await?;
+    let peek = Peek::read(&dataset, full).await?;
+    if json {
+        let mut report = peek.json();
+        report["path"] = json!(filepath.display().to_string());
+        writeln!(out, "{}", serde_json::to_string_pretty(&report)?)?;
+        return Ok(());
+    }
+
+    writeln!(out, "=== Peek: {} ===", filepath.display())?;
+    for line in peek.lines() {
+        writeln!(out, "{line}")?;
+    }
+    Ok(())
+}
+
+/// Sparse figures of a SparseCoo dataset.
+struct CooPeek {
+    shape: Option<(usize, usize)>,
+    /// Distinct coordinates with `--full`, stored triples otherwise
+    nnz: usize,
+    density: Option<f64>,
+    /// `(row, degree)`, largest first
+    top_degrees: Vec<(u32, usize)>,
+}
+
+/// What `peek` reports.
+struct Peek {
+    version: u64,
+    rows: usize,
+    layout: LanceLayout,
+    layout_lines: Vec<String>,
+    dims_line: Option<String>,
+    /// Rows the column figures were taken from
+    scanned: usize,
+    full: bool,
+    /// `(type, columns)` in schema order of first appearance
+    dtypes: Vec<(String, usize)>,
+    data_cols: usize,
+    nulls: usize,
+    preview_cols: Vec<String>,
+    preview_rows: Vec<Vec<String>>,
+    /// Data columns left out of the preview to fit its width
+    preview_hidden: usize,
+    coo: Option<CooPeek>,
+}
+
+impl Peek {
+    async fn read(dataset: &Dataset, full: bool) -> Result<Self> {
+        let rows = dataset.count_rows(None).await?;
+        let (layout, _) = dataset_layout(dataset).await?;
+        let mut layout_lines = layout_report(dataset).await?;
+        if layout_lines.len() > LAYOUT_LINES {
+            let cut = layout_lines.len() - (LAYOUT_LINES - 1);
+            layout_lines.truncate(LAYOUT_LINES - 1);
+            layout_lines.push(format!("  … {cut} more ('javelin info' lists them)"));
+        }
+        let dims_line = metadata_dims(dataset).await?.map(|dims| dims.line());
+
+        let batch = if layout == LanceLayout::SparseCoo && full {
+            load_coo(dataset, false).await?
+        } else if full {
+            dataset.scan().try_into_batch().await?
+        } else {
+            head_batch(dataset, PEEK_SAMPLE_ROWS, 0).await?
+        };
+        let coo = if layout == LanceLayout::SparseCoo {
+            Some(coo_peek(dataset, &batch, full, rows).await?)
+        } else {
+            None
+        };
+
+        let shown = normalize_for_display(&batch)?;
+        let schema = shown.schema();
+        let data: Vec<usize> = (0..schema.fields().len())
+            .filter(|&i| {
+                let name = schema.field(i).name();
+                !is_metadata_column(name) && name != ROWID_COLUMN
+            })
+            .collect();
+        let mut dtypes: Vec<(String, usize)> = Vec::new();
+        for &i in &data {
+            let name = format_data_type(schema.field(i).data_type());
+            match dtypes.iter_mut().find(|(t, _)| *t == name) {
+                Some((_, n)) => *n += 1,
+                None => dtypes.push((name, 1)),
+            }
+        }
+        let nulls = data.iter().map(|&i| shown.column(i).null_count()).sum();
+        let (preview_cols, preview_rows, preview_hidden) = preview(&shown, &data);
+
+        Ok(Self {
+            version: dataset.version().version,
+            rows,
+            layout,
+            layout_lines,
+            dims_line,
+            scanned: shown.num_rows(),
+            full,
+            dtypes,
+            data_cols: data.len(),
+            nulls,
+            preview_cols,
+            preview_rows,
+            preview_hidden,
+            coo,
+        })
+    }
+
+    /// `first 10000 rows`, or `all rows` with `--full` or a small dataset.
+    fn scope(&self) -> String {
+        if self.full || self.scanned >= self.rows {
+            "all rows".to_string()
+        } else {
+            format!("first {} rows", self.scanned)
+        }
+    }
+
+    fn null_fraction(&self) -> f64 {
+        let values = self.scanned * self.data_cols;
+        if values == 0 {
+            0.0
+        } else {
+            self.nulls as f64 / values as f64
+        }
+    }
+
+    fn dtype_summary(&self) -> String {
+        self.dtypes
+            .iter()
+            .map(|(t, n)| format!("{t} × {n}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut out = vec![format!("Version: {}  Rows: {}", self.version, self.rows)];
+        out.extend(self.layout_lines.iter().cloned());
+        out.extend(self.dims_line.clone());
+        out.push(format!(
+            "Columns: {} ({})",
+            self.data_cols,
+            self.dtype_summary()
+        ));
+        out.push(format!(
+            "Nulls: {:.2}% ({} of {} values, {})",
+            self.null_fraction() * 100.0,
+            self.nulls,
+            self.scanned * self.data_cols,
+            self.scope()
+        ));
+        if let Some(coo) = &self.coo {
+            out.push(match (coo.density, self.full) {
+                (Some(d), true) => format!("Density: {d:.6} ({} distinct non-zeros)", coo.nnz),
+                (Some(d), false) => {
+                    format!("Density: ≈{d:.6} (stored triples; --full counts distinct ones)")
+                }
+                (None, _) => "Density: unknown (matrix shape unknown)".to_string(),
+            });
+            let degrees: Vec<String> = coo
+                .top_degrees
+                .iter()
+                .map(|(row, degree)| format!("{row} ({degree})"))
+                .collect();
+            out.push(format!(
+                "Top row degrees ({}): {}",
+                self.scope(),
+                degrees.join(", ")
+            ));
+        }
+
+        out.push(format!(
+            "Preview (first {} rows{}):",
+            self.preview_rows.len(),
+            if self.preview_hidden > 0 {
+                format!(", {} more columns not shown", self.preview_hidden)
+            } else {
+                String::new()
+            }
+        ));
+        let widths: Vec<usize> = (0..self.preview_cols.len())
+            .map(|c| {
+                self.preview_rows
+                    .iter()
+                    .map(|r| r[c].chars().count())
+                    .chain([self.preview_cols[c].chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |cells: &[String]| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &w)| right(cell, w as u16))
+                .collect::<Vec<_>>()
+                .join("  ")
+        };
+        out.push(line(&self.preview_cols));
+        out.extend(self.preview_rows.iter().map(|r| line(r)));
+        out
+    }
+
+    fn json(&self) -> Value {
+        let dtypes: serde_json::Map<String, Value> = self
+            .dtypes
+            .iter()
+            .map(|(t, n)| (t.clone(), json!(n)))
+            .collect();
+        let mut report = json!({
+            "version": self.version,
+            "rows": self.rows,
+            "layout": format!("{:?}", self.layout),
+            "layout_report": self.layout_lines,
+            "declared_dims": self.dims_line,
+            "columns": self.data_cols,
+            "dtypes": dtypes,
+            "scanned_rows": self.scanned,
+            "full": self.full,
+            "nulls": self.nulls,
+            "null_fraction": self.null_fraction(),
+            "preview": {
+                "columns": self.preview_cols,
+                "rows": self.preview_rows,
+                "columns_not_shown": self.preview_hidden,
+            },
+        });
+        if let Some(coo) = &self.coo {
+            report["coo"] = json!({
+                "shape": coo.shape.map(|(r, c)| [r, c]),
+                "nnz": coo.nnz,
+                "density": coo.density,
+                "top_row_degrees": coo.top_degrees,
+            });
+        }
+        report
+    }
+}
+
+/// Density and row degrees of the COO `batch`: exact over all triples with
+/// `full`, otherwise the stored triple count over the matrix shape and the
+/// degrees among the leading triples.
+async fn coo_peek(
+    dataset: &Dataset,
+    batch: &RecordBatch,
+    full: bool,
+    rows: usize,
+) -> Result<CooPeek> {
+    let canonical = canonical_coo(batch)?;
+    let mut degrees: HashMap<u32, usize> = HashMap::new();
+    for &row in canonical.column(0).as_primitive::<UInt32Type>().values() {
+        *degrees.entry(row).or_default() += 1;
+    }
+    let mut top_degrees: Vec<(u32, usize)> = degrees.into_iter().collect();
+    top_degrees.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    top_degrees.truncate(TOP_DEGREES);
+
+    if full {
+        let stats = SparseStats::of_batch(&canonical)?;
+        return Ok(CooPeek {
+            shape: Some((stats.rows, stats.cols)),
+            nnz: stats.nnz,
+            density: Some(stats.density),
+            top_degrees,
+        });
+    }
+    let shape = coo_shape(dataset).await?.map(|(shape, _)| shape);
+    Ok(CooPeek {
+        shape,
+        nnz: rows,
+        density: shape
+            .filter(|&(r, c)| r > 0 && c > 0)
+            .map(|(r, c)| rows as f64 / (r as f64 * c as f64)),
+        top_degrees,
+    })
+}
+
+/// Header and the first [`PREVIEW_ROWS`] rows of the `data` columns of
+/// `batch`, formatted as the viewer's cells, with as many columns as fit in
+/// [`PREVIEW_WIDTH`] after a row number; also the number of columns cut.
+fn preview(batch: &RecordBatch, data: &[usize]) -> (Vec<String>, Vec<Vec<String>>, usize) {
+    let schema = batch.schema();
+    let n = batch.num_rows().min(PREVIEW_ROWS);
+    let cut = |text: String| {
+        if text.chars().count() > PREVIEW_CELL_WIDTH {
+            let head: String = text.chars().take(PREVIEW_CELL_WIDTH - 1).collect();
+            format!("{head}…")
+        } else {
+            text
+        }
+    };
+    let mut cols = vec!["#".to_string()];
+    let mut rows: Vec<Vec<String>> = (0..n).map(|r| vec![r.to_string()]).collect();
+    let mut width = cols[0].len().max(n.to_string().len());
+    let mut shown = 0;
+    for &c in data {
+        let name = cut(display_name(schema.field(c)).to_string());
+        let cells: Vec<String> = (0..n)
+            .map(|r| cut(format_value(batch.column(c), r)))
+            .collect();
+        let w = cells
+            .iter()
+            .chain([&name])
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or(0);
+        if width + 2 + w > PREVIEW_WIDTH {
+            break;
+        }
+        width += 2 + w;
+        cols.push(name);
+        for (row, cell) in rows.iter_mut().zip(cells) {
+            row.push(cell);
+        }
+        shown += 1;
+    }
+    (cols, rows, data.len() - shown)
+}
@@ -0,0 +1,117 @@
+//! `plot-lambdas`: histogram of a 1D Float64 dataset (eigenvalues, norms),
+//! full-screen or as plain text.
+
+use anyhow::{Result, bail};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Schema as ArrowSchema};
+use arrow_array::{Array, Float64Array};
+use futures::TryStreamExt;
+use lance::Dataset;
+use std::path::Path;
+
+use crate::datasets::open_dataset;
+use crate::display::display_histogram::{Histogram, display_histogram_interactive};
+use crate::display::{LanceLayout, ROWID_COLUMN, run_viewer};
+use crate::errors::ErrorCategory;
+use crate::functions::info::dataset_layout;
+use crate::functions::progress::Progress;
+
+/// Width of the longest bar in `--plain` output.
+const PLAIN_BAR_WIDTH: usize = 40;
+
+/// Show the histogram of the dataset's values with `bins` bins.
+pub async fn cmd_plot_lambdas(filepath: &Path, bins: usize, plain: bool) -> Result<()> {
+    let dataset = open_dataset(filepath).await?;
+    let (name, values) = load_vector_1d(&dataset, !plain).await?;
+
+    if plain {
+        print!("{}", histogram_text(&name, &values, bins));
+        return Ok(());
+    }
+    run_viewer(move || display_histogram_interactive(&name, values, bins)).await
+}
+
+/// Column name and non-null values of a Vector1D dataset, as f64.
+pub(crate) async fn load_vector_1d(
+    dataset: &Dataset,
+    progress: bool,
+) -> Result<(String, Vec<f64>)> {
+    let (layout, _) = dataset_layout(dataset).await?;
+    if layout != LanceLayout::Vector1D {
+        return Err(ErrorCategory::UnsupportedLayout.error(format!(
+            "plot-lambdas needs a 1D dataset (one numeric column), found {layout:?}"
+        )));
+    }
+    let schema = ArrowSchema::from(dataset.schema());
+    let Some(field) = schema.fields().iter().find(|f| f.name() != ROWID_COLUMN) else {
+        bail!("dataset has no columns");
+    };
+    let name = field.name().clone();
+
+    let total = dataset.count_rows(None).await?;
+    let mut scanner = dataset.scan();
+    scanner.project(&[name.as_str()])?;
+    let mut stream = scanner.try_into_stream().await?;
+    let mut bar = Progress::new("plot-lambdas", total, progress);
+    let mut values = Vec::with_capacity(total);
+    while let Some(batch) = stream.try_next().await? {
+        bar.inc(batch.num_rows());
+        let col = cast(batch.column(0), &DataType::Float64)?;
+        let col = col
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .expect("cast to Float64");
+        values.extend(col.iter().flatten());
+    }
+    bar.finish();
+    Ok((name, values))
+}
+
+/// One line per bin: `[lo, hi)`, count and a bar scaled to the largest bin.
+/// Degenerate inputs give a single explanatory line.
+pub(crate) fn histogram_text(name: &str, values: &[f64], bins: usize) -> String {
+    let hist = match Histogram::new(values, bins, false) {
+        Ok(hist) => hist,
+        Err(message) => return format!("Histogram of {name}: {message}\n"),
+    };
+    let mut out = format!(
+        "=== Histogram of {name} ({} values, {} bins) ===\n",
+        hist.total(),
+        hist.counts.len()
+    );
+    let max = hist.counts.iter().copied().max().unwrap_or(0).max(1);
+    let count_width = max.to_string().len();
+    for (i, &count) in hist.counts.iter().enumerate() {
+        let close = if i + 1 == hist.counts.len() { ']' } else { ')' };
+        let bar = "█".repeat((count as f64 / max as f64 * PLAIN_BAR_WIDTH as f64).round() as usize);
+        out.push_str(&format!(
+            "[{:>12.6}, {:>12.6}{close}  {count:>count_width$}  {bar}\n",
+            hist.edges[i],
+            hist.edges[i + 1]
+        ));
+    }
+    if hist.non_finite > 0 {
+        out.push_str(&format!("{} NaN/Inf values skipped\n", hist.non_finite));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_histogram_lists_bins_with_bars() {
+        let text = histogram_text("lambdas", &[0.0, 1.0, 1.5, 2.0, f64::NAN], 2);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "=== Histogram of lambdas (4 values, 2 bins) ===");
+        assert!(lines[1].starts_with("[    0.000000,     1.000000)  1  "));
+        assert!(lines[2].ends_with(&format!("]  3  {}", "█".repeat(40))));
+        assert_eq!(lines[3], "1 NaN/Inf values skipped");
+
+        assert_eq!(
+            histogram_text("lambdas", &[7.0; 3], 10),
+            "Histogram of lambdas: all 3 values equal 7; nothing to bin\n"
+        );
+    }
+}
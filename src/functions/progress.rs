@@ -0,0 +1,113 @@
+//! Single-line stderr progress for long dataset scans.
+//!
+//! Only drawn when stderr is a terminal, and only once a scan has run for
+//! [`FIRST_DRAW_AFTER`], so quick scans and piped output stay silent.
+
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+const FIRST_DRAW_AFTER: Duration = Duration::from_millis(300);
+const REDRAW_EVERY: Duration = Duration::from_millis(100);
+
+pub struct Progress {
+    label: String,
+    total: usize,
+    done: usize,
+    start: Instant,
+    last_draw: Option<Instant>,
+    enabled: bool,
+}
+
+impl Progress {
+    /// Progress over `total` rows; `enabled` is further gated on stderr being
+    /// a terminal.
+    pub fn new(label: impl Into<String>, total: usize, enabled: bool) -> Self {
+        Self {
+            label: label.into(),
+            total,
+            done: 0,
+            start: Instant::now(),
+            last_draw: None,
+            enabled: enabled && std::io::stderr().is_terminal(),
+        }
+    }
+
+    /// Record `rows` more rows read and redraw if due.
+    pub fn inc(&mut self, rows: usize) {
+        self.done += rows;
+        if !self.enabled {
+            return;
+        }
+        let now = Instant::now();
+        let due = match self.last_draw {
+            None => now - self.start >= FIRST_DRAW_AFTER,
+            Some(last) => now - last >= REDRAW_EVERY,
+        };
+        if due {
+            self.last_draw = Some(now);
+            let mut err = std::io::stderr();
+            let _ = write!(err, "\r\x1b[2K{}", self.line(now - self.start));
+            let _ = err.flush();
+        }
+    }
+
+    /// Erase the progress line, if one was drawn.
+    pub fn finish(&mut self) {
+        if self.last_draw.take().is_some() {
+            let mut err = std::io::stderr();
+            let _ = write!(err, "\r\x1b[2K");
+            let _ = err.flush();
+        }
+    }
+
+    fn line(&self, elapsed: Duration) -> String {
+        let secs = elapsed.as_secs_f64();
+        let rate = if secs > 0.0 {
+            self.done as f64 / secs
+        } else {
+            0.0
+        };
+        let percent = if self.total == 0 {
+            100.0
+        } else {
+            self.done as f64 * 100.0 / self.total as f64
+        };
+        let eta = if rate > 0.0 && self.done < self.total {
+            format!("{:.0}s", (self.total - self.done) as f64 / rate)
+        } else {
+            "-".to_string()
+        };
+        format!(
+            "{}: {}/{} rows ({percent:.0}%) {rate:.0} rows/s ETA {eta}",
+            self.label, self.done, self.total
+        )
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_reports_rate_and_eta() {
+        let mut p = Progress::new("stats", 1_000, false);
+        p.inc(250);
+        assert_eq!(
+            p.line(Duration::from_secs(5)),
+            "stats: 250/1000 rows (25%) 50 rows/s ETA 15s"
+        );
+        p.inc(750);
+        assert_eq!(
+            p.line(Duration::from_secs(10)),
+            "stats: 1000/1000 rows (100%) 100 rows/s ETA -"
+        );
+        // Disabled progress never draws, so finish has nothing to erase.
+        assert!(p.last_draw.is_none());
+    }
+}
@@ -0,0 +1,234 @@
+//! Symmetric reorderings of square sparse matrices, for now reverse
+//! Cuthill–McKee: `sparse-stats --reorder rcm` reports the bandwidth and
+//! profile before and after and can write the permutation out, and `r` in
+//! the COO viewer draws the matrix under it.
+//!
+//! RCM walks each connected component breadth-first from its lowest-degree
+//! node, visiting neighbours by ascending degree, and reverses the visit
+//! order. Components are taken by ascending (degree, index) of their first
+//! node and ties are broken by index throughout, so the ordering is
+//! deterministic; isolated nodes end up last.
+
+use anyhow::{Result, bail};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow_array::cast::AsArray;
+use arrow_array::types::UInt32Type;
+use arrow_array::{ArrayRef, RecordBatch, UInt32Array, UInt64Array};
+use std::sync::Arc;
+
+use crate::functions::functions::canonical_coo;
+use crate::functions::sparse_stats::{Band, SparseStats};
+
+/// Reorderings offered by `--reorder`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Reorder {
+    /// Reverse Cuthill–McKee
+    Rcm,
+}
+
+/// A symmetric permutation of a square COO matrix and its effect on the band.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reordering {
+    /// `order[new] = old` row/column index
+    pub order: Vec<usize>,
+    /// `position[old] = new`
+    pub position: Vec<usize>,
+    pub before: Band,
+    pub after: Band,
+}
+
+impl Reordering {
+    /// The RCM ordering of a square SparseCoo batch's structure (its pattern
+    /// symmetrized, the diagonal ignored).
+    pub fn rcm(batch: &RecordBatch) -> Result<Self> {
+        let stats = SparseStats::of_batch(batch)?;
+        if stats.rows != stats.cols {
+            bail!(
+                "reordering needs a square matrix, found {} × {}",
+                stats.rows,
+                stats.cols
+            );
+        }
+        let coords = coordinates(batch)?;
+        let order = rcm_order(stats.rows, coords.iter().copied());
+        let mut position = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            position[old] = new;
+        }
+        let after = Band::of_entries(
+            coords.iter().map(|&(r, c)| (position[r], position[c])),
+            stats.rows,
+        );
+        Ok(Self {
+            order,
+            position,
+            before: stats.band,
+            after,
+        })
+    }
+
+    /// `batch` with its rows and columns renumbered by the permutation; any
+    /// other columns and the schema metadata are kept.
+    pub fn apply(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let batch = canonical_coo(batch)?;
+        let renumber = |i: usize| -> ArrayRef {
+            let idx = batch.column(i).as_primitive::<UInt32Type>();
+            Arc::new(UInt32Array::from_iter(idx.iter().map(|v| {
+                v.map(|v| self.position.get(v as usize).map_or(v, |&p| p as u32))
+            })))
+        };
+        let mut cols = batch.columns().to_vec();
+        cols[0] = renumber(0);
+        cols[1] = renumber(1);
+        Ok(RecordBatch::try_new(batch.schema(), cols)?)
+    }
+
+    /// The permutation as a 1D dataset: row `new` holds the original index
+    /// `order[new]`.
+    pub fn to_batch(&self) -> Result<RecordBatch> {
+        let schema = Schema::new(vec![Field::new("order", DataType::UInt64, false)]);
+        let order = UInt64Array::from_iter_values(self.order.iter().map(|&o| o as u64));
+        Ok(RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(order)],
+        )?)
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        vec![
+            format!(
+                "Bandwidth: {} → {}",
+                self.before.bandwidth, self.after.bandwidth
+            ),
+            format!("Profile: {} → {}", self.before.profile, self.after.profile),
+        ]
+    }
+}
+
+fn coordinates(batch: &RecordBatch) -> Result<Vec<(usize, usize)>> {
+    let batch = canonical_coo(batch)?;
+    let row = batch.column(0).as_primitive::<UInt32Type>();
+    let col = batch.column(1).as_primitive::<UInt32Type>();
+    Ok(row
+        .values()
+        .iter()
+        .zip(col.values().iter())
+        .map(|(&r, &c)| (r as usize, c as usize))
+        .collect())
+}
+
+/// Reverse Cuthill–McKee order (`order[new] = old`) of the `n` nodes of the
+/// graph with an edge for each off-diagonal `(row, col)`.
+pub fn rcm_order(n: usize, coords: impl IntoIterator<Item = (usize, usize)>) -> Vec<usize> {
+    let mut adjacency = vec![Vec::new(); n];
+    for (r, c) in coords {
+        if r != c && r < n && c < n {
+            adjacency[r].push(c);
+            adjacency[c].push(r);
+        }
+    }
+    for neighbours in &mut adjacency {
+        neighbours.sort_unstable();
+        neighbours.dedup();
+    }
+    let by_degree = |v: &usize| (adjacency[*v].len(), *v);
+
+    let mut starts: Vec<usize> = (0..n).collect();
+    starts.sort_by_key(by_degree);
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    for start in starts {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut head = order.len();
+        order.push(start);
+        while let Some(&v) = order.get(head) {
+            head += 1;
+            let mut next: Vec<usize> = adjacency[v]
+                .iter()
+                .copied()
+                .filter(|&u| !visited[u])
+                .collect();
+            next.sort_by_key(by_degree);
+            for u in next {
+                visited[u] = true;
+                order.push(u);
+            }
+        }
+    }
+    order.reverse();
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::csr_to_coo_batch;
+    use sprs::TriMat;
+
+    /// The path 0 − 1 − … − (n−1) with its nodes renumbered by `label`.
+    fn scrambled_path(n: usize, label: impl Fn(usize) -> usize) -> RecordBatch {
+        let mut tri = TriMat::<f64>::new((n, n));
+        for i in 0..n {
+            tri.add_triplet(label(i), label(i), 2.0);
+            if i + 1 < n {
+                tri.add_triplet(label(i), label(i + 1), -1.0);
+                tri.add_triplet(label(i + 1), label(i), -1.0);
+            }
+        }
+        csr_to_coo_batch(&tri.to_csr()).unwrap()
+    }
+
+    #[test]
+    fn rcm_recovers_a_scrambled_path() {
+        // 7 is coprime to 20, so i ↦ 7i mod 20 is a permutation.
+        let batch = scrambled_path(20, |i| (7 * i) % 20);
+        let reordering = Reordering::rcm(&batch).unwrap();
+        assert!(reordering.before.bandwidth > 1);
+        assert_eq!(reordering.after.bandwidth, 1);
+        assert_eq!(reordering.after.profile, 3 * 20 - 2);
+
+        let reordered = reordering.apply(&batch).unwrap();
+        let stats = SparseStats::of_batch(&reordered).unwrap();
+        assert_eq!(stats.band, reordering.after);
+        assert_eq!(stats.nnz, SparseStats::of_batch(&batch).unwrap().nnz);
+
+        let exported = reordering.to_batch().unwrap();
+        let order = exported
+            .column(0)
+            .as_primitive::<arrow_array::types::UInt64Type>();
+        assert_eq!(order.len(), 20);
+        assert_eq!(order.value(0) as usize, reordering.order[0]);
+    }
+
+    #[test]
+    fn disconnected_graphs_are_ordered_deterministically() {
+        // Two paths (0-2-4, 1-3) and isolated nodes 5, 6.
+        let edges = [(0, 2), (2, 4), (1, 3)];
+        let order = rcm_order(7, edges);
+        assert_eq!(order, rcm_order(7, edges.iter().rev().copied()));
+
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..7).collect::<Vec<_>>());
+        // Isolated nodes start the walk, so they end up last.
+        assert_eq!(order, [3, 1, 4, 2, 0, 6, 5]);
+
+        let mut position = [0; 7];
+        for (new, &old) in order.iter().enumerate() {
+            position[old] = new;
+        }
+        let band = Band::of_entries(edges.iter().map(|&(r, c)| (position[r], position[c])), 7);
+        assert_eq!(band.bandwidth, 1);
+    }
+
+    #[test]
+    fn rectangular_matrices_are_refused() {
+        let mut tri = TriMat::<f64>::new((2, 3));
+        tri.add_triplet(0, 2, 1.0);
+        let batch = csr_to_coo_batch(&tri.to_csr()).unwrap();
+        assert!(Reordering::rcm(&batch).is_err());
+    }
+}
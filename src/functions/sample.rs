@@ -1,76 +1,114 @@
 use anyhow::Result;
-use arrow::compute::take;
-use arrow::datatypes::{DataType, Field, Schema};
+use arrow::compute::{concat_batches, take};
 use arrow_array::{ArrayRef, RecordBatch, UInt64Array};
+use futures::TryStreamExt;
 use lance::Dataset;
-use log::{debug, info};
-use std::path::PathBuf;
+use std::path::Path;
 use std::sync::Arc;
 
-use crate::datasets::path_to_uri;
+use crate::datasets::read_dataset;
 use crate::display::display::display_spreadsheet_interactive;
-use crate::functions::functions::normalize_for_display;
+use crate::display::run_viewer;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::export::{ExportOptions, Provenance, write_export};
+use crate::functions::functions::{DenseMatrix, attach_row_ids, normalize_for_display};
+use crate::functions::progress::Progress;
+use crate::session::SessionStore;
 
-/// Randomly sample `n_rows` rows from a Lance dataset and show them
-/// in the interactive spreadsheet viewer.
-pub async fn cmd_sample(filepath: &PathBuf, n_rows: usize) -> Result<()> {
-    use rand::rng;
-    use rand::seq::SliceRandom;
-
-    let uri = path_to_uri(filepath);
-    let dataset = Dataset::open(&uri).await?;
+/// Randomly sample `n_rows` rows from a Lance dataset, drawn with `seed`
+/// (a random one when `None`), and show them in the interactive spreadsheet
+/// viewer (with `visible_cols` feature columns at once when given), or write
+/// them to a new dataset with `export`.
+pub async fn cmd_sample(
+    filepath: &Path,
+    n_rows: usize,
+    seed: Option<u64>,
+    export: Option<&ExportOptions>,
+    visible_cols: Option<usize>,
+) -> Result<()> {
+    let seed = seed.unwrap_or_else(rand::random);
+    // Nothing sampled from an empty dataset opens the viewer's placeholder
+    let batch = read_dataset(filepath, |dataset| async move {
+        let total_rows = dataset.count_rows(None).await?;
+        let indices = sample_indices(total_rows, n_rows, seed);
+        sample_batch(&dataset, &indices).await
+    })
+    .await?;
+    let provenance = Provenance::new(filepath, format!("sample {n_rows}")).with_seed(seed);
 
-    // Count total rows
-    let total_rows = dataset.count_rows(None).await?;
-
-    if total_rows == 0 {
-        println!("No data to display");
+    if let Some(opts) = export {
+        let provenance = provenance.with_filter(opts.filter());
+        let summary = write_export(&batch, opts, &provenance).await?;
+        println!(
+            "{} sampled rows (seed {seed}) written to {}",
+            summary.rows,
+            opts.out.display()
+        );
+        if let Some(error) = summary.max_cast_error {
+            println!("Cast to Float32: max rounding error {error:.3e}");
+        }
         return Ok(());
     }
 
-    // Clamp to dataset size
-    let n = n_rows.min(total_rows);
+    let dense = DenseMatrix::from_batch(&batch, 0..usize::MAX);
+    let data = BatchSource::from(normalize_for_display(&batch)?);
+    let session = SessionStore::user(filepath);
+    run_viewer(move || {
+        display_spreadsheet_interactive(
+            &data,
+            dense.into_iter().collect(),
+            visible_cols,
+            &session,
+            &provenance,
+        )
+    })
+    .await
+}
+
+/// `n` distinct row indices out of `total` (all of them when `n >= total`),
+/// sorted; the same `seed` draws the same rows.
+pub(crate) fn sample_indices(total: usize, n: usize, seed: u64) -> Vec<u64> {
+    use rand::SeedableRng;
+    use rand::seq::SliceRandom;
 
-    // Generate random indices
-    let mut rng = rng();
-    let mut indices: Vec<i64> = (0..total_rows as i64).collect();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let mut indices: Vec<u64> = (0..total as u64).collect();
     indices.shuffle(&mut rng);
-    indices.truncate(n);
+    indices.truncate(n.min(total));
     indices.sort_unstable();
+    indices
+}
 
-    // Read all rows up to the max sampled index
-    let max_index = *indices.last().unwrap();
-    let mut scanner = dataset.scan();
-    let full_batch = scanner
-        .limit(Some(max_index + 1), None)?
-        .try_into_batch()
-        .await?;
+/// Rows at the sorted source `indices`, with the indices attached as a
+/// `_rowid` column so the viewer can show where each row came from.
+pub(crate) async fn sample_batch(dataset: &Dataset, indices: &[u64]) -> Result<RecordBatch> {
+    let Some(&max_index) = indices.last() else {
+        let schema = Arc::new(arrow::datatypes::Schema::from(dataset.schema()));
+        return attach_row_ids(&RecordBatch::new_empty(schema), Vec::new());
+    };
 
-    if full_batch.num_rows() == 0 {
-        println!("No data to display");
-        return Ok(());
+    // Read all rows up to the max sampled index, streaming so long prefixes
+    // can report progress.
+    let mut scanner = dataset.scan();
+    scanner.limit(Some(max_index as i64 + 1), None)?;
+    let schema = scanner.schema().await?;
+    let mut stream = scanner.try_into_stream().await?;
+    let mut batches = Vec::new();
+    let mut bar = Progress::new("sample", max_index as usize + 1, true);
+    while let Some(batch) = stream.try_next().await? {
+        bar.inc(batch.num_rows());
+        batches.push(batch);
     }
+    bar.finish();
+    let full_batch = concat_batches(&schema, &batches)?;
 
     // Take only the sampled rows
-    let index_array = Arc::new(arrow::array::UInt64Array::from(
-        indices.iter().map(|&i| i as u64).collect::<Vec<_>>(),
-    )) as ArrayRef;
-
+    let index_array = Arc::new(UInt64Array::from(indices.to_vec())) as ArrayRef;
     let mut sampled_columns = Vec::with_capacity(full_batch.num_columns());
     for col in full_batch.columns().iter() {
-        let taken = take(col.as_ref(), &index_array, None)?;
-        sampled_columns.push(taken);
+        sampled_columns.push(take(col.as_ref(), &index_array, None)?);
     }
-
-    // Create sampled batch with same schema
     let batch = RecordBatch::try_new(full_batch.schema(), sampled_columns)?;
 
-    if batch.num_rows() == 0 {
-        println!("No data to display");
-        return Ok(());
-    }
-
-    let batch = normalize_for_display(&batch)?;
-    display_spreadsheet_interactive(&batch)?;
-    Ok(())
+    attach_row_ids(&batch, indices.to_vec())
 }
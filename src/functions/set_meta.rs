@@ -0,0 +1,89 @@
+//! `set-meta`: set one schema-level metadata key, e.g. the `rows`/`cols` a
+//! writer left out, by committing a new dataset version. No data is
+//! rewritten; earlier versions keep their metadata.
+//!
+//! Only the latest version may be changed: a dataset checked out at an
+//! older version is refused rather than branched from.
+
+use anyhow::Result;
+use lance::Dataset;
+use std::path::Path;
+
+use crate::datasets::open_dataset;
+use crate::errors::ErrorCategory;
+
+/// Set schema metadata `key` to `value` on the dataset at `filepath` and
+/// print the version written.
+pub async fn cmd_set_meta(filepath: &Path, key: &str, value: &str) -> Result<()> {
+    let mut dataset = open_dataset(filepath).await?;
+    let version = set_schema_metadata(&mut dataset, key, value).await?;
+    println!(
+        "Set schema metadata {key} = {value} on {}: now at version {version}",
+        filepath.display()
+    );
+    Ok(())
+}
+
+/// Commit `key = value` into the schema metadata of `dataset`, which must be
+/// checked out at its latest version; returns the new version.
+pub(crate) async fn set_schema_metadata(
+    dataset: &mut Dataset,
+    key: &str,
+    value: &str,
+) -> Result<u64> {
+    validate_key(key)?;
+    let current = dataset.version().version;
+    let latest = dataset.latest_version_id().await?;
+    if current != latest {
+        return Err(ErrorCategory::InvalidArgs.error(format!(
+            "the dataset is opened at version {current}, not the latest ({latest}); \
+             metadata can only be set on the latest version"
+        )));
+    }
+    dataset.update_schema_metadata([(key, value)]).await?;
+    Ok(dataset.version().version)
+}
+
+/// `=` in the viewer's metadata popup: [`set_schema_metadata`] on the latest
+/// version of the dataset at `filepath`. Must run on a blocking thread of
+/// the runtime, as the viewer does.
+pub(crate) fn set_meta_from_viewer(filepath: &Path, key: &str, value: &str) -> Result<u64> {
+    tokio::runtime::Handle::current().block_on(async {
+        let mut dataset = open_dataset(filepath).await?;
+        set_schema_metadata(&mut dataset, key, value).await
+    })
+}
+
+/// Metadata keys are non-empty, without surrounding whitespace or control
+/// characters. (They are UTF-8 by construction: the CLI rejects other
+/// arguments.)
+pub(crate) fn validate_key(key: &str) -> Result<()> {
+    if key.is_empty() {
+        return Err(ErrorCategory::InvalidArgs.error("metadata keys cannot be empty"));
+    }
+    if key.trim() != key || key.chars().any(char::is_control) {
+        return Err(ErrorCategory::InvalidArgs.error(format!(
+            "metadata key {key:?} has surrounding whitespace or control characters"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_must_be_non_empty_and_printable() {
+        assert!(validate_key("rows").is_ok());
+        assert!(validate_key("javelin.n cols").is_ok());
+        for bad in ["", " rows", "rows\n", "a\tb"] {
+            let err = validate_key(bad).unwrap_err();
+            assert_eq!(
+                ErrorCategory::of(&err),
+                ErrorCategory::InvalidArgs,
+                "{bad:?}"
+            );
+        }
+    }
+}
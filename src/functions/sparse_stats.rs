@@ -0,0 +1,286 @@
+//! `sparse-stats`: shape, density, duplicates and band structure of a COO
+//! matrix.
+//!
+//! The band is measured over the distinct non-zero coordinates: the lower
+//! (upper) bandwidth is the largest distance of an entry below (above) the
+//! diagonal and the profile is the sum over rows of the span from the
+//! leftmost to the rightmost non-zero, inclusive. A tridiagonal `n × n`
+//! matrix has bandwidth 1 and profile `3n − 2`.
+
+use anyhow::Result;
+use arrow::compute::concat_batches;
+use arrow::datatypes::Schema as ArrowSchema;
+use arrow_array::RecordBatch;
+use arrow_array::cast::AsArray;
+use arrow_array::types::UInt32Type;
+use futures::TryStreamExt;
+use lance::Dataset;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::datasets::{open_dataset, path_to_uri, write_lance_batch};
+use crate::errors::ErrorCategory;
+use crate::functions::functions::{LanceLayout, canonical_coo};
+use crate::functions::info::dataset_layout;
+use crate::functions::progress::Progress;
+use crate::functions::reorder::{Reorder, Reordering};
+
+/// All triplets of a SparseCoo dataset as one batch, with the dataset's
+/// schema metadata.
+pub(crate) async fn load_coo(dataset: &Dataset, progress: bool) -> Result<RecordBatch> {
+    let (layout, _) = dataset_layout(dataset).await?;
+    if layout != LanceLayout::SparseCoo {
+        return Err(ErrorCategory::UnsupportedLayout.error(format!(
+            "expected a SparseCoo dataset (row, col, value), found {layout:?}"
+        )));
+    }
+    let schema = Arc::new(ArrowSchema::from(dataset.schema()));
+    let total = dataset.count_rows(None).await?;
+    let mut stream = dataset.scan().try_into_stream().await?;
+    let mut bar = Progress::new("triples", total, progress);
+    let mut batches = Vec::new();
+    while let Some(batch) = stream.try_next().await? {
+        bar.inc(batch.num_rows());
+        batches.push(batch);
+    }
+    bar.finish();
+    canonical_coo(&concat_batches(&schema, &batches)?)
+}
+
+/// Band structure of a matrix's non-zeros; all zero for an empty matrix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Band {
+    /// Largest `row − col` of an entry
+    pub lower_bandwidth: usize,
+    /// Largest `col − row` of an entry
+    pub upper_bandwidth: usize,
+    /// Larger of the two
+    pub bandwidth: usize,
+    /// Sum of the per-row spans `max col − min col + 1` (0 for empty rows)
+    pub profile: u64,
+}
+
+impl Band {
+    /// The band of the `(row, col)` coordinates; rows at or past `n_rows`
+    /// are ignored.
+    pub fn of_entries(entries: impl IntoIterator<Item = (usize, usize)>, n_rows: usize) -> Self {
+        Self::of_spans(&row_spans(entries, n_rows))
+    }
+
+    /// The band of per-row `(min col, max col)` spans, indexed by row.
+    pub fn of_spans(spans: &[Option<(usize, usize)>]) -> Self {
+        let mut band = Band::default();
+        for (r, &(lo, hi)) in spans
+            .iter()
+            .enumerate()
+            .filter_map(|(r, s)| Some((r, s.as_ref()?)))
+        {
+            band.lower_bandwidth = band.lower_bandwidth.max(r.saturating_sub(lo));
+            band.upper_bandwidth = band.upper_bandwidth.max(hi.saturating_sub(r));
+            band.profile += (hi - lo + 1) as u64;
+        }
+        band.bandwidth = band.lower_bandwidth.max(band.upper_bandwidth);
+        band
+    }
+
+    /// Whether `(row, col)` lies within the band.
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        if row >= col {
+            row - col <= self.lower_bandwidth
+        } else {
+            col - row <= self.upper_bandwidth
+        }
+    }
+
+    /// Whether `(row, col)` lies on the band's outermost diagonal, i.e. is
+    /// one of the entries setting the bandwidth.
+    pub fn on_edge(&self, row: usize, col: usize) -> bool {
+        row.abs_diff(col) == self.bandwidth
+    }
+}
+
+/// Leftmost and rightmost non-zero column of each of the first `n_rows`
+/// rows; `None` for empty rows.
+pub fn row_spans(
+    entries: impl IntoIterator<Item = (usize, usize)>,
+    n_rows: usize,
+) -> Vec<Option<(usize, usize)>> {
+    let mut spans = vec![None; n_rows];
+    for (r, c) in entries {
+        if let Some(span) = spans.get_mut(r) {
+            *span = Some(match *span {
+                Some((lo, hi)) => (c.min(lo), c.max(hi)),
+                None => (c, c),
+            });
+        }
+    }
+    spans
+}
+
+/// What `sparse-stats` reports.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SparseStats {
+    pub rows: usize,
+    pub cols: usize,
+    /// Stored (row, col, value) triples
+    pub triples: usize,
+    /// Distinct coordinates among them
+    pub nnz: usize,
+    /// Triples repeating an earlier coordinate
+    pub duplicates: usize,
+    /// `nnz / (rows × cols)`
+    pub density: f64,
+    /// Rows without a non-zero
+    pub empty_rows: usize,
+    #[serde(flatten)]
+    pub band: Band,
+}
+
+impl SparseStats {
+    /// Statistics of a SparseCoo batch. The shape comes from the `rows` /
+    /// `cols` schema metadata when present, otherwise from the largest index.
+    pub fn of_batch(batch: &RecordBatch) -> Result<Self> {
+        let batch = canonical_coo(batch)?;
+        let row = batch.column(0).as_primitive::<UInt32Type>();
+        let col = batch.column(1).as_primitive::<UInt32Type>();
+        let mut coords: Vec<(usize, usize)> = row
+            .values()
+            .iter()
+            .zip(col.values().iter())
+            .map(|(&r, &c)| (r as usize, c as usize))
+            .collect();
+        let triples = coords.len();
+        coords.sort_unstable();
+        coords.dedup();
+
+        let schema = batch.schema();
+        let dim = |key: &str, max: Option<usize>| {
+            schema
+                .metadata()
+                .get(key)
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or_else(|| max.map_or(0, |m| m + 1))
+        };
+        let rows = dim("rows", coords.last().map(|&(r, _)| r));
+        let cols = dim("cols", coords.iter().map(|&(_, c)| c).max());
+
+        let spans = row_spans(coords.iter().copied(), rows);
+        let nnz = coords.len();
+        Ok(Self {
+            rows,
+            cols,
+            triples,
+            nnz,
+            duplicates: triples - nnz,
+            density: if rows == 0 || cols == 0 {
+                0.0
+            } else {
+                nnz as f64 / (rows as f64 * cols as f64)
+            },
+            empty_rows: spans.iter().filter(|s| s.is_none()).count(),
+            band: Band::of_spans(&spans),
+        })
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        vec![
+            format!("Matrix: {} × {}", self.rows, self.cols),
+            format!(
+                "Non-zeros: {} ({} triples, {} duplicate coordinates)",
+                self.nnz, self.triples, self.duplicates
+            ),
+            format!("Density: {:.6}", self.density),
+            format!("Empty rows: {}", self.empty_rows),
+            format!(
+                "Bandwidth: {} (lower {}, upper {})",
+                self.band.bandwidth, self.band.lower_bandwidth, self.band.upper_bandwidth
+            ),
+            format!("Profile: {}", self.band.profile),
+        ]
+    }
+}
+
+/// `sparse-stats`; with `reorder` also the band under that reordering, whose
+/// permutation is written to `permutation_out` when given.
+pub async fn cmd_sparse_stats(
+    filepath: &Path,
+    json: bool,
+    reorder: Option<Reorder>,
+    permutation_out: Option<&Path>,
+) -> Result<()> {
+    let dataset = open_dataset(filepath).await?;
+    let batch = load_coo(&dataset, !json).await?;
+    let stats = SparseStats::of_batch(&batch)?;
+    let reordering = match reorder {
+        Some(Reorder::Rcm) => Some(Reordering::rcm(&batch)?),
+        None => None,
+    };
+    if let (Some(reordering), Some(out)) = (&reordering, permutation_out) {
+        write_lance_batch(&path_to_uri(out), reordering.to_batch()?).await?;
+        if !json {
+            eprintln!("Permutation written to {}", out.display());
+        }
+    }
+
+    if json {
+        let mut report = serde_json::to_value(&stats)?;
+        if let Some(reordering) = &reordering {
+            report["rcm"] = serde_json::to_value(reordering.after)?;
+        }
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    println!("=== Sparse structure of {} ===", filepath.display());
+    for line in stats.lines() {
+        println!("{line}");
+    }
+    if let Some(reordering) = &reordering {
+        println!("--- Reverse Cuthill–McKee ---");
+        for line in reordering.lines() {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::csr_to_coo_batch;
+    use sprs::TriMat;
+
+    #[test]
+    fn band_of_a_skewed_pattern() {
+        // Row 0: cols 0..=3, row 1 empty, row 2: col 0, row 3: col 3
+        let band = Band::of_entries([(0, 0), (0, 3), (2, 0), (3, 3), (9, 0)], 4);
+        assert_eq!(
+            band,
+            Band {
+                lower_bandwidth: 2,
+                upper_bandwidth: 3,
+                bandwidth: 3,
+                profile: 4 + 1 + 1,
+            }
+        );
+        assert!(band.contains(2, 0) && band.contains(0, 3));
+        assert!(!band.contains(3, 0));
+        assert!(band.on_edge(0, 3) && !band.on_edge(2, 0));
+    }
+
+    #[test]
+    fn stats_count_duplicates_once() {
+        let mut tri = TriMat::<f64>::new((3, 4));
+        tri.add_triplet(0, 1, 1.0);
+        tri.add_triplet(2, 3, 1.0);
+        let batch = csr_to_coo_batch(&tri.to_csr()).unwrap();
+        let doubled = arrow::compute::concat_batches(&batch.schema(), [&batch, &batch]).unwrap();
+
+        let stats = SparseStats::of_batch(&doubled).unwrap();
+        assert_eq!((stats.rows, stats.cols), (3, 4));
+        assert_eq!((stats.triples, stats.nnz, stats.duplicates), (4, 2, 2));
+        assert_eq!(stats.density, 2.0 / 12.0);
+        assert_eq!(stats.empty_rows, 1);
+        assert_eq!((stats.band.bandwidth, stats.band.profile), (1, 2));
+    }
+}
@@ -4,12 +4,16 @@
 //! as a graph, showing relationships between nodes (rows) based on shared
 //! non-zero entries in columns.
 
-use anyhow::{Result, anyhow};
-use arrow::array::{Float64Array, UInt32Array};
+use anyhow::Result;
 use arrow::record_batch::RecordBatch;
 use arrow_array::Array;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, UInt32Type};
 use std::collections::{HashMap, HashSet};
 
+use crate::display::LanceLayout;
+use crate::functions::functions::{canonical_coo, detect_lance_layout, sparse_per_row_to_coo};
+
 /// Represents a node in the connectivity graph
 #[derive(Debug, Clone)]
 pub struct GraphNode {
@@ -36,53 +40,27 @@ pub struct ConnectivityGraph {
 }
 
 impl ConnectivityGraph {
-    /// Build a connectivity graph from a COO sparse matrix
+    /// Build a connectivity graph from a SparseCoo batch, or from a
+    /// SparsePerRow batch after flattening its records to COO.
+    pub fn from_batch(batch: &RecordBatch) -> Result<Self> {
+        match detect_lance_layout(batch) {
+            LanceLayout::SparsePerRow => Self::from_coo_batch(&sparse_per_row_to_coo(batch)?),
+            _ => Self::from_coo_batch(batch),
+        }
+    }
+
+    /// Build a connectivity graph from a COO sparse matrix; the indices may
+    /// be of any integer type and the values of any numeric type.
     ///
     /// Two rows are connected if they share at least one column with non-zero values.
     /// Edge weight represents the number of shared columns.
     pub fn from_coo_batch(batch: &RecordBatch) -> Result<Self> {
+        // row, col, value first, cast; fails on negative or oversized indices.
+        let batch = canonical_coo(batch)?;
         let schema = batch.schema();
-
-        // Locate row/col/value columns
-        let mut row_idx = None;
-        let mut col_idx = None;
-        let mut val_idx = None;
-
-        for (i, field) in schema.fields().iter().enumerate() {
-            match field.name().as_str() {
-                "row" => row_idx = Some(i),
-                "col" => col_idx = Some(i),
-                "value" => val_idx = Some(i),
-                _ => {}
-            }
-        }
-
-        let (row_i, col_i, val_i) = match (row_idx, col_idx, val_idx) {
-            (Some(r), Some(c), Some(v)) => (r, c, v),
-            _ => {
-                return Err(anyhow!(
-                    "COO schema must contain 'row', 'col', and 'value' columns"
-                ));
-            }
-        };
-
-        let row_arr = batch
-            .column(row_i)
-            .as_any()
-            .downcast_ref::<UInt32Array>()
-            .ok_or_else(|| anyhow!("row must be UInt32"))?;
-
-        let col_arr = batch
-            .column(col_i)
-            .as_any()
-            .downcast_ref::<UInt32Array>()
-            .ok_or_else(|| anyhow!("col must be UInt32"))?;
-
-        let val_arr = batch
-            .column(val_i)
-            .as_any()
-            .downcast_ref::<Float64Array>()
-            .ok_or_else(|| anyhow!("value must be Float64"))?;
+        let row_arr = batch.column(0).as_primitive::<UInt32Type>();
+        let col_arr = batch.column(1).as_primitive::<UInt32Type>();
+        let val_arr = batch.column(2).as_primitive::<Float64Type>();
 
         let nnz = row_arr.len();
 
@@ -91,12 +69,12 @@ impl ConnectivityGraph {
         let n_rows = md
             .get("rows")
             .and_then(|r| r.parse::<usize>().ok())
-            .unwrap_or_else(|| row_arr.iter().filter_map(|x| x).max().unwrap_or(0) as usize + 1);
+            .unwrap_or_else(|| row_arr.iter().flatten().max().unwrap_or(0) as usize + 1);
 
         let n_cols = md
             .get("cols")
             .and_then(|c| c.parse::<usize>().ok())
-            .unwrap_or_else(|| col_arr.iter().filter_map(|x| x).max().unwrap_or(0) as usize + 1);
+            .unwrap_or_else(|| col_arr.iter().flatten().max().unwrap_or(0) as usize + 1);
 
         // Build row -> columns mapping
         let mut row_to_cols: HashMap<usize, HashSet<usize>> = HashMap::new();
@@ -107,10 +85,7 @@ impl ConnectivityGraph {
             }
             let row = row_arr.value(i) as usize;
             let col = col_arr.value(i) as usize;
-            row_to_cols
-                .entry(row)
-                .or_insert_with(HashSet::new)
-                .insert(col);
+            row_to_cols.entry(row).or_default().insert(col);
         }
 
         // Build connectivity graph
@@ -175,7 +150,7 @@ impl ConnectivityGraph {
     /// Get the most connected nodes (hubs)
     pub fn get_hubs(&self, top_k: usize) -> Vec<&GraphNode> {
         let mut sorted_nodes: Vec<&GraphNode> = self.nodes.iter().collect();
-        sorted_nodes.sort_by(|a, b| b.degree.cmp(&a.degree));
+        sorted_nodes.sort_by_key(|n| std::cmp::Reverse(n.degree));
         sorted_nodes.into_iter().take(top_k).collect()
     }
 
@@ -263,7 +238,7 @@ impl ConnectivityGraph {
             0.0
         };
 
-        output.push_str(&format!("\nEdge Statistics:\n"));
+        output.push_str("\nEdge Statistics:\n");
         output.push_str(&format!("  Total edges: {}\n", self.edges.len()));
         output.push_str(&format!("  Avg shared columns: {:.2}\n", avg_weight));
 
@@ -308,7 +283,7 @@ impl ConnectivityGraph {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use arrow::array::ArrayRef;
+    use arrow::array::{ArrayRef, Float64Array, UInt32Array};
     use arrow::datatypes::{DataType, Field, Schema};
     use std::sync::Arc;
 
@@ -333,4 +308,52 @@ mod tests {
         let hubs = graph.get_hubs(3);
         assert!(!hubs.is_empty());
     }
+
+    #[test]
+    fn integer_indices_and_float_values_of_any_width_are_accepted() {
+        let row: ArrayRef = Arc::new(UInt32Array::from(vec![0, 0, 1, 1, 2]));
+        let col: ArrayRef = Arc::new(UInt32Array::from(vec![0, 1, 1, 2, 2]));
+        let val: ArrayRef = Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0, 4.0, 5.0]));
+        let cast = |a: &ArrayRef, t: &DataType| arrow::compute::cast(a, t).unwrap();
+
+        for index in [
+            DataType::UInt32,
+            DataType::UInt64,
+            DataType::Int32,
+            DataType::Int64,
+        ] {
+            for value in [DataType::Float64, DataType::Float32] {
+                let schema = Arc::new(Schema::new(vec![
+                    Field::new("row", index.clone(), false),
+                    Field::new("col", index.clone(), false),
+                    Field::new("value", value.clone(), false),
+                ]));
+                let batch = RecordBatch::try_new(
+                    schema,
+                    vec![cast(&row, &index), cast(&col, &index), cast(&val, &value)],
+                )
+                .unwrap();
+                let graph = ConnectivityGraph::from_coo_batch(&batch).unwrap();
+                assert_eq!((graph.n_rows, graph.n_cols), (3, 3), "{index} / {value}");
+                assert_eq!(graph.edges.len(), 2, "{index} / {value}");
+            }
+        }
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("row", DataType::Int64, false),
+            Field::new("col", DataType::Int64, false),
+            Field::new("value", DataType::Float64, false),
+        ]));
+        let negative = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(arrow::array::Int64Array::from(vec![0, -3])),
+                Arc::new(arrow::array::Int64Array::from(vec![1, 1])),
+                Arc::new(Float64Array::from(vec![1.0, 1.0])),
+            ],
+        )
+        .unwrap();
+        let err = ConnectivityGraph::from_coo_batch(&negative).unwrap_err();
+        assert!(format!("{err:#}").contains("-3"), "{err:#}");
+    }
 }
@@ -1,83 +1,611 @@
 use anyhow::Result;
 use arrow::array::*;
-use arrow::datatypes::DataType;
+use arrow::datatypes::{DataType, Schema as ArrowSchema};
+use futures::TryStreamExt;
 use lance::Dataset;
-use std::path::PathBuf;
-
-use crate::datasets::path_to_uri;
-
-pub async fn cmd_stats(filepath: &PathBuf) -> Result<()> {
-    println!("=== Dataset Statistics ===\n");
-
-    let uri = path_to_uri(filepath);
-    let dataset = Dataset::open(&uri).await?;
-    let schema = dataset.schema();
-    let count = dataset.count_rows(None).await?;
+use serde_json::{Value, json};
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::cache::{ColumnStats, StatsCache};
+use crate::datasets::read_dataset;
+use crate::functions::batch_source::BatchSource;
+use crate::functions::binary::dataset_binary_stats;
+use crate::functions::constant_columns::{Constancy, constant_columns};
+use crate::functions::frequencies::{
+    FrequencyTable, TOP_VALUES, dataset_frequencies, is_always_categorical, is_categorical_type,
+};
+use crate::functions::functions::{length_summary, row_length};
+use crate::functions::outliers::{Feature, feature_stats, features};
+use crate::functions::progress::Progress;
+
+/// Number of equal-width histogram bins kept per cached column.
+pub(crate) const HISTOGRAM_BINS: usize = 20;
+
+/// Print dataset statistics, as a human-readable report or (`json`) as a
+/// single JSON document. Boolean, string and integer columns also get
+/// frequency tables, counted exactly up to `max_distinct` distinct values;
+/// binary columns get their value lengths and distinct count. Columns with
+/// a std of at most `constant_threshold`·|mean| are listed as near-constant.
+pub async fn cmd_stats(
+    filepath: &Path,
+    json: bool,
+    max_distinct: usize,
+    constant_threshold: f64,
+) -> Result<()> {
+    write_stats(
+        &mut io::stdout(),
+        filepath,
+        json,
+        max_distinct,
+        constant_threshold,
+    )
+    .await
+}
 
-    println!("Total rows: {}", count);
-    println!("Total columns: {}\n", schema.fields.len());
+/// [`cmd_stats`] writing its report to `out`.
+pub async fn write_stats(
+    out: &mut dyn Write,
+    filepath: &Path,
+    json: bool,
+    max_distinct: usize,
+    constant_threshold: f64,
+) -> Result<()> {
+    let cache = &StatsCache::user();
+
+    if json {
+        let report = read_dataset(filepath, |dataset| async move {
+            stats_json(&dataset, filepath, cache, max_distinct, constant_threshold).await
+        })
+        .await?;
+        writeln!(out, "{}", serde_json::to_string_pretty(&report)?)?;
+        return Ok(());
+    }
 
-    // Sample first 1000 rows for statistics
-    let sample_size = 1000.min(count);
-    let mut scanner = dataset.scan();
-    let batch = scanner
-        .limit(Some(sample_size as i64), None)?
-        .try_into_batch()
+    // Everything is read before printing, so a reload after a concurrent
+    // commit doesn't print the report twice.
+    let (schema, count, batch, full, constant, tables, binary) =
+        read_dataset(filepath, |dataset| async move {
+            let count = dataset.count_rows(None).await?;
+            // Sample first 1000 rows for statistics
+            let batch = dataset
+                .scan()
+                .limit(Some(1000.min(count) as i64), None)?
+                .try_into_batch()
+                .await?;
+            let full = cached_column_stats(&dataset, filepath, cache, true).await?;
+            let constant =
+                constant_features(&dataset, filepath, cache, &full, constant_threshold, true)
+                    .await?;
+            let tables = categorical_frequencies(&dataset, max_distinct, true).await?;
+            let binary = dataset_binary_stats(&dataset, true).await?;
+            Ok((
+                dataset.schema().clone(),
+                count,
+                batch,
+                full,
+                constant,
+                tables,
+                binary,
+            ))
+        })
         .await?;
+    let sample_size = batch.num_rows();
 
-    println!("Column details (based on {} sample rows):\n", sample_size);
+    writeln!(out, "=== Dataset Statistics ===\n")?;
+    writeln!(out, "Total rows: {}", count)?;
+    writeln!(out, "Total columns: {}\n", schema.fields.len())?;
+
+    writeln!(
+        out,
+        "Column details (based on {} sample rows):\n",
+        sample_size
+    )?;
 
     for (idx, field) in schema.fields.iter().enumerate() {
-        println!("  • Column: {}", field.name);
-        println!("    Type: {}", format_data_type(&field.data_type()));
-        println!("    Nullable: {}", field.nullable);
+        writeln!(out, "  • Column: {}", field.name)?;
+        writeln!(out, "    Type: {}", format_data_type(&field.data_type()))?;
+        writeln!(out, "    Nullable: {}", field.nullable)?;
 
         let col = batch.column(idx);
 
         // Detect data structure type
         match detect_structure(col, &field.data_type()) {
             DataStructure::Vector1D(size) => {
-                println!("    Structure: 1D Vector (size {})", size);
-                calculate_vector_stats(col);
+                writeln!(out, "    Structure: 1D Vector (size {})", size)?;
+                calculate_vector_stats(out, col)?;
             }
             DataStructure::Matrix2D(rows, cols) => {
-                println!("    Structure: 2D Matrix ({}×{})", rows, cols);
-                calculate_matrix_stats(col);
+                writeln!(out, "    Structure: 2D Matrix ({}×{})", rows, cols)?;
+                calculate_matrix_stats(out, col)?;
             }
             DataStructure::DenseMatrix(rows, cols) => {
-                println!("    Structure: 2D Dense Matrix ({}×{})", rows, cols);
-                calculate_dense_matrix_stats(col, rows, cols);
+                writeln!(out, "    Structure: 2D Dense Matrix ({}×{})", rows, cols)?;
+                calculate_dense_matrix_stats(out, col, rows, cols)?;
             }
             DataStructure::SparseMatrix => {
-                println!("    Structure: Sparse Matrix (COO/CSR format)");
-                calculate_sparse_matrix_stats(col);
+                writeln!(out, "    Structure: Sparse Matrix (COO/CSR format)")?;
+                calculate_sparse_matrix_stats(out, col)?;
             }
             DataStructure::Scalar => {
-                println!("    Structure: Scalar value");
-                calculate_scalar_stats(col);
+                writeln!(out, "    Structure: Scalar value")?;
+                calculate_scalar_stats(out, col)?;
+            }
+            DataStructure::RaggedList => {
+                writeln!(out, "    Structure: Ragged list (variable-length rows)")?;
+                calculate_ragged_stats(out, col)?;
             }
             DataStructure::Other => {
-                println!("    Structure: Other/Complex");
+                writeln!(out, "    Structure: Other/Complex")?;
             }
         }
 
-        println!();
+        writeln!(out)?;
+    }
+
+    writeln!(out, "Null counts (all {} rows):", count)?;
+    for (name, stats) in &full {
+        writeln!(
+            out,
+            "  {name}: {}",
+            format_null_count(stats.null_count as usize, count)
+        )?;
+    }
+
+    writeln!(out, "\nNumeric columns (all {} rows):", count)?;
+    for (name, stats) in &full {
+        if let (Some(min), Some(max), Some(mean), Some(std)) =
+            (stats.min, stats.max, stats.mean, stats.std)
+        {
+            writeln!(
+                out,
+                "  {name}: min {min:.6}  max {max:.6}  mean {mean:.6}  std {std:.6}"
+            )?;
+        }
+    }
+
+    if !constant.is_empty() {
+        writeln!(
+            out,
+            "\nNear-constant columns (all {} rows, std ≤ {}·|mean|):",
+            count, constant_threshold
+        )?;
+        for (name, constancy) in &constant {
+            writeln!(out, "  {name}: {}", constancy.label())?;
+        }
+    }
+
+    if !tables.is_empty() {
+        writeln!(
+            out,
+            "\nCategorical columns (all {} rows, up to {} distinct values):",
+            count, max_distinct
+        )?;
+        for (name, table) in &tables {
+            writeln!(out, "  {name}: {}", table.cardinality_label())?;
+            for v in table.values.iter().take(TOP_VALUES) {
+                writeln!(
+                    out,
+                    "    {}  {} ({:.2}%)",
+                    v.value,
+                    v.count,
+                    table.fraction(v.count) * 100.0
+                )?;
+            }
+            if table.values.len() > TOP_VALUES {
+                writeln!(out, "    … {} more", table.values.len() - TOP_VALUES)?;
+            }
+        }
+    }
+
+    if !binary.is_empty() {
+        writeln!(out, "\nBinary columns (all {} rows):", count)?;
+        for (name, stats) in &binary {
+            writeln!(out, "  {name}: {}", stats.label())?;
+        }
     }
 
     Ok(())
 }
 
+/// Frequency tables for the dataset's Boolean and string columns, plus its
+/// integer columns that stay within `max_distinct` distinct values.
+pub(crate) async fn categorical_frequencies(
+    dataset: &Dataset,
+    max_distinct: usize,
+    progress: bool,
+) -> Result<Vec<(String, FrequencyTable)>> {
+    let schema = ArrowSchema::from(dataset.schema());
+    let columns: Vec<String> = schema
+        .fields()
+        .iter()
+        .filter(|f| is_categorical_type(f.data_type()))
+        .map(|f| f.name().clone())
+        .collect();
+    let tables = dataset_frequencies(dataset, &columns, max_distinct, progress).await?;
+    Ok(tables
+        .into_iter()
+        .filter(|(name, table)| {
+            let always = schema
+                .field_with_name(name)
+                .is_ok_and(|f| is_always_categorical(f.data_type()));
+            always || !table.is_high_cardinality()
+        })
+        .collect())
+}
+
+/// The features of `dataset` (its numeric columns and the dimensions of its
+/// vector columns, named as `outliers` names them) that do not vary, given
+/// the stats of its top-level columns in `full`. Dimensions' stats come
+/// from `cache` where possible.
+pub(crate) async fn constant_features(
+    dataset: &Dataset,
+    filepath: &Path,
+    cache: &StatsCache,
+    full: &[(String, ColumnStats)],
+    threshold: f64,
+    progress: bool,
+) -> Result<Vec<(String, Constancy)>> {
+    let features = features(&ArrowSchema::from(dataset.schema()));
+    let named: Vec<(String, ColumnStats)> = if features.iter().any(Feature::is_dimension) {
+        let stats = feature_stats(dataset, filepath, cache, &features, progress).await?;
+        features.into_iter().map(|f| f.name).zip(stats).collect()
+    } else {
+        full.to_vec()
+    };
+    Ok(constant_columns(named.iter().map(|(_, s)| s), threshold)
+        .into_iter()
+        .map(|(i, constancy)| (named[i].0.clone(), constancy))
+        .collect())
+}
+
+/// Full-dataset [`ColumnStats`] for every top-level column, taken from
+/// `cache` where possible. Columns missing from the cache are computed in
+/// two streaming passes (moments, then histogram) and written back, with a
+/// stderr progress line when `progress` is set and stderr is a terminal.
+pub(crate) async fn cached_column_stats(
+    dataset: &Dataset,
+    filepath: &Path,
+    cache: &StatsCache,
+    progress: bool,
+) -> Result<Vec<(String, ColumnStats)>> {
+    let version = dataset.version().version;
+    let names: Vec<String> = dataset
+        .schema()
+        .fields
+        .iter()
+        .map(|f| f.name.clone())
+        .collect();
+
+    let mut found: Vec<Option<ColumnStats>> = names
+        .iter()
+        .map(|name| cache.get(filepath, version, name))
+        .collect();
+    let missing: Vec<String> = names
+        .iter()
+        .zip(&found)
+        .filter(|(_, s)| s.is_none())
+        .map(|(n, _)| n.clone())
+        .collect();
+
+    if !missing.is_empty() {
+        log::debug!("stats cache: computing {} column(s)", missing.len());
+        let computed = compute_column_stats(dataset, &missing, progress).await?;
+        for (name, stats) in missing.iter().zip(computed) {
+            if let Err(e) = cache.put(filepath, version, name, &stats) {
+                log::warn!("stats cache: could not store {name}: {e:#}");
+            }
+            let idx = names.iter().position(|n| n == name).unwrap();
+            found[idx] = Some(stats);
+        }
+    }
+
+    Ok(names
+        .into_iter()
+        .zip(found.into_iter().map(Option::unwrap))
+        .collect())
+}
+
+/// Running mean and variance (Welford's update), which stay accurate for
+/// near-constant columns where `E[x²] − mean²` cancels to noise.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Welford {
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    pub(crate) fn push(&mut self, v: f64) {
+        self.count += 1;
+        let delta = v - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (v - self.mean);
+    }
+
+    pub(crate) fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Mean of the values pushed so far (NaN when none were).
+    pub(crate) fn mean(&self) -> f64 {
+        if self.count == 0 { f64::NAN } else { self.mean }
+    }
+
+    /// Population standard deviation (NaN when no values were pushed).
+    pub(crate) fn std(&self) -> f64 {
+        (self.m2 / self.count as f64).sqrt()
+    }
+}
+
+/// Builds the [`ColumnStats`] of one column (or one dimension of a vector
+/// column) over two passes: [`push`](Self::push) every value, then
+/// [`bin`](Self::bin) every value again when [`needs_histogram`](Self::needs_histogram).
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnStatsBuilder {
+    nulls: u64,
+    spread: Welford,
+    min: f64,
+    max: f64,
+    numeric: bool,
+    histogram: Vec<u64>,
+}
+
+impl Default for ColumnStatsBuilder {
+    fn default() -> Self {
+        Self {
+            nulls: 0,
+            spread: Welford::default(),
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            numeric: false,
+            histogram: Vec::new(),
+        }
+    }
+}
+
+impl ColumnStatsBuilder {
+    pub(crate) fn add_nulls(&mut self, nulls: u64) {
+        self.nulls += nulls;
+    }
+
+    /// Mark the column numeric even if every value turns out to be null.
+    pub(crate) fn set_numeric(&mut self) {
+        self.numeric = true;
+    }
+
+    /// First pass: a non-null value.
+    pub(crate) fn push(&mut self, v: f64) {
+        self.numeric = true;
+        self.spread.push(v);
+        self.min = self.min.min(v);
+        self.max = self.max.max(v);
+    }
+
+    /// Whether a second pass is needed: the values have a range to bin.
+    pub(crate) fn needs_histogram(&mut self) -> bool {
+        if self.histogram.is_empty()
+            && self.numeric
+            && self.spread.count() > 0
+            && self.max > self.min
+        {
+            self.histogram = vec![0; HISTOGRAM_BINS];
+        }
+        !self.histogram.is_empty()
+    }
+
+    /// Second pass: a non-null value, after [`needs_histogram`](Self::needs_histogram).
+    pub(crate) fn bin(&mut self, v: f64) {
+        let width = (self.max - self.min) / HISTOGRAM_BINS as f64;
+        let bin = (((v - self.min) / width) as usize).min(HISTOGRAM_BINS - 1);
+        self.histogram[bin] += 1;
+    }
+
+    pub(crate) fn finish(self) -> ColumnStats {
+        let has_values = self.numeric && self.spread.count() > 0;
+        ColumnStats {
+            count: self.spread.count(),
+            null_count: self.nulls,
+            min: has_values.then_some(self.min),
+            max: has_values.then_some(self.max),
+            mean: has_values.then(|| self.spread.mean()),
+            std: has_values.then(|| self.spread.std()),
+            histogram: self.histogram,
+        }
+    }
+}
+
+/// [`ColumnStats`] of the schema columns `columns` over the rows held in
+/// `data`, as the viewer's `z` and `!` use them.
+pub(crate) fn source_column_stats(data: &BatchSource, columns: &[usize]) -> Vec<ColumnStats> {
+    let mut builders = vec![ColumnStatsBuilder::default(); columns.len()];
+    for (_, batch) in data.iter() {
+        for (b, &col) in builders.iter_mut().zip(columns) {
+            let col = batch.column(col);
+            b.add_nulls(col.null_count() as u64);
+            if let Some(values) = numeric_values(col) {
+                b.set_numeric();
+                values.iter().flatten().for_each(|v| b.push(v));
+            }
+        }
+    }
+    let second_pass: Vec<bool> = builders.iter_mut().map(|b| b.needs_histogram()).collect();
+    for (_, batch) in data.iter() {
+        for ((b, &col), _) in builders
+            .iter_mut()
+            .zip(columns)
+            .zip(&second_pass)
+            .filter(|(_, binned)| **binned)
+        {
+            if let Some(values) = numeric_values(batch.column(col)) {
+                values.iter().flatten().for_each(|v| b.bin(v));
+            }
+        }
+    }
+    builders
+        .into_iter()
+        .map(ColumnStatsBuilder::finish)
+        .collect()
+}
+
+async fn compute_column_stats(
+    dataset: &Dataset,
+    columns: &[String],
+    progress: bool,
+) -> Result<Vec<ColumnStats>> {
+    let total = dataset.count_rows(None).await?;
+    let mut builders = vec![ColumnStatsBuilder::default(); columns.len()];
+
+    let mut scanner = dataset.scan();
+    scanner.project(columns)?;
+    let mut stream = scanner.try_into_stream().await?;
+    let mut bar = Progress::new("stats pass 1/2", total, progress);
+    while let Some(batch) = stream.try_next().await? {
+        bar.inc(batch.num_rows());
+        for (b, col) in builders.iter_mut().zip(batch.columns()) {
+            b.add_nulls(col.null_count() as u64);
+            let Some(values) = numeric_values(col) else {
+                continue;
+            };
+            b.set_numeric();
+            values.iter().flatten().for_each(|v| b.push(v));
+        }
+    }
+
+    let second_pass: Vec<bool> = builders.iter_mut().map(|b| b.needs_histogram()).collect();
+    if second_pass.iter().any(|&s| s) {
+        let mut scanner = dataset.scan();
+        scanner.project(columns)?;
+        let mut stream = scanner.try_into_stream().await?;
+        let mut bar = Progress::new("stats pass 2/2", total, progress);
+        while let Some(batch) = stream.try_next().await? {
+            bar.inc(batch.num_rows());
+            for ((b, &binned), col) in builders.iter_mut().zip(&second_pass).zip(batch.columns()) {
+                if !binned {
+                    continue;
+                }
+                let Some(values) = numeric_values(col) else {
+                    continue;
+                };
+                values.iter().flatten().for_each(|v| b.bin(v));
+            }
+        }
+    }
+
+    Ok(builders
+        .into_iter()
+        .map(ColumnStatsBuilder::finish)
+        .collect())
+}
+
+/// Primitive numeric columns as `Float64`; `None` for anything else.
+pub(crate) fn numeric_values(col: &ArrayRef) -> Option<Float64Array> {
+    if !col.data_type().is_numeric() {
+        return None;
+    }
+    let cast = arrow::compute::cast(col, &DataType::Float64).ok()?;
+    cast.as_any().downcast_ref::<Float64Array>().cloned()
+}
+
+fn format_null_count(nulls: usize, rows: usize) -> String {
+    if rows == 0 {
+        return nulls.to_string();
+    }
+    format!("{nulls} ({:.2}%)", nulls as f64 * 100.0 / rows as f64)
+}
+
+/// Machine-readable summary used by `stats --json`.
+pub(crate) async fn stats_json(
+    dataset: &Dataset,
+    filepath: &Path,
+    cache: &StatsCache,
+    max_distinct: usize,
+    constant_threshold: f64,
+) -> Result<Value> {
+    let schema = dataset.schema();
+    let rows = dataset.count_rows(None).await?;
+
+    let columns: Vec<Value> = schema
+        .fields
+        .iter()
+        .map(|f| {
+            json!({
+                "name": f.name,
+                "type": format_data_type(&f.data_type()),
+                "nullable": f.nullable,
+            })
+        })
+        .collect();
+
+    let mut nulls = serde_json::Map::new();
+    let mut stats = serde_json::Map::new();
+    // JSON output is meant for pipes, so it never draws progress.
+    let full = cached_column_stats(dataset, filepath, cache, false).await?;
+    for (name, col) in &full {
+        let fraction = if rows == 0 {
+            0.0
+        } else {
+            col.null_count as f64 / rows as f64
+        };
+        nulls.insert(
+            name.clone(),
+            json!({ "count": col.null_count, "fraction": fraction }),
+        );
+        if col.mean.is_some() {
+            stats.insert(name.clone(), serde_json::to_value(col)?);
+        }
+    }
+
+    let mut constant = serde_json::Map::new();
+    for (name, constancy) in
+        constant_features(dataset, filepath, cache, &full, constant_threshold, false).await?
+    {
+        let entry = match constancy {
+            Constancy::Zero => json!({ "kind": "zero" }),
+            Constancy::Constant(value) => json!({ "kind": "constant", "value": value }),
+            Constancy::NearConstant { mean, std } => {
+                json!({ "kind": "near_constant", "mean": mean, "std": std })
+            }
+        };
+        constant.insert(name, entry);
+    }
+
+    let mut frequencies = serde_json::Map::new();
+    for (name, table) in categorical_frequencies(dataset, max_distinct, false).await? {
+        frequencies.insert(name, table.to_json(TOP_VALUES));
+    }
+
+    let mut binary = serde_json::Map::new();
+    for (name, col) in dataset_binary_stats(dataset, false).await? {
+        binary.insert(name, serde_json::to_value(&col)?);
+    }
+
+    Ok(json!({
+        "path": filepath.display().to_string(),
+        "rows": rows,
+        "columns": columns,
+        "nulls": nulls,
+        "stats": stats,
+        "constant": constant,
+        "constant_threshold": constant_threshold,
+        "frequencies": frequencies,
+        "binary": binary,
+    }))
+}
+
 #[derive(Debug)]
+#[allow(dead_code)]
 enum DataStructure {
     Vector1D(i32),         // 1D vector with size
     Matrix2D(i32, i32),    // 2D matrix (rows, cols)
     DenseMatrix(i32, i32), // Dense 2D matrix stored as flattened
     SparseMatrix,          // Sparse matrix (COO or CSR)
     Scalar,                // Single value
+    RaggedList,            // Variable-length numeric lists
     Other,                 // Complex or unknown structure
 }
 
-fn detect_structure(col: &ArrayRef, data_type: &DataType) -> DataStructure {
+fn detect_structure(_col: &ArrayRef, data_type: &DataType) -> DataStructure {
     match data_type {
         // Dense row-major matrix: each row is a FixedSizeList of scalars
         DataType::FixedSizeList(inner, size) => {
@@ -87,7 +615,7 @@ fn detect_structure(col: &ArrayRef, data_type: &DataType) -> DataStructure {
                     DataStructure::DenseMatrix(1, *size) // 1 row per record, size columns
                 }
                 // 3D or higher: FixedSizeList of FixedSizeList
-                DataType::FixedSizeList(inner2, cols) => DataStructure::Matrix2D(*size, *cols),
+                DataType::FixedSizeList(_inner2, cols) => DataStructure::Matrix2D(*size, *cols),
                 _ => DataStructure::Other,
             }
         }
@@ -116,6 +644,9 @@ fn detect_structure(col: &ArrayRef, data_type: &DataType) -> DataStructure {
         }
 
         // Variable-length list (could be sparse or ragged)
+        DataType::List(inner) | DataType::LargeList(inner) if inner.data_type().is_numeric() => {
+            DataStructure::RaggedList
+        }
         DataType::List(_) => DataStructure::Other,
 
         // Scalar types
@@ -132,22 +663,23 @@ fn detect_structure(col: &ArrayRef, data_type: &DataType) -> DataStructure {
     }
 }
 
-fn calculate_vector_stats(col: &ArrayRef) {
+fn calculate_vector_stats(out: &mut dyn Write, col: &ArrayRef) -> io::Result<()> {
     if let Some(list_array) = col.as_any().downcast_ref::<FixedSizeListArray>() {
         let values = list_array.values();
 
         if let Some(stats) = calculate_numeric_stats(values.as_ref()) {
-            println!("    Vector element statistics:");
-            println!("      Mean:   {:.6}", stats.mean);
-            println!("      Std:    {:.6}", stats.std);
-            println!("      Min:    {:.6}", stats.min);
-            println!("      Max:    {:.6}", stats.max);
-            println!("      Nulls:  {}", stats.null_count);
+            writeln!(out, "    Vector element statistics:")?;
+            writeln!(out, "      Mean:   {:.6}", stats.mean)?;
+            writeln!(out, "      Std:    {:.6}", stats.std)?;
+            writeln!(out, "      Min:    {:.6}", stats.min)?;
+            writeln!(out, "      Max:    {:.6}", stats.max)?;
+            writeln!(out, "      Nulls:  {}", stats.null_count)?;
         }
     }
+    Ok(())
 }
 
-fn calculate_matrix_stats(col: &ArrayRef) {
+fn calculate_matrix_stats(out: &mut dyn Write, col: &ArrayRef) -> io::Result<()> {
     if let Some(outer_list) = col.as_any().downcast_ref::<FixedSizeListArray>() {
         let inner_list_ref = outer_list.values();
 
@@ -155,40 +687,52 @@ fn calculate_matrix_stats(col: &ArrayRef) {
             let values = inner_list.values();
 
             if let Some(stats) = calculate_numeric_stats(values.as_ref()) {
-                println!("    Matrix element statistics:");
-                println!("      Mean:   {:.6}", stats.mean);
-                println!("      Std:    {:.6}", stats.std);
-                println!("      Min:    {:.6}", stats.min);
-                println!("      Max:    {:.6}", stats.max);
-                println!("      Nulls:  {}", stats.null_count);
+                writeln!(out, "    Matrix element statistics:")?;
+                writeln!(out, "      Mean:   {:.6}", stats.mean)?;
+                writeln!(out, "      Std:    {:.6}", stats.std)?;
+                writeln!(out, "      Min:    {:.6}", stats.min)?;
+                writeln!(out, "      Max:    {:.6}", stats.max)?;
+                writeln!(out, "      Nulls:  {}", stats.null_count)?;
             }
         }
     }
+    Ok(())
 }
 
-fn calculate_dense_matrix_stats(col: &ArrayRef, _rows: i32, cols: i32) {
+fn calculate_dense_matrix_stats(
+    out: &mut dyn Write,
+    col: &ArrayRef,
+    _rows: i32,
+    cols: i32,
+) -> io::Result<()> {
     if let Some(list_array) = col.as_any().downcast_ref::<FixedSizeListArray>() {
         let values = list_array.values();
         let num_records = list_array.len();
 
         if let Some(stats) = calculate_numeric_stats(values.as_ref()) {
-            println!("    Dense matrix representation:");
-            println!("      Shape: {} records × {} features", num_records, cols);
-            println!(
+            writeln!(out, "    Dense matrix representation:")?;
+            writeln!(
+                out,
+                "      Shape: {} records × {} features",
+                num_records, cols
+            )?;
+            writeln!(
+                out,
                 "      Storage: Row-major (each record is a {}-dim vector)",
                 cols
-            );
-            println!("    Element statistics:");
-            println!("      Mean:   {:.6}", stats.mean);
-            println!("      Std:    {:.6}", stats.std);
-            println!("      Min:    {:.6}", stats.min);
-            println!("      Max:    {:.6}", stats.max);
-            println!("      Nulls:  {}", stats.null_count);
+            )?;
+            writeln!(out, "    Element statistics:")?;
+            writeln!(out, "      Mean:   {:.6}", stats.mean)?;
+            writeln!(out, "      Std:    {:.6}", stats.std)?;
+            writeln!(out, "      Min:    {:.6}", stats.min)?;
+            writeln!(out, "      Max:    {:.6}", stats.max)?;
+            writeln!(out, "      Nulls:  {}", stats.null_count)?;
         }
     }
+    Ok(())
 }
 
-fn calculate_sparse_matrix_stats(col: &ArrayRef) {
+fn calculate_sparse_matrix_stats(out: &mut dyn Write, col: &ArrayRef) -> io::Result<()> {
     if let Some(struct_array) = col.as_any().downcast_ref::<StructArray>() {
         // Calculate sparsity
         let mut total_nnz = 0;
@@ -209,9 +753,9 @@ fn calculate_sparse_matrix_stats(col: &ArrayRef) {
 
                 if sample_count > 0 {
                     let avg_nnz = total_nnz as f64 / sample_count as f64;
-                    println!("    Sparse matrix statistics:");
-                    println!("      Avg non-zeros per sample: {:.2}", avg_nnz);
-                    println!("      Total samples analyzed: {}", sample_count);
+                    writeln!(out, "    Sparse matrix statistics:")?;
+                    writeln!(out, "      Avg non-zeros per sample: {:.2}", avg_nnz)?;
+                    writeln!(out, "      Total samples analyzed: {}", sample_count)?;
 
                     // Calculate stats on non-zero values
                     if let Some(list_array) = values_col.as_any().downcast_ref::<ListArray>() {
@@ -236,10 +780,10 @@ fn calculate_sparse_matrix_stats(col: &ArrayRef) {
                             let min = all_values.iter().cloned().fold(f64::INFINITY, f64::min);
                             let max = all_values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
 
-                            println!("      Non-zero value statistics:");
-                            println!("        Mean: {:.6}", mean);
-                            println!("        Min:  {:.6}", min);
-                            println!("        Max:  {:.6}", max);
+                            writeln!(out, "      Non-zero value statistics:")?;
+                            writeln!(out, "        Mean: {:.6}", mean)?;
+                            writeln!(out, "        Min:  {:.6}", min)?;
+                            writeln!(out, "        Max:  {:.6}", max)?;
                         }
                     }
                 }
@@ -247,16 +791,29 @@ fn calculate_sparse_matrix_stats(col: &ArrayRef) {
             }
         }
     }
+    Ok(())
 }
 
-fn calculate_scalar_stats(col: &ArrayRef) {
+fn calculate_ragged_stats(out: &mut dyn Write, col: &ArrayRef) -> io::Result<()> {
+    let lengths: Vec<usize> = (0..col.len()).filter_map(|r| row_length(col, r)).collect();
+    if let Some(summary) = length_summary(&lengths) {
+        writeln!(out, "    Row lengths:")?;
+        writeln!(out, "      Min:    {}", summary.min)?;
+        writeln!(out, "      Mean:   {:.2}", summary.mean)?;
+        writeln!(out, "      Max:    {}", summary.max)?;
+        writeln!(out, "      Nulls:  {}", col.len() - lengths.len())?;
+    }
+    Ok(())
+}
+
+fn calculate_scalar_stats(out: &mut dyn Write, col: &ArrayRef) -> io::Result<()> {
     if let Some(stats) = calculate_numeric_stats(col) {
-        println!("    Scalar statistics:");
-        println!("      Mean:   {:.6}", stats.mean);
-        println!("      Std:    {:.6}", stats.std);
-        println!("      Min:    {:.6}", stats.min);
-        println!("      Max:    {:.6}", stats.max);
-        println!("      Nulls:  {}", stats.null_count);
+        writeln!(out, "    Scalar statistics:")?;
+        writeln!(out, "      Mean:   {:.6}", stats.mean)?;
+        writeln!(out, "      Std:    {:.6}", stats.std)?;
+        writeln!(out, "      Min:    {:.6}", stats.min)?;
+        writeln!(out, "      Max:    {:.6}", stats.max)?;
+        writeln!(out, "      Nulls:  {}", stats.null_count)?;
     } else if let Some(string_array) = col.as_any().downcast_ref::<StringArray>() {
         let null_count = string_array.null_count();
         let total_len: usize = (0..string_array.len())
@@ -266,14 +823,16 @@ fn calculate_scalar_stats(col: &ArrayRef) {
         let non_null = string_array.len() - null_count;
 
         if non_null > 0 {
-            println!("    String statistics:");
-            println!(
+            writeln!(out, "    String statistics:")?;
+            writeln!(
+                out,
                 "      Avg length: {:.2}",
                 total_len as f64 / non_null as f64
-            );
-            println!("      Nulls: {}", null_count);
+            )?;
+            writeln!(out, "      Nulls: {}", null_count)?;
         }
     }
+    Ok(())
 }
 
 struct NumericStats {
@@ -285,11 +844,9 @@ struct NumericStats {
 }
 
 fn calculate_numeric_stats(array: &dyn Array) -> Option<NumericStats> {
-    let mut sum = 0.0;
-    let mut sum_sq = 0.0;
+    let mut spread = Welford::default();
     let mut min_val = f64::INFINITY;
     let mut max_val = f64::NEG_INFINITY;
-    let mut count = 0;
     let null_count = array.null_count();
 
     // Try different numeric types
@@ -297,68 +854,56 @@ fn calculate_numeric_stats(array: &dyn Array) -> Option<NumericStats> {
         for i in 0..float_array.len() {
             if !float_array.is_null(i) {
                 let val = float_array.value(i);
-                sum += val;
-                sum_sq += val * val;
+                spread.push(val);
                 min_val = min_val.min(val);
                 max_val = max_val.max(val);
-                count += 1;
             }
         }
     } else if let Some(float_array) = array.as_any().downcast_ref::<Float32Array>() {
         for i in 0..float_array.len() {
             if !float_array.is_null(i) {
                 let val = float_array.value(i) as f64;
-                sum += val;
-                sum_sq += val * val;
+                spread.push(val);
                 min_val = min_val.min(val);
                 max_val = max_val.max(val);
-                count += 1;
             }
         }
     } else if let Some(int_array) = array.as_any().downcast_ref::<Int64Array>() {
         for i in 0..int_array.len() {
             if !int_array.is_null(i) {
                 let val = int_array.value(i) as f64;
-                sum += val;
-                sum_sq += val * val;
+                spread.push(val);
                 min_val = min_val.min(val);
                 max_val = max_val.max(val);
-                count += 1;
             }
         }
     } else if let Some(int_array) = array.as_any().downcast_ref::<Int32Array>() {
         for i in 0..int_array.len() {
             if !int_array.is_null(i) {
                 let val = int_array.value(i) as f64;
-                sum += val;
-                sum_sq += val * val;
+                spread.push(val);
                 min_val = min_val.min(val);
                 max_val = max_val.max(val);
-                count += 1;
             }
         }
     } else {
         return None;
     }
 
-    if count == 0 {
+    if spread.count() == 0 {
         return None;
     }
 
-    let mean = sum / count as f64;
-    let variance = (sum_sq / count as f64) - (mean * mean);
-    let std = variance.max(0.0).sqrt();
-
     Some(NumericStats {
-        mean,
-        std,
+        mean: spread.mean(),
+        std: spread.std(),
         min: min_val,
         max: max_val,
         null_count,
     })
 }
 
-fn format_data_type(dt: &DataType) -> String {
+pub(crate) fn format_data_type(dt: &DataType) -> String {
     match dt {
         DataType::FixedSizeList(inner, size) => {
             format!(
@@ -373,7 +918,7 @@ fn format_data_type(dt: &DataType) -> String {
         DataType::Struct(fields) => {
             let field_strs: Vec<String> = fields
                 .iter()
-                .map(|f| format!("{}: {}", f.name(), format_data_type(&f.data_type())))
+                .map(|f| format!("{}: {}", f.name(), format_data_type(f.data_type())))
                 .collect();
             format!("Struct<{}>", field_strs.join(", "))
         }
@@ -0,0 +1,241 @@
+//! Physical storage breakdown for `info --verbose`: on-disk bytes, pages and
+//! encodings per top-level column, read from the Lance data files' column
+//! metadata.
+
+use anyhow::{Context, Result};
+use arrow::datatypes::{DataType, Schema as ArrowSchema};
+use lance::Dataset;
+use lance_encoding::decoder::PageEncoding;
+use lance_file::reader::FileReader;
+use lance_io::scheduler::{ScanScheduler, SchedulerConfig};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+/// Bytes assumed per value of a variable-width type (strings, binary) and
+/// elements assumed per variable-length list, since neither is known from
+/// the schema alone.
+pub(crate) const VARIABLE_WIDTH_GUESS: u64 = 32;
+pub(crate) const LIST_LENGTH_GUESS: u64 = 8;
+
+/// Approximate in-memory bytes of one row of `data_type`, including offsets
+/// but ignoring validity bitmaps.
+pub fn estimate_value_width(data_type: &DataType) -> u64 {
+    match data_type {
+        DataType::Boolean => 1,
+        DataType::Utf8 | DataType::Binary => VARIABLE_WIDTH_GUESS + 4,
+        DataType::LargeUtf8 | DataType::LargeBinary => VARIABLE_WIDTH_GUESS + 8,
+        DataType::FixedSizeList(inner, size) => {
+            (*size).max(0) as u64 * estimate_value_width(inner.data_type())
+        }
+        DataType::List(inner) => LIST_LENGTH_GUESS * estimate_value_width(inner.data_type()) + 4,
+        DataType::LargeList(inner) => {
+            LIST_LENGTH_GUESS * estimate_value_width(inner.data_type()) + 8
+        }
+        DataType::Struct(fields) => fields
+            .iter()
+            .map(|f| estimate_value_width(f.data_type()))
+            .sum(),
+        other => other.primitive_width().unwrap_or(8) as u64,
+    }
+}
+
+/// Storage used by one top-level column across all data files.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnStorage {
+    pub name: String,
+    /// Bytes of page and column buffers attributed to the column
+    pub bytes: u64,
+    /// Pages over all data files; `None` for estimates
+    pub pages: Option<usize>,
+    /// Distinct page encodings, e.g. `MiniBlockLayout`; empty for estimates
+    pub encodings: Vec<String>,
+}
+
+/// Per-column storage of a dataset.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StorageReport {
+    /// Data file format versions present, e.g. `2.0`
+    pub file_versions: Vec<String>,
+    pub fragments: usize,
+    pub data_files: usize,
+    /// `true` when sizes are field width × rows instead of file metadata
+    pub estimated: bool,
+    /// Columns in schema order
+    pub columns: Vec<ColumnStorage>,
+}
+
+impl StorageReport {
+    /// Columns ordered largest first.
+    pub fn largest(&self) -> Vec<&ColumnStorage> {
+        let mut cols: Vec<&ColumnStorage> = self.columns.iter().collect();
+        cols.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.name.cmp(&b.name)));
+        cols
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.columns.iter().map(|c| c.bytes).sum()
+    }
+}
+
+/// Read the column metadata of every data file and attribute its buffers to
+/// the top-level columns. Legacy (v1) files carry no per-column sizes, so
+/// those datasets get a width × rows estimate instead.
+pub(crate) async fn storage_report(dataset: &Dataset) -> Result<StorageReport> {
+    let schema = dataset.schema();
+    // Every field id (nested ones too) -> index of its top-level column.
+    let mut top_level: HashMap<i32, usize> = HashMap::new();
+    for (idx, field) in schema.fields.iter().enumerate() {
+        let mut stack = vec![field];
+        while let Some(f) = stack.pop() {
+            top_level.insert(f.id, idx);
+            stack.extend(f.children.iter());
+        }
+    }
+
+    let mut columns: Vec<ColumnStorage> = schema
+        .fields
+        .iter()
+        .map(|f| ColumnStorage {
+            name: f.name.clone(),
+            bytes: 0,
+            pages: Some(0),
+            encodings: Vec::new(),
+        })
+        .collect();
+    let mut encodings: Vec<BTreeSet<String>> = vec![BTreeSet::new(); columns.len()];
+    let mut versions = BTreeSet::new();
+    let mut data_files = 0;
+    let mut legacy = false;
+
+    let scheduler = ScanScheduler::new(
+        dataset.object_store.clone(),
+        SchedulerConfig::max_bandwidth(&dataset.object_store),
+    );
+    let fragments = dataset.fragments();
+    for fragment in fragments.iter() {
+        for file in &fragment.files {
+            data_files += 1;
+            versions.insert(format!(
+                "{}.{}",
+                file.file_major_version, file.file_minor_version
+            ));
+            if file.file_major_version < 2 {
+                legacy = true;
+                continue;
+            }
+            let path = dataset.data_dir().child(file.path.as_str());
+            let file_scheduler = scheduler
+                .open_file(&path, &file.file_size_bytes)
+                .await
+                .with_context(|| format!("Failed to open data file {}", file.path))?;
+            let metadata = FileReader::read_all_metadata(&file_scheduler)
+                .await
+                .with_context(|| format!("Failed to read metadata of {}", file.path))?;
+
+            // Columns without an entry continue the previous field (e.g. the
+            // child column of a list), as in Lance's own storage statistics.
+            let field_of_column: HashMap<i32, i32> = file
+                .column_indices
+                .iter()
+                .zip(&file.fields)
+                .map(|(&col, &field)| (col, field))
+                .collect();
+            let mut current: Option<usize> = None;
+            for (col_idx, info) in metadata.column_infos.iter().enumerate() {
+                if let Some(field_id) = field_of_column.get(&(col_idx as i32)) {
+                    current = top_level.get(field_id).copied();
+                }
+                let Some(idx) = current else { continue };
+                let column = &mut columns[idx];
+                column.bytes += info
+                    .buffer_offsets_and_sizes
+                    .iter()
+                    .map(|(_, size)| size)
+                    .sum::<u64>();
+                for page in info.page_infos.iter() {
+                    column.bytes += page
+                        .buffer_offsets_and_sizes
+                        .iter()
+                        .map(|(_, size)| size)
+                        .sum::<u64>();
+                    encodings[idx].insert(encoding_name(&page.encoding));
+                }
+                if let Some(pages) = column.pages.as_mut() {
+                    *pages += info.page_infos.len();
+                }
+            }
+        }
+    }
+
+    let estimated = legacy && data_files > 0;
+    if estimated {
+        let rows = dataset.count_rows(None).await? as u64;
+        let arrow_schema = ArrowSchema::from(schema);
+        for (column, field) in columns.iter_mut().zip(arrow_schema.fields()) {
+            column.bytes = estimate_value_width(field.data_type()).saturating_mul(rows);
+            column.pages = None;
+        }
+    } else {
+        for (column, names) in columns.iter_mut().zip(encodings) {
+            column.encodings = names.into_iter().collect();
+        }
+    }
+
+    Ok(StorageReport {
+        file_versions: versions.into_iter().collect(),
+        fragments: fragments.len(),
+        data_files,
+        estimated,
+        columns,
+    })
+}
+
+/// Short name of a page encoding: the page layout for 2.1+ files, the
+/// nested array encodings for 2.0 files (e.g. `Nullable/NoNulls/Flat`).
+fn encoding_name(encoding: &PageEncoding) -> String {
+    let debug = match encoding {
+        PageEncoding::Structural(layout) => format!("{:?}", layout.layout),
+        PageEncoding::Legacy(array) => format!("{:?}", array.array_encoding),
+    };
+    variant_chain(&debug)
+}
+
+/// Protobuf oneof variants in the Debug form of an encoding, outermost
+/// first: every `Some(Name(` — as opposed to `Some(Struct {` — names one.
+fn variant_chain(debug: &str) -> String {
+    let names: Vec<&str> = debug
+        .split("Some(")
+        .skip(1)
+        .filter_map(|rest| {
+            let end = rest.find(|c: char| !c.is_alphanumeric() && c != '_')?;
+            (end > 0 && rest[end..].starts_with('(')).then(|| &rest[..end])
+        })
+        .collect();
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variant_chains_come_from_oneof_debug_output() {
+        assert_eq!(
+            variant_chain(
+                "Some(Nullable(Nullable { nullability: Some(NoNulls(NoNull { values: \
+                 Some(ArrayEncoding { array_encoding: Some(Flat(Flat { bits_per_value: 64, \
+                 buffer: Some(Buffer { buffer_index: 0 }), compression: None })) }) })) }))"
+            ),
+            "Nullable/NoNulls/Flat"
+        );
+        assert_eq!(
+            variant_chain("Some(MiniBlockLayout(MiniBlockLayout { num_buffers: 1 }))"),
+            "MiniBlockLayout"
+        );
+        assert_eq!(variant_chain("None"), "none");
+    }
+}
@@ -1,12 +1,22 @@
 use anyhow::Result;
 use std::path::PathBuf;
 
-use crate::functions::{display::cmd_display, head::cmd_head, sample::cmd_sample};
+use crate::display::display_log::{LogPopup, render_log_popup};
+use crate::display::keys::{is_interrupt, pressed};
+use crate::display::redraw::{Redraw, poll_event};
+use crate::display::run_viewer;
+use crate::display::screenshot::ScreenCapture;
+use crate::functions::{
+    display::{DisplayOptions, cmd_display},
+    head::cmd_head,
+    sample::cmd_sample,
+};
+use crate::logging;
 
 pub async fn run_tui(root: PathBuf) -> Result<()> {
     use crossterm::{
         ExecutableCommand,
-        event::{self, Event, KeyCode},
+        event::KeyCode,
         terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
     };
     use ratatui::{
@@ -67,145 +77,165 @@ pub async fn run_tui(root: PathBuf) -> Result<()> {
     let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
 
+    let mut redraw = Redraw::new();
+    let mut capture = ScreenCapture::new();
+    let mut log_popup: Option<LogPopup> = None; // `L` overlay
+
     loop {
-        terminal.draw(|frame| {
-            let size = frame.area();
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Length(3), // title
-                    Constraint::Min(5),    // file list
-                    Constraint::Length(5), // command selector
-                ])
-                .split(size);
-
-            // Header
-            let header = Paragraph::new(format!(
-                "Javelin - Lance Inspector\nDirectory: {}",
-                root.display()
-            ))
-            .block(Block::default().borders(Borders::ALL).title(" Info "));
-            frame.render_widget(header, chunks[0]);
-
-            // File list
-            let items: Vec<ListItem> = entries
-                .iter()
-                .map(|p| {
-                    let name = p.file_name().and_then(|s| s.to_str()).unwrap_or("<?>");
-                    ListItem::new(name.to_string())
-                })
-                .collect();
-
-            let file_list = List::new(items)
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .title(" Lance files "),
-                )
-                .highlight_style(
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD),
-                )
-                .highlight_symbol(">> ");
-
-            frame.render_stateful_widget(
-                file_list,
-                chunks[1],
-                &mut ratatui::widgets::ListState::default().with_selected(Some(selected_file_idx)),
-            );
-
-            // Command chooser
-            let cmd_labels: Vec<&str> = commands
-                .iter()
-                .map(|c| match c {
-                    TuiCommand::Head => "Head",
-                    TuiCommand::Sample => "Sample",
-                    TuiCommand::Display => "Display",
-                })
-                .collect();
-
-            let mut cmd_spans = String::new();
-            for (i, label) in cmd_labels.iter().enumerate() {
-                if i == selected_cmd_idx {
-                    cmd_spans.push_str(&format!("[{}]  ", label));
-                } else {
-                    cmd_spans.push_str(&format!(" {}   ", label));
+        if redraw.take() {
+            let completed = terminal.draw(|frame| {
+                let size = frame.area();
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(3), // title
+                        Constraint::Min(5),    // file list
+                        Constraint::Length(5), // command selector
+                    ])
+                    .split(size);
+
+                // Header
+                let header = Paragraph::new(format!(
+                    "Javelin - Lance Inspector\nDirectory: {}",
+                    root.display()
+                ))
+                .block(Block::default().borders(Borders::ALL).title(" Info "));
+                frame.render_widget(header, chunks[0]);
+
+                // File list
+                let items: Vec<ListItem> = entries
+                    .iter()
+                    .map(|p| {
+                        let name = p.file_name().and_then(|s| s.to_str()).unwrap_or("<?>");
+                        ListItem::new(name.to_string())
+                    })
+                    .collect();
+
+                let file_list = List::new(items)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(" Lance files "),
+                    )
+                    .highlight_style(
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .highlight_symbol(">> ");
+
+                frame.render_stateful_widget(
+                    file_list,
+                    chunks[1],
+                    &mut ratatui::widgets::ListState::default()
+                        .with_selected(Some(selected_file_idx)),
+                );
+
+                // Command chooser
+                let cmd_labels: Vec<&str> = commands
+                    .iter()
+                    .map(|c| match c {
+                        TuiCommand::Head => "Head",
+                        TuiCommand::Sample => "Sample",
+                        TuiCommand::Display => "Display",
+                    })
+                    .collect();
+
+                let mut cmd_spans = String::new();
+                for (i, label) in cmd_labels.iter().enumerate() {
+                    if i == selected_cmd_idx {
+                        cmd_spans.push_str(&format!("[{}]  ", label));
+                    } else {
+                        cmd_spans.push_str(&format!(" {}   ", label));
+                    }
                 }
-            }
 
-            let cmd_para = Paragraph::new(cmd_spans)
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .title(" Command (←/→ to change, Enter to run, q to quit) "),
-                )
-                .style(Style::default().fg(Color::White));
-
-            frame.render_widget(cmd_para, chunks[2]);
-        })?;
+                let cmd_para = Paragraph::new(cmd_spans)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(" Command (←/→ to change, Enter to run, L log, q to quit) "),
+                    )
+                    .style(Style::default().fg(Color::White));
+
+                frame.render_widget(cmd_para, chunks[2]);
+                if let Some(popup) = log_popup.as_mut() {
+                    render_log_popup(frame, popup, &logging::tail(logging::RING_LINES));
+                }
+                capture.render(frame);
+            })?;
+            capture.keep(completed.buffer);
+        }
 
         // Handle input
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => {
-                        break;
-                    }
-                    // File selection up/down
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        if selected_file_idx > 0 {
-                            selected_file_idx -= 1;
-                        }
-                    }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        if selected_file_idx + 1 < entries.len() {
-                            selected_file_idx += 1;
+        // Wait for input off the runtime's workers.
+        let timeout = redraw.poll_timeout();
+        let event = run_viewer(move || poll_event(timeout)).await?;
+        redraw.observe(event.as_ref());
+        if let Some(key) = event.as_ref().and_then(pressed) {
+            if !is_interrupt(&key) && capture.handle_key(&key) {
+                continue;
+            }
+            if let Some(popup) = log_popup.as_mut() {
+                if is_interrupt(&key) {
+                    break;
+                }
+                if !popup.handle_key(key.code) {
+                    log_popup = None;
+                }
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    break;
+                }
+                _ if is_interrupt(&key) => break,
+                // File selection up/down
+                KeyCode::Up | KeyCode::Char('k') => {
+                    selected_file_idx = selected_file_idx.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') if selected_file_idx + 1 < entries.len() => {
+                    selected_file_idx += 1;
+                }
+                // Command selection left/right
+                KeyCode::Left | KeyCode::Char('h') => {
+                    selected_cmd_idx = selected_cmd_idx.saturating_sub(1);
+                }
+                KeyCode::Right | KeyCode::Char('l') if selected_cmd_idx + 1 < commands.len() => {
+                    selected_cmd_idx += 1;
+                }
+                KeyCode::Char('L') => log_popup = Some(LogPopup::new()),
+                // Enter: run selected command on selected file
+                KeyCode::Enter => {
+                    let file = entries[selected_file_idx].clone();
+                    let cmd = commands[selected_cmd_idx];
+
+                    // Leave current TUI before launching nested viewer
+                    disable_raw_mode()?;
+                    terminal.backend_mut().execute(LeaveAlternateScreen)?;
+                    terminal.show_cursor()?;
+
+                    // Reuse existing async command functions
+                    match cmd {
+                        TuiCommand::Head => {
+                            // default n=20 for example; you can tune or prompt later
+                            cmd_head(&file, 20, 0, None, false).await?;
                         }
-                    }
-                    // Command selection left/right
-                    KeyCode::Left | KeyCode::Char('h') => {
-                        if selected_cmd_idx > 0 {
-                            selected_cmd_idx -= 1;
+                        TuiCommand::Sample => {
+                            cmd_sample(&file, 20, None, None, None).await?;
                         }
-                    }
-                    KeyCode::Right | KeyCode::Char('l') => {
-                        if selected_cmd_idx + 1 < commands.len() {
-                            selected_cmd_idx += 1;
+                        TuiCommand::Display => {
+                            cmd_display(&file, &DisplayOptions::default()).await?;
                         }
                     }
-                    // Enter: run selected command on selected file
-                    KeyCode::Enter => {
-                        let file = entries[selected_file_idx].clone();
-                        let cmd = commands[selected_cmd_idx];
-
-                        // Leave current TUI before launching nested viewer
-                        disable_raw_mode()?;
-                        terminal.backend_mut().execute(LeaveAlternateScreen)?;
-                        terminal.show_cursor()?;
-
-                        // Reuse existing async command functions
-                        match cmd {
-                            TuiCommand::Head => {
-                                // default n=20 for example; you can tune or prompt later
-                                cmd_head(&file, 20).await?;
-                            }
-                            TuiCommand::Sample => {
-                                cmd_sample(&file, 20).await?;
-                            }
-                            TuiCommand::Display => {
-                                cmd_display(&file).await?;
-                            }
-                        }
 
-                        // Re-enter launcher TUI after the viewer exits
-                        enable_raw_mode()?;
-                        stdout().execute(EnterAlternateScreen)?;
-                        let backend = CrosstermBackend::new(stdout());
-                        terminal = Terminal::new(backend)?;
-                    }
-                    _ => {}
+                    // Re-enter launcher TUI after the viewer exits
+                    enable_raw_mode()?;
+                    stdout().execute(EnterAlternateScreen)?;
+                    let backend = CrosstermBackend::new(stdout());
+                    terminal = Terminal::new(backend)?;
                 }
+                _ => {}
             }
         }
     }
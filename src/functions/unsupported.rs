@@ -0,0 +1,256 @@
+//! Columns the table cannot show as values (structs, nested lists, maps,
+//! …). They are left out of the table, and out of the column count, with a
+//! notice naming them; `U` brings them back with a short type-aware
+//! placeholder in each cell (`Struct{3}`, `List[5]`, `Map{2}`).
+
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::datatypes::{DataType, Schema};
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use serde::Serialize;
+
+use crate::functions::binary::is_binary_type;
+use crate::functions::encoded::encoded_value_type;
+use crate::functions::format::truncate_text;
+use crate::functions::functions::{LanceLayout, ROWID_COLUMN, is_metadata_column, is_vector_type};
+
+/// Key showing and hiding the unsupported columns.
+pub(crate) const UNSUPPORTED_KEY: char = 'U';
+
+/// Whether the table shows values of `data_type` as such, looking through
+/// dictionary and run-end encoding.
+pub(crate) fn is_supported(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Float32
+            | DataType::Float64
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Boolean
+            | DataType::Utf8
+            | DataType::LargeUtf8
+    ) || is_binary_type(data_type)
+        || is_vector_type(data_type)
+        || encoded_value_type(data_type).is_some_and(is_supported)
+}
+
+/// A column the table hides, as `info` lists it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnsupportedColumn {
+    pub name: String,
+    /// Short type name, e.g. `Struct` or `List`
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// A data column and whether the table shows it, as `info --json` lists
+/// it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ColumnSupport {
+    pub name: String,
+    /// Short type name, as in [`UnsupportedColumn`]
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub supported: bool,
+}
+
+/// The data columns of the stored `schema` (not metadata, not row ids),
+/// each supported unless it is among the `hidden` ones.
+pub(crate) fn column_support(schema: &Schema, hidden: &[UnsupportedColumn]) -> Vec<ColumnSupport> {
+    schema
+        .fields()
+        .iter()
+        .filter(|f| !is_metadata_column(f.name()) && f.name() != ROWID_COLUMN)
+        .map(|f| ColumnSupport {
+            name: f.name().clone(),
+            kind: type_label(f.data_type()),
+            supported: !hidden.iter().any(|h| h.name == *f.name()),
+        })
+        .collect()
+}
+
+/// Short name of `data_type` for notices and `info`.
+pub(crate) fn type_label(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Struct(_) => "Struct".to_string(),
+        DataType::List(_)
+        | DataType::LargeList(_)
+        | DataType::ListView(_)
+        | DataType::LargeListView(_)
+        | DataType::FixedSizeList(_, _) => "List".to_string(),
+        DataType::Map(_, _) => "Map".to_string(),
+        other => {
+            let name = format!("{other:?}");
+            name.split('(').next().unwrap_or_default().to_string()
+        }
+    }
+}
+
+/// The data columns of `schema` (not metadata, not row ids) the table
+/// cannot show, by index.
+pub(crate) fn unsupported_columns(schema: &Schema) -> Vec<usize> {
+    schema
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| !is_metadata_column(f.name()) && f.name() != ROWID_COLUMN)
+        .filter(|(_, f)| !is_supported(f.data_type()))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// [`unsupported_columns`] of a `layout` shown as a table; none for the
+/// layouts with views of their own.
+pub(crate) fn table_unsupported(schema: &Schema, layout: LanceLayout) -> Vec<usize> {
+    match layout {
+        LanceLayout::DenseRowMajor | LanceLayout::Vector1D | LanceLayout::Other => {
+            unsupported_columns(schema)
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The table's columns: the `features` without the `unsupported` ones,
+/// followed by all of those when `show` is set. Appending them keeps the
+/// features' positions, which marks and computed columns refer to.
+pub(crate) fn table_columns(features: &[usize], unsupported: &[usize], show: bool) -> Vec<usize> {
+    let mut cols: Vec<usize> = features
+        .iter()
+        .copied()
+        .filter(|c| !unsupported.contains(c))
+        .collect();
+    if show {
+        cols.extend(unsupported);
+    }
+    cols
+}
+
+/// How many unsupported columns a `layout` table of `cols` leaves out.
+pub(crate) fn hidden_count(schema: &Schema, layout: LanceLayout, cols: &[usize]) -> usize {
+    table_unsupported(schema, layout)
+        .iter()
+        .filter(|c| !cols.contains(c))
+        .count()
+}
+
+/// The unsupported columns of a dataset whose viewer shows `schema` (after
+/// `normalize_for_display`) as a `layout` table, for notices and `info`.
+pub(crate) fn hidden_columns(schema: &Schema, layout: LanceLayout) -> Vec<UnsupportedColumn> {
+    table_unsupported(schema, layout)
+        .into_iter()
+        .map(|i| {
+            let field = schema.field(i);
+            UnsupportedColumn {
+                name: field.name().clone(),
+                kind: type_label(field.data_type()),
+            }
+        })
+        .collect()
+}
+
+/// `"2 columns hidden: meta (Struct), tags (List) — press U to show"`,
+/// or `None` when nothing is hidden.
+pub(crate) fn hidden_notice(hidden: &[UnsupportedColumn]) -> Option<String> {
+    if hidden.is_empty() {
+        return None;
+    }
+    let names: Vec<String> = hidden
+        .iter()
+        .map(|c| format!("{} ({})", c.name, c.kind))
+        .collect();
+    let noun = if hidden.len() == 1 {
+        "column"
+    } else {
+        "columns"
+    };
+    Some(format!(
+        "{} {noun} hidden: {} — press {UNSUPPORTED_KEY} to show",
+        hidden.len(),
+        names.join(", ")
+    ))
+}
+
+/// Placeholder for a non-null value the table cannot show: the field count
+/// of a struct, the length of a list or map, otherwise Arrow's own
+/// rendering cut to a cell.
+pub(crate) fn placeholder(array: &ArrayRef, row: usize) -> String {
+    match array.data_type() {
+        DataType::Struct(fields) => format!("Struct{{{}}}", fields.len()),
+        DataType::List(_) => format!("List[{}]", array.as_list::<i32>().value(row).len()),
+        DataType::LargeList(_) => format!("List[{}]", array.as_list::<i64>().value(row).len()),
+        DataType::FixedSizeList(_, size) => format!("List[{size}]"),
+        DataType::Map(_, _) => format!("Map{{{}}}", array.as_map().value(row).len()),
+        _ => ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default())
+            .map(|f| truncate_text(&f.value(row).to_string()))
+            .unwrap_or_else(|_| "?".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Date32Array, Int32Array, ListArray, StructArray};
+    use arrow::datatypes::{Field, Int32Type};
+    use std::sync::Arc;
+
+    #[test]
+    fn placeholders_name_the_shape_of_each_value() {
+        let tags: ArrayRef = Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1), Some(2), Some(3)]),
+            Some(vec![]),
+        ]));
+        assert_eq!(placeholder(&tags, 0), "List[3]");
+        assert_eq!(placeholder(&tags, 1), "List[0]");
+
+        let meta: ArrayRef = Arc::new(StructArray::from(vec![
+            (
+                Arc::new(Field::new("a", DataType::Int32, false)),
+                Arc::new(Int32Array::from(vec![1])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("b", DataType::Int32, false)),
+                Arc::new(Int32Array::from(vec![2])) as ArrayRef,
+            ),
+        ]));
+        assert_eq!(placeholder(&meta, 0), "Struct{2}");
+
+        let day: ArrayRef = Arc::new(Date32Array::from(vec![0]));
+        assert_eq!(placeholder(&day, 0), "1970-01-01");
+    }
+
+    #[test]
+    fn hidden_columns_are_listed_in_the_notice() {
+        let schema = Schema::new(vec![
+            Field::new("x", DataType::Float64, false),
+            Field::new(
+                "meta",
+                DataType::Struct(vec![Field::new("a", DataType::Int32, false)].into()),
+                false,
+            ),
+            Field::new(
+                "tags",
+                DataType::List(Arc::new(Field::new("item", DataType::Int32, true))),
+                false,
+            ),
+            Field::new("n_rows", DataType::Struct(Default::default()), true),
+            Field::new("hash", DataType::FixedSizeBinary(32), false),
+        ]);
+        assert_eq!(unsupported_columns(&schema), [1, 2]);
+        assert_eq!(table_columns(&[0], &[1, 2], false), [0]);
+        assert_eq!(table_columns(&[0, 2], &[1, 2], true), [0, 1, 2]);
+        assert_eq!(table_columns(&[2, 3], &[1, 2], true), [3, 1, 2]);
+        assert_eq!(hidden_count(&schema, LanceLayout::Other, &[0, 1]), 1);
+        let hidden = hidden_columns(&schema, LanceLayout::Other);
+        assert_eq!(
+            hidden_notice(&hidden).unwrap(),
+            "2 columns hidden: meta (Struct), tags (List) — press U to show"
+        );
+        assert!(hidden_columns(&schema, LanceLayout::SparseCoo).is_empty());
+        assert_eq!(hidden_notice(&[]), None);
+    }
+}
@@ -0,0 +1,513 @@
+//! `validate`: structural integrity checks for javelin-friendly datasets.
+
+use anyhow::Result;
+use arrow::array::*;
+use arrow::datatypes::{DataType, Schema as ArrowSchema};
+use futures::TryStreamExt;
+use lance::Dataset;
+use serde::Serialize;
+use serde_json::json;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::datasets::open_dataset;
+use crate::errors::ErrorCategory;
+use crate::functions::functions::LanceLayout;
+use crate::functions::info::{dataset_layout, metadata_dims};
+use crate::functions::progress::Progress;
+
+/// Offending items listed per failed check before the rest is summarised.
+const MAX_DETAILS: usize = 5;
+
+/// Outcome of one validation check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub passed: bool,
+    /// What was checked (on success) or where it failed
+    pub details: Vec<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            passed: true,
+            details: vec![detail.into()],
+        }
+    }
+
+    fn fail(name: &'static str, details: Vec<String>) -> Self {
+        Self {
+            name,
+            passed: false,
+            details,
+        }
+    }
+}
+
+/// Run every check on the dataset at `filepath` and print the results (as
+/// JSON with `json`). Fails when any check fails, so the exit code is
+/// non-zero.
+pub async fn cmd_validate(filepath: &Path, check_finite: bool, json: bool) -> Result<()> {
+    write_validate(&mut io::stdout(), filepath, check_finite, json).await
+}
+
+/// [`cmd_validate`] writing its results to `out`.
+pub async fn write_validate(
+    out: &mut dyn Write,
+    filepath: &Path,
+    check_finite: bool,
+    json: bool,
+) -> Result<()> {
+    let dataset = open_dataset(filepath).await?;
+    let checks = validate_dataset(&dataset, check_finite, !json).await?;
+    let failed = checks.iter().filter(|c| !c.passed).count();
+
+    if json {
+        let report = json!({
+            "path": filepath.display().to_string(),
+            "passed": failed == 0,
+            "checks": checks,
+        });
+        writeln!(out, "{}", serde_json::to_string_pretty(&report)?)?;
+    } else {
+        writeln!(out, "=== Validate {} ===", filepath.display())?;
+        for check in &checks {
+            let status = if check.passed { "PASS" } else { "FAIL" };
+            let mut details = check.details.iter();
+            writeln!(
+                out,
+                "[{status}] {}: {}",
+                check.name,
+                details.next().map(String::as_str).unwrap_or("")
+            )?;
+            for detail in details {
+                writeln!(out, "       {detail}")?;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(
+            ErrorCategory::CheckFailed.error(format!("{failed} of {} checks failed", checks.len()))
+        );
+    }
+    if !json {
+        writeln!(out, "All {} checks passed", checks.len())?;
+    }
+    Ok(())
+}
+
+/// Run the checks that apply to the dataset's layout. NaN/Inf values are
+/// only looked for with `check_finite`.
+pub(crate) async fn validate_dataset(
+    dataset: &Dataset,
+    check_finite: bool,
+    progress: bool,
+) -> Result<Vec<CheckResult>> {
+    let schema = ArrowSchema::from(dataset.schema());
+    let (layout, mismatches) = dataset_layout(dataset).await?;
+
+    let mut checks = vec![if mismatches.is_empty() {
+        CheckResult::pass("layout", format!("{layout:?}"))
+    } else {
+        CheckResult::fail("layout", mismatches)
+    }];
+
+    let dims = match declared_dims(&schema) {
+        Ok(dims) => {
+            checks.push(CheckResult::pass(
+                "schema metadata",
+                match dims {
+                    Some((r, c)) => format!("rows/cols parse as {r} × {c}"),
+                    None => "no rows/cols metadata".to_string(),
+                },
+            ));
+            dims
+        }
+        Err(reason) => {
+            checks.push(CheckResult::fail("schema metadata", vec![reason]));
+            None
+        }
+    };
+
+    checks.push(row_count_check(dataset).await?);
+    if let Some(dims) = metadata_dims(dataset).await? {
+        checks.push(if dims.mismatch() {
+            CheckResult::fail(
+                "metadata dims",
+                vec![format!(
+                    "n_rows/n_cols declare {} × {} but the data is {} × {}",
+                    dims.declared.0, dims.declared.1, dims.actual.0, dims.actual.1
+                )],
+            )
+        } else {
+            CheckResult::pass(
+                "metadata dims",
+                format!(
+                    "n_rows/n_cols match the data ({} × {})",
+                    dims.actual.0, dims.actual.1
+                ),
+            )
+        });
+    }
+
+    let mut scan = ScanChecks::new(layout, &schema, dims, check_finite);
+    let mut stream = dataset.scan().try_into_stream().await?;
+    let total = dataset.count_rows(None).await?;
+    let mut bar = Progress::new("validate", total, progress);
+    let mut offset = 0;
+    while let Some(batch) = stream.try_next().await? {
+        bar.inc(batch.num_rows());
+        scan.visit(&batch, offset);
+        offset += batch.num_rows();
+    }
+    bar.finish();
+    checks.extend(scan.results());
+    Ok(checks)
+}
+
+/// `rows`/`cols` schema metadata: `None` when absent, an error when only
+/// one is set or either does not parse.
+fn declared_dims(schema: &ArrowSchema) -> std::result::Result<Option<(usize, usize)>, String> {
+    let md = schema.metadata();
+    let parse = |key: &str| {
+        md.get(key)
+            .map(|v| {
+                v.trim()
+                    .parse::<usize>()
+                    .map_err(|_| format!("{key} = {v:?} is not a non-negative integer"))
+            })
+            .transpose()
+    };
+    match (parse("rows")?, parse("cols")?) {
+        (Some(r), Some(c)) => Ok(Some((r, c))),
+        (None, None) => Ok(None),
+        (Some(_), None) => Err("rows is set but cols is missing".to_string()),
+        (None, Some(_)) => Err("cols is set but rows is missing".to_string()),
+    }
+}
+
+/// The dataset row count must equal the fragments' physical rows minus
+/// their deletions.
+async fn row_count_check(dataset: &Dataset) -> Result<CheckResult> {
+    const NAME: &str = "row count";
+    let rows = dataset.count_rows(None).await?;
+    let mut expected = 0;
+    for fragment in dataset.fragments().iter() {
+        let unknown = |what: &str| {
+            CheckResult::pass(
+                NAME,
+                format!("{rows} rows (fragment {} has no {what})", fragment.id),
+            )
+        };
+        let Some(physical) = fragment.physical_rows else {
+            return Ok(unknown("row count"));
+        };
+        let deleted = match &fragment.deletion_file {
+            None => 0,
+            Some(file) => match file.num_deleted_rows {
+                Some(n) => n,
+                None => return Ok(unknown("deletion count")),
+            },
+        };
+        expected += physical.saturating_sub(deleted);
+    }
+    Ok(if expected == rows {
+        CheckResult::pass(NAME, format!("{rows} rows match fragment metadata"))
+    } else {
+        CheckResult::fail(
+            NAME,
+            vec![format!(
+                "dataset reports {rows} rows but fragments hold {expected}"
+            )],
+        )
+    })
+}
+
+/// Per-row checks accumulated over one scan of the dataset.
+struct ScanChecks {
+    layout: LanceLayout,
+    /// FixedSizeList width from the schema (dense layouts)
+    width: Option<i32>,
+    dims: Option<(usize, usize)>,
+    check_finite: bool,
+    width_errors: Vec<String>,
+    width_failures: usize,
+    null_index_errors: Vec<String>,
+    null_index_failures: usize,
+    bounds_errors: Vec<String>,
+    bounds_failures: usize,
+    finite_errors: Vec<String>,
+    finite_failures: usize,
+}
+
+impl ScanChecks {
+    fn new(
+        layout: LanceLayout,
+        schema: &ArrowSchema,
+        dims: Option<(usize, usize)>,
+        check_finite: bool,
+    ) -> Self {
+        let width = match schema.fields().first().map(|f| f.data_type()) {
+            Some(DataType::FixedSizeList(_, w)) if layout == LanceLayout::DenseRowMajor => Some(*w),
+            _ => None,
+        };
+        Self {
+            layout,
+            width,
+            dims,
+            check_finite,
+            width_errors: Vec::new(),
+            width_failures: 0,
+            null_index_errors: Vec::new(),
+            null_index_failures: 0,
+            bounds_errors: Vec::new(),
+            bounds_failures: 0,
+            finite_errors: Vec::new(),
+            finite_failures: 0,
+        }
+    }
+
+    /// Check one batch whose first row is dataset row `offset`.
+    fn visit(&mut self, batch: &RecordBatch, offset: usize) {
+        match self.layout {
+            LanceLayout::DenseRowMajor => self.visit_dense(batch, offset),
+            LanceLayout::SparseCoo => self.visit_coo(batch, offset),
+            _ => {}
+        }
+        if self.check_finite {
+            self.visit_finite(batch, offset);
+        }
+    }
+
+    fn visit_dense(&mut self, batch: &RecordBatch, offset: usize) {
+        let (Some(width), Some(list)) = (
+            self.width,
+            batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<FixedSizeListArray>(),
+        ) else {
+            return;
+        };
+        let expected = list.len() * width.max(0) as usize;
+        if list.value_length() != width || list.values().len() < expected {
+            self.width_failures += 1;
+            push_limited(
+                &mut self.width_errors,
+                format!(
+                    "rows {}–{}: list width {} with {} child values, schema width {width}",
+                    offset,
+                    offset + list.len().saturating_sub(1),
+                    list.value_length(),
+                    list.values().len()
+                ),
+            );
+        }
+    }
+
+    fn visit_coo(&mut self, batch: &RecordBatch, offset: usize) {
+        let cast = |name: &str| {
+            batch
+                .column_by_name(name)
+                .and_then(|c| arrow::compute::cast(c, &DataType::Int64).ok())
+        };
+        let (Some(rows), Some(cols)) = (cast("row"), cast("col")) else {
+            return;
+        };
+        let rows = rows.as_any().downcast_ref::<Int64Array>().unwrap();
+        let cols = cols.as_any().downcast_ref::<Int64Array>().unwrap();
+        for i in 0..batch.num_rows() {
+            let triple = offset + i;
+            if rows.is_null(i) || cols.is_null(i) {
+                self.null_index_failures += 1;
+                push_limited(
+                    &mut self.null_index_errors,
+                    format!("triple {triple}: null or non-integer row/col index"),
+                );
+                continue;
+            }
+            let (r, c) = (rows.value(i), cols.value(i));
+            let in_bounds = match self.dims {
+                Some((nr, nc)) => r >= 0 && c >= 0 && (r as usize) < nr && (c as usize) < nc,
+                None => r >= 0 && c >= 0,
+            };
+            if !in_bounds {
+                self.bounds_failures += 1;
+                let bound = self
+                    .dims
+                    .map_or("non-negative indices".to_string(), |(nr, nc)| {
+                        format!("declared {nr} × {nc}")
+                    });
+                push_limited(
+                    &mut self.bounds_errors,
+                    format!("triple {triple}: (row {r}, col {c}) outside {bound}"),
+                );
+            }
+        }
+    }
+
+    fn visit_finite(&mut self, batch: &RecordBatch, offset: usize) {
+        let schema = batch.schema();
+        for (field, col) in schema.fields().iter().zip(batch.columns()) {
+            let values = match col.data_type() {
+                DataType::FixedSizeList(_, w) => {
+                    let list = col.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
+                    float_positions(list.values(), (*w).max(1) as usize)
+                }
+                _ => float_positions(col, 1),
+            };
+            for (row, idx) in values {
+                self.finite_failures += 1;
+                let at = idx.map(|i| format!("[{i}]")).unwrap_or_default();
+                push_limited(
+                    &mut self.finite_errors,
+                    format!("row {}: {}{at} is not finite", offset + row, field.name()),
+                );
+            }
+        }
+    }
+
+    fn results(self) -> Vec<CheckResult> {
+        let mut out = Vec::new();
+        match self.layout {
+            LanceLayout::DenseRowMajor => out.push(summarise(
+                "list widths",
+                self.width_errors,
+                self.width_failures,
+                format!("every row has {} values", self.width.unwrap_or_default()),
+            )),
+            LanceLayout::SparseCoo => {
+                out.push(summarise(
+                    "coo indices",
+                    self.null_index_errors,
+                    self.null_index_failures,
+                    "row/col/value lengths equal, no null indices",
+                ));
+                out.push(summarise(
+                    "coo bounds",
+                    self.bounds_errors,
+                    self.bounds_failures,
+                    match self.dims {
+                        Some((r, c)) => format!("all indices within {r} × {c}"),
+                        None => "all indices non-negative (no rows/cols metadata)".to_string(),
+                    },
+                ));
+            }
+            _ => {}
+        }
+        if self.check_finite {
+            out.push(summarise(
+                "finite values",
+                self.finite_errors,
+                self.finite_failures,
+                "no NaN or infinite values",
+            ));
+        }
+        out
+    }
+}
+
+/// Positions of NaN/Inf values in a float array: (row, index within the row
+/// when `per_row` > 1). Non-float arrays have none.
+fn float_positions(values: &ArrayRef, per_row: usize) -> Vec<(usize, Option<usize>)> {
+    let positions: Vec<usize> = match values.data_type() {
+        DataType::Float64 => values
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.is_some_and(|v| !v.is_finite()))
+            .map(|(i, _)| i)
+            .collect(),
+        DataType::Float32 => values
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.is_some_and(|v| !v.is_finite()))
+            .map(|(i, _)| i)
+            .collect(),
+        _ => Vec::new(),
+    };
+    positions
+        .into_iter()
+        .map(|i| {
+            if per_row > 1 {
+                (i / per_row, Some(i % per_row))
+            } else {
+                (i, None)
+            }
+        })
+        .collect()
+}
+
+fn push_limited(errors: &mut Vec<String>, detail: String) {
+    if errors.len() < MAX_DETAILS {
+        errors.push(detail);
+    }
+}
+
+fn summarise(
+    name: &'static str,
+    mut errors: Vec<String>,
+    failures: usize,
+    ok: impl Into<String>,
+) -> CheckResult {
+    if failures == 0 {
+        return CheckResult::pass(name, ok);
+    }
+    let shown = errors.len();
+    errors.insert(0, format!("{failures} problem(s); first {shown}:"));
+    CheckResult::fail(name, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn schema_with(md: &[(&str, &str)]) -> ArrowSchema {
+        ArrowSchema::empty().with_metadata(
+            md.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn declared_dims_need_both_keys_as_integers() {
+        assert_eq!(declared_dims(&schema_with(&[])), Ok(None));
+        assert_eq!(
+            declared_dims(&schema_with(&[("rows", "3"), ("cols", "4")])),
+            Ok(Some((3, 4)))
+        );
+        assert!(declared_dims(&schema_with(&[("rows", "3")])).is_err());
+        assert!(
+            declared_dims(&schema_with(&[("rows", "3"), ("cols", "-1")]))
+                .unwrap_err()
+                .contains("cols = \"-1\"")
+        );
+    }
+
+    #[test]
+    fn non_finite_values_are_located_within_rows() {
+        let values: ArrayRef = std::sync::Arc::new(Float64Array::from(vec![
+            1.0,
+            f64::NAN,
+            2.0,
+            3.0,
+            f64::INFINITY,
+            4.0,
+        ]));
+        assert_eq!(
+            float_positions(&values, 3),
+            vec![(0, Some(1)), (1, Some(1))]
+        );
+        assert_eq!(float_positions(&values, 1), vec![(1, None), (4, None)]);
+    }
+}
@@ -1,55 +1,903 @@
+// Without the viewers much crate-private code has no caller.
+#![cfg_attr(not(feature = "tui"), allow(dead_code))]
+
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod bench;
+pub mod cache;
 pub mod datasets;
+#[cfg(feature = "tui")]
 pub mod display;
+pub mod errors;
 pub mod functions;
+pub mod logging;
+pub mod session;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "tui"))]
 mod tests;
 
+pub use functions::functions::LanceLayout;
+pub use functions::normalized::{ReadOptions, open_normalized, open_normalized_with};
+
+use std::path::Path;
 use std::sync::Once;
 
 static INIT: Once = Once::new();
 
-pub fn init() {
-    INIT.call_once(|| {
-        // Read RUST_LOG env variable, default to "info" if not set
-        let env = env_logger::Env::default().default_filter_or("info");
-
-        // don't panic if called multiple times across binaries
-        let _ = env_logger::Builder::from_env(env).try_init();
-    });
+/// Install the logger, writing to `log_file` (or `JAVELIN_LOG_FILE`) instead
+/// of stderr when given; see [`logging`]. Only the first call has an effect.
+pub fn init(log_file: Option<&Path>) -> std::io::Result<()> {
+    let mut result = Ok(());
+    INIT.call_once(|| result = logging::install(log_file));
+    result
 }
 
-use clap::{Parser, Subcommand};
+use anyhow::Error;
+#[cfg(feature = "tui")]
+use clap::{Args, Parser, Subcommand};
+use std::fmt;
+#[cfg(feature = "tui")]
 use std::path::PathBuf;
 
+#[cfg(feature = "tui")]
+use crate::errors::ErrorCategory;
+#[cfg(feature = "tui")]
+use crate::functions::{
+    clusters::cmd_clusters,
+    convert::cmd_convert,
+    dedup::cmd_dedup,
+    display::{DisplayOptions, Screenshot, cmd_display},
+    export::ExportOptions,
+    fingerprint::cmd_fingerprint,
+    generate::{AppendOptions, GenerateOptions, cmd_generate},
+    head::cmd_head,
+    import::cmd_import,
+    info::cmd_info,
+    laplacian::cmd_laplacian,
+    names::load_names,
+    neighbors::cmd_neighbors,
+    outliers::{DEFAULT_K, OutlierMethod, TOP_OUTLIERS, cmd_outliers},
+    peek::cmd_peek,
+    plot::cmd_plot_lambdas,
+    sample::cmd_sample,
+    set_meta::cmd_set_meta,
+    sparse_stats::cmd_sparse_stats,
+    stats::cmd_stats,
+    tui::run_tui,
+    validate::cmd_validate,
+};
+
+#[cfg(feature = "tui")]
 #[derive(Parser)]
-#[command(name = "javelin", about = "Display and work with Lance matrices")]
+#[command(
+    name = "javelin",
+    about = "Display and work with Lance matrices",
+    after_help = errors::EXIT_CODES_HELP
+)]
 pub struct Cli {
     /// Path to a lance file or directory
     #[arg(long)]
     pub filepath: Option<PathBuf>,
+    /// Append log output to this file instead of stderr (also
+    /// JAVELIN_LOG_FILE); the viewers show recent lines with `L` either way
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+    /// On failure print the whole chain of causes, not just the summary
+    #[arg(long)]
+    pub verbose: bool,
+    /// Neither read nor write the on-disk column statistics cache
+    #[arg(long, global = true)]
+    pub no_cache: bool,
     #[command(subcommand)]
     pub cmd: Option<Command>,
 }
 
+/// Options of the viewers, taken by the subcommands that open one.
+#[cfg(feature = "tui")]
+#[derive(Args, Clone)]
+pub struct ViewerArgs {
+    /// Aggregates after each viewer row (and each transposed feature),
+    /// e.g. `mean,std,median`; `a` shows or hides them
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub row_stats: Vec<display::Aggregate>,
+    /// Start the viewers with the row aggregates hidden
+    #[arg(long)]
+    pub no_row_stats: bool,
+    /// How NULLs enter the row aggregates: left out, counted as 0.0, or
+    /// making the aggregate NaN; `_` cycles through them in the viewers
+    #[arg(long, value_enum, default_value = "skip")]
+    pub null_policy: display::NullPolicy,
+    /// Features the row aggregates run over: `auto` leaves out index-like
+    /// columns (named `id`, `index` or `row`, or integers rising row by
+    /// row) of a table without `col_*` features, `all` keeps every one, and
+    /// a list of names aggregates only those
+    #[arg(long, default_value = "auto", value_name = "auto|all|NAMES")]
+    pub stats_cols: display::StatsColumns,
+    /// Std, as a fraction of |mean|, at or below which a column counts as
+    /// near-constant: the viewers offer to hide such columns with `Z`
+    #[arg(
+        long,
+        default_value_t = display::DEFAULT_CONSTANT_THRESHOLD,
+        value_parser = functions::constant_columns::parse_constant_threshold
+    )]
+    pub constant_threshold: f64,
+    /// Delimiter ending the name prefix `g` in the viewers groups columns by
+    #[arg(long, default_value = "_")]
+    pub group_delimiter: String,
+    /// Group the columns as this file says instead: a JSON object of group
+    /// name → member names, or one `name: member, member` line per group
+    #[arg(long, value_name = "PATH")]
+    pub groups: Option<PathBuf>,
+    /// Number rows, features and nodes in the viewers from 0 or 1, or from
+    /// 0 without the Row column (`none`); `#` cycles through them
+    #[arg(long, value_enum, default_value = "0")]
+    pub index_base: display::IndexBase,
+}
+
+#[cfg(feature = "tui")]
+impl Default for ViewerArgs {
+    fn default() -> Self {
+        ViewerArgs {
+            row_stats: Vec::new(),
+            no_row_stats: false,
+            null_policy: display::NullPolicy::default(),
+            stats_cols: display::StatsColumns::default(),
+            constant_threshold: display::DEFAULT_CONSTANT_THRESHOLD,
+            group_delimiter: "_".to_string(),
+            groups: None,
+            index_base: display::IndexBase::default(),
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+impl ViewerArgs {
+    /// Make these the settings of every viewer the process opens.
+    pub fn configure(&self) {
+        display::configure_row_stats(self.row_stats.clone(), !self.no_row_stats, self.null_policy);
+        display::configure_stats_columns(self.stats_cols.clone());
+        display::configure_constant_threshold(self.constant_threshold);
+        display::configure_index_base(self.index_base);
+        display::configure_column_groups(match &self.groups {
+            Some(path) => display::GroupSpec::File(path.clone()),
+            None => display::GroupSpec::Delimiter(self.group_delimiter.clone()),
+        });
+    }
+}
+
+#[cfg(feature = "tui")]
 #[derive(Subcommand)]
 pub enum Command {
-    Tui,
-    Info,
+    Tui {
+        #[command(flatten)]
+        viewer: ViewerArgs,
+    },
+    Info {
+        /// Also report on-disk bytes, pages and encoding per column
+        #[arg(long)]
+        verbose: bool,
+        /// Print the information as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a one-screen summary: layout, dimensions, column types, nulls,
+    /// the first rows and, for COO, density and top row degrees
+    Peek {
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
+        /// Compute the column figures over every row rather than the first
+        /// 10000
+        #[arg(long)]
+        full: bool,
+    },
     Head {
         n: usize,
+        /// Skip this many rows before the first one shown
+        #[arg(long, alias = "skip", default_value = "0")]
+        offset: usize,
+        /// Feature columns shown at once (default 8, or what the last
+        /// session of the dataset used); `<`/`>` adjust it in the viewer
+        #[arg(long)]
+        visible_cols: Option<std::num::NonZeroUsize>,
+        /// Debug: also read the rows deleted in this version, struck
+        /// through in the viewer
+        #[arg(long)]
+        include_deleted: bool,
+        #[command(flatten)]
+        viewer: ViewerArgs,
     },
     Sample {
         n: usize,
+        /// Seed of the random draw; a random one when omitted
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Write the sampled rows to this new Lance dataset instead of
+        /// opening the viewer
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Overwrite `--output` if it already exists
+        #[arg(long, requires = "output")]
+        force: bool,
+        /// Write the rows as the viewer shows them (`col_*` columns for a
+        /// dense matrix) instead of in the source schema
+        #[arg(long, requires = "output")]
+        normalized: bool,
+        /// Format of `--output`
+        #[arg(long, value_enum, default_value = "lance", requires = "output")]
+        format: functions::export::ExportFormat,
+        /// Columns to keep, e.g. `col_0,col_5..col_9` (ranges include both
+        /// ends); a vector column is sliced to the named dimensions
+        #[arg(long, value_parser = functions::export::parse_select, requires = "output")]
+        select: Option<Vec<functions::export::ColumnSelector>>,
+        /// Downcast Float64 values (integer columns are kept)
+        #[arg(long, value_enum, requires = "output")]
+        cast: Option<functions::export::CastTarget>,
+        /// Feature columns shown at once (default 8, or what the last
+        /// session of the dataset used); `<`/`>` adjust it in the viewer
+        #[arg(long)]
+        visible_cols: Option<std::num::NonZeroUsize>,
+        #[command(flatten)]
+        viewer: ViewerArgs,
+    },
+    Stats {
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+        /// Distinct values counted per Boolean/string/integer column before
+        /// it is reported as high cardinality
+        #[arg(long, default_value_t = functions::frequencies::DEFAULT_MAX_DISTINCT)]
+        max_distinct: usize,
+        /// Std, as a fraction of |mean|, at or below which a column is
+        /// listed as near-constant
+        #[arg(
+        long,
+        default_value_t = display::DEFAULT_CONSTANT_THRESHOLD,
+        value_parser = functions::constant_columns::parse_constant_threshold
+    )]
+        constant_threshold: f64,
+    },
+    Display {
+        /// Refuse to load datasets estimated to need more memory than this
+        /// (e.g. `512MiB`, `4G`)
+        #[arg(long, default_value = "2GiB", value_parser = functions::display::parse_byte_size)]
+        max_memory: u64,
+        /// Only show source rows A..B (zero-based, end exclusive)
+        #[arg(long, value_parser = functions::display::parse_slice_range)]
+        rows: Option<functions::display::SliceRange>,
+        /// Read at most this many rows (of `--rows`, when given); the viewer
+        /// flags the cut with a banner
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Rows per batch read from Lance (read granularity)
+        #[arg(long)]
+        batch_size: Option<std::num::NonZeroUsize>,
+        /// Only show feature columns C..D (zero-based, end exclusive)
+        #[arg(long, value_parser = functions::display::parse_slice_range)]
+        cols: Option<functions::display::SliceRange>,
+        /// Sidecar file naming the feature columns: a JSON array or one
+        /// name per line, in feature order
+        #[arg(long)]
+        names: Option<PathBuf>,
+        /// Render the first frame headlessly to this file and exit (ANSI
+        /// colours for a `.ansi` path, plain text otherwise)
+        #[arg(long)]
+        screenshot: Option<PathBuf>,
+        /// Terminal size of `--screenshot`, as WIDTHxHEIGHT
+        #[arg(long, default_value = "100x30", value_parser = functions::display::parse_screen_size)]
+        screenshot_size: (u16, u16),
+        /// Feature columns shown at once (default 8, or what the last
+        /// session of the dataset used); `<`/`>` adjust it in the viewer
+        #[arg(long)]
+        visible_cols: Option<std::num::NonZeroUsize>,
+        /// Debug: also read the rows deleted in this version, struck
+        /// through in the viewer
+        #[arg(long)]
+        include_deleted: bool,
+        /// Mark the rows that did not exist at this earlier version, with a
+        /// count in the metadata panel
+        #[arg(long)]
+        since_version: Option<u64>,
+        /// Only show the rows added since `--since-version`
+        #[arg(long, requires = "since_version")]
+        only_new: bool,
+        /// Keep a dense dataset's vector column whole, one preview per
+        /// cell, instead of expanding it to `col_*` features
+        #[arg(long, conflicts_with_all = ["cols", "names"])]
+        no_expand: bool,
+        /// Keep the view on the last page of rows and add the rows newer
+        /// versions append, like `tail -f`; scrolling up pauses, `G` resumes
+        #[arg(long, conflicts_with_all = ["rows", "limit", "screenshot", "include_deleted", "only_new"])]
+        follow: bool,
+        /// Seconds between checks for a new version under `--follow`
+        #[arg(long, default_value = "1", requires = "follow")]
+        follow_interval: std::num::NonZeroU64,
+        #[command(flatten)]
+        viewer: ViewerArgs,
     },
-    Stats,
-    Display,
     Generate {
+        /// Output directory for the generated datasets
+        #[arg(long, default_value = "javelin_test")]
+        out: PathBuf,
+        /// Overwrite the output directory if it already exists
+        #[arg(long)]
+        force: bool,
+        /// Shape of the generated matrices
+        #[arg(long, value_enum, default_value = "cliques")]
+        kind: functions::generate::GenerateKind,
         #[arg(long, default_value = "200")]
         n_items: usize,
         #[arg(long, default_value = "300")]
         n_dims: usize,
         #[arg(long, default_value = "42")]
         seed: u64,
+        /// Number of Gaussian cliques
+        #[arg(long, default_value = "5")]
+        n_cliques: usize,
+        /// Standard deviation of points around their clique center
+        #[arg(long, default_value = "0.3")]
+        noise: f64,
+        /// Fraction of points generated as outliers
+        #[arg(long, default_value = "0.05")]
+        outlier_frac: f64,
+        /// Band half-width for `--kind banded`
+        #[arg(long, default_value = "3")]
+        bandwidth: usize,
+        /// Number of diagonal blocks for `--kind block`
+        #[arg(long, default_value = "4")]
+        blocks: usize,
+        /// Power-law exponent for `--kind powerlaw` (must be > 1)
+        #[arg(long, default_value = "2.5")]
+        alpha: f64,
+        /// Write the legacy genegraph_storage layout instead of plain Lance datasets
+        #[arg(long)]
+        genegraph: bool,
+        /// Then grow the datasets: append `--rounds` batches of rows (a
+        /// tenth of `--n-items` each) as new versions (`--kind cliques` only)
+        #[arg(long)]
+        append: bool,
+        /// Batches `--append` adds
+        #[arg(long, default_value = "3", requires = "append")]
+        rounds: usize,
+        /// Milliseconds `--append` waits before each batch
+        #[arg(long, default_value = "1000", requires = "append")]
+        interval_ms: u64,
+    },
+    /// Convert a CSV, Parquet or NumPy `.npy` file into a Lance dataset
+    Import {
+        /// Input file to convert
+        #[arg(long)]
+        input: PathBuf,
+        /// Destination Lance dataset directory (must not exist)
+        #[arg(long)]
+        output: PathBuf,
+        /// Input format; inferred from the file extension when omitted
+        #[arg(long, value_enum)]
+        format: Option<functions::import::ImportFormat>,
+    },
+    /// Convert between a dense (FixedSizeList) and a COO dataset
+    Convert {
+        /// Layout to write
+        #[arg(long, value_enum)]
+        to: functions::convert::ConvertTarget,
+        /// Destination Lance dataset directory (must not exist)
+        #[arg(long)]
+        output: PathBuf,
+        /// Dense → COO: drop entries with a magnitude at or below this
+        /// (exact zeros by default)
+        #[arg(long, default_value_t = 0.0)]
+        threshold: f64,
+        /// COO → dense: refuse matrices needing more memory than this
+        #[arg(long, default_value = "2GiB", value_parser = functions::display::parse_byte_size)]
+        max_memory: u64,
+    },
+    /// Check a dataset's structure; exits non-zero if any check fails
+    Validate {
+        /// Also fail on NaN or infinite float values
+        #[arg(long)]
+        check_finite: bool,
+        /// Print the check results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Hash the schema and logical values of a dataset, independent of its
+    /// fragment layout, to compare it against a golden copy
+    Fingerprint {
+        /// Round floats to this many decimals before hashing
+        #[arg(long, value_name = "K")]
+        round: Option<u32>,
+        /// Fail unless the fingerprint equals this hash
+        #[arg(long, value_name = "HASH")]
+        verify: Option<String>,
+    },
+    /// Report rows that repeat an earlier row exactly (NaN equals NaN);
+    /// optionally write the dataset without them
+    Dedup {
+        /// Write the distinct rows, first occurrences in order, to a new
+        /// Lance dataset here
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+        /// Round floats to this many decimals before comparing
+        #[arg(long, value_name = "K")]
+        round: Option<u32>,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the rows with values outside k·IQR (or k·std) of their feature
+    /// column, most severe first
+    Outliers {
+        /// Multiple of the IQR or std beyond which a value is an outlier
+        #[arg(long, default_value_t = DEFAULT_K)]
+        k: f64,
+        /// Measure the spread of each feature by IQR or std
+        #[arg(long, value_enum, default_value_t)]
+        method: OutlierMethod,
+        /// Rows to list
+        #[arg(long, default_value_t = TOP_OUTLIERS)]
+        top: usize,
+    },
+    /// List the rows of a dense matrix nearest to one row
+    Neighbors {
+        /// Row to find neighbours of (zero-based)
+        #[arg(long)]
+        row: usize,
+        /// Number of neighbours to list
+        #[arg(long, default_value_t = functions::neighbors::DEFAULT_NEIGHBORS)]
+        k: usize,
+        #[arg(long, value_enum, default_value = "cosine")]
+        metric: functions::neighbors::Metric,
+    },
+    /// Histogram of a 1D dataset (e.g. eigenvalues), full-screen or as text
+    PlotLambdas {
+        /// Number of histogram bins (`+`/`-` change it in the viewer)
+        #[arg(long, default_value = "50")]
+        bins: usize,
+        /// Print the histogram as text rows instead of opening the viewer
+        #[arg(long)]
+        plain: bool,
     },
+    /// Check a COO matrix as a graph Laplacian and estimate its spectrum
+    Laplacian {
+        #[arg(long, value_enum, default_value = "combinatorial")]
+        mode: functions::laplacian::LaplacianMode,
+        /// Skip the eigenvalue estimate above this many nodes
+        #[arg(long, default_value_t = functions::laplacian::DEFAULT_MAX_N)]
+        max_n: usize,
+        /// Show the report in the Structure section of the COO viewer
+        #[arg(long)]
+        tui: bool,
+        #[command(flatten)]
+        viewer: ViewerArgs,
+    },
+    /// Shape, density, duplicates, bandwidth and profile of a COO matrix
+    SparseStats {
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+        /// Also report the bandwidth and profile under this reordering
+        #[arg(long, value_enum)]
+        reorder: Option<functions::reorder::Reorder>,
+        /// Write the reordering's permutation to a new 1D Lance dataset
+        #[arg(long, requires = "reorder")]
+        permutation_out: Option<PathBuf>,
+    },
+    /// Summarize a clustering of a dense matrix: sizes, centroids,
+    /// distances and a silhouette estimate
+    Clusters {
+        /// 1D integer dataset with the cluster label of each matrix row
+        #[arg(long)]
+        labels: PathBuf,
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
+        /// Browse the clusters in a full-screen view
+        #[arg(long)]
+        tui: bool,
+    },
+    /// Set a schema metadata key (e.g. a missing `rows` or `cols`) by
+    /// writing a new dataset version
+    SetMeta {
+        /// Metadata key to set; must be non-empty
+        key: String,
+        /// Its new value
+        value: String,
+    },
+    /// Manage the on-disk column statistics cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+}
+
+#[cfg(feature = "tui")]
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// Delete every cached entry
+    Clear,
+}
+
+#[cfg(feature = "tui")]
+impl Command {
+    /// Make the viewer options of a command opening a viewer the settings
+    /// of the viewers opened from now on.
+    fn configure(&self) {
+        match self {
+            Command::Tui { viewer }
+            | Command::Head { viewer, .. }
+            | Command::Sample { viewer, .. }
+            | Command::Display { viewer, .. }
+            | Command::Laplacian { viewer, .. } => viewer.configure(),
+            _ => {}
+        }
+    }
+}
+
+/// Failure of a single subcommand, tagged with the command that produced it.
+#[derive(Debug)]
+pub enum AppError {
+    Info(Error),
+    Peek(Error),
+    Head(Error),
+    Sample(Error),
+    Stats(Error),
+    Display(Error),
+    Tui(Error),
+    Generate(Error),
+    Import(Error),
+    Convert(Error),
+    Validate(Error),
+    Fingerprint(Error),
+    Dedup(Error),
+    Outliers(Error),
+    Neighbors(Error),
+    PlotLambdas(Error),
+    Laplacian(Error),
+    SparseStats(Error),
+    Clusters(Error),
+    SetMeta(Error),
+    Cache(Error),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Info(e) => write!(f, "info command failed: {e}"),
+            AppError::Peek(e) => write!(f, "peek command failed: {e}"),
+            AppError::Head(e) => write!(f, "head command failed: {e}"),
+            AppError::Sample(e) => write!(f, "sample command failed: {e}"),
+            AppError::Stats(e) => write!(f, "stats command failed: {e}"),
+            AppError::Display(e) => write!(f, "display command failed: {e}"),
+            AppError::Tui(e) => write!(f, "tui command failed: {e}"),
+            AppError::Generate(e) => write!(f, "generate command failed: {e}"),
+            AppError::Import(e) => write!(f, "import command failed: {e}"),
+            AppError::Convert(e) => write!(f, "convert command failed: {e}"),
+            AppError::Validate(e) => write!(f, "validate command failed: {e}"),
+            AppError::Fingerprint(e) => write!(f, "fingerprint command failed: {e}"),
+            AppError::Dedup(e) => write!(f, "dedup command failed: {e}"),
+            AppError::Outliers(e) => write!(f, "outliers command failed: {e}"),
+            AppError::Neighbors(e) => write!(f, "neighbors command failed: {e}"),
+            AppError::PlotLambdas(e) => write!(f, "plot-lambdas command failed: {e}"),
+            AppError::Laplacian(e) => write!(f, "laplacian command failed: {e}"),
+            AppError::SparseStats(e) => write!(f, "sparse-stats command failed: {e}"),
+            AppError::Clusters(e) => write!(f, "clusters command failed: {e}"),
+            AppError::SetMeta(e) => write!(f, "set-meta command failed: {e}"),
+            AppError::Cache(e) => write!(f, "cache command failed: {e}"),
+        }
+    }
+}
+
+impl AppError {
+    /// The underlying failure, without the command tag.
+    pub fn inner(&self) -> &Error {
+        match self {
+            AppError::Info(e)
+            | AppError::Peek(e)
+            | AppError::Head(e)
+            | AppError::Sample(e)
+            | AppError::Stats(e)
+            | AppError::Display(e)
+            | AppError::Tui(e)
+            | AppError::Generate(e)
+            | AppError::Import(e)
+            | AppError::Convert(e)
+            | AppError::Validate(e)
+            | AppError::Fingerprint(e)
+            | AppError::Dedup(e)
+            | AppError::Outliers(e)
+            | AppError::Neighbors(e)
+            | AppError::PlotLambdas(e)
+            | AppError::Laplacian(e)
+            | AppError::SparseStats(e)
+            | AppError::Clusters(e)
+            | AppError::SetMeta(e)
+            | AppError::Cache(e) => e,
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.inner().as_ref())
+    }
+}
+
+#[cfg(feature = "tui")]
+fn require_filepath(filepath: Option<PathBuf>) -> anyhow::Result<PathBuf> {
+    filepath
+        .ok_or_else(|| ErrorCategory::InvalidArgs.error("--filepath is required for this command"))
+}
+
+#[cfg(feature = "tui")]
+fn cmd_cache_clear(cache: &cache::StatsCache) -> anyhow::Result<()> {
+    let Some(root) = cache.root() else {
+        println!("Statistics cache is disabled");
+        return Ok(());
+    };
+    let cleared = cache.clear()?;
+    println!(
+        "Cleared cached statistics for {cleared} dataset(s) in {}",
+        root.display()
+    );
+    Ok(())
+}
+
+#[cfg(feature = "tui")]
+/// Run one parsed subcommand; one opening a viewer first makes its
+/// [`ViewerArgs`] the settings of the viewers. Errors are wrapped in
+/// [`AppError`] so callers can tell which command failed
+/// (`err.downcast_ref::<AppError>()`).
+pub async fn dispatch(cmd: Command, filepath: Option<PathBuf>) -> anyhow::Result<()> {
+    cmd.configure();
+    let result = match cmd {
+        Command::Info { verbose, json } => {
+            async { cmd_info(&require_filepath(filepath)?, verbose, json).await }
+                .await
+                .map_err(AppError::Info)
+        }
+        Command::Peek { json, full } => {
+            async { cmd_peek(&require_filepath(filepath)?, json, full).await }
+                .await
+                .map_err(AppError::Peek)
+        }
+        Command::Head {
+            n,
+            offset,
+            visible_cols,
+            include_deleted,
+            ..
+        } => async {
+            cmd_head(
+                &require_filepath(filepath)?,
+                n,
+                offset,
+                visible_cols.map(std::num::NonZeroUsize::get),
+                include_deleted,
+            )
+            .await
+        }
+        .await
+        .map_err(AppError::Head),
+        Command::Sample {
+            n,
+            seed,
+            output,
+            force,
+            normalized,
+            format,
+            select,
+            cast,
+            visible_cols,
+            ..
+        } => async {
+            let export = output.map(|out| ExportOptions {
+                out,
+                force,
+                normalized,
+                format,
+                select: select.unwrap_or_default(),
+                cast,
+            });
+            cmd_sample(
+                &require_filepath(filepath)?,
+                n,
+                seed,
+                export.as_ref(),
+                visible_cols.map(std::num::NonZeroUsize::get),
+            )
+            .await
+        }
+        .await
+        .map_err(AppError::Sample),
+        Command::Stats {
+            json,
+            max_distinct,
+            constant_threshold,
+        } => async {
+            cmd_stats(
+                &require_filepath(filepath)?,
+                json,
+                max_distinct,
+                constant_threshold,
+            )
+            .await
+        }
+        .await
+        .map_err(AppError::Stats),
+        Command::Tui { .. } => async { run_tui(require_filepath(filepath)?).await }
+            .await
+            .map_err(AppError::Tui),
+        Command::Display {
+            max_memory,
+            rows,
+            limit,
+            batch_size,
+            cols,
+            names,
+            screenshot,
+            screenshot_size: (width, height),
+            visible_cols,
+            include_deleted,
+            since_version,
+            only_new,
+            no_expand,
+            follow,
+            follow_interval,
+            ..
+        } => async {
+            let opts = DisplayOptions {
+                max_memory,
+                rows,
+                limit,
+                batch_size: batch_size.map(std::num::NonZeroUsize::get),
+                cols,
+                names: names.as_deref().map(load_names).transpose()?,
+                visible_cols: visible_cols.map(std::num::NonZeroUsize::get),
+                include_deleted,
+                since_version,
+                only_new,
+                no_expand,
+                follow: follow.then(|| std::time::Duration::from_secs(follow_interval.get())),
+                screenshot: screenshot.map(|path| Screenshot {
+                    path,
+                    width,
+                    height,
+                }),
+            };
+            cmd_display(&require_filepath(filepath)?, &opts).await
+        }
+        .await
+        .map_err(AppError::Display),
+        Command::Generate {
+            out,
+            force,
+            kind,
+            n_items,
+            n_dims,
+            seed,
+            n_cliques,
+            noise,
+            outlier_frac,
+            bandwidth,
+            blocks,
+            alpha,
+            genegraph,
+            append,
+            rounds,
+            interval_ms,
+        } => {
+            let opts = GenerateOptions {
+                out,
+                force,
+                kind,
+                n_items,
+                n_dims,
+                n_cliques,
+                noise,
+                outlier_frac,
+                bandwidth,
+                blocks,
+                alpha,
+                seed,
+                genegraph,
+                append: append.then_some(AppendOptions {
+                    rounds,
+                    interval: std::time::Duration::from_millis(interval_ms),
+                }),
+            };
+            cmd_generate(&opts).await.map_err(AppError::Generate)
+        }
+        Command::Import {
+            input,
+            output,
+            format,
+        } => cmd_import(&input, &output, format)
+            .await
+            .map_err(AppError::Import),
+        Command::Convert {
+            to,
+            output,
+            threshold,
+            max_memory,
+        } => async {
+            cmd_convert(
+                &require_filepath(filepath)?,
+                to,
+                &output,
+                threshold,
+                max_memory,
+            )
+            .await
+        }
+        .await
+        .map_err(AppError::Convert),
+        Command::Validate { check_finite, json } => {
+            async { cmd_validate(&require_filepath(filepath)?, check_finite, json).await }
+                .await
+                .map_err(AppError::Validate)
+        }
+        Command::Fingerprint { round, verify } => {
+            async { cmd_fingerprint(&require_filepath(filepath)?, round, verify.as_deref()).await }
+                .await
+                .map_err(AppError::Fingerprint)
+        }
+        Command::Dedup {
+            output,
+            round,
+            json,
+        } => {
+            async { cmd_dedup(&require_filepath(filepath)?, output.as_deref(), round, json).await }
+                .await
+                .map_err(AppError::Dedup)
+        }
+        Command::Outliers { k, method, top } => {
+            async { cmd_outliers(&require_filepath(filepath)?, k, method, top).await }
+                .await
+                .map_err(AppError::Outliers)
+        }
+        Command::Neighbors { row, k, metric } => {
+            async { cmd_neighbors(&require_filepath(filepath)?, row, k, metric).await }
+                .await
+                .map_err(AppError::Neighbors)
+        }
+        Command::PlotLambdas { bins, plain } => {
+            async { cmd_plot_lambdas(&require_filepath(filepath)?, bins, plain).await }
+                .await
+                .map_err(AppError::PlotLambdas)
+        }
+        Command::Laplacian {
+            mode, max_n, tui, ..
+        } => async { cmd_laplacian(&require_filepath(filepath)?, mode, max_n, tui).await }
+            .await
+            .map_err(AppError::Laplacian),
+        Command::SparseStats {
+            json,
+            reorder,
+            permutation_out,
+        } => async {
+            cmd_sparse_stats(
+                &require_filepath(filepath)?,
+                json,
+                reorder,
+                permutation_out.as_deref(),
+            )
+            .await
+        }
+        .await
+        .map_err(AppError::SparseStats),
+        Command::Clusters { labels, json, tui } => {
+            async { cmd_clusters(&require_filepath(filepath)?, &labels, json, tui).await }
+                .await
+                .map_err(AppError::Clusters)
+        }
+        Command::SetMeta { key, value } => {
+            async { cmd_set_meta(&require_filepath(filepath)?, &key, &value).await }
+                .await
+                .map_err(AppError::SetMeta)
+        }
+        Command::Cache {
+            action: CacheAction::Clear,
+        } => cmd_cache_clear(&cache::StatsCache::user()).map_err(AppError::Cache),
+    };
+    result.map_err(Error::new)
 }
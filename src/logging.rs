@@ -0,0 +1,166 @@
+//! Where log records go. Every formatted line is kept in an in-memory ring of
+//! the last [`RING_LINES`], which the viewers' log popup (`L`) shows, and is
+//! written to the `--log-file` / `JAVELIN_LOG_FILE` file when one is given,
+//! otherwise to stderr. Stderr is skipped while a viewer holds the terminal
+//! in raw mode, where the lines would be drawn over the screen.
+//!
+//! Without `RUST_LOG` the logger accepts every level and [`set_level`] moves
+//! the global maximum (info at start), so the popup can raise it to debug at
+//! runtime. With `RUST_LOG` its filters apply as before and [`set_level`]
+//! can only lower them.
+
+use log::LevelFilter;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Environment variable naming a log file, used when `--log-file` is absent.
+pub const LOG_FILE_ENV: &str = "JAVELIN_LOG_FILE";
+
+/// Lines kept for the log popup.
+pub const RING_LINES: usize = 1000;
+
+static RING: Mutex<LogRing> = Mutex::new(LogRing::new(RING_LINES));
+
+/// The last `capacity` complete lines written, plus any unterminated tail.
+#[derive(Debug)]
+pub(crate) struct LogRing {
+    lines: VecDeque<String>,
+    partial: String,
+    capacity: usize,
+}
+
+impl LogRing {
+    pub(crate) const fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            partial: String::new(),
+            capacity,
+        }
+    }
+
+    pub(crate) fn push_bytes(&mut self, buf: &[u8]) {
+        self.partial.push_str(&String::from_utf8_lossy(buf));
+        while let Some(end) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=end).collect();
+            if self.lines.len() == self.capacity {
+                self.lines.pop_front();
+            }
+            self.lines.push_back(line.trim_end().to_string());
+        }
+    }
+
+    /// The last `n` complete lines, oldest first.
+    pub(crate) fn tail(&self, n: usize) -> Vec<String> {
+        let skip = self.lines.len().saturating_sub(n);
+        self.lines.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// The last `n` logged lines, oldest first.
+pub fn tail(n: usize) -> Vec<String> {
+    RING.lock().map(|ring| ring.tail(n)).unwrap_or_default()
+}
+
+/// Most verbose level currently logged.
+pub fn level() -> LevelFilter {
+    log::max_level()
+}
+
+/// Change the most verbose level logged from now on.
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
+
+/// Whether a viewer holds the terminal in raw mode (never without the `tui`
+/// feature).
+#[cfg(feature = "tui")]
+fn raw_mode() -> bool {
+    crossterm::terminal::is_raw_mode_enabled().unwrap_or(false)
+}
+
+#[cfg(not(feature = "tui"))]
+fn raw_mode() -> bool {
+    false
+}
+
+/// The `env_logger` target: the ring, then the file or stderr.
+struct Sink {
+    file: Option<File>,
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Ok(mut ring) = RING.lock() {
+            ring.push_bytes(buf);
+        }
+        match self.file.as_mut() {
+            Some(file) => file.write_all(buf)?,
+            None if !raw_mode() => io::stderr().write_all(buf)?,
+            None => {}
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => io::stderr().flush(),
+        }
+    }
+}
+
+/// The log file to append to: `log_file`, else `JAVELIN_LOG_FILE` if set.
+pub fn log_file_path(log_file: Option<&Path>) -> Option<PathBuf> {
+    log_file.map(Path::to_path_buf).or_else(|| {
+        std::env::var_os(LOG_FILE_ENV)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// Install the logger; a second call leaves the first one in place.
+pub(crate) fn install(log_file: Option<&Path>) -> io::Result<()> {
+    let file = match log_file_path(log_file) {
+        Some(path) => Some(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|e| io::Error::new(e.kind(), format!("log file {path:?}: {e}")))?,
+        ),
+        None => None,
+    };
+    let rust_log = std::env::var("RUST_LOG").ok().filter(|v| !v.is_empty());
+
+    let mut builder = env_logger::Builder::new();
+    match &rust_log {
+        Some(filters) => builder.parse_filters(filters),
+        None => builder.filter_level(LevelFilter::Trace),
+    };
+    builder
+        .write_style(env_logger::WriteStyle::Never)
+        .target(env_logger::Target::Pipe(Box::new(Sink { file })));
+    // Don't panic if called multiple times across binaries.
+    if builder.try_init().is_ok() && rust_log.is_none() {
+        set_level(LevelFilter::Info);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_keeps_the_last_complete_lines() {
+        let mut ring = LogRing::new(3);
+        ring.push_bytes(b"one\ntwo\nthr");
+        assert_eq!(ring.tail(10), ["one", "two"]);
+        ring.push_bytes(b"ee\nfour\nfive\n");
+        assert_eq!(ring.tail(10), ["three", "four", "five"]);
+        assert_eq!(ring.tail(1), ["five"]);
+    }
+}
@@ -0,0 +1,179 @@
+//! Viewer state kept between sessions of one dataset: its row bookmarks,
+//! the number of visible columns, the hidden columns, the column order,
+//! the review flags and the columns' display formats.
+//!
+//! Each dataset has one file under `~/.local/state/javelin/sessions/` (or
+//! `$XDG_STATE_HOME/javelin/sessions`, or `$JAVELIN_STATE_DIR/sessions`)
+//! named `<hash(canonical dataset path)>.json`. Like cache entries, files
+//! are written to a temporary name and renamed into place.
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cache::fnv1a;
+
+/// What a viewer session leaves behind for the next one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionState {
+    /// Bookmarked rows by source row id
+    #[serde(default)]
+    pub bookmarks: BTreeSet<u64>,
+    /// Columns shown at once, as last set with `--visible-cols` or `<`/`>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visible_cols: Option<usize>,
+    /// Columns taken out of the table with `d`, by name
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub hidden_columns: BTreeSet<String>,
+    /// Feature columns in the order Shift+Left/Right left them, by name;
+    /// empty for schema order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub column_order: Vec<String>,
+    /// Source row ids flagged in review mode (`V`)
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub flagged: BTreeSet<u64>,
+    /// Source row id of the review cursor, where `V` resumes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_row: Option<u64>,
+    /// Display format of integer columns picked in the column picker, by
+    /// column name (`thousands`, `bytes` or `hex`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub value_formats: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    path: String,
+    #[serde(flatten)]
+    state: SessionState,
+}
+
+/// Where one dataset's session state is kept; a disabled store loads the
+/// empty state and never writes.
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    file: Option<PathBuf>,
+    /// Canonical dataset path, stored in the file to catch hash collisions
+    dataset: String,
+}
+
+impl SessionStore {
+    /// The user state directory's file for `dataset`; disabled when no home
+    /// directory can be found.
+    pub fn user(dataset: &Path) -> Self {
+        match user_dir() {
+            Some(root) => Self::at(root, dataset),
+            None => Self::disabled(),
+        }
+    }
+
+    /// The file for `dataset` under `root` (mainly for tests).
+    pub fn at(root: impl Into<PathBuf>, dataset: &Path) -> Self {
+        let canonical = dataset
+            .canonicalize()
+            .unwrap_or_else(|_| dataset.to_path_buf());
+        let dataset = canonical.to_string_lossy().into_owned();
+        let name = format!("{:016x}.json", fnv1a(dataset.as_bytes()));
+        Self {
+            file: Some(root.into().join("sessions").join(name)),
+            dataset,
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            file: None,
+            dataset: String::new(),
+        }
+    }
+
+    /// The saved state, or the empty one when there is none or it cannot be
+    /// read.
+    pub fn load(&self) -> SessionState {
+        let Some(file) = &self.file else {
+            return SessionState::default();
+        };
+        let Ok(bytes) = fs::read(file) else {
+            return SessionState::default();
+        };
+        match serde_json::from_slice::<Entry>(&bytes) {
+            Ok(entry) if entry.path == self.dataset => {
+                debug!("session: loaded {:?}", file);
+                entry.state
+            }
+            Ok(_) => SessionState::default(),
+            Err(e) => {
+                warn!("session: ignoring unreadable state {:?}: {e}", file);
+                SessionState::default()
+            }
+        }
+    }
+
+    pub fn save(&self, state: &SessionState) -> Result<()> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        let dir = file.parent().expect("session file has a parent");
+        fs::create_dir_all(dir).with_context(|| format!("creating state dir {:?}", dir))?;
+        let entry = Entry {
+            path: self.dataset.clone(),
+            state: state.clone(),
+        };
+        let tmp = dir.join(format!(
+            ".{}.{}.tmp",
+            file.file_name().unwrap().to_string_lossy(),
+            std::process::id()
+        ));
+        fs::write(&tmp, serde_json::to_vec(&entry)?)
+            .with_context(|| format!("writing session state {:?}", tmp))?;
+        fs::rename(&tmp, file).with_context(|| format!("renaming session state to {:?}", file))?;
+        Ok(())
+    }
+}
+
+fn user_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("JAVELIN_STATE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/state")))?;
+    Some(base.join("javelin"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_round_trips_per_dataset() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("state");
+        let a = SessionStore::at(&root, &tmp.path().join("a.lance"));
+        let b = SessionStore::at(&root, &tmp.path().join("b.lance"));
+        assert_eq!(a.load(), SessionState::default());
+
+        let state = SessionState {
+            bookmarks: [3, 100_000].into(),
+            visible_cols: Some(3),
+            hidden_columns: ["col_2".to_string()].into(),
+            column_order: vec!["col_1".to_string(), "col_0".to_string()],
+            flagged: [41].into(),
+            review_row: Some(42),
+            value_formats: [("size".to_string(), "bytes".to_string())].into(),
+        };
+        a.save(&state).unwrap();
+        assert_eq!(a.load(), state);
+        assert_eq!(b.load(), SessionState::default());
+
+        // An unreadable file is ignored, and a disabled store stays empty.
+        fs::write(a.file.as_ref().unwrap(), "{").unwrap();
+        assert_eq!(a.load(), SessionState::default());
+        let off = SessionStore::disabled();
+        off.save(&state).unwrap();
+        assert_eq!(off.load(), SessionState::default());
+    }
+}
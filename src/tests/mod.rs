@@ -1 +1,3 @@
 mod test_functions;
+mod test_layout;
+mod test_render;
@@ -1,11 +1,47 @@
+use crate::cache::{ColumnStats, StatsCache};
+use crate::display::{DEFAULT_CONSTANT_THRESHOLD, LanceLayout};
+#[cfg(feature = "generate")]
+#[cfg(feature = "generate")]
+use crate::functions::generate::{GenerateKind, GenerateOptions, cmd_generate};
+// most of these are only exercised on the `generate` fixtures
+#[cfg_attr(not(feature = "generate"), allow(unused_imports))]
 use crate::functions::{
-    generate::cmd_generate, head::cmd_head, sample::cmd_sample, stats::cmd_stats, tui::run_tui,
+    clusters::{cluster_report, load_labels},
+    convert::ConvertTarget,
+    dedup::dedup_dataset,
+    deletions::{deletion_lines, deletion_report, drop_tombstones, scan_with_deleted},
+    diff::{Comparison, added_rows, open_version},
+    display::{
+        DEFAULT_MAX_MEMORY, DisplayOptions, load_display_window, parse_byte_size,
+        parse_screen_size, parse_slice_range,
+    },
+    export::{self, ExportFormat},
+    fingerprint::fingerprint_dataset,
+    frequencies::DEFAULT_MAX_DISTINCT,
+    functions::{DenseMatrix, detect_lance_layout, normalize_for_display},
+    head::{cmd_head, head_batch},
+    info::{cmd_info, info_json, layout_report, metadata_dims, storage_lines, write_info},
+    laplacian::{LaplacianMode, LaplacianReport, coo_batch_to_csr},
+    neighbors::{Metric, dataset_neighbors, matrix_neighbors},
+    outliers::{OutlierMethod, find_outliers},
+    peek::write_peek,
+    plot::{histogram_text, load_vector_1d},
+    reorder::Reorder,
+    sample::{cmd_sample, sample_batch, sample_indices},
+    set_meta::set_schema_metadata,
+    sparse_stats::{SparseStats, load_coo},
+    stats::{cached_column_stats, cmd_stats, stats_json, write_stats},
+    storage::storage_report,
+    tui::run_tui,
+    validate::{validate_dataset, write_validate},
 };
+use crate::{AppError, Command, ViewerArgs, dispatch};
 
-use genegraph_storage::lance_storage_graph::LanceStorageGraph;
-use genegraph_storage::traits::backend::StorageBackend;
-use smartcore::linalg::basic::arrays::Array;
-use std::path::PathBuf;
+use arrow_array::{Array as _, Float64Array, Int64Array, RecordBatch, UInt32Array};
+use lance::Dataset;
+use serde_json::json;
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
 
 // Helper: resolve a path relative to project root for test data.
 // Adjust "tests/data" and filenames to match your repo layout.
@@ -17,6 +53,14 @@ fn test_data_path(name: &str) -> PathBuf {
     p
 }
 
+// Helper: read a whole Lance dataset into one batch.
+async fn read_lance(path: &Path) -> RecordBatch {
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(path))
+        .await
+        .unwrap_or_else(|e| panic!("{path:?} should open as a Lance dataset: {e}"));
+    dataset.scan().try_into_batch().await.unwrap()
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn cmd_stats_runs_on_valid_lance() {
     // Requires a small valid Lance dataset in tests/data/sample.lance
@@ -27,7 +71,15 @@ async fn cmd_stats_runs_on_valid_lance() {
         return;
     }
 
-    let result = cmd_stats(&path).await;
+    // Keep test runs out of the user's statistics cache.
+    crate::cache::disable();
+    let result = cmd_stats(
+        &path,
+        false,
+        DEFAULT_MAX_DISTINCT,
+        DEFAULT_CONSTANT_THRESHOLD,
+    )
+    .await;
     assert!(
         result.is_ok(),
         "cmd_stats should succeed on sample.lance: {result:?}"
@@ -46,7 +98,7 @@ async fn cmd_head_handles_empty_or_small_dataset() {
     }
 
     // n larger than dataset size should not panic or error
-    let result = cmd_head(&path, 10_000).await;
+    let result = cmd_head(&path, 10_000, 0, None, false).await;
     assert!(
         result.is_ok(),
         "cmd_head should not fail on large n: {result:?}"
@@ -66,7 +118,7 @@ async fn cmd_sample_produces_at_most_n_rows() {
 
     // Just check that the command returns Ok; semantics tested indirectly
     let n = 5;
-    let result = cmd_sample(&path, n).await;
+    let result = cmd_sample(&path, n, None, None, None).await;
     assert!(result.is_ok(), "cmd_sample should succeed: {result:?}");
 }
 
@@ -96,23 +148,34 @@ async fn run_tui_returns_ok_for_directory() {
     );
 }
 
+#[cfg(feature = "generate")]
 #[tokio::test(flavor = "multi_thread")]
 async fn cmd_generate_creates_expected_artifacts() {
+    use genegraph_storage::lance_storage_graph::LanceStorageGraph;
+    use genegraph_storage::traits::backend::StorageBackend;
+    use smartcore::linalg::basic::arrays::Array;
+
     // Use a small dataset so the test is fast.
     const N_ITEMS: usize = 20;
     const N_DIMS: usize = 5;
     const SEED: u64 = 42;
 
     // 1. Call cmd_generate
-    cmd_generate(N_ITEMS, N_DIMS, SEED)
+    let tmp = tempfile::tempdir().unwrap();
+    let out_dir = tmp.path().join("javelin_test");
+    let opts = GenerateOptions {
+        out: out_dir.clone(),
+        n_items: N_ITEMS,
+        n_dims: N_DIMS,
+        seed: SEED,
+        genegraph: true,
+        ..GenerateOptions::default()
+    };
+    cmd_generate(&opts)
         .await
         .expect("cmd_generate should succeed");
 
     // 2. Verify that storage can be opened and metadata exists
-    let mut out_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    out_dir.push("target");
-    out_dir.push("debug");
-    out_dir.push("javelin_test");
     let uri = crate::datasets::path_to_uri(&out_dir);
 
     let storage = LanceStorageGraph::new(uri, "javelin_test".to_string());
@@ -158,7 +221,2871 @@ async fn cmd_generate_creates_expected_artifacts() {
         N_ITEMS,
         "norms vector length should match N_ITEMS"
     );
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn cmd_generate_requires_force_to_overwrite() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out_dir = tmp.path().join("existing");
+    std::fs::create_dir(&out_dir).unwrap();
+    std::fs::write(out_dir.join("keep.txt"), "keep").unwrap();
+
+    let mut opts = GenerateOptions {
+        out: out_dir.clone(),
+        n_items: 20,
+        n_dims: 4,
+        n_cliques: 2,
+        noise: 0.1,
+        outlier_frac: 0.0,
+        ..GenerateOptions::default()
+    };
+    let err = cmd_generate(&opts)
+        .await
+        .expect_err("existing output dir without --force must fail");
+    assert!(err.to_string().contains("--force"), "{err}");
+    assert!(
+        out_dir.join("keep.txt").exists(),
+        "nothing should be deleted"
+    );
+
+    opts.force = true;
+    cmd_generate(&opts)
+        .await
+        .expect("--force should overwrite the output dir");
+    assert!(!out_dir.join("keep.txt").exists());
+
+    let adj = read_lance(&out_dir.join("adjacency.lance")).await;
+    assert_eq!(adj.schema().metadata().get("rows").unwrap(), "20");
+    assert!(adj.num_rows() > 0);
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn cmd_generate_writes_plain_lance_datasets() {
+    const N_ITEMS: usize = 24;
+    const N_DIMS: usize = 6;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let out_dir = tmp.path().join("plain");
+    let opts = GenerateOptions {
+        out: out_dir.clone(),
+        n_items: N_ITEMS,
+        n_dims: N_DIMS,
+        n_cliques: 3,
+        outlier_frac: 0.0,
+        ..GenerateOptions::default()
+    };
+    cmd_generate(&opts)
+        .await
+        .expect("cmd_generate should succeed");
+
+    // No genegraph metadata, just three Lance datasets.
+    let mut entries: Vec<String> = std::fs::read_dir(&out_dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    entries.sort();
+    assert_eq!(entries, ["adjacency.lance", "dense.lance", "norms.lance"]);
+
+    // Dense: one FixedSizeList row per item, expanded to col_* for display.
+    let dense = read_lance(&out_dir.join("dense.lance")).await;
+    assert!(matches!(
+        detect_lance_layout(&dense),
+        LanceLayout::DenseRowMajor
+    ));
+    assert_eq!(dense.num_rows(), N_ITEMS);
+    let expanded = normalize_for_display(&dense).unwrap();
+    assert_eq!(expanded.num_columns(), N_DIMS);
+    assert_eq!(expanded.schema().field(0).name(), "col_0");
+
+    // Adjacency: COO triplets with the matrix shape in schema metadata.
+    let adj = read_lance(&out_dir.join("adjacency.lance")).await;
+    assert!(matches!(detect_lance_layout(&adj), LanceLayout::SparseCoo));
+    let md = adj.schema().metadata().clone();
+    assert_eq!(md.get("rows").map(String::as_str), Some("24"));
+    assert_eq!(md.get("cols").map(String::as_str), Some("24"));
+    assert_eq!(md.get("nnz"), Some(&adj.num_rows().to_string()));
+    // 3 cliques of 8 → 3 * 8 * 7 directed edges.
+    assert_eq!(adj.num_rows(), 3 * 8 * 7);
+    let rows = adj
+        .column(0)
+        .as_any()
+        .downcast_ref::<UInt32Array>()
+        .unwrap();
+    assert!(rows.values().iter().all(|&r| (r as usize) < N_ITEMS));
+
+    // Norms: a single Float64 column, one value per item.
+    let norms = read_lance(&out_dir.join("norms.lance")).await;
+    assert!(matches!(detect_lance_layout(&norms), LanceLayout::Vector1D));
+    let values = norms
+        .column(0)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    assert_eq!(values.len(), N_ITEMS);
+    assert!(values.values().iter().all(|&v| v >= 0.0));
+
+    // The non-interactive commands work on every artifact.
+    crate::cache::disable();
+    for name in ["dense.lance", "adjacency.lance", "norms.lance"] {
+        let path = out_dir.join(name);
+        cmd_info(&path, false, false)
+            .await
+            .expect("cmd_info should succeed");
+        cmd_stats(
+            &path,
+            false,
+            DEFAULT_MAX_DISTINCT,
+            DEFAULT_CONSTANT_THRESHOLD,
+        )
+        .await
+        .expect("cmd_stats should succeed");
+    }
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn cmd_generate_plain_lance_supports_every_kind() {
+    let tmp = tempfile::tempdir().unwrap();
+    for kind in [
+        GenerateKind::Banded,
+        GenerateKind::Block,
+        GenerateKind::Powerlaw,
+    ] {
+        let out_dir = tmp.path().join(format!("{kind:?}"));
+        let opts = GenerateOptions {
+            out: out_dir.clone(),
+            kind,
+            n_items: 16,
+            ..GenerateOptions::default()
+        };
+        cmd_generate(&opts).await.unwrap();
+
+        let dense = read_lance(&out_dir.join("dense.lance")).await;
+        let expanded = normalize_for_display(&dense).unwrap();
+        assert_eq!((expanded.num_rows(), expanded.num_columns()), (16, 16));
+        let adj = read_lance(&out_dir.join("adjacency.lance")).await;
+        assert!(matches!(detect_lance_layout(&adj), LanceLayout::SparseCoo));
+        assert_eq!(adj.schema().metadata().get("cols").unwrap(), "16");
+    }
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn cmd_generate_append_grows_each_dataset_one_version_per_round() {
+    use crate::functions::generate::{AppendOptions, append_round, append_rows};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let out_dir = tmp.path().join("growing");
+    let opts = GenerateOptions {
+        out: out_dir.clone(),
+        n_items: 20,
+        n_dims: 3,
+        n_cliques: 2,
+        outlier_frac: 0.0,
+        append: Some(AppendOptions {
+            rounds: 2,
+            interval: std::time::Duration::ZERO,
+        }),
+        ..GenerateOptions::default()
+    };
+    cmd_generate(&opts).await.unwrap();
+    let per_round = append_rows(20);
+    assert_eq!(per_round, 2);
+
+    // Version 1 is the initial write, version 1 + r ends with round r's rows
+    let uri = crate::datasets::path_to_uri(&out_dir.join("dense.lance"));
+    let dense = Dataset::open(&uri).await.unwrap();
+    assert_eq!(dense.version().version, 3);
+    for round in 1..=2 {
+        let version = open_version(&dense, 1 + round as u64).await.unwrap();
+        let batch = version.scan().try_into_batch().await.unwrap();
+        let rows = 20 + round * per_round;
+        assert_eq!(batch.num_rows(), rows);
+        let values = normalize_for_display(&batch).unwrap();
+        let col_0 = values
+            .column(0)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        let (expected, _, _) = append_round(&opts, round);
+        let tail: Vec<f64> = (rows - per_round..rows).map(|r| col_0.value(r)).collect();
+        let first: Vec<f64> = expected.iter().map(|row| row[0]).collect();
+        assert_eq!(tail, first, "round {round}");
+    }
+    assert_eq!(
+        read_lance(&out_dir.join("norms.lance")).await.num_rows(),
+        24
+    );
+
+    // The adjacency grows with the rows and its metadata follows
+    let adj = read_lance(&out_dir.join("adjacency.lance")).await;
+    let md = adj.schema().metadata().clone();
+    assert_eq!(md.get("rows").map(String::as_str), Some("24"));
+    assert_eq!(md.get("nnz"), Some(&adj.num_rows().to_string()));
+    let adjacency = Dataset::open(&crate::datasets::path_to_uri(
+        &out_dir.join("adjacency.lance"),
+    ))
+    .await
+    .unwrap();
+    let checks = validate_dataset(&adjacency, false, false).await.unwrap();
+    assert!(checks.iter().all(|c| c.passed), "{checks:?}");
+
+    let err = cmd_generate(&GenerateOptions {
+        kind: GenerateKind::Banded,
+        force: true,
+        ..opts.clone()
+    })
+    .await
+    .expect_err("only cliques grow");
+    assert!(err.to_string().contains("--append"), "{err}");
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn cmd_generate_rejects_invalid_knobs() {
+    let tmp = tempfile::tempdir().unwrap();
+    let base = GenerateOptions {
+        out: tmp.path().join("out"),
+        n_items: 10,
+        n_dims: 3,
+        ..GenerateOptions::default()
+    };
+    for opts in [
+        GenerateOptions {
+            n_cliques: 0,
+            ..base.clone()
+        },
+        GenerateOptions {
+            noise: -1.0,
+            ..base.clone()
+        },
+        GenerateOptions {
+            outlier_frac: 1.5,
+            ..base.clone()
+        },
+    ] {
+        assert!(cmd_generate(&opts).await.is_err());
+    }
+    assert!(!base.out.exists());
+}
+
+#[cfg(feature = "generate")]
+fn generate_command(out: &Path) -> Command {
+    Command::Generate {
+        out: out.to_path_buf(),
+        force: false,
+        kind: GenerateKind::Cliques,
+        n_items: 12,
+        n_dims: 4,
+        seed: 7,
+        n_cliques: 2,
+        noise: 0.1,
+        outlier_frac: 0.0,
+        bandwidth: 3,
+        blocks: 4,
+        alpha: 2.5,
+        genegraph: false,
+        append: false,
+        rounds: 3,
+        interval_ms: 1000,
+    }
+}
+
+fn app_error(result: anyhow::Result<()>) -> AppError {
+    let err = result.expect_err("dispatch should fail");
+    err.downcast::<AppError>()
+        .expect("dispatch errors should be AppError")
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn info_stats_and_validate_write_their_reports() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+    crate::cache::disable();
+
+    async fn info(path: &Path) -> String {
+        let mut text = Vec::new();
+        write_info(&mut text, path, false, false).await.unwrap();
+        String::from_utf8(text).unwrap()
+    }
+    let dense = info(&out.join("dense.lance")).await;
+    assert!(dense.contains("\nRows: 12\n"), "{dense}");
+    assert!(
+        dense.contains("Layout: DenseRowMajor (vector: FixedSizeList<Float64>[4])"),
+        "{dense}"
+    );
+    assert!(dense.contains("  Matrix: 12 × 4\n"), "{dense}");
+    assert!(dense.contains("\nSchema:\n - 0 : "), "{dense}");
+    assert!(dense.contains("name: \"vector\""), "{dense}");
+    assert!(dense.contains("\nSchema metadata:\n (none)\n"), "{dense}");
+
+    let adjacency = info(&out.join("adjacency.lance")).await;
+    assert!(
+        adjacency.contains("Layout: SparseCoo (row/col/value)"),
+        "{adjacency}"
+    );
+    assert!(
+        adjacency.contains("  Matrix: 12 × 12 (from schema metadata)"),
+        "{adjacency}"
+    );
+    for line in [" - cols = 12\n", " - rows = 12\n"] {
+        assert!(adjacency.contains(line), "{adjacency}");
+    }
+    let norms = info(&out.join("norms.lance")).await;
+    assert!(
+        norms.contains("Layout: Vector1D (norm: Float64)"),
+        "{norms}"
+    );
+
+    let mut text = Vec::new();
+    write_info(&mut text, &out.join("norms.lance"), false, true)
+        .await
+        .unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&text).unwrap();
+    assert_eq!(report["rows"], 12, "{report}");
+
+    let mut text = Vec::new();
+    write_stats(
+        &mut text,
+        &out.join("dense.lance"),
+        false,
+        DEFAULT_MAX_DISTINCT,
+        DEFAULT_CONSTANT_THRESHOLD,
+    )
+    .await
+    .unwrap();
+    let stats = String::from_utf8(text).unwrap();
+    for line in [
+        "Total rows: 12\n",
+        "Total columns: 1\n",
+        "  • Column: vector\n",
+        "    Structure: 2D Dense Matrix (1×4)\n",
+        "      Shape: 12 records × 4 features\n",
+        "  vector: 0 (0.00%)\n",
+    ] {
+        assert!(stats.contains(line), "{line:?} in {stats}");
+    }
+
+    let mut text = Vec::new();
+    write_validate(&mut text, &out.join("adjacency.lance"), true, false)
+        .await
+        .unwrap();
+    let checks = String::from_utf8(text).unwrap();
+    assert!(checks.contains("[PASS] layout: SparseCoo\n"), "{checks}");
+    assert!(checks.ends_with("All 6 checks passed\n"), "{checks}");
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn peek_fits_one_screen_for_dense_and_coo() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    async fn peek(path: &Path, json: bool, full: bool) -> String {
+        let mut text = Vec::new();
+        write_peek(&mut text, path, json, full).await.unwrap();
+        String::from_utf8(text).unwrap()
+    }
+    let dense = peek(&out.join("dense.lance"), false, false).await;
+    assert!(dense.lines().count() <= 25, "{dense}");
+    assert!(
+        dense.contains("Layout: DenseRowMajor (vector: FixedSizeList<Float64>[4])"),
+        "{dense}"
+    );
+    assert!(dense.contains("\nColumns: 4 (Float64 × 4)\n"), "{dense}");
+    assert!(
+        dense.contains("\nNulls: 0.00% (0 of 48 values, all rows)\n"),
+        "{dense}"
+    );
+    assert!(dense.contains("\nPreview (first 5 rows):\n"), "{dense}");
+    let table: Vec<&str> = dense
+        .lines()
+        .skip_while(|l| !l.starts_with("Preview"))
+        .collect();
+    assert_eq!(table.len(), 7, "{dense}");
+    assert!(table[1].trim_start().starts_with('#') && table[1].contains("col_3"));
+
+    let adjacency = peek(&out.join("adjacency.lance"), false, true).await;
+    assert!(adjacency.lines().count() <= 25, "{adjacency}");
+    assert!(adjacency.contains("\nDensity: "), "{adjacency}");
+    assert!(
+        adjacency.contains("\nTop row degrees (all rows): "),
+        "{adjacency}"
+    );
+    assert!(!adjacency.contains("Density: ≈"), "{adjacency}");
+
+    let report: serde_json::Value =
+        serde_json::from_str(&peek(&out.join("adjacency.lance"), true, false).await).unwrap();
+    assert_eq!(report["layout"], "SparseCoo", "{report}");
+    assert_eq!(report["coo"]["shape"], json!([12, 12]), "{report}");
+    assert_eq!(report["preview"]["rows"].as_array().unwrap().len(), 5);
+    assert!(report["coo"]["density"].as_f64().unwrap() > 0.0, "{report}");
+    let degrees = report["coo"]["top_row_degrees"].as_array().unwrap();
+    assert!(!degrees.is_empty() && degrees.len() <= 5, "{report}");
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn dispatch_runs_generate_then_info_and_stats() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+    crate::cache::disable();
+
+    for file in ["dense.lance", "adjacency.lance", "norms.lance"] {
+        let path = Some(out.join(file));
+        dispatch(
+            Command::Info {
+                verbose: false,
+                json: false,
+            },
+            path.clone(),
+        )
+        .await
+        .unwrap();
+        dispatch(
+            Command::Stats {
+                json: false,
+                max_distinct: DEFAULT_MAX_DISTINCT,
+                constant_threshold: DEFAULT_CONSTANT_THRESHOLD,
+            },
+            path.clone(),
+        )
+        .await
+        .unwrap();
+        dispatch(
+            Command::Stats {
+                json: true,
+                max_distinct: DEFAULT_MAX_DISTINCT,
+                constant_threshold: DEFAULT_CONSTANT_THRESHOLD,
+            },
+            path,
+        )
+        .await
+        .unwrap();
+    }
+
+    // A second run without --force must be reported as a generate failure.
+    let err = app_error(dispatch(generate_command(&out), None).await);
+    assert!(matches!(err, AppError::Generate(_)), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dispatch_imports_csv() {
+    let tmp = tempfile::tempdir().unwrap();
+    let input = tmp.path().join("m.csv");
+    std::fs::write(&input, "a,b\n1.0,2.0\n3.0,4.0\n").unwrap();
+    let output = tmp.path().join("m.lance");
+
+    let cmd = Command::Import {
+        input: input.clone(),
+        output: output.clone(),
+        format: None,
+    };
+    dispatch(cmd, None).await.unwrap();
+    assert_eq!(read_lance(&output).await.num_rows(), 2);
+
+    let again = Command::Import {
+        input,
+        output,
+        format: None,
+    };
+    let err = app_error(dispatch(again, None).await);
+    assert!(matches!(err, AppError::Import(_)), "{err}");
+}
+
+fn file_commands() -> Vec<(Command, &'static str)> {
+    vec![
+        (
+            Command::Info {
+                verbose: false,
+                json: false,
+            },
+            "info",
+        ),
+        (
+            Command::Peek {
+                json: false,
+                full: false,
+            },
+            "peek",
+        ),
+        (
+            Command::Head {
+                n: 1,
+                offset: 0,
+                visible_cols: None,
+                include_deleted: false,
+                viewer: ViewerArgs::default(),
+            },
+            "head",
+        ),
+        (
+            Command::Sample {
+                n: 1,
+                seed: None,
+                output: None,
+                force: false,
+                normalized: false,
+                format: ExportFormat::Lance,
+                select: None,
+                cast: None,
+                visible_cols: None,
+                viewer: ViewerArgs::default(),
+            },
+            "sample",
+        ),
+        (
+            Command::Stats {
+                json: false,
+                max_distinct: DEFAULT_MAX_DISTINCT,
+                constant_threshold: DEFAULT_CONSTANT_THRESHOLD,
+            },
+            "stats",
+        ),
+        (
+            Command::Display {
+                max_memory: DEFAULT_MAX_MEMORY,
+                rows: None,
+                limit: None,
+                batch_size: None,
+                cols: None,
+                names: None,
+                screenshot: None,
+                screenshot_size: (100, 30),
+                visible_cols: None,
+                include_deleted: false,
+                since_version: None,
+                only_new: false,
+                no_expand: false,
+                follow: false,
+                follow_interval: NonZeroU64::MIN,
+                viewer: ViewerArgs::default(),
+            },
+            "display",
+        ),
+        (
+            Command::Tui {
+                viewer: ViewerArgs::default(),
+            },
+            "tui",
+        ),
+        (
+            Command::Validate {
+                check_finite: false,
+                json: false,
+            },
+            "validate",
+        ),
+    ]
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dispatch_requires_filepath_and_tags_the_command() {
+    for (cmd, name) in file_commands() {
+        let err = app_error(dispatch(cmd, None).await);
+        let msg = err.to_string();
+        assert!(msg.starts_with(&format!("{name} command failed")), "{msg}");
+        assert!(msg.contains("--filepath is required"), "{msg}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dispatch_reports_bad_paths_before_opening_terminal() {
+    // Head, Sample, Display and Tui take over the terminal on success, so
+    // only their error paths are exercised here.
+    let tmp = tempfile::tempdir().unwrap();
+    let file = tmp.path().join("not_a_dataset.txt");
+    std::fs::write(&file, "x").unwrap();
+
+    for (cmd, name) in file_commands() {
+        let err = app_error(dispatch(cmd, Some(file.clone())).await);
+        let msg = err.to_string();
+        assert!(msg.starts_with(&format!("{name} command failed")), "{msg}");
+    }
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn display_refuses_datasets_over_the_memory_budget() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    let cmd = Command::Display {
+        max_memory: parse_byte_size("100").unwrap(),
+        rows: None,
+        limit: None,
+        batch_size: None,
+        cols: None,
+        names: None,
+        screenshot: None,
+        screenshot_size: (100, 30),
+        visible_cols: None,
+        include_deleted: false,
+        since_version: None,
+        only_new: false,
+        no_expand: false,
+        follow: false,
+        follow_interval: NonZeroU64::MIN,
+        viewer: ViewerArgs::default(),
+    };
+    let err = app_error(dispatch(cmd, Some(out.join("dense.lance"))).await);
+    let msg = err.to_string();
+    assert!(matches!(err, AppError::Display(_)), "{msg}");
+    assert!(
+        msg.contains("about 384 B in memory, over the --max-memory budget of 100 B"),
+        "{msg}"
+    );
+    assert!(msg.contains("`head <N>`"), "{msg}");
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn info_names_the_layout_with_dimensions_and_hints() {
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    let report = |name: &str| {
+        let path = out.join(name);
+        async move {
+            let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+                .await
+                .unwrap();
+            layout_report(&dataset).await.unwrap()
+        }
+    };
+
+    let dense = report("dense.lance").await;
+    assert!(dense[0].starts_with("Layout: DenseRowMajor"), "{dense:?}");
+    assert!(
+        dense.contains(&"  Vector width: 4".to_string()),
+        "{dense:?}"
+    );
+    assert!(dense.contains(&"  Matrix: 12 × 4".to_string()), "{dense:?}");
+
+    let coo = report("adjacency.lance").await;
+    assert!(
+        coo[0].starts_with("Layout: SparseCoo (row/col/value)"),
+        "{coo:?}"
+    );
+    assert_eq!(coo[1], "  Matrix: 12 × 12 (from schema metadata)");
+
+    let norms = report("norms.lance").await;
+    assert!(norms[0].starts_with("Layout: Vector1D"), "{norms:?}");
+    assert_eq!(norms[1], "  Length: 12");
+
+    // A single string column used to be unclassifiable; it is now Other with
+    // the failed expectations spelled out.
+    let path = tmp.path().join("labels.lance");
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "label",
+        DataType::Utf8,
+        false,
+    )]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![Arc::new(arrow_array::StringArray::from(vec!["a", "b"]))],
+    )
+    .unwrap();
+    let uri = crate::datasets::path_to_uri(&path);
+    crate::datasets::write_lance_batch(&uri, batch)
+        .await
+        .unwrap();
+    let other = layout_report(&Dataset::open(&uri).await.unwrap())
+        .await
+        .unwrap();
+    assert!(other[0].starts_with("Layout: Other"), "{other:?}");
+    assert!(
+        other
+            .iter()
+            .any(|l| l.contains("expected FixedSizeList<Float64>, found Utf8")),
+        "{other:?}"
+    );
+    assert!(
+        other.iter().any(|l| l.contains("found [label]")),
+        "{other:?}"
+    );
+    cmd_info(&path, false, false).await.unwrap();
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn sample_batch_carries_source_row_ids() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    let uri = crate::datasets::path_to_uri(&out.join("norms.lance"));
+    let dataset = Dataset::open(&uri).await.unwrap();
+    let full = dataset.scan().try_into_batch().await.unwrap();
+
+    let batch = sample_batch(&dataset, &[2, 5, 11]).await.unwrap();
+    assert_eq!(batch.num_rows(), 3);
+    let ids = batch
+        .column_by_name(crate::display::ROWID_COLUMN)
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow_array::UInt64Array>()
+        .unwrap();
+    assert_eq!(ids.values(), &[2, 5, 11]);
+
+    // Each sampled value is the source row named by its id.
+    let source = full
+        .column(0)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    let sampled = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    for (i, &id) in ids.values().iter().enumerate() {
+        assert_eq!(sampled.value(i), source.value(id as usize));
+    }
+
+    assert_eq!(sample_batch(&dataset, &[]).await.unwrap().num_rows(), 0);
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn sampled_rows_round_trip_through_a_new_dataset() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+    let dense = out.join("dense.lance");
+
+    let sample = |output: &Path, force, normalized| Command::Sample {
+        n: 7,
+        seed: Some(11),
+        output: Some(output.to_path_buf()),
+        force,
+        normalized,
+        format: ExportFormat::Lance,
+        select: None,
+        cast: None,
+        visible_cols: None,
+        viewer: ViewerArgs::default(),
+    };
+    let first = tmp.path().join("first.lance");
+    dispatch(sample(&first, false, false), Some(dense.clone()))
+        .await
+        .unwrap();
+    cmd_info(&first, false, false).await.unwrap();
+
+    let batch = read_lance(&first).await;
+    assert_eq!(batch.num_rows(), 7);
+    assert_eq!(detect_lance_layout(&batch), LanceLayout::DenseRowMajor);
+    assert!(batch.column_by_name(crate::display::ROWID_COLUMN).is_none());
+    let metadata = batch.schema().metadata().clone();
+    assert_eq!(metadata[export::COMMAND_KEY], "sample 7");
+    assert_eq!(metadata[export::SEED_KEY], "11");
+    assert!(metadata[export::SOURCE_KEY].ends_with("dense.lance"));
+
+    // An existing output is only replaced with --force; the same seed draws
+    // the same rows.
+    assert!(matches!(
+        app_error(dispatch(sample(&first, false, false), Some(dense.clone())).await),
+        AppError::Sample(_)
+    ));
+    dispatch(sample(&first, true, false), Some(dense.clone()))
+        .await
+        .unwrap();
+    assert_eq!(read_lance(&first).await.column(0), batch.column(0));
+
+    // Normalized: the col_* columns the viewer shows.
+    let normalized = tmp.path().join("normalized.lance");
+    dispatch(sample(&normalized, false, true), Some(dense))
+        .await
+        .unwrap();
+    let wide = read_lance(&normalized).await;
+    assert_eq!(wide.num_rows(), 7);
+    assert_eq!(wide.schema().field(0).name(), "col_0");
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn sampled_rows_can_be_selected_and_downcast() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+    let dense = out.join("dense.lance");
+    let sample = |output: &Path, format| Command::Sample {
+        n: 5,
+        seed: Some(3),
+        output: Some(output.to_path_buf()),
+        force: false,
+        normalized: false,
+        format,
+        select: Some(export::parse_select("col_0,col_2..col_3").unwrap()),
+        cast: Some(export::CastTarget::F32),
+        visible_cols: None,
+        viewer: ViewerArgs::default(),
+    };
+
+    // Lance: the vector column sliced to three Float32 dimensions.
+    let lance = tmp.path().join("subset.lance");
+    dispatch(sample(&lance, ExportFormat::Lance), Some(dense.clone()))
+        .await
+        .unwrap();
+    let batch = read_lance(&lance).await;
+    assert_eq!(batch.num_columns(), 1);
+    assert_eq!(batch.num_rows(), 5);
+    let list = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<arrow_array::FixedSizeListArray>()
+        .unwrap();
+    assert_eq!(list.value_length(), 3);
+    assert!(list.values().as_any().is::<arrow_array::Float32Array>());
+    assert_eq!(
+        batch.schema().metadata()[export::FILTER_KEY],
+        "select col_0,col_2..col_3, cast f32"
+    );
+
+    // Parquet of the whole matrix, as the col_* columns of the selection.
+    let source = read_lance(&dense).await;
+    let parquet = tmp.path().join("subset.parquet");
+    let mut opts = export::ExportOptions {
+        out: parquet.clone(),
+        normalized: true,
+        format: ExportFormat::Parquet,
+        select: export::parse_select("col_0,col_2..col_3").unwrap(),
+        cast: Some(export::CastTarget::F32),
+        ..export::ExportOptions::default()
+    };
+    let summary = export::write_export(&source, &opts, &export::Provenance::default())
+        .await
+        .unwrap();
+    assert_eq!((summary.rows, summary.columns), (source.num_rows(), 3));
+    assert!(summary.max_cast_error.unwrap() > 0.0);
+    let file = std::fs::File::open(&parquet).unwrap();
+    let read = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let names: Vec<String> = read
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.name().clone())
+        .collect();
+    assert_eq!(names, ["col_0", "col_2", "col_3"]);
+    assert!(read.column(0).as_any().is::<arrow_array::Float32Array>());
+
+    // CSV is always written as col_* columns.
+    opts.out = tmp.path().join("subset.csv");
+    opts.format = ExportFormat::Csv;
+    opts.normalized = false;
+    export::write_export(&source, &opts, &export::Provenance::default())
+        .await
+        .unwrap();
+    let csv = std::fs::read_to_string(&opts.out).unwrap();
+    assert_eq!(csv.lines().next(), Some("col_0,col_2,col_3"));
+    assert_eq!(csv.lines().count(), source.num_rows() + 1);
+
+    // Unknown columns are refused.
+    assert!(matches!(
+        app_error(
+            dispatch(
+                sample(&tmp.path().join("bad.lance"), ExportFormat::Lance),
+                Some(out.join("norms.lance"))
+            )
+            .await
+        ),
+        AppError::Sample(_)
+    ));
+}
+
+#[test]
+fn sample_indices_are_seeded_and_clamped() {
+    let a = sample_indices(100, 10, 3);
+    assert_eq!(a.len(), 10);
+    assert!(a.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(a, sample_indices(100, 10, 3));
+    assert_ne!(a, sample_indices(100, 10, 4));
+    assert_eq!(sample_indices(3, 10, 3), [0, 1, 2]);
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn head_batch_reads_a_window_at_an_offset() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    let path = out.join("norms.lance");
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let full = read_lance(&path).await;
+    let source = full
+        .column(0)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+
+    let window = head_batch(&dataset, 4, 5).await.unwrap();
+    assert_eq!(window.num_rows(), 4);
+    let values = window
+        .column(0)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    let ids = window
+        .column_by_name(crate::display::ROWID_COLUMN)
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow_array::UInt64Array>()
+        .unwrap();
+    for i in 0..4 {
+        assert_eq!(values.value(i), source.value(5 + i));
+        assert_eq!(ids.value(i), 5 + i as u64);
+    }
+
+    // Windows running off the end are truncated; past the end is empty.
+    assert_eq!(head_batch(&dataset, 4, 10).await.unwrap().num_rows(), 2);
+    assert_eq!(head_batch(&dataset, 4, 12).await.unwrap().num_rows(), 0);
+    assert_eq!(head_batch(&dataset, 4, 1_000).await.unwrap().num_rows(), 0);
+    // Offset 0 is the plain head without ids.
+    assert_eq!(head_batch(&dataset, 3, 0).await.unwrap().num_columns(), 1);
+
+    // An offset past the end prints a message instead of opening the viewer.
+    cmd_head(&path, 4, 1_000, None, false).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn follow_reads_the_rows_a_version_appends() {
+    use crate::datasets::{
+        append_lance_batch, dense_rows_to_batch, path_to_uri, write_lance_batch,
+    };
+    use crate::functions::follow::{FollowUpdate, read_appended};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("growing.lance");
+    let uri = path_to_uri(&path);
+    let rows = |n: usize, v: f64| dense_rows_to_batch(&vec![vec![v; 3]; n]).unwrap();
+    write_lance_batch(&uri, rows(4, 1.0)).await.unwrap();
+
+    let opts = DisplayOptions {
+        cols: Some(parse_slice_range("1..3").unwrap()),
+        ..DisplayOptions::default()
+    };
+    let dataset = Dataset::open(&uri).await.unwrap();
+    let version = dataset.version().version;
+    let (data, source, _, _) = load_display_window(&dataset, &path, &opts)
+        .await
+        .unwrap()
+        .unwrap();
+    let schema = data.schema();
+
+    // Nothing new while the version stands.
+    let appended = |seen, version| read_appended(&path, &opts, &schema, seen, version);
+    assert!(appended(4, version).await.unwrap().is_none());
+
+    // Appended rows come shaped like the view: the same two features, no
+    // `_rowid`, and packed matrices.
+    let next = append_lance_batch(&uri, rows(3, 2.0)).await.unwrap();
+    let Some(FollowUpdate::Appended(new)) = appended(source.total_rows, version).await.unwrap()
+    else {
+        panic!("expected appended rows");
+    };
+    assert_eq!((new.total_rows, new.version), (7, next));
+    assert_eq!(new.rows.num_rows(), 3);
+    assert_eq!(new.rows.schema(), schema);
+    assert_eq!(new.dense.len(), new.rows.batches().len());
+
+    // A version with fewer rows stops following.
+    let reader = arrow::record_batch::RecordBatchIterator::new(
+        vec![Ok(rows(2, 3.0))],
+        rows(2, 3.0).schema(),
+    );
+    let params = lance::dataset::WriteParams {
+        mode: lance::dataset::WriteMode::Overwrite,
+        ..Default::default()
+    };
+    Dataset::write(reader, uri.as_str(), Some(params))
+        .await
+        .unwrap();
+    let Some(FollowUpdate::Stopped { reason, .. }) = appended(7, next).await.unwrap() else {
+        panic!("expected a stop");
+    };
+    assert!(
+        reason.contains("has 2 rows, fewer than the 7 shown"),
+        "{reason}"
+    );
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn display_window_slices_rows_and_feature_columns() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    let path = out.join("dense.lance");
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let full = normalize_for_display(&read_lance(&path).await).unwrap();
+
+    // Rows 3..20 clamp to 3..12; feature columns 1..3 are col_1 and col_2.
+    let opts = DisplayOptions {
+        rows: Some(parse_slice_range("3..20").unwrap()),
+        cols: Some(parse_slice_range("1..3").unwrap()),
+        ..DisplayOptions::default()
+    };
+    let (data, source, dense, _) = load_display_window(&dataset, &path, &opts)
+        .await
+        .unwrap()
+        .unwrap();
+    let batch = data.whole();
+    assert_eq!(batch.num_rows(), 9);
+    assert_eq!(
+        (
+            source.row_offset,
+            source.total_rows,
+            source.col_offset,
+            source.total_cols
+        ),
+        (3, 12, 1, Some(4))
+    );
+    for name in ["col_1", "col_2"] {
+        let got = batch.column_by_name(name).unwrap().as_any();
+        let got = got.downcast_ref::<Float64Array>().unwrap();
+        let want = full.column_by_name(name).unwrap().as_any();
+        let want = want.downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(got.values(), &want.values()[3..12], "{name}");
+    }
+    assert!(batch.column_by_name("col_0").is_none());
+    // The packed matrix is kept for the same block, one per batch.
+    let rows: usize = dense.iter().map(|m| m.list.len()).sum();
+    assert_eq!((rows, dense[0].features.clone()), (9, 1..3));
+
+    let text = crate::display::render_batch_to_string(
+        batch,
+        &crate::display::RenderOptions {
+            source: Some(source),
+            ..Default::default()
+        },
+    );
+    assert!(text.contains("rows 3–11 of 12"), "{text}");
+    assert!(text.contains("feature cols 1–2 of 4"), "{text}");
+    assert!(text.contains("#11"), "{text}");
+
+    // A range entirely past the end leaves nothing to show.
+    let past = DisplayOptions {
+        rows: Some(parse_slice_range("50..60").unwrap()),
+        ..DisplayOptions::default()
+    };
+    assert!(
+        load_display_window(&dataset, &path, &past)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn display_limit_caps_the_read_and_flags_the_view() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    let path = out.join("dense.lance");
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let load = |opts: DisplayOptions| {
+        let (dataset, path) = (&dataset, &path);
+        async move {
+            load_display_window(dataset, path, &opts)
+                .await
+                .unwrap()
+                .unwrap()
+        }
+    };
+
+    // The first 5 of 12 rows, read two at a time.
+    let (data, source, dense, _) = load(DisplayOptions {
+        limit: Some(5),
+        batch_size: Some(2),
+        ..DisplayOptions::default()
+    })
+    .await;
+    assert_eq!(data.num_rows(), 5);
+    assert_eq!(data.batches().len(), 3);
+    assert_eq!(dense.len(), 3);
+    assert_eq!(source.limited, Some((5, 12)));
+    let text = crate::display::render_batch_to_string(
+        data.whole(),
+        &crate::display::RenderOptions {
+            source: Some(source),
+            ..Default::default()
+        },
+    );
+    assert!(text.contains("rows 0–4 of 12, limit 5"), "{text}");
+    assert!(text.contains("showing first 5 of 12 rows"), "{text}");
+
+    // The limit applies within --rows; a limit that cuts nothing is not
+    // flagged.
+    let (data, source, _, _) = load(DisplayOptions {
+        rows: Some(parse_slice_range("3..10").unwrap()),
+        limit: Some(4),
+        ..DisplayOptions::default()
+    })
+    .await;
+    assert_eq!((data.num_rows(), source.row_offset), (4, 3));
+    assert_eq!(source.limited, Some((4, 7)));
+    let (data, source, _, _) = load(DisplayOptions {
+        limit: Some(12),
+        ..DisplayOptions::default()
+    })
+    .await;
+    assert_eq!((data.num_rows(), source.limited), (12, None));
+    let text = crate::display::render_batch_to_string(
+        data.whole(),
+        &crate::display::RenderOptions {
+            source: Some(source),
+            ..Default::default()
+        },
+    );
+    assert!(!text.contains("limit"), "{text}");
+
+    // --no-expand keeps the vector column whole, one preview per cell.
+    let (data, _, dense, _) = load(DisplayOptions {
+        limit: Some(3),
+        no_expand: true,
+        ..DisplayOptions::default()
+    })
+    .await;
+    assert_eq!(data.schema().field(0).name(), "vector");
+    assert_eq!(dense.len(), 1);
+    let text = crate::display::render_batch_to_string(data.whole(), &Default::default());
+    assert!(text.contains("…] (4)"), "{text}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn empty_dataset_loads_for_the_placeholder() {
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("empty.lance");
+    let uri = crate::datasets::path_to_uri(&path);
+    let schema = Schema::new(vec![
+        Field::new("col_0", DataType::Float64, true),
+        Field::new("col_1", DataType::Float64, true),
+    ]);
+    crate::datasets::write_lance_batch(&uri, RecordBatch::new_empty(Arc::new(schema)))
+        .await
+        .unwrap();
+    let dataset = Dataset::open(&uri).await.unwrap();
+
+    // The empty batch is handed to the viewer instead of an early return.
+    let (data, source, _, _) = load_display_window(&dataset, &path, &DisplayOptions::default())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((data.num_rows(), data.num_columns()), (0, 2));
+    let text = crate::display::render_batch_to_string(
+        data.whole(),
+        &crate::display::RenderOptions {
+            source: Some(source),
+            ..Default::default()
+        },
+    );
+    assert!(text.contains("No rows"), "{text}");
+    assert!(text.contains("col_1: Float64"), "{text}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stats_json_reports_null_counts_from_validity() {
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("nulls.lance");
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Float64, true),
+        Field::new("b", DataType::Float64, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(vec![Some(1.0), None, None, Some(4.0)])),
+            Arc::new(Float64Array::from(vec![Some(1.0); 4])),
+        ],
+    )
+    .unwrap();
+    crate::datasets::write_lance_batch(&crate::datasets::path_to_uri(&path), batch)
+        .await
+        .unwrap();
+
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let report = stats_json(
+        &dataset,
+        &path,
+        &StatsCache::disabled(),
+        DEFAULT_MAX_DISTINCT,
+        DEFAULT_CONSTANT_THRESHOLD,
+    )
+    .await
+    .unwrap();
+    assert_eq!(report["rows"], 4);
+    assert_eq!(report["nulls"]["a"]["count"], 2);
+    assert_eq!(report["nulls"]["a"]["fraction"], 0.5);
+    assert_eq!(report["nulls"]["b"]["count"], 0);
+    assert_eq!(report["columns"][0]["name"], "a");
+    assert_eq!(report["stats"]["a"]["count"], 2);
+    assert_eq!(report["stats"]["a"]["mean"], 2.5);
+    assert_eq!(report["stats"]["b"]["histogram"], json!([]));
+    // `b` never changes; `a` does
+    assert_eq!(report["constant"]["b"]["kind"], "constant");
+    assert_eq!(report["constant"]["b"]["value"], 1.0);
+    assert!(report["constant"].get("a").is_none(), "{report}");
+
+    crate::cache::disable();
+    cmd_stats(
+        &path,
+        true,
+        DEFAULT_MAX_DISTINCT,
+        DEFAULT_CONSTANT_THRESHOLD,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stats_lists_dead_vector_dimensions_apart_from_near_constant_ones() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("dead.lance");
+    // col_1 is all zero, col_2 barely moves around 50
+    let rows: Vec<Vec<f64>> = (0..8)
+        .map(|r| vec![r as f64, 0.0, 50.0 + (r % 2) as f64 * 1e-4])
+        .collect();
+    let batch = crate::datasets::dense_rows_to_batch(&rows).unwrap();
+    crate::datasets::write_lance_batch(&crate::datasets::path_to_uri(&path), batch)
+        .await
+        .unwrap();
+
+    let mut text = Vec::new();
+    write_stats(
+        &mut text,
+        &path,
+        false,
+        DEFAULT_MAX_DISTINCT,
+        DEFAULT_CONSTANT_THRESHOLD,
+    )
+    .await
+    .unwrap();
+    let stats = String::from_utf8(text).unwrap();
+    assert!(
+        stats.contains("\nNear-constant columns (all 8 rows, std ≤ 0.001·|mean|):\n"),
+        "{stats}"
+    );
+    assert!(stats.contains("  col_1: all zero\n"), "{stats}");
+    assert!(
+        stats.contains("  col_2: near-constant 50.000050"),
+        "{stats}"
+    );
+    assert!(!stats.contains("  col_0: "), "{stats}");
+
+    // Each call takes its own threshold: 0 keeps only the exact constants
+    let mut text = Vec::new();
+    write_stats(&mut text, &path, false, DEFAULT_MAX_DISTINCT, 0.0)
+        .await
+        .unwrap();
+    let stats = String::from_utf8(text).unwrap();
+    assert!(stats.contains("  col_1: all zero\n"), "{stats}");
+    assert!(!stats.contains("  col_2: "), "{stats}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stats_json_lists_categorical_frequencies() {
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::{BooleanArray, StringArray};
+    use std::sync::Arc;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("categorical.lance");
+    let schema = Schema::new(vec![
+        Field::new("flag", DataType::Boolean, true),
+        Field::new("label", DataType::Utf8, true),
+        Field::new("id", DataType::Int64, false),
+        Field::new("small", DataType::Int64, false),
+    ]);
+    let n = 40;
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(BooleanArray::from(
+                (0..n)
+                    .map(|i| (i != 0).then_some(i % 4 == 0))
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(StringArray::from(
+                (0..n).map(|i| format!("cell-{i}")).collect::<Vec<_>>(),
+            )),
+            Arc::new(Int64Array::from((0..n).collect::<Vec<i64>>())),
+            Arc::new(Int64Array::from(
+                (0..n).map(|i| i % 3).collect::<Vec<i64>>(),
+            )),
+        ],
+    )
+    .unwrap();
+    crate::datasets::write_lance_batch(&crate::datasets::path_to_uri(&path), batch)
+        .await
+        .unwrap();
+
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let report = stats_json(
+        &dataset,
+        &path,
+        &StatsCache::disabled(),
+        10,
+        DEFAULT_CONSTANT_THRESHOLD,
+    )
+    .await
+    .unwrap();
+    let freq = &report["frequencies"];
+    assert_eq!(freq["flag"]["distinct"], 2);
+    assert_eq!(freq["flag"]["nulls"], 1);
+    assert_eq!(
+        freq["flag"]["top"][0],
+        json!({"value": "false", "count": 30, "fraction": 0.75})
+    );
+    assert_eq!(freq["label"]["high_cardinality"], true);
+    assert!(freq["label"]["estimated_distinct"].as_u64().unwrap() > 10);
+    assert_eq!(freq["small"]["distinct"], 3);
+    // High-cardinality integers are plain numeric columns, not categories.
+    assert!(freq.get("id").is_none());
+
+    crate::cache::disable();
+    cmd_stats(&path, false, 10, DEFAULT_CONSTANT_THRESHOLD)
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn binary_columns_get_length_stats_and_export_as_full_hex() {
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::FixedSizeBinaryArray;
+    use std::sync::Arc;
+
+    let hashes: Vec<[u8; 32]> = (0..20u8).map(|i| [i % 5; 32]).collect();
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("hash", DataType::FixedSizeBinary(32), false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int64Array::from((0..20).collect::<Vec<i64>>())),
+            Arc::new(FixedSizeBinaryArray::try_from_iter(hashes.iter()).unwrap()),
+        ],
+    )
+    .unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("hashes.lance");
+    crate::datasets::write_lance_batch(&crate::datasets::path_to_uri(&path), batch.clone())
+        .await
+        .unwrap();
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let report = stats_json(
+        &dataset,
+        &path,
+        &StatsCache::disabled(),
+        10,
+        DEFAULT_CONSTANT_THRESHOLD,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        report["binary"]["hash"],
+        json!({
+            "nulls": 0,
+            "min_length": 32,
+            "mean_length": 32.0,
+            "max_length": 32,
+            "distinct": 5,
+            "distinct_exact": true,
+        })
+    );
+    crate::cache::disable();
+    cmd_stats(&path, false, 10, DEFAULT_CONSTANT_THRESHOLD)
+        .await
+        .unwrap();
+
+    let opts = export::ExportOptions {
+        out: tmp.path().join("hashes.csv"),
+        format: ExportFormat::Csv,
+        ..export::ExportOptions::default()
+    };
+    export::write_export(&batch, &opts, &export::Provenance::default())
+        .await
+        .unwrap();
+    let csv = std::fs::read_to_string(&opts.out).unwrap();
+    assert_eq!(
+        csv.lines().nth(2),
+        Some(format!("1,{}", "01".repeat(32)).as_str())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn near_constant_columns_keep_their_std() {
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    // 1 + k·2⁻³⁰ for k = 0..3 repeated: exact values whose spread is far
+    // below the rounding error of E[x²] − mean².
+    let step = 2f64.powi(-30);
+    let near: Vec<f64> = (0..1000).map(|i| 1.0 + (i % 4) as f64 * step).collect();
+    let expected_std = step * 1.25f64.sqrt();
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("near_constant.lance");
+    let uri = crate::datasets::path_to_uri(&path);
+    let schema = Schema::new(vec![
+        Field::new("near", DataType::Float64, false),
+        Field::new("flat", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(near)),
+            Arc::new(Float64Array::from(vec![0.1; 1000])),
+        ],
+    )
+    .unwrap();
+    crate::datasets::write_lance_batch(&uri, batch)
+        .await
+        .unwrap();
+
+    let dataset = Dataset::open(&uri).await.unwrap();
+    let stats = cached_column_stats(&dataset, &path, &StatsCache::disabled(), false)
+        .await
+        .unwrap();
+    let near = &stats[0].1;
+    assert!((near.mean.unwrap() - (1.0 + 1.5 * step)).abs() < 1e-12);
+    let std = near.std.unwrap();
+    assert!((std - expected_std).abs() < 1e-12, "std {std:e}");
+    assert!((std / expected_std - 1.0).abs() < 1e-6, "std {std:e}");
+    assert_eq!(stats[1].1.std, Some(0.0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn column_stats_are_cached_until_the_version_changes() {
+    use arrow::datatypes::{DataType, Field, Schema};
+    use lance::dataset::{WriteMode, WriteParams};
+    use std::sync::Arc;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("cached.lance");
+    let uri = crate::datasets::path_to_uri(&path);
+    let schema = Arc::new(Schema::new(vec![Field::new("x", DataType::Int64, true)]));
+    let batch = |values: Vec<Option<i64>>| {
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(values))]).unwrap()
+    };
+    crate::datasets::write_lance_batch(&uri, batch((0..10).map(Some).collect()))
+        .await
+        .unwrap();
+
+    let cache = StatsCache::at(tmp.path().join("cache"));
+    let dataset = Dataset::open(&uri).await.unwrap();
+    let version = dataset.version().version;
+    assert!(cache.get(&path, version, "x").is_none());
+
+    let stats = cached_column_stats(&dataset, &path, &cache, false)
+        .await
+        .unwrap();
+    let x = &stats[0].1;
+    assert_eq!((x.min, x.max, x.count), (Some(0.0), Some(9.0), 10));
+    assert_eq!(x.histogram.iter().sum::<u64>(), 10);
+    assert_eq!(cache.get(&path, version, "x").as_ref(), Some(x));
+
+    // A planted entry proves the second lookup is served from the cache.
+    let planted = ColumnStats {
+        mean: Some(-1.0),
+        ..x.clone()
+    };
+    cache.put(&path, version, "x", &planted).unwrap();
+    let again = cached_column_stats(&dataset, &path, &cache, false)
+        .await
+        .unwrap();
+    assert_eq!(again[0].1.mean, Some(-1.0));
+
+    // Appending rows bumps the version, so the stats are recomputed.
+    let reader = arrow::record_batch::RecordBatchIterator::new(
+        vec![Ok(batch(vec![None, Some(100)]))],
+        schema.clone(),
+    );
+    let params = WriteParams {
+        mode: WriteMode::Append,
+        ..Default::default()
+    };
+    Dataset::write(reader, &uri, Some(params)).await.unwrap();
+    let dataset = Dataset::open(&uri).await.unwrap();
+    assert_ne!(dataset.version().version, version);
+
+    let fresh = cached_column_stats(&dataset, &path, &cache, false)
+        .await
+        .unwrap();
+    let x = &fresh[0].1;
+    assert_eq!((x.max, x.null_count, x.count), (Some(100.0), 1, 11));
+    assert!(cache.get(&path, version, "x").is_none());
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn info_verbose_reports_column_storage() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    let path = out.join("adjacency.lance");
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let storage = storage_report(&dataset).await.unwrap();
+    assert!(!storage.estimated);
+    assert_eq!(storage.fragments, 1);
+    let names: Vec<&str> = storage.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["row", "col", "value"]);
+    for c in &storage.columns {
+        assert!(c.bytes > 0, "{c:?}");
+        assert!(c.pages.unwrap() >= 1, "{c:?}");
+        assert!(!c.encodings.is_empty(), "{c:?}");
+    }
+    // Float64 values take more room than UInt32 indices.
+    assert_eq!(storage.largest()[0].name, "value");
+
+    let lines = storage_lines(&storage);
+    assert!(lines[0].starts_with("Storage (file format 2."), "{lines:?}");
+    assert!(
+        lines.iter().any(|l| l.starts_with("  1. value")),
+        "{lines:?}"
+    );
+
+    let report = info_json(&dataset, &path, Some(&storage), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        report["rows"],
+        json!(dataset.count_rows(None).await.unwrap())
+    );
+    assert_eq!(report["storage"]["largest"][0], json!("value"));
+    assert_eq!(report["storage"]["columns"].as_array().unwrap().len(), 3);
+
+    cmd_info(&path, true, false).await.unwrap();
+    cmd_info(&path, true, true).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rows_added_since_a_version_are_marked_and_filtered() {
+    use lance::dataset::{WriteMode, WriteParams};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("growing.lance");
+    let uri = crate::datasets::path_to_uri(&path);
+    let rows = |range: std::ops::Range<usize>| -> Vec<Vec<f64>> {
+        range.map(|i| vec![i as f64, 10.0 * i as f64]).collect()
+    };
+    let write = |rows: Vec<Vec<f64>>, mode: WriteMode| {
+        let uri = uri.clone();
+        async move {
+            let batch = crate::datasets::dense_rows_to_batch(&rows).unwrap();
+            let schema = batch.schema();
+            let reader = arrow::record_batch::RecordBatchIterator::new(vec![Ok(batch)], schema);
+            let params = WriteParams {
+                mode,
+                ..Default::default()
+            };
+            Dataset::write(reader, &uri, Some(params)).await.unwrap();
+        }
+    };
+    write(rows(0..4), WriteMode::Create).await;
+    write(rows(4..6), WriteMode::Append).await;
+    let dataset = Dataset::open(&uri).await.unwrap();
+
+    // Appended fragments are new by lineage
+    let base = open_version(&dataset, 1).await.unwrap();
+    let added = added_rows(&dataset, &base).await.unwrap();
+    assert_eq!(added.comparison, Comparison::Lineage);
+    assert_eq!(added.count(), 2);
+    assert!(added.contains(4) && added.contains(5) && !added.contains(3));
+    assert!(open_version(&dataset, 99).await.is_err());
+
+    let since = DisplayOptions {
+        since_version: Some(1),
+        ..DisplayOptions::default()
+    };
+    let (data, source, _, added) = load_display_window(&dataset, &path, &since)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(data.num_rows(), 6);
+    let text = crate::display::render_batch_to_string(
+        data.whole(),
+        &crate::display::RenderOptions {
+            source: Some(source),
+            added,
+            ..Default::default()
+        },
+    );
+    assert!(text.contains("2 rows new since v1"), "{text}");
+
+    let only_new = DisplayOptions {
+        only_new: true,
+        ..since
+    };
+    assert_eq!(only_new.filter(), "new since version 1");
+    let (data, source, _, _) = load_display_window(&dataset, &path, &only_new)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(data.num_rows(), 2);
+    assert_eq!(source.total_rows, 2);
+    let ids = data
+        .whole()
+        .column_by_name(crate::display::ROWID_COLUMN)
+        .unwrap();
+    assert_eq!(
+        ids.as_any()
+            .downcast_ref::<arrow_array::UInt64Array>()
+            .unwrap()
+            .values(),
+        &[4, 5]
+    );
+
+    // An overwrite keeps no fragment of version 1, so rows compare by value
+    let mut rewritten = rows(0..4);
+    rewritten.reverse();
+    rewritten.insert(2, vec![-1.0, -1.0]);
+    write(rewritten, WriteMode::Overwrite).await;
+    let dataset = Dataset::open(&uri).await.unwrap();
+    let base = open_version(&dataset, 1).await.unwrap();
+    let added = added_rows(&dataset, &base).await.unwrap();
+    assert_eq!(added.comparison, Comparison::Hashed);
+    assert_eq!(added.count(), 1);
+    assert!(added.contains(2));
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn info_lists_the_columns_the_viewer_hides() {
+    use arrow_array::Array;
+
+    let meta = arrow_array::StructArray::from(vec![(
+        std::sync::Arc::new(arrow::datatypes::Field::new(
+            "a",
+            arrow::datatypes::DataType::Int32,
+            false,
+        )),
+        std::sync::Arc::new(arrow_array::Int32Array::from(vec![1, 2])) as arrow_array::ArrayRef,
+    )]);
+    let schema = arrow::datatypes::Schema::new(vec![
+        arrow::datatypes::Field::new("x", arrow::datatypes::DataType::Float64, false),
+        arrow::datatypes::Field::new("meta", meta.data_type().clone(), false),
+        arrow::datatypes::Field::new("blob", arrow::datatypes::DataType::Binary, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        std::sync::Arc::new(schema),
+        vec![
+            std::sync::Arc::new(Float64Array::from(vec![1.0, 2.0])),
+            std::sync::Arc::new(meta),
+            std::sync::Arc::new(arrow_array::BinaryArray::from(vec![
+                &b"ab"[..],
+                &b"cde"[..],
+            ])),
+        ],
+    )
+    .unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("blobs.lance");
+    crate::datasets::write_lance_batch(&crate::datasets::path_to_uri(&path), batch)
+        .await
+        .unwrap();
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let report = info_json(&dataset, &path, None, None).await.unwrap();
+    assert_eq!(report["layout"], "Other", "{report}");
+    assert!(report["layout_mismatches"].is_array(), "{report}");
+    assert_eq!(
+        report["columns"],
+        json!([
+            { "name": "x", "type": "Float64", "supported": true },
+            { "name": "meta", "type": "Struct", "supported": false },
+            { "name": "blob", "type": "Binary", "supported": true },
+        ])
+    );
+    cmd_info(&path, false, false).await.unwrap();
+
+    // Dense vectors are shown expanded, so nothing is hidden
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+    let dense = Dataset::open(&crate::datasets::path_to_uri(&out.join("dense.lance")))
+        .await
+        .unwrap();
+    let report = info_json(&dense, &out.join("dense.lance"), None, None)
+        .await
+        .unwrap();
+    // and the layout is a bare kind, without hints on what to run
+    assert_eq!(report["layout"], "DenseRowMajor", "{report}");
+    assert!(report.get("layout_mismatches").is_none(), "{report}");
+    let columns = report["columns"].as_array().unwrap();
+    assert!(
+        columns.iter().all(|c| c["supported"] == json!(true)),
+        "{report}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deleted_rows_are_counted_and_read_back_as_tombstones() {
+    let schema = arrow::datatypes::Schema::new(vec![
+        arrow::datatypes::Field::new("id", arrow::datatypes::DataType::Int64, false),
+        arrow::datatypes::Field::new("x", arrow::datatypes::DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        std::sync::Arc::new(schema),
+        vec![
+            std::sync::Arc::new(Int64Array::from_iter_values(0..6)),
+            std::sync::Arc::new(Float64Array::from_iter_values(
+                (0..6).map(|i| i as f64 / 2.0),
+            )),
+        ],
+    )
+    .unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("deleted.lance");
+    let uri = crate::datasets::path_to_uri(&path);
+    crate::datasets::write_lance_batch(&uri, batch)
+        .await
+        .unwrap();
+    let mut dataset = Dataset::open(&uri).await.unwrap();
+    dataset.delete("id = 1 OR id = 4").await.unwrap();
+
+    let report = deletion_report(&dataset).await.unwrap();
+    assert_eq!(
+        (
+            report.physical_rows(),
+            report.deleted_rows(),
+            report.logical_rows()
+        ),
+        (6, 2, 4)
+    );
+    let lines = deletion_lines(&report);
+    assert!(
+        lines[0].starts_with("Deletions: 2 of 6 physical row(s) deleted"),
+        "{lines:?}"
+    );
+    let json = info_json(&dataset, &path, None, Some(&report))
+        .await
+        .unwrap();
+    assert_eq!(json["deletions"]["logical_rows"], json!(4));
+
+    // Live rows 1..3 with the tombstone just before them.
+    let ids = |data: &crate::functions::batch_source::BatchSource| -> Vec<Option<u64>> {
+        data.whole()
+            .column_by_name(crate::display::ROWID_COLUMN)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow_array::UInt64Array>()
+            .unwrap()
+            .iter()
+            .collect()
+    };
+    let window = scan_with_deleted(&dataset, 1..3, None).await.unwrap();
+    assert_eq!(ids(&window), [None, Some(1), Some(2)]);
+
+    let (data, source, _, _) = load_display_window(
+        &dataset,
+        &path,
+        &DisplayOptions {
+            include_deleted: true,
+            ..DisplayOptions::default()
+        },
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(ids(&data), [Some(0), None, Some(1), Some(2), None, Some(3)]);
+    assert_eq!((source.total_rows, source.deleted_rows), (4, 2));
+    let text = crate::display::render_batch_to_string(
+        data.whole(),
+        &crate::display::RenderOptions {
+            source: Some(source),
+            ..Default::default()
+        },
+    );
+    assert!(text.contains("2 rows deleted in this version"), "{text}");
+    assert!(text.contains("✗ del"), "{text}");
+    assert_eq!(drop_tombstones(data.whole()).unwrap().num_rows(), 4);
+
+    cmd_info(&path, true, false).await.unwrap();
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn validate_passes_generated_fixture() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    for name in ["dense.lance", "adjacency.lance", "norms.lance"] {
+        let path = out.join(name);
+        let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+            .await
+            .unwrap();
+        let checks = validate_dataset(&dataset, true, false).await.unwrap();
+        assert!(checks.iter().all(|c| c.passed), "{name}: {checks:?}");
+        assert!(checks.iter().any(|c| c.name == "finite values"));
+
+        dispatch(
+            Command::Validate {
+                check_finite: true,
+                json: true,
+            },
+            Some(path),
+        )
+        .await
+        .unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn validate_locates_out_of_bounds_triples() {
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("bad.lance");
+    let schema = Schema::new(vec![
+        Field::new("row", DataType::UInt32, false),
+        Field::new("col", DataType::UInt32, false),
+        Field::new("value", DataType::Float64, false),
+    ])
+    .with_metadata(HashMap::from([
+        ("rows".to_string(), "3".to_string()),
+        ("cols".to_string(), "3".to_string()),
+    ]));
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt32Array::from(vec![0, 1, 2, 1])),
+            Arc::new(UInt32Array::from(vec![0, 5, 2, 1])),
+            Arc::new(Float64Array::from(vec![1.0, 2.0, f64::NAN, 4.0])),
+        ],
+    )
+    .unwrap();
+    crate::datasets::write_lance_batch(&crate::datasets::path_to_uri(&path), batch)
+        .await
+        .unwrap();
+
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let checks = validate_dataset(&dataset, true, false).await.unwrap();
+    let failed: Vec<_> = checks.iter().filter(|c| !c.passed).collect();
+    assert_eq!(failed.len(), 2, "{checks:?}");
+    let bounds = &failed[0];
+    assert_eq!(bounds.name, "coo bounds");
+    assert_eq!(
+        bounds.details[1],
+        "triple 1: (row 1, col 5) outside declared 3 × 3"
+    );
+    assert_eq!(failed[1].details[1], "row 2: value is not finite");
+
+    // NaN values only fail with --check-finite; bounds always do.
+    let err = app_error(
+        dispatch(
+            Command::Validate {
+                check_finite: false,
+                json: false,
+            },
+            Some(path),
+        )
+        .await,
+    );
+    assert!(matches!(err, AppError::Validate(_)), "{err}");
+    assert!(err.to_string().contains("1 of 5 checks failed"), "{err}");
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn dataset_neighbors_match_the_in_memory_search() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    let path = out.join("dense.lance");
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let matrix = [DenseMatrix::from_batch(&read_lance(&path).await, 0..usize::MAX).unwrap()];
+
+    for metric in [Metric::Cosine, Metric::Euclidean] {
+        let streamed = dataset_neighbors(&dataset, 4, 5, metric, false)
+            .await
+            .unwrap();
+        let in_memory = matrix_neighbors(&matrix, 4, 5, metric, |_| {}).unwrap();
+        assert_eq!(streamed, in_memory, "{metric:?}");
+        assert_eq!(streamed.len(), 5);
+        assert!(streamed.iter().all(|n| n.row != 4));
+        assert!(streamed.windows(2).all(|w| w[0].distance <= w[1].distance));
+    }
+
+    dispatch(
+        Command::Neighbors {
+            row: 4,
+            k: 3,
+            metric: Metric::Euclidean,
+        },
+        Some(path.clone()),
+    )
+    .await
+    .unwrap();
+
+    // Out-of-range rows and non-dense datasets are refused.
+    assert!(
+        dataset_neighbors(&dataset, 1_000, 5, Metric::Cosine, false)
+            .await
+            .is_err()
+    );
+    let coo = Dataset::open(&crate::datasets::path_to_uri(&out.join("adjacency.lance")))
+        .await
+        .unwrap();
+    let err = dataset_neighbors(&coo, 0, 5, Metric::Cosine, false)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("DenseRowMajor"), "{err}");
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn plot_lambdas_reads_a_1d_dataset() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    let path = out.join("norms.lance");
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let (_, values) = load_vector_1d(&dataset, false).await.unwrap();
+    assert_eq!(values.len(), dataset.count_rows(None).await.unwrap());
+    let text = histogram_text("norms", &values, 5);
+    assert_eq!(text.lines().count(), 6, "{text}");
+
+    dispatch(
+        Command::PlotLambdas {
+            bins: 5,
+            plain: true,
+        },
+        Some(path),
+    )
+    .await
+    .unwrap();
+
+    let dense = Dataset::open(&crate::datasets::path_to_uri(&out.join("dense.lance")))
+        .await
+        .unwrap();
+    assert!(load_vector_1d(&dense, false).await.is_err());
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test]
+async fn laplacian_checks_a_stored_path_graph() {
+    use sprs::TriMat;
+
+    // Path graph on 6 nodes: eigenvalues 2 − 2cos(kπ/6).
+    let n = 6;
+    let mut tri = TriMat::new((n, n));
+    for i in 0..n - 1 {
+        tri.add_triplet(i, i + 1, -1.0);
+        tri.add_triplet(i + 1, i, -1.0);
+        tri.add_triplet(i, i, 1.0);
+        tri.add_triplet(i + 1, i + 1, 1.0);
+    }
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("laplacian.lance");
+    let uri = crate::datasets::path_to_uri(&path);
+    crate::datasets::write_lance_batch(
+        &uri,
+        crate::datasets::csr_to_coo_batch(&tri.to_csr()).unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let dataset = Dataset::open(&uri).await.unwrap();
+    let matrix = coo_batch_to_csr(&load_coo(&dataset, false).await.unwrap()).unwrap();
+    let report = LaplacianReport::new(&matrix, LaplacianMode::Combinatorial, 100);
+    assert_eq!(report.n, n);
+    assert!(report.deviating_rows.is_empty());
+    let spectrum = report.spectrum.unwrap();
+    let second = 2.0 - 2.0 * (std::f64::consts::PI / n as f64).cos();
+    assert!((spectrum.smallest[1] - second).abs() < 1e-6, "{spectrum:?}");
+    assert!(
+        (spectrum.largest[2] - (2.0 + 3f64.sqrt())).abs() < 1e-6,
+        "{spectrum:?}"
+    );
+
+    dispatch(
+        Command::Laplacian {
+            mode: LaplacianMode::Normalized,
+            max_n: 2,
+            tui: false,
+            viewer: ViewerArgs::default(),
+        },
+        Some(path),
+    )
+    .await
+    .unwrap();
+
+    // A generated adjacency matrix is not a Laplacian, and dense data is not COO.
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+    let adjacency = Dataset::open(&crate::datasets::path_to_uri(&out.join("adjacency.lance")))
+        .await
+        .unwrap();
+    let matrix = coo_batch_to_csr(&load_coo(&adjacency, false).await.unwrap()).unwrap();
+    let report = LaplacianReport::new(&matrix, LaplacianMode::Combinatorial, 0);
+    assert!(report.positive_off_diagonal > 0);
+    let dense = Dataset::open(&crate::datasets::path_to_uri(&out.join("dense.lance")))
+        .await
+        .unwrap();
+    assert!(load_coo(&dense, false).await.is_err());
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn sparse_stats_of_a_tridiagonal_matrix() {
+    const N: usize = 12;
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("banded");
+    let opts = GenerateOptions {
+        out: out.clone(),
+        kind: GenerateKind::Banded,
+        n_items: N,
+        bandwidth: 1,
+        ..GenerateOptions::default()
+    };
+    cmd_generate(&opts).await.unwrap();
+
+    let path = out.join("adjacency.lance");
+    let dataset = Dataset::open(&crate::datasets::path_to_uri(&path))
+        .await
+        .unwrap();
+    let stats = SparseStats::of_batch(&load_coo(&dataset, false).await.unwrap()).unwrap();
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["rows"], N);
+    assert_eq!(json["nnz"], 3 * N - 2);
+    assert_eq!(json["duplicates"], 0);
+    assert_eq!(json["bandwidth"], 1);
+    assert_eq!(json["lower_bandwidth"], 1);
+    assert_eq!(json["upper_bandwidth"], 1);
+    assert_eq!(json["profile"], 3 * N - 2);
+    assert_eq!(json["empty_rows"], 0);
+
+    let sparse_stats = |json| Command::SparseStats {
+        json,
+        reorder: None,
+        permutation_out: None,
+    };
+    dispatch(sparse_stats(true), Some(path)).await.unwrap();
+    let dense = out.join("dense.lance");
+    assert!(matches!(
+        app_error(dispatch(sparse_stats(false), Some(dense)).await),
+        AppError::SparseStats(_)
+    ));
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn sparse_stats_exports_the_rcm_permutation() {
+    const N: usize = 10;
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("banded");
+    let opts = GenerateOptions {
+        out: out.clone(),
+        kind: GenerateKind::Banded,
+        n_items: N,
+        bandwidth: 2,
+        ..GenerateOptions::default()
+    };
+    cmd_generate(&opts).await.unwrap();
+
+    let path = out.join("adjacency.lance");
+    let perm = tmp.path().join("perm.lance");
+    let command = || Command::SparseStats {
+        json: true,
+        reorder: Some(Reorder::Rcm),
+        permutation_out: Some(perm.clone()),
+    };
+    dispatch(command(), Some(path.clone())).await.unwrap();
+
+    let batch = read_lance(&perm).await;
+    assert_eq!(detect_lance_layout(&batch), LanceLayout::Vector1D);
+    let order = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<arrow_array::UInt64Array>()
+        .unwrap();
+    let mut sorted: Vec<u64> = order.values().to_vec();
+    sorted.sort_unstable();
+    assert_eq!(sorted, (0..N as u64).collect::<Vec<_>>());
+
+    // The permutation is a new dataset, never overwritten.
+    assert!(matches!(
+        app_error(dispatch(command(), Some(path)).await),
+        AppError::SparseStats(_)
+    ));
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn convert_round_trips_dense_and_coo() {
+    const N: usize = 9;
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("banded");
+    let opts = GenerateOptions {
+        out: out.clone(),
+        kind: GenerateKind::Banded,
+        n_items: N,
+        bandwidth: 2,
+        ..GenerateOptions::default()
+    };
+    cmd_generate(&opts).await.unwrap();
+    let convert = |to, output: &Path, max_memory| Command::Convert {
+        to,
+        output: output.to_path_buf(),
+        threshold: 0.0,
+        max_memory,
+    };
+
+    // Dense → COO → dense gives back every value.
+    let dense = out.join("dense.lance");
+    let coo = tmp.path().join("coo.lance");
+    let back = tmp.path().join("back.lance");
+    dispatch(
+        convert(ConvertTarget::Coo, &coo, DEFAULT_MAX_MEMORY),
+        Some(dense.clone()),
+    )
+    .await
+    .unwrap();
+    let triples = read_lance(&coo).await;
+    assert_eq!(detect_lance_layout(&triples), LanceLayout::SparseCoo);
+    assert_eq!(triples.schema().metadata()["rows"], N.to_string());
+    assert_eq!(triples.schema().metadata()["cols"], N.to_string());
+    dispatch(
+        convert(ConvertTarget::Dense, &back, DEFAULT_MAX_MEMORY),
+        Some(coo.clone()),
+    )
+    .await
+    .unwrap();
+    let (original, restored) = (read_lance(&dense).await, read_lance(&back).await);
+    assert_eq!(detect_lance_layout(&restored), LanceLayout::DenseRowMajor);
+    let values = |batch: &RecordBatch| {
+        let list = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<arrow_array::FixedSizeListArray>()
+            .unwrap();
+        (0..list.len())
+            .flat_map(|r| {
+                let row = list.value(r);
+                let row = row.as_any().downcast_ref::<Float64Array>().unwrap();
+                row.values().to_vec()
+            })
+            .collect::<Vec<f64>>()
+    };
+    assert_eq!(values(&restored), values(&original));
+    assert_eq!(
+        triples.num_rows(),
+        values(&original).iter().filter(|&&v| v != 0.0).count()
+    );
+
+    // A threshold above every value leaves an empty COO dataset.
+    let empty = tmp.path().join("empty.lance");
+    dispatch(
+        Command::Convert {
+            to: ConvertTarget::Coo,
+            output: empty.clone(),
+            threshold: f64::MAX,
+            max_memory: DEFAULT_MAX_MEMORY,
+        },
+        Some(dense.clone()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(read_lance(&empty).await.num_rows(), 0);
+
+    // Existing outputs, over-budget matrices and no-op conversions fail.
+    for (cmd, input) in [
+        (
+            convert(ConvertTarget::Dense, &back, DEFAULT_MAX_MEMORY),
+            coo.clone(),
+        ),
+        (
+            convert(ConvertTarget::Dense, &tmp.path().join("small.lance"), 64),
+            coo,
+        ),
+        (
+            convert(
+                ConvertTarget::Dense,
+                &tmp.path().join("same.lance"),
+                DEFAULT_MAX_MEMORY,
+            ),
+            dense,
+        ),
+    ] {
+        assert!(matches!(
+            app_error(dispatch(cmd, Some(input)).await),
+            AppError::Convert(_)
+        ));
+    }
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test]
+async fn clusters_summarize_a_labelled_matrix() {
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::Int32Array;
+    use std::sync::Arc;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+    let dense_path = out.join("dense.lance");
+    let dense = Dataset::open(&crate::datasets::path_to_uri(&dense_path))
+        .await
+        .unwrap();
+    let rows = dense.count_rows(None).await.unwrap();
+
+    let write_labels = |name: &str, labels: Vec<i32>| {
+        let path = tmp.path().join(name);
+        async move {
+            let schema = Schema::new(vec![Field::new("label", DataType::Int32, false)]);
+            let batch =
+                RecordBatch::try_new(Arc::new(schema), vec![Arc::new(Int32Array::from(labels))])
+                    .unwrap();
+            crate::datasets::write_lance_batch(&crate::datasets::path_to_uri(&path), batch)
+                .await
+                .unwrap();
+            path
+        }
+    };
+    let labels_path = write_labels("labels.lance", (0..rows as i32).map(|r| r % 3).collect()).await;
+    let labels = Dataset::open(&crate::datasets::path_to_uri(&labels_path))
+        .await
+        .unwrap();
+    let labels = load_labels(&labels).await.unwrap();
+    let report = cluster_report(&dense, labels, 50, false).await.unwrap();
+    assert_eq!(report.rows, rows);
+    assert_eq!(report.clusters.len(), 3);
+    assert_eq!(
+        report.clusters.iter().map(|c| c.size).sum::<usize>(),
+        rows - report.skipped_rows
+    );
+    assert!(report.sample_size <= 50);
+    assert!(report.silhouette.is_some());
+
+    dispatch(
+        Command::Clusters {
+            labels: labels_path,
+            json: true,
+            tui: false,
+        },
+        Some(dense_path.clone()),
+    )
+    .await
+    .unwrap();
+
+    // One label short.
+    let short = write_labels("short.lance", vec![0; rows - 1]).await;
+    let err = dispatch(
+        Command::Clusters {
+            labels: short,
+            json: true,
+            tui: false,
+        },
+        Some(dense_path),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string().contains(&format!(
+            "the matrix has {rows} rows but the labels dataset has {}",
+            rows - 1
+        )),
+        "{err}"
+    );
+
+    // Float labels are refused.
+    let norms = Dataset::open(&crate::datasets::path_to_uri(&out.join("norms.lance")))
+        .await
+        .unwrap();
+    assert!(load_labels(&norms).await.is_err());
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test]
+async fn metadata_dims_are_checked_against_the_data() {
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::{StringArray, UInt64Array};
+    use std::sync::Arc;
+
+    // Three data rows and a metadata row still declaring the four rows the
+    // data had before it was filtered.
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Float64, true),
+        Field::new("y", DataType::Float64, true),
+        Field::new("name_id", DataType::Utf8, true),
+        Field::new("n_rows", DataType::UInt64, true),
+        Field::new("n_cols", DataType::UInt64, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(vec![
+                Some(1.0),
+                Some(2.0),
+                Some(3.0),
+                None,
+            ])),
+            Arc::new(Float64Array::from(vec![
+                Some(4.0),
+                Some(5.0),
+                Some(6.0),
+                None,
+            ])),
+            Arc::new(StringArray::from(vec![None, None, None, Some("demo")])),
+            Arc::new(UInt64Array::from(vec![None, None, None, Some(4)])),
+            Arc::new(UInt64Array::from(vec![None, None, None, Some(2)])),
+        ],
+    )
+    .unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("filtered.lance");
+    let uri = crate::datasets::path_to_uri(&path);
+    crate::datasets::write_lance_batch(&uri, batch)
+        .await
+        .unwrap();
+    let dataset = Dataset::open(&uri).await.unwrap();
+
+    let dims = metadata_dims(&dataset).await.unwrap().unwrap();
+    assert_eq!((dims.declared, dims.actual), ((4, 2), (3, 2)));
+    assert_eq!(
+        dims.line(),
+        "Declared dims (n_rows/n_cols): 4 × 2 (mismatch: actual 3×2)"
+    );
+    let report = info_json(&dataset, &path, None, None).await.unwrap();
+    assert_eq!(report["metadata_dims"]["mismatch"], json!(true));
+
+    let checks = validate_dataset(&dataset, false, false).await.unwrap();
+    let check = checks.iter().find(|c| c.name == "metadata dims").unwrap();
+    assert!(!check.passed);
+    assert_eq!(
+        check.details[0],
+        "n_rows/n_cols declare 4 × 2 but the data is 3 × 2"
+    );
+
+    // Datasets without the columns skip the check.
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+    let dense = Dataset::open(&crate::datasets::path_to_uri(&out.join("dense.lance")))
+        .await
+        .unwrap();
+    assert_eq!(metadata_dims(&dense).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn viewer_loops_leave_the_runtime_free() {
+    // The "viewer" blocks until a task on the same single-threaded runtime
+    // signals it, which only works if the loop runs off that thread.
+    let (tx, rx) = std::sync::mpsc::channel();
+    let viewer = crate::display::run_viewer(move || {
+        rx.recv()?;
+        Ok(42)
+    });
+    let loader = async move {
+        tokio::task::yield_now().await;
+        tx.send(()).unwrap();
+    };
+    let (shown, ()) = tokio::join!(viewer, loader);
+    assert_eq!(shown.unwrap(), 42);
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn display_screenshot_writes_one_headless_frame() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+
+    let shot = |path: PathBuf, size: &str, viewer| Command::Display {
+        max_memory: DEFAULT_MAX_MEMORY,
+        rows: None,
+        limit: None,
+        batch_size: None,
+        cols: None,
+        names: None,
+        screenshot: Some(path),
+        screenshot_size: parse_screen_size(size).unwrap(),
+        visible_cols: None,
+        include_deleted: false,
+        since_version: None,
+        only_new: false,
+        no_expand: false,
+        follow: false,
+        follow_interval: NonZeroU64::MIN,
+        viewer,
+    };
+    let text = tmp.path().join("dense.txt");
+    let viewer = ViewerArgs::default();
+    dispatch(
+        shot(text.clone(), "100x30", viewer),
+        Some(out.join("dense.lance")),
+    )
+    .await
+    .unwrap();
+    let text = std::fs::read_to_string(text).unwrap();
+    assert_eq!(text.lines().count(), 30);
+    assert!(text.lines().all(|l| l.chars().count() <= 100));
+    assert!(text.contains("Lance Data (rows 0–11 of 12"), "{text}");
+    assert!(!text.contains('\x1b'));
+
+    let ansi = tmp.path().join("dense.ansi");
+    let viewer = ViewerArgs::default();
+    dispatch(
+        shot(ansi.clone(), "60x20", viewer),
+        Some(out.join("dense.lance")),
+    )
+    .await
+    .unwrap();
+    let ansi = std::fs::read_to_string(ansi).unwrap();
+    assert_eq!(ansi.lines().count(), 20);
+    assert!(ansi.contains("\x1b[0;38;2;"), "{ansi:?}");
+
+    // The command's viewer options decide the frame, not an earlier call's
+    let median = tmp.path().join("median.txt");
+    let viewer = ViewerArgs {
+        row_stats: vec![crate::display::Aggregate::Median],
+        ..ViewerArgs::default()
+    };
+    dispatch(
+        shot(median.clone(), "160x30", viewer),
+        Some(out.join("dense.lance")),
+    )
+    .await
+    .unwrap();
+    let median = std::fs::read_to_string(median).unwrap();
+    assert!(median.contains("median"), "{median}");
+    assert!(!text.contains("median"), "{text}");
+
+    assert_eq!(parse_screen_size("120X40"), Ok((120, 40)));
+    assert!(parse_screen_size("0x40").is_err());
+    assert!(parse_screen_size("120").is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fingerprints_ignore_the_fragment_layout() {
+    use crate::datasets::{BatchWriter, dense_rows_to_batch, path_to_uri, write_lance_batch};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let rows: Vec<Vec<f64>> = (0..12)
+        .map(|r| (0..4).map(|c| (r * 4 + c) as f64 / 7.0).collect())
+        .collect();
+    let batch = dense_rows_to_batch(&rows).unwrap();
+
+    let single = tmp.path().join("single.lance");
+    write_lance_batch(&path_to_uri(&single), batch.clone())
+        .await
+        .unwrap();
+    let split = tmp.path().join("split.lance");
+    let mut writer = BatchWriter::new(&path_to_uri(&split), batch.schema());
+    for (offset, len) in [(0, 5), (5, 1), (6, 6)] {
+        writer.write(batch.slice(offset, len)).await.unwrap();
+    }
+    assert_eq!(writer.finish().await.unwrap(), 12);
+
+    let open = |path: PathBuf| async move { Dataset::open(&path_to_uri(&path)).await.unwrap() };
+    let (single_ds, split_ds) = (open(single.clone()).await, open(split.clone()).await);
+    assert_eq!(single_ds.get_fragments().len(), 1);
+    assert_eq!(split_ds.get_fragments().len(), 3);
+    let hash = fingerprint_dataset(&single_ds, None, false).await.unwrap();
+    assert_eq!(
+        hash,
+        fingerprint_dataset(&split_ds, None, false).await.unwrap()
+    );
+    assert_eq!(hash.len(), 32);
+
+    // A perturbation below the rounding tolerance only shows unrounded.
+    let mut nudged = rows.clone();
+    nudged[11][3] += 1e-10;
+    let perturbed = tmp.path().join("perturbed.lance");
+    write_lance_batch(
+        &path_to_uri(&perturbed),
+        dense_rows_to_batch(&nudged).unwrap(),
+    )
+    .await
+    .unwrap();
+    let perturbed_ds = open(perturbed).await;
+    assert_ne!(
+        hash,
+        fingerprint_dataset(&perturbed_ds, None, false)
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        fingerprint_dataset(&split_ds, Some(6), false)
+            .await
+            .unwrap(),
+        fingerprint_dataset(&perturbed_ds, Some(6), false)
+            .await
+            .unwrap()
+    );
+
+    let verify = |expected: &str| Command::Fingerprint {
+        round: None,
+        verify: Some(expected.to_string()),
+    };
+    dispatch(verify(&hash.to_uppercase()), Some(split.clone()))
+        .await
+        .unwrap();
+    let err = app_error(dispatch(verify(&"0".repeat(32)), Some(split)).await);
+    assert!(
+        matches!(&err, AppError::Fingerprint(e) if e.to_string().contains("mismatch")),
+        "{err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reads_reload_when_a_commit_replaces_the_scanned_version() {
+    use crate::datasets::{dense_rows_to_batch, path_to_uri, test_hooks, write_lance_batch};
+    use lance::dataset::{WriteMode, WriteParams};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("live.lance");
+    let rows = |n: usize, v: f64| dense_rows_to_batch(&vec![vec![v; 3]; n]).unwrap();
+    write_lance_batch(&path_to_uri(&path), rows(4, 1.0))
+        .await
+        .unwrap();
+    let data_files = |path: &Path| -> Vec<PathBuf> {
+        std::fs::read_dir(path.join("data"))
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect()
+    };
+    // A writer overwrites the dataset, then cleans up the files of the
+    // version the reader has just opened.
+    let commit_under_the_reader = |path: PathBuf| {
+        move || {
+            let old = data_files(&path);
+            tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(async {
+                    let batch = rows(6, 2.0);
+                    let reader = arrow_array::RecordBatchIterator::new(
+                        vec![Ok(batch.clone())],
+                        batch.schema(),
+                    );
+                    let params = WriteParams {
+                        mode: WriteMode::Overwrite,
+                        ..WriteParams::default()
+                    };
+                    Dataset::write(reader, path_to_uri(&path).as_str(), Some(params))
+                        .await
+                        .unwrap();
+                })
+            });
+            for file in old {
+                std::fs::remove_file(file).unwrap();
+            }
+        }
+    };
+
+    let sample = |output: &Path| Command::Sample {
+        n: 100,
+        seed: Some(1),
+        output: Some(output.to_path_buf()),
+        force: false,
+        normalized: false,
+        format: ExportFormat::Lance,
+        select: None,
+        cast: None,
+        visible_cols: None,
+        viewer: ViewerArgs::default(),
+    };
+    test_hooks::set_before_read(&path, commit_under_the_reader(path.clone()));
+    let out = tmp.path().join("sampled.lance");
+    dispatch(sample(&out), Some(path.clone())).await.unwrap();
+    let batch = read_lance(&out).await;
+    assert_eq!(batch.num_rows(), 6, "the rows of the reloaded version");
+
+    test_hooks::set_before_read(&path, commit_under_the_reader(path.clone()));
+    dispatch(
+        Command::Stats {
+            json: true,
+            max_distinct: DEFAULT_MAX_DISTINCT,
+            constant_threshold: DEFAULT_CONSTANT_THRESHOLD,
+        },
+        Some(path.clone()),
+    )
+    .await
+    .unwrap();
+
+    // Files missing without a newer version to reload stay an error.
+    let files = data_files(&path);
+    test_hooks::set_before_read(&path, move || {
+        for file in files {
+            std::fs::remove_file(file).unwrap();
+        }
+    });
+    let err = app_error(dispatch(sample(&tmp.path().join("lost.lance")), Some(path)).await);
+    assert!(matches!(err, AppError::Sample(_)), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dedup_reports_planted_duplicates_and_writes_first_occurrences() {
+    use crate::datasets::{BatchWriter, path_to_uri};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::{StringArray, cast::AsArray};
+    use std::sync::Arc;
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("label", DataType::Utf8, true),
+        Field::new("x", DataType::Float64, true),
+    ]));
+    // Rows 2 and 6 repeat 0, 4 repeats the NaN row 1, 5 the null row 3.
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(vec![
+                Some("a"),
+                Some("b"),
+                Some("a"),
+                None,
+                Some("b"),
+                None,
+                Some("a"),
+                Some("a"),
+            ])),
+            Arc::new(Float64Array::from(vec![
+                Some(1.0),
+                Some(f64::NAN),
+                Some(1.0),
+                None,
+                Some(f64::NAN),
+                None,
+                Some(1.0),
+                Some(-1.0),
+            ])),
+        ],
+    )
+    .unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let input = tmp.path().join("planted.lance");
+    let mut writer = BatchWriter::new(&path_to_uri(&input), schema);
+    writer.write(batch.slice(0, 5)).await.unwrap();
+    writer.write(batch.slice(5, 3)).await.unwrap();
+    writer.finish().await.unwrap();
+
+    let dataset = Dataset::open(&path_to_uri(&input)).await.unwrap();
+    let report = dedup_dataset(&dataset, None, None, false).await.unwrap();
+    assert_eq!((report.rows, report.distinct_rows), (8, 4));
+    assert_eq!((report.duplicate_groups, report.redundant_rows), (3, 4));
+    assert_eq!(report.largest[0].rows, [0, 2, 6]);
+    assert_eq!(report.largest[1].rows, [1, 4]);
+    assert_eq!(report.largest[2].rows, [3, 5]);
+
+    let output = tmp.path().join("distinct.lance");
+    let dedup = |output: &Path| Command::Dedup {
+        output: Some(output.to_path_buf()),
+        round: None,
+        json: true,
+    };
+    dispatch(dedup(&output), Some(input.clone())).await.unwrap();
+    let distinct = read_lance(&output).await;
+    let labels = distinct.column(0).as_string::<i32>();
+    let labels: Vec<Option<&str>> = labels.iter().collect();
+    assert_eq!(labels, [Some("a"), Some("b"), None, Some("a")]);
+    assert_eq!(
+        distinct
+            .column(1)
+            .as_primitive::<arrow::datatypes::Float64Type>()
+            .value(3),
+        -1.0
+    );
+    assert_eq!(distinct.schema().metadata()[export::COMMAND_KEY], "dedup");
+
+    let err = app_error(dispatch(dedup(&output), Some(input)).await);
+    assert!(
+        matches!(&err, AppError::Dedup(e) if e.to_string().contains("already exists")),
+        "{err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn outliers_rank_planted_rows_and_skip_constant_dimensions() {
+    use crate::datasets::{dense_rows_to_batch, path_to_uri, write_lance_batch};
+
+    // Dimension 0 is a ramp, 1 a ramp with two planted spikes, 2 constant.
+    let mut rows: Vec<Vec<f64>> = (0..40)
+        .map(|r| vec![r as f64, (r % 10) as f64, 5.0])
+        .collect();
+    rows[7][1] = 80.0;
+    rows[31][1] = -45.0;
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("planted.lance");
+    write_lance_batch(&path_to_uri(&path), dense_rows_to_batch(&rows).unwrap())
+        .await
+        .unwrap();
+    let dataset = Dataset::open(&path_to_uri(&path)).await.unwrap();
+    let cache = StatsCache::at(tmp.path().join("cache"));
+
+    for method in [OutlierMethod::Iqr, OutlierMethod::Std] {
+        let report = find_outliers(&dataset, &path, &cache, method, 3.0, 1, false)
+            .await
+            .unwrap();
+        assert_eq!((report.rows, report.checked), (40, 2), "{method:?}");
+        assert_eq!(report.skipped, ["col_2"]);
+        assert_eq!(report.flagged_rows, 2, "{method:?}");
+        // Capped at one row, the larger spike first.
+        assert_eq!(report.top.len(), 1);
+        assert_eq!(report.top[0].row, 7);
+        assert_eq!(report.top[0].triggers[0].feature, "col_1");
+        assert_eq!(report.top[0].triggers[0].value, 80.0);
+    }
+    // The dimension stats were cached under `vector[d]`.
+    let version = dataset.version().version;
+    assert!(cache.get(&path, version, "vector[1]").is_some());
+
+    let err = app_error(
+        dispatch(
+            Command::Outliers {
+                k: -1.0,
+                method: OutlierMethod::Iqr,
+                top: 5,
+            },
+            Some(path),
+        )
+        .await,
+    );
+    assert!(matches!(err, AppError::Outliers(_)), "{err}");
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn set_meta_writes_a_new_version_and_refuses_old_ones() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    dispatch(generate_command(&out), None).await.unwrap();
+    let path = out.join("dense.lance");
+    let uri = crate::datasets::path_to_uri(&path);
+    let before = Dataset::open(&uri).await.unwrap().version().version;
+
+    let set = |key: &str, value: &str| Command::SetMeta {
+        key: key.to_string(),
+        value: value.to_string(),
+    };
+    dispatch(set("cols", "4"), Some(path.clone()))
+        .await
+        .unwrap();
+    let dataset = Dataset::open(&uri).await.unwrap();
+    assert_eq!(dataset.version().version, before + 1);
+    let report = info_json(&dataset, &path, None, None).await.unwrap();
+    assert_eq!(report["schema_metadata"]["cols"], json!("4"));
+    cmd_info(&path, false, false).await.unwrap();
+
+    // The earlier version keeps its metadata and cannot be changed
+    let mut old = open_version(&dataset, before).await.unwrap();
+    let err = set_schema_metadata(&mut old, "cols", "5")
+        .await
+        .expect_err("an old version is refused");
+    assert_eq!(
+        crate::errors::ErrorCategory::of(&err),
+        crate::errors::ErrorCategory::InvalidArgs
+    );
+    assert!(err.to_string().contains("not the latest"), "{err}");
+    assert!(!old.schema().metadata.contains_key("cols"));
+
+    assert!(matches!(
+        app_error(dispatch(set("", "4"), Some(path.clone())).await),
+        AppError::SetMeta(_)
+    ));
+    let latest = Dataset::open(&uri).await.unwrap();
+    assert_eq!(latest.version().version, before + 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn review_flags_are_written_as_a_row_id_dataset() {
+    use arrow_array::UInt64Array;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("javelin_flags_m.lance");
+    let provenance = export::Provenance::new(Path::new("/data/m.lance"), "display");
+    // the viewer writes from its blocking thread, and a resumed review
+    // replaces the earlier dataset
+    for ids in [vec![3, 41, 7], vec![3, 41]] {
+        let (out, provenance) = (out.clone(), provenance.clone());
+        tokio::task::spawn_blocking(move || export::export_row_ids(&ids, &provenance, &out))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    let flags = read_lance(&out).await;
+    assert_eq!(flags.schema().field(0).name(), "row_id");
+    let ids = flags
+        .column(0)
+        .as_any()
+        .downcast_ref::<UInt64Array>()
+        .unwrap();
+    assert_eq!(ids.values().to_vec(), [3, 41]);
+    assert_eq!(detect_lance_layout(&flags), LanceLayout::Vector1D);
+    assert_eq!(
+        flags
+            .schema()
+            .metadata()
+            .get(export::SOURCE_KEY)
+            .map(String::as_str),
+        Some("/data/m.lance")
+    );
+}
+
+#[test]
+fn viewer_options_belong_to_the_viewer_commands() {
+    use clap::Parser;
+
+    let cli = crate::Cli::try_parse_from(["javelin", "head", "5", "--index-base", "1"]).unwrap();
+    let Some(Command::Head { viewer, .. }) = cli.cmd else {
+        panic!("expected head");
+    };
+    assert_eq!(viewer.index_base, crate::display::IndexBase::One);
 
-    // tear down
-    std::fs::remove_dir_all(&out_dir).unwrap();
+    // The logging and cache switches stay global; the viewer ones do not.
+    assert!(crate::Cli::try_parse_from(["javelin", "info", "--no-cache"]).is_ok());
+    assert!(crate::Cli::try_parse_from(["javelin", "info", "--row-stats", "mean"]).is_err());
+    assert!(crate::Cli::try_parse_from(["javelin", "--null-policy", "zero", "display"]).is_err());
 }
@@ -0,0 +1,276 @@
+//! Property tests of layout detection and display normalization over small
+//! random batches of every supported shape, plus shapes that fit none.
+
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow_array::builder::{Float64Builder, ListBuilder};
+use arrow_array::{
+    Array, ArrayRef, BooleanArray, FixedSizeListArray, Float64Array, Int32Array, Int64Array,
+    RecordBatch, StringArray, UInt8Array, UInt32Array,
+};
+use proptest::prelude::*;
+use std::sync::Arc;
+
+use crate::display::{LanceLayout, ROWID_COLUMN};
+use crate::functions::functions::{
+    attach_row_ids, detect_lance_layout, layout_mismatches, normalize_for_display,
+};
+
+fn batch_of(columns: Vec<(&str, ArrayRef)>) -> RecordBatch {
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|(name, col)| Field::new(*name, col.data_type().clone(), true))
+        .collect();
+    let cols = columns.into_iter().map(|(_, col)| col).collect();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), cols).unwrap()
+}
+
+/// Any f64, NaN and infinities included.
+fn value() -> impl Strategy<Value = f64> {
+    prop_oneof![
+        8 => -1e6..1e6f64,
+        1 => Just(f64::NAN),
+        1 => Just(f64::INFINITY),
+        1 => Just(-0.0),
+    ]
+}
+
+/// (rows, dim, row-major values) of a dense matrix.
+fn dense_values() -> impl Strategy<Value = (usize, usize, Vec<f64>)> {
+    (0..6usize, 1..6usize).prop_flat_map(|(rows, dim)| {
+        (
+            Just(rows),
+            Just(dim),
+            prop::collection::vec(value(), rows * dim),
+        )
+    })
+}
+
+fn dense_batch(dim: usize, values: &[f64]) -> RecordBatch {
+    let item = Arc::new(Field::new("item", DataType::Float64, true));
+    let list = FixedSizeListArray::try_new(
+        item,
+        dim as i32,
+        Arc::new(Float64Array::from(values.to_vec())),
+        None,
+    )
+    .unwrap();
+    batch_of(vec![("vector", Arc::new(list))])
+}
+
+fn vector_1d() -> impl Strategy<Value = RecordBatch> {
+    prop::collection::vec(-1000i32..1000, 0..8).prop_flat_map(|v| {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Float64Array::from_iter_values(v.iter().map(|&x| x as f64))),
+            Arc::new(Int64Array::from_iter_values(v.iter().map(|&x| x as i64))),
+            Arc::new(Int32Array::from(v.clone())),
+            Arc::new(UInt8Array::from_iter_values(v.iter().map(|&x| x as u8))),
+        ];
+        prop::sample::select(columns).prop_map(|col| batch_of(vec![("norm", col)]))
+    })
+}
+
+fn ragged_lists() -> impl Strategy<Value = RecordBatch> {
+    prop::collection::vec(prop::option::of(prop::collection::vec(value(), 0..4)), 0..6).prop_map(
+        |rows| {
+            let mut builder = ListBuilder::new(Float64Builder::new());
+            for row in rows {
+                match row {
+                    Some(values) => {
+                        builder.values().append_slice(&values);
+                        builder.append(true);
+                    }
+                    None => builder.append(false),
+                }
+            }
+            batch_of(vec![("values", Arc::new(builder.finish()))])
+        },
+    )
+}
+
+fn coo_batch() -> impl Strategy<Value = RecordBatch> {
+    prop::collection::vec((0..20u32, 0..20u32, value()), 0..10).prop_map(|triples| {
+        batch_of(vec![
+            (
+                "row",
+                Arc::new(UInt32Array::from_iter_values(triples.iter().map(|t| t.0))),
+            ),
+            (
+                "col",
+                Arc::new(UInt32Array::from_iter_values(triples.iter().map(|t| t.1))),
+            ),
+            (
+                "value",
+                Arc::new(Float64Array::from_iter_values(triples.iter().map(|t| t.2))),
+            ),
+        ])
+    })
+}
+
+/// The columns of a [`coo_batch`] shuffled, with an extra column and with
+/// indices of another integer type.
+fn reordered_coo() -> impl Strategy<Value = (RecordBatch, RecordBatch)> {
+    (
+        coo_batch(),
+        Just(vec![0usize, 1, 2, 3]).prop_shuffle(),
+        0..4usize,
+    )
+        .prop_map(|(coo, order, index_type)| {
+            let index_type = [
+                DataType::UInt32,
+                DataType::UInt64,
+                DataType::Int32,
+                DataType::Int64,
+            ][index_type]
+                .clone();
+            let extra: ArrayRef = Arc::new(StringArray::from_iter_values(
+                (0..coo.num_rows()).map(|r| format!("e{r}")),
+            ));
+            let columns = [
+                ("row", cast(coo.column(0), &index_type).unwrap()),
+                ("col", cast(coo.column(1), &index_type).unwrap()),
+                ("value", coo.column(2).clone()),
+                ("extra", extra),
+            ];
+            let shuffled = batch_of(order.iter().map(|&i| columns[i].clone()).collect());
+            (coo, shuffled)
+        })
+}
+
+/// Schemas of no known layout: one column of a non-numeric type, several
+/// numeric columns, or a FixedSizeList of something other than Float64.
+fn unknown_shape() -> impl Strategy<Value = RecordBatch> {
+    (0..6usize, 0..3usize).prop_map(|(rows, kind)| match kind {
+        0 => batch_of(vec![(
+            "label",
+            Arc::new(StringArray::from_iter_values(
+                (0..rows).map(|r| format!("r{r}")),
+            )),
+        )]),
+        1 => batch_of(vec![(
+            "flag",
+            Arc::new(BooleanArray::from_iter((0..rows).map(|r| Some(r % 2 == 0)))),
+        )]),
+        _ => {
+            let ints = FixedSizeListArray::try_new(
+                Arc::new(Field::new("item", DataType::Int32, true)),
+                2,
+                Arc::new(Int32Array::from_iter_values(0..(2 * rows) as i32)),
+                None,
+            )
+            .unwrap();
+            batch_of(vec![("vector", Arc::new(ints))])
+        }
+    })
+}
+
+fn multi_column() -> impl Strategy<Value = RecordBatch> {
+    (2..5usize, 0..5usize).prop_map(|(cols, rows)| {
+        let columns = (0..cols)
+            .map(|c| {
+                let col: ArrayRef = Arc::new(Float64Array::from_iter_values(
+                    (0..rows).map(|r| (r * cols + c) as f64),
+                ));
+                (["a", "b", "c", "d"][c], col)
+            })
+            .collect();
+        batch_of(columns)
+    })
+}
+
+fn any_batch() -> impl Strategy<Value = RecordBatch> {
+    prop_oneof![
+        dense_values().prop_map(|(_, dim, values)| dense_batch(dim, &values)),
+        vector_1d(),
+        ragged_lists(),
+        coo_batch(),
+        unknown_shape(),
+        multi_column(),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn normalization_never_panics(batch in any_batch(), with_ids in any::<bool>()) {
+        let batch = if with_ids {
+            let ids = (0..batch.num_rows() as u64).map(|r| r + 100).collect();
+            attach_row_ids(&batch, ids).unwrap()
+        } else {
+            batch
+        };
+        let layout = detect_lance_layout(&batch);
+        if let Ok(normalized) = normalize_for_display(&batch) {
+            prop_assert_eq!(normalized.num_rows(), batch.num_rows());
+            prop_assert_eq!(
+                normalized.column_by_name(ROWID_COLUMN).is_some(),
+                with_ids
+            );
+        }
+        // Detection agrees with the mismatch report.
+        prop_assert_eq!(
+            layout == LanceLayout::Other,
+            !layout_mismatches(&batch.schema()).is_empty()
+        );
+    }
+
+    #[test]
+    fn dense_expansion_keeps_every_value((rows, dim, values) in dense_values()) {
+        let batch = dense_batch(dim, &values);
+        prop_assert_eq!(detect_lance_layout(&batch), LanceLayout::DenseRowMajor);
+        let expanded = normalize_for_display(&batch).unwrap();
+        prop_assert_eq!(expanded.num_columns(), dim);
+        for d in 0..dim {
+            let field = expanded.schema().field(d).clone();
+            prop_assert_eq!(field.name(), &format!("col_{d}"));
+            let col = expanded
+                .column(d)
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .unwrap();
+            for r in 0..rows {
+                // Bitwise, so NaN and -0.0 count as kept.
+                prop_assert_eq!(col.value(r).to_bits(), values[r * dim + d].to_bits());
+            }
+        }
+    }
+
+    #[test]
+    fn uniform_lists_expand_like_dense(
+        (rows, dim, values) in dense_values().prop_filter("some rows", |(r, _, _)| *r > 0)
+    ) {
+        let mut builder = ListBuilder::new(Float64Builder::new());
+        for row in values.chunks(dim) {
+            builder.values().append_slice(row);
+            builder.append(true);
+        }
+        let lists = batch_of(vec![("values", Arc::new(builder.finish()) as ArrayRef)]);
+        prop_assert_eq!(detect_lance_layout(&lists), LanceLayout::RaggedList);
+        let expanded = normalize_for_display(&lists).unwrap();
+        let dense = normalize_for_display(&dense_batch(dim, &values)).unwrap();
+        prop_assert_eq!(expanded.num_rows(), rows);
+        prop_assert_eq!(expanded.columns(), dense.columns());
+    }
+
+    #[test]
+    fn coo_detection_ignores_column_order_and_extras((coo, shuffled) in reordered_coo()) {
+        prop_assert_eq!(detect_lance_layout(&shuffled), LanceLayout::SparseCoo);
+        let normalized = normalize_for_display(&shuffled).unwrap();
+        prop_assert_eq!(&normalized.columns()[..3], coo.columns());
+        prop_assert_eq!(normalized.schema().field(3).name().clone(), "extra");
+    }
+
+    #[test]
+    fn shapes_are_detected(
+        vector in vector_1d(),
+        coo in coo_batch(),
+        unknown in unknown_shape(),
+        multi in multi_column(),
+    ) {
+        prop_assert_eq!(detect_lance_layout(&vector), LanceLayout::Vector1D);
+        prop_assert_eq!(detect_lance_layout(&coo), LanceLayout::SparseCoo);
+        prop_assert_eq!(detect_lance_layout(&unknown), LanceLayout::Other);
+        prop_assert_eq!(detect_lance_layout(&multi), LanceLayout::Other);
+        // Unknown shapes are shown as they are.
+        prop_assert_eq!(&normalize_for_display(&unknown).unwrap(), &unknown);
+    }
+}
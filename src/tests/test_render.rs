@@ -0,0 +1,1940 @@
+use crate::datasets::{csr_to_coo_batch, dense_rows_to_batch};
+use crate::display::display::{
+    HorizontalOffsets, ScrollWindow, collect_feature_cols, null_label, render_frame,
+    render_source_to_string, with_dtype_tag,
+};
+use crate::display::display_1d::VectorPanels;
+use crate::display::display_bookmarks::Bookmarks;
+use crate::display::display_histogram::Clamp;
+use crate::display::row_stats::RowStats;
+use crate::display::value_format::ValueFormatter;
+use crate::display::{
+    Aggregate, DEFAULT_AGGREGATES, DiffKind, IndexBase, LanceLayout, NullFilter, NullPolicy,
+    RenderOptions, SourceWindow, Transform, render_batch_to_string,
+};
+use crate::functions::batch_source::BatchSource;
+use crate::functions::functions::{attach_row_ids, detect_lance_layout, normalize_for_display};
+use crate::functions::names::apply_names;
+
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, UInt32Array, UInt64Array};
+use ratatui::{Terminal, backend::TestBackend};
+use sprs::TriMat;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// Golden files live in src/tests/snapshots/<name>.snap. Run the tests with
+// JAVELIN_UPDATE_SNAPSHOTS=1 to (re)write them after an intended UI change.
+// A missing one is written on first run, except in CI, where it fails.
+fn assert_snapshot(name: &str, actual: &str) {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("src/tests/snapshots");
+    path.push(format!("{name}.snap"));
+
+    assert!(
+        path.exists() || std::env::var_os("CI").is_none(),
+        "snapshot {name} is missing; run the tests with JAVELIN_UPDATE_SNAPSHOTS=1 and commit it"
+    );
+    if std::env::var_os("JAVELIN_UPDATE_SNAPSHOTS").is_some() || !path.exists() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, format!("{actual}\n")).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        expected.trim_end_matches('\n'),
+        actual,
+        "snapshot {name} differs; rerun with JAVELIN_UPDATE_SNAPSHOTS=1 if the change is intended"
+    );
+}
+
+fn dense_batch(columns: Vec<Vec<Option<f64>>>) -> RecordBatch {
+    let fields: Vec<Field> = (0..columns.len())
+        .map(|i| Field::new(format!("col_{i}"), DataType::Float64, true))
+        .collect();
+    let arrays: Vec<ArrayRef> = columns
+        .into_iter()
+        .map(|c| Arc::new(Float64Array::from(c)) as ArrayRef)
+        .collect();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays).unwrap()
+}
+
+fn vector_batch(values: Vec<Option<f64>>) -> RecordBatch {
+    let schema = Schema::new(vec![Field::new("norm", DataType::Float64, true)]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(Float64Array::from(values)) as ArrayRef],
+    )
+    .unwrap()
+}
+
+fn coo_batch_with_nulls() -> RecordBatch {
+    let schema = Schema::new(vec![
+        Field::new("row", DataType::UInt32, false),
+        Field::new("col", DataType::UInt32, false),
+        Field::new("value", DataType::Float64, true),
+    ])
+    .with_metadata(HashMap::from([
+        ("rows".to_string(), "4".to_string()),
+        ("cols".to_string(), "4".to_string()),
+    ]));
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt32Array::from(vec![0, 1, 2, 3])) as ArrayRef,
+            Arc::new(UInt32Array::from(vec![0, 2, 1, 3])) as ArrayRef,
+            Arc::new(Float64Array::from(vec![None, Some(0.5), None, None])) as ArrayRef,
+        ],
+    )
+    .unwrap()
+}
+
+fn null_heavy_dense() -> RecordBatch {
+    dense_batch(vec![
+        vec![None, Some(1.0), None, None],
+        vec![None, None, None, None],
+        vec![Some(2.5), None, Some(-1.0), None],
+    ])
+}
+
+const SMALL: RenderOptions = RenderOptions {
+    width: 90,
+    height: 16,
+    row_start: 0,
+    col_offset: 0,
+    visible_cols: 8,
+    transposed: false,
+    pinned_col: None,
+    source: None,
+    row_stats: DEFAULT_AGGREGATES,
+    windowed_stats: false,
+    null_policy: NullPolicy::Skip,
+    bookmarks: &[],
+    diff: None,
+    outliers: false,
+    transform: None,
+    norm: false,
+    dot: None,
+    groups: None,
+    added: None,
+    unsupported: false,
+    null_rows: NullFilter::Off,
+    hidden: &[],
+    summary: false,
+    formats: &[],
+    index_base: IndexBase::Zero,
+};
+
+#[test]
+fn snapshot_base_view() {
+    let empty = dense_batch(vec![vec![], vec![]]);
+    assert_snapshot("base_empty", &render_batch_to_string(&empty, &SMALL));
+
+    let single = dense_batch(vec![vec![Some(1.0)], vec![Some(-2.0)], vec![Some(0.25)]]);
+    assert_snapshot("base_single_row", &render_batch_to_string(&single, &SMALL));
+
+    let nulls = null_heavy_dense();
+    assert_snapshot("base_null_heavy", &render_batch_to_string(&nulls, &SMALL));
+}
+
+#[test]
+fn snapshot_empty_placeholder() {
+    // No columns at all.
+    let nothing = RecordBatch::new_empty(Arc::new(Schema::empty()));
+    assert_snapshot(
+        "empty_no_columns",
+        &render_batch_to_string(&nothing, &SMALL),
+    );
+
+    // Rows, but only a date column and metadata: no feature columns.
+    let schema = Schema::new(vec![
+        Field::new("day", DataType::Date32, true),
+        Field::new("name_id", DataType::Utf8, true),
+    ]);
+    let days = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(arrow_array::Date32Array::from(vec![Some(0), None])) as ArrayRef,
+            Arc::new(StringArray::from(vec![Some("days"), None])) as ArrayRef,
+        ],
+    )
+    .unwrap();
+    assert_snapshot("empty_no_features", &render_batch_to_string(&days, &SMALL));
+
+    // A schema too long for the screen is cut with a count.
+    let wide = dense_batch(vec![vec![]; 30]);
+    let text = render_batch_to_string(&wide, &SMALL);
+    assert!(text.contains("schema (30 columns)"), "{text}");
+    assert!(text.contains("more"), "{text}");
+}
+
+#[test]
+fn snapshot_dense_row_major_view() {
+    // Row-major `vector` datasets are expanded into col_* columns before
+    // rendering, exactly as cmd_display/cmd_head do.
+    let raw = dense_rows_to_batch(&[vec![1.0, 0.5, -3.0], vec![0.0, 2.0, 4.25]]).unwrap();
+    assert!(matches!(
+        detect_lance_layout(&raw),
+        LanceLayout::DenseRowMajor
+    ));
+    let batch = normalize_for_display(&raw).unwrap();
+    assert_snapshot("dense_row_major", &render_batch_to_string(&batch, &SMALL));
+}
+
+#[test]
+fn snapshot_transposed_view() {
+    let opts = RenderOptions {
+        transposed: true,
+        ..SMALL
+    };
+
+    let empty = dense_batch(vec![vec![], vec![]]);
+    assert_snapshot("transposed_empty", &render_batch_to_string(&empty, &opts));
+
+    let single = dense_batch(vec![vec![Some(1.0)], vec![Some(-2.0)], vec![Some(0.25)]]);
+    assert_snapshot(
+        "transposed_single_row",
+        &render_batch_to_string(&single, &opts),
+    );
+
+    let nulls = null_heavy_dense();
+    assert_snapshot(
+        "transposed_null_heavy",
+        &render_batch_to_string(&nulls, &opts),
+    );
+}
+
+#[test]
+fn snapshot_1d_view() {
+    let empty = vector_batch(vec![]);
+    assert_snapshot("1d_empty", &render_batch_to_string(&empty, &SMALL));
+
+    let single = vector_batch(vec![Some(3.5)]);
+    assert_snapshot("1d_single_row", &render_batch_to_string(&single, &SMALL));
+
+    let nulls = vector_batch(vec![None, Some(1.0), None, Some(2.0), None, None]);
+    assert_snapshot("1d_null_heavy", &render_batch_to_string(&nulls, &SMALL));
+}
+
+#[test]
+fn snapshot_coo_view() {
+    let empty = csr_to_coo_batch(&TriMat::<f64>::new((4, 4)).to_csr()).unwrap();
+    assert_snapshot("coo_empty", &render_batch_to_string(&empty, &SMALL));
+
+    let mut tri = TriMat::<f64>::new((4, 4));
+    tri.add_triplet(1, 2, 0.75);
+    let single = csr_to_coo_batch(&tri.to_csr()).unwrap();
+    assert_snapshot("coo_single_row", &render_batch_to_string(&single, &SMALL));
+
+    let nulls = coo_batch_with_nulls();
+    assert_snapshot("coo_null_heavy", &render_batch_to_string(&nulls, &SMALL));
+}
+
+#[test]
+fn reordered_and_extended_coo_schemas_use_the_coo_view() {
+    use arrow_array::Int32Array;
+
+    let mut tri = TriMat::<f64>::new((4, 4));
+    tri.add_triplet(0, 3, 1.5);
+    tri.add_triplet(1, 2, 0.75);
+    tri.add_triplet(3, 0, -2.0);
+    let canonical = csr_to_coo_batch(&tri.to_csr()).unwrap();
+
+    // value, col, row with 64-bit / signed indices and an extra column
+    let schema = Schema::new(vec![
+        Field::new("value", DataType::Float64, false),
+        Field::new("col", DataType::Int32, false),
+        Field::new("weight", DataType::Utf8, false),
+        Field::new("row", DataType::UInt64, false),
+    ])
+    .with_metadata(canonical.schema().metadata().clone());
+    let shuffled = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(vec![1.5, 0.75, -2.0])),
+            Arc::new(Int32Array::from(vec![3, 2, 0])),
+            Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            Arc::new(UInt64Array::from(vec![0, 1, 3])),
+        ],
+    )
+    .unwrap();
+    assert_eq!(detect_lance_layout(&shuffled), LanceLayout::SparseCoo);
+
+    let normalized = normalize_for_display(&shuffled).unwrap();
+    assert_eq!(normalized.columns()[..3], canonical.columns()[..]);
+    assert_eq!(normalized.schema().field(3).name(), "weight");
+    assert_eq!(
+        render_batch_to_string(&normalized, &SMALL),
+        render_batch_to_string(&canonical, &SMALL)
+    );
+
+    // Indices that do not fit in UInt32 are an error, not a wrap-around.
+    let negative = RecordBatch::try_new(
+        Arc::new(Schema::new(vec![
+            Field::new("row", DataType::Int32, false),
+            Field::new("col", DataType::Int32, false),
+            Field::new("value", DataType::Float64, false),
+        ])),
+        vec![
+            Arc::new(Int32Array::from(vec![-1])),
+            Arc::new(Int32Array::from(vec![0])),
+            Arc::new(Float64Array::from(vec![1.0])),
+        ],
+    )
+    .unwrap();
+    assert!(normalize_for_display(&negative).is_err());
+}
+
+#[test]
+fn render_respects_requested_size() {
+    let batch = dense_batch(vec![vec![Some(1.0)], vec![Some(2.0)]]);
+    let opts = RenderOptions {
+        width: 40,
+        height: 13,
+        ..RenderOptions::default()
+    };
+    let text = render_batch_to_string(&batch, &opts);
+    assert!(text.contains("Lance Data"), "{text}");
+    assert_eq!(text.lines().count(), 13);
+    assert!(text.lines().all(|l| l.chars().count() <= 40));
+}
+
+#[test]
+fn tiny_areas_render_placeholder_without_panicking() {
+    let mut tri = TriMat::<f64>::new((4, 4));
+    tri.add_triplet(1, 2, 0.75);
+    let coo = csr_to_coo_batch(&tri.to_csr()).unwrap();
+    let views = [
+        ("base", null_heavy_dense(), false),
+        ("transposed", null_heavy_dense(), true),
+        ("1d", vector_batch(vec![Some(1.0), Some(2.0), None]), false),
+        ("coo", coo, false),
+    ];
+
+    for (name, batch, transposed) in &views {
+        for (width, height) in [(1, 1), (10, 3), (20, 5)] {
+            let opts = RenderOptions {
+                width,
+                height,
+                transposed: *transposed,
+                ..RenderOptions::default()
+            };
+            let text = render_batch_to_string(batch, &opts);
+            assert_eq!(text.split('\n').count(), height as usize, "{name}");
+            let flat = text.lines().collect::<Vec<_>>().join(" ");
+            let expected = "window too small (need at least";
+            let shown = &expected[..flat.len().min(expected.len())];
+            assert!(
+                flat.starts_with(shown),
+                "{name} at {width}×{height}: {text:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn minimum_areas_render_the_real_view() {
+    let cases = [
+        (null_heavy_dense(), false, 40, 13, "Lance Data"),
+        (null_heavy_dense(), true, 40, 12, "Lance Data Transposed"),
+        (
+            vector_batch(vec![Some(1.0), Some(2.0)]),
+            false,
+            40,
+            12,
+            "Distribution",
+        ),
+        (coo_batch_with_nulls(), false, 40, 14, "Triples"),
+    ];
+    for (batch, transposed, width, height, marker) in cases {
+        let opts = RenderOptions {
+            width,
+            height,
+            transposed,
+            ..RenderOptions::default()
+        };
+        let text = render_batch_to_string(&batch, &opts);
+        assert!(
+            text.contains(marker),
+            "{marker} missing at {width}×{height}:\n{text}"
+        );
+
+        let smaller = RenderOptions {
+            height: height - 1,
+            ..opts
+        };
+        let text = render_batch_to_string(&batch, &smaller);
+        assert!(text.contains(&format!("{width}×{height}")), "{text}");
+    }
+}
+
+#[test]
+fn scroll_labels_follow_vim_conventions() {
+    let label = |start, len, total| ScrollWindow { start, len, total }.label();
+    assert_eq!(label(0, 10, 5), "ALL");
+    assert_eq!(label(0, 10, 10), "ALL");
+    assert_eq!(label(0, 10, 200), "TOP");
+    assert_eq!(label(190, 10, 200), "BOT");
+    assert_eq!(label(95, 10, 200), "50%");
+    assert_eq!(label(1, 10, 200), "0%");
+}
+
+#[test]
+fn snapshot_scrolled_views_show_scrollbars() {
+    let tall = dense_batch(
+        (0..12)
+            .map(|c| (0..60).map(|r| Some((r * 12 + c) as f64)).collect())
+            .collect(),
+    );
+    let opts = RenderOptions {
+        row_start: 25,
+        col_offset: 2,
+        ..SMALL
+    };
+    let text = render_batch_to_string(&tall, &opts);
+    assert!(text.contains(" 45% | 60 rows"), "{text}");
+    assert_snapshot("base_scrolled", &text);
+
+    let transposed = RenderOptions {
+        transposed: true,
+        row_start: 4,
+        col_offset: 10,
+        ..SMALL
+    };
+    assert_snapshot(
+        "transposed_scrolled",
+        &render_batch_to_string(&tall, &transposed),
+    );
+
+    let long = vector_batch((0..60).map(|i| Some(i as f64)).collect());
+    let at_end = RenderOptions {
+        row_start: 59,
+        ..SMALL
+    };
+    let text = render_batch_to_string(&long, &at_end);
+    assert!(text.contains(" BOT | 60 rows"), "{text}");
+    assert_snapshot("1d_scrolled", &text);
+}
+
+// Two numeric data columns plus a metadata row appended at the end, as
+// produced by concatenating a single-row metadata batch onto the data.
+fn batch_with_trailing_metadata() -> RecordBatch {
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Float64, true),
+        Field::new("y", DataType::Float64, true),
+        Field::new("name_id", DataType::Utf8, true),
+        Field::new("n_rows", DataType::UInt64, true),
+        Field::new("n_cols", DataType::UInt64, true),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(vec![Some(1.0), Some(3.0), None])) as ArrayRef,
+            Arc::new(Float64Array::from(vec![Some(2.0), Some(5.0), None])) as ArrayRef,
+            Arc::new(StringArray::from(vec![None, None, Some("demo")])) as ArrayRef,
+            Arc::new(UInt64Array::from(vec![None, None, Some(2)])) as ArrayRef,
+            Arc::new(UInt64Array::from(vec![None, None, Some(2)])) as ArrayRef,
+        ],
+    )
+    .unwrap()
+}
+
+#[test]
+fn metadata_columns_are_read_from_any_row_and_hidden_from_table() {
+    let batch = batch_with_trailing_metadata();
+    assert_eq!(collect_feature_cols(&batch).unwrap(), vec![0, 1]);
+
+    let text = render_batch_to_string(&batch, &SMALL);
+    assert!(
+        text.contains("name_id: demo    n_rows: 2    n_cols: 2"),
+        "{text}"
+    );
+    let header = text
+        .lines()
+        .find(|l| l.contains("Row "))
+        .expect("table header");
+    assert!(header.contains('x') && header.contains('y'), "{header}");
+    assert!(
+        !header.contains("n_rows") && !header.contains("name_id"),
+        "{header}"
+    );
+    assert!(text.contains("3 rows × 2 total cols"), "{text}");
+    // Per-row stats only see the data columns.
+    assert!(text.contains("4.0000"), "avg of row 1 should be 4: {text}");
+
+    let transposed = RenderOptions {
+        transposed: true,
+        ..SMALL
+    };
+    let text = render_batch_to_string(&batch, &transposed);
+    assert!(text.contains("name_id: demo"), "{text}");
+    assert!(!text.contains("│n_rows"), "{text}");
+}
+
+#[test]
+fn declared_dims_that_disagree_with_the_data_are_flagged() {
+    let batch = batch_with_trailing_metadata();
+    // Declare 5 rows where the data (without the metadata row) has 2.
+    let mut columns = batch.columns().to_vec();
+    columns[3] = Arc::new(UInt64Array::from(vec![None, None, Some(5)])) as ArrayRef;
+    let filtered = RecordBatch::try_new(batch.schema(), columns).unwrap();
+
+    let text = render_batch_to_string(&filtered, &SMALL);
+    assert!(
+        text.contains("n_rows: 5    n_cols: 2 (mismatch: actual 2×2)"),
+        "{text}"
+    );
+    let text = render_batch_to_string(&batch, &SMALL);
+    assert!(!text.contains("mismatch"), "{text}");
+}
+
+#[test]
+fn metadata_free_batches_show_shape() {
+    let batch = null_heavy_dense();
+    assert_eq!(collect_feature_cols(&batch).unwrap(), vec![0, 1, 2]);
+    let text = render_batch_to_string(&batch, &SMALL);
+    assert!(text.contains("rows: 4    cols: 3"), "{text}");
+    assert!(!text.contains("name_id"), "{text}");
+}
+
+#[test]
+fn feature_names_relabel_headers_and_transposed_rows() {
+    let raw = dense_rows_to_batch(&[vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]).unwrap();
+    let batch = normalize_for_display(&raw).unwrap();
+    let names = ["age".to_string(), "height".to_string()];
+    let named = apply_names(&batch, &names, 0, 3).unwrap();
+
+    let text = render_batch_to_string(&named, &SMALL);
+    assert!(text.contains("age"), "{text}");
+    assert!(text.contains("height"), "{text}");
+    assert!(text.contains("col_2"), "{text}");
+    // Only the column strip still names the renamed focused column.
+    assert!(text.contains(" age  column col_0 · Float64"), "{text}");
+    let table = text.split_once("Lance Data").unwrap().1;
+    assert!(!table.contains("col_0"), "{text}");
+
+    let text = render_batch_to_string(
+        &named,
+        &RenderOptions {
+            transposed: true,
+            ..SMALL
+        },
+    );
+    assert!(text.contains("height"), "{text}");
+}
+
+#[test]
+fn dense_expansion_keeps_schema_metadata() {
+    let raw = dense_rows_to_batch(&[vec![1.0, 2.0]]).unwrap();
+    let schema = raw.schema().as_ref().clone().with_metadata(HashMap::from([(
+        "source".to_string(),
+        "unit-test".to_string(),
+    )]));
+    let raw = raw.with_schema(Arc::new(schema)).unwrap();
+
+    let expanded = normalize_for_display(&raw).unwrap();
+    assert_eq!(
+        expanded
+            .schema()
+            .metadata()
+            .get("source")
+            .map(String::as_str),
+        Some("unit-test")
+    );
+}
+
+#[test]
+fn null_labels_round_down_but_never_hide_nulls() {
+    let mut values: Vec<Option<f64>> = vec![Some(0.0); 199];
+    values.push(None);
+    let label = |array: &ArrayRef| null_label(array.null_count(), array.len());
+    let sparse: ArrayRef = Arc::new(Float64Array::from(values));
+    assert_eq!(label(&sparse).as_deref(), Some("<1% null"));
+
+    let half: ArrayRef = Arc::new(Float64Array::from(vec![None, Some(1.0)]));
+    assert_eq!(label(&half).as_deref(), Some("50% null"));
+
+    let dense: ArrayRef = Arc::new(Float64Array::from(vec![1.0, 2.0]));
+    assert_eq!(label(&dense), None);
+}
+
+#[test]
+fn sampled_rows_show_source_ids() {
+    let raw = dense_rows_to_batch(&[vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]).unwrap();
+    let sampled = attach_row_ids(&raw, vec![7, 1234, 100_000]).unwrap();
+    assert!(matches!(
+        detect_lance_layout(&sampled),
+        LanceLayout::DenseRowMajor
+    ));
+
+    let batch = normalize_for_display(&sampled).unwrap();
+    assert_eq!(collect_feature_cols(&batch).unwrap(), vec![0, 1]);
+
+    let text = render_batch_to_string(&batch, &SMALL);
+    for id in ["#7", "#1234", "#100000"] {
+        assert!(text.contains(id), "{id} missing:\n{text}");
+    }
+    assert!(!text.contains("_rowid"), "{text}");
+    assert!(text.contains("2 total cols"), "{text}");
+
+    let transposed = RenderOptions {
+        transposed: true,
+        ..SMALL
+    };
+    let text = render_batch_to_string(&batch, &transposed);
+    assert!(text.contains("#1234") && !text.contains("R1 "), "{text}");
+
+    // 1D vectors keep their layout with ids attached.
+    let norms = attach_row_ids(&vector_batch(vec![Some(0.5), Some(1.5)]), vec![3, 9]).unwrap();
+    assert!(matches!(detect_lance_layout(&norms), LanceLayout::Vector1D));
+    let text = render_batch_to_string(&norms, &SMALL);
+    assert!(text.contains("#9"), "{text}");
+}
+
+#[test]
+fn offset_windows_are_titled_in_source_rows() {
+    let window = attach_row_ids(
+        &dense_batch(vec![
+            vec![Some(1.0), Some(2.0), Some(3.0)],
+            vec![Some(4.0), Some(5.0), Some(6.0)],
+        ]),
+        vec![100_000, 100_001, 100_002],
+    )
+    .unwrap();
+    let source = Some(SourceWindow {
+        row_offset: 100_000,
+        total_rows: 250_000,
+        ..SourceWindow::whole(250_000)
+    });
+    let render = |index_base, transposed| {
+        render_batch_to_string(
+            &window,
+            &RenderOptions {
+                source,
+                index_base,
+                transposed,
+                ..SMALL
+            },
+        )
+    };
+    let text = render(IndexBase::Zero, false);
+    assert!(text.contains("rows 100000–100002 of 250000"), "{text}");
+    assert!(text.contains("#100001"), "{text}");
+    let text = render(IndexBase::Zero, true);
+    assert!(
+        text.contains("sample rows 100000–100002 of 250000"),
+        "{text}"
+    );
+
+    // Counting from 1 shifts titles and labels alike.
+    let text = render(IndexBase::One, false);
+    assert!(text.contains("rows 100001–100003 of 250000"), "{text}");
+    assert!(text.contains("feature cols 1–"), "{text}");
+    assert!(text.contains("#100002"), "{text}");
+    let text = render(IndexBase::One, true);
+    assert!(
+        text.contains("sample rows 100001–100003 of 250000"),
+        "{text}"
+    );
+    assert!(text.contains("features 1–"), "{text}");
+
+    // Without the Row column the titles count from 0 and no label is drawn.
+    let text = render(IndexBase::NoRowColumn, false);
+    assert!(text.contains("rows 100000–100002 of 250000"), "{text}");
+    assert!(
+        !text.contains("#100001") && !text.contains(" Row "),
+        "{text}"
+    );
+}
+
+#[test]
+fn pinned_column_stays_leftmost_while_scrolling() {
+    let wide = dense_batch(
+        (0..12)
+            .map(|c| (0..3).map(|r| Some((r * 12 + c) as f64)).collect())
+            .collect(),
+    );
+    let opts = RenderOptions {
+        width: 140,
+        col_offset: 6,
+        pinned_col: Some(1),
+        ..SMALL
+    };
+    let text = render_batch_to_string(&wide, &opts);
+    assert!(text.contains("pinned col_1"), "{text}");
+    let header = text.lines().find(|l| l.contains("col_6")).unwrap();
+    let pinned = header.find("col_1 ").unwrap();
+    assert!(pinned < header.find("col_6").unwrap(), "{header}");
+    assert!(header.contains("col_11"), "{header}");
+    assert!(!header.contains("col_0 "), "{header}");
+}
+
+#[test]
+fn column_strip_names_the_leftmost_column_in_full() {
+    let names: Vec<String> = (120..130).map(|i| format!("embedding_col_{i}")).collect();
+    let batch = RecordBatch::try_new(
+        Arc::new(Schema::new(
+            names
+                .iter()
+                .map(|n| Field::new(n, DataType::Float32, false))
+                .collect::<Vec<_>>(),
+        )),
+        (0..10)
+            .map(|c| Arc::new(arrow_array::Float32Array::from(vec![c as f32; 2])) as ArrayRef)
+            .collect(),
+    )
+    .unwrap();
+    let strip = |opts: &RenderOptions| {
+        let text = render_batch_to_string(&batch, opts);
+        text.lines().nth(3).unwrap().to_string()
+    };
+
+    // Headers truncate the long names, the strip does not.
+    let opts = RenderOptions {
+        col_offset: 7,
+        visible_cols: 2,
+        ..SMALL
+    };
+    assert_eq!(
+        strip(&opts).trim_end(),
+        " embedding_col_127  Float32 · not null · feature 7 of 10 · schema index 7"
+    );
+    // A pinned column is leftmost, so it is the one described.
+    let pinned = RenderOptions {
+        pinned_col: Some(1),
+        ..opts
+    };
+    assert!(strip(&pinned).starts_with(" embedding_col_121  Float32"));
+
+    // Type tags only join headers that have room for them.
+    assert_eq!(
+        with_dtype_tag("col_3", &DataType::Float64, 12),
+        "col_3 ·f64"
+    );
+    assert_eq!(with_dtype_tag("col_3", &DataType::Float64, 9), "col_3");
+    assert_eq!(
+        with_dtype_tag("embedding_col_127", &DataType::Float32, 12),
+        "embedding_col_127"
+    );
+    assert_eq!(with_dtype_tag("when", &DataType::Date32, 12), "when");
+}
+
+fn list_batch(rows: Vec<Option<Vec<f64>>>) -> RecordBatch {
+    use arrow::datatypes::Float64Type;
+    use arrow_array::{Array as _, ListArray};
+
+    let list = ListArray::from_iter_primitive::<Float64Type, _, _>(
+        rows.into_iter()
+            .map(|r| r.map(|values| values.into_iter().map(Some).collect::<Vec<_>>())),
+    );
+    let schema = Schema::new(vec![Field::new("vector", list.data_type().clone(), true)]);
+    RecordBatch::try_new(Arc::new(schema), vec![Arc::new(list) as ArrayRef]).unwrap()
+}
+
+#[test]
+fn snapshot_ragged_view() {
+    let ragged = list_batch(vec![
+        Some(vec![1.0, 2.0]),
+        Some((0..30).map(|i| i as f64 / 4.0).collect()),
+        None,
+        Some(vec![]),
+    ]);
+    assert!(matches!(
+        detect_lance_layout(&ragged),
+        LanceLayout::RaggedList
+    ));
+    // Mixed lengths stay ragged.
+    let batch = normalize_for_display(&ragged).unwrap();
+    assert_eq!(batch.num_columns(), 1);
+
+    let text = render_batch_to_string(&batch, &SMALL);
+    assert!(text.contains("0.7500, 1.0000, …"), "{text}");
+    assert!(text.contains("mean:   10.67"), "{text}");
+    assert_snapshot("ragged_rows", &text);
+}
+
+#[test]
+fn uniform_lists_expand_like_dense_vectors() {
+    let uniform = list_batch(vec![Some(vec![1.0, 2.0, 3.0]), Some(vec![4.0, 5.0, 6.0])]);
+    let batch = normalize_for_display(&uniform).unwrap();
+    assert_eq!(collect_feature_cols(&batch).unwrap(), vec![0, 1, 2]);
+    let col_2 = batch
+        .column_by_name("col_2")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    assert_eq!(col_2.values(), &[3.0, 6.0]);
+}
+
+/// `(row_indices, col_indices, values)` of one COO record.
+type CooRecord = (Vec<u32>, Vec<u32>, Vec<f64>);
+
+/// Per-record COO structs; `None` is a null record.
+fn sparse_records_batch(records: Vec<Option<CooRecord>>) -> RecordBatch {
+    use arrow::datatypes::{Fields, UInt32Type};
+    use arrow_array::types::Float64Type;
+    use arrow_array::{Array as _, ListArray, StructArray};
+
+    let valid: Vec<bool> = records.iter().map(Option::is_some).collect();
+    let (rows, cols, values): (Vec<_>, Vec<_>, Vec<_>) = records
+        .into_iter()
+        .map(|r| {
+            let (rows, cols, values) = r.unwrap_or_default();
+            (
+                Some(rows.into_iter().map(Some).collect::<Vec<_>>()),
+                Some(cols.into_iter().map(Some).collect::<Vec<_>>()),
+                Some(values.into_iter().map(Some).collect::<Vec<_>>()),
+            )
+        })
+        .collect();
+    let rows = ListArray::from_iter_primitive::<UInt32Type, _, _>(rows);
+    let cols = ListArray::from_iter_primitive::<UInt32Type, _, _>(cols);
+    let values = ListArray::from_iter_primitive::<Float64Type, _, _>(values);
+    let fields = Fields::from(vec![
+        Field::new("row_indices", rows.data_type().clone(), true),
+        Field::new("col_indices", cols.data_type().clone(), true),
+        Field::new("values", values.data_type().clone(), true),
+    ]);
+    let struct_array = StructArray::try_new(
+        fields.clone(),
+        vec![Arc::new(rows) as ArrayRef, Arc::new(cols), Arc::new(values)],
+        Some(valid.into()),
+    )
+    .unwrap();
+    let schema = Schema::new(vec![Field::new("record", DataType::Struct(fields), true)])
+        .with_metadata(HashMap::from([
+            ("rows".to_string(), "4".to_string()),
+            ("cols".to_string(), "6".to_string()),
+        ]));
+    RecordBatch::try_new(Arc::new(schema), vec![Arc::new(struct_array) as ArrayRef]).unwrap()
+}
+
+#[test]
+fn snapshot_sparse_records_view() {
+    let batch = sparse_records_batch(vec![
+        Some((vec![0, 0, 1], vec![1, 5, 2], vec![0.5, -1.0, 2.5])),
+        Some((vec![3], vec![3], vec![7.0])),
+        None,
+        Some((vec![2, 2], vec![0], vec![1.0])),
+    ]);
+    assert!(matches!(
+        detect_lance_layout(&batch),
+        LanceLayout::SparsePerRow
+    ));
+    assert_eq!(normalize_for_display(&batch).unwrap().num_columns(), 1);
+
+    let text = render_batch_to_string(&batch, &SMALL);
+    assert!(text.contains("Lance Sparse Records (COO"), "{text}");
+    assert!(text.contains("0–1"), "{text}");
+    assert!(text.contains("NULL"), "{text}");
+    assert!(text.contains("!      invalid"), "{text}");
+    assert_snapshot("sparse_records", &text);
+}
+
+#[test]
+fn sparse_records_flatten_to_the_same_graph_as_coo() {
+    use crate::functions::functions::sparse_per_row_to_coo;
+    use crate::functions::sparse_viz::ConnectivityGraph;
+
+    let batch = sparse_records_batch(vec![
+        Some((vec![0, 0], vec![0, 1], vec![1.0, 2.0])),
+        None,
+        Some((vec![1, 1, 2], vec![1, 2, 2], vec![3.0, 4.0, 5.0])),
+    ]);
+    let coo = sparse_per_row_to_coo(&batch).unwrap();
+    assert!(matches!(detect_lance_layout(&coo), LanceLayout::SparseCoo));
+    let rows = coo
+        .column(0)
+        .as_any()
+        .downcast_ref::<UInt32Array>()
+        .unwrap();
+    assert_eq!(rows.values(), &[0, 0, 1, 1, 2]);
+    assert_eq!(coo.schema().metadata()["rows"], "4");
+
+    let from_records = ConnectivityGraph::from_batch(&batch).unwrap();
+    let from_coo = ConnectivityGraph::from_coo_batch(&coo).unwrap();
+    assert_eq!(from_records.nodes.len(), from_coo.nodes.len());
+    assert_eq!(from_records.edges.len(), from_coo.edges.len());
+}
+
+#[test]
+fn boolean_and_string_columns_are_shown_but_not_averaged() {
+    use arrow_array::BooleanArray;
+
+    let schema = Schema::new(vec![
+        Field::new("score", DataType::Float64, false),
+        Field::new("flag", DataType::Boolean, false),
+        Field::new("label", DataType::Utf8, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(vec![2.0, 4.0])) as ArrayRef,
+            Arc::new(BooleanArray::from(vec![true, false])),
+            Arc::new(StringArray::from(vec!["Ångström-unit", "b"])),
+        ],
+    )
+    .unwrap();
+    assert_eq!(collect_feature_cols(&batch).unwrap(), vec![0, 1, 2]);
+
+    let text = render_batch_to_string(&batch, &SMALL);
+    assert!(text.contains("true"), "{text}");
+    // Multi-byte strings are cut on character boundaries.
+    assert!(text.contains("Ångström-…"), "{text}");
+    // The only numeric value in each row is its own mean.
+    assert!(text.contains("2.0000"), "{text}");
+    assert!(text.contains("4.0000"), "{text}");
+}
+
+#[test]
+fn snapshot_mixed_width_strings_keep_columns_aligned() {
+    use crate::functions::format::truncate_to_width;
+    use unicode_width::UnicodeWidthStr;
+
+    // Cut by display width: CJK and emoji take two cells each.
+    assert_eq!(truncate_to_width("数据科学家们", 10), "数据科学…");
+    assert_eq!(truncate_to_width("🎉🎉🎉🎉🎉🎉", 10), "🎉🎉🎉🎉…");
+    assert_eq!(truncate_to_width("数据abc", 7), "数据abc");
+    assert_eq!(truncate_to_width("数据abc", 6), "数据a…");
+
+    let schema = Schema::new(vec![
+        Field::new("score", DataType::Float64, false),
+        Field::new("label", DataType::Utf8, false),
+        Field::new("count", DataType::UInt64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(vec![1.5, -20.25, 300.0])) as ArrayRef,
+            Arc::new(StringArray::from(vec![
+                "数据科学家们",
+                "🎉🎉🎉🎉🎉🎉",
+                "plain",
+            ])),
+            Arc::new(UInt64Array::from(vec![7, 1234, 42])),
+        ],
+    )
+    .unwrap();
+    let text = render_batch_to_string(&batch, &SMALL);
+    assert!(text.contains("数据科学…"), "{text}");
+    // Every row spans the same number of cells, and the decimal points of
+    // the right-aligned score column line up.
+    let rows: Vec<&str> = text.lines().filter(|l| l.contains('│')).collect();
+    assert!(
+        rows.iter().all(|l| l.width() == SMALL.width as usize),
+        "{text}"
+    );
+    let points: Vec<usize> = ["1.5000", "-20.2500", "300.0000"]
+        .iter()
+        .map(|v| {
+            let line = text.lines().find(|l| l.contains(v)).unwrap();
+            line[..line.find(v).unwrap()].width() + v.find('.').unwrap()
+        })
+        .collect();
+    assert!(
+        points.windows(2).all(|w| w[0] == w[1]),
+        "{points:?}\n{text}"
+    );
+    assert_snapshot("mixed_width_strings", &text);
+}
+
+#[test]
+fn negative_zero_prints_unsigned_and_stats_use_one_fewer_digit() {
+    use crate::display::display::mean_std;
+    use crate::functions::format::format_float;
+
+    assert_eq!(format_float(-0.0, 8), "0.00000000");
+    // A negative subnormal rounds to zero as well.
+    assert_eq!(format_float(-1e-320, 8), "0.00000000");
+    assert_eq!(format_float(-4e-9, 8), "0.00000000");
+    assert_eq!(format_float(-6e-9, 8), "-0.00000001");
+
+    // Near-constant rows keep their spread.
+    let step = 2f64.powi(-30);
+    let row: Vec<f64> = (0..4).map(|k| 1.0 + k as f64 * step).collect();
+    let (mean, std) = mean_std(&row).unwrap();
+    assert_eq!(mean, 1.0 + 1.5 * step);
+    assert!((std - step * 1.25f64.sqrt()).abs() < 1e-12, "std {std:e}");
+
+    let batch = dense_batch(vec![vec![Some(-0.0)], vec![Some(-0.0)]]);
+    let text = render_batch_to_string(&batch, &SMALL);
+    assert!(!text.contains("-0.0"), "{text}");
+    assert!(text.contains("0.00000000"), "{text}");
+    // avg/std carry one decimal fewer than the cells.
+    assert!(text.contains(" 0.0000000 "), "{text}");
+}
+
+#[test]
+fn row_aggregates_follow_the_configured_choice() {
+    let batch = dense_batch(vec![
+        vec![Some(3.0), Some(1.0)],
+        vec![Some(0.0), Some(1.0)],
+        vec![Some(-4.0), Some(4.0)],
+    ]);
+    // The header is the first line inside the table's border
+    let header = |text: &str| {
+        let mut lines = text.lines().skip_while(|l| !l.contains("Lance Data"));
+        lines.nth(1).unwrap().to_string()
+    };
+
+    // Hidden: no aggregate columns at all.
+    let hidden = render_batch_to_string(
+        &batch,
+        &RenderOptions {
+            row_stats: &[],
+            ..SMALL
+        },
+    );
+    assert!(!header(&hidden).contains("avg"), "{hidden}");
+
+    let chosen = &[Aggregate::Median, Aggregate::L2, Aggregate::Nnz];
+    let text = render_batch_to_string(
+        &batch,
+        &RenderOptions {
+            row_stats: chosen,
+            ..SMALL
+        },
+    );
+    let labels: Vec<String> = header(&text)
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    assert_eq!(
+        labels[labels.len() - 4..],
+        ["median[skip]", "l2[skip]", "nnz[skip]", "│"]
+    );
+    // Row 0 is (3, 0, -4): median 0, norm 5, two non-zeros.
+    let row0 = text.lines().find(|l| l.starts_with("│     0")).unwrap();
+    assert!(
+        row0.contains("0.0000000    5.0000000            2"),
+        "{text}"
+    );
+
+    // The transposed view shows the same aggregates per feature.
+    let transposed = render_batch_to_string(
+        &batch,
+        &RenderOptions {
+            row_stats: chosen,
+            transposed: true,
+            ..SMALL
+        },
+    );
+    assert!(header(&transposed).contains("median"), "{transposed}");
+    // col_2 is (-4, 4): median 0, norm √32.
+    let col2 = transposed.lines().find(|l| l.contains("col_2")).unwrap();
+    assert!(col2.contains("5.6568542"), "{transposed}");
+}
+
+#[test]
+fn windowed_row_stats_cover_only_the_visible_columns() {
+    // Row 0 is (1, 2, 3, 10); with two visible features only 1 and 2 count.
+    let batch = dense_batch(vec![
+        vec![Some(1.0)],
+        vec![Some(2.0)],
+        vec![Some(3.0)],
+        vec![Some(10.0)],
+    ]);
+    let narrow = RenderOptions {
+        visible_cols: 2,
+        ..SMALL
+    };
+    let all = render_batch_to_string(&batch, &narrow);
+    assert!(all.contains("avg"), "{all}");
+    assert!(all.contains("4.0000000"), "{all}");
+
+    let windowed = RenderOptions {
+        windowed_stats: true,
+        ..narrow
+    };
+    let text = render_batch_to_string(&batch, &windowed);
+    assert!(text.contains("avg*") && text.contains("std*"), "{text}");
+    assert!(text.contains("1.5000000"), "{text}");
+    // Scrolling the window moves the scope with it.
+    let scrolled = render_batch_to_string(
+        &batch,
+        &RenderOptions {
+            col_offset: 2,
+            ..windowed
+        },
+    );
+    assert!(scrolled.contains("6.5000000"), "{scrolled}");
+
+    // Transposed, each feature is aggregated over the visible rows only.
+    let rows = dense_batch(vec![
+        (0..6).map(|r| Some(r as f64)).collect(),
+        (0..6).map(|r| Some(10.0 * r as f64)).collect(),
+    ]);
+    let transposed = RenderOptions {
+        transposed: true,
+        visible_cols: 2,
+        ..SMALL
+    };
+    let text = render_batch_to_string(&rows, &transposed);
+    assert!(text.contains("2.5000000"), "{text}");
+    let text = render_batch_to_string(
+        &rows,
+        &RenderOptions {
+            windowed_stats: true,
+            ..transposed
+        },
+    );
+    assert!(text.contains("avg*"), "{text}");
+    assert!(text.contains("0.5000000"), "{text}");
+}
+
+#[test]
+fn vector_cells_preview_their_values_and_aggregate_over_them() {
+    // Unexpanded, the dense column is one vector per row.
+    let rows = vec![vec![0.125, -0.5, 2.0, 6.375], vec![1.0, 1.0, 1.0, 1.0]];
+    let batch = dense_rows_to_batch(&rows).unwrap();
+    assert_eq!(detect_lance_layout(&batch), LanceLayout::DenseRowMajor);
+    let text = render_batch_to_string(
+        &batch,
+        &RenderOptions {
+            width: 120,
+            ..SMALL
+        },
+    );
+    assert!(text.contains("[0.12, -0.50, …] (4)"), "{text}");
+    // avg over the elements, and the vector counted as its width
+    assert!(text.contains("2.0000000"), "{text}");
+    assert!(text.contains("1.0000000"), "{text}");
+    assert!(text.contains("4 total cols"), "{text}");
+}
+
+#[test]
+fn hidden_columns_leave_the_table_and_the_aggregates() {
+    let batch = dense_batch(vec![
+        vec![Some(1.0)],
+        vec![Some(2.0)],
+        vec![Some(3.0)],
+        vec![Some(10.0)],
+    ]);
+    let hidden = RenderOptions {
+        hidden: &["col_3"],
+        ..SMALL
+    };
+    let text = render_batch_to_string(&batch, &hidden);
+    assert!(!text.contains("col_3"), "{text}");
+    assert!(text.contains("1 hidden"), "{text}");
+    assert!(text.contains("avg†"), "{text}");
+    assert!(text.contains("2.0000000"), "{text}");
+
+    // The window scope does not reach hidden columns either, unmarked.
+    let windowed = RenderOptions {
+        windowed_stats: true,
+        ..hidden
+    };
+    let text = render_batch_to_string(&batch, &windowed);
+    assert!(text.contains("avg*") && !text.contains('†'), "{text}");
+}
+
+#[test]
+fn integer_columns_show_their_display_format() {
+    let batch = RecordBatch::try_from_iter([
+        (
+            "size",
+            Arc::new(UInt64Array::from(vec![1536, 1023])) as ArrayRef,
+        ),
+        (
+            "flags",
+            Arc::new(UInt32Array::from(vec![255, 4096])) as ArrayRef,
+        ),
+        (
+            "score",
+            Arc::new(Float64Array::from(vec![0.5, 0.25])) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+    let raw = render_batch_to_string(&batch, &SMALL);
+    assert!(raw.contains("1536") && raw.contains("4096"), "{raw}");
+
+    let formatted = RenderOptions {
+        formats: &[("size", "bytes"), ("flags", "hex"), ("score", "hex")],
+        ..SMALL
+    };
+    let text = render_batch_to_string(&batch, &formatted);
+    assert!(
+        text.contains("1.5 KiB") && text.contains("1023 B"),
+        "{text}"
+    );
+    assert!(text.contains("0xff") && text.contains("0x1000"), "{text}");
+    // a float column has no format to take
+    assert!(text.contains("0.50000000"), "{text}");
+}
+
+#[test]
+fn transposed_scroll_past_the_end_shows_the_last_feature_and_rows() {
+    // Three features of five rows, scrolled past both ends.
+    let batch = dense_batch(vec![
+        (0..5).map(|r| Some(r as f64)).collect(),
+        (0..5).map(|r| Some(10.0 + r as f64)).collect(),
+        (0..5).map(|r| Some(20.0 + r as f64)).collect(),
+    ]);
+    let text = render_batch_to_string(
+        &batch,
+        &RenderOptions {
+            transposed: true,
+            row_start: 7,
+            col_offset: 9,
+            visible_cols: 2,
+            ..SMALL
+        },
+    );
+    assert!(!text.contains("OOB"), "{text}");
+    assert!(text.contains("col_2"), "{text}");
+    assert!(!text.contains("col_1"), "{text}");
+    // The window holds the last two rows.
+    assert!(text.contains("23.00000000"), "{text}");
+    assert!(text.contains("24.00000000"), "{text}");
+}
+
+/// Screen text of one frame of `data`, drawn with the viewer's `stats` so
+/// their cache carries over between frames as it does while scrolling.
+fn source_frame(data: &BatchSource, opts: &RenderOptions, stats: &RowStats) -> String {
+    let empty = data.empty_batch();
+    let layout = detect_lance_layout(&empty);
+    let features = collect_feature_cols(&empty).unwrap();
+    let offsets = HorizontalOffsets {
+        col_offset: opts.col_offset,
+        row_offset: opts.col_offset,
+        ..HorizontalOffsets::default()
+    };
+    let mut terminal = Terminal::new(TestBackend::new(opts.width, opts.height)).unwrap();
+    terminal
+        .draw(|f| {
+            render_frame(
+                f,
+                data,
+                &layout,
+                &features,
+                offsets,
+                opts.visible_cols,
+                opts.row_start,
+                opts.transposed,
+                SourceWindow::whole(data.num_rows()),
+                None,
+                None,
+                None,
+                &VectorPanels::default(),
+                stats,
+                &Bookmarks::default(),
+                None,
+                &[],
+                None,
+                None,
+                None,
+                0,
+                None,
+                &ValueFormatter::default(),
+            );
+        })
+        .unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer.content.iter().map(|c| c.symbol()).collect()
+}
+
+#[test]
+fn three_batches_scroll_like_one() {
+    // 14 rows of three features split 5 + 6 + 3, with nulls on both sides
+    // of the seams.
+    let column = |scale: f64| -> Vec<Option<f64>> {
+        (0..14)
+            .map(|r| (r % 4 != 1).then_some(scale * r as f64 - 3.0))
+            .collect()
+    };
+    let whole = dense_batch(vec![column(1.0), column(-2.5), column(0.25)]);
+    let parts = [0..5, 5..11, 11..14].map(|rows| whole.slice(rows.start, rows.len()));
+    let split = BatchSource::new(whole.schema(), parts.to_vec()).unwrap();
+    let whole = BatchSource::from(whole);
+    assert_eq!(split.batches().len(), 3);
+
+    for transposed in [false, true] {
+        for windowed in [false, true] {
+            // One cache per viewer for the whole scroll, as in the event loop
+            let stats = |windowed| RowStats::new(DEFAULT_AGGREGATES, true).windowed(windowed);
+            let (split_stats, whole_stats) = (stats(windowed), stats(windowed));
+            for start in 0..14 {
+                let opts = RenderOptions {
+                    transposed,
+                    visible_cols: 2,
+                    row_start: if transposed { 0 } else { start },
+                    col_offset: if transposed { start } else { 0 },
+                    ..SMALL
+                };
+                assert_eq!(
+                    source_frame(&split, &opts, &split_stats),
+                    source_frame(&whole, &opts, &whole_stats),
+                    "transposed {transposed}, windowed {windowed}, start {start}"
+                );
+            }
+        }
+    }
+
+    // Rows 8 and 11, from the second and third batches, share one window.
+    let text = render_source_to_string(
+        &split,
+        &RenderOptions {
+            row_start: 8,
+            ..SMALL
+        },
+    );
+    assert!(text.contains("rows 8–11 of 14"), "{text}");
+    assert!(text.contains(" 5.00000000"), "{text}");
+    assert!(text.contains(" 8.00000000"), "{text}");
+
+    // A 1D vector scrolls across the seams too.
+    let vector = vector_batch(column(1.0));
+    let parts = [0..4, 4..9, 9..14].map(|rows| vector.slice(rows.start, rows.len()));
+    let split = BatchSource::new(vector.schema(), parts.to_vec()).unwrap();
+    for start in 0..14 {
+        let opts = RenderOptions {
+            row_start: start,
+            ..SMALL
+        };
+        assert_eq!(
+            render_source_to_string(&split, &opts),
+            render_batch_to_string(&vector, &opts),
+            "1D, start {start}"
+        );
+    }
+}
+
+#[test]
+fn bookmarked_rows_are_marked_in_the_row_column() {
+    let batch = dense_batch(vec![
+        vec![Some(1.0), Some(2.0)],
+        vec![Some(3.0), Some(4.0)],
+        vec![Some(5.0), Some(6.0)],
+    ]);
+    let opts = RenderOptions {
+        bookmarks: &[1],
+        ..SMALL
+    };
+    let text = render_batch_to_string(&batch, &opts);
+    let marked: Vec<&str> = text.lines().filter(|l| l.contains('◆')).collect();
+    assert_eq!(marked.len(), 1, "{text}");
+    assert!(marked[0].starts_with("│◆    1   2.00000000"), "{text}");
+
+    // F×N marks the sample's column header instead.
+    let transposed = render_batch_to_string(
+        &batch,
+        &RenderOptions {
+            transposed: true,
+            ..opts
+        },
+    );
+    let header = transposed.lines().find(|l| l.contains("Feature")).unwrap();
+    assert!(
+        header.contains(" R0 ") && header.contains("◆R1"),
+        "{header}"
+    );
+}
+
+#[test]
+fn diff_column_is_labelled_as_computed_with_its_error_summary() {
+    let batch = dense_batch(vec![
+        vec![Some(1.0), Some(2.0), Some(4.0)],
+        vec![Some(1.5), Some(2.0), Some(3.0)],
+        vec![Some(0.0), Some(0.0), Some(0.0)],
+    ]);
+    let text = render_batch_to_string(
+        &batch,
+        &RenderOptions {
+            diff: Some((0, 1, DiffKind::Difference)),
+            ..SMALL
+        },
+    );
+    assert!(text.contains("Δ = col_1 − col_0 (computed)"), "{text}");
+    let header = text.lines().find(|l| l.contains("Row")).unwrap();
+    let labels: Vec<&str> = header.split_whitespace().collect();
+    assert_eq!(
+        labels[labels.len() - 5..],
+        ["Δ", "diff", "avg[skip]", "std[skip]", "│"]
+    );
+    let row2 = text.lines().find(|l| l.starts_with("│     2")).unwrap();
+    assert!(row2.contains("0.00000000   -1.0000000"), "{text}");
+    // max and mean |Δ| of (0.5, 0, -1), stacked under the column
+    assert!(text.contains("   max 1.0000"), "{text}");
+    assert!(text.contains("  mean 0.5000"), "{text}");
+}
+
+#[test]
+fn outlier_cells_are_drawn_in_the_warning_colour() {
+    use crate::display::display::render_source_to_buffer;
+    use ratatui::style::Color;
+
+    let mut wide: Vec<Option<f64>> = (0..10).map(|i| Some(i as f64)).collect();
+    wide.push(Some(900.0));
+    let batch = dense_batch(vec![wide, vec![Some(1.0); 11]]);
+    let opts = RenderOptions {
+        height: 24,
+        outliers: true,
+        ..SMALL
+    };
+    let text = render_batch_to_string(&batch, &opts);
+    assert!(text.contains("! 1 outliers (1.5×IQR)"), "{text}");
+
+    let buffer = render_source_to_buffer(&BatchSource::from(batch), &opts);
+    let fg_of = |needle: &str| {
+        let area = buffer.area;
+        (0..area.height)
+            .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+            .find(|&(x, y)| {
+                (0..needle.len() as u16)
+                    .all(|i| buffer[(x + i, y)].symbol() == &needle[i as usize..=i as usize])
+            })
+            .map(|(x, y)| buffer[(x, y)].fg)
+            .unwrap_or_else(|| panic!("{needle} not drawn"))
+    };
+    assert_eq!(fg_of("900.0"), Color::Rgb(255, 121, 198));
+    assert_ne!(fg_of("9.00000"), Color::Rgb(255, 121, 198));
+}
+
+#[test]
+fn window_summaries_flag_windows_far_from_all_rows() {
+    use crate::display::display::render_source_to_buffer;
+    use ratatui::style::Color;
+
+    // 20 rows of 100 on top of 180 of 0: mean 10, std 30
+    let high: Vec<Option<f64>> = (0..200)
+        .map(|i| Some(if i < 20 { 100.0 } else { 0.0 }))
+        .collect();
+    let batch = dense_batch(vec![high, vec![Some(1.0); 200]]);
+    let opts = |width, row_start| RenderOptions {
+        width,
+        height: 20,
+        row_start,
+        row_stats: &[],
+        summary: true,
+        ..SMALL
+    };
+    let text = render_batch_to_string(&batch, &opts(120, 0));
+    assert!(text.contains(" vs all (S) "), "{text}");
+    assert!(
+        text.contains("100.00±0.00     10.00±30.00  ↑900%"),
+        "{text}"
+    );
+    assert!(text.contains("1.00±0.00       1.00±0.00      ="), "{text}");
+
+    let buffer = render_source_to_buffer(&BatchSource::from(batch.clone()), &opts(120, 0));
+    let fg_of = |needle: &str| {
+        let area = buffer.area;
+        let width = needle.chars().count() as u16;
+        (0..area.height)
+            .flat_map(|y| (0..area.width.saturating_sub(width)).map(move |x| (x, y)))
+            .find(|&(x, y)| {
+                needle
+                    .chars()
+                    .enumerate()
+                    .all(|(i, c)| buffer[(x + i as u16, y)].symbol() == c.to_string())
+            })
+            .map(|(x, y)| buffer[(x, y)].fg)
+            .unwrap_or_else(|| panic!("{needle} not drawn"))
+    };
+    assert_eq!(fg_of("↑900%"), Color::Rgb(255, 121, 198));
+    assert_ne!(fg_of("1.00±0.00"), Color::Rgb(255, 121, 198));
+
+    // further down the window is typical again, and narrow terminals drop
+    // the spreads, then the panel
+    let lower = render_batch_to_string(&batch, &opts(120, 100));
+    assert!(lower.contains("↓100%"), "{lower}");
+    let compact = render_batch_to_string(&batch, &opts(80, 0));
+    assert!(compact.contains("100.000   10.000"), "{compact}");
+    let narrow = render_batch_to_string(&batch, &opts(60, 0));
+    assert!(!narrow.contains("vs all"), "{narrow}");
+}
+
+#[test]
+fn transformed_views_tag_the_headers_and_title_but_not_integer_columns() {
+    let schema = Schema::new(vec![
+        Field::new("col_0", DataType::Float64, false),
+        Field::new("col_1", DataType::UInt32, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(vec![10.0, 20.0, 30.0])),
+            Arc::new(UInt32Array::from(vec![1, 2, 3])),
+        ],
+    )
+    .unwrap();
+    let opts = RenderOptions {
+        transform: Some(Transform::MinMax),
+        row_stats: &[],
+        ..SMALL
+    };
+    let text = render_batch_to_string(&batch, &opts);
+    assert!(text.contains("min-max view"), "{text}");
+    let header = text.lines().find(|l| l.contains("Row")).unwrap();
+    assert!(
+        header.contains("col_0 [mm]") && !header.contains("col_1 ["),
+        "{header}"
+    );
+    let last = text.lines().find(|l| l.starts_with("│     2")).unwrap();
+    assert!(last.contains("1.0000") && last.contains(" 3"), "{text}");
+
+    let z = render_batch_to_string(
+        &batch,
+        &RenderOptions {
+            transform: Some(Transform::ZScore),
+            ..opts
+        },
+    );
+    assert!(z.contains("col_0 [z]") && z.contains("z-score view"), "{z}");
+}
+
+#[test]
+fn fewer_visible_columns_are_wider() {
+    let batch = dense_batch(vec![vec![Some(12_345.678)]; 5]);
+    let full = "12345.67800000";
+
+    let default = render_batch_to_string(&batch, &SMALL);
+    assert!(!default.contains(full), "{default}");
+
+    let three = render_batch_to_string(
+        &batch,
+        &RenderOptions {
+            visible_cols: 3,
+            ..SMALL
+        },
+    );
+    let header = three.lines().find(|l| l.contains("Row")).unwrap();
+    assert!(
+        header.contains("col_2") && !header.contains("col_3"),
+        "{header}"
+    );
+    let row = three.lines().find(|l| l.starts_with("│     0")).unwrap();
+    assert_eq!(row.matches(full).count(), 3, "{three}");
+}
+
+#[test]
+fn norm_and_dot_columns_follow_the_features_and_star_null_dims() {
+    let batch = dense_batch(vec![
+        vec![Some(3.0), Some(1.0), Some(2.0)],
+        vec![Some(4.0), None, Some(0.0)],
+    ]);
+    let opts = RenderOptions {
+        norm: true,
+        dot: Some(0),
+        row_stats: &[],
+        ..SMALL
+    };
+    let text = render_batch_to_string(&batch, &opts);
+    assert!(text.contains("‖x‖ & x·0 (computed)"), "{text}");
+    let header = text.lines().find(|l| l.contains("Row")).unwrap();
+    assert!(
+        header.find("col_1").unwrap() < header.find("‖x‖").unwrap()
+            && header.find("‖x‖").unwrap() < header.find("x·0").unwrap(),
+        "{header}"
+    );
+    let row = |i: usize| {
+        let prefix = format!("│     {i}");
+        text.lines().find(|l| l.starts_with(&prefix)).unwrap()
+    };
+    assert!(
+        row(0).contains("5.0000000") && row(0).contains("25.0000000"),
+        "{text}"
+    );
+    assert!(
+        row(1).contains("1.0000000*") && row(1).contains("3.0000000*"),
+        "{text}"
+    );
+    let footer = text.lines().find(|l| l.contains("nulls")).unwrap();
+    assert!(footer.contains("1*"), "{footer}");
+}
+
+#[test]
+fn prefix_groups_collapse_to_their_means_and_expand_between_separators() {
+    let names = ["txt_0", "txt_1", "id", "img_0", "img_1"];
+    let fields: Vec<Field> = names
+        .iter()
+        .map(|n| Field::new(*n, DataType::Float64, false))
+        .collect();
+    let columns: Vec<ArrayRef> = (0..names.len())
+        .map(|i| Arc::new(Float64Array::from(vec![i as f64, 10.0 * i as f64])) as ArrayRef)
+        .collect();
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap();
+    let opts = RenderOptions {
+        groups: Some(&[]),
+        row_stats: &[],
+        ..SMALL
+    };
+    let text = render_batch_to_string(&batch, &opts);
+    assert!(text.contains("2 groups, 0 expanded"), "{text}");
+    assert!(text.contains("cols: 5"), "{text}");
+    let header = text.lines().find(|l| l.contains("Row")).unwrap();
+    assert!(
+        header.find("txt (2)").unwrap() < header.find("id").unwrap()
+            && header.find("id").unwrap() < header.find("img (2)").unwrap()
+            && !header.contains("txt_0"),
+        "{header}"
+    );
+    // row 1: txt (1 + 0) / 2 · 10, img (3 + 4) / 2 · 10
+    let row = text.lines().find(|l| l.starts_with("│     1")).unwrap();
+    assert!(
+        row.contains("5.00000000") && row.contains("35.00000000"),
+        "{row}"
+    );
+    assert_eq!(row.matches('┃').count(), 2, "{row}");
+
+    let opts = RenderOptions {
+        groups: Some(&["img"]),
+        ..opts
+    };
+    let text = render_batch_to_string(&batch, &opts);
+    assert!(text.contains("2 groups, 1 expanded"), "{text}");
+    let header = text.lines().find(|l| l.contains("Row")).unwrap();
+    assert!(
+        header.contains("img_0") && header.contains("img_1"),
+        "{header}"
+    );
+    let row = text.lines().find(|l| l.starts_with("│     1")).unwrap();
+    // txt | id | img_0 img_1
+    assert_eq!(row.matches('┃').count(), 2, "{row}");
+}
+
+#[test]
+fn unsupported_columns_are_hidden_until_shown_as_placeholders() {
+    use arrow_array::{Array, BinaryArray, Int32Array, StructArray};
+
+    let meta = StructArray::from(vec![
+        (
+            Arc::new(Field::new("a", DataType::Int32, false)),
+            Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("b", DataType::Int32, false)),
+            Arc::new(Int32Array::from(vec![3, 4])) as ArrayRef,
+        ),
+    ]);
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Float64, false),
+        Field::new("meta", meta.data_type().clone(), false),
+        Field::new("blob", DataType::Binary, false),
+        Field::new("y", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(vec![1.0, 2.0])),
+            Arc::new(meta),
+            Arc::new(BinaryArray::from(vec![
+                &[0x4f, 0xa1, 0, 7][..],
+                &[0xff][..],
+            ])),
+            Arc::new(Float64Array::from(vec![3.0, 4.0])),
+        ],
+    )
+    .unwrap();
+
+    // binaries are values, shown as hex with their length
+    let text = render_batch_to_string(&batch, &SMALL);
+    assert!(text.contains("cols: 3"), "{text}");
+    assert!(!text.contains('?') && !text.contains("meta"), "{text}");
+    let row = text.lines().find(|l| l.starts_with("│     0")).unwrap();
+    assert!(row.contains("4fa100… 4 B"), "{text}");
+
+    let text = render_batch_to_string(
+        &batch,
+        &RenderOptions {
+            unsupported: true,
+            ..SMALL
+        },
+    );
+    assert!(text.contains("cols: 4"), "{text}");
+    // the shown columns follow the features
+    let header = text.lines().find(|l| l.contains("Row")).unwrap();
+    assert!(
+        header.find('y').unwrap() < header.find("meta").unwrap(),
+        "{header}"
+    );
+    let row = text.lines().find(|l| l.starts_with("│     0")).unwrap();
+    assert!(row.contains("Struct{2}"), "{row}");
+    let row = text.lines().find(|l| l.starts_with("│     1")).unwrap();
+    assert!(row.contains("ff 1 B"), "{row}");
+}
+
+#[test]
+fn dictionary_and_run_end_encoded_labels_render_as_their_values() {
+    use arrow::datatypes::Int32Type;
+    use arrow_array::{Array, DictionaryArray, Int32Array, RunArray};
+
+    let label = DictionaryArray::<Int32Type>::new(
+        Int32Array::from(vec![Some(1), Some(0), None]),
+        Arc::new(StringArray::from(vec!["b-cell", "t-cell"])),
+    );
+    let stage = RunArray::<Int32Type>::try_new(
+        &Int32Array::from(vec![2, 3]),
+        &StringArray::from(vec!["early", "late"]),
+    )
+    .unwrap();
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Float64, false),
+        Field::new("label", label.data_type().clone(), true),
+        Field::new("stage", stage.data_type().clone(), false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0])),
+            Arc::new(label),
+            Arc::new(stage),
+        ],
+    )
+    .unwrap();
+
+    let text = render_batch_to_string(&batch, &SMALL);
+    assert!(text.contains("cols: 3"), "{text}");
+    assert!(!text.contains("hidden"), "{text}");
+    let row = |r: &str| {
+        text.lines()
+            .find(|l| l.starts_with(&format!("│     {r}")))
+            .unwrap()
+            .to_string()
+    };
+    assert!(
+        row("0").contains("t-cell") && row("0").contains("early"),
+        "{text}"
+    );
+    assert!(
+        row("1").contains("b-cell") && row("1").contains("early"),
+        "{text}"
+    );
+    assert!(
+        row("2").contains("NULL") && row("2").contains("late"),
+        "{text}"
+    );
+}
+
+#[test]
+fn null_policy_decides_how_nulls_enter_the_row_aggregates() {
+    // Row 0 is (2, NULL), row 1 all NULL
+    let batch = dense_batch(vec![vec![Some(2.0), None], vec![None, None]]);
+    let render = |null_policy| {
+        render_batch_to_string(
+            &batch,
+            &RenderOptions {
+                null_policy,
+                ..SMALL
+            },
+        )
+    };
+    let row = |text: &str, r: &str| {
+        text.lines()
+            .find(|l| l.starts_with(&format!("│     {r}")))
+            .unwrap()
+            .to_string()
+    };
+
+    let text = render(NullPolicy::Skip);
+    assert!(text.contains("avg[skip]"), "{text}");
+    assert!(row(&text, "0").contains("2.0000000    0.0000000"), "{text}");
+    assert!(row(&text, "1").contains("NA           NA"), "{text}");
+
+    let text = render(NullPolicy::Zero);
+    assert!(
+        text.contains("avg[zero]") && text.contains("std[zero]"),
+        "{text}"
+    );
+    assert!(row(&text, "0").contains("1.0000000    1.0000000"), "{text}");
+    assert!(row(&text, "1").contains("0.0000000    0.0000000"), "{text}");
+
+    let text = render(NullPolicy::Propagate);
+    assert!(text.contains("avg[nan]"), "{text}");
+    assert!(row(&text, "0").contains("NaN          NaN"), "{text}");
+    assert!(row(&text, "1").contains("NaN          NaN"), "{text}");
+}
+
+#[test]
+fn null_rows_filter_keeps_the_rows_with_or_without_nulls() {
+    // Rows (1, 2), (NULL, 3), (4, 5), (NULL, NULL)
+    let batch = dense_batch(vec![
+        vec![Some(1.0), None, Some(4.0), None],
+        vec![Some(2.0), Some(3.0), Some(5.0), None],
+    ]);
+    let render = |null_rows| render_batch_to_string(&batch, &RenderOptions { null_rows, ..SMALL });
+
+    let text = render(NullFilter::WithNulls);
+    assert!(text.contains("rows 0–1 of 2 with NULLs"), "{text}");
+    assert!(text.contains("#1") && text.contains("#3"), "{text}");
+    assert!(!text.contains("#0") && !text.contains("#2"), "{text}");
+
+    let text = render(NullFilter::Complete);
+    assert!(text.contains("rows 0–1 of 2 without NULLs"), "{text}");
+    assert!(text.contains("#0") && text.contains("#2"), "{text}");
+    assert!(!text.contains("#1") && !text.contains("#3"), "{text}");
+}
+
+// === Views of the `generate` fixture =======================================
+
+/// The terminal sizes every view of the fixture is pinned at.
+#[cfg(feature = "generate")]
+const FIXTURE_SIZES: [(u16, u16); 2] = [(100, 30), (60, 20)];
+
+/// The genegraph datasets `generate --seed 42` writes for 20 points of 5
+/// dimensions, each read as `display` reads it.
+#[cfg(feature = "generate")]
+async fn generated_fixture() -> HashMap<&'static str, (BatchSource, SourceWindow)> {
+    use crate::datasets::read_dataset;
+    use crate::functions::display::{DisplayOptions, load_display_window};
+    use crate::functions::generate::{GenerateOptions, cmd_generate};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("fixture");
+    let opts = GenerateOptions {
+        out: out.clone(),
+        n_items: 20,
+        n_dims: 5,
+        seed: 42,
+        genegraph: true,
+        ..GenerateOptions::default()
+    };
+    cmd_generate(&opts).await.unwrap();
+
+    let mut views = HashMap::new();
+    for key in ["raw_input", "norms", "adjacency"] {
+        let path = out.join(format!("javelin_test_{key}.lance"));
+        let (data, source, ..) = read_dataset(&path, |dataset| {
+            let path = path.clone();
+            async move {
+                load_display_window(&dataset, &path, &DisplayOptions::default())
+                    .await
+                    .map(|window| window.expect("the fixture is not empty"))
+            }
+        })
+        .await
+        .unwrap();
+        views.insert(key, (data, source));
+    }
+    views
+}
+
+/// Snapshot `key` of the fixture as `<name>_<W>x<H>` at each of
+/// [`FIXTURE_SIZES`].
+#[cfg(feature = "generate")]
+fn assert_fixture_snapshots(
+    views: &HashMap<&str, (BatchSource, SourceWindow)>,
+    key: &str,
+    name: &str,
+    transposed: bool,
+) {
+    let (data, source) = &views[key];
+    for (width, height) in FIXTURE_SIZES {
+        let opts = RenderOptions {
+            width,
+            height,
+            transposed,
+            source: Some(*source),
+            ..SMALL
+        };
+        let text = render_source_to_string(data, &opts);
+        // the same fixture draws the same frame
+        assert_eq!(text, render_source_to_string(data, &opts));
+        assert_snapshot(&format!("{name}_{width}x{height}"), &text);
+    }
+}
+
+#[cfg(feature = "generate")]
+#[tokio::test(flavor = "multi_thread")]
+async fn snapshot_generated_fixture_views() {
+    let views = generated_fixture().await;
+    assert_eq!(
+        detect_lance_layout(&views["norms"].0.empty_batch()),
+        LanceLayout::Vector1D
+    );
+    assert_eq!(
+        detect_lance_layout(&views["adjacency"].0.empty_batch()),
+        LanceLayout::SparseCoo
+    );
+
+    assert_fixture_snapshots(&views, "raw_input", "generated_dense", false);
+    assert_fixture_snapshots(&views, "raw_input", "generated_transposed", true);
+    assert_fixture_snapshots(&views, "norms", "generated_norms", false);
+    assert_fixture_snapshots(&views, "adjacency", "generated_adjacency", false);
+}
+
+/// Screen text of one 100×30 frame of the 1D view of `data` with `panels`.
+fn vector_frame(data: &BatchSource, panels: &VectorPanels) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal
+        .draw(|f| {
+            render_frame(
+                f,
+                data,
+                &LanceLayout::Vector1D,
+                &[0],
+                HorizontalOffsets::default(),
+                1,
+                0,
+                false,
+                SourceWindow::whole(data.num_rows()),
+                None,
+                None,
+                None,
+                panels,
+                &RowStats::new(&[], false),
+                &Bookmarks::default(),
+                None,
+                &[],
+                None,
+                None,
+                None,
+                0,
+                None,
+                &ValueFormatter::default(),
+            );
+        })
+        .unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer.content.iter().map(|c| c.symbol()).collect()
+}
+
+#[test]
+fn clamped_distribution_panel_counts_the_values_left_outside() {
+    // 1..=50 and an outlier that squeezes them into the first bin
+    let mut values: Vec<Option<f64>> = (1..=50).map(|v| Some(f64::from(v))).collect();
+    values.push(Some(10_000.0));
+    let data = BatchSource::from(vector_batch(values));
+    let frame = |panels: &VectorPanels| vector_frame(&data, panels);
+
+    let mut panels = VectorPanels::default();
+    let text = frame(&panels);
+    assert!(text.contains("Distribution (Tab focus)"), "{text}");
+    assert!(text.contains("1.00") && text.contains("10000.00"), "{text}");
+    assert!(!text.contains("outside"), "{text}");
+
+    // the central 95% runs from the 3rd to the 49th value
+    panels.distribution.focused = true;
+    panels.distribution.clamp = Clamp::P95;
+    let text = frame(&panels);
+    assert!(text.contains("% range 95%, Tab back"), "{text}");
+    assert!(
+        text.contains("central 95%: 4 outside [3.00, 49.00]"),
+        "{text}"
+    );
+    assert!(text.contains("Count: 51"), "{text}");
+}
+
+#[test]
+fn row_profile_shows_the_mean_of_each_row_bucket() {
+    // values rising with the row: the bars grow down the panel
+    let values: Vec<Option<f64>> = (0..100).map(|v| Some(f64::from(v))).collect();
+    let data = BatchSource::from(vector_batch(values));
+    let mut panels = VectorPanels::default();
+    assert!(!vector_frame(&data, &panels).contains("Row profile"));
+
+    panels.profile = true;
+    let text = vector_frame(&data, &panels);
+    assert!(text.contains("Row profile (p hides)"), "{text}");
+    // 22 lines for 100 rows: 12 buckets of 5 rows, then 10 of 4
+    assert!(text.contains("0–4 ") && text.contains("2.0000"), "{text}");
+    assert!(
+        text.contains("96–99 ") && text.contains("97.5000"),
+        "{text}"
+    );
+    assert!(!text.contains("100–"), "{text}");
+}
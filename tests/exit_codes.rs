@@ -0,0 +1,80 @@
+//! The `javelin` binary's exit codes, as scripts see them.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn javelin(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_javelin"))
+        .args(args)
+        .env_remove("JAVELIN_LOG_FILE")
+        .output()
+        .expect("the javelin binary should run")
+}
+
+fn info(path: &Path, verbose: bool) -> Output {
+    let path = path.to_str().unwrap();
+    let mut args = vec!["--filepath", path, "--no-cache"];
+    if verbose {
+        args.insert(0, "--verbose");
+    }
+    args.push("info");
+    javelin(&args)
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn a_missing_path_is_an_open_failure() {
+    let tmp = tempfile::tempdir().unwrap();
+    let output = info(&tmp.path().join("missing.lance"), false);
+    assert_eq!(output.status.code(), Some(2), "{}", stderr(&output));
+    let message = stderr(&output);
+    assert_eq!(message.lines().count(), 1, "{message}");
+    assert!(message.contains("does not exist"), "{message}");
+}
+
+#[test]
+fn a_text_file_is_an_unsupported_layout() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("notes.txt");
+    std::fs::write(&path, "not a dataset\n").unwrap();
+    let output = info(&path, false);
+    assert_eq!(output.status.code(), Some(3), "{}", stderr(&output));
+
+    // --verbose adds the chain of causes.
+    let verbose = info(&path, true);
+    assert_eq!(verbose.status.code(), Some(3));
+    assert!(
+        stderr(&verbose).contains("Caused by"),
+        "{}",
+        stderr(&verbose)
+    );
+}
+
+#[test]
+fn bad_arguments_exit_4_and_help_lists_the_codes() {
+    let output = javelin(&["head", "not-a-number"]);
+    assert_eq!(output.status.code(), Some(4), "{}", stderr(&output));
+    let output = javelin(&["info"]);
+    assert_eq!(output.status.code(), Some(4), "{}", stderr(&output));
+
+    let help = javelin(&["--help"]);
+    assert_eq!(help.status.code(), Some(0));
+    let text = String::from_utf8_lossy(&help.stdout);
+    assert!(
+        text.contains("Exit codes:") && text.contains("10  internal"),
+        "{text}"
+    );
+}
+
+#[cfg(feature = "generate")]
+#[test]
+fn values_checked_by_the_command_exit_4_too() {
+    let tmp = tempfile::tempdir().unwrap();
+    let out = tmp.path().join("gen");
+    let output = javelin(&["generate", "--out", out.to_str().unwrap(), "--n-items", "0"]);
+    assert_eq!(output.status.code(), Some(4), "{}", stderr(&output));
+    assert!(stderr(&output).contains("--n-items"), "{}", stderr(&output));
+}